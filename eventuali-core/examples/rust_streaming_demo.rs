@@ -247,7 +247,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let all_users = projection_arc.get_all_users().await;
     println!("   Users in projection: {}", all_users.len());
     
-    for (_user_id, user_data) in &all_users {
+    for user_data in all_users.values() {
         println!("   - {} ({}) - registered: {}", 
             user_data["name"], user_data["email"], user_data["registered_at"]);
     }
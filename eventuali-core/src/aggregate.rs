@@ -1,3 +1,4 @@
+use crate::{Event, EventualiError, Result};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -57,4 +58,78 @@ impl AggregateSnapshot {
             timestamp: chrono::Utc::now(),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Rebuild state by folding `events` into `initial` in order.
+///
+/// The first event `apply` rejects stops the replay with
+/// `EventualiError::ApplyError`, naming that event's ID and aggregate version
+/// and wrapping the error `apply` returned.
+pub fn replay<S, F>(initial: S, events: &[Event], mut apply: F) -> Result<S>
+where
+    F: FnMut(&mut S, &Event) -> Result<()>,
+{
+    let mut state = initial;
+    for event in events {
+        apply(&mut state, event).map_err(|e| EventualiError::ApplyError {
+            event_id: event.id,
+            aggregate_version: event.aggregate_version,
+            source: Box::new(e),
+        })?;
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventData;
+
+    #[test]
+    fn test_replay_identifies_the_event_that_failed_to_apply() {
+        let events: Vec<Event> = (1..=5)
+            .map(|version| Event::new(
+                "account-1".to_string(),
+                "Account".to_string(),
+                "Deposited".to_string(),
+                1,
+                version,
+                EventData::from_json(&serde_json::json!({ "amount": if version == 3 { -10 } else { 10 } })).unwrap(),
+            ))
+            .collect();
+
+        let deposit = |balance: &mut i64, event: &Event| -> Result<()> {
+            let amount = match &event.data {
+                EventData::Json(data) => data["amount"].as_i64().unwrap_or(0),
+                _ => 0,
+            };
+            if amount < 0 {
+                return Err(EventualiError::InvalidEventData(format!("negative deposit {amount}")));
+            }
+            *balance += amount;
+            Ok(())
+        };
+
+        assert_eq!(replay(0i64, &events[..2], deposit).unwrap(), 20);
+
+        match replay(0i64, &events, deposit) {
+            Err(EventualiError::ApplyError { event_id, aggregate_version, source }) => {
+                assert_eq!(event_id, events[2].id);
+                assert_eq!(aggregate_version, 3);
+                assert!(matches!(*source, EventualiError::InvalidEventData(_)));
+            }
+            other => panic!("expected ApplyError, got {other:?}"),
+        }
+
+        // The cause is left to the source chain rather than repeated in the message
+        let error = replay(0i64, &events, deposit).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("Failed to apply event {} at aggregate version 3", events[2].id)
+        );
+        assert_eq!(
+            std::error::Error::source(&error).unwrap().to_string(),
+            "Invalid event data: negative deposit -10"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//! Time source abstraction
+//!
+//! Components that stamp times (such as server-assigned event timestamps) read
+//! them through a `Clock`, so a deterministic clock can be substituted in tests.
+
+use chrono::{DateTime, Utc};
+use std::sync::RwLock;
+
+/// Source of the current time
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Clock backed by the system wall clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that always reports a fixed, manually adjusted time
+#[derive(Debug)]
+pub struct FixedClock {
+    now: RwLock<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: RwLock::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        if let Ok(mut current) = self.now.write() {
+            *current = now;
+        }
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        if let Ok(mut current) = self.now.write() {
+            *current += by;
+        }
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now.read().map(|now| *now).unwrap_or_else(|e| *e.into_inner())
+    }
+}
@@ -1,4 +1,7 @@
 use thiserror::Error;
+use crate::performance::PoolStats;
+use crate::tenancy::QuotaExceeded;
+use crate::{AggregateVersion, EventId};
 
 pub type Result<T> = std::result::Result<T, EventualiError>;
 
@@ -33,6 +36,9 @@ pub enum EventualiError {
     
     #[error("Tenant error: {0}")]
     Tenant(String),
+
+    #[error("{0}")]
+    QuotaExceeded(Box<QuotaExceeded>),
     
     #[error("Observability error: {0}")]
     ObservabilityError(String),
@@ -43,6 +49,9 @@ pub enum EventualiError {
     #[error("Authentication error: {0}")]
     Authentication(String),
     
+    #[error("Decryption failed for data under key {key_id}: no held key authenticated it ({keys_tried} tried)")]
+    AuthenticationFailed { key_id: String, keys_tried: usize },
+    
     #[error("Authorization error: {0}")]
     Authorization(String),
     
@@ -57,4 +66,304 @@ pub enum EventualiError {
     
     #[error("Database error: {0}")]
     DatabaseError(String),
-}
\ No newline at end of file
+    
+    #[error("Connection pool timed out after {timeout_ms}ms ({stats_snapshot})")]
+    PoolTimeout { timeout_ms: u64, stats_snapshot: Box<PoolStats> },
+
+    #[error("Failed to apply event {event_id} at aggregate version {aggregate_version}")]
+    ApplyError {
+        event_id: EventId,
+        aggregate_version: AggregateVersion,
+        #[source]
+        source: Box<EventualiError>,
+    },
+
+    #[error("History of aggregate {aggregate_id} through version {compacted_through} was compacted into a snapshot")]
+    HistoryCompacted { aggregate_id: String, compacted_through: AggregateVersion },
+
+    #[error("Event {event_id} is stored in format version {version}, newer than the supported version {supported}")]
+    UnsupportedStorageFormat { event_id: String, version: i32, supported: i32 },
+
+    /// Another snapshot was already stored at the same aggregate version or
+    /// projection position
+    #[error("Snapshot conflict: {0}")]
+    SnapshotConflict(String),
+
+    /// The events were committed, but forcing them onto disk failed. Retrying
+    /// the save would write them twice; retry `sync_to_disk` instead.
+    #[error("Events were saved but could not be synced to disk: {source}")]
+    NotDurable {
+        #[source]
+        source: Box<EventualiError>,
+    },
+
+    /// The events were committed, but the database could not give them their
+    /// global positions yet; they get them at the next save or when the store
+    /// is next initialized. Retrying the save would write them twice.
+    #[error("Events were saved but their global positions are not yet assigned: {source}")]
+    PositionsPending {
+        #[source]
+        source: Box<EventualiError>,
+    },
+}
+
+impl EventualiError {
+    /// The innermost error in the `source()` chain, such as the I/O or driver
+    /// error a `Database` or `Io` variant wraps, or this error when it wraps
+    /// nothing.
+    pub fn root_cause(&self) -> &(dyn std::error::Error + 'static) {
+        let mut current: &(dyn std::error::Error + 'static) = self;
+        while let Some(source) = current.source() {
+            current = source;
+        }
+        current
+    }
+
+    /// The variant's name, such as `"OptimisticConcurrency"`, for logs and
+    /// metrics that group errors by kind
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EventualiError::Database(_) => "Database",
+            EventualiError::Serialization(_) => "Serialization",
+            EventualiError::Protobuf(_) => "Protobuf",
+            EventualiError::AggregateNotFound { .. } => "AggregateNotFound",
+            EventualiError::OptimisticConcurrency { .. } => "OptimisticConcurrency",
+            EventualiError::InvalidEventData(_) => "InvalidEventData",
+            EventualiError::Configuration(_) => "Configuration",
+            EventualiError::Io(_) => "Io",
+            EventualiError::Encryption(_) => "Encryption",
+            EventualiError::Tenant(_) => "Tenant",
+            EventualiError::QuotaExceeded(_) => "QuotaExceeded",
+            EventualiError::ObservabilityError(_) => "ObservabilityError",
+            EventualiError::Validation(_) => "Validation",
+            EventualiError::Authentication(_) => "Authentication",
+            EventualiError::AuthenticationFailed { .. } => "AuthenticationFailed",
+            EventualiError::Authorization(_) => "Authorization",
+            EventualiError::InvalidState(_) => "InvalidState",
+            EventualiError::BackpressureApplied(_) => "BackpressureApplied",
+            EventualiError::BatchProcessingError(_) => "BatchProcessingError",
+            EventualiError::DatabaseError(_) => "DatabaseError",
+            EventualiError::PoolTimeout { .. } => "PoolTimeout",
+            EventualiError::ApplyError { .. } => "ApplyError",
+            EventualiError::HistoryCompacted { .. } => "HistoryCompacted",
+            EventualiError::UnsupportedStorageFormat { .. } => "UnsupportedStorageFormat",
+            EventualiError::SnapshotConflict(_) => "SnapshotConflict",
+            EventualiError::NotDurable { .. } => "NotDurable",
+            EventualiError::PositionsPending { .. } => "PositionsPending",
+        }
+    }
+
+    /// Whether retrying the same operation may succeed.
+    ///
+    /// Connection and transient I/O failures, pool timeouts, backpressure,
+    /// quotas with a `retry_after` hint and SQLite busy/locked errors (plus PostgreSQL serialization failures and
+    /// deadlocks) are retryable. Validation, concurrency conflicts,
+    /// configuration and every other error fail the same way again, so retry
+    /// loops should give up on them immediately.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            EventualiError::Database(e) => is_retryable_sqlx_error(e),
+            EventualiError::Io(e) => is_retryable_io_error(e),
+            EventualiError::PoolTimeout { .. } | EventualiError::BackpressureApplied(_) => true,
+            EventualiError::ApplyError { source, .. } => source.is_retryable(),
+            // Only quotas that replenish, such as the write rate, admit a retry
+            EventualiError::QuotaExceeded(exceeded) => exceeded.retry_after.is_some(),
+            EventualiError::DatabaseError(msg) => {
+                let msg = msg.to_lowercase();
+                msg.contains("database is locked") || msg.contains("database table is locked")
+            }
+            EventualiError::Serialization(_)
+            | EventualiError::Protobuf(_)
+            | EventualiError::AggregateNotFound { .. }
+            | EventualiError::OptimisticConcurrency { .. }
+            | EventualiError::InvalidEventData(_)
+            | EventualiError::Configuration(_)
+            | EventualiError::Encryption(_)
+            | EventualiError::Tenant(_)
+            | EventualiError::ObservabilityError(_)
+            | EventualiError::Validation(_)
+            | EventualiError::Authentication(_)
+            | EventualiError::AuthenticationFailed { .. }
+            | EventualiError::Authorization(_)
+            | EventualiError::InvalidState(_)
+            | EventualiError::BatchProcessingError(_)
+            | EventualiError::HistoryCompacted { .. }
+            | EventualiError::UnsupportedStorageFormat { .. }
+            | EventualiError::SnapshotConflict(_)
+            | EventualiError::NotDurable { .. }
+            | EventualiError::PositionsPending { .. } => false,
+        }
+    }
+}
+
+fn is_retryable_sqlx_error(error: &sqlx::Error) -> bool {
+    match error {
+        sqlx::Error::Io(e) => is_retryable_io_error(e),
+        sqlx::Error::PoolTimedOut | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(db_error) => match db_error.code() {
+            // PostgreSQL SQLSTATE: serialization failure, deadlock, connection
+            // exceptions, too many connections and "cannot connect now"
+            Some(code) if code.len() == 5 => {
+                matches!(code.as_ref(), "40001" | "40P01" | "53300" | "57P03") || code.starts_with("08")
+            }
+            // SQLite extended result codes are at most four digits; the low
+            // byte is SQLITE_BUSY (5) or SQLITE_LOCKED (6)
+            Some(code) => code.parse::<i32>().map(|c| matches!(c & 0xff, 5 | 6)).unwrap_or(false),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+fn is_retryable_io_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+    !matches!(
+        error.kind(),
+        ErrorKind::NotFound
+            | ErrorKind::PermissionDenied
+            | ErrorKind::AlreadyExists
+            | ErrorKind::InvalidInput
+            | ErrorKind::InvalidData
+            | ErrorKind::Unsupported
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_wrapped_errors_are_exposed_as_source() {
+        let missing = std::env::temp_dir().join(format!("eventuali-missing-{}", uuid::Uuid::new_v4()));
+        let error: EventualiError = std::fs::File::open(&missing).unwrap_err().into();
+
+        let source = error.source().expect("I/O error should be the source");
+        let io = source.downcast_ref::<std::io::Error>().unwrap();
+        assert_eq!(io.kind(), std::io::ErrorKind::NotFound);
+        assert!(error.root_cause().downcast_ref::<std::io::Error>().is_some());
+
+        let error: EventualiError = sqlx::Error::RowNotFound.into();
+        assert!(matches!(error.source().and_then(|e| e.downcast_ref::<sqlx::Error>()), Some(sqlx::Error::RowNotFound)));
+
+        // Variants that only carry a message are their own root cause
+        let error = EventualiError::Validation("bad".to_string());
+        assert!(error.source().is_none());
+        assert_eq!(error.root_cause().to_string(), "Validation error: bad");
+    }
+
+    #[derive(Debug)]
+    struct CodedDatabaseError(&'static str);
+
+    impl std::fmt::Display for CodedDatabaseError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "database error {}", self.0)
+        }
+    }
+
+    impl std::error::Error for CodedDatabaseError {}
+
+    impl sqlx::error::DatabaseError for CodedDatabaseError {
+        fn message(&self) -> &str {
+            self.0
+        }
+
+        fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+            Some(self.0.into())
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::Other
+        }
+    }
+
+    fn database_error(code: &'static str) -> EventualiError {
+        EventualiError::Database(sqlx::Error::Database(Box::new(CodedDatabaseError(code))))
+    }
+
+    #[test]
+    fn test_retryable_classification() {
+        let io = |kind| EventualiError::Io(std::io::Error::new(kind, "io"));
+        let stats = Box::new(PoolStats {
+            total_connections: 1,
+            active_connections: 1,
+            idle_connections: 0,
+            total_requests: 1,
+            successful_requests: 0,
+            failed_requests: 1,
+            avg_wait_time_ms: 0.0,
+            max_wait_time_ms: 0,
+            waiting_requests: 1,
+        });
+
+        let retryable = [
+            EventualiError::Database(sqlx::Error::PoolTimedOut),
+            EventualiError::Database(sqlx::Error::Io(std::io::Error::from(std::io::ErrorKind::ConnectionReset))),
+            database_error("5"),   // SQLITE_BUSY
+            database_error("517"), // SQLITE_BUSY_SNAPSHOT
+            database_error("6"),   // SQLITE_LOCKED
+            database_error("40001"),
+            database_error("40P01"),
+            database_error("08006"),
+            io(std::io::ErrorKind::TimedOut),
+            io(std::io::ErrorKind::ConnectionRefused),
+            EventualiError::PoolTimeout { timeout_ms: 100, stats_snapshot: stats },
+            EventualiError::BackpressureApplied("queue full".to_string()),
+            EventualiError::DatabaseError("database is locked".to_string()),
+        ];
+        for error in &retryable {
+            assert!(error.is_retryable(), "expected retryable: {error}");
+        }
+
+        let terminal = [
+            EventualiError::Database(sqlx::Error::RowNotFound),
+            EventualiError::Database(sqlx::Error::PoolClosed),
+            database_error("19"), // SQLITE_CONSTRAINT
+            database_error("23505"),
+            EventualiError::Serialization(serde_json::from_str::<u8>("x").unwrap_err()),
+            EventualiError::Protobuf(prost::DecodeError::new("bad")),
+            EventualiError::AggregateNotFound { id: "a".to_string() },
+            EventualiError::OptimisticConcurrency { expected: 1, actual: 2 },
+            EventualiError::InvalidEventData("bad".to_string()),
+            EventualiError::Configuration("bad".to_string()),
+            io(std::io::ErrorKind::NotFound),
+            io(std::io::ErrorKind::PermissionDenied),
+            EventualiError::Encryption("bad".to_string()),
+            EventualiError::Tenant("bad".to_string()),
+            EventualiError::ObservabilityError("bad".to_string()),
+            EventualiError::Validation("bad".to_string()),
+            EventualiError::Authentication("bad".to_string()),
+            EventualiError::AuthenticationFailed { key_id: "k".to_string(), keys_tried: 1 },
+            EventualiError::Authorization("bad".to_string()),
+            EventualiError::InvalidState("bad".to_string()),
+            EventualiError::BatchProcessingError("bad".to_string()),
+            EventualiError::DatabaseError("no such table: events".to_string()),
+            EventualiError::ApplyError {
+                event_id: uuid::Uuid::new_v4(),
+                aggregate_version: 3,
+                source: Box::new(EventualiError::InvalidEventData("bad".to_string())),
+            },
+            EventualiError::HistoryCompacted { aggregate_id: "a".to_string(), compacted_through: 3 },
+            EventualiError::UnsupportedStorageFormat { event_id: "e".to_string(), version: 2, supported: 1 },
+            EventualiError::SnapshotConflict("bad".to_string()),
+            EventualiError::PositionsPending {
+                source: Box::new(EventualiError::DatabaseError("connection reset".to_string())),
+            },
+        ];
+        for error in &terminal {
+            assert!(!error.is_retryable(), "expected terminal: {error}");
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! CNCF CloudEvents 1.0 (JSON format) conversion
+//!
+//! Events map onto the required attributes as `id`, `source` (the aggregate
+//! type under a caller-chosen prefix) and `type` (the event type), with the
+//! timestamp as `time` and the aggregate ID as `subject`. Everything else an
+//! event carries travels as extension attributes, whose names the spec limits
+//! to lowercase letters and digits:
+//!
+//! | extension          | field                          |
+//! |--------------------|--------------------------------|
+//! | `aggregateid`      | `aggregate_id`                 |
+//! | `aggregatetype`    | `aggregate_type`               |
+//! | `aggregateversion` | `aggregate_version`            |
+//! | `eventversion`     | `event_version`                |
+//! | `correlationid`    | `metadata.correlation_id`      |
+//! | `causationid`      | `metadata.causation_id`        |
+//! | `userid`           | `metadata.user_id`             |
+//! | `eventualiheaders` | `metadata.headers`, JSON text  |
+//!
+//! JSON payloads are carried as `data`; protobuf payloads as `data_base64`
+//! with content type `application/protobuf`.
+
+use super::{Event, EventData, EventMetadata};
+use crate::{EventualiError, Result};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+const SPEC_VERSION: &str = "1.0";
+const PROTOBUF_CONTENT_TYPE: &str = "application/protobuf";
+
+impl Event {
+    /// This event as a structured-mode CloudEvent. `source` is
+    /// `"{source_prefix}/{aggregate_type}"`, or just the aggregate type when
+    /// the prefix is empty.
+    pub fn to_cloudevent(&self, source_prefix: &str) -> Value {
+        let prefix = source_prefix.trim_end_matches('/');
+        let source = if prefix.is_empty() {
+            self.aggregate_type.clone()
+        } else {
+            format!("{prefix}/{}", self.aggregate_type)
+        };
+
+        let mut cloudevent = json!({
+            "specversion": SPEC_VERSION,
+            "id": self.id.to_string(),
+            "source": source,
+            "type": self.event_type,
+            "subject": self.aggregate_id,
+            "time": self.timestamp.to_rfc3339(),
+            "aggregateid": self.aggregate_id,
+            "aggregatetype": self.aggregate_type,
+            "aggregateversion": self.aggregate_version,
+            "eventversion": self.event_version,
+        });
+        let attributes = cloudevent.as_object_mut().expect("CloudEvent is a JSON object");
+
+        match &self.data {
+            EventData::Json(data) => {
+                attributes.insert("datacontenttype".to_string(), json!("application/json"));
+                attributes.insert("data".to_string(), data.clone());
+            }
+            EventData::Protobuf(bytes) => {
+                attributes.insert("datacontenttype".to_string(), json!(PROTOBUF_CONTENT_TYPE));
+                attributes.insert("data_base64".to_string(), json!(general_purpose::STANDARD.encode(bytes)));
+            }
+        }
+
+        let metadata = &self.metadata;
+        if let Some(correlation_id) = metadata.correlation_id {
+            attributes.insert("correlationid".to_string(), json!(correlation_id.to_string()));
+        }
+        if let Some(causation_id) = metadata.causation_id {
+            attributes.insert("causationid".to_string(), json!(causation_id.to_string()));
+        }
+        if let Some(user_id) = &metadata.user_id {
+            attributes.insert("userid".to_string(), json!(user_id));
+        }
+        if !metadata.headers.is_empty() {
+            let headers = serde_json::to_string(&metadata.headers).expect("string map serializes");
+            attributes.insert("eventualiheaders".to_string(), json!(headers));
+        }
+
+        cloudevent
+    }
+
+    /// Read a structured-mode CloudEvent, such as one from `to_cloudevent`.
+    ///
+    /// The ID must be a UUID and the aggregate version is required, from the
+    /// `aggregateversion` extension. The aggregate ID falls back to `subject`
+    /// and the aggregate type to the last path segment of `source`; a missing
+    /// `time` is read as now and a missing `eventversion` as 1.
+    pub fn from_cloudevent(cloudevent: &Value) -> Result<Event> {
+        let attributes = cloudevent
+            .as_object()
+            .ok_or_else(|| invalid("a CloudEvent must be a JSON object".to_string()))?;
+
+        let spec_version = string_attribute(attributes, "specversion")?;
+        if spec_version != SPEC_VERSION {
+            return Err(invalid(format!("unsupported CloudEvents specversion {spec_version}")));
+        }
+
+        let id = string_attribute(attributes, "id")?;
+        let id = Uuid::parse_str(id).map_err(|e| invalid(format!("id {id} is not a UUID: {e}")))?;
+        let source = string_attribute(attributes, "source")?;
+        let event_type = string_attribute(attributes, "type")?.to_string();
+
+        let aggregate_id = match optional_string(attributes, "aggregateid")? {
+            Some(aggregate_id) => aggregate_id,
+            None => optional_string(attributes, "subject")?
+                .ok_or_else(|| invalid("missing aggregateid and subject".to_string()))?,
+        };
+        let aggregate_type = match optional_string(attributes, "aggregatetype")? {
+            Some(aggregate_type) => aggregate_type,
+            None => source.rsplit('/').next().unwrap_or(source).to_string(),
+        };
+        let aggregate_version = integer_attribute(attributes, "aggregateversion")?
+            .ok_or_else(|| invalid("missing aggregateversion".to_string()))?;
+        let event_version = match integer_attribute(attributes, "eventversion")? {
+            Some(version) => i32::try_from(version).map_err(|_| invalid(format!("eventversion {version} is out of range")))?,
+            None => 1,
+        };
+
+        let timestamp = match optional_string(attributes, "time")? {
+            Some(time) => DateTime::parse_from_rfc3339(&time)
+                .map_err(|e| invalid(format!("time {time} is not RFC 3339: {e}")))?
+                .with_timezone(&Utc),
+            None => Utc::now(),
+        };
+
+        let data = match (attributes.get("data"), optional_string(attributes, "data_base64")?) {
+            (Some(_), Some(_)) => return Err(invalid("both data and data_base64 are set".to_string())),
+            (_, Some(encoded)) => EventData::Protobuf(
+                general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|e| invalid(format!("data_base64 is not base64: {e}")))?,
+            ),
+            (Some(data), None) => EventData::Json(data.clone()),
+            (None, None) => EventData::Json(Value::Null),
+        };
+
+        let metadata = EventMetadata {
+            causation_id: optional_uuid(attributes, "causationid")?,
+            correlation_id: optional_uuid(attributes, "correlationid")?,
+            user_id: optional_string(attributes, "userid")?,
+            headers: match optional_string(attributes, "eventualiheaders")? {
+                Some(headers) => serde_json::from_str(&headers)
+                    .map_err(|e| invalid(format!("eventualiheaders is not a JSON string map: {e}")))?,
+                None => Default::default(),
+            },
+        };
+
+        Ok(Event {
+            id,
+            aggregate_id,
+            aggregate_type,
+            event_type,
+            event_version,
+            aggregate_version,
+            data,
+            metadata,
+            timestamp,
+        })
+    }
+}
+
+fn invalid(reason: String) -> EventualiError {
+    EventualiError::InvalidEventData(format!("Invalid CloudEvent: {reason}"))
+}
+
+fn string_attribute<'a>(attributes: &'a Map<String, Value>, name: &str) -> Result<&'a str> {
+    match attributes.get(name) {
+        Some(Value::String(value)) => Ok(value),
+        Some(_) => Err(invalid(format!("{name} must be a string"))),
+        None => Err(invalid(format!("missing required attribute {name}"))),
+    }
+}
+
+fn optional_string(attributes: &Map<String, Value>, name: &str) -> Result<Option<String>> {
+    match attributes.get(name) {
+        Some(Value::String(value)) => Ok(Some(value.clone())),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => Err(invalid(format!("{name} must be a string"))),
+    }
+}
+
+/// Integer extension, as a JSON number or, as some transports deliver every
+/// attribute, as a decimal string
+fn integer_attribute(attributes: &Map<String, Value>, name: &str) -> Result<Option<i64>> {
+    match attributes.get(name) {
+        Some(Value::Number(number)) => number
+            .as_i64()
+            .map(Some)
+            .ok_or_else(|| invalid(format!("{name} must be an integer"))),
+        Some(Value::String(text)) => text
+            .parse()
+            .map(Some)
+            .map_err(|_| invalid(format!("{name} must be an integer"))),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => Err(invalid(format!("{name} must be an integer"))),
+    }
+}
+
+fn optional_uuid(attributes: &Map<String, Value>, name: &str) -> Result<Option<Uuid>> {
+    optional_string(attributes, name)?
+        .map(|value| Uuid::parse_str(&value).map_err(|e| invalid(format!("{name} {value} is not a UUID: {e}"))))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order_placed() -> Event {
+        let mut headers = std::collections::HashMap::new();
+        headers.insert("tenant".to_string(), "acme".to_string());
+        Event::new(
+            "order-42".to_string(),
+            "Order".to_string(),
+            "OrderPlaced".to_string(),
+            2,
+            7,
+            EventData::Json(json!({ "total_cents": 1299, "items": ["sku-1"] })),
+        )
+        .with_metadata(EventMetadata {
+            causation_id: Some(Uuid::new_v4()),
+            correlation_id: Some(Uuid::new_v4()),
+            user_id: Some("alice".to_string()),
+            headers,
+        })
+    }
+
+    #[test]
+    fn test_cloudevent_round_trip_carries_required_attributes() {
+        let event = order_placed();
+        let cloudevent = event.to_cloudevent("https://shop.example.com/events/");
+
+        assert_eq!(cloudevent["specversion"], "1.0");
+        assert_eq!(cloudevent["id"], event.id.to_string());
+        assert_eq!(cloudevent["source"], "https://shop.example.com/events/Order");
+        assert_eq!(cloudevent["type"], "OrderPlaced");
+        assert_eq!(cloudevent["subject"], "order-42");
+        assert_eq!(cloudevent["time"], event.timestamp.to_rfc3339());
+        assert_eq!(cloudevent["datacontenttype"], "application/json");
+        assert_eq!(cloudevent["data"], json!({ "total_cents": 1299, "items": ["sku-1"] }));
+        assert_eq!(cloudevent["aggregateversion"], 7);
+        assert_eq!(cloudevent["correlationid"], event.metadata.correlation_id.unwrap().to_string());
+        for name in cloudevent.as_object().unwrap().keys().filter(|name| name.as_str() != "data") {
+            assert!(
+                name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()),
+                "attribute {name} is not a valid CloudEvents name"
+            );
+        }
+
+        assert_eq!(Event::from_cloudevent(&cloudevent).unwrap(), event);
+
+        // Protobuf payloads travel base64-encoded
+        let mut binary = event.clone();
+        binary.data = EventData::Protobuf(vec![8, 150, 1]);
+        let cloudevent = binary.to_cloudevent("");
+        assert_eq!(cloudevent["source"], "Order");
+        assert_eq!(cloudevent["data_base64"], "CJYB");
+        assert!(cloudevent.get("data").is_none());
+        assert_eq!(Event::from_cloudevent(&cloudevent).unwrap(), binary);
+    }
+
+    #[test]
+    fn test_foreign_cloudevents_fall_back_to_core_attributes() {
+        let id = Uuid::new_v4();
+        let event = Event::from_cloudevent(&json!({
+            "specversion": "1.0",
+            "id": id.to_string(),
+            "source": "/billing/Invoice",
+            "type": "InvoiceIssued",
+            "subject": "invoice-9",
+            "aggregateversion": "3",
+            "data": { "amount": 10 },
+        }))
+        .unwrap();
+        assert_eq!(event.id, id);
+        assert_eq!(event.aggregate_type, "Invoice");
+        assert_eq!(event.aggregate_id, "invoice-9");
+        assert_eq!(event.aggregate_version, 3);
+        assert_eq!(event.event_version, 1);
+
+        let valid = order_placed().to_cloudevent("");
+        for (name, value) in [
+            ("specversion", json!("0.3")),
+            ("id", json!("not-a-uuid")),
+            ("aggregateversion", Value::Null),
+            ("type", json!(5)),
+            ("data_base64", json!("CJYB")),
+        ] {
+            let mut broken = valid.clone();
+            broken[name] = value;
+            assert!(
+                matches!(Event::from_cloudevent(&broken), Err(EventualiError::InvalidEventData(_))),
+                "{name} should be rejected"
+            );
+        }
+    }
+}
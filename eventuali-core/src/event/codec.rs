@@ -0,0 +1,158 @@
+use crate::{EventData, EventualiError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Names of the formats every backend handles natively; codecs may not shadow them.
+const BUILT_IN_FORMATS: [&str; 2] = ["json", "protobuf"];
+
+/// A pluggable wire format for event payloads.
+///
+/// Backends store the codec's `name` alongside each encoded row, so a codec must
+/// keep decoding everything it ever encoded under that name.
+pub trait Codec: Send + Sync {
+    /// Stable identifier recorded with every event written through this codec
+    fn name(&self) -> &str;
+
+    /// Encode event data into the bytes persisted by the backend
+    fn encode(&self, data: &EventData) -> Result<Vec<u8>>;
+
+    /// Decode bytes previously produced by `encode`
+    fn decode(&self, bytes: &[u8]) -> Result<EventData>;
+}
+
+/// Registry of custom codecs, keyed by name, shared by the backends of a store.
+#[derive(Clone, Default)]
+pub struct CodecRegistry {
+    codecs: HashMap<String, Arc<dyn Codec>>,
+}
+
+impl CodecRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a codec, rejecting built-in format names and duplicates.
+    pub fn register<C: Codec + 'static>(&mut self, codec: C) -> Result<()> {
+        self.register_arc(Arc::new(codec))
+    }
+
+    pub fn register_arc(&mut self, codec: Arc<dyn Codec>) -> Result<()> {
+        let name = codec.name().to_string();
+        if Self::is_built_in(&name) {
+            return Err(EventualiError::Configuration(format!(
+                "Codec name '{name}' is reserved for a built-in format"
+            )));
+        }
+        if self.codecs.contains_key(&name) {
+            return Err(EventualiError::Configuration(format!(
+                "Codec '{name}' is already registered"
+            )));
+        }
+
+        self.codecs.insert(name, codec);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Codec>> {
+        self.codecs.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.codecs.contains_key(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.codecs.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Whether `name` is a format the backends encode without a registered codec.
+    pub fn is_built_in(name: &str) -> bool {
+        BUILT_IN_FORMATS.contains(&name)
+    }
+
+    pub fn encode(&self, name: &str, data: &EventData) -> Result<Vec<u8>> {
+        self.require(name)?.encode(data)
+    }
+
+    pub fn decode(&self, name: &str, bytes: &[u8]) -> Result<EventData> {
+        self.require(name)?.decode(bytes)
+    }
+
+    fn require(&self, name: &str) -> Result<&Arc<dyn Codec>> {
+        self.codecs.get(name).ok_or_else(|| {
+            EventualiError::InvalidEventData(format!("Unknown event data type: {name}"))
+        })
+    }
+}
+
+impl std::fmt::Debug for CodecRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CodecRegistry")
+            .field("codecs", &self.names())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ReversedJson;
+
+    impl Codec for ReversedJson {
+        fn name(&self) -> &str {
+            "reversed-json"
+        }
+
+        fn encode(&self, data: &EventData) -> Result<Vec<u8>> {
+            let mut bytes = serde_json::to_vec(&data.to_json::<serde_json::Value>()?)?;
+            bytes.reverse();
+            Ok(bytes)
+        }
+
+        fn decode(&self, bytes: &[u8]) -> Result<EventData> {
+            let mut bytes = bytes.to_vec();
+            bytes.reverse();
+            Ok(EventData::Json(serde_json::from_slice(&bytes)?))
+        }
+    }
+
+    struct Named(&'static str);
+
+    impl Codec for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn encode(&self, _data: &EventData) -> Result<Vec<u8>> {
+            Ok(Vec::new())
+        }
+
+        fn decode(&self, _bytes: &[u8]) -> Result<EventData> {
+            Ok(EventData::Json(serde_json::Value::Null))
+        }
+    }
+
+    #[test]
+    fn test_registered_codec_round_trips() {
+        let mut registry = CodecRegistry::new();
+        registry.register(ReversedJson).unwrap();
+
+        let data = EventData::Json(serde_json::json!({"amount": 42}));
+        let bytes = registry.encode("reversed-json", &data).unwrap();
+        assert_eq!(registry.decode("reversed-json", &bytes).unwrap(), data);
+    }
+
+    #[test]
+    fn test_reserved_and_duplicate_names_are_rejected() {
+        let mut registry = CodecRegistry::new();
+        assert!(registry.register(Named("json")).is_err());
+        assert!(registry.register(Named("protobuf")).is_err());
+
+        registry.register(Named("custom")).unwrap();
+        assert!(registry.register(Named("custom")).is_err());
+        assert!(registry.decode("missing", b"").is_err());
+    }
+}
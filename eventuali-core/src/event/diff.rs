@@ -0,0 +1,142 @@
+//! JSON Patch (RFC 6902) differences between JSON event payloads
+
+use super::EventData;
+use crate::{EventualiError, Result};
+use serde_json::{json, Value};
+
+impl EventData {
+    /// Describe how to turn this payload into `other` as a JSON Patch document.
+    ///
+    /// The result is an array of `add`, `remove` and `replace` operations that,
+    /// applied in order to `self`, yield `other`; identical payloads give an empty
+    /// array. Array elements are compared by index. Protobuf payloads are opaque
+    /// bytes and cannot be diffed.
+    pub fn diff(&self, other: &EventData) -> Result<Value> {
+        match (self, other) {
+            (EventData::Json(from), EventData::Json(to)) => {
+                let mut operations = Vec::new();
+                diff_values(from, to, &mut String::new(), &mut operations);
+                Ok(Value::Array(operations))
+            }
+            _ => Err(EventualiError::InvalidEventData(
+                "Only JSON event data can be diffed".to_string(),
+            )),
+        }
+    }
+}
+
+fn diff_values(from: &Value, to: &Value, path: &mut String, operations: &mut Vec<Value>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(from_map), Value::Object(to_map)) => {
+            for (key, from_value) in from_map {
+                with_segment(path, key, |path| match to_map.get(key) {
+                    Some(to_value) => diff_values(from_value, to_value, path, operations),
+                    None => operations.push(json!({ "op": "remove", "path": path })),
+                });
+            }
+            for (key, to_value) in to_map {
+                if !from_map.contains_key(key) {
+                    with_segment(path, key, |path| {
+                        operations.push(json!({ "op": "add", "path": path, "value": to_value }));
+                    });
+                }
+            }
+        }
+        (Value::Array(from_items), Value::Array(to_items)) => {
+            let shared = from_items.len().min(to_items.len());
+            for index in 0..shared {
+                with_segment(path, &index.to_string(), |path| {
+                    diff_values(&from_items[index], &to_items[index], path, operations);
+                });
+            }
+            // Remove from the back so earlier indices stay valid while applying
+            for index in (shared..from_items.len()).rev() {
+                with_segment(path, &index.to_string(), |path| {
+                    operations.push(json!({ "op": "remove", "path": path }));
+                });
+            }
+            for (index, to_value) in to_items.iter().enumerate().skip(shared) {
+                with_segment(path, &index.to_string(), |path| {
+                    operations.push(json!({ "op": "add", "path": path, "value": to_value }));
+                });
+            }
+        }
+        _ => operations.push(json!({ "op": "replace", "path": path, "value": to })),
+    }
+}
+
+/// Run `f` with `path` extended by one JSON Pointer segment (RFC 6901 escaping)
+fn with_segment(path: &mut String, segment: &str, f: impl FnOnce(&mut String)) {
+    let parent_len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    f(path);
+    path.truncate(parent_len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(from: Value, to: Value) -> Value {
+        EventData::Json(from).diff(&EventData::Json(to)).unwrap()
+    }
+
+    #[test]
+    fn test_diff_produces_add_remove_and_replace() {
+        let from = json!({
+            "name": "Alice",
+            "email": "alice@example.com",
+            "address": { "city": "Paris", "zip": "75001" },
+            "tags": ["a", "b", "c"]
+        });
+        let to = json!({
+            "name": "Alice",
+            "address": { "city": "Lyon", "zip": "75001", "country": "FR" },
+            "tags": ["a", "x"],
+            "age": 30
+        });
+
+        assert_eq!(
+            diff(from, to),
+            json!([
+                { "op": "replace", "path": "/address/city", "value": "Lyon" },
+                { "op": "add", "path": "/address/country", "value": "FR" },
+                { "op": "remove", "path": "/email" },
+                { "op": "replace", "path": "/tags/1", "value": "x" },
+                { "op": "remove", "path": "/tags/2" },
+                { "op": "add", "path": "/age", "value": 30 }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_diff_edge_cases() {
+        assert_eq!(diff(json!({"a": 1}), json!({"a": 1})), json!([]));
+        assert_eq!(
+            diff(json!({"a": 1}), json!(["a"])),
+            json!([{ "op": "replace", "path": "", "value": ["a"] }])
+        );
+        assert_eq!(
+            diff(json!({"a/b": {"c~d": 1}}), json!({"a/b": {"c~d": 2}})),
+            json!([{ "op": "replace", "path": "/a~1b/c~0d", "value": 2 }])
+        );
+        assert_eq!(
+            diff(json!([1]), json!([1, 2, 3])),
+            json!([
+                { "op": "add", "path": "/1", "value": 2 },
+                { "op": "add", "path": "/2", "value": 3 }
+            ])
+        );
+
+        let protobuf = EventData::Protobuf(vec![1, 2, 3]);
+        assert!(matches!(
+            protobuf.diff(&EventData::Json(json!({}))),
+            Err(EventualiError::InvalidEventData(_))
+        ));
+    }
+}
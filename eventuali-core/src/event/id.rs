@@ -0,0 +1,160 @@
+//! Event ID generation
+//!
+//! Event IDs are always 128-bit values stored as `Uuid`, but how they are
+//! generated is configurable. Random v4 UUIDs scatter inserts across the primary
+//! key index; ULIDs put a millisecond timestamp in the high 48 bits, so IDs sort
+//! in creation order both as bytes and as their hyphenated text form.
+//!
+//! Systems whose IDs are decided elsewhere, such as by a central sequencer,
+//! give a store an `IdAllocator`; that store's `new_event_id` then hands out
+//! IDs from it instead of the process default kind.
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use super::EventId;
+
+const ULID_RANDOM_BITS: u32 = 80;
+const ULID_RANDOM_MASK: u128 = (1 << ULID_RANDOM_BITS) - 1;
+
+static DEFAULT_KIND: AtomicU8 = AtomicU8::new(EventIdKind::UuidV4 as u8);
+static LAST_ULID: Mutex<u128> = Mutex::new(0);
+
+/// Source of event IDs generated outside the built-in kinds, for
+/// deterministic or externally coordinated ID schemes
+pub trait IdAllocator: Send + Sync {
+    /// The next event ID; each call must return an ID not handed out before
+    fn allocate(&self) -> crate::Result<EventId>;
+}
+
+impl IdAllocator for EventIdKind {
+    fn allocate(&self) -> crate::Result<EventId> {
+        Ok(self.generate())
+    }
+}
+
+/// How new event IDs are generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventIdKind {
+    /// Random version 4 UUIDs
+    #[default]
+    UuidV4 = 0,
+    /// Time-prefixed ULIDs, monotonic within the process
+    Ulid = 1,
+}
+
+impl EventIdKind {
+    /// Generate a new ID of this kind
+    pub fn generate(self) -> EventId {
+        match self {
+            EventIdKind::UuidV4 => Uuid::new_v4(),
+            EventIdKind::Ulid => next_ulid(Utc::now()),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventIdKind::UuidV4 => "uuid4",
+            EventIdKind::Ulid => "ulid",
+        }
+    }
+}
+
+impl std::str::FromStr for EventIdKind {
+    type Err = crate::EventualiError;
+
+    fn from_str(s: &str) -> crate::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "uuid" | "uuid4" | "uuid_v4" => Ok(EventIdKind::UuidV4),
+            "ulid" => Ok(EventIdKind::Ulid),
+            other => Err(crate::EventualiError::Configuration(format!(
+                "Unknown event ID type '{other}', expected 'uuid4' or 'ulid'"
+            ))),
+        }
+    }
+}
+
+/// Kind used by `Event::new` and `new_event_id` in this process
+pub fn default_event_id_kind() -> EventIdKind {
+    match DEFAULT_KIND.load(Ordering::Relaxed) {
+        1 => EventIdKind::Ulid,
+        _ => EventIdKind::UuidV4,
+    }
+}
+
+/// Change the kind used by `Event::new` and `new_event_id` in this process.
+///
+/// Existing events keep their IDs; both kinds can live in the same store.
+pub fn set_default_event_id_kind(kind: EventIdKind) {
+    DEFAULT_KIND.store(kind as u8, Ordering::Relaxed);
+}
+
+/// Generate an event ID of the process default kind
+pub fn new_event_id() -> EventId {
+    default_event_id_kind().generate()
+}
+
+/// Creation time encoded in a ULID.
+///
+/// Version 4 UUIDs have no timestamp, and nothing in the bits tells the kinds
+/// apart, so only call this on IDs known to be ULIDs.
+pub fn ulid_timestamp(id: &EventId) -> Option<DateTime<Utc>> {
+    let millis = (id.as_u128() >> ULID_RANDOM_BITS) as i64;
+    Utc.timestamp_millis_opt(millis).single()
+}
+
+fn next_ulid(now: DateTime<Utc>) -> EventId {
+    let millis = now.timestamp_millis().max(0) as u128;
+    // A v4 UUID is the random source; two of its low 80 bits are fixed variant
+    // bits, which still leaves 78 random bits per millisecond
+    let random = Uuid::new_v4().as_u128() & ULID_RANDOM_MASK;
+    let candidate = (millis << ULID_RANDOM_BITS) | random;
+
+    let mut last = LAST_ULID.lock().unwrap_or_else(|e| e.into_inner());
+    // Within the same millisecond (or if the clock stepped back), increment the
+    // previous value instead, so IDs from this process never go backwards
+    let value = if candidate > *last { candidate } else { *last + 1 };
+    *last = value;
+    Uuid::from_u128(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ulids_sort_in_creation_order() {
+        let ids: Vec<EventId> = (0..2_000).map(|_| EventIdKind::Ulid.generate()).collect();
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        assert_eq!(sorted, ids);
+
+        let text: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+        let mut sorted_text = text.clone();
+        sorted_text.sort();
+        assert_eq!(sorted_text, text);
+
+        let created = ulid_timestamp(&ids[0]).unwrap();
+        assert!((Utc::now() - created).num_seconds().abs() < 5);
+    }
+
+    #[test]
+    fn test_ulid_stays_monotonic_when_clock_steps_back() {
+        let later = next_ulid(Utc::now() + chrono::Duration::seconds(1));
+        let earlier = next_ulid(Utc::now());
+        assert!(earlier > later);
+    }
+
+    #[test]
+    fn test_event_id_kind_parsing() {
+        assert_eq!("ulid".parse::<EventIdKind>().unwrap(), EventIdKind::Ulid);
+        assert_eq!("uuid4".parse::<EventIdKind>().unwrap(), EventIdKind::UuidV4);
+        assert!("snowflake".parse::<EventIdKind>().is_err());
+        assert_eq!(EventIdKind::default(), EventIdKind::UuidV4);
+    }
+}
@@ -1,3 +1,14 @@
+mod cloudevents;
+mod codec;
+mod diff;
+mod id;
+
+pub use codec::{Codec, CodecRegistry};
+pub use id::{
+    default_event_id_kind, new_event_id, set_default_event_id_kind, ulid_timestamp, EventIdKind,
+    IdAllocator,
+};
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -19,6 +30,10 @@ pub struct Event {
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EventData {
+    /// JSON payload. Integers within the `i64`/`u64` range always round-trip
+    /// exactly; other numbers are parsed as `f64` unless the
+    /// `arbitrary-precision` feature is enabled, which keeps every number as
+    /// its original decimal text.
     Json(serde_json::Value),
     Protobuf(Vec<u8>),
 }
@@ -42,7 +57,7 @@ impl Event {
         data: EventData,
     ) -> Self {
         Self {
-            id: Uuid::new_v4(),
+            id: new_event_id(),
             aggregate_id,
             aggregate_type,
             event_type,
@@ -58,6 +73,12 @@ impl Event {
         self.metadata = metadata;
         self
     }
+
+    /// Replace the generated ID, for example with one from `EventStore::new_event_id`
+    pub fn with_id(mut self, id: EventId) -> Self {
+        self.id = id;
+        self
+    }
 }
 
 
@@ -4,53 +4,90 @@ pub mod store;
 pub mod error;
 pub mod proto;
 pub mod streaming;
+pub mod read_model;
 pub mod snapshot;
 pub mod security;
 pub mod tenancy;
 pub mod performance;
+pub mod clock;
+pub mod testing;
 
 #[cfg(feature = "observability")]
 pub mod observability;
 
-pub use event::{Event, EventData, EventId, EventMetadata};
+pub use event::{
+    Event, EventData, EventId, EventMetadata, Codec, CodecRegistry, EventIdKind,
+    default_event_id_kind, new_event_id, set_default_event_id_kind, ulid_timestamp,
+    IdAllocator,
+};
 pub use aggregate::{Aggregate, AggregateId, AggregateVersion};
-pub use store::{EventStore, EventStoreConfig, EventStoreImpl, create_event_store};
+pub use store::{
+    EventStore, EventStoreConfig, EventStoreImpl, StoreStats, STORAGE_FORMAT_VERSION, TimestampSource, GlobalPositionAllocation, OversizedBatch,
+    AggregateLocks, AggregateLockGuard, PublishOutbox, PublishFailure, OutboxRelay, Compactor, CompactionReport, CompactedStream,
+    DeleteMode, TOMBSTONE_EVENT_TYPE,
+    LenientLoad, QuarantinedRow, ConflictResolution, FailedWrite, FailedWriteFilter, FailedWriteLog,
+    RecentEvents, ExistenceCache, ExistenceCacheStats, StreamAnomaly, StreamValidation,
+    migrate_store, MigrationReport, StoreMigration, ThroughputGovernor,
+    with_operation_timeout, operation_timed_out,
+    create_event_store, create_event_store_with_codecs
+};
+#[cfg(feature = "test-util")]
+pub use store::{BackendOperation, FaultInjectingBackend, FaultInjector, InjectedFault};
+#[cfg(feature = "parquet")]
+pub use store::{ParquetExport, ParquetExportReport};
+pub use clock::{Clock, SystemClock, FixedClock};
 pub use error::{EventualiError, Result};
 pub use proto::ProtoSerializer;
 pub use streaming::{
     EventStreamer, EventStreamReceiver, StreamEvent, Subscription, SubscriptionBuilder,
-    InMemoryEventStreamer, EventStreamProcessor, Projection, ProjectionProcessor,
-    SagaHandler, SagaProcessor
+    InMemoryEventStreamer, SharedStreamer, EventStreamProcessor, Projection, ProjectionProcessor, ProjectionStats,
+    LagWatchdog, LagAlert, LagAlertCallback,
+    ReplayThrottle, SagaHandler, SagaProcessor, CatchUpEvent, CatchUpSubscription,
+    DerivingProjection, DerivedEvent, DerivationRule, StateFold, DERIVED_BY_HEADER,
+    DERIVATION_RULE_HEADER, TypedProjection, EventTypeHandler
+};
+pub use read_model::{
+    ReadModelSink, ReadModelWrite, ReadModelProjection, ReadModelProcessor,
+    SqliteReadModelSink, PostgresReadModelSink
 };
 pub use snapshot::{
     AggregateSnapshot, SnapshotStore, SnapshotService, SnapshotConfig, SnapshotCompression,
-    SnapshotMetadata, SqliteSnapshotStore
+    SnapshotMetadata, StreamedSnapshotData, SqliteSnapshotStore, ZSTD_COMPRESSION_LEVELS,
+    DEFAULT_ZSTD_COMPRESSION_LEVEL, DEFAULT_MAX_SNAPSHOT_STATE_SIZE, ProjectionSnapshot, ProjectionSnapshotStore,
+    SqliteProjectionSnapshotStore, StateCodec, JsonStateCodec, MessagePackStateCodec,
+    STATE_CODEC_METADATA_KEY, state_codec_by_name,
 };
 pub use security::{
-    EventEncryption, KeyManager, EncryptionKey, EncryptedEventData, EncryptionAlgorithm
+    EventEncryption, KeyManager, EncryptionKey, EncryptedEventData, EncryptionAlgorithm, KeyMaterial
 };
 pub use tenancy::{
     TenantId, TenantInfo, TenantConfig, TenantMetadata, TenantIsolation, 
     IsolatedEventStore, TenantScope, TenantQuota, ResourceType, 
     TenantManager, TenantOperations, TenantAwareEventStorage, 
-    TenantStorageMetrics, TenantEventBatch, TenantScopedProjection,
+    TenantStorageMetrics, TenantEventBatch, TenantEventStreamer, TenantSubscription,
+    TenantScopedProjection,
     TenantProjectionManager, TenantProjectionRegistry, TenantProjectionMetrics
 };
 pub use performance::{
-    ConnectionPool, PoolConfig, PoolStats,
+    ConnectionPool, PoolConfig, PoolFairness, ForkPolicy, PoolStats,
     WalConfig, WalOptimizer, WalStats, WalSynchronousMode, WalJournalMode, 
-    TempStoreMode, AutoVacuumMode, benchmark_wal_configurations
+    TempStoreMode, AutoVacuumMode, benchmark_wal_configurations,
+    DryRunConfig, DryRunReport, StageTiming, benchmark_dry_run_writes
 };
 
 #[cfg(feature = "observability")]
 pub use observability::{
     ObservabilityService, ObservabilityServiceBuilder, ObservabilityConfig,
     TelemetryProvider, TracingService, TraceContext, EventTrace,
-    MetricsCollector, PrometheusExporter, EventMetrics, PerformanceMetrics,
+    MetricsCollector, PrometheusExporter, EventMetrics, PerformanceMetrics, PoolMetricsSampler, PrometheusListener,
+    StatsdConfig,
     StructuredLogger, LogLevel, LogContext, CorrelationLogger,
     CorrelationId, CorrelationContext, CorrelationTracker, generate_correlation_id
 };
 
+#[cfg(all(feature = "observability", feature = "statsd"))]
+pub use observability::StatsdExporter;
+
 // Re-export specific backend implementations
 #[cfg(feature = "postgres")]
 pub use store::postgres::PostgreSQLBackend;
@@ -58,6 +95,8 @@ pub use store::postgres::PostgreSQLBackend;
 #[cfg(feature = "sqlite")]
 pub use store::sqlite::SQLiteBackend;
 
+pub use store::memory::MemoryBackend;
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -384,7 +384,7 @@ mod tests {
         
         assert_eq!(child.operation, "child_op");
         assert_eq!(child.service, "test_service");
-        assert_eq!(child.parent_id, Some(parent.correlation_id));
+        assert_eq!(child.parent_id, Some(parent.correlation_id.clone()));
         assert_ne!(child.correlation_id, parent.correlation_id);
     }
 
@@ -253,6 +253,38 @@ impl HealthReport {
     }
 }
 
+/// Outcome of a liveness or readiness probe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProbeResult {
+    /// Whether the probe passed
+    pub passed: bool,
+    /// Why the probe failed, or a short confirmation when it passed
+    pub message: String,
+    /// Component checks the probe was decided on; empty for liveness
+    pub components: Vec<HealthCheckResult>,
+    /// Timestamp of the probe
+    pub timestamp: u64,
+}
+
+impl ProbeResult {
+    fn new(passed: bool, message: String, components: Vec<HealthCheckResult>) -> Self {
+        Self {
+            passed,
+            message,
+            components,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        }
+    }
+
+    /// HTTP status code for a probe endpoint: 200 when passed, 503 otherwise
+    pub fn http_status(&self) -> u16 {
+        if self.passed { 200 } else { 503 }
+    }
+}
+
 /// Service information for health reports
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceInfo {
@@ -666,7 +698,7 @@ impl HealthMonitorService {
                 let timeout = Duration::from_secs(config.check_timeout_seconds);
                 
                 async move {
-                    match tokio::time::timeout(timeout, checker.check()).await {
+                    let result = match tokio::time::timeout(timeout, checker.check()).await {
                         Ok(Ok(result)) => result,
                         Ok(Err(e)) => HealthCheckResult::new(
                             checker.name().to_string(),
@@ -678,6 +710,12 @@ impl HealthMonitorService {
                             HealthStatus::Unhealthy,
                             "Health check timed out".to_string(),
                         ),
+                    };
+                    // A failed or timed-out check of a critical component is still critical
+                    if checker.is_critical() {
+                        result.as_critical()
+                    } else {
+                        result
                     }
                 }
             })
@@ -754,27 +792,67 @@ impl HealthMonitorService {
         self.latest_report.read().await.clone()
     }
 
-    /// Check if service is ready (all critical components healthy)
+    /// Whether the service is ready; the outcome of `readiness`
     pub async fn is_ready(&self) -> bool {
-        if let Some(report) = self.get_latest_report().await {
-            // Check if all critical components are healthy
-            report.components
-                .iter()
-                .filter(|c| c.critical)
-                .all(|c| c.status == HealthStatus::Healthy)
-        } else {
-            false
-        }
+        self.readiness().await.passed
     }
 
-    /// Check if service is live (basic responsiveness)
+    /// Whether the service is live; the outcome of `liveness`
     pub async fn is_live(&self) -> bool {
-        if let Some(report) = self.get_latest_report().await {
-            report.overall_status != HealthStatus::Unhealthy
-        } else {
-            // If no report available, assume live but run quick check
-            true
+        self.liveness().await.passed
+    }
+
+    /// Liveness probe: whether the process itself is still making progress.
+    ///
+    /// Dependencies are deliberately not consulted. Orchestrators restart a
+    /// process whose liveness fails, and restarting every instance during a
+    /// database outage does not bring the database back. The probe only checks
+    /// that the runtime still schedules tasks and that the service's own state
+    /// is not held by a stuck task, each within the check timeout.
+    pub async fn liveness(&self) -> ProbeResult {
+        let timeout = Duration::from_secs(self.config.check_timeout_seconds.max(1));
+
+        let scheduler = tokio::time::timeout(timeout, tokio::spawn(async {})).await;
+        if !matches!(scheduler, Ok(Ok(()))) {
+            return ProbeResult::new(false, "Runtime did not run a task within the check timeout".to_string(), Vec::new());
         }
+
+        if tokio::time::timeout(timeout, self.latest_report.read()).await.is_err() {
+            return ProbeResult::new(false, "Health state lock was not released within the check timeout".to_string(), Vec::new());
+        }
+
+        ProbeResult::new(true, "Process is live".to_string(), Vec::new())
+    }
+
+    /// Readiness probe: whether every critical dependency can serve traffic.
+    ///
+    /// Runs all checks now rather than trusting the cached report, and caches
+    /// the fresh report. Degraded critical components still count as ready;
+    /// failures of non-critical components never fail readiness.
+    pub async fn readiness(&self) -> ProbeResult {
+        let report = match self.run_health_checks().await {
+            Ok(report) => report,
+            Err(e) => return ProbeResult::new(false, format!("Health checks failed to run: {e}"), Vec::new()),
+        };
+
+        let failing: Vec<&str> = report
+            .components
+            .iter()
+            .filter(|c| c.critical && matches!(c.status, HealthStatus::Unhealthy | HealthStatus::Unknown))
+            .map(|c| c.component.as_str())
+            .collect();
+        let probe = if failing.is_empty() {
+            ProbeResult::new(true, "All critical components are available".to_string(), report.components.clone())
+        } else {
+            ProbeResult::new(
+                false,
+                format!("Critical components unavailable: {}", failing.join(", ")),
+                report.components.clone(),
+            )
+        };
+
+        *self.latest_report.write().await = Some(report);
+        probe
     }
 
     /// Get health summary for quick status checks
@@ -799,4 +877,65 @@ impl HealthMonitorService {
         
         summary
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Database checker whose connection can be cut
+    struct SwitchableDatabase {
+        available: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl HealthChecker for SwitchableDatabase {
+        fn name(&self) -> &str {
+            "database"
+        }
+
+        fn is_critical(&self) -> bool {
+            true
+        }
+
+        async fn check(&self) -> Result<HealthCheckResult> {
+            if self.available.load(Ordering::SeqCst) {
+                Ok(HealthCheckResult::new(self.name().to_string(), HealthStatus::Healthy, "Connected".to_string()).as_critical())
+            } else {
+                Err(crate::EventualiError::DatabaseError("connection refused".to_string()))
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_database_outage_fails_readiness_but_not_liveness() {
+        let available = Arc::new(AtomicBool::new(true));
+        let mut service = HealthMonitorService::new(HealthConfig {
+            include_system_metrics: false,
+            background_checks: false,
+            ..Default::default()
+        });
+        service.add_checker(Arc::new(SwitchableDatabase { available: available.clone() }));
+        service.add_checker(Arc::new(StreamingHealthChecker));
+
+        assert!(service.readiness().await.passed);
+        assert!(service.liveness().await.passed);
+
+        available.store(false, Ordering::SeqCst);
+        let readiness = service.readiness().await;
+        assert!(!readiness.passed);
+        assert_eq!(readiness.http_status(), 503);
+        assert!(readiness.message.contains("database"), "{}", readiness.message);
+
+        let liveness = service.liveness().await;
+        assert!(liveness.passed);
+        assert_eq!(liveness.http_status(), 200);
+        // The older boolean checks agree with the probes
+        assert!(!service.is_ready().await);
+        assert!(service.is_live().await);
+
+        available.store(true, Ordering::SeqCst);
+        assert!(service.readiness().await.passed);
+    }
+}
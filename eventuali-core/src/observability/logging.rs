@@ -5,6 +5,7 @@
 use crate::error::{EventualiError, Result};
 use crate::observability::{
     correlation::{CorrelationId, CorrelationContext},
+    redaction::PayloadRedactor,
     telemetry::TraceContext,
     ObservabilityConfig,
 };
@@ -207,6 +208,7 @@ impl LogEntry {
 #[derive(Debug)]
 pub struct StructuredLogger {
     config: ObservabilityConfig,
+    redactor: PayloadRedactor,
     entries: Arc<RwLock<Vec<LogEntry>>>,
     #[allow(dead_code)] // Correlation logger for request tracing (initialized but not currently used in main logger)
     correlation_logger: CorrelationLogger,
@@ -217,6 +219,7 @@ impl StructuredLogger {
     pub fn new(config: &ObservabilityConfig) -> Result<Self> {
         Ok(Self {
             config: config.clone(),
+            redactor: PayloadRedactor::from_config(config),
             entries: Arc::new(RwLock::new(Vec::new())),
             correlation_logger: CorrelationLogger::new(config.service_name.clone()),
         })
@@ -313,6 +316,32 @@ impl StructuredLogger {
         self.correlation_logger.log(level, message, None);
     }
 
+    /// Log a message about a stored event, including its payload with the
+    /// configured sensitive fields masked
+    pub fn log_event(&self, level: LogLevel, message: &str, event: &crate::Event) {
+        let payload = self.redactor.redact_event(event);
+        let context = LogContext::new(&self.config.service_name)
+            .with_attribute("aggregate_id", event.aggregate_id.clone());
+        let entry = LogEntry::new(level, message, context)
+            .with_field("event_id", serde_json::Value::String(event.id.to_string()))
+            .with_field("event_type", serde_json::Value::String(event.event_type.clone()))
+            .with_field("payload", payload.clone());
+
+        if let Ok(mut entries) = self.entries.try_write() {
+            entries.push(entry);
+        }
+
+        let event_id = event.id;
+        let event_type = &event.event_type;
+        match level {
+            LogLevel::Error => tracing::error!(%event_id, event_type, %payload, message = message),
+            LogLevel::Warn => tracing::warn!(%event_id, event_type, %payload, message = message),
+            LogLevel::Info => tracing::info!(%event_id, event_type, %payload, message = message),
+            LogLevel::Debug => tracing::debug!(%event_id, event_type, %payload, message = message),
+            LogLevel::Trace => tracing::trace!(%event_id, event_type, %payload, message = message),
+        }
+    }
+
     /// Get recent log entries
     pub async fn get_recent_entries(&self, limit: usize) -> Vec<LogEntry> {
         let entries = self.entries.read().await;
@@ -678,6 +707,38 @@ mod tests {
         assert_eq!(logger.config.service_name, "eventuali");
     }
 
+    #[tokio::test]
+    async fn test_logged_and_traced_events_mask_sensitive_fields() {
+        let config = ObservabilityConfig {
+            structured_logging: false,
+            redacted_fields: vec!["ssn".to_string()],
+            ..ObservabilityConfig::default()
+        };
+        let event = crate::Event::new(
+            "customer-1".to_string(),
+            "Customer".to_string(),
+            "CustomerVerified".to_string(),
+            1,
+            1,
+            crate::EventData::Json(serde_json::json!({ "ssn": "123-45-6789", "country": "NZ" })),
+        );
+
+        let logger = StructuredLogger::new(&config).unwrap();
+        logger.log_event(LogLevel::Info, "Customer verified", &event);
+        let entry = logger.get_recent_entries(1).await.pop().unwrap();
+        assert_eq!(entry.fields["payload"]["ssn"], crate::observability::REDACTED_VALUE);
+        assert_eq!(entry.fields["payload"]["country"], "NZ");
+        assert!(!entry.to_json().unwrap().contains("123-45-6789"));
+
+        let provider = Arc::new(crate::observability::TelemetryProvider::new(&config).await.unwrap());
+        let tracing_service = crate::observability::TracingService::new(provider);
+        let mut trace = tracing_service.start_trace("verify_customer").await;
+        tracing_service.record_event(&mut trace, &event);
+        let traced = &trace.attributes["event.payload"];
+        assert!(traced.contains(crate::observability::REDACTED_VALUE));
+        assert!(!traced.contains("123-45-6789"));
+    }
+
     #[tokio::test]
     async fn test_log_aggregator() {
         let aggregator = LogAggregator::new();
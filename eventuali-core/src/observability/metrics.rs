@@ -4,14 +4,20 @@
 
 use crate::error::{EventualiError, Result};
 use crate::observability::ObservabilityConfig;
+use crate::performance::{ConnectionPool, PoolStats};
+use crate::streaming::ProjectionStats;
 use metrics::Label;
 use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 // Prometheus encoders - not currently used but available for direct export
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 /// Labels for categorizing metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -133,42 +139,77 @@ pub struct EventMetrics {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// Label value reported for event and aggregate types outside the bounded label set
+pub const OTHER_TYPE_LABEL: &str = "other";
+
+/// Per-type event counts with a bounded set of label values
+#[derive(Debug, Default)]
+struct TypeCounts {
+    counts: HashMap<String, u64>,
+}
+
+impl TypeCounts {
+    /// Count one event, returning the label value it was recorded under
+    fn record(&mut self, value: &str, allowlist: Option<&[String]>, max_labels: usize) -> String {
+        let admitted = match allowlist {
+            Some(allowed) => allowed.iter().any(|a| a == value),
+            None => {
+                self.counts.contains_key(value)
+                    || self.counts.keys().filter(|k| *k != OTHER_TYPE_LABEL).count() < max_labels
+            }
+        };
+        let label = if admitted && value != OTHER_TYPE_LABEL { value } else { OTHER_TYPE_LABEL };
+        *self.counts.entry(label.to_string()).or_insert(0) += 1;
+        label.to_string()
+    }
+}
+
 /// Main metrics collector
 pub struct MetricsCollector {
-    #[allow(dead_code)]
     prometheus_handle: Option<PrometheusHandle>,
+    #[cfg(feature = "statsd")]
+    statsd: Option<Arc<StatsdExporter>>,
     config: ObservabilityConfig,
     performance_metrics: Arc<RwLock<PerformanceMetrics>>,
     counters: Arc<Mutex<HashMap<String, u64>>>,
-    gauges: Arc<Mutex<HashMap<String, f64>>>,
+    gauges: Arc<Mutex<HashMap<MetricSeries, f64>>>,
     histograms: Arc<Mutex<HashMap<String, Vec<f64>>>>,
+    event_type_counts: Arc<Mutex<TypeCounts>>,
+    aggregate_type_counts: Arc<Mutex<TypeCounts>>,
 }
 
 impl MetricsCollector {
     /// Create a new metrics collector
     pub fn new(config: &ObservabilityConfig) -> Result<Self> {
         let prometheus_handle = if config.metrics_enabled {
-            match PrometheusBuilder::new().install() {
-                Ok(()) => {
-                    tracing::info!("Prometheus recorder installed successfully");
-                    None // For now, we don't store the handle
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to install Prometheus recorder: {}", e);
-                    None
-                }
-            }
+            install_prometheus_recorder()
         } else {
             None
         };
 
+        #[cfg(feature = "statsd")]
+        let statsd = match &config.statsd {
+            Some(statsd) if config.metrics_enabled => Some(StatsdExporter::start(statsd)?),
+            _ => None,
+        };
+        #[cfg(not(feature = "statsd"))]
+        if config.statsd.is_some() {
+            return Err(EventualiError::Configuration(
+                "StatsD export requires the `statsd` feature".to_string(),
+            ));
+        }
+
         Ok(Self {
             prometheus_handle,
+            #[cfg(feature = "statsd")]
+            statsd,
             config: config.clone(),
             performance_metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
             counters: Arc::new(Mutex::new(HashMap::new())),
             gauges: Arc::new(Mutex::new(HashMap::new())),
             histograms: Arc::new(Mutex::new(HashMap::new())),
+            event_type_counts: Arc::new(Mutex::new(TypeCounts::default())),
+            aggregate_type_counts: Arc::new(Mutex::new(TypeCounts::default())),
         })
     }
 
@@ -200,6 +241,11 @@ impl MetricsCollector {
             "Counter incremented"
         );
         
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            statsd.count(name, 1, &labels);
+        }
+
         // Also track locally for aggregation
         if let Ok(mut counters) = self.counters.lock() {
             *counters.entry(name.to_string()).or_insert(0) += 1;
@@ -215,10 +261,15 @@ impl MetricsCollector {
             ?labels,
             "Gauge recorded"
         );
+        metrics::gauge!(name.to_string(), labels.to_metrics_labels()).set(value);
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            statsd.gauge(name, value, &labels);
+        }
         
-        // Also track locally
+        // Also track locally, one value per label set
         if let Ok(mut gauges) = self.gauges.lock() {
-            gauges.insert(name.to_string(), value);
+            gauges.insert(metric_series(name, &labels), value);
         }
     }
 
@@ -231,6 +282,16 @@ impl MetricsCollector {
             ?labels,
             "Histogram recorded"
         );
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            // Durations go out as StatsD timers, anything else as histograms
+            match name.strip_suffix("_seconds") {
+                Some(_) if value.is_finite() && value >= 0.0 => {
+                    statsd.time(name, Duration::from_secs_f64(value), &labels)
+                }
+                _ => statsd.histogram(name, value, &labels),
+            }
+        }
         
         // Also track locally
         if let Ok(mut histograms) = self.histograms.lock() {
@@ -248,6 +309,22 @@ impl MetricsCollector {
         // Increment event counter
         self.increment_counter("eventuali_events_processed_total", labels.clone());
 
+        // Per-type counters, with label values bounded by the configured allowlist or limit
+        let event_type_allowlist = self.config.event_type_allowlist.as_deref();
+        if let Some(event_type) = self.record_type(&self.event_type_counts, &metrics.event_type, event_type_allowlist) {
+            let type_labels = MetricLabels::new().with_label("event_type", event_type);
+            metrics::counter!("eventuali_events_by_type_total", type_labels.to_metrics_labels()).increment(1);
+            self.increment_counter("eventuali_events_by_type_total", type_labels);
+        }
+        let aggregate_type_allowlist = self.config.aggregate_type_allowlist.as_deref();
+        if let Some(aggregate_type) =
+            self.record_type(&self.aggregate_type_counts, &metrics.aggregate_type, aggregate_type_allowlist)
+        {
+            let type_labels = MetricLabels::new().with_label("aggregate_type", aggregate_type);
+            metrics::counter!("eventuali_events_by_aggregate_type_total", type_labels.to_metrics_labels()).increment(1);
+            self.increment_counter("eventuali_events_by_aggregate_type_total", type_labels);
+        }
+
         // Record durations
         self.record_metric("eventuali_event_operation_duration_seconds", 
                           metrics.operation_duration_ms / 1000.0, labels.clone());
@@ -270,6 +347,27 @@ impl MetricsCollector {
         }
     }
 
+    fn record_type(&self, counts: &Mutex<TypeCounts>, value: &str, allowlist: Option<&[String]>) -> Option<String> {
+        let mut counts = counts.lock().ok()?;
+        Some(counts.record(value, allowlist, self.config.max_type_labels))
+    }
+
+    /// Events recorded per event type label, with overflow counted under `other`
+    pub fn event_type_counts(&self) -> HashMap<String, u64> {
+        self.event_type_counts
+            .lock()
+            .map(|c| c.counts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Events recorded per aggregate type label, with overflow counted under `other`
+    pub fn aggregate_type_counts(&self) -> HashMap<String, u64> {
+        self.aggregate_type_counts
+            .lock()
+            .map(|c| c.counts.clone())
+            .unwrap_or_default()
+    }
+
     /// Get current performance metrics
     pub async fn get_performance_metrics(&self) -> PerformanceMetrics {
         let mut metrics = self.performance_metrics.write().await;
@@ -283,11 +381,11 @@ impl MetricsCollector {
         }
 
         // Update gauges from local tracking
-        if let Ok(gauges) = self.gauges.lock() {
-            metrics.active_connections = *gauges.get("eventuali_active_connections").unwrap_or(&0.0) as u64;
-            metrics.memory_usage_bytes = *gauges.get("eventuali_memory_usage_bytes").unwrap_or(&0.0) as u64;
-            metrics.cpu_usage_percent = *gauges.get("eventuali_cpu_usage_percent").unwrap_or(&0.0);
-        }
+        let unlabelled = MetricLabels::new();
+        let gauge = |name| self.gauge_value(name, &unlabelled).unwrap_or(0.0);
+        metrics.active_connections = gauge("eventuali_active_connections") as u64;
+        metrics.memory_usage_bytes = gauge("eventuali_memory_usage_bytes") as u64;
+        metrics.cpu_usage_percent = gauge("eventuali_cpu_usage_percent");
 
         // Calculate throughput (simplified)
         let duration = chrono::Utc::now() - metrics.timestamp;
@@ -300,8 +398,78 @@ impl MetricsCollector {
         metrics.clone()
     }
 
+    /// Record a connection pool's statistics as gauges labelled with `pool_name`
+    pub fn record_pool_stats(&self, pool_name: &str, stats: &PoolStats) {
+        let completed = stats.successful_requests + stats.failed_requests;
+        let success_rate = if completed == 0 {
+            1.0
+        } else {
+            stats.successful_requests as f64 / completed as f64
+        };
+
+        for (name, value) in [
+            ("eventuali_pool_total_connections", stats.total_connections as f64),
+            ("eventuali_pool_active_connections", stats.active_connections as f64),
+            ("eventuali_pool_idle_connections", stats.idle_connections as f64),
+            ("eventuali_pool_waiting_requests", stats.waiting_requests as f64),
+            ("eventuali_pool_avg_wait_time_ms", stats.avg_wait_time_ms),
+            ("eventuali_pool_max_wait_time_ms", stats.max_wait_time_ms as f64),
+            ("eventuali_pool_success_rate", success_rate),
+        ] {
+            self.record_gauge(name, value, MetricLabels::new().with_label("pool", pool_name));
+        }
+    }
+
+    /// Record a projection's throughput, error rate, lag and position as
+    /// gauges labelled with `projection`
+    pub fn record_projection_stats(&self, projection: &str, stats: &ProjectionStats) {
+        for (name, value) in [
+            ("eventuali_projection_events_processed", stats.events_processed as f64),
+            ("eventuali_projection_errors", stats.errors as f64),
+            ("eventuali_projection_events_per_second", stats.events_per_second),
+            ("eventuali_projection_error_rate", stats.error_rate),
+            ("eventuali_projection_lag_events", stats.lag as f64),
+            ("eventuali_projection_last_position", stats.last_position.unwrap_or(0) as f64),
+        ] {
+            self.record_gauge(name, value, MetricLabels::new().with_label("projection", projection));
+        }
+    }
+
+    /// Last value recorded for the gauge `name` with exactly `labels`
+    pub fn gauge_value(&self, name: &str, labels: &MetricLabels) -> Option<f64> {
+        self.gauges.lock().ok()?.get(&metric_series(name, labels)).copied()
+    }
+
+    /// Render every metric in the Prometheus text format, if metrics are enabled
+    pub fn render_prometheus(&self) -> Option<String> {
+        self.prometheus_handle.as_ref().map(PrometheusHandle::render)
+    }
+
+    /// Serve the Prometheus text format over HTTP on `addr`, answering every
+    /// request with the current metrics. Must be called from within a Tokio
+    /// runtime.
+    ///
+    /// Creating a collector never opens a port; the listener runs until the
+    /// returned handle is stopped or dropped.
+    pub async fn serve_prometheus(&self, addr: SocketAddr) -> Result<PrometheusListener> {
+        let handle = self.prometheus_handle.clone().ok_or_else(|| {
+            EventualiError::Configuration("Prometheus export requires metrics to be enabled".to_string())
+        })?;
+        PrometheusListener::bind(handle, addr).await
+    }
+
+    /// The StatsD exporter metrics are pushed to, if one is configured
+    #[cfg(feature = "statsd")]
+    pub fn statsd_exporter(&self) -> Option<&Arc<StatsdExporter>> {
+        self.statsd.as_ref()
+    }
+
     /// Shutdown the metrics collector
     pub async fn shutdown(&self) -> Result<()> {
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &self.statsd {
+            statsd.flush()?;
+        }
         tracing::info!("Metrics collector shut down successfully");
         Ok(())
     }
@@ -316,6 +484,367 @@ impl std::fmt::Debug for MetricsCollector {
             .field("counters", &"[Counters]")
             .field("gauges", &"[Gauges]")
             .field("histograms", &"[Histograms]")
+            .field("event_type_counts", &"[TypeCounts]")
+            .field("aggregate_type_counts", &"[TypeCounts]")
+            .finish()
+    }
+}
+
+/// Install the process-wide Prometheus recorder once and share its handle.
+///
+/// No listener is started here; see [`MetricsCollector::serve_prometheus`].
+fn install_prometheus_recorder() -> Option<PrometheusHandle> {
+    static HANDLE: OnceLock<Option<PrometheusHandle>> = OnceLock::new();
+
+    HANDLE
+        .get_or_init(|| {
+            let recorder = PrometheusBuilder::new().build_recorder();
+            let handle = recorder.handle();
+
+            match metrics::set_global_recorder(recorder) {
+                Ok(()) => {
+                    tracing::info!("Prometheus recorder installed successfully");
+                    Some(handle)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to install Prometheus recorder: {}", e);
+                    None
+                }
+            }
+        })
+        .clone()
+}
+
+/// HTTP listener serving a Prometheus scrape endpoint, started with
+/// [`MetricsCollector::serve_prometheus`].
+///
+/// The listener closes when it is stopped or dropped.
+pub struct PrometheusListener {
+    local_addr: SocketAddr,
+    task: JoinHandle<()>,
+}
+
+impl PrometheusListener {
+    async fn bind(handle: PrometheusHandle, addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await.map_err(|e| {
+            EventualiError::ObservabilityError(format!("Failed to start Prometheus listener on {addr}: {e}"))
+        })?;
+        let local_addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            loop {
+                let mut stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        tracing::warn!("Failed to accept Prometheus scrape: {}", e);
+                        continue;
+                    }
+                };
+                let handle = handle.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = respond_to_scrape(&mut stream, &handle).await {
+                        tracing::debug!("Prometheus scrape failed: {}", e);
+                    }
+                });
+            }
+        });
+        Ok(Self { local_addr, task })
+    }
+
+    /// Address the listener is bound to, with the actual port when `0` was asked for
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for PrometheusListener {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Read one request's headers and answer with the rendered metrics
+async fn respond_to_scrape(stream: &mut TcpStream, handle: &PrometheusHandle) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 16 * 1024 {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&buf[..read]);
+    }
+
+    let body = handle.render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Default interval between connection pool samples
+pub const DEFAULT_POOL_SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Background task that periodically copies a connection pool's statistics into a
+/// `MetricsCollector`, so pool health shows up next to the other metrics.
+///
+/// Sampling stops when the sampler is stopped or dropped.
+pub struct PoolMetricsSampler {
+    task: JoinHandle<()>,
+}
+
+impl PoolMetricsSampler {
+    /// Sample `pool` every `interval`, starting immediately. Must be called from
+    /// within a Tokio runtime.
+    pub fn start(
+        collector: Arc<MetricsCollector>,
+        pool: ConnectionPool,
+        pool_name: impl Into<String>,
+        interval: Duration,
+    ) -> Self {
+        let pool_name = pool_name.into();
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let stats = pool.get_stats().await;
+                collector.record_pool_stats(&pool_name, &stats);
+            }
+        });
+        Self { task }
+    }
+
+    pub fn stop(self) {
+        self.task.abort();
+    }
+}
+
+impl Drop for PoolMetricsSampler {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Default interval between pushes to a StatsD endpoint
+pub const DEFAULT_STATSD_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Where and how often to push metrics to a StatsD endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsdConfig {
+    /// `host:port` of the StatsD or DogStatsD agent
+    pub endpoint: String,
+    /// Prepended to every metric name, joined with a `.`; empty for none
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default = "default_statsd_flush_interval")]
+    pub flush_interval: Duration,
+    /// Tags added to every metric, ahead of each metric's own labels
+    #[serde(default)]
+    pub tags: MetricLabels,
+}
+
+fn default_statsd_flush_interval() -> Duration {
+    DEFAULT_STATSD_FLUSH_INTERVAL
+}
+
+impl StatsdConfig {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            prefix: String::new(),
+            flush_interval: DEFAULT_STATSD_FLUSH_INTERVAL,
+            tags: MetricLabels::new(),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    pub fn with_tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags = self.tags.with_label(key, value);
+        self
+    }
+}
+
+/// A metric name with its labels sorted, identifying one series
+type MetricSeries = (String, Vec<(String, String)>);
+
+fn metric_series(name: &str, labels: &MetricLabels) -> MetricSeries {
+    let mut tags: Vec<(String, String)> = labels
+        .labels
+        .iter()
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    tags.sort();
+    (name.to_string(), tags)
+}
+
+/// Metrics recorded since the last push
+#[cfg(feature = "statsd")]
+#[derive(Debug, Default)]
+struct PendingStatsd {
+    /// Summed, so each push sends one count per series
+    counters: HashMap<MetricSeries, i64>,
+    /// Latest value per series
+    gauges: HashMap<MetricSeries, f64>,
+    /// Every sample, since the agent computes the percentiles
+    timers: Vec<(MetricSeries, Duration)>,
+    histograms: Vec<(MetricSeries, f64)>,
+}
+
+/// Pushes counters, gauges and timers to a StatsD endpoint over UDP.
+///
+/// Metrics are buffered and sent every flush interval by a background task,
+/// with `MetricLabels` becoming DogStatsD tags (`|#key:value`). The task stops
+/// once the exporter is dropped. Sends are fire-and-forget: UDP gives no
+/// delivery guarantee, and an unreachable agent never blocks the caller.
+#[cfg(feature = "statsd")]
+pub struct StatsdExporter {
+    client: cadence::StatsdClient,
+    pending: Mutex<PendingStatsd>,
+}
+
+#[cfg(feature = "statsd")]
+impl StatsdExporter {
+    /// Exporter that only sends when `flush` is called
+    pub fn new(config: &StatsdConfig) -> Result<Self> {
+        let socket = std::net::UdpSocket::bind("0.0.0.0:0")
+            .map_err(|e| EventualiError::ObservabilityError(format!("Failed to bind StatsD socket: {e}")))?;
+        let sink = cadence::UdpMetricSink::from(config.endpoint.as_str(), socket).map_err(|e| {
+            EventualiError::Configuration(format!("Invalid StatsD endpoint {}: {e}", config.endpoint))
+        })?;
+
+        let mut tags: Vec<_> = config.tags.labels.iter().collect();
+        tags.sort();
+        let client = tags
+            .into_iter()
+            .fold(cadence::StatsdClient::builder(&config.prefix, sink), |builder, (k, v)| {
+                builder.with_tag(k, v)
+            })
+            .with_error_handler(|e| tracing::debug!("Failed to send StatsD metric: {}", e))
+            .build();
+
+        Ok(Self {
+            client,
+            pending: Mutex::new(PendingStatsd::default()),
+        })
+    }
+
+    /// Exporter that also flushes every `config.flush_interval`. Must be called
+    /// from within a Tokio runtime.
+    pub fn start(config: &StatsdConfig) -> Result<Arc<Self>> {
+        if config.flush_interval.is_zero() {
+            return Err(EventualiError::Configuration(
+                "StatsD flush interval must be positive".to_string(),
+            ));
+        }
+        let exporter = Arc::new(Self::new(config)?);
+        let weak = Arc::downgrade(&exporter);
+        let interval = config.flush_interval;
+        tokio::runtime::Handle::try_current()
+            .map_err(|_| EventualiError::Configuration("StatsD export needs a Tokio runtime".to_string()))?
+            .spawn(async move {
+                let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticker.tick().await;
+                    let Some(exporter) = weak.upgrade() else {
+                        break;
+                    };
+                    if let Err(e) = exporter.flush() {
+                        tracing::warn!("Failed to push metrics to StatsD: {}", e);
+                    }
+                }
+            });
+        Ok(exporter)
+    }
+
+    /// Add `value` to a counter
+    pub fn count(&self, name: &str, value: i64, labels: &MetricLabels) {
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending.counters.entry(metric_series(name, labels)).or_insert(0) += value;
+        }
+    }
+
+    /// Set a gauge, replacing any value not yet pushed
+    pub fn gauge(&self, name: &str, value: f64, labels: &MetricLabels) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.gauges.insert(metric_series(name, labels), value);
+        }
+    }
+
+    /// Record a duration, sent in milliseconds
+    pub fn time(&self, name: &str, duration: Duration, labels: &MetricLabels) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.timers.push((metric_series(name, labels), duration));
+        }
+    }
+
+    pub fn histogram(&self, name: &str, value: f64, labels: &MetricLabels) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.histograms.push((metric_series(name, labels), value));
+        }
+    }
+
+    /// Send everything recorded since the last flush, returning the number of
+    /// metric lines sent
+    pub fn flush(&self) -> Result<usize> {
+        use cadence::prelude::*;
+
+        let pending = {
+            let mut pending = self
+                .pending
+                .lock()
+                .map_err(|_| EventualiError::InvalidState("StatsD buffer lock poisoned".to_string()))?;
+            std::mem::take(&mut *pending)
+        };
+
+        fn tagged<'m, 'c, T: cadence::Metric + From<String>>(
+            builder: cadence::MetricBuilder<'m, 'c, T>,
+            tags: &'m [(String, String)],
+        ) -> cadence::MetricBuilder<'m, 'c, T> {
+            tags.iter().fold(builder, |builder, (k, v)| builder.with_tag(k, v))
+        }
+
+        let mut sent = 0;
+        for ((name, tags), value) in &pending.counters {
+            tagged(self.client.count_with_tags(name, *value), tags).send();
+            sent += 1;
+        }
+        for ((name, tags), value) in &pending.gauges {
+            tagged(self.client.gauge_with_tags(name, *value), tags).send();
+            sent += 1;
+        }
+        for ((name, tags), duration) in &pending.timers {
+            tagged(self.client.time_with_tags(name, *duration), tags).send();
+            sent += 1;
+        }
+        for ((name, tags), value) in &pending.histograms {
+            tagged(self.client.histogram_with_tags(name, *value), tags).send();
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
+
+#[cfg(feature = "statsd")]
+impl std::fmt::Debug for StatsdExporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatsdExporter")
+            .field("client", &self.client)
             .finish()
     }
 }
@@ -376,7 +905,7 @@ mod tests {
             metrics_enabled: false, // Disable for testing
             ..ObservabilityConfig::default()
         };
-        let collector = MetricsCollector::new(&config).unwrap();
+        assert!(MetricsCollector::new(&config).is_ok());
         
         // Should succeed without Prometheus
         assert!(!config.metrics_enabled);
@@ -416,6 +945,179 @@ mod tests {
         assert_eq!(metrics.payload_size_bytes, 1024);
     }
 
+    fn event_metrics(event_type: &str, aggregate_type: &str) -> EventMetrics {
+        EventMetrics {
+            event_type: event_type.to_string(),
+            aggregate_type: aggregate_type.to_string(),
+            tenant_id: None,
+            operation_duration_ms: 1.0,
+            payload_size_bytes: 64,
+            serialization_duration_ms: 0.1,
+            storage_duration_ms: 0.5,
+            success: true,
+            error_type: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_per_type_event_counts() {
+        let config = ObservabilityConfig {
+            metrics_enabled: false,
+            max_type_labels: 2,
+            ..ObservabilityConfig::default()
+        };
+        let collector = MetricsCollector::new(&config).unwrap();
+
+        for (event_type, aggregate_type) in [
+            ("UserCreated", "User"),
+            ("OrderPlaced", "Order"),
+            ("UserCreated", "User"),
+            ("OrderShipped", "Order"),
+            ("UserDeleted", "User"),
+            ("OrderPlaced", "Order"),
+        ] {
+            collector.record_event_metrics(event_metrics(event_type, aggregate_type)).await;
+        }
+
+        let by_type = collector.event_type_counts();
+        assert_eq!(by_type.len(), 3);
+        assert_eq!(by_type["UserCreated"], 2);
+        assert_eq!(by_type["OrderPlaced"], 2);
+        assert_eq!(by_type[OTHER_TYPE_LABEL], 2);
+
+        let by_aggregate = collector.aggregate_type_counts();
+        assert_eq!(by_aggregate.len(), 2);
+        assert_eq!(by_aggregate["User"], 3);
+        assert_eq!(by_aggregate["Order"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_per_type_counts_respect_allowlist() {
+        let config = ObservabilityConfig {
+            metrics_enabled: false,
+            event_type_allowlist: Some(vec!["UserCreated".to_string(), "Order".to_string()]),
+            aggregate_type_allowlist: Some(vec!["User".to_string()]),
+            ..ObservabilityConfig::default()
+        };
+        let collector = MetricsCollector::new(&config).unwrap();
+
+        collector.record_event_metrics(event_metrics("UserCreated", "User")).await;
+        collector.record_event_metrics(event_metrics("OrderPlaced", "Order")).await;
+        collector.record_event_metrics(event_metrics("UserCreated", "User")).await;
+
+        collector.record_event_metrics(event_metrics("Order", "Order")).await;
+
+        // Each label checks only its own allowlist
+        let by_type = collector.event_type_counts();
+        assert_eq!(by_type["UserCreated"], 2);
+        assert_eq!(by_type["Order"], 1);
+        assert_eq!(by_type[OTHER_TYPE_LABEL], 1);
+        assert!(!by_type.contains_key("OrderPlaced"));
+
+        let by_aggregate = collector.aggregate_type_counts();
+        assert_eq!(by_aggregate["User"], 2);
+        assert_eq!(by_aggregate[OTHER_TYPE_LABEL], 2);
+        assert!(!by_aggregate.contains_key("Order"));
+    }
+
+    #[tokio::test]
+    async fn test_pool_sampler_exports_gauges() {
+        let collector = Arc::new(MetricsCollector::new(&ObservabilityConfig::default()).unwrap());
+        let pool = ConnectionPool::new(":memory:".to_string(), crate::performance::PoolConfig::default())
+            .await
+            .unwrap();
+        let _held = pool.get_connection().await.unwrap();
+
+        let sampler = PoolMetricsSampler::start(
+            collector.clone(),
+            pool.clone(),
+            "primary",
+            Duration::from_millis(10),
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        sampler.stop();
+
+        let primary = MetricLabels::new().with_label("pool", "primary");
+        assert_eq!(collector.gauge_value("eventuali_pool_total_connections", &primary), Some(5.0));
+        assert_eq!(collector.gauge_value("eventuali_pool_active_connections", &primary), Some(1.0));
+        assert_eq!(collector.gauge_value("eventuali_pool_idle_connections", &primary), Some(4.0));
+        assert_eq!(collector.gauge_value("eventuali_pool_success_rate", &primary), Some(1.0));
+        assert_eq!(collector.gauge_value("eventuali_pool_total_connections", &MetricLabels::new()), None);
+
+        let rendered = collector.render_prometheus().expect("metrics are enabled");
+        assert!(rendered.contains("eventuali_pool_active_connections{pool=\"primary\"} 1"), "{rendered}");
+        assert!(rendered.contains("eventuali_pool_idle_connections{pool=\"primary\"} 4"), "{rendered}");
+        assert!(rendered.contains("eventuali_pool_success_rate{pool=\"primary\"} 1"), "{rendered}");
+    }
+
+    #[tokio::test]
+    async fn test_prometheus_listener_is_started_explicitly() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let collector = MetricsCollector::new(&ObservabilityConfig::default()).unwrap();
+        collector.record_gauge("eventuali_listener_test", 7.0, MetricLabels::new());
+
+        let listener = collector.serve_prometheus("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let addr = listener.local_addr();
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.contains("eventuali_listener_test 7"), "{response}");
+
+        listener.stop();
+        tokio::task::yield_now().await;
+        assert!(tokio::net::TcpStream::connect(addr).await.is_err());
+
+        let disabled = ObservabilityConfig { metrics_enabled: false, ..ObservabilityConfig::default() };
+        let result = MetricsCollector::new(&disabled).unwrap().serve_prometheus(addr).await;
+        assert!(matches!(result, Err(EventualiError::Configuration(_))));
+    }
+
+    #[cfg(feature = "statsd")]
+    #[tokio::test]
+    async fn test_statsd_exporter_pushes_tagged_lines_each_interval() {
+        let agent = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let config = StatsdConfig::new(agent.local_addr().unwrap().to_string())
+            .with_prefix("eventuali")
+            .with_flush_interval(Duration::from_millis(20))
+            .with_tag("env", "test");
+        let exporter = StatsdExporter::start(&config).unwrap();
+
+        let labels = MetricLabels::new()
+            .with_label("event_type", "UserCreated")
+            .with_label("aggregate_type", "User");
+        exporter.count("events_total", 1, &labels);
+        exporter.count("events_total", 2, &labels);
+        exporter.gauge("active_connections", 4.5, &MetricLabels::new());
+        exporter.time("save_duration_seconds", Duration::from_millis(12), &MetricLabels::new());
+
+        let mut lines = Vec::new();
+        let mut buf = [0u8; 1024];
+        while lines.len() < 3 {
+            let len = tokio::time::timeout(Duration::from_secs(5), agent.recv(&mut buf))
+                .await
+                .expect("the exporter should push within the interval")
+                .unwrap();
+            lines.push(String::from_utf8(buf[..len].to_vec()).unwrap());
+        }
+        lines.sort();
+
+        assert_eq!(
+            lines,
+            vec![
+                "eventuali.active_connections:4.5|g|#env:test",
+                "eventuali.events_total:3|c|#env:test,aggregate_type:User,event_type:UserCreated",
+                "eventuali.save_duration_seconds:12|ms|#env:test",
+            ]
+        );
+
+        // Pushed metrics are not sent again
+        assert_eq!(exporter.flush().unwrap(), 0);
+    }
+
     #[test]
     fn test_operation_timer() {
         let labels = MetricLabels::new();
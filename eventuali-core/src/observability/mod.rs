@@ -14,6 +14,7 @@ pub mod logging;
 pub mod correlation;
 pub mod health;
 pub mod profiling;
+pub mod redaction;
 
 pub use telemetry::{
     ObservabilityConfig, TelemetryProvider, TracingService, 
@@ -21,8 +22,11 @@ pub use telemetry::{
 };
 pub use metrics::{
     MetricsCollector, PrometheusExporter, EventMetrics, 
-    PerformanceMetrics, OperationTimer, MetricLabels
+    PerformanceMetrics, OperationTimer, MetricLabels, OTHER_TYPE_LABEL,
+    PoolMetricsSampler, PrometheusListener, DEFAULT_POOL_SAMPLE_INTERVAL, StatsdConfig, DEFAULT_STATSD_FLUSH_INTERVAL
 };
+#[cfg(feature = "statsd")]
+pub use metrics::StatsdExporter;
 pub use logging::{
     StructuredLogger, LogLevel, LogContext, CorrelationLogger,
     ObservabilityLogger, LogEntry, LogAggregator
@@ -35,8 +39,9 @@ pub use health::{
     HealthStatus, HealthCheckResult, SystemMetrics, SystemHealthThresholds,
     HealthReport, ServiceInfo, HealthConfig, HealthChecker, 
     DatabaseHealthChecker, EventStoreHealthChecker, StreamingHealthChecker,
-    SecurityHealthChecker, TenancyHealthChecker, HealthMonitorService
+    SecurityHealthChecker, TenancyHealthChecker, HealthMonitorService, ProbeResult
 };
+pub use redaction::{PayloadRedactor, REDACTED_VALUE};
 pub use profiling::{
     PerformanceProfiler, PerformanceProfilerBuilder, ProfilingConfig,
     ProfileType, ProfileEntry, MemoryInfo, IoInfo, CallGraphNode,
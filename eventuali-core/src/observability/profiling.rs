@@ -690,7 +690,7 @@ impl PerformanceProfiler {
 
         // Identify top bottlenecks
         let mut sorted_functions: Vec<_> = function_times.iter().collect();
-        sorted_functions.sort_by(|a, b| b.1.0.cmp(&a.1.0));
+        sorted_functions.sort_by_key(|(_, (time, _))| std::cmp::Reverse(*time));
 
         for (function, (time, count)) in sorted_functions.iter().take(10) {
             let percentage = (time.as_nanos() as f64 / total_time.as_nanos() as f64) * 100.0;
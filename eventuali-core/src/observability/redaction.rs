@@ -0,0 +1,128 @@
+//! Payload redaction for logs and traces
+//!
+//! Event payloads often carry PII that must not reach the telemetry pipeline.
+//! A `PayloadRedactor` masks the values of sensitive JSON fields before a
+//! payload is attached to a log entry or trace. Fields are named explicitly or
+//! through the retention `DataCategory`s, whose indicator fields are the same
+//! ones retention classification looks for.
+
+use crate::observability::ObservabilityConfig;
+use crate::security::DataCategory;
+use crate::{Event, EventData};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Replacement for the value of every redacted field
+pub const REDACTED_VALUE: &str = "[REDACTED]";
+
+/// Masks sensitive fields in event payloads
+#[derive(Debug, Clone, Default)]
+pub struct PayloadRedactor {
+    /// Field names redacted on an exact, case-insensitive match
+    fields: HashSet<String>,
+    /// Fragments that redact any field whose name contains them
+    indicators: Vec<&'static str>,
+}
+
+impl PayloadRedactor {
+    /// Redact fields whose name matches one of `fields`, ignoring case
+    pub fn new<I, S>(fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            fields: fields.into_iter().map(|f| f.as_ref().to_lowercase()).collect(),
+            indicators: Vec::new(),
+        }
+    }
+
+    /// Also redact every field whose name contains an indicator of one of
+    /// `categories`, e.g. `customer_email` for `PersonalData`
+    pub fn with_categories(mut self, categories: &[DataCategory]) -> Self {
+        for category in categories {
+            for indicator in category.indicator_fields() {
+                if !self.indicators.contains(indicator) {
+                    self.indicators.push(indicator);
+                }
+            }
+        }
+        self
+    }
+
+    /// Redactor for the fields and categories listed in `config`
+    pub fn from_config(config: &ObservabilityConfig) -> Self {
+        Self::new(&config.redacted_fields).with_categories(&config.redacted_categories)
+    }
+
+    /// Whether nothing would be redacted
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty() && self.indicators.is_empty()
+    }
+
+    /// Whether values under `field` are masked
+    pub fn is_sensitive(&self, field: &str) -> bool {
+        let field = field.to_lowercase();
+        self.fields.contains(&field) || self.indicators.iter().any(|i| field.contains(i))
+    }
+
+    /// Copy of `value` with every sensitive field masked, at any depth
+    pub fn redact_json(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = if self.is_sensitive(key) {
+                            Value::String(REDACTED_VALUE.to_string())
+                        } else {
+                            self.redact_json(value)
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(items.iter().map(|v| self.redact_json(v)).collect()),
+            other => other.clone(),
+        }
+    }
+
+    /// The payload of `data` as it may appear in telemetry. Protobuf payloads
+    /// are opaque, so only their size is reported.
+    pub fn redact_event_data(&self, data: &EventData) -> Value {
+        match data {
+            EventData::Json(value) => self.redact_json(value),
+            EventData::Protobuf(bytes) => serde_json::json!({ "protobuf_bytes": bytes.len() }),
+        }
+    }
+
+    /// The redacted payload of `event`
+    pub fn redact_event(&self, event: &Event) -> Value {
+        self.redact_event_data(&event.data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_named_and_category_fields_are_masked_at_any_depth() {
+        let redactor = PayloadRedactor::new(["SSN"]).with_categories(&[DataCategory::FinancialData]);
+        let payload = json!({
+            "ssn": "123-45-6789",
+            "amount": 40,
+            "customer": { "ssn": "987-65-4321", "tier": "gold" },
+            "cards": [{ "credit_card_number": "4111111111111111" }],
+        });
+
+        let redacted = redactor.redact_json(&payload);
+
+        assert_eq!(redacted["ssn"], REDACTED_VALUE);
+        assert_eq!(redacted["customer"]["ssn"], REDACTED_VALUE);
+        assert_eq!(redacted["cards"][0]["credit_card_number"], REDACTED_VALUE);
+        assert_eq!(redacted["amount"], 40);
+        assert_eq!(redacted["customer"]["tier"], "gold");
+        assert!(PayloadRedactor::default().is_empty());
+    }
+}
@@ -4,6 +4,10 @@
 
 use crate::error::Result;
 use crate::observability::correlation::{CorrelationId, generate_correlation_id};
+use crate::observability::metrics::StatsdConfig;
+use crate::observability::redaction::PayloadRedactor;
+use crate::security::DataCategory;
+use crate::Event;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -24,6 +28,33 @@ pub struct ObservabilityConfig {
     pub sample_rate: f64,
     pub max_events_per_span: u32,
     pub export_timeout_millis: u64,
+    /// Event types that get their own label on per-event-type counters;
+    /// anything else is reported as `other`
+    #[serde(default)]
+    pub event_type_allowlist: Option<Vec<String>>,
+    /// Aggregate types that get their own label on per-aggregate-type
+    /// counters; anything else is reported as `other`
+    #[serde(default)]
+    pub aggregate_type_allowlist: Option<Vec<String>>,
+    /// For a label without an allowlist, the number of distinct types that
+    /// are reported before further types fall into `other`
+    #[serde(default = "default_max_type_labels")]
+    pub max_type_labels: usize,
+    /// Payload fields whose values are masked before an event is logged or
+    /// traced, matched by exact name ignoring case
+    #[serde(default)]
+    pub redacted_fields: Vec<String>,
+    /// Data categories whose indicator fields are masked the same way, e.g.
+    /// `SensitivePersonalData` masks any field containing `ssn` or `passport`
+    #[serde(default)]
+    pub redacted_categories: Vec<DataCategory>,
+    /// Also push metrics to a StatsD endpoint; requires the `statsd` feature
+    #[serde(default)]
+    pub statsd: Option<StatsdConfig>,
+}
+
+fn default_max_type_labels() -> usize {
+    100
 }
 
 impl Default for ObservabilityConfig {
@@ -40,6 +71,12 @@ impl Default for ObservabilityConfig {
             sample_rate: 1.0, // Sample all traces in development
             max_events_per_span: 128,
             export_timeout_millis: 30000,
+            event_type_allowlist: None,
+            aggregate_type_allowlist: None,
+            max_type_labels: default_max_type_labels(),
+            redacted_fields: Vec::new(),
+            redacted_categories: Vec::new(),
+            statsd: None,
         }
     }
 }
@@ -48,6 +85,7 @@ impl Default for ObservabilityConfig {
 #[derive(Debug)]
 pub struct TelemetryProvider {
     config: ObservabilityConfig,
+    redactor: PayloadRedactor,
     active_traces: Arc<RwLock<HashMap<CorrelationId, TraceContext>>>,
 }
 
@@ -56,10 +94,16 @@ impl TelemetryProvider {
     pub async fn new(config: &ObservabilityConfig) -> Result<Self> {
         Ok(Self {
             config: config.clone(),
+            redactor: PayloadRedactor::from_config(config),
             active_traces: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
+    /// Redactor applied to event payloads attached to traces
+    pub fn redactor(&self) -> &PayloadRedactor {
+        &self.redactor
+    }
+
     /// Initialize the telemetry provider
     pub async fn initialize(&self) -> Result<()> {
         tracing::info!(
@@ -161,6 +205,14 @@ impl TraceContext {
         );
     }
 
+    /// Attach `event`'s identity and payload to this trace, masking the
+    /// fields `redactor` considers sensitive
+    pub fn record_event_payload(&mut self, event: &Event, redactor: &PayloadRedactor) {
+        self.add_attribute("event.id", &event.id.to_string());
+        self.add_attribute("event.type", &event.event_type);
+        self.add_attribute("event.payload", &redactor.redact_event(event).to_string());
+    }
+
     /// Record an error in this trace
     pub fn record_error(&self, error: &dyn std::error::Error) {
         tracing::error!(
@@ -203,6 +255,11 @@ impl TracingService {
     pub async fn end_trace(&self, trace: TraceContext) {
         self.provider.end_trace(&trace).await;
     }
+
+    /// Attach `event` to `trace` with its payload redacted per the provider's config
+    pub fn record_event(&self, trace: &mut TraceContext, event: &Event) {
+        trace.record_event_payload(event, self.provider.redactor());
+    }
 }
 
 /// Represents an event trace with metadata
@@ -2,8 +2,10 @@
 // This will be replaced with a proper async implementation later
 
 // use crate::error::EventualiError; // Available for future error handling
+use crate::Event;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchConfig {
@@ -12,6 +14,14 @@ pub struct BatchConfig {
     pub timeout_ms: u64,
     pub worker_pool_size: usize,
     pub parallel_processing: bool,
+    /// Flush once buffered items take more than this many MiB, whatever
+    /// their count or age, so bursts of large payloads cannot exhaust memory
+    #[serde(default = "default_max_buffer_memory_mb")]
+    pub max_buffer_memory_mb: usize,
+}
+
+fn default_max_buffer_memory_mb() -> usize {
+    64
 }
 
 impl Default for BatchConfig {
@@ -22,6 +32,7 @@ impl Default for BatchConfig {
             timeout_ms: 100,
             worker_pool_size: 4,
             parallel_processing: true,
+            max_buffer_memory_mb: 64,
         }
     }
 }
@@ -34,6 +45,7 @@ impl BatchConfig {
             timeout_ms: 50,
             worker_pool_size: 8,
             parallel_processing: true,
+            max_buffer_memory_mb: 128,
         }
     }
 
@@ -44,6 +56,7 @@ impl BatchConfig {
             timeout_ms: 200,
             worker_pool_size: 2,
             parallel_processing: false,
+            max_buffer_memory_mb: 32,
         }
     }
 
@@ -54,6 +67,7 @@ impl BatchConfig {
             timeout_ms: 10,
             worker_pool_size: 4,
             parallel_processing: true,
+            max_buffer_memory_mb: 16,
         }
     }
 }
@@ -74,6 +88,12 @@ pub struct BatchStats {
     pub peak_throughput_per_sec: f64,
     pub avg_processing_time_ms: f64,
     pub total_processing_time_ms: u64,
+    /// Bytes held by the items currently buffered
+    #[serde(default)]
+    pub current_buffer_bytes: usize,
+    /// Flushes triggered by `max_buffer_memory_mb` rather than count or age
+    #[serde(default)]
+    pub memory_flushes: u64,
 }
 
 impl Default for BatchStats {
@@ -93,25 +113,175 @@ impl Default for BatchStats {
             peak_throughput_per_sec: 0.0,
             avg_processing_time_ms: 0.0,
             total_processing_time_ms: 0,
+            current_buffer_bytes: 0,
+            memory_flushes: 0,
         }
     }
 }
 
+/// Memory an item holds while it waits in a batch buffer
+pub trait BufferedSize {
+    fn buffered_bytes(&self) -> usize;
+}
+
+impl BufferedSize for Event {
+    fn buffered_bytes(&self) -> usize {
+        let payload = match &self.data {
+            crate::EventData::Json(value) => {
+                let mut counter = ByteCounter(0);
+                serde_json::to_writer(&mut counter, value).map(|_| counter.0).unwrap_or(0)
+            }
+            crate::EventData::Protobuf(bytes) => bytes.len(),
+        };
+        let headers: usize = self.metadata.headers.iter().map(|(k, v)| k.len() + v.len()).sum();
+
+        std::mem::size_of::<Event>()
+            + self.aggregate_id.len()
+            + self.aggregate_type.len()
+            + self.event_type.len()
+            + self.metadata.user_id.as_ref().map_or(0, String::len)
+            + headers
+            + payload
+    }
+}
+
+/// Counts serialized bytes without keeping them
+struct ByteCounter(usize);
+
+impl std::io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// What made a buffer flush
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlushTrigger {
+    /// `max_batch_size` items were buffered
+    Count,
+    /// Buffered items exceeded `max_buffer_memory_mb`
+    Memory,
+    /// The oldest item waited `timeout_ms`
+    Timeout,
+    /// `flush` was called
+    Manual,
+}
+
+/// Items drained from the buffer in arrival order, ready to write
+#[derive(Debug)]
+pub struct FlushedBatch<T> {
+    pub items: Vec<T>,
+    pub bytes: usize,
+    pub trigger: FlushTrigger,
+}
+
+struct BatchBuffer<T> {
+    items: Vec<T>,
+    bytes: usize,
+    oldest: Option<Instant>,
+}
+
+/// Buffers items and hands them back as batches once `max_batch_size` items,
+/// `max_buffer_memory_mb` of memory or `timeout_ms` of waiting is reached,
+/// whichever comes first.
 pub struct BatchProcessor<T> {
-    _config: BatchConfig,
-    _phantom: std::marker::PhantomData<T>,
+    config: BatchConfig,
+    buffer: Mutex<BatchBuffer<T>>,
+    stats: Mutex<BatchStats>,
 }
 
-impl<T> BatchProcessor<T> {
+impl<T: BufferedSize> BatchProcessor<T> {
     pub fn new(config: BatchConfig) -> Self {
         Self {
-            _config: config,
-            _phantom: std::marker::PhantomData,
+            config,
+            buffer: Mutex::new(BatchBuffer { items: Vec::new(), bytes: 0, oldest: None }),
+            stats: Mutex::new(BatchStats::default()),
         }
     }
 
+    /// Buffer `item`, returning the whole buffer as a batch when this item
+    /// fills it by count or memory
+    pub fn add_item(&self, item: T) -> Option<FlushedBatch<T>> {
+        let mut buffer = self.buffer.lock().unwrap();
+        buffer.bytes += item.buffered_bytes();
+        buffer.items.push(item);
+        buffer.oldest.get_or_insert_with(Instant::now);
+
+        let trigger = if buffer.bytes > self.max_buffer_bytes() {
+            Some(FlushTrigger::Memory)
+        } else if buffer.items.len() >= self.config.max_batch_size {
+            Some(FlushTrigger::Count)
+        } else {
+            None
+        };
+
+        match trigger {
+            Some(trigger) => Some(self.drain(&mut buffer, trigger)),
+            None => {
+                self.record_depth(&buffer);
+                None
+            }
+        }
+    }
+
+    /// Return the buffer as a batch if its oldest item has waited `timeout_ms`
+    pub fn poll_timeout(&self) -> Option<FlushedBatch<T>> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let waited = buffer.oldest?.elapsed();
+        (waited >= Duration::from_millis(self.config.timeout_ms))
+            .then(|| self.drain(&mut buffer, FlushTrigger::Timeout))
+    }
+
+    /// Return whatever is buffered as a batch
+    pub fn flush(&self) -> Option<FlushedBatch<T>> {
+        let mut buffer = self.buffer.lock().unwrap();
+        (!buffer.items.is_empty()).then(|| self.drain(&mut buffer, FlushTrigger::Manual))
+    }
+
+    /// Bytes held by the items currently buffered
+    pub fn buffered_bytes(&self) -> usize {
+        self.buffer.lock().unwrap().bytes
+    }
+
     pub fn get_stats(&self) -> BatchStats {
-        BatchStats::default()
+        self.stats.lock().unwrap().clone()
+    }
+
+    fn max_buffer_bytes(&self) -> usize {
+        self.config.max_buffer_memory_mb.saturating_mul(1024 * 1024)
+    }
+
+    fn drain(&self, buffer: &mut BatchBuffer<T>, trigger: FlushTrigger) -> FlushedBatch<T> {
+        let batch = FlushedBatch {
+            items: std::mem::take(&mut buffer.items),
+            bytes: std::mem::take(&mut buffer.bytes),
+            trigger,
+        };
+        buffer.oldest = None;
+
+        let mut stats = self.stats.lock().unwrap();
+        stats.total_batches_processed += 1;
+        stats.total_items_processed += batch.items.len() as u64;
+        stats.avg_batch_size = stats.total_items_processed as f64 / stats.total_batches_processed as f64;
+        stats.average_batch_size = stats.avg_batch_size;
+        if trigger == FlushTrigger::Memory {
+            stats.memory_flushes += 1;
+        }
+        stats.current_queue_depth = 0;
+        stats.current_buffer_bytes = 0;
+        batch
+    }
+
+    fn record_depth(&self, buffer: &BatchBuffer<T>) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.current_queue_depth = buffer.items.len();
+        stats.max_queue_depth = stats.max_queue_depth.max(buffer.items.len());
+        stats.current_buffer_bytes = buffer.bytes;
     }
 }
 
@@ -123,4 +293,59 @@ impl EventBatchProcessor {
     pub fn new(pool: Arc<crate::performance::ConnectionPool>) -> Self {
         Self { _pool: pool }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventData;
+
+    fn large_event(version: i64, payload_bytes: usize) -> Event {
+        Event::new(
+            "upload-1".to_string(),
+            "Upload".to_string(),
+            "ChunkReceived".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "chunk": "x".repeat(payload_bytes) })),
+        )
+    }
+
+    #[test]
+    fn test_large_events_flush_on_memory_before_the_count_threshold() {
+        let config = BatchConfig { max_batch_size: 1000, max_buffer_memory_mb: 1, ..BatchConfig::default() };
+        let processor = BatchProcessor::new(config);
+
+        // Three 300 KiB events fit within 1 MiB; the fourth pushes the buffer over
+        for version in 1..=3 {
+            assert!(processor.add_item(large_event(version, 300 * 1024)).is_none());
+        }
+        assert!(processor.buffered_bytes() > 900 * 1024);
+        assert_eq!(processor.get_stats().current_queue_depth, 3);
+
+        let batch = processor.add_item(large_event(4, 300 * 1024)).expect("memory limit should flush");
+        assert_eq!(batch.trigger, FlushTrigger::Memory);
+        assert_eq!(batch.items.len(), 4);
+        assert!(batch.bytes > 1024 * 1024);
+        assert_eq!(processor.buffered_bytes(), 0);
+
+        let stats = processor.get_stats();
+        assert_eq!(stats.memory_flushes, 1);
+        assert_eq!(stats.total_items_processed, 4);
+        assert_eq!(stats.current_buffer_bytes, 0);
+    }
+
+    #[test]
+    fn test_small_events_still_flush_on_count() {
+        let config = BatchConfig { max_batch_size: 3, ..BatchConfig::default() };
+        let processor = BatchProcessor::new(config);
+
+        assert!(processor.add_item(large_event(1, 10)).is_none());
+        assert!(processor.add_item(large_event(2, 10)).is_none());
+        let batch = processor.add_item(large_event(3, 10)).unwrap();
+
+        assert_eq!(batch.trigger, FlushTrigger::Count);
+        assert_eq!(batch.items.len(), 3);
+        assert!(processor.flush().is_none());
+    }
+}
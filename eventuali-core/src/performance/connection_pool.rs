@@ -2,10 +2,30 @@
 //!
 //! Provides optimized connection pool management with automatic sizing,
 //! health monitoring, and load balancing capabilities.
+//!
+//! # Forking
+//!
+//! SQLite connections must not cross a `fork()`, and a pool created before
+//! one is inherited by the child with the parent's slots and counters, held
+//! by tasks that do not exist there. Pre-fork servers such as Gunicorn and
+//! uWSGI do exactly this when the application is loaded in the master. The
+//! pool remembers the process that created it, and the first
+//! `get_connection` in a different process applies the configured
+//! `ForkPolicy`: by default the child starts over with a fresh pool, and
+//! guards it inherited no longer count against it. Only a pool that was idle
+//! when the process forked can be recovered, since a lock held by a parent
+//! thread stays held in the child, so create pools in each worker (for
+//! example from Gunicorn's `post_fork` hook) rather than in the master.
+//! This applies to this pool only: the sqlx pools event store backends open
+//! do no fork detection, so stores must be created in each worker too.
 
+use std::collections::VecDeque;
+use std::marker::PhantomData;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
-use tokio::sync::{Mutex, Semaphore};
+use tokio::sync::{oneshot, Mutex};
+use tracing::warn;
 use crate::error::EventualiError;
 
 /// Connection pool statistics for monitoring and optimization
@@ -19,6 +39,22 @@ pub struct PoolStats {
     pub failed_requests: u64,
     pub avg_wait_time_ms: f64,
     pub max_wait_time_ms: u64,
+    /// Requests currently queued waiting for a connection slot
+    pub waiting_requests: usize,
+}
+
+impl std::fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "total={}, active={}, idle={}, waiting={}, failed_requests={}",
+            self.total_connections,
+            self.active_connections,
+            self.idle_connections,
+            self.waiting_requests,
+            self.failed_requests
+        )
+    }
 }
 
 impl Default for PoolStats {
@@ -32,10 +68,36 @@ impl Default for PoolStats {
             failed_requests: 0,
             avg_wait_time_ms: 0.0,
             max_wait_time_ms: 0,
+            waiting_requests: 0,
         }
     }
 }
 
+/// Order in which requests queued for a connection slot are served
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolFairness {
+    /// Serve waiters in arrival order, so no request waits behind more than
+    /// the queue that was ahead of it. Bounds tail latency.
+    #[default]
+    Fifo,
+    /// Serve the most recent waiter first. Keeps recently active callers and
+    /// their caches warm for better throughput, but early waiters can starve
+    /// under sustained contention.
+    Lifo,
+}
+
+/// What a pool does when first used in a process forked after it was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ForkPolicy {
+    /// Discard the slots, counters and waiters inherited from the parent and
+    /// start afresh in the child
+    #[default]
+    Reinitialize,
+    /// Refuse to hand out connections in the child, so a pool shared across
+    /// a fork by mistake fails loudly
+    Reject,
+}
+
 /// Configuration for connection pool optimization
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -47,6 +109,10 @@ pub struct PoolConfig {
     pub auto_scaling_enabled: bool,
     pub scale_up_threshold: f64,
     pub scale_down_threshold: f64,
+    /// Order in which waiting requests are handed slots
+    pub fairness: PoolFairness,
+    /// Handling of a pool inherited across `fork()`
+    pub fork_policy: ForkPolicy,
 }
 
 impl Default for PoolConfig {
@@ -60,6 +126,8 @@ impl Default for PoolConfig {
             auto_scaling_enabled: true,
             scale_up_threshold: 0.8, // Scale up when 80% connections are in use
             scale_down_threshold: 0.3, // Scale down when less than 30% are in use
+            fairness: PoolFairness::Fifo,
+            fork_policy: ForkPolicy::Reinitialize,
         }
     }
 }
@@ -76,18 +144,190 @@ impl PoolConfig {
             auto_scaling_enabled: true,
             scale_up_threshold: 0.7, // Scale up when 70% connections are in use
             scale_down_threshold: 0.2, // Scale down when less than 20% are in use
+            fairness: PoolFairness::Fifo,
+            fork_policy: ForkPolicy::Reinitialize,
         }
     }
 }
 
+/// Connection slots and the requests queued for them.
+///
+/// A released slot goes straight to the next waiter chosen by the fairness
+/// policy, so a request arriving at the moment of release cannot jump the
+/// queue.
+struct Slots {
+    capacity: usize,
+    fairness: PoolFairness,
+    queue: std::sync::Mutex<SlotQueue>,
+}
+
+struct SlotQueue {
+    available: usize,
+    /// Bumped by `reset`; slots taken in an earlier generation are not returned
+    generation: u64,
+    next_waiter_id: u64,
+    waiters: VecDeque<(u64, oneshot::Sender<()>)>,
+}
+
+impl Slots {
+    fn new(capacity: usize, fairness: PoolFairness) -> Self {
+        Self {
+            capacity,
+            fairness,
+            queue: std::sync::Mutex::new(SlotQueue {
+                available: capacity,
+                generation: 0,
+                next_waiter_id: 0,
+                waiters: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Free every slot and drop every waiter, disowning slots already taken
+    fn reset(&self) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.generation += 1;
+        queue.available = self.capacity;
+        queue.waiters.clear();
+    }
+
+    fn generation(&self) -> u64 {
+        self.queue.lock().unwrap().generation
+    }
+
+    /// Wait up to `timeout` for a slot; `None` when none was handed over in time
+    async fn acquire(self: &Arc<Self>, timeout: Duration) -> Option<SlotPermit> {
+        let (id, generation, rx) = {
+            let mut queue = self.queue.lock().unwrap();
+            let generation = queue.generation;
+            if queue.available > 0 && queue.waiters.is_empty() {
+                queue.available -= 1;
+                return Some(SlotPermit { slots: self.clone(), generation });
+            }
+            let id = queue.next_waiter_id;
+            queue.next_waiter_id += 1;
+            let (tx, rx) = oneshot::channel();
+            queue.waiters.push_back((id, tx));
+            (id, generation, rx)
+        };
+
+        let mut waiter = Waiter { slots: self, id, generation, rx: Some(rx) };
+        let handed_over = match waiter.rx.as_mut() {
+            Some(rx) => matches!(tokio::time::timeout(timeout, rx).await, Ok(Ok(()))),
+            None => false,
+        };
+        if !handed_over {
+            return None;
+        }
+        waiter.rx = None;
+        Some(SlotPermit { slots: self.clone(), generation })
+    }
+
+    /// Hand a slot taken in `generation` to the next live waiter, or return
+    /// it to the free count
+    fn release(&self, generation: u64) {
+        let mut queue = self.queue.lock().unwrap();
+        if queue.generation != generation {
+            return;
+        }
+        loop {
+            let next = match self.fairness {
+                PoolFairness::Fifo => queue.waiters.pop_front(),
+                PoolFairness::Lifo => queue.waiters.pop_back(),
+            };
+            match next {
+                // A failed send means the waiter gave up; try the next one
+                Some((_, tx)) => {
+                    if tx.send(()).is_ok() {
+                        return;
+                    }
+                }
+                None => {
+                    queue.available += 1;
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// A queued request; leaving the queue without its slot passes the slot on
+struct Waiter<'a> {
+    slots: &'a Arc<Slots>,
+    id: u64,
+    generation: u64,
+    rx: Option<oneshot::Receiver<()>>,
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        let Some(mut rx) = self.rx.take() else {
+            return;
+        };
+        let mut queue = self.slots.queue.lock().unwrap();
+        if let Some(pos) = queue.waiters.iter().position(|(id, _)| *id == self.id) {
+            queue.waiters.remove(pos);
+            return;
+        }
+        drop(queue);
+        // Hand-over happens under the lock, so if we are no longer queued the
+        // slot is already in the channel and must not be lost
+        if rx.try_recv().is_ok() {
+            self.slots.release(self.generation);
+        }
+    }
+}
+
+/// Counts a request as waiting for a slot until dropped, including when the
+/// request is cancelled mid-wait
+struct Waiting<'a> {
+    count: &'a AtomicUsize,
+}
+
+impl<'a> Waiting<'a> {
+    fn new(count: &'a AtomicUsize) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self { count }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// An acquired slot, released when dropped
+struct SlotPermit {
+    slots: Arc<Slots>,
+    generation: u64,
+}
+
+impl SlotPermit {
+    /// Whether the slot still belongs to the pool, rather than to one it was
+    /// inherited from across a fork
+    fn is_current(&self) -> bool {
+        self.slots.generation() == self.generation
+    }
+}
+
+impl Drop for SlotPermit {
+    fn drop(&mut self) {
+        self.slots.release(self.generation);
+    }
+}
+
 /// High-performance connection pool with automatic optimization
 pub struct ConnectionPool {
     config: PoolConfig,
     connection_count: Arc<Mutex<usize>>,
     active_count: Arc<Mutex<usize>>,
-    semaphore: Arc<Semaphore>,
+    waiting_count: Arc<AtomicUsize>,
+    slots: Arc<Slots>,
     stats: Arc<Mutex<PoolStats>>,
     database_path: String,
+    /// Process the slots and counters belong to
+    owner_pid: Arc<AtomicU32>,
 }
 
 impl ConnectionPool {
@@ -95,27 +335,70 @@ impl ConnectionPool {
     pub async fn new(database_path: String, config: PoolConfig) -> Result<Self, EventualiError> {
         let connection_count = Arc::new(Mutex::new(config.min_connections));
         let active_count = Arc::new(Mutex::new(0));
-        let semaphore = Arc::new(Semaphore::new(config.max_connections));
-        let stats = Arc::new(Mutex::new(PoolStats {
-            total_connections: config.min_connections,
-            idle_connections: config.min_connections,
-            ..Default::default()
-        }));
+        let waiting_count = Arc::new(AtomicUsize::new(0));
+        let slots = Arc::new(Slots::new(config.max_connections, config.fairness));
+        let stats = Arc::new(Mutex::new(Self::initial_stats(&config)));
 
         let pool = Self {
             config,
             connection_count,
             active_count,
-            semaphore,
+            waiting_count,
+            slots,
             stats,
             database_path,
+            owner_pid: Arc::new(AtomicU32::new(std::process::id())),
         };
 
         Ok(pool)
     }
 
+    fn initial_stats(config: &PoolConfig) -> PoolStats {
+        PoolStats {
+            total_connections: config.min_connections,
+            idle_connections: config.min_connections,
+            ..Default::default()
+        }
+    }
+
+    /// ID of the process the pool's slots belong to: the one that created
+    /// it, or the forked child that reinitialized it
+    pub fn owner_pid(&self) -> u32 {
+        self.owner_pid.load(Ordering::SeqCst)
+    }
+
+    /// Apply the fork policy if this process is not the pool's owner.
+    ///
+    /// The first caller to see the new process ID resets the pool; callers
+    /// racing it proceed against the old slots, which the reset disowns.
+    async fn ensure_owned_by_this_process(&self) -> Result<(), EventualiError> {
+        let pid = std::process::id();
+        let owner = self.owner_pid.load(Ordering::SeqCst);
+        if owner == pid {
+            return Ok(());
+        }
+        if self.config.fork_policy == ForkPolicy::Reject {
+            return Err(EventualiError::Configuration(format!(
+                "Connection pool was created in process {owner} and cannot be used after fork() in process {pid}; \
+                 create the pool in each worker process"
+            )));
+        }
+        if self.owner_pid.compare_exchange(owner, pid, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return Ok(());
+        }
+
+        warn!(parent_pid = owner, pid, "Connection pool used after fork(); reinitializing it for this process");
+        self.slots.reset();
+        *self.active_count.lock().await = 0;
+        *self.connection_count.lock().await = self.config.min_connections;
+        self.waiting_count.store(0, Ordering::SeqCst);
+        *self.stats.lock().await = Self::initial_stats(&self.config);
+        Ok(())
+    }
+
     /// Get a connection from the pool with performance tracking
     pub async fn get_connection(&self) -> Result<PoolGuard<'_>, EventualiError> {
+        self.ensure_owned_by_this_process().await?;
         let start_time = Instant::now();
         
         // Update stats
@@ -124,19 +407,27 @@ impl ConnectionPool {
             stats.total_requests += 1;
         }
 
-        // Acquire semaphore permit
-        let permit = match tokio::time::timeout(
-            Duration::from_millis(self.config.connection_timeout_ms),
-            self.semaphore.acquire()
-        ).await {
-            Ok(Ok(permit)) => permit,
-            Ok(Err(_)) => {
-                self.record_failed_request().await;
-                return Err(EventualiError::Configuration("Failed to acquire connection permit".to_string()));
+        // Acquire a connection slot
+        let waiting = Waiting::new(&self.waiting_count);
+        let acquired = self.slots
+            .acquire(Duration::from_millis(self.config.connection_timeout_ms))
+            .await;
+
+        let permit = match acquired {
+            Some(permit) => {
+                drop(waiting);
+                permit
             }
-            Err(_) => {
+            None => {
                 self.record_failed_request().await;
-                return Err(EventualiError::Configuration("Connection timeout".to_string()));
+                // Snapshot while this request still counts as queued, so the
+                // reported depth reflects what it was waiting behind
+                let stats_snapshot = self.get_stats().await;
+                drop(waiting);
+                return Err(EventualiError::PoolTimeout {
+                    timeout_ms: self.config.connection_timeout_ms,
+                    stats_snapshot: Box::new(stats_snapshot),
+                });
             }
         };
 
@@ -153,6 +444,7 @@ impl ConnectionPool {
             database_path: self.database_path.clone(),
             pool: self.clone(),
             permit: Some(permit),
+            _pool: PhantomData,
         })
     }
 
@@ -165,6 +457,7 @@ impl ConnectionPool {
         stats.active_connections = active_count;
         stats.total_connections = total_count;
         stats.idle_connections = total_count.saturating_sub(active_count);
+        stats.waiting_requests = self.waiting_count.load(Ordering::SeqCst);
         
         stats.clone()
     }
@@ -207,9 +500,11 @@ impl Clone for ConnectionPool {
             config: self.config.clone(),
             connection_count: self.connection_count.clone(),
             active_count: self.active_count.clone(),
-            semaphore: self.semaphore.clone(),
+            waiting_count: self.waiting_count.clone(),
+            slots: self.slots.clone(),
             stats: self.stats.clone(),
             database_path: self.database_path.clone(),
+            owner_pid: self.owner_pid.clone(),
         }
     }
 }
@@ -218,8 +513,9 @@ impl Clone for ConnectionPool {
 pub struct PoolGuard<'a> {
     database_path: String,
     pool: ConnectionPool,
-    #[allow(dead_code)] // Semaphore permit for connection limiting (held but not directly accessed in current implementation)
-    permit: Option<tokio::sync::SemaphorePermit<'a>>,
+    /// Held for its Drop, which hands the slot to the next waiter
+    permit: Option<SlotPermit>,
+    _pool: PhantomData<&'a ConnectionPool>,
 }
 
 impl<'a> PoolGuard<'a> {
@@ -251,6 +547,10 @@ impl<'a> PoolGuard<'a> {
 
 impl<'a> Drop for PoolGuard<'a> {
     fn drop(&mut self) {
+        // A guard inherited across a fork was never counted by the child's pool
+        if !self.permit.as_ref().is_some_and(SlotPermit::is_current) {
+            return;
+        }
         let pool = self.pool.clone();
         tokio::spawn(async move {
             pool.release_connection().await;
@@ -298,4 +598,147 @@ mod tests {
         assert_eq!(stats.successful_requests, 1);
         assert_eq!(stats.active_connections, 1);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_timeout_error_carries_pool_diagnostics() {
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 1,
+            connection_timeout_ms: 50,
+            ..PoolConfig::default()
+        };
+        let pool = ConnectionPool::new(":memory:".to_string(), config).await.unwrap();
+
+        let _held = pool.get_connection().await.unwrap();
+
+        // Two more callers queue behind the single held slot and both time out
+        let (first, second) = tokio::join!(pool.get_connection(), pool.get_connection());
+        for result in [first, second] {
+            match result {
+                Err(EventualiError::PoolTimeout { timeout_ms, stats_snapshot }) => {
+                    assert_eq!(timeout_ms, 50);
+                    assert_eq!(stats_snapshot.total_connections, 1);
+                    assert_eq!(stats_snapshot.active_connections, 1);
+                    assert_eq!(stats_snapshot.idle_connections, 0);
+                    assert!((1..=2).contains(&stats_snapshot.waiting_requests));
+                    assert!(stats_snapshot.failed_requests >= 1);
+                }
+                Err(other) => panic!("expected PoolTimeout, got {other:?}"),
+                Ok(_) => panic!("expected PoolTimeout, got a connection"),
+            }
+        }
+
+        let stats = pool.get_stats().await;
+        assert_eq!(stats.waiting_requests, 0);
+        assert_eq!(stats.failed_requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiters_stop_counting_as_waiting() {
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 1,
+            connection_timeout_ms: 60_000,
+            ..PoolConfig::default()
+        };
+        let pool = ConnectionPool::new(":memory:".to_string(), config).await.unwrap();
+
+        let held = pool.get_connection().await.unwrap();
+        let waited = tokio::time::timeout(Duration::from_millis(20), pool.get_connection()).await;
+        assert!(waited.is_err());
+        assert_eq!(pool.get_stats().await.waiting_requests, 0);
+
+        drop(held);
+        let _next = pool.get_connection().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pool_reinitializes_after_a_pid_change() {
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 2,
+            connection_timeout_ms: 50,
+            ..PoolConfig::default()
+        };
+        let pool = ConnectionPool::new(":memory:".to_string(), config).await.unwrap();
+        assert_eq!(pool.owner_pid(), std::process::id());
+
+        // Slots held in the parent by tasks that do not survive the fork
+        let parent_guards = (pool.get_connection().await.unwrap(), pool.get_connection().await.unwrap());
+        assert!(pool.get_connection().await.is_err());
+
+        // Running in a child of the process that created the pool
+        pool.owner_pid.store(std::process::id().wrapping_add(1), Ordering::SeqCst);
+
+        let guard = pool.get_connection().await.unwrap();
+        assert_eq!(pool.owner_pid(), std::process::id());
+        guard.create_connection().unwrap().execute_batch("CREATE TABLE t (id INTEGER)").unwrap();
+        let stats = pool.get_stats().await;
+        assert_eq!((stats.total_requests, stats.failed_requests), (1, 0));
+        assert_eq!(stats.active_connections, 1);
+
+        // The forking thread continues in the child, so its guards can still
+        // be dropped there; they must not hand back slots or counts
+        drop(parent_guards);
+        tokio::task::yield_now().await;
+        assert_eq!(pool.get_stats().await.active_connections, 1);
+        let _second = pool.get_connection().await.unwrap();
+        assert!(pool.get_connection().await.is_err(), "inherited guards freed extra slots");
+    }
+
+    #[tokio::test]
+    async fn test_reject_fork_policy_refuses_an_inherited_pool() {
+        let config = PoolConfig { fork_policy: ForkPolicy::Reject, ..PoolConfig::default() };
+        let pool = ConnectionPool::new(":memory:".to_string(), config).await.unwrap();
+        let parent = std::process::id().wrapping_add(1);
+        pool.owner_pid.store(parent, Ordering::SeqCst);
+
+        match pool.get_connection().await {
+            Err(EventualiError::Configuration(message)) => assert!(message.contains("fork()"), "{message}"),
+            Err(other) => panic!("expected Configuration, got {other:?}"),
+            Ok(_) => panic!("an inherited pool handed out a connection"),
+        }
+        assert_eq!(pool.owner_pid(), parent);
+    }
+
+    /// Queue four requests one after another behind the only slot, then
+    /// release it and return the order the requests were served in
+    async fn handout_order(fairness: PoolFairness) -> Vec<usize> {
+        let config = PoolConfig {
+            min_connections: 1,
+            max_connections: 1,
+            fairness,
+            ..PoolConfig::default()
+        };
+        let pool = Arc::new(ConnectionPool::new(":memory:".to_string(), config).await.unwrap());
+        let held = pool.get_connection().await.unwrap();
+        let served = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let mut waiters = Vec::new();
+        for i in 0..4 {
+            let (pool_ref, served) = (pool.clone(), served.clone());
+            waiters.push(tokio::spawn(async move {
+                let _guard = pool_ref.get_connection().await.unwrap();
+                served.lock().unwrap().push(i);
+            }));
+            while pool.slots.queue.lock().unwrap().waiters.len() < i + 1 {
+                tokio::task::yield_now().await;
+            }
+        }
+        drop(held);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+
+        let order = served.lock().unwrap().clone();
+        order
+    }
+
+    #[tokio::test]
+    async fn test_fifo_serves_waiters_in_arrival_order_and_lifo_newest_first() {
+        // FIFO bounds every wait to one turn of those ahead; LIFO serves the
+        // newest request first and leaves the oldest for last
+        assert_eq!(handout_order(PoolFairness::Fifo).await, vec![0, 1, 2, 3]);
+        assert_eq!(handout_order(PoolFairness::Lifo).await, vec![3, 2, 1, 0]);
+    }
+}
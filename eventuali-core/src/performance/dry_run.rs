@@ -0,0 +1,257 @@
+//! Dry-run write benchmarking
+//!
+//! Runs the full save path (serialization, validation, compression, encryption
+//! and the store write) against a real store, rolling every write back, so
+//! capacity estimates measure the configured store without polluting it.
+
+use std::io::Write;
+use std::time::{Duration, Instant};
+use flate2::{write::GzEncoder, Compression};
+use crate::error::EventualiError;
+use crate::security::{EventEncryption, KeyManager};
+use crate::store::EventStore;
+use crate::{Event, EventData};
+use super::compression::CompressionAlgorithm;
+
+/// Stages timed by the dry-run benchmark, in pipeline order
+pub const DRY_RUN_STAGES: [&str; 5] = ["serialization", "validation", "compression", "encryption", "write"];
+
+/// Configuration for a dry-run write benchmark
+#[derive(Debug, Clone)]
+pub struct DryRunConfig {
+    pub event_count: usize,
+    pub batch_size: usize,
+    pub payload_size_bytes: usize,
+    pub compression: CompressionAlgorithm,
+    pub encryption_enabled: bool,
+}
+
+impl Default for DryRunConfig {
+    fn default() -> Self {
+        Self {
+            event_count: 10_000,
+            batch_size: 100,
+            payload_size_bytes: 256,
+            compression: CompressionAlgorithm::None,
+            encryption_enabled: false,
+        }
+    }
+}
+
+/// Time spent in one stage of the save path across the whole run
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    pub stage: String,
+    pub total_ms: f64,
+    pub per_event_us: f64,
+}
+
+/// Result of a dry-run write benchmark
+#[derive(Debug, Clone)]
+pub struct DryRunReport {
+    pub events_written: usize,
+    pub total_time_ms: f64,
+    pub events_per_second: f64,
+    /// Per-stage timings in pipeline order; they sum to at most `total_time_ms`
+    pub stages: Vec<StageTiming>,
+}
+
+impl DryRunReport {
+    pub fn stage(&self, name: &str) -> Option<&StageTiming> {
+        self.stages.iter().find(|s| s.stage == name)
+    }
+}
+
+/// Estimate write throughput against `store` without committing anything.
+///
+/// Each batch goes through `EventStore::dry_run_save_events`, so the store's
+/// own validation and inserts run and are then rolled back. Compression and
+/// encryption stages are still reported when disabled, with the (near zero)
+/// time spent deciding to skip them.
+pub async fn benchmark_dry_run_writes(
+    store: &(dyn EventStore + Send + Sync),
+    config: DryRunConfig,
+) -> Result<DryRunReport, EventualiError> {
+    if config.batch_size == 0 {
+        return Err(EventualiError::Configuration("Dry-run batch size must be positive".to_string()));
+    }
+    if matches!(config.compression, CompressionAlgorithm::LZ4 | CompressionAlgorithm::ZSTD) {
+        return Err(EventualiError::Configuration(format!(
+            "Compression algorithm {:?} is not available for dry runs",
+            config.compression
+        )));
+    }
+
+    let encryption = if config.encryption_enabled {
+        let key = KeyManager::generate_key("dry-run".to_string())?;
+        Some(EventEncryption::with_key(key.id, key.key_data)?)
+    } else {
+        None
+    };
+
+    let padding = "x".repeat(config.payload_size_bytes);
+    let mut stage_time = [Duration::ZERO; DRY_RUN_STAGES.len()];
+    let run_start = Instant::now();
+
+    let mut written = 0;
+    let mut batch_index = 0;
+    while written < config.event_count {
+        let batch_len = config.batch_size.min(config.event_count - written);
+        // Unique per run, so batches never collide with aggregates already stored
+        let aggregate_id = format!("dry-run-{}-{batch_index}", uuid::Uuid::new_v4());
+        let mut batch = Vec::with_capacity(batch_len);
+
+        for offset in 0..batch_len {
+            let version = offset as i64 + 1;
+            let payload = serde_json::json!({ "seq": written + offset, "padding": padding });
+
+            let started = Instant::now();
+            let mut bytes = serde_json::to_vec(&payload)?;
+            stage_time[0] += started.elapsed();
+
+            let started = Instant::now();
+            let mut event = Event::new(
+                aggregate_id.clone(),
+                "DryRun".to_string(),
+                "DryRunRecorded".to_string(),
+                1,
+                version,
+                EventData::Protobuf(Vec::new()),
+            );
+            validate_event(&event)?;
+            stage_time[1] += started.elapsed();
+
+            let started = Instant::now();
+            if matches!(config.compression, CompressionAlgorithm::Gzip) {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(&bytes)?;
+                bytes = encoder.finish()?;
+            }
+            stage_time[2] += started.elapsed();
+
+            let started = Instant::now();
+            if let Some(encryption) = &encryption {
+                let encrypted = encryption.encrypt_event_data(&EventData::Protobuf(bytes))?;
+                bytes = encrypted.to_base64().into_bytes();
+            }
+            stage_time[3] += started.elapsed();
+
+            event.data = EventData::Protobuf(bytes);
+            batch.push(event);
+        }
+
+        let started = Instant::now();
+        store.dry_run_save_events(batch).await?;
+        stage_time[4] += started.elapsed();
+
+        written += batch_len;
+        batch_index += 1;
+    }
+
+    let total = run_start.elapsed();
+    let per_event = |d: Duration| if written == 0 { 0.0 } else { d.as_secs_f64() * 1_000_000.0 / written as f64 };
+    let stages = DRY_RUN_STAGES
+        .iter()
+        .zip(stage_time)
+        .map(|(stage, elapsed)| StageTiming {
+            stage: stage.to_string(),
+            total_ms: elapsed.as_secs_f64() * 1000.0,
+            per_event_us: per_event(elapsed),
+        })
+        .collect();
+
+    Ok(DryRunReport {
+        events_written: written,
+        total_time_ms: total.as_secs_f64() * 1000.0,
+        events_per_second: if total.is_zero() { 0.0 } else { written as f64 / total.as_secs_f64() },
+        stages,
+    })
+}
+
+fn validate_event(event: &Event) -> Result<(), EventualiError> {
+    if event.aggregate_id.is_empty() || event.aggregate_type.is_empty() || event.event_type.is_empty() {
+        return Err(EventualiError::Validation(
+            "Events need an aggregate id, aggregate type and event type".to_string(),
+        ));
+    }
+    if event.aggregate_version < 1 {
+        return Err(EventualiError::Validation(format!(
+            "Aggregate version must be at least 1, got {}",
+            event.aggregate_version
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{create_event_store, memory::MemoryBackend, EventStoreConfig, EventStoreImpl};
+
+    #[tokio::test]
+    async fn test_dry_run_reports_every_stage_and_commits_nothing() {
+        let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        let config = DryRunConfig {
+            event_count: 250,
+            batch_size: 40,
+            payload_size_bytes: 128,
+            compression: CompressionAlgorithm::Gzip,
+            encryption_enabled: true,
+        };
+
+        let report = benchmark_dry_run_writes(store.as_ref(), config).await.unwrap();
+
+        assert_eq!(report.events_written, 250);
+        assert!(report.events_per_second > 0.0);
+
+        let names: Vec<&str> = report.stages.iter().map(|s| s.stage.as_str()).collect();
+        assert_eq!(names, DRY_RUN_STAGES);
+        for stage in ["serialization", "compression", "encryption", "write"] {
+            assert!(report.stage(stage).unwrap().total_ms > 0.0, "{stage} was not timed");
+        }
+
+        let stage_sum: f64 = report.stages.iter().map(|s| s.total_ms).sum();
+        assert!(stage_sum > 0.0);
+        assert!(stage_sum <= report.total_time_ms);
+
+        // Every write was rolled back
+        assert_eq!(store.stats().await.unwrap().total_events, 0);
+        assert!(store.load_events_after_position(0, 10).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_enforces_store_validation() {
+        let config = EventStoreConfig::sqlite(":memory:".to_string()).with_max_aggregate_version(5);
+        let store = create_event_store(config).await.unwrap();
+        let config = DryRunConfig {
+            event_count: 20,
+            batch_size: 10,
+            ..DryRunConfig::default()
+        };
+
+        let result = benchmark_dry_run_writes(store.as_ref(), config).await;
+        assert!(matches!(result, Err(EventualiError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_rejects_conflicts_like_a_save() {
+        let backend = MemoryBackend::new();
+        let event = Event::new(
+            "order-1".to_string(),
+            "Order".to_string(),
+            "Placed".to_string(),
+            1,
+            1,
+            EventData::Json(serde_json::json!({})),
+        );
+        let store = EventStoreImpl::new(backend);
+        store.save_events(vec![event.clone()]).await.unwrap();
+
+        let mut conflicting = event.clone();
+        conflicting.id = uuid::Uuid::new_v4();
+        assert!(matches!(
+            store.dry_run_save_events(vec![conflicting]).await,
+            Err(EventualiError::OptimisticConcurrency { .. })
+        ));
+    }
+}
@@ -10,10 +10,16 @@ pub mod batch_processing_stub;
 pub mod read_replicas;
 pub mod caching;
 pub mod compression;
+pub mod dry_run;
+pub mod roundtrip;
 
 pub use connection_pool::*;
 pub use wal_optimization::*;
-pub use batch_processing_stub::{BatchConfig, BatchStats, BatchProcessor, EventBatchProcessor};
+pub use batch_processing_stub::{
+    BatchConfig, BatchStats, BatchProcessor, BufferedSize, EventBatchProcessor, FlushTrigger, FlushedBatch,
+};
 pub use read_replicas::*;
 pub use caching::*;
-pub use compression::*;
\ No newline at end of file
+pub use compression::*;
+pub use dry_run::*;
+pub use roundtrip::*;
\ No newline at end of file
@@ -0,0 +1,168 @@
+//! End-to-end save and load benchmarking
+//!
+//! Unlike the pool and WAL benchmarks, this drives a real SQLite event store
+//! through the public `EventStore` API, so the numbers include serialization,
+//! the database round trip and decoding on the way back.
+
+use std::time::{Duration, Instant};
+use crate::error::EventualiError;
+use crate::store::{create_event_store, EventStoreConfig};
+use crate::tenancy::{AggregatedMetric, MetricDataPoint};
+use crate::{Event, EventData, EventMetadata};
+
+/// Events written per aggregate; loads read one aggregate at a time
+const EVENTS_PER_AGGREGATE: usize = 10;
+
+/// Latency percentiles of one operation, in milliseconds
+#[derive(Debug, Clone)]
+pub struct LatencyPercentiles {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencyPercentiles {
+    fn from_samples(name: &str, samples: &[Duration]) -> Self {
+        let points: Vec<MetricDataPoint> = samples
+            .iter()
+            .map(|d| MetricDataPoint::new(d.as_secs_f64() * 1000.0))
+            .collect();
+        let refs: Vec<&MetricDataPoint> = points.iter().collect();
+        let metric = AggregatedMetric::from_points(name.to_string(), &refs);
+        Self {
+            p50_ms: metric.p50,
+            p95_ms: metric.p95,
+            p99_ms: metric.p99,
+            max_ms: metric.max,
+        }
+    }
+}
+
+/// Result of an end-to-end round-trip benchmark
+#[derive(Debug, Clone)]
+pub struct RoundTripReport {
+    pub events: usize,
+    pub payload_size_bytes: usize,
+    pub save_events_per_second: f64,
+    pub load_events_per_second: f64,
+    /// Latency of saving one event
+    pub save_latency: LatencyPercentiles,
+    /// Latency of loading one aggregate of `EVENTS_PER_AGGREGATE` events,
+    /// divided by the events it returned
+    pub load_latency: LatencyPercentiles,
+}
+
+/// Save `event_count` events one at a time into the SQLite database at
+/// `database_path`, then load them back aggregate by aggregate.
+///
+/// Events carry metadata and a JSON payload padded to roughly
+/// `payload_size_bytes`, ten per aggregate. Pass `:memory:` to leave nothing on
+/// disk; a file path measures real disk writes but adds the events to it.
+pub async fn benchmark_event_roundtrip(
+    database_path: &str,
+    event_count: usize,
+    payload_size_bytes: usize,
+) -> Result<RoundTripReport, EventualiError> {
+    if event_count == 0 {
+        return Err(EventualiError::Configuration("Round-trip benchmark needs at least one event".to_string()));
+    }
+
+    let store = create_event_store(EventStoreConfig::sqlite(database_path.to_string())).await?;
+    let run_id = uuid::Uuid::new_v4();
+    let padding = "x".repeat(payload_size_bytes);
+    let aggregate_ids: Vec<String> = (0..event_count.div_ceil(EVENTS_PER_AGGREGATE))
+        .map(|i| format!("roundtrip-{run_id}-{i}"))
+        .collect();
+
+    let mut save_samples = Vec::with_capacity(event_count);
+    let save_start = Instant::now();
+    for seq in 0..event_count {
+        let aggregate_id = &aggregate_ids[seq / EVENTS_PER_AGGREGATE];
+        let version = (seq % EVENTS_PER_AGGREGATE) as i64 + 1;
+        let mut event = Event::new(
+            aggregate_id.clone(),
+            "Order".to_string(),
+            "OrderLineAdded".to_string(),
+            1,
+            version,
+            EventData::from_json(&serde_json::json!({
+                "order_id": aggregate_id,
+                "line": version,
+                "sku": format!("SKU-{}", seq % 97),
+                "quantity": seq % 5 + 1,
+                "unit_price_cents": 1_999,
+                "notes": padding,
+            }))?,
+        );
+        event.metadata = EventMetadata {
+            correlation_id: Some(run_id),
+            user_id: Some("benchmark".to_string()),
+            ..EventMetadata::default()
+        };
+
+        let started = Instant::now();
+        store.save_events(vec![event]).await?;
+        save_samples.push(started.elapsed());
+    }
+    let save_elapsed = save_start.elapsed();
+
+    let mut load_samples = Vec::with_capacity(event_count);
+    let mut loaded = 0;
+    let load_start = Instant::now();
+    for aggregate_id in &aggregate_ids {
+        let started = Instant::now();
+        let events = store.load_events(aggregate_id, None).await?;
+        let elapsed = started.elapsed();
+        if !events.is_empty() {
+            load_samples.push(elapsed / events.len() as u32);
+        }
+        loaded += events.len();
+    }
+    let load_elapsed = load_start.elapsed();
+
+    if loaded != event_count {
+        return Err(EventualiError::InvalidState(format!(
+            "Round-trip benchmark saved {event_count} events but loaded {loaded}"
+        )));
+    }
+
+    let per_second = |count: usize, elapsed: Duration| {
+        if elapsed.is_zero() { 0.0 } else { count as f64 / elapsed.as_secs_f64() }
+    };
+
+    Ok(RoundTripReport {
+        events: event_count,
+        payload_size_bytes,
+        save_events_per_second: per_second(event_count, save_elapsed),
+        load_events_per_second: per_second(loaded, load_elapsed),
+        save_latency: LatencyPercentiles::from_samples("save_latency_ms", &save_samples),
+        load_latency: LatencyPercentiles::from_samples("load_latency_ms", &load_samples),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_roundtrip_reports_throughput_and_percentiles() {
+        let report = benchmark_event_roundtrip(":memory:", 120, 200).await.unwrap();
+
+        assert_eq!(report.events, 120);
+        assert!(report.save_events_per_second > 0.0);
+        assert!(report.load_events_per_second > 0.0);
+        for latency in [&report.save_latency, &report.load_latency] {
+            assert!(latency.p50_ms > 0.0);
+            assert!(latency.p50_ms <= latency.p95_ms);
+            assert!(latency.p95_ms <= latency.p99_ms);
+            assert!(latency.p99_ms <= latency.max_ms);
+            assert!(latency.max_ms < 10_000.0);
+        }
+
+        assert!(matches!(
+            benchmark_event_roundtrip(":memory:", 0, 200).await,
+            Err(EventualiError::Configuration(_))
+        ));
+    }
+}
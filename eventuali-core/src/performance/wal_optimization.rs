@@ -175,38 +175,38 @@ impl WalOptimizer {
     pub fn optimize_connection(&self, conn: &SqliteConnection) -> Result<(), EventualiError> {
         // Set journal mode
         let journal_mode = self.journal_mode_to_string(&self.config.journal_mode);
-        conn.execute(&format!("PRAGMA journal_mode = {journal_mode}"), [])
+        conn.pragma_update(None, "journal_mode", journal_mode)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set journal mode: {e}")))?;
 
         // Set synchronous mode
         let sync_mode = self.sync_mode_to_string(&self.config.synchronous_mode);
-        conn.execute(&format!("PRAGMA synchronous = {sync_mode}"), [])
+        conn.pragma_update(None, "synchronous", sync_mode)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set synchronous mode: {e}")))?;
 
         // Set cache size
-        conn.execute(&format!("PRAGMA cache_size = {}", self.config.cache_size_kb), [])
+        conn.pragma_update(None, "cache_size", self.config.cache_size_kb)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set cache size: {e}")))?;
 
         // Set temp store mode
         let temp_store = self.temp_store_to_string(&self.config.temp_store);
-        conn.execute(&format!("PRAGMA temp_store = {temp_store}"), [])
+        conn.pragma_update(None, "temp_store", temp_store)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set temp store: {e}")))?;
 
         // Set memory mapping size
-        conn.execute(&format!("PRAGMA mmap_size = {}", self.config.mmap_size_mb * 1024 * 1024), [])
+        conn.pragma_update(None, "mmap_size", self.config.mmap_size_mb * 1024 * 1024)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set mmap size: {e}")))?;
 
         // Set page size (only effective on new databases)
-        conn.execute(&format!("PRAGMA page_size = {}", self.config.page_size), [])
+        conn.pragma_update(None, "page_size", self.config.page_size)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set page size: {e}")))?;
 
         // Set auto-vacuum mode
         let auto_vacuum = self.auto_vacuum_to_string(&self.config.auto_vacuum);
-        conn.execute(&format!("PRAGMA auto_vacuum = {auto_vacuum}"), [])
+        conn.pragma_update(None, "auto_vacuum", auto_vacuum)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set auto vacuum: {e}")))?;
 
         // Set WAL auto-checkpoint
-        conn.execute(&format!("PRAGMA wal_autocheckpoint = {}", self.config.wal_autocheckpoint), [])
+        conn.pragma_update(None, "wal_autocheckpoint", self.config.wal_autocheckpoint)
             .map_err(|e| EventualiError::Configuration(format!("Failed to set WAL autocheckpoint: {e}")))?;
 
         Ok(())
@@ -216,7 +216,7 @@ impl WalOptimizer {
     pub fn checkpoint(&mut self, conn: &SqliteConnection) -> Result<(), EventualiError> {
         let start_time = Instant::now();
         
-        conn.execute("PRAGMA wal_checkpoint(TRUNCATE)", [])
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE)")
             .map_err(|e| EventualiError::Configuration(format!("Failed to checkpoint WAL: {e}")))?;
 
         let checkpoint_time = start_time.elapsed();
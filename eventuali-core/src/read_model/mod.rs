@@ -0,0 +1,140 @@
+//! Read-model sinks for persisting projection output
+//!
+//! A `ReadModelProjection` turns each event into row writes keyed by entity ID;
+//! a `ReadModelProcessor` applies them to a `ReadModelSink` together with the
+//! projection's checkpoint, in one transaction, so the table never reflects an
+//! event the checkpoint does not (and vice versa).
+//!
+//! Several projections can share a sink's table: rows are keyed by projection
+//! name and entity ID, and clearing one projection leaves the others' rows.
+
+mod postgres;
+mod sqlite;
+
+pub use postgres::PostgresReadModelSink;
+pub use sqlite::SqliteReadModelSink;
+
+use crate::store::EventStore;
+use crate::streaming::{EventStreamProcessor, StreamEvent};
+use crate::{Event, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// A single change to a read-model table
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadModelWrite {
+    /// Insert the row, or replace the existing row with the same entity ID
+    Upsert { entity_id: String, row: Value },
+    /// Remove the row if it exists
+    Delete { entity_id: String },
+}
+
+impl ReadModelWrite {
+    pub fn upsert(entity_id: impl Into<String>, row: Value) -> Self {
+        ReadModelWrite::Upsert { entity_id: entity_id.into(), row }
+    }
+
+    pub fn delete(entity_id: impl Into<String>) -> Self {
+        ReadModelWrite::Delete { entity_id: entity_id.into() }
+    }
+}
+
+/// Destination table for a projection's rows, keyed by entity ID
+#[async_trait]
+pub trait ReadModelSink {
+    /// Apply `writes` in order and record `position` as the checkpoint of
+    /// `projection_name`, all in one transaction.
+    ///
+    /// The checkpoint is checked in the same transaction: if `position` is at
+    /// or before it, nothing is written and `false` is returned, so concurrent
+    /// or repeated deliveries of an event apply it once.
+    async fn apply(&self, projection_name: &str, writes: Vec<ReadModelWrite>, position: u64) -> Result<bool>;
+
+    /// Last global position applied for `projection_name`
+    async fn checkpoint(&self, projection_name: &str) -> Result<Option<u64>>;
+
+    /// Current row of `projection_name` for `entity_id`
+    async fn get(&self, projection_name: &str, entity_id: &str) -> Result<Option<Value>>;
+
+    /// Remove the rows and the checkpoint of `projection_name`, ready for a rebuild
+    async fn clear(&self, projection_name: &str) -> Result<()>;
+}
+
+/// Projection whose output is a list of row writes per event
+pub trait ReadModelProjection {
+    /// Writes to make for `event`; events the projection ignores return none
+    fn project(&self, event: &Event) -> Result<Vec<ReadModelWrite>>;
+}
+
+/// Drives a `ReadModelProjection` into a `ReadModelSink`
+pub struct ReadModelProcessor<P, S: ?Sized> {
+    projection_name: String,
+    projection: P,
+    sink: Arc<S>,
+}
+
+impl<P, S> ReadModelProcessor<P, S>
+where
+    P: ReadModelProjection + Send + Sync,
+    S: ReadModelSink + Send + Sync + ?Sized,
+{
+    pub fn new(projection_name: impl Into<String>, projection: P, sink: Arc<S>) -> Self {
+        Self {
+            projection_name: projection_name.into(),
+            projection,
+            sink,
+        }
+    }
+
+    pub fn sink(&self) -> &Arc<S> {
+        &self.sink
+    }
+
+    /// Apply one event at `global_position`.
+    ///
+    /// Events at or before the checkpoint were already applied and are skipped,
+    /// so redelivery is harmless. Returns whether the event was applied.
+    pub async fn apply_event(&self, global_position: u64, event: &Event) -> Result<bool> {
+        let writes = self.projection.project(event)?;
+        self.sink.apply(&self.projection_name, writes, global_position).await
+    }
+
+    /// Apply every event in `store` past the sink's checkpoint, reading
+    /// `batch_size` events per query. Returns the number of events applied.
+    pub async fn catch_up(&self, store: &(dyn EventStore + Send + Sync), batch_size: usize) -> Result<u64> {
+        let batch_size = batch_size.max(1);
+        let mut position = self.sink.checkpoint(&self.projection_name).await?.unwrap_or(0);
+        let mut applied = 0;
+
+        loop {
+            let page = store.load_events_after_position(position, batch_size).await?;
+            if page.is_empty() {
+                return Ok(applied);
+            }
+            for (global_position, event) in page {
+                if self.apply_event(global_position, &event).await? {
+                    applied += 1;
+                }
+                position = global_position;
+            }
+        }
+    }
+
+    /// Clear the table and checkpoint, then replay the whole store
+    pub async fn rebuild(&self, store: &(dyn EventStore + Send + Sync), batch_size: usize) -> Result<u64> {
+        self.sink.clear(&self.projection_name).await?;
+        self.catch_up(store, batch_size).await
+    }
+}
+
+#[async_trait]
+impl<P, S> EventStreamProcessor for ReadModelProcessor<P, S>
+where
+    P: ReadModelProjection + Send + Sync,
+    S: ReadModelSink + Send + Sync + ?Sized,
+{
+    async fn process_event(&self, event: &StreamEvent) -> Result<()> {
+        self.apply_event(event.global_position, &event.event).await.map(|_| ())
+    }
+}
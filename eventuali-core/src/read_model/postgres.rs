@@ -0,0 +1,155 @@
+use super::{ReadModelSink, ReadModelWrite};
+use crate::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{postgres::PgPool, Row};
+
+/// Read-model table in PostgreSQL, storing each row as a JSONB document
+pub struct PostgresReadModelSink {
+    pool: PgPool,
+    table_name: String,
+}
+
+impl PostgresReadModelSink {
+    pub fn new(pool: PgPool, table_name: Option<String>) -> Self {
+        Self {
+            pool,
+            table_name: table_name.unwrap_or_else(|| "read_models".to_string()),
+        }
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Create the row table and its `<table>_checkpoints` companion
+    pub async fn initialize(&self) -> Result<()> {
+        sqlx::query(&format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {} (
+                projection_name TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                data JSONB NOT NULL,
+                position BIGINT NOT NULL,
+                PRIMARY KEY (projection_name, entity_id)
+            )
+            "#,
+            self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {}_checkpoints (
+                projection_name TEXT PRIMARY KEY,
+                position BIGINT NOT NULL
+            )
+            "#,
+            self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReadModelSink for PostgresReadModelSink {
+    async fn apply(&self, projection_name: &str, writes: Vec<ReadModelWrite>, position: u64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Moving the checkpoint first locks its row, so a concurrent delivery
+        // of the same event waits here and then finds it applied
+        let advanced = sqlx::query(&format!(
+            "INSERT INTO {table}_checkpoints (projection_name, position) VALUES ($1, $2) \
+             ON CONFLICT (projection_name) DO UPDATE SET position = EXCLUDED.position \
+             WHERE {table}_checkpoints.position < EXCLUDED.position",
+            table = self.table_name
+        ))
+        .bind(projection_name)
+        .bind(position as i64)
+        .execute(&mut *tx)
+        .await?;
+        if advanced.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for write in writes {
+            match write {
+                ReadModelWrite::Upsert { entity_id, row } => {
+                    sqlx::query(&format!(
+                        "INSERT INTO {} (projection_name, entity_id, data, position) VALUES ($1, $2, $3, $4) \
+                         ON CONFLICT (projection_name, entity_id) DO UPDATE SET data = EXCLUDED.data, position = EXCLUDED.position",
+                        self.table_name
+                    ))
+                    .bind(projection_name)
+                    .bind(entity_id)
+                    .bind(row)
+                    .bind(position as i64)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                ReadModelWrite::Delete { entity_id } => {
+                    sqlx::query(&format!(
+                        "DELETE FROM {} WHERE projection_name = $1 AND entity_id = $2",
+                        self.table_name
+                    ))
+                    .bind(projection_name)
+                    .bind(entity_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn checkpoint(&self, projection_name: &str) -> Result<Option<u64>> {
+        let row = sqlx::query(&format!(
+            "SELECT position FROM {}_checkpoints WHERE projection_name = $1",
+            self.table_name
+        ))
+        .bind(projection_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match row {
+            Some(row) => Some(row.try_get::<i64, _>("position")? as u64),
+            None => None,
+        })
+    }
+
+    async fn get(&self, projection_name: &str, entity_id: &str) -> Result<Option<Value>> {
+        let row = sqlx::query(&format!(
+            "SELECT data FROM {} WHERE projection_name = $1 AND entity_id = $2",
+            self.table_name
+        ))
+        .bind(projection_name)
+        .bind(entity_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(row.try_get::<Value, _>("data")?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn clear(&self, projection_name: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE projection_name = $1", self.table_name))
+            .bind(projection_name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!("DELETE FROM {}_checkpoints WHERE projection_name = $1", self.table_name))
+            .bind(projection_name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
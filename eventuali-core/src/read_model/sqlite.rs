@@ -0,0 +1,153 @@
+use super::{ReadModelSink, ReadModelWrite};
+use crate::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+use sqlx::{sqlite::SqlitePool, Row};
+
+/// Read-model table in SQLite, storing each row as a JSON document
+pub struct SqliteReadModelSink {
+    pool: SqlitePool,
+    table_name: String,
+}
+
+impl SqliteReadModelSink {
+    pub fn new(pool: SqlitePool, table_name: Option<String>) -> Self {
+        Self {
+            pool,
+            table_name: table_name.unwrap_or_else(|| "read_models".to_string()),
+        }
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Create the row table and its `<table>_checkpoints` companion
+    pub async fn initialize(&self) -> Result<()> {
+        let create_tables = format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {table} (
+                projection_name TEXT NOT NULL,
+                entity_id TEXT NOT NULL,
+                data TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                PRIMARY KEY (projection_name, entity_id)
+            );
+
+            CREATE TABLE IF NOT EXISTS {table}_checkpoints (
+                projection_name TEXT PRIMARY KEY,
+                position INTEGER NOT NULL
+            );
+            "#,
+            table = self.table_name
+        );
+
+        sqlx::query(&create_tables)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReadModelSink for SqliteReadModelSink {
+    async fn apply(&self, projection_name: &str, writes: Vec<ReadModelWrite>, position: u64) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+
+        // Moving the checkpoint first takes the write lock, so a concurrent
+        // delivery of the same event waits here and then finds it applied
+        let advanced = sqlx::query(&format!(
+            "INSERT INTO {table}_checkpoints (projection_name, position) VALUES (?, ?) \
+             ON CONFLICT(projection_name) DO UPDATE SET position = excluded.position \
+             WHERE {table}_checkpoints.position < excluded.position",
+            table = self.table_name
+        ))
+        .bind(projection_name)
+        .bind(position as i64)
+        .execute(&mut *tx)
+        .await?;
+        if advanced.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for write in writes {
+            match write {
+                ReadModelWrite::Upsert { entity_id, row } => {
+                    sqlx::query(&format!(
+                        "INSERT INTO {} (projection_name, entity_id, data, position) VALUES (?, ?, ?, ?) \
+                         ON CONFLICT(projection_name, entity_id) DO UPDATE SET data = excluded.data, position = excluded.position",
+                        self.table_name
+                    ))
+                    .bind(projection_name)
+                    .bind(entity_id)
+                    .bind(serde_json::to_string(&row)?)
+                    .bind(position as i64)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                ReadModelWrite::Delete { entity_id } => {
+                    sqlx::query(&format!(
+                        "DELETE FROM {} WHERE projection_name = ? AND entity_id = ?",
+                        self.table_name
+                    ))
+                    .bind(projection_name)
+                    .bind(entity_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn checkpoint(&self, projection_name: &str) -> Result<Option<u64>> {
+        let row = sqlx::query(&format!(
+            "SELECT position FROM {}_checkpoints WHERE projection_name = ?",
+            self.table_name
+        ))
+        .bind(projection_name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(match row {
+            Some(row) => Some(row.try_get::<i64, _>("position")? as u64),
+            None => None,
+        })
+    }
+
+    async fn get(&self, projection_name: &str, entity_id: &str) -> Result<Option<Value>> {
+        let row = sqlx::query(&format!(
+            "SELECT data FROM {} WHERE projection_name = ? AND entity_id = ?",
+            self.table_name
+        ))
+        .bind(projection_name)
+        .bind(entity_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => {
+                let data: String = row.try_get("data")?;
+                Ok(Some(serde_json::from_str(&data)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn clear(&self, projection_name: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!("DELETE FROM {} WHERE projection_name = ?", self.table_name))
+            .bind(projection_name)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&format!("DELETE FROM {}_checkpoints WHERE projection_name = ?", self.table_name))
+            .bind(projection_name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,709 @@
+//! Audit sinks for shipping audit trail entries to durable or external stores
+//!
+//! `AuditManager` keeps the head of its hash chain in memory; sinks receive a
+//! copy of every entry as it is recorded so retention is not tied to the
+//! manager's lifetime.
+
+use crate::security::audit::{AuditSearchCriteria, AuditTrailEntry};
+use crate::tenancy::TenantId;
+use crate::store::config::validate_table_name;
+use crate::{EventualiError, Result};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Destination for audit trail entries
+pub trait AuditSink {
+    /// Persist a single entry. Entries arrive in the order they were recorded.
+    fn write(&self, entry: &AuditTrailEntry) -> Result<()>;
+}
+
+/// Sink that keeps entries in memory, mainly for tests and short-lived tooling
+#[derive(Debug, Default)]
+pub struct InMemoryAuditSink {
+    entries: Mutex<Vec<AuditTrailEntry>>,
+}
+
+impl InMemoryAuditSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Entries received so far, in write order
+    pub fn entries(&self) -> Vec<AuditTrailEntry> {
+        self.entries.lock().map(|e| e.clone()).unwrap_or_default()
+    }
+}
+
+impl AuditSink for InMemoryAuditSink {
+    fn write(&self, entry: &AuditTrailEntry) -> Result<()> {
+        self.entries
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Audit sink lock poisoned".to_string()))?
+            .push(entry.clone());
+        Ok(())
+    }
+}
+
+/// Sink that appends one JSON document per line to a file
+#[derive(Debug)]
+pub struct JsonLinesAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl JsonLinesAuditSink {
+    /// Open `path` for appending, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read every entry in the file back, in write order
+    pub fn read_entries(&self) -> Result<Vec<AuditTrailEntry>> {
+        let reader = BufReader::new(File::open(&self.path)?);
+        let mut entries = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                entries.push(serde_json::from_str(&line)?);
+            }
+        }
+        Ok(entries)
+    }
+}
+
+impl AuditSink for JsonLinesAuditSink {
+    fn write(&self, entry: &AuditTrailEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Audit sink lock poisoned".to_string()))?;
+        file.write_all(&line)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Sink that stores entries in a SQLite table
+///
+/// Entries keep their logging order in the `sequence` column, which is also
+/// the order of the hash chain. The filterable fields are stored in indexed
+/// columns, so `search_entries` answers queries over millions of entries
+/// without reading them all.
+#[derive(Debug)]
+pub struct SqliteAuditSink {
+    conn: Mutex<Connection>,
+    table_name: String,
+}
+
+impl SqliteAuditSink {
+    /// Open (or create) the database at `path`; `:memory:` opens a private in-memory database
+    pub fn open(path: &str, table_name: Option<String>) -> Result<Self> {
+        let table_name = table_name.unwrap_or_else(|| "audit_trail".to_string());
+        validate_table_name(&table_name)?;
+
+        let conn = if path == ":memory:" {
+            Connection::open_in_memory()
+        } else {
+            Connection::open(path)
+        }
+        .map_err(|e| EventualiError::DatabaseError(format!("Failed to open audit database: {e}")))?;
+
+        let sink = Self {
+            conn: Mutex::new(conn),
+            table_name,
+        };
+        sink.initialize()?;
+        Ok(sink)
+    }
+
+    fn initialize(&self) -> Result<()> {
+        let create_table = format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {table} (
+                sequence INTEGER PRIMARY KEY AUTOINCREMENT,
+                entry_id TEXT NOT NULL UNIQUE,
+                event_type TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                timestamp TEXT NOT NULL,
+                entry_json TEXT NOT NULL
+            );
+            "#,
+            table = self.table_name
+        );
+
+        let conn = self.lock()?;
+        conn.execute_batch(&create_table)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to create audit table: {e}")))?;
+        self.add_search_columns(&conn)?;
+
+        let create_indexes = format!(
+            r#"
+            CREATE INDEX IF NOT EXISTS idx_{table}_user_time ON {table} (user_id, timestamp_micros);
+            CREATE INDEX IF NOT EXISTS idx_{table}_event_type_time ON {table} (event_type, timestamp_micros);
+            CREATE INDEX IF NOT EXISTS idx_{table}_risk_time ON {table} (risk_level, timestamp_micros);
+            CREATE INDEX IF NOT EXISTS idx_{table}_time ON {table} (timestamp_micros);
+            CREATE INDEX IF NOT EXISTS idx_{table}_tenant_time ON {table} (tenant_id, timestamp_micros);
+            "#,
+            table = self.table_name
+        );
+        conn.execute_batch(&create_indexes)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to create audit indexes: {e}")))
+    }
+
+    /// Add the columns searches filter on to tables created before they
+    /// existed, filling them in from the stored entries
+    fn add_search_columns(&self, conn: &Connection) -> Result<()> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", self.table_name))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to inspect audit table: {e}")))?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to inspect audit table: {e}")))?;
+        if columns.iter().any(|c| c == "timestamp_micros") {
+            return Ok(());
+        }
+
+        for column in SEARCH_COLUMNS {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {column}", self.table_name), [])
+                .map_err(|e| EventualiError::DatabaseError(format!("Failed to add audit column: {e}")))?;
+        }
+
+        let mut stmt = conn
+            .prepare(&format!("SELECT sequence, entry_json FROM {}", self.table_name))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entries: {e}")))?;
+        let rows: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entries: {e}")))?;
+
+        let update = format!(
+            "UPDATE {} SET resource = ?1, risk_level = ?2, ip_address = ?3, tenant_id = ?4, timestamp_micros = ?5 \
+             WHERE sequence = ?6",
+            self.table_name
+        );
+        for (sequence, json) in rows {
+            let entry: AuditTrailEntry = serde_json::from_str(&json)?;
+            conn.execute(
+                &update,
+                params![
+                    entry.resource,
+                    format!("{:?}", entry.risk_level),
+                    entry.ip_address,
+                    entry.tenant_id.as_ref().map(|t| t.as_str().to_string()),
+                    entry.timestamp.timestamp_micros(),
+                    sequence
+                ],
+            )
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to backfill audit entry: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Entries matching `criteria`, newest first, at most `limit` of them
+    /// (1000 by default)
+    ///
+    /// User, event type, resource, time range, risk level and IP address
+    /// filters run in SQL against the indexes. Compliance tags are checked on
+    /// the decoded rows as the cursor advances, so only matching rows up to
+    /// the limit are ever decoded.
+    ///
+    /// Only entries logged without a tenant are searched; see
+    /// [`search_tenant_entries`](Self::search_tenant_entries) and
+    /// [`search_entries_across_tenants`](Self::search_entries_across_tenants).
+    pub fn search_entries(&self, criteria: &AuditSearchCriteria, limit: Option<usize>) -> Result<Vec<AuditTrailEntry>> {
+        self.search_scoped(SearchScope::Global, criteria, limit)
+    }
+
+    /// Entries logged for `tenant_id` matching `criteria`, newest first, at
+    /// most `limit` of them (1000 by default)
+    pub fn search_tenant_entries(
+        &self,
+        tenant_id: &TenantId,
+        criteria: &AuditSearchCriteria,
+        limit: Option<usize>,
+    ) -> Result<Vec<AuditTrailEntry>> {
+        self.search_scoped(SearchScope::Tenant(tenant_id), criteria, limit)
+    }
+
+    /// Entries from every tenant and the global log matching `criteria`,
+    /// newest first, at most `limit` of them (1000 by default)
+    pub fn search_entries_across_tenants(
+        &self,
+        criteria: &AuditSearchCriteria,
+        limit: Option<usize>,
+    ) -> Result<Vec<AuditTrailEntry>> {
+        self.search_scoped(SearchScope::AllTenants, criteria, limit)
+    }
+
+    fn search_scoped(
+        &self,
+        scope: SearchScope<'_>,
+        criteria: &AuditSearchCriteria,
+        limit: Option<usize>,
+    ) -> Result<Vec<AuditTrailEntry>> {
+        let limit = limit.unwrap_or(1000);
+        let (query, values) = self.search_query(scope, criteria);
+
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to search audit entries: {e}")))?;
+        let mut rows = stmt
+            .query(params_from_iter(values))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to search audit entries: {e}")))?;
+
+        let mut entries = Vec::new();
+        while entries.len() < limit {
+            let Some(row) = rows
+                .next()
+                .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entry: {e}")))?
+            else {
+                break;
+            };
+            let json: String = row
+                .get(0)
+                .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entry: {e}")))?;
+            let entry: AuditTrailEntry = serde_json::from_str(&json)?;
+            if criteria.matches(&entry) {
+                entries.push(entry);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// SQLite's plan for a [`search_entries`](Self::search_entries) call, one line per step
+    pub fn explain_search(&self, criteria: &AuditSearchCriteria) -> Result<Vec<String>> {
+        let (query, values) = self.search_query(SearchScope::Global, criteria);
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&format!("EXPLAIN QUERY PLAN {query}"))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to explain audit search: {e}")))?;
+        stmt.query_map(params_from_iter(values), |row| row.get::<_, String>(3))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to explain audit search: {e}")))
+    }
+
+    fn search_query(&self, scope: SearchScope<'_>, criteria: &AuditSearchCriteria) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        match scope {
+            SearchScope::Global => conditions.push("tenant_id IS NULL".to_string()),
+            SearchScope::Tenant(tenant_id) => {
+                conditions.push("tenant_id = ?".to_string());
+                values.push(Value::Text(tenant_id.as_str().to_string()));
+            }
+            SearchScope::AllTenants => {}
+        }
+
+        if let Some(user_id) = &criteria.user_id {
+            conditions.push("user_id = ?".to_string());
+            values.push(Value::Text(user_id.clone()));
+        }
+        if let Some(event_types) = &criteria.event_types {
+            push_in(&mut conditions, &mut values, "event_type", event_types.iter().map(|t| format!("{t:?}")));
+        }
+        if let Some(resources) = &criteria.resources {
+            push_in(&mut conditions, &mut values, "resource", resources.iter().cloned());
+        }
+        if let Some(start_time) = criteria.start_time {
+            conditions.push("timestamp_micros >= ?".to_string());
+            values.push(Value::Integer(start_time.timestamp_micros()));
+        }
+        if let Some(end_time) = criteria.end_time {
+            conditions.push("timestamp_micros <= ?".to_string());
+            values.push(Value::Integer(end_time.timestamp_micros()));
+        }
+        if let Some(risk_levels) = &criteria.risk_levels {
+            push_in(&mut conditions, &mut values, "risk_level", risk_levels.iter().map(|r| format!("{r:?}")));
+        }
+        if let Some(ip_addresses) = &criteria.ip_addresses {
+            push_in(&mut conditions, &mut values, "ip_address", ip_addresses.iter().cloned());
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let query = format!(
+            "SELECT entry_json FROM {} {where_clause} ORDER BY timestamp_micros DESC, sequence DESC",
+            self.table_name
+        );
+        (query, values)
+    }
+
+    /// Read every stored entry back, in write order
+    pub fn read_entries(&self) -> Result<Vec<AuditTrailEntry>> {
+        let mut entries = Vec::new();
+        self.for_each_entry(|entry| entries.push(entry))?;
+        Ok(entries)
+    }
+
+    /// Pass every stored entry to `f` in write order, decoding one row at a
+    /// time so the table never has to fit in memory
+    pub fn for_each_entry(&self, mut f: impl FnMut(AuditTrailEntry)) -> Result<()> {
+        let conn = self.lock()?;
+        let query = format!("SELECT entry_json FROM {} ORDER BY sequence", self.table_name);
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entries: {e}")))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entries: {e}")))?;
+
+        for row in rows {
+            let json = row.map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit entry: {e}")))?;
+            f(serde_json::from_str(&json)?);
+        }
+        Ok(())
+    }
+
+    /// Number of stored entries
+    pub fn count_entries(&self) -> Result<usize> {
+        let query = format!("SELECT COUNT(*) FROM {}", self.table_name);
+        self.lock()?
+            .query_row(&query, [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to count audit entries: {e}")))
+    }
+
+    /// The last stored entry of every hash chain: the global chain (no tenant)
+    /// and one per tenant, so a reopened manager continues each chain
+    pub fn chain_heads(&self) -> Result<Vec<ChainHead>> {
+        let query = format!(
+            "SELECT t.tenant_id, heads.entry_count, t.entry_json FROM {table} t \
+             JOIN (SELECT MAX(sequence) AS sequence, COUNT(*) AS entry_count FROM {table} GROUP BY tenant_id) heads \
+             ON t.sequence = heads.sequence",
+            table = self.table_name
+        );
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit chain heads: {e}")))?;
+        let rows: Vec<(Option<String>, i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read audit chain heads: {e}")))?;
+
+        let mut heads = Vec::with_capacity(rows.len());
+        for (tenant_id, entry_count, json) in rows {
+            let entry: AuditTrailEntry = serde_json::from_str(&json)?;
+            let tenant_id = tenant_id.map(TenantId::new).transpose()?;
+            heads.push(ChainHead {
+                tenant_id,
+                entry_count: entry_count as usize,
+                integrity_hash: entry.integrity_hash,
+            });
+        }
+        Ok(heads)
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Audit sink lock poisoned".to_string()))
+    }
+}
+
+impl AuditSink for SqliteAuditSink {
+    fn write(&self, entry: &AuditTrailEntry) -> Result<()> {
+        let entry_json = serde_json::to_string(entry)?;
+        let query = format!(
+            "INSERT INTO {} (entry_id, event_type, user_id, timestamp, entry_json, \
+             resource, risk_level, ip_address, tenant_id, timestamp_micros) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            self.table_name
+        );
+
+        self.lock()?
+            .execute(
+                &query,
+                params![
+                    entry.entry_id,
+                    format!("{:?}", entry.event_type),
+                    entry.user_id,
+                    entry.timestamp.to_rfc3339(),
+                    entry_json,
+                    entry.resource,
+                    format!("{:?}", entry.risk_level),
+                    entry.ip_address,
+                    entry.tenant_id.as_ref().map(|t| t.as_str().to_string()),
+                    entry.timestamp.timestamp_micros()
+                ],
+            )
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to write audit entry: {e}")))?;
+        Ok(())
+    }
+}
+
+/// Last stored entry of one hash chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainHead {
+    /// `None` for the global chain
+    pub tenant_id: Option<TenantId>,
+    pub entry_count: usize,
+    pub integrity_hash: String,
+}
+
+/// Columns added for indexed search, in `ALTER TABLE ... ADD COLUMN` form
+const SEARCH_COLUMNS: [&str; 5] = [
+    "resource TEXT",
+    "risk_level TEXT",
+    "ip_address TEXT",
+    "tenant_id TEXT",
+    "timestamp_micros INTEGER",
+];
+
+/// Which tenant's entries a search may return
+#[derive(Clone, Copy)]
+enum SearchScope<'a> {
+    /// Entries logged without a tenant
+    Global,
+    Tenant(&'a TenantId),
+    AllTenants,
+}
+
+fn push_in(conditions: &mut Vec<String>, values: &mut Vec<Value>, column: &str, items: impl Iterator<Item = String>) {
+    let start = values.len();
+    values.extend(items.map(Value::Text));
+    let placeholders = vec!["?"; values.len() - start].join(", ");
+    conditions.push(format!("{column} IN ({placeholders})"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::audit::{AuditEventType, AuditManager, AuditOutcome};
+    use std::sync::Arc;
+
+    fn log_entries(manager: &mut AuditManager, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|i| {
+                manager
+                    .log_audit_event(
+                        AuditEventType::DataAccess,
+                        format!("user{i}"),
+                        "read".to_string(),
+                        "orders".to_string(),
+                        AuditOutcome::Success,
+                        None,
+                    )
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_entries_reach_file_sink_in_order() {
+        let path = std::env::temp_dir().join(format!("eventuali-audit-{}.jsonl", uuid::Uuid::new_v4()));
+        let sink = Arc::new(JsonLinesAuditSink::open(&path).unwrap());
+        let mut manager = AuditManager::new().with_sink(sink.clone());
+
+        let ids = log_entries(&mut manager, 5);
+        manager
+            .log_authentication_event(
+                "user9".to_string(),
+                Some("session-1".to_string()),
+                Some("10.0.0.1".to_string()),
+                None,
+                false,
+                Some("bad password".to_string()),
+            )
+            .unwrap();
+
+        let written = sink.read_entries().unwrap();
+        assert_eq!(written.len(), 6);
+        let written_ids: Vec<&str> = written.iter().map(|e| e.entry_id.as_str()).collect();
+        assert_eq!(&written_ids[..5], ids.iter().map(String::as_str).collect::<Vec<_>>().as_slice());
+
+        // Each entry links to the previous one, matching the in-memory chain
+        for pair in written.windows(2) {
+            assert_eq!(pair[1].previous_hash.as_deref(), Some(pair[0].integrity_hash.as_str()));
+        }
+
+        // Event-specific details are filled in before the entry is shipped
+        assert_eq!(written[5].session_id.as_deref(), Some("session-1"));
+        assert_eq!(written[5].error_details.as_deref(), Some("bad password"));
+        assert!(manager.verify_integrity().chain_verified);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_manager_fans_out_to_every_sink() {
+        let memory = Arc::new(InMemoryAuditSink::new());
+        let sqlite = Arc::new(SqliteAuditSink::open(":memory:", None).unwrap());
+        let mut manager = AuditManager::new()
+            .with_sink(memory.clone())
+            .with_sink(sqlite.clone());
+
+        let ids = log_entries(&mut manager, 3);
+
+        let from_memory: Vec<String> = memory.entries().into_iter().map(|e| e.entry_id).collect();
+        let from_sqlite: Vec<String> = sqlite.read_entries().unwrap().into_iter().map(|e| e.entry_id).collect();
+        assert_eq!(from_memory, ids);
+        assert_eq!(from_sqlite, ids);
+    }
+
+    #[test]
+    fn test_a_rejected_sink_write_is_reported_without_undoing_the_entry() {
+        struct RejectingSink;
+
+        impl AuditSink for RejectingSink {
+            fn write(&self, _entry: &AuditTrailEntry) -> Result<()> {
+                Err(EventualiError::DatabaseError("sink offline".to_string()))
+            }
+        }
+
+        let memory = Arc::new(InMemoryAuditSink::new());
+        let mut manager = AuditManager::new()
+            .with_sink(Arc::new(RejectingSink))
+            .with_sink(memory.clone());
+
+        let ids = log_entries(&mut manager, 2);
+
+        // Both entries are recorded, chained and still reach the later sink
+        assert_eq!(memory.entries().len(), 2);
+        let status = manager.verify_integrity();
+        assert!(status.chain_verified);
+        assert_eq!(status.total_entries, 2);
+
+        let failures = manager.take_sink_failures();
+        let failed_ids: Vec<&str> = failures.iter().map(|f| f.entry_id.as_str()).collect();
+        assert_eq!(failed_ids, ids.iter().map(String::as_str).collect::<Vec<_>>());
+        assert!(failures.iter().all(|f| f.sink_index == 0 && f.error.contains("sink offline")));
+        assert!(manager.take_sink_failures().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_store_search_uses_indexes() {
+        use crate::security::audit::{AuditSearchCriteria, RiskLevel};
+        use std::collections::HashSet;
+
+        let path = std::env::temp_dir().join(format!("eventuali-audit-{}.db", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap().to_string();
+        let mut manager = AuditManager::new().with_sqlite_store(&path, None).unwrap();
+
+        for i in 0..3000 {
+            let event_type = if i % 3 == 0 { AuditEventType::DataModification } else { AuditEventType::DataAccess };
+            manager
+                .log_audit_event(
+                    event_type,
+                    format!("user{}", i % 50),
+                    "read".to_string(),
+                    format!("resource{}", i % 7),
+                    AuditOutcome::Success,
+                    None,
+                )
+                .unwrap();
+        }
+
+        let by_user = AuditSearchCriteria { user_id: Some("user7".to_string()), ..Default::default() };
+        let by_type_and_resource = AuditSearchCriteria {
+            event_types: Some(HashSet::from([AuditEventType::DataModification])),
+            resources: Some(HashSet::from(["resource3".to_string()])),
+            ..Default::default()
+        };
+        let by_risk = AuditSearchCriteria { risk_levels: Some(HashSet::from([RiskLevel::Low])), ..Default::default() };
+
+        let planner = SqliteAuditSink::open(&path, None).unwrap();
+        let stored = planner.read_entries().unwrap();
+        for criteria in [&by_user, &by_type_and_resource, &by_risk] {
+            let from_sql = manager.search_audit_entries(criteria, Some(5000)).unwrap();
+            assert!(!from_sql.is_empty());
+            let mut sql_ids: Vec<_> = from_sql.iter().map(|e| e.entry_id.clone()).collect();
+            let mut scanned_ids: Vec<_> =
+                stored.iter().filter(|e| criteria.matches(e)).map(|e| e.entry_id.clone()).collect();
+            sql_ids.sort();
+            scanned_ids.sort();
+            assert_eq!(sql_ids, scanned_ids);
+            assert!(from_sql.windows(2).all(|w| w[0].timestamp >= w[1].timestamp));
+
+            let plan = planner.explain_search(criteria).unwrap().join("\n");
+            assert!(plan.contains("USING INDEX"), "search scanned the table: {plan}");
+        }
+
+        let newest = manager.search_audit_entries(&by_user, Some(3)).unwrap();
+        assert_eq!(newest.len(), 3);
+        assert_eq!(newest[0].entry_id, manager.search_audit_entries(&by_user, None).unwrap()[0].entry_id);
+
+        drop(planner);
+        drop(manager);
+        let reopened = AuditManager::new().with_sqlite_store(&path, None).unwrap();
+        assert_eq!(reopened.search_audit_entries(&by_user, None).unwrap().len(), 60);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_reopened_sqlite_store_continues_every_chain() {
+        use crate::security::audit::AuditSearchCriteria;
+
+        let path = std::env::temp_dir().join(format!("eventuali-audit-{}.db", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap().to_string();
+        let tenant = TenantId::new("tenant-a".to_string()).unwrap();
+        let log_tenant_entry = |manager: &mut AuditManager| {
+            manager
+                .log_tenant_audit_event(
+                    tenant.clone(),
+                    AuditEventType::DataAccess,
+                    "tenant-user".to_string(),
+                    "read".to_string(),
+                    "orders".to_string(),
+                    AuditOutcome::Success,
+                    None,
+                )
+                .unwrap()
+        };
+
+        let mut manager = AuditManager::new().with_sqlite_store(&path, None).unwrap();
+        log_entries(&mut manager, 3);
+        log_tenant_entry(&mut manager);
+        drop(manager);
+
+        let mut reopened = AuditManager::new().with_sqlite_store(&path, None).unwrap();
+        log_entries(&mut reopened, 2);
+        log_tenant_entry(&mut reopened);
+
+        let stored = SqliteAuditSink::open(&path, None).unwrap().read_entries().unwrap();
+        assert_eq!(stored.len(), 7);
+        let global: Vec<_> = stored.iter().filter(|e| e.tenant_id.is_none()).collect();
+        for pair in global.windows(2) {
+            assert_eq!(pair[1].previous_hash.as_deref(), Some(pair[0].integrity_hash.as_str()));
+        }
+        let tenant_entries: Vec<_> = stored.iter().filter(|e| e.tenant_id.is_some()).collect();
+        assert_eq!(tenant_entries[1].previous_hash.as_deref(), Some(tenant_entries[0].integrity_hash.as_str()));
+
+        // Verification, search and statistics all read the table
+        let status = reopened.verify_integrity();
+        assert!(status.chain_verified, "{:?}", status.verification_errors);
+        assert_eq!(status.total_entries, 7);
+        assert_eq!(reopened.verify_tenant_integrity(&tenant).total_entries, 2);
+        let everything = AuditSearchCriteria::default();
+        assert_eq!(reopened.search_tenant_audit_entries(&tenant, &everything, None).unwrap().len(), 2);
+        assert_eq!(reopened.search_audit_entries(&everything, None).unwrap().len(), 5);
+        assert_eq!(reopened.search_audit_entries_across_tenants(&everything, None).unwrap().len(), 7);
+        let stats = reopened.get_audit_statistics(1).unwrap();
+        assert_eq!(stats["total_entries"], serde_json::json!(7));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
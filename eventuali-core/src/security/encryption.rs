@@ -1,10 +1,12 @@
+use crate::security::key_material::KeyMaterial;
 use crate::{EventData, EventualiError, Result};
 use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use zeroize::Zeroizing;
 
-/// AES-256-GCM encryption implementation for event data
+/// Authenticated encryption of event data with AES-256-GCM or ChaCha20-Poly1305
 pub struct EventEncryption {
     key_manager: KeyManager,
 }
@@ -16,11 +18,68 @@ pub struct KeyManager {
     default_key_id: String,
 }
 
+/// Portable, passphrase-wrapped export of a key set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WrappedKeySet {
+    version: u32,
+    kdf: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl WrappedKeySet {
+    const VERSION: u32 = 1;
+    const KDF: &'static str = "argon2id";
+    const MEMORY_KIB: u32 = 19 * 1024;
+    const ITERATIONS: u32 = 2;
+    const PARALLELISM: u32 = 1;
+    /// Highest KDF cost an imported blob may ask for. The parameters are only
+    /// authenticated after the key is derived, so without a cap a crafted blob
+    /// could make an import allocate gigabytes or spin for minutes.
+    const MAX_MEMORY_KIB: u32 = 256 * 1024;
+    const MAX_ITERATIONS: u32 = 16;
+    const MAX_PARALLELISM: u32 = 8;
+
+    /// Header fields bound to the ciphertext as associated data, so tampering
+    /// with the KDF parameters fails authentication instead of weakening them
+    fn associated_data(&self) -> Vec<u8> {
+        format!(
+            "{}|{}|{}|{}|{}|{}",
+            self.version, self.kdf, self.memory_kib, self.iterations, self.parallelism, self.salt
+        )
+        .into_bytes()
+    }
+
+    fn derive_key(&self, passphrase: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        use argon2::{Algorithm, Argon2, Params, Version};
+
+        let params = Params::new(self.memory_kib, self.iterations, self.parallelism, Some(32))
+            .map_err(|e| EventualiError::Encryption(format!("Invalid key wrapping parameters: {e}")))?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+            .map_err(|e| EventualiError::Encryption(format!("Key derivation failed: {e}")))?;
+        Ok(key)
+    }
+}
+
+/// Plaintext contents of a wrapped key set
+#[derive(Serialize, Deserialize)]
+struct KeySetContents {
+    default_key_id: String,
+    keys: Vec<EncryptionKey>,
+}
+
 /// Encryption key with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptionKey {
     pub id: String,
-    pub key_data: Vec<u8>, // 32 bytes for AES-256
+    /// 32 bytes for either algorithm, zeroed on drop and redacted from `Debug`
+    pub key_data: KeyMaterial,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub algorithm: EncryptionAlgorithm,
 }
@@ -29,8 +88,60 @@ pub struct EncryptionKey {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EncryptionAlgorithm {
     Aes256Gcm,
+    /// ChaCha20-Poly1305 (RFC 8439), constant-time in software and faster
+    /// than AES-GCM on CPUs without AES instructions
+    ChaCha20Poly1305,
 }
 
+impl EncryptionAlgorithm {
+    /// Name used in error messages and by the Python bindings
+    pub fn name(&self) -> &'static str {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => "AES-256-GCM",
+            EncryptionAlgorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        }
+    }
+
+    /// Key length, in bytes
+    pub fn key_len(&self) -> usize {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => AES_256_KEY_LEN,
+            EncryptionAlgorithm::ChaCha20Poly1305 => CHACHA20_KEY_LEN,
+        }
+    }
+
+    /// Nonce length, in bytes
+    pub fn iv_len(&self) -> usize {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => AES_GCM_IV_LEN,
+            EncryptionAlgorithm::ChaCha20Poly1305 => CHACHA20_POLY1305_NONCE_LEN,
+        }
+    }
+
+    /// Authentication tag length, in bytes
+    pub fn tag_len(&self) -> usize {
+        match self {
+            EncryptionAlgorithm::Aes256Gcm => AES_GCM_TAG_LEN,
+            EncryptionAlgorithm::ChaCha20Poly1305 => CHACHA20_POLY1305_TAG_LEN,
+        }
+    }
+}
+
+/// Length of an AES-256-GCM nonce, in bytes
+pub const AES_GCM_IV_LEN: usize = 12;
+/// Length of an AES-256-GCM authentication tag, in bytes
+pub const AES_GCM_TAG_LEN: usize = 16;
+/// Length of an AES-256 key, in bytes
+pub const AES_256_KEY_LEN: usize = 32;
+/// Length of a ChaCha20-Poly1305 nonce, in bytes
+pub const CHACHA20_POLY1305_NONCE_LEN: usize = 12;
+/// Length of a ChaCha20-Poly1305 authentication tag, in bytes
+pub const CHACHA20_POLY1305_TAG_LEN: usize = 16;
+/// Length of a ChaCha20 key, in bytes
+pub const CHACHA20_KEY_LEN: usize = 32;
+/// Longest key ID accepted when parsing stored ciphertext
+pub const MAX_KEY_ID_LEN: usize = 256;
+
 /// Encrypted event data with metadata
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EncryptedEventData {
@@ -48,11 +159,11 @@ impl EventEncryption {
     }
 
     /// Create a new encryption instance with a single key
-    pub fn with_key(key_id: String, key_data: Vec<u8>) -> Result<Self> {
+    pub fn with_key(key_id: String, key_data: impl Into<KeyMaterial>) -> Result<Self> {
         let mut keys = HashMap::new();
         let encryption_key = EncryptionKey {
             id: key_id.clone(),
-            key_data,
+            key_data: key_data.into(),
             created_at: chrono::Utc::now(),
             algorithm: EncryptionAlgorithm::Aes256Gcm,
         };
@@ -71,19 +182,18 @@ impl EventEncryption {
         self.encrypt_event_data_with_key(data, &self.key_manager.default_key_id)
     }
 
-    /// Encrypt event data using a specific key
+    /// Encrypt event data using a specific key, with that key's algorithm
     pub fn encrypt_event_data_with_key(&self, data: &EventData, key_id: &str) -> Result<EncryptedEventData> {
         let key = self.key_manager.get_key(key_id)?;
         let plaintext = self.serialize_event_data(data)?;
         
-        // Generate random IV (12 bytes for GCM)
-        let iv = self.generate_iv()?;
+        // Both algorithms take a random 12-byte IV
+        let iv = self.generate_iv();
         
-        // Encrypt using AES-256-GCM
-        let (encrypted_data, tag) = self.encrypt_aes_256_gcm(&plaintext, &key.key_data, &iv)?;
+        let (encrypted_data, tag) = self.encrypt_with_key(&plaintext, key, &iv)?;
         
         Ok(EncryptedEventData {
-            algorithm: EncryptionAlgorithm::Aes256Gcm,
+            algorithm: key.algorithm.clone(),
             key_id: key_id.to_string(),
             iv,
             encrypted_data,
@@ -91,21 +201,80 @@ impl EventEncryption {
         })
     }
 
-    /// Decrypt event data
+    /// Decrypt event data.
+    ///
+    /// The key named in the payload is tried first; if it is missing or fails
+    /// authentication, every other held key is tried newest first, so data
+    /// written on either side of a key rotation stays readable.
     pub fn decrypt_event_data(&self, encrypted_data: &EncryptedEventData) -> Result<EventData> {
-        let key = self.key_manager.get_key(&encrypted_data.key_id)?;
-        
-        match encrypted_data.algorithm {
-            EncryptionAlgorithm::Aes256Gcm => {
-                let plaintext = self.decrypt_aes_256_gcm(
-                    &encrypted_data.encrypted_data,
-                    &key.key_data,
-                    &encrypted_data.iv,
-                    &encrypted_data.tag,
-                )?;
-                self.deserialize_event_data(&plaintext)
+        let named_key = self.key_manager.keys.get(&encrypted_data.key_id);
+        let mut fallback_keys: Vec<&EncryptionKey> = self
+            .key_manager
+            .keys
+            .values()
+            .filter(|key| key.id != encrypted_data.key_id)
+            .collect();
+        fallback_keys.sort_by_key(|key| std::cmp::Reverse(key.created_at));
+
+        let mut keys_tried = 0;
+        for key in named_key.into_iter().chain(fallback_keys) {
+            keys_tried += 1;
+            if let Ok(plaintext) = self.decrypt_with_key(encrypted_data, key) {
+                return self.deserialize_event_data(&plaintext);
             }
         }
+
+        Err(EventualiError::AuthenticationFailed {
+            key_id: encrypted_data.key_id.clone(),
+            keys_tried,
+        })
+    }
+
+    /// Re-encrypt data under `target_key_id`, decrypting it with the key its
+    /// `key_id` names.
+    ///
+    /// Used to migrate ciphertext to a new key after `KeyManager::rotate_to`,
+    /// one payload at a time. The plaintext is re-encrypted byte for byte under
+    /// a fresh IV and never leaves this call.
+    pub fn reencrypt(&self, data: &EncryptedEventData, target_key_id: &str) -> Result<EncryptedEventData> {
+        let source = self.key_manager.get_key(&data.key_id)?;
+        let target = self.key_manager.get_key(target_key_id)?;
+        let plaintext = Zeroizing::new(self.decrypt_with_key(data, source)?);
+
+        let iv = self.generate_iv();
+        let (encrypted_data, tag) = self.encrypt_with_key(&plaintext, target, &iv)?;
+        Ok(EncryptedEventData {
+            algorithm: target.algorithm.clone(),
+            key_id: target.id.clone(),
+            iv,
+            encrypted_data,
+            tag,
+        })
+    }
+
+    fn encrypt_with_key(&self, plaintext: &[u8], key: &EncryptionKey, iv: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        match key.algorithm {
+            EncryptionAlgorithm::Aes256Gcm => self.encrypt_aes_256_gcm(plaintext, &key.key_data, iv),
+            EncryptionAlgorithm::ChaCha20Poly1305 => self.encrypt_chacha20_poly1305(plaintext, &key.key_data, iv),
+        }
+    }
+
+    /// A key is only ever used with the algorithm it was created for, so the
+    /// same key bytes never serve as both an AES and a ChaCha20 key
+    fn decrypt_with_key(&self, encrypted_data: &EncryptedEventData, key: &EncryptionKey) -> Result<Vec<u8>> {
+        if key.algorithm != encrypted_data.algorithm {
+            return Err(EventualiError::Encryption(format!(
+                "Key {} is a {} key, but the data was encrypted with {}",
+                key.id,
+                key.algorithm.name(),
+                encrypted_data.algorithm.name()
+            )));
+        }
+        let (ciphertext, iv, tag) = (&encrypted_data.encrypted_data, &encrypted_data.iv, &encrypted_data.tag);
+        match encrypted_data.algorithm {
+            EncryptionAlgorithm::Aes256Gcm => self.decrypt_aes_256_gcm(ciphertext, &key.key_data, iv, tag),
+            EncryptionAlgorithm::ChaCha20Poly1305 => self.decrypt_chacha20_poly1305(ciphertext, &key.key_data, iv, tag),
+        }
     }
 
     /// Serialize event data to bytes for encryption
@@ -131,24 +300,18 @@ impl EventEncryption {
         Ok(EventData::Protobuf(bytes.to_vec()))
     }
 
-    /// Generate a random IV for AES-GCM
-    fn generate_iv(&self) -> Result<Vec<u8>> {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
-        // Generate a 12-byte IV using system time and random data
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(|e| EventualiError::Encryption(format!("Time error: {e}")))?
-            .as_nanos() as u64;
-        
-        let mut iv = Vec::with_capacity(12);
-        iv.extend_from_slice(&timestamp.to_be_bytes());
-        
-        // Add 4 more random bytes using a simple PRNG
-        let random_seed = (timestamp.wrapping_mul(1103515245).wrapping_add(12345)) % (1u64 << 31);
-        iv.extend_from_slice(&(random_seed as u32).to_be_bytes());
-        
-        Ok(iv)
+    /// Generate a fresh 96-bit IV for AES-GCM or ChaCha20-Poly1305.
+    ///
+    /// Every IV is drawn from the operating system CSPRNG (`OsRng`) and never
+    /// derived from time, a counter or previous IVs. Reusing an IV under the
+    /// same key lets an attacker recover the XOR of plaintexts and forge tags,
+    /// so each encryption must get its own. With random 96-bit IVs, a key
+    /// stays within NIST's 2^-32 collision bound for 2^32 encryptions.
+    fn generate_iv(&self) -> Vec<u8> {
+        use aes_gcm::aead::{AeadCore, OsRng};
+        use aes_gcm::Aes256Gcm;
+
+        Aes256Gcm::generate_nonce(&mut OsRng).to_vec()
     }
 
     /// Encrypt data using AES-256-GCM
@@ -156,6 +319,7 @@ impl EventEncryption {
         use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
         use aes_gcm::aead::{Aead, generic_array::GenericArray};
         
+        check_lengths(&EncryptionAlgorithm::Aes256Gcm, key, iv)?;
         let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
         let nonce = Nonce::from_slice(iv);
         
@@ -176,6 +340,8 @@ impl EventEncryption {
         use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
         use aes_gcm::aead::{Aead, generic_array::GenericArray};
         
+        check_lengths(&EncryptionAlgorithm::Aes256Gcm, key, iv)?;
+        check_tag_length(&EncryptionAlgorithm::Aes256Gcm, tag)?;
         let cipher = Aes256Gcm::new(GenericArray::from_slice(key));
         let nonce = Nonce::from_slice(iv);
         
@@ -189,6 +355,75 @@ impl EventEncryption {
         
         Ok(plaintext)
     }
+
+    /// Encrypt data using ChaCha20-Poly1305
+    fn encrypt_chacha20_poly1305(&self, plaintext: &[u8], key: &[u8], iv: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+        use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+        use chacha20poly1305::aead::{Aead, generic_array::GenericArray};
+
+        check_lengths(&EncryptionAlgorithm::ChaCha20Poly1305, key, iv)?;
+        let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(key));
+        let nonce = Nonce::from_slice(iv);
+
+        let mut encrypted_data = cipher
+            .encrypt(nonce, plaintext)
+            .map_err(|e| EventualiError::Encryption(format!("ChaCha20-Poly1305 encryption failed: {e}")))?;
+
+        // The tag is appended after the ciphertext
+        let tag = encrypted_data.split_off(encrypted_data.len() - CHACHA20_POLY1305_TAG_LEN);
+        Ok((encrypted_data, tag))
+    }
+
+    /// Decrypt data using ChaCha20-Poly1305
+    fn decrypt_chacha20_poly1305(&self, ciphertext: &[u8], key: &[u8], iv: &[u8], tag: &[u8]) -> Result<Vec<u8>> {
+        use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
+        use chacha20poly1305::aead::{Aead, generic_array::GenericArray};
+
+        check_lengths(&EncryptionAlgorithm::ChaCha20Poly1305, key, iv)?;
+        check_tag_length(&EncryptionAlgorithm::ChaCha20Poly1305, tag)?;
+        let cipher = ChaCha20Poly1305::new(GenericArray::from_slice(key));
+        let nonce = Nonce::from_slice(iv);
+
+        let mut full_ciphertext = ciphertext.to_vec();
+        full_ciphertext.extend_from_slice(tag);
+
+        cipher
+            .decrypt(nonce, full_ciphertext.as_ref())
+            .map_err(|e| EventualiError::Encryption(format!("ChaCha20-Poly1305 decryption failed: {e}")))
+    }
+}
+
+/// `from_slice` panics on the wrong length, so check before building the cipher
+fn check_lengths(algorithm: &EncryptionAlgorithm, key: &[u8], iv: &[u8]) -> Result<()> {
+    if key.len() != algorithm.key_len() {
+        return Err(EventualiError::Encryption(format!(
+            "{} key must be {} bytes, got {}",
+            algorithm.name(),
+            algorithm.key_len(),
+            key.len()
+        )));
+    }
+    if iv.len() != algorithm.iv_len() {
+        return Err(EventualiError::Encryption(format!(
+            "{} IV must be {} bytes, got {}",
+            algorithm.name(),
+            algorithm.iv_len(),
+            iv.len()
+        )));
+    }
+    Ok(())
+}
+
+fn check_tag_length(algorithm: &EncryptionAlgorithm, tag: &[u8]) -> Result<()> {
+    if tag.len() != algorithm.tag_len() {
+        return Err(EventualiError::Encryption(format!(
+            "{} tag must be {} bytes, got {}",
+            algorithm.name(),
+            algorithm.tag_len(),
+            tag.len()
+        )));
+    }
+    Ok(())
 }
 
 impl KeyManager {
@@ -202,10 +437,12 @@ impl KeyManager {
 
     /// Add a key to the manager
     pub fn add_key(&mut self, key: EncryptionKey) -> Result<()> {
-        if key.key_data.len() != 32 {
-            return Err(EventualiError::Encryption(
-                "AES-256 requires 32-byte keys".to_string()
-            ));
+        if key.key_data.len() != key.algorithm.key_len() {
+            return Err(EventualiError::Encryption(format!(
+                "{} requires {}-byte keys",
+                key.algorithm.name(),
+                key.algorithm.key_len()
+            )));
         }
         
         if self.keys.is_empty() {
@@ -218,12 +455,17 @@ impl KeyManager {
 
     /// Generate a new AES-256 key
     pub fn generate_key(id: String) -> Result<EncryptionKey> {
+        Self::generate_key_with_algorithm(id, EncryptionAlgorithm::Aes256Gcm)
+    }
+
+    /// Generate a new key for `algorithm`
+    pub fn generate_key_with_algorithm(id: String, algorithm: EncryptionAlgorithm) -> Result<EncryptionKey> {
         let key_data = Self::generate_random_key()?;
         Ok(EncryptionKey {
             id,
-            key_data,
+            key_data: key_data.into(),
             created_at: chrono::Utc::now(),
-            algorithm: EncryptionAlgorithm::Aes256Gcm,
+            algorithm,
         })
     }
 
@@ -232,12 +474,12 @@ impl KeyManager {
         use pbkdf2::{pbkdf2_hmac};
         use sha2::Sha256;
         
-        let mut key_data = [0u8; 32];
+        let mut key_data = KeyMaterial::zeroed(AES_256_KEY_LEN);
         pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, 100_000, &mut key_data);
         
         Ok(EncryptionKey {
             id,
-            key_data: key_data.to_vec(),
+            key_data,
             created_at: chrono::Utc::now(),
             algorithm: EncryptionAlgorithm::Aes256Gcm,
         })
@@ -261,6 +503,133 @@ impl KeyManager {
         Ok(())
     }
 
+    /// Make `new_key_id` the key new data is encrypted with. Earlier keys stay
+    /// held, so data encrypted under them keeps decrypting until it is moved
+    /// over with `EventEncryption::reencrypt`.
+    pub fn rotate_to(&mut self, new_key_id: &str) -> Result<()> {
+        self.set_default_key(new_key_id)
+    }
+
+    /// IDs of every key held, sorted
+    pub fn list_key_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.keys.keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+
+    /// ID of the key new data is encrypted with, once any key is held
+    pub fn default_key_id(&self) -> Option<String> {
+        Some(self.default_key_id.clone()).filter(|id| !id.is_empty())
+    }
+
+    /// Export every key, encrypted under a passphrase, as a portable base64 blob.
+    ///
+    /// The wrapping key is derived with Argon2id and the key set is sealed with
+    /// AES-256-GCM, so the blob can be escrowed or moved between environments
+    /// without exposing raw key material.
+    pub fn export_wrapped(&self, passphrase: &str) -> Result<String> {
+        use aes_gcm::aead::{Aead, AeadCore, OsRng, Payload, rand_core::RngCore};
+        use aes_gcm::{Aes256Gcm, KeyInit};
+
+        if passphrase.is_empty() {
+            return Err(EventualiError::Encryption("Key export requires a non-empty passphrase".to_string()));
+        }
+
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+        let mut keys: Vec<EncryptionKey> = self.keys.values().cloned().collect();
+        keys.sort_by(|a, b| a.id.cmp(&b.id));
+        let plaintext = Zeroizing::new(serde_json::to_vec(&KeySetContents {
+            default_key_id: self.default_key_id.clone(),
+            keys,
+        })?);
+
+        let mut wrapped = WrappedKeySet {
+            version: WrappedKeySet::VERSION,
+            kdf: WrappedKeySet::KDF.to_string(),
+            memory_kib: WrappedKeySet::MEMORY_KIB,
+            iterations: WrappedKeySet::ITERATIONS,
+            parallelism: WrappedKeySet::PARALLELISM,
+            salt: general_purpose::STANDARD.encode(salt),
+            nonce: general_purpose::STANDARD.encode(nonce),
+            ciphertext: String::new(),
+        };
+
+        let wrapping_key = wrapped.derive_key(passphrase, &salt)?;
+        let sealed = Aes256Gcm::new(&(*wrapping_key).into()).encrypt(
+            &nonce,
+            Payload { msg: &plaintext, aad: &wrapped.associated_data() },
+        );
+
+        let ciphertext = sealed.map_err(|_| EventualiError::Encryption("Failed to wrap key set".to_string()))?;
+        wrapped.ciphertext = general_purpose::STANDARD.encode(ciphertext);
+
+        Ok(general_purpose::STANDARD.encode(serde_json::to_vec(&wrapped)?))
+    }
+
+    /// Import keys from a blob produced by `export_wrapped`.
+    ///
+    /// Imported keys replace existing keys with the same ID. The blob's default
+    /// key becomes the default only if this manager had no keys before.
+    pub fn import_wrapped(&mut self, blob: &str, passphrase: &str) -> Result<()> {
+        use aes_gcm::aead::{Aead, Payload};
+        use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+
+        let decode = |field: &str, value: &str| {
+            general_purpose::STANDARD
+                .decode(value)
+                .map_err(|_| EventualiError::Encryption(format!("Wrapped key set has an invalid {field}")))
+        };
+
+        let wrapped: WrappedKeySet = serde_json::from_slice(&decode("encoding", blob)?)
+            .map_err(|_| EventualiError::Encryption("Wrapped key set is malformed".to_string()))?;
+        if wrapped.version != WrappedKeySet::VERSION || wrapped.kdf != WrappedKeySet::KDF {
+            return Err(EventualiError::Encryption(format!(
+                "Unsupported wrapped key set (version {}, kdf {})",
+                wrapped.version, wrapped.kdf
+            )));
+        }
+        if wrapped.memory_kib > WrappedKeySet::MAX_MEMORY_KIB
+            || wrapped.iterations > WrappedKeySet::MAX_ITERATIONS
+            || wrapped.parallelism > WrappedKeySet::MAX_PARALLELISM
+        {
+            return Err(EventualiError::Encryption(format!(
+                "Wrapped key set asks for KDF parameters above the supported maximum \
+                 (memory {} KiB, {} iterations, parallelism {})",
+                wrapped.memory_kib, wrapped.iterations, wrapped.parallelism
+            )));
+        }
+
+        let salt = decode("salt", &wrapped.salt)?;
+        let nonce = decode("nonce", &wrapped.nonce)?;
+        let ciphertext = decode("ciphertext", &wrapped.ciphertext)?;
+        if nonce.len() != 12 {
+            return Err(EventualiError::Encryption("Wrapped key set has an invalid nonce".to_string()));
+        }
+
+        let wrapping_key = wrapped.derive_key(passphrase, &salt)?;
+        let opened = Aes256Gcm::new(&(*wrapping_key).into()).decrypt(
+            Nonce::from_slice(&nonce),
+            Payload { msg: &ciphertext, aad: &wrapped.associated_data() },
+        );
+
+        let plaintext = Zeroizing::new(opened.map_err(|_| {
+            EventualiError::Encryption("Failed to unwrap key set: wrong passphrase or corrupted blob".to_string())
+        })?);
+        let contents: KeySetContents = serde_json::from_slice(&plaintext)?;
+
+        let was_empty = self.keys.is_empty();
+        for key in contents.keys {
+            self.add_key(key)?;
+        }
+        if was_empty && self.keys.contains_key(&contents.default_key_id) {
+            self.default_key_id = contents.default_key_id;
+        }
+        Ok(())
+    }
+
     /// Generate a cryptographically secure random 32-byte key
     fn generate_random_key() -> Result<Vec<u8>> {
         use std::time::{SystemTime, UNIX_EPOCH};
@@ -304,14 +673,39 @@ impl EncryptedEventData {
         general_purpose::STANDARD.encode(serialized)
     }
 
-    /// Deserialize from base64 string
+    /// Deserialize from base64 string.
+    ///
+    /// Stored ciphertext is untrusted input, so malformed or adversarial data is
+    /// rejected with an error here rather than reaching the cipher.
     pub fn from_base64(data: &str) -> Result<Self> {
         let bytes = general_purpose::STANDARD
             .decode(data)
             .map_err(|e| EventualiError::Encryption(format!("Base64 decode error: {e}")))?;
         
-        serde_json::from_slice(&bytes)
-            .map_err(EventualiError::from)
+        let parsed: Self = serde_json::from_slice(&bytes)
+            .map_err(|e| EventualiError::Encryption(format!("Malformed encrypted data: {e}")))?;
+        parsed.validate()?;
+        Ok(parsed)
+    }
+
+    /// Check field lengths against what the algorithm requires
+    pub fn validate(&self) -> Result<()> {
+        if self.key_id.is_empty() || self.key_id.len() > MAX_KEY_ID_LEN {
+            return Err(EventualiError::Encryption(format!(
+                "Encrypted data key ID must be 1 to {MAX_KEY_ID_LEN} bytes, got {}",
+                self.key_id.len()
+            )));
+        }
+
+        if self.iv.len() != self.algorithm.iv_len() {
+            return Err(EventualiError::Encryption(format!(
+                "{} IV must be {} bytes, got {}",
+                self.algorithm.name(),
+                self.algorithm.iv_len(),
+                self.iv.len()
+            )));
+        }
+        check_tag_length(&self.algorithm, &self.tag)
     }
 }
 
@@ -320,6 +714,21 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_debug_never_prints_key_bytes() {
+        let key = EncryptionKey {
+            id: "debug-key".to_string(),
+            key_data: vec![0xC3; 32].into(),
+            created_at: chrono::Utc::now(),
+            algorithm: EncryptionAlgorithm::Aes256Gcm,
+        };
+        let mut manager = KeyManager::new();
+        manager.add_key(key.clone()).unwrap();
+        let printed = format!("{key:?} {manager:?}");
+        assert!(printed.contains("debug-key") && printed.contains("REDACTED"));
+        assert!(!printed.contains("195"), "key bytes leaked: {printed}");
+    }
+
     #[test]
     fn test_key_generation() {
         let key = KeyManager::generate_key("test-key".to_string()).unwrap();
@@ -371,6 +780,28 @@ mod tests {
         assert_eq!(original_data, decrypted);
     }
 
+    #[test]
+    fn test_repeated_encryption_never_reuses_an_iv() {
+        let key = KeyManager::generate_key("test-key".to_string()).unwrap();
+        let encryption = EventEncryption::with_key("test-key".to_string(), key.key_data).unwrap();
+        let data = EventData::Json(json!({"card": "4111111111111111"}));
+
+        // Encrypting back to back puts many calls in the same clock tick, where
+        // a time- or counter-derived IV would repeat
+        let encryptions = 10_000;
+        let mut ivs = std::collections::HashSet::new();
+        let mut ciphertexts = std::collections::HashSet::new();
+        for _ in 0..encryptions {
+            let encrypted = encryption.encrypt_event_data(&data).unwrap();
+            assert_eq!(encrypted.iv.len(), AES_GCM_IV_LEN);
+            ivs.insert(encrypted.iv);
+            ciphertexts.insert(encrypted.encrypted_data);
+        }
+
+        assert_eq!(ivs.len(), encryptions);
+        assert_eq!(ciphertexts.len(), encryptions);
+    }
+
     #[test]
     fn test_protobuf_encryption_decryption() {
         let key = KeyManager::generate_key("test-key".to_string()).unwrap();
@@ -384,6 +815,72 @@ mod tests {
         assert_eq!(original_data, decrypted);
     }
 
+    #[test]
+    fn test_round_trip_with_each_algorithm() {
+        let json = EventData::Json(json!({"user_id": "user123", "amount": 100.50}));
+        let protobuf = EventData::Protobuf(vec![0x08, 0x96, 0x01]);
+
+        for algorithm in [EncryptionAlgorithm::Aes256Gcm, EncryptionAlgorithm::ChaCha20Poly1305] {
+            let key = KeyManager::generate_key_with_algorithm("key".to_string(), algorithm.clone()).unwrap();
+            let mut key_manager = KeyManager::new();
+            key_manager.add_key(key).unwrap();
+            let encryption = EventEncryption::new(key_manager);
+
+            for data in [&json, &protobuf] {
+                let encrypted = encryption.encrypt_event_data(data).unwrap();
+                assert_eq!(encrypted.algorithm, algorithm);
+                assert_eq!(encrypted.iv.len(), algorithm.iv_len());
+                assert_eq!(encrypted.tag.len(), algorithm.tag_len());
+
+                let stored = EncryptedEventData::from_base64(&encrypted.to_base64()).unwrap();
+                assert_eq!(&encryption.decrypt_event_data(&stored).unwrap(), data);
+
+                let mut tampered = encrypted.clone();
+                tampered.encrypted_data[0] ^= 1;
+                assert!(encryption.decrypt_event_data(&tampered).is_err(), "{} accepted a forgery", algorithm.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_ciphertext_never_decrypts_under_the_other_algorithm() {
+        let aes = KeyManager::generate_key("aes".to_string()).unwrap();
+        let mut chacha = aes.clone();
+        chacha.id = "chacha".to_string();
+        chacha.algorithm = EncryptionAlgorithm::ChaCha20Poly1305;
+
+        let mut key_manager = KeyManager::new();
+        key_manager.add_key(aes).unwrap();
+        key_manager.add_key(chacha).unwrap();
+        let encryption = EventEncryption::new(key_manager);
+        let data = EventData::Json(json!({"card": "4111111111111111"}));
+
+        let from_aes = encryption.encrypt_event_data_with_key(&data, "aes").unwrap();
+        let from_chacha = encryption.encrypt_event_data_with_key(&data, "chacha").unwrap();
+        assert_eq!(from_chacha.algorithm, EncryptionAlgorithm::ChaCha20Poly1305);
+        assert_eq!(encryption.decrypt_event_data(&from_aes).unwrap(), data);
+        assert_eq!(encryption.decrypt_event_data(&from_chacha).unwrap(), data);
+
+        // Relabelling the algorithm, even with the same key bytes held under
+        // both, must fail authentication rather than decrypt
+        for (payload, other) in [
+            (&from_aes, EncryptionAlgorithm::ChaCha20Poly1305),
+            (&from_chacha, EncryptionAlgorithm::Aes256Gcm),
+        ] {
+            let mut relabelled = payload.clone();
+            relabelled.algorithm = other;
+            assert!(matches!(
+                encryption.decrypt_event_data(&relabelled),
+                Err(EventualiError::AuthenticationFailed { keys_tried: 2, .. })
+            ));
+        }
+
+        // Re-encrypting moves data to the target key's algorithm
+        let moved = encryption.reencrypt(&from_aes, "chacha").unwrap();
+        assert_eq!(moved.algorithm, EncryptionAlgorithm::ChaCha20Poly1305);
+        assert_eq!(encryption.decrypt_event_data(&moved).unwrap(), data);
+    }
+
     #[test]
     fn test_multiple_keys() {
         let mut key_manager = KeyManager::new();
@@ -410,6 +907,161 @@ mod tests {
         assert_eq!(data, decrypted2);
     }
 
+    #[test]
+    fn test_mixed_keys_decrypt_during_rotation() {
+        let old_key = KeyManager::generate_key("key-2023".to_string()).unwrap();
+        let mut new_key = KeyManager::generate_key("key-2024".to_string()).unwrap();
+        new_key.created_at = old_key.created_at + chrono::Duration::days(365);
+
+        let mut key_manager = KeyManager::new();
+        key_manager.add_key(old_key.clone()).unwrap();
+        key_manager.add_key(new_key.clone()).unwrap();
+        let mut encryption = EventEncryption::new(key_manager.clone());
+
+        let mut encrypted = Vec::new();
+        for i in 0..6 {
+            let data = EventData::Json(json!({ "seq": i }));
+            encrypted.push((data.clone(), encryption.encrypt_event_data(&data).unwrap()));
+            if i == 2 {
+                key_manager.set_default_key("key-2024").unwrap();
+                encryption = EventEncryption::new(key_manager.clone());
+            }
+        }
+        assert_eq!(encrypted[0].1.key_id, "key-2023");
+        assert_eq!(encrypted[5].1.key_id, "key-2024");
+
+        // A payload labelled with the wrong key still decrypts via the fallback
+        let mut mislabelled = encrypted[0].1.clone();
+        mislabelled.key_id = "key-2024".to_string();
+        encrypted.push((encrypted[0].0.clone(), mislabelled));
+
+        for (data, payload) in &encrypted {
+            assert_eq!(&encryption.decrypt_event_data(payload).unwrap(), data);
+        }
+
+        // Once no held key matches, reads fail with a clear error
+        let stranger = EventEncryption::new({
+            let mut manager = KeyManager::new();
+            manager.add_key(KeyManager::generate_key("other".to_string()).unwrap()).unwrap();
+            manager
+        });
+        match stranger.decrypt_event_data(&encrypted[0].1) {
+            Err(EventualiError::AuthenticationFailed { key_id, keys_tried }) => {
+                assert_eq!(key_id, "key-2023");
+                assert_eq!(keys_tried, 1);
+            }
+            other => panic!("expected AuthenticationFailed, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_key_ids_are_listed_sorted_with_the_default() {
+        let mut manager = KeyManager::new();
+        assert!(manager.list_key_ids().is_empty());
+        assert_eq!(manager.default_key_id(), None);
+
+        for id in ["key-2025", "key-2023", "key-2024"] {
+            manager.add_key(KeyManager::generate_key(id.to_string()).unwrap()).unwrap();
+        }
+        assert_eq!(manager.list_key_ids(), vec!["key-2023", "key-2024", "key-2025"]);
+        assert_eq!(manager.default_key_id().as_deref(), Some("key-2025"));
+
+        manager.set_default_key("key-2024").unwrap();
+        assert_eq!(manager.default_key_id().as_deref(), Some("key-2024"));
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_ciphertext_readable_until_reencrypted() {
+        let mut key_manager = KeyManager::new();
+        key_manager.add_key(KeyManager::generate_key("key-1".to_string()).unwrap()).unwrap();
+        let data = EventData::Json(json!({ "account": "acc-1", "balance": 120 }));
+        let old = EventEncryption::new(key_manager.clone()).encrypt_event_data(&data).unwrap();
+
+        let new_key = KeyManager::generate_key("key-2".to_string()).unwrap();
+        key_manager.add_key(new_key.clone()).unwrap();
+        assert!(key_manager.rotate_to("key-3").is_err());
+        key_manager.rotate_to("key-2").unwrap();
+        assert_eq!(key_manager.default_key_id().as_deref(), Some("key-2"));
+
+        let encryption = EventEncryption::new(key_manager);
+        assert_eq!(encryption.encrypt_event_data(&data).unwrap().key_id, "key-2");
+        assert_eq!(encryption.decrypt_event_data(&old).unwrap(), data);
+
+        let moved = encryption.reencrypt(&old, "key-2").unwrap();
+        assert_eq!(moved.key_id, "key-2");
+        assert_ne!(moved.iv, old.iv);
+        assert_eq!(encryption.decrypt_event_data(&moved).unwrap(), data);
+        assert!(encryption.reencrypt(&old, "key-3").is_err());
+
+        // Once migrated, the data no longer needs the retired key
+        let retired = EventEncryption::new({
+            let mut manager = KeyManager::new();
+            manager.add_key(new_key).unwrap();
+            manager
+        });
+        assert_eq!(retired.decrypt_event_data(&moved).unwrap(), data);
+        assert!(retired.decrypt_event_data(&old).is_err());
+        assert!(retired.reencrypt(&old, "key-2").is_err());
+    }
+
+    #[test]
+    fn test_wrapped_key_export_round_trip() {
+        let mut source = KeyManager::new();
+        source.add_key(KeyManager::generate_key("key1".to_string()).unwrap()).unwrap();
+        source.add_key(KeyManager::generate_key("key2".to_string()).unwrap()).unwrap();
+        source.set_default_key("key2").unwrap();
+
+        let blob = source.export_wrapped("correct horse battery staple").unwrap();
+        assert!(!blob.contains("key_data"));
+
+        let mut restored = KeyManager::new();
+        restored.import_wrapped(&blob, "correct horse battery staple").unwrap();
+
+        for id in ["key1", "key2"] {
+            assert_eq!(restored.get_key(id).unwrap().key_data, source.get_key(id).unwrap().key_data);
+        }
+        assert_eq!(restored.default_key_id, "key2");
+
+        // Data encrypted before export decrypts with the restored keys
+        let data = EventData::Json(json!({"test": "data"}));
+        let encrypted = EventEncryption::new(source).encrypt_event_data(&data).unwrap();
+        let decrypted = EventEncryption::new(restored).decrypt_event_data(&encrypted).unwrap();
+        assert_eq!(decrypted, data);
+    }
+
+    #[test]
+    fn test_wrapped_key_import_rejects_wrong_passphrase() {
+        let mut source = KeyManager::new();
+        source.add_key(KeyManager::generate_key("key1".to_string()).unwrap()).unwrap();
+        let blob = source.export_wrapped("right passphrase").unwrap();
+
+        let mut restored = KeyManager::new();
+        let result = restored.import_wrapped(&blob, "wrong passphrase");
+        assert!(matches!(result, Err(EventualiError::Encryption(_))));
+        assert!(restored.get_key("key1").is_err());
+    }
+
+    #[test]
+    fn test_wrapped_key_import_rejects_oversized_kdf_parameters() {
+        let mut source = KeyManager::new();
+        source.add_key(KeyManager::generate_key("key1".to_string()).unwrap()).unwrap();
+        let blob = source.export_wrapped("passphrase").unwrap();
+        let wrapped: WrappedKeySet =
+            serde_json::from_slice(&general_purpose::STANDARD.decode(&blob).unwrap()).unwrap();
+
+        let crafted = [
+            WrappedKeySet { memory_kib: 4 * 1024 * 1024, ..wrapped.clone() },
+            WrappedKeySet { iterations: 1_000_000, ..wrapped.clone() },
+            WrappedKeySet { parallelism: 1024, ..wrapped },
+        ];
+        for crafted in crafted {
+            let blob = general_purpose::STANDARD.encode(serde_json::to_vec(&crafted).unwrap());
+            let mut restored = KeyManager::new();
+            let err = restored.import_wrapped(&blob, "passphrase").unwrap_err();
+            assert!(err.to_string().contains("supported maximum"), "{err}");
+        }
+    }
+
     #[test]
     fn test_base64_serialization() {
         let key = KeyManager::generate_key("test-key".to_string()).unwrap();
@@ -427,4 +1079,90 @@ mod tests {
         let decrypted = encryption.decrypt_event_data(&deserialized).unwrap();
         assert_eq!(data, decrypted);
     }
-}
\ No newline at end of file
+
+    fn sample_encryption() -> (EventEncryption, EncryptedEventData) {
+        let key = KeyManager::generate_key("fuzz-key".to_string()).unwrap();
+        let encryption = EventEncryption::with_key("fuzz-key".to_string(), key.key_data).unwrap();
+        let encrypted = encryption
+            .encrypt_event_data(&EventData::Json(json!({"account": 42})))
+            .unwrap();
+        (encryption, encrypted)
+    }
+
+    fn encode_json(value: serde_json::Value) -> String {
+        general_purpose::STANDARD.encode(serde_json::to_vec(&value).unwrap())
+    }
+
+    #[test]
+    fn test_malformed_encrypted_data_is_rejected_without_panicking() {
+        let (encryption, encrypted) = sample_encryption();
+        let valid = serde_json::to_value(&encrypted).unwrap();
+
+        // Inputs that used to parse and then panic inside `Nonce::from_slice`, or
+        // that are otherwise malformed
+        let mut cases = vec![
+            String::new(),
+            "not base64!".to_string(),
+            general_purpose::STANDARD.encode(b"{\"algorithm\":"),
+            encode_json(json!([])),
+        ];
+        for (field, bad) in [
+            ("iv", json!([])),
+            ("iv", json!(vec![0u8; 200])),
+            ("tag", json!([1, 2, 3])),
+            ("key_id", json!("")),
+            ("key_id", json!("k".repeat(MAX_KEY_ID_LEN + 1))),
+            ("algorithm", json!("Rot13")),
+        ] {
+            let mut tampered = valid.clone();
+            tampered[field] = bad;
+            cases.push(encode_json(tampered));
+        }
+
+        for case in cases {
+            let result = EncryptedEventData::from_base64(&case);
+            assert!(matches!(result, Err(EventualiError::Encryption(_))), "accepted {case:?}");
+        }
+
+        // Structs built directly skip `from_base64`, so decryption checks too
+        let mut short_iv = encrypted.clone();
+        short_iv.iv.truncate(3);
+        assert!(encryption.decrypt_event_data(&short_iv).is_err());
+        let mut long_tag = encrypted;
+        long_tag.tag.extend_from_slice(&[0; 8]);
+        assert!(encryption.decrypt_event_data(&long_tag).is_err());
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_from_base64_never_panics(input in ".*") {
+            let _ = EncryptedEventData::from_base64(&input);
+        }
+
+        #[test]
+        fn prop_arbitrary_bytes_never_panic(bytes in proptest::collection::vec(proptest::num::u8::ANY, 0..512)) {
+            let _ = EncryptedEventData::from_base64(&general_purpose::STANDARD.encode(&bytes));
+        }
+
+        #[test]
+        fn prop_truncated_payloads_never_panic(cut in 0usize..400) {
+            let (_, encrypted) = sample_encryption();
+            let bytes = serde_json::to_vec(&encrypted).unwrap();
+            let truncated = &bytes[..cut.min(bytes.len())];
+            let _ = EncryptedEventData::from_base64(&general_purpose::STANDARD.encode(truncated));
+        }
+
+        #[test]
+        fn prop_decrypt_with_any_field_lengths_never_panics(
+            iv in proptest::collection::vec(proptest::num::u8::ANY, 0..32),
+            tag in proptest::collection::vec(proptest::num::u8::ANY, 0..32),
+            data in proptest::collection::vec(proptest::num::u8::ANY, 0..64),
+        ) {
+            let (encryption, mut encrypted) = sample_encryption();
+            encrypted.iv = iv;
+            encrypted.tag = tag;
+            encrypted.encrypted_data = data;
+            proptest::prop_assert!(encryption.decrypt_event_data(&encrypted).is_err());
+        }
+    }
+}
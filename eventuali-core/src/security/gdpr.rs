@@ -2,15 +2,17 @@ use crate::{Result, EventualiError};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap};
 use chrono::{DateTime, Utc, Duration};
+use crate::security::serde_time::{duration_seconds, duration_seconds_map, option_duration_seconds};
 use uuid::Uuid;
 use sha2::{Sha256, Digest};
+use std::io::{BufWriter, Write};
+use std::sync::Arc;
 
 /// Comprehensive GDPR compliance system for European Union regulatory requirements
 pub struct GdprManager {
     data_subjects: BTreeMap<String, DataSubject>,
     processing_activities: Vec<ProcessingActivity>,
     consent_records: BTreeMap<String, ConsentRecord>,
-    #[allow(dead_code)] // Registry for tracking lawful basis for data processing (GDPR compliance)
     lawful_basis_registry: BTreeMap<String, LawfulBasis>,
     retention_policies: BTreeMap<String, RetentionPolicy>,
     breach_notifications: Vec<BreachNotification>,
@@ -18,6 +20,8 @@ pub struct GdprManager {
     privacy_by_design_controls: Vec<PrivacyControl>,
     data_exports: Vec<DataExportRecord>,
     deletion_log: Vec<DeletionRecord>,
+    export_formatters: ExportFormatterRegistry,
+    default_export_format: ExportFormat,
 }
 
 /// Data subject with GDPR rights and personal data tracking
@@ -48,6 +52,7 @@ pub struct DataLocation {
     pub data_classification: DataClassification,
     pub encrypted: bool,
     pub pseudonymized: bool,
+    #[serde(default, with = "option_duration_seconds")]
     pub retention_period: Option<Duration>,
 }
 
@@ -89,6 +94,7 @@ pub struct ProcessingActivity {
     pub categories_of_personal_data: Vec<PersonalDataType>,
     pub categories_of_recipients: Vec<String>,
     pub transfers_to_third_countries: Vec<InternationalTransfer>,
+    #[serde(with = "duration_seconds_map")]
     pub retention_periods: HashMap<PersonalDataType, Duration>,
     pub technical_and_organizational_measures: Vec<SecurityMeasure>,
     pub lawful_basis: LawfulBasisType,
@@ -177,6 +183,20 @@ pub struct ConsentRecord {
     pub evidence_of_consent: ConsentEvidence,
     pub parental_consent_required: bool,
     pub parental_consent_obtained: Option<DateTime<Utc>>,
+    /// When the consent lapses and must be renewed, if it is time-limited
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ConsentRecord {
+    /// Whether the consent can justify processing at `now`: given, not
+    /// withdrawn, not past its expiry and, where required, confirmed by a parent
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.consent_status, ConsentStatus::Given)
+            && self.withdrawn_at.is_none()
+            && self.expires_at.is_none_or(|expires_at| expires_at > now)
+            && (!self.parental_consent_required || self.parental_consent_obtained.is_some())
+    }
 }
 
 /// Method of obtaining consent
@@ -242,12 +262,15 @@ pub enum LawfulBasisType {
 pub struct RetentionPolicy {
     pub policy_id: String,
     pub data_category: PersonalDataType,
+    #[serde(with = "duration_seconds")]
     pub retention_period: Duration,
     pub retention_criteria: String,
     pub disposal_method: DisposalMethod,
+    #[serde(with = "duration_seconds")]
     pub review_frequency: Duration,
     pub last_reviewed: DateTime<Utc>,
     pub automatic_deletion: bool,
+    #[serde(default, with = "option_duration_seconds")]
     pub archival_period: Option<Duration>,
 }
 
@@ -492,6 +515,186 @@ pub enum ExportFormat {
     StructuredFormat(String),
 }
 
+impl ExportFormat {
+    /// Name of the formatter that writes this format; `StructuredFormat`
+    /// carries its own
+    pub fn formatter_name(&self) -> &str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Xml => "xml",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::StructuredFormat(name) => name,
+        }
+    }
+}
+
+/// Name of the JSON Lines format every `GdprManager` writes without registration
+pub const JSON_LINES_EXPORT_FORMAT: &str = "jsonl";
+
+/// What an export knows before its first record
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportHeader<'a> {
+    pub export_id: &'a str,
+    pub subject: &'a DataSubject,
+    pub consents: Vec<&'a ConsentRecord>,
+}
+
+/// Writer for one named data export format, such as `"fhir"` for health data.
+///
+/// A streaming export calls `write_header` once, `write_record` for each
+/// record in order and `write_footer` once at the end, so a formatter never
+/// needs the whole export in memory.
+pub trait ExportFormatter: Send + Sync {
+    /// Name that `ExportFormat::StructuredFormat` selects this formatter by
+    fn name(&self) -> &str;
+
+    fn write_header(&self, out: &mut dyn Write, header: &ExportHeader<'_>) -> Result<()>;
+
+    fn write_record(&self, out: &mut dyn Write, record: &serde_json::Value) -> Result<()>;
+
+    fn write_footer(&self, out: &mut dyn Write, export_id: &str, records_written: u64) -> Result<()>;
+}
+
+/// The built-in JSON Lines format: a `subject` line, one `event` line per
+/// record and a closing `summary` line
+struct JsonLinesFormatter;
+
+impl ExportFormatter for JsonLinesFormatter {
+    fn name(&self) -> &str {
+        JSON_LINES_EXPORT_FORMAT
+    }
+
+    fn write_header(&self, out: &mut dyn Write, header: &ExportHeader<'_>) -> Result<()> {
+        let line: ExportLine<'_, serde_json::Value> = ExportLine::Subject {
+            export_id: header.export_id,
+            subject: header.subject,
+            consents: header.consents.clone(),
+        };
+        write_export_line(out, &line)
+    }
+
+    fn write_record(&self, out: &mut dyn Write, record: &serde_json::Value) -> Result<()> {
+        write_export_line(out, &ExportLine::Event { data: record })
+    }
+
+    fn write_footer(&self, out: &mut dyn Write, export_id: &str, records_written: u64) -> Result<()> {
+        let line: ExportLine<'_, serde_json::Value> = ExportLine::Summary { export_id, records_written };
+        write_export_line(out, &line)
+    }
+}
+
+/// Export formatters by name; JSON Lines is always available
+#[derive(Clone)]
+pub struct ExportFormatterRegistry {
+    formatters: HashMap<String, Arc<dyn ExportFormatter>>,
+}
+
+impl Default for ExportFormatterRegistry {
+    fn default() -> Self {
+        let built_in: Arc<dyn ExportFormatter> = Arc::new(JsonLinesFormatter);
+        Self { formatters: HashMap::from([(JSON_LINES_EXPORT_FORMAT.to_string(), built_in)]) }
+    }
+}
+
+impl ExportFormatterRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a formatter, rejecting the built-in name and duplicates.
+    pub fn register<F: ExportFormatter + 'static>(&mut self, formatter: F) -> Result<()> {
+        self.register_arc(Arc::new(formatter))
+    }
+
+    pub fn register_arc(&mut self, formatter: Arc<dyn ExportFormatter>) -> Result<()> {
+        let name = formatter.name().to_string();
+        if name == JSON_LINES_EXPORT_FORMAT {
+            return Err(EventualiError::Configuration(format!(
+                "Export format name '{name}' is reserved for the built-in format"
+            )));
+        }
+        if self.formatters.contains_key(&name) {
+            return Err(EventualiError::Configuration(format!(
+                "Export formatter '{name}' is already registered"
+            )));
+        }
+
+        self.formatters.insert(name, formatter);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ExportFormatter>> {
+        self.formatters.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.formatters.contains_key(name)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.formatters.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// The formatter writing `format`, failing if none is registered for it
+    pub fn require(&self, format: &ExportFormat) -> Result<Arc<dyn ExportFormatter>> {
+        self.get(format.formatter_name()).ok_or_else(|| {
+            EventualiError::Configuration(format!(
+                "No export formatter is registered for format '{}'",
+                format.formatter_name()
+            ))
+        })
+    }
+}
+
+/// Records written between flushes by a streaming export unless the caller picks a size
+pub const DEFAULT_EXPORT_CHUNK_SIZE: usize = 1000;
+
+/// Progress of a streaming data export, reported after every flushed chunk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub records_written: u64,
+    pub bytes_written: u64,
+}
+
+/// One line of a JSON Lines data export
+#[derive(Serialize)]
+#[serde(tag = "record", rename_all = "snake_case")]
+enum ExportLine<'a, T: Serialize> {
+    Subject {
+        export_id: &'a str,
+        subject: &'a DataSubject,
+        consents: Vec<&'a ConsentRecord>,
+    },
+    Event {
+        data: &'a T,
+    },
+    Summary {
+        export_id: &'a str,
+        records_written: u64,
+    },
+}
+
+/// Writer that counts the bytes passed through it
+struct CountingWriter<W: Write> {
+    inner: W,
+    bytes_written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.bytes_written += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 /// Record of data deletion/erasure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeletionRecord {
@@ -522,9 +725,34 @@ impl GdprManager {
             privacy_by_design_controls: Vec::new(),
             data_exports: Vec::new(),
             deletion_log: Vec::new(),
+            export_formatters: ExportFormatterRegistry::new(),
+            default_export_format: ExportFormat::StructuredFormat(JSON_LINES_EXPORT_FORMAT.to_string()),
         }
     }
 
+    /// Make `formatter` available to exports requesting
+    /// `ExportFormat::StructuredFormat` with its name
+    pub fn register_export_formatter<F: ExportFormatter + 'static>(&mut self, formatter: F) -> Result<()> {
+        self.export_formatters.register(formatter)
+    }
+
+    pub fn export_formatters(&self) -> &ExportFormatterRegistry {
+        &self.export_formatters
+    }
+
+    /// Write `stream_data_export` output in `format` from now on; its
+    /// formatter must already be registered
+    pub fn set_default_export_format(&mut self, format: ExportFormat) -> Result<()> {
+        self.export_formatters.require(&format)?;
+        self.default_export_format = format;
+        Ok(())
+    }
+
+    /// Format `stream_data_export` writes, JSON Lines unless changed
+    pub fn default_export_format(&self) -> &ExportFormat {
+        &self.default_export_format
+    }
+
     /// Create GDPR manager with standard EU configuration
     pub fn with_eu_configuration() -> Self {
         let mut manager = Self::new();
@@ -584,6 +812,7 @@ impl GdprManager {
             evidence_of_consent: evidence,
             parental_consent_required: false,
             parental_consent_obtained: None,
+            expires_at: None,
         };
 
         self.consent_records.insert(consent_id.clone(), consent_record);
@@ -614,6 +843,101 @@ impl GdprManager {
         }
     }
 
+    /// Make a consent lapse at `expires_at`
+    pub fn set_consent_expiry(&mut self, consent_id: &str, expires_at: DateTime<Utc>) -> Result<()> {
+        let consent = self
+            .consent_records
+            .get_mut(consent_id)
+            .ok_or_else(|| EventualiError::Validation("Consent record not found".to_string()))?;
+        consent.expires_at = Some(expires_at);
+        Ok(())
+    }
+
+    /// Document a lawful basis other than consent (Article 6(1)(b)-(f)) for
+    /// processing a subject's data for `purpose`. Consent is recorded with
+    /// `record_consent` instead, so it can be withdrawn.
+    pub fn record_lawful_basis(
+        &mut self,
+        data_subject_id: String,
+        purpose: String,
+        basis_type: LawfulBasisType,
+        basis_details: String,
+    ) -> Result<String> {
+        if basis_type == LawfulBasisType::Consent {
+            return Err(EventualiError::Validation(
+                "Consent is recorded with record_consent, not as a standing lawful basis".to_string(),
+            ));
+        }
+        let data_subject = self
+            .data_subjects
+            .get_mut(&data_subject_id)
+            .ok_or_else(|| EventualiError::Validation("Data subject not found".to_string()))?;
+        data_subject.lawful_basis.insert(purpose.clone(), basis_type.clone());
+
+        let basis_id = Uuid::new_v4().to_string();
+        self.lawful_basis_registry.insert(
+            basis_id.clone(),
+            LawfulBasis {
+                basis_id: basis_id.clone(),
+                data_subject_id,
+                processing_purpose: purpose,
+                basis_type,
+                basis_details,
+                documented_at: Utc::now(),
+                reviewed_at: None,
+                balancing_test_conducted: None,
+                balancing_test_result: None,
+            },
+        );
+        Ok(basis_id)
+    }
+
+    /// Whether the subject currently has valid consent for `purpose`
+    pub fn has_valid_consent(&self, subject_id: &str, purpose: &str) -> bool {
+        let now = Utc::now();
+        self.consent_records
+            .values()
+            .any(|c| c.data_subject_id == subject_id && c.purpose == purpose && c.is_valid_at(now))
+    }
+
+    /// The lawful basis for processing the subject's data for `purpose`, to be
+    /// checked at the point of use. Valid consent is preferred; otherwise a
+    /// basis documented with `record_lawful_basis` applies. Returns an
+    /// `Authorization` error naming why when nothing applies.
+    pub fn assert_lawful_basis(&self, subject_id: &str, purpose: &str) -> Result<LawfulBasisType> {
+        if self.has_valid_consent(subject_id, purpose) {
+            return Ok(LawfulBasisType::Consent);
+        }
+
+        let documented = self
+            .lawful_basis_registry
+            .values()
+            .filter(|b| b.data_subject_id == subject_id && b.processing_purpose == purpose)
+            .max_by_key(|b| b.documented_at);
+        if let Some(basis) = documented {
+            return Ok(basis.basis_type.clone());
+        }
+
+        let now = Utc::now();
+        let latest_consent = self
+            .consent_records
+            .values()
+            .filter(|c| c.data_subject_id == subject_id && c.purpose == purpose)
+            .max_by_key(|c| c.consent_given_at);
+        let reason = match latest_consent {
+            None => "no consent or other lawful basis is recorded",
+            Some(c) if c.withdrawn_at.is_some() || matches!(c.consent_status, ConsentStatus::Withdrawn) => {
+                "consent was withdrawn"
+            }
+            Some(c) if matches!(c.consent_status, ConsentStatus::Expired)
+                || c.expires_at.is_some_and(|expires_at| expires_at <= now) => "consent has expired",
+            Some(_) => "consent is not in effect",
+        };
+        Err(EventualiError::Authorization(format!(
+            "No lawful basis to process data of subject {subject_id} for '{purpose}': {reason}"
+        )))
+    }
+
     /// Process data subject access request (Article 15)
     pub fn process_access_request(&mut self, data_subject_id: String, request_details: String) -> Result<SubjectRightsRequest> {
         let request_id = Uuid::new_v4().to_string();
@@ -767,6 +1091,117 @@ impl GdprManager {
         Ok(request)
     }
 
+    /// Stream a data portability export for a subject to `writer` in the
+    /// default export format, JSON Lines unless changed.
+    ///
+    /// In JSON Lines the first line carries the subject profile and consent
+    /// records, followed by one `event` line per item of `records` and a
+    /// closing `summary` line. Output is flushed every `chunk_size` records and
+    /// `on_progress` is called after each flush, so a subject's history is
+    /// never held in memory as a whole. The completed export is recorded with
+    /// its final byte size and returned.
+    pub fn stream_data_export<W, I, T, F>(
+        &mut self,
+        data_subject_id: &str,
+        records: I,
+        writer: W,
+        chunk_size: usize,
+        on_progress: F,
+    ) -> Result<DataExportRecord>
+    where
+        W: Write,
+        I: IntoIterator<Item = Result<T>>,
+        T: Serialize,
+        F: FnMut(ExportProgress),
+    {
+        let format = self.default_export_format.clone();
+        self.stream_data_export_as(&format, data_subject_id, records, writer, chunk_size, on_progress)
+    }
+
+    /// Stream a data portability export like `stream_data_export`, written
+    /// by the formatter registered for `format`
+    pub fn stream_data_export_as<W, I, T, F>(
+        &mut self,
+        format: &ExportFormat,
+        data_subject_id: &str,
+        records: I,
+        writer: W,
+        chunk_size: usize,
+        mut on_progress: F,
+    ) -> Result<DataExportRecord>
+    where
+        W: Write,
+        I: IntoIterator<Item = Result<T>>,
+        T: Serialize,
+        F: FnMut(ExportProgress),
+    {
+        let formatter = self.export_formatters.require(format)?;
+        if chunk_size == 0 {
+            return Err(EventualiError::Validation("Export chunk size must be positive".to_string()));
+        }
+        let data_subject = self
+            .data_subjects
+            .get(data_subject_id)
+            .ok_or_else(|| EventualiError::Validation("Data subject not found".to_string()))?;
+
+        let export_id = Uuid::new_v4().to_string();
+        let requested_at = Utc::now();
+        let mut out = CountingWriter { inner: BufWriter::new(writer), bytes_written: 0 };
+
+        let consents = self
+            .consent_records
+            .values()
+            .filter(|consent| consent.data_subject_id == data_subject_id)
+            .collect();
+        let header = ExportHeader {
+            export_id: &export_id,
+            subject: data_subject,
+            consents,
+        };
+        formatter.write_header(&mut out, &header)?;
+
+        let mut records_written = 0u64;
+        for record in records {
+            formatter.write_record(&mut out, &serde_json::to_value(record?)?)?;
+            records_written += 1;
+
+            if records_written.is_multiple_of(chunk_size as u64) {
+                out.flush()?;
+                on_progress(ExportProgress { records_written, bytes_written: out.bytes_written });
+            }
+        }
+
+        formatter.write_footer(&mut out, &export_id, records_written)?;
+        out.flush()?;
+        let progress = ExportProgress { records_written, bytes_written: out.bytes_written };
+        on_progress(progress);
+
+        let mut data_categories_exported: Vec<PersonalDataType> = Vec::new();
+        for location in &data_subject.data_locations {
+            if !data_categories_exported.contains(&location.data_type) {
+                data_categories_exported.push(location.data_type.clone());
+            }
+        }
+
+        let now = Utc::now();
+        let export_record = DataExportRecord {
+            export_id,
+            data_subject_id: data_subject_id.to_string(),
+            export_requested_at: requested_at,
+            export_completed_at: Some(now),
+            export_format: format.clone(),
+            data_categories_exported,
+            file_size_bytes: Some(progress.bytes_written),
+            download_expires_at: now + Duration::days(30), // 30-day expiry
+            downloaded_at: None,
+            encryption_applied: false,
+            secure_delivery_method: "caller_provided_writer".to_string(),
+        };
+
+        self.data_exports.push(export_record.clone());
+        Ok(export_record)
+    }
+
     /// Execute data deletion/erasure
     pub fn execute_data_deletion(&mut self, data_subject_id: String, deletion_method: DisposalMethod, locations: Vec<DataLocation>) -> Result<String> {
         let deletion_id = Uuid::new_v4().to_string();
@@ -1200,6 +1635,12 @@ pub struct GdprComplianceReport {
     pub recommendations: Vec<String>,
 }
 
+fn write_export_line<W: Write + ?Sized, T: Serialize>(out: &mut W, line: &ExportLine<'_, T>) -> Result<()> {
+    serde_json::to_writer(&mut *out, line)?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
 impl Default for GdprManager {
     fn default() -> Self {
         Self::new()
@@ -1217,6 +1658,38 @@ mod tests {
         assert_eq!(manager.consent_records.len(), 0);
     }
 
+    #[test]
+    fn test_retention_policy_round_trips_durations_exactly() {
+        let policy = RetentionPolicy {
+            policy_id: "basic".to_string(),
+            data_category: PersonalDataType::BasicPersonalData,
+            retention_period: Duration::days(365 * 6) + Duration::seconds(1),
+            retention_criteria: "Contract end".to_string(),
+            disposal_method: DisposalMethod::SecureDeletion,
+            review_frequency: Duration::days(365),
+            last_reviewed: Utc::now(),
+            automatic_deletion: true,
+            archival_period: Some(Duration::milliseconds(90_061_500)),
+        };
+
+        let value = serde_json::to_value(&policy).unwrap();
+        assert_eq!(value["retention_period"], serde_json::json!(189_216_001));
+        assert_eq!(value["review_frequency"], serde_json::json!(31_536_000));
+        assert_eq!(value["archival_period"], serde_json::json!(90_061.5));
+
+        let restored: RetentionPolicy = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.retention_period, policy.retention_period);
+        assert_eq!(restored.review_frequency, policy.review_frequency);
+        assert_eq!(restored.archival_period, policy.archival_period);
+        assert_eq!(restored.last_reviewed, policy.last_reviewed);
+
+        let periods = HashMap::from([(PersonalDataType::FinancialData, Duration::days(3650))]);
+        let value = duration_seconds_map::serialize(&periods, serde_json::value::Serializer).unwrap();
+        assert_eq!(value, serde_json::json!({ "FinancialData": 315_360_000 }));
+        let restored: HashMap<PersonalDataType, Duration> = duration_seconds_map::deserialize(value).unwrap();
+        assert_eq!(restored, periods);
+    }
+
     #[test]
     fn test_data_subject_registration() {
         let mut manager = GdprManager::new();
@@ -1303,6 +1776,67 @@ mod tests {
         assert!(consent.withdrawn_at.is_some());
     }
 
+    fn consent_for(manager: &mut GdprManager, subject_id: &str, purpose: &str) -> String {
+        let evidence = ConsentEvidence {
+            timestamp: Utc::now(),
+            ip_address: None,
+            user_agent: None,
+            form_version: None,
+            witness: None,
+            digital_signature: None,
+            audit_trail: Vec::new(),
+        };
+        manager.record_consent(
+            subject_id.to_string(),
+            purpose.to_string(),
+            format!("I agree to {purpose}"),
+            ConsentMethod::WebForm,
+            evidence,
+        ).unwrap()
+    }
+
+    #[test]
+    fn test_lawful_basis_follows_consent_lifecycle_and_other_bases() {
+        let mut manager = GdprManager::new();
+        let subject_id = manager.register_data_subject("user123".to_string(), None, None).unwrap();
+
+        let marketing = consent_for(&mut manager, &subject_id, "marketing");
+        assert!(manager.has_valid_consent(&subject_id, "marketing"));
+        assert_eq!(manager.assert_lawful_basis(&subject_id, "marketing").unwrap(), LawfulBasisType::Consent);
+
+        manager.withdraw_consent(marketing, "Email unsubscribe".to_string()).unwrap();
+        assert!(!manager.has_valid_consent(&subject_id, "marketing"));
+        let err = manager.assert_lawful_basis(&subject_id, "marketing").unwrap_err();
+        assert!(matches!(&err, EventualiError::Authorization(msg) if msg.contains("withdrawn")));
+
+        let analytics = consent_for(&mut manager, &subject_id, "analytics");
+        manager.set_consent_expiry(&analytics, Utc::now() - Duration::days(1)).unwrap();
+        assert!(!manager.has_valid_consent(&subject_id, "analytics"));
+        let err = manager.assert_lawful_basis(&subject_id, "analytics").unwrap_err();
+        assert!(matches!(&err, EventualiError::Authorization(msg) if msg.contains("expired")));
+
+        // Fulfilling an order needs no consent once the contract is documented
+        assert!(manager.assert_lawful_basis(&subject_id, "order_fulfilment").is_err());
+        manager.record_lawful_basis(
+            subject_id.clone(),
+            "order_fulfilment".to_string(),
+            LawfulBasisType::Contract,
+            "Purchase agreement".to_string(),
+        ).unwrap();
+        assert!(!manager.has_valid_consent(&subject_id, "order_fulfilment"));
+        assert_eq!(
+            manager.assert_lawful_basis(&subject_id, "order_fulfilment").unwrap(),
+            LawfulBasisType::Contract
+        );
+
+        assert!(manager.record_lawful_basis(
+            subject_id,
+            "marketing".to_string(),
+            LawfulBasisType::Consent,
+            String::new(),
+        ).is_err());
+    }
+
     #[test]
     fn test_subject_rights_requests() {
         let mut manager = GdprManager::new();
@@ -1340,6 +1874,136 @@ mod tests {
         assert_eq!(manager.data_exports.len(), 1);
     }
 
+    #[test]
+    fn test_streaming_export_of_large_subject() {
+        use crate::{Event, EventData};
+        use std::io::{BufRead, BufReader};
+
+        let mut manager = GdprManager::new();
+        let subject_id = manager.register_data_subject(
+            "user123".to_string(),
+            Some("user@example.com".to_string()),
+            Some("John Doe".to_string()),
+        ).unwrap();
+
+        let event_count = 50_000;
+        let events = (1..=event_count).map(|version| {
+            Ok(Event::new(
+                "user123".to_string(),
+                "User".to_string(),
+                "ProfileUpdated".to_string(),
+                1,
+                version,
+                EventData::from_json(&serde_json::json!({ "seq": version, "bio": "x".repeat(64) }))?,
+            ))
+        });
+
+        let path = std::env::temp_dir().join(format!("eventuali-gdpr-export-{}.jsonl", Uuid::new_v4()));
+        let file = std::fs::File::create(&path).unwrap();
+        let mut progress = Vec::new();
+        let record = manager
+            .stream_data_export(&subject_id, events, file, 5_000, |p| progress.push(p))
+            .unwrap();
+
+        // One report per chunk plus the final one
+        assert_eq!(progress.len(), 11);
+        assert!(progress.windows(2).all(|w| w[0].bytes_written < w[1].bytes_written));
+        let last = progress.last().unwrap();
+        assert_eq!(last.records_written, event_count as u64);
+
+        let file_size = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(record.file_size_bytes, Some(file_size));
+        assert_eq!(last.bytes_written, file_size);
+        assert!(record.export_completed_at.is_some());
+        assert_eq!(manager.data_exports.len(), 1);
+
+        let lines: Vec<serde_json::Value> = BufReader::new(std::fs::File::open(&path).unwrap())
+            .lines()
+            .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+            .collect();
+        assert_eq!(lines.len(), event_count as usize + 2);
+        assert_eq!(lines[0]["record"], "subject");
+        assert_eq!(lines[0]["subject"]["subject_id"], subject_id.as_str());
+        assert_eq!(lines[1]["record"], "event");
+        assert_eq!(lines[1]["data"]["aggregate_version"], 1);
+        assert_eq!(lines[event_count as usize]["data"]["aggregate_version"], event_count);
+        assert_eq!(lines[event_count as usize + 1]["record"], "summary");
+        assert_eq!(lines[event_count as usize + 1]["records_written"], event_count);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_registered_formatter_writes_structured_format_exports() {
+        struct CsvIds;
+
+        impl ExportFormatter for CsvIds {
+            fn name(&self) -> &str {
+                "csv-ids"
+            }
+
+            fn write_header(&self, out: &mut dyn Write, header: &ExportHeader<'_>) -> Result<()> {
+                writeln!(out, "# subject {}", header.subject.subject_id)?;
+                writeln!(out, "aggregate_id,aggregate_version")?;
+                Ok(())
+            }
+
+            fn write_record(&self, out: &mut dyn Write, record: &serde_json::Value) -> Result<()> {
+                writeln!(out, "{},{}", record["aggregate_id"].as_str().unwrap_or(""), record["aggregate_version"])?;
+                Ok(())
+            }
+
+            fn write_footer(&self, out: &mut dyn Write, _export_id: &str, records_written: u64) -> Result<()> {
+                writeln!(out, "# {records_written} records")?;
+                Ok(())
+            }
+        }
+
+        let mut manager = GdprManager::new();
+        let subject_id = manager.register_data_subject("user123".to_string(), None, None).unwrap();
+        let records = || {
+            (1..=3).map(|version| Ok(serde_json::json!({ "aggregate_id": "user123", "aggregate_version": version })))
+        };
+
+        let csv_ids = ExportFormat::StructuredFormat("csv-ids".to_string());
+        let mut out = Vec::new();
+        assert!(matches!(
+            manager.stream_data_export_as(&csv_ids, &subject_id, records(), &mut out, 10, |_| {}),
+            Err(EventualiError::Configuration(_))
+        ));
+        assert!(manager.set_default_export_format(csv_ids.clone()).is_err());
+
+        manager.register_export_formatter(CsvIds).unwrap();
+        assert!(manager.register_export_formatter(CsvIds).is_err());
+        let record = manager.stream_data_export_as(&csv_ids, &subject_id, records(), &mut out, 10, |_| {}).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("# subject {subject_id}\naggregate_id,aggregate_version\nuser123,1\nuser123,2\nuser123,3\n# 3 records\n")
+        );
+        assert!(matches!(&record.export_format, ExportFormat::StructuredFormat(name) if name == "csv-ids"));
+
+        // Once the default, plain `stream_data_export` uses it as well
+        manager.set_default_export_format(csv_ids).unwrap();
+        let mut out = Vec::new();
+        manager.stream_data_export(&subject_id, records(), &mut out, 10, |_| {}).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("# subject"));
+        assert_eq!(manager.export_formatters().names(), vec!["csv-ids".to_string(), "jsonl".to_string()]);
+    }
+
+    #[test]
+    fn test_streaming_export_requires_known_subject() {
+        let mut manager = GdprManager::new();
+        let result = manager.stream_data_export(
+            "missing",
+            std::iter::empty::<Result<serde_json::Value>>(),
+            Vec::new(),
+            DEFAULT_EXPORT_CHUNK_SIZE,
+            |_| {},
+        );
+        assert!(matches!(result, Err(EventualiError::Validation(_))));
+        assert!(manager.data_exports.is_empty());
+    }
+
     #[test]
     fn test_data_breach_reporting() {
         let mut manager = GdprManager::new();
@@ -1368,7 +2032,6 @@ mod tests {
         
         assert_eq!(status.total_data_subjects, 0);
         assert_eq!(status.active_consents, 0);
-        assert!(status.total_processing_activities >= 0);
     }
 
     #[test]
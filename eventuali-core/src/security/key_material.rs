@@ -0,0 +1,136 @@
+//! Secret key bytes that are wiped on drop and never printed
+//!
+//! Encryption and signing keys otherwise sit in plain `Vec<u8>`s, which keep
+//! their contents after being dropped or reallocated and print them through
+//! `Debug`, so key bytes can end up in swap, core dumps or logs.
+//! `KeyMaterial` zeroes its buffer when dropped, formats as `[REDACTED]`, and
+//! compares in constant time. It serializes like the `Vec<u8>` it replaces,
+//! so stored and wrapped keys keep loading.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Secret key bytes, zeroed when dropped
+#[derive(Clone, Default)]
+pub struct KeyMaterial(Zeroizing<Vec<u8>>);
+
+impl KeyMaterial {
+    /// Take ownership of `bytes` without copying them
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    /// `len` zero bytes, to be filled in place by a key derivation
+    pub fn zeroed(len: usize) -> Self {
+        Self::new(vec![0; len])
+    }
+}
+
+impl Deref for KeyMaterial {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl DerefMut for KeyMaterial {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl From<Vec<u8>> for KeyMaterial {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<&[u8]> for KeyMaterial {
+    fn from(bytes: &[u8]) -> Self {
+        Self::new(bytes.to_vec())
+    }
+}
+
+impl Zeroize for KeyMaterial {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for KeyMaterial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "KeyMaterial([REDACTED; {} bytes])", self.0.len())
+    }
+}
+
+impl PartialEq for KeyMaterial {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for KeyMaterial {}
+
+impl Serialize for KeyMaterial {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.as_slice().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyMaterial {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<u8>::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// Compare two byte strings in time that depends only on their lengths, not
+/// on where they first differ, so secrets and MACs cannot be guessed byte by
+/// byte from response times
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_omits_key_bytes() {
+        let key = KeyMaterial::new(vec![0xAB; 32]);
+        let printed = format!("{key:?} {key:#?}");
+        assert!(printed.contains("REDACTED"));
+        assert!(!printed.contains("171"), "decimal bytes leaked: {printed}");
+        assert!(!printed.to_lowercase().contains("ab, "), "hex bytes leaked: {printed}");
+    }
+
+    #[test]
+    fn test_zeroize_wipes_the_buffer_in_place() {
+        // Dropping runs the same wipe before the allocation is released;
+        // reading freed memory is unsound, so check the wipe on a live key
+        let mut key = KeyMaterial::new(vec![0x5A; 32]);
+        let buffer = key.as_ptr();
+        key.zeroize();
+        assert!(key.is_empty());
+        // Zeroizing a Vec clears its whole capacity and keeps the allocation
+        let wiped = unsafe { std::slice::from_raw_parts(buffer, 32) };
+        assert!(wiped.iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_comparison_and_serialization_match_plain_bytes() {
+        let key = KeyMaterial::new(vec![1, 2, 3]);
+        assert_eq!(key, KeyMaterial::from(&[1u8, 2, 3][..]));
+        assert_ne!(key, KeyMaterial::new(vec![1, 2, 4]));
+        assert_ne!(key, KeyMaterial::new(vec![1, 2]));
+        assert!(constant_time_eq(b"mac", b"mac"));
+        assert!(!constant_time_eq(b"mac", b"mad"));
+
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(json, serde_json::to_string(&vec![1u8, 2, 3]).unwrap());
+        assert_eq!(serde_json::from_str::<KeyMaterial>(&json).unwrap(), key);
+    }
+}
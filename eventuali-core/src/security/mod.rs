@@ -1,35 +1,46 @@
 //! Security module providing encryption, digital signatures, audit trails, RBAC, and GDPR compliance
 
 pub mod encryption;
+pub mod key_material;
 pub mod rbac;
 pub mod audit;
+pub mod audit_sink;
+pub mod session_store;
 pub mod gdpr;
 pub mod signatures;
 pub mod retention;
+pub mod serde_time;
 pub mod vulnerability;
 
 pub use encryption::{
     EventEncryption, KeyManager, EncryptionKey, EncryptedEventData, EncryptionAlgorithm
 };
 
+pub use key_material::{constant_time_eq, KeyMaterial};
+
 pub use rbac::{
     RbacManager, User, Role, Permission, Session, SecurityLevel, 
     AccessDecision, AuditEntry, AccessPolicy, PolicyCondition, PolicyEffect
 };
 
+pub use session_store::{SessionStore, SessionStoreConfig, InMemorySessionStore, SqliteSessionStore};
+
 pub use audit::{
     AuditManager, AuditTrailEntry, AuditEventType, AuditOutcome, RiskLevel,
     DataClassification, ComplianceTag, AuditSearchCriteria, ComplianceReport,
-    IntegrityStatus, RiskSummary, RetentionPolicy, ComplianceSettings
+    IntegrityStatus, RiskSummary, RetentionPolicy, ComplianceSettings, AuditSinkFailure
 };
 
+pub use audit_sink::{AuditSink, InMemoryAuditSink, JsonLinesAuditSink, SqliteAuditSink};
+
 pub use gdpr::{
     GdprManager, DataSubject, ProcessingActivity, ConsentRecord, LawfulBasis,
     BreachNotification, DataProtectionImpactAssessment, SubjectRightsRequest,
     DataExportRecord, DeletionRecord, GdprComplianceStatus, GdprComplianceReport,
     PersonalDataType, DataClassification as GdprDataClassification, LawfulBasisType,
     ConsentStatus, ConsentMethod, ConsentEvidence, DataSubjectRight, RequestStatus,
-    BreachType, ExportFormat, DisposalMethod, ComplexityLevel, ResponseMethod
+    BreachType, ExportFormat, ExportFormatter, ExportFormatterRegistry, ExportHeader, ExportProgress,
+    DEFAULT_EXPORT_CHUNK_SIZE, JSON_LINES_EXPORT_FORMAT, DisposalMethod, ComplexityLevel, ResponseMethod
 };
 
 pub use signatures::{
@@ -44,7 +55,7 @@ pub use retention::{
 };
 
 pub use vulnerability::{
-    VulnerabilityScanner, VulnerabilityScanResult, VulnerabilityFinding,
+    VulnerabilityScanner, VulnerabilityScanResult, VulnerabilityFinding, ScanCursor,
     VulnerabilityCategory, VulnerabilitySeverity, VulnerabilityStatus,
-    PenetrationTestFramework, PenetrationTest, AttackScenario, AttackType
+    PenetrationTestFramework, PenetrationTest, AttackScenario, AttackType, SARIF_SCHEMA_URI
 };
\ No newline at end of file
@@ -1,15 +1,21 @@
 use crate::{EventualiError, Result};
+use crate::security::session_store::{InMemorySessionStore, SessionStore, SessionStoreConfig};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use chrono::{DateTime, Utc, Duration};
 use uuid::Uuid;
 
+/// How long a session lasts unless configured otherwise
+pub const DEFAULT_SESSION_TTL_HOURS: i64 = 8;
+
 /// Role-Based Access Control (RBAC) implementation
 pub struct RbacManager {
     roles: HashMap<String, Role>,
     users: HashMap<String, User>,
     permissions: HashMap<String, Permission>,
-    sessions: HashMap<String, Session>,
+    sessions: Arc<dyn SessionStore + Send + Sync>,
+    session_ttl: Duration,
     audit_log: Vec<AuditEntry>,
     role_hierarchy: RoleHierarchy,
     #[allow(dead_code)] // Policy engine is part of the RBAC API but not yet implemented
@@ -147,7 +153,8 @@ impl RbacManager {
             roles: HashMap::new(),
             users: HashMap::new(),
             permissions: HashMap::new(),
-            sessions: HashMap::new(),
+            sessions: Arc::new(InMemorySessionStore::new()),
+            session_ttl: Duration::hours(DEFAULT_SESSION_TTL_HOURS),
             audit_log: Vec::new(),
             role_hierarchy: RoleHierarchy::new(),
             policy_engine: PolicyEngine::new(),
@@ -157,6 +164,24 @@ impl RbacManager {
         rbac
     }
     
+    /// Keep sessions in `store` instead of process memory, e.g. to share
+    /// them between instances
+    pub fn with_session_store(mut self, store: Arc<dyn SessionStore + Send + Sync>) -> Self {
+        self.sessions = store;
+        self
+    }
+    
+    /// Keep sessions in the store described by `config`
+    pub fn with_session_store_config(self, config: &SessionStoreConfig) -> Result<Self> {
+        Ok(self.with_session_store(config.open()?))
+    }
+    
+    /// How long new sessions stay valid
+    pub fn with_session_ttl(mut self, ttl: Duration) -> Self {
+        self.session_ttl = ttl;
+        self
+    }
+    
     /// Initialize default system roles
     fn initialize_system_roles(&mut self) {
         // System Administrator role
@@ -209,7 +234,8 @@ impl RbacManager {
         
         // Set up hierarchy: Admin > Manager > Employee > Guest
         // Note: In this hierarchy, child roles inherit from parent roles
-        // Manager inherits from Employee, Employee inherits from Guest
+        // Admin inherits from Manager, Manager from Employee, Employee from Guest
+        self.role_hierarchy.add_parent("system:admin", "system:manager");
         self.role_hierarchy.add_parent("system:manager", "system:employee");
         self.role_hierarchy.add_parent("system:employee", "system:guest");
         
@@ -376,6 +402,43 @@ impl RbacManager {
         Ok(role_id)
     }
     
+    /// Look up a role by ID
+    pub fn get_role(&self, role_id: &str) -> Option<&Role> {
+        self.roles.get(role_id)
+    }
+    
+    /// Delete a custom role, removing it from every user that holds it
+    pub fn delete_role(&mut self, role_id: &str) -> Result<()> {
+        match self.roles.get(role_id) {
+            None => return Err(EventualiError::Validation(format!("Role {role_id} not found"))),
+            Some(role) if role.is_system_role => {
+                return Err(EventualiError::Validation(format!("System role {role_id} cannot be deleted")))
+            }
+            Some(_) => {}
+        }
+        
+        self.roles.remove(role_id);
+        for user in self.users.values_mut() {
+            user.roles.remove(role_id);
+        }
+        
+        self.audit_log.push(AuditEntry {
+            audit_id: Uuid::new_v4().to_string(),
+            user_id: "system".to_string(),
+            action: "role:delete".to_string(),
+            resource: "role".to_string(),
+            resource_id: Some(role_id.to_string()),
+            decision: AccessDecision::Allow,
+            timestamp: Utc::now(),
+            ip_address: None,
+            session_id: None,
+            reason: Some(format!("Role {role_id} deleted")),
+            metadata: HashMap::new(),
+        });
+        
+        Ok(())
+    }
+    
     /// Assign permission to role
     pub fn assign_permission_to_role(&mut self, role_id: &str, permission_id: &str) -> Result<()> {
         if !self.roles.contains_key(role_id) {
@@ -411,14 +474,14 @@ impl RbacManager {
                 user_id: user_info.0.clone(),
                 token: token.clone(),
                 created_at: Utc::now(),
-                expires_at: Utc::now() + Duration::hours(8),
+                expires_at: Utc::now() + self.session_ttl,
                 ip_address: ip_address.clone(),
                 user_agent: None,
                 permissions_cache: self.get_effective_permissions(&user_info.0)?,
                 is_active: true,
             };
             
-            self.sessions.insert(session_id.clone(), session);
+            self.sessions.insert(session)?;
             
             // Update user last login
             let user = self.users.get_mut(&user_info.0).unwrap();
@@ -466,11 +529,15 @@ impl RbacManager {
         action: &str,
         context: Option<HashMap<String, String>>,
     ) -> AccessDecision {
-        // Clone necessary data to avoid borrowing conflicts
-        let session_data = match self.get_session_by_token(token) {
-            Some(session) if session.is_active && session.expires_at > Utc::now() => {
-                (session.user_id.clone(), session.permissions_cache.clone())
+        let session_data = match self.sessions.get(token) {
+            Ok(Some(session)) if session.is_active && session.expires_at > Utc::now() => {
+                (session.user_id, session.permissions_cache)
             },
+            Err(e) => {
+                let decision = AccessDecision::DenyWithReason(format!("Session store unavailable: {e}"));
+                self.audit_access(None, resource, action, decision.clone(), context);
+                return decision;
+            }
             _ => {
                 let decision = AccessDecision::DenyWithReason("Invalid or expired token".to_string());
                 self.audit_access(None, resource, action, decision.clone(), context);
@@ -566,11 +633,6 @@ impl RbacManager {
         format!("eventuali_token_{:x}", hasher.finish())
     }
     
-    /// Get session by token
-    fn get_session_by_token(&self, token: &str) -> Option<&Session> {
-        self.sessions.values().find(|session| session.token == token)
-    }
-    
     /// Verify password (simplified for example)
     fn verify_password(&self, _password: &str) -> bool {
         // In production, use proper password hashing (bcrypt, scrypt, argon2)
@@ -609,9 +671,7 @@ impl RbacManager {
     
     /// Revoke session
     pub fn revoke_session(&mut self, token: &str) -> Result<()> {
-        if let Some(session) = self.sessions.values_mut().find(|s| s.token == token) {
-            session.is_active = false;
-            
+        if let Some(session) = self.sessions.remove(token)? {
             self.audit_log.push(AuditEntry {
                 audit_id: Uuid::new_v4().to_string(),
                 user_id: session.user_id.clone(),
@@ -632,18 +692,9 @@ impl RbacManager {
         }
     }
     
-    /// Clean up expired sessions
-    pub fn cleanup_expired_sessions(&mut self) {
-        let now = Utc::now();
-        let expired_sessions: Vec<String> = self.sessions
-            .iter()
-            .filter(|(_, session)| session.expires_at < now)
-            .map(|(id, _)| id.clone())
-            .collect();
-        
-        for session_id in expired_sessions {
-            self.sessions.remove(&session_id);
-        }
+    /// Clean up expired sessions, returning how many were removed
+    pub fn cleanup_expired_sessions(&mut self) -> Result<usize> {
+        self.sessions.cleanup_expired(Utc::now())
     }
     
     /// Get system statistics
@@ -656,9 +707,9 @@ impl RbacManager {
         ));
         stats.insert("total_roles".to_string(), serde_json::Value::Number(self.roles.len().into()));
         stats.insert("total_permissions".to_string(), serde_json::Value::Number(self.permissions.len().into()));
-        stats.insert("active_sessions".to_string(), serde_json::Value::Number(
-            self.sessions.values().filter(|s| s.is_active && s.expires_at > Utc::now()).count().into()
-        ));
+        if let Ok(active_sessions) = self.sessions.active_count(Utc::now()) {
+            stats.insert("active_sessions".to_string(), serde_json::Value::Number(active_sessions.into()));
+        }
         stats.insert("audit_entries".to_string(), serde_json::Value::Number(self.audit_log.len().into()));
         
         stats
@@ -794,6 +845,72 @@ mod tests {
         assert!(matches!(decision, AccessDecision::DenyWithReason(_)));
     }
 
+    /// Store that records every call before delegating to memory
+    #[derive(Default)]
+    struct RecordingStore {
+        inner: InMemorySessionStore,
+        calls: std::sync::Mutex<Vec<&'static str>>,
+    }
+
+    impl RecordingStore {
+        fn record(&self, call: &'static str) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl SessionStore for RecordingStore {
+        fn insert(&self, session: Session) -> Result<()> {
+            self.record("insert");
+            self.inner.insert(session)
+        }
+
+        fn get(&self, token: &str) -> Result<Option<Session>> {
+            self.record("get");
+            self.inner.get(token)
+        }
+
+        fn remove(&self, token: &str) -> Result<Option<Session>> {
+            self.record("remove");
+            self.inner.remove(token)
+        }
+
+        fn cleanup_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+            self.record("cleanup_expired");
+            self.inner.cleanup_expired(now)
+        }
+
+        fn active_count(&self, now: DateTime<Utc>) -> Result<usize> {
+            self.record("active_count");
+            self.inner.active_count(now)
+        }
+    }
+
+    #[test]
+    fn test_sessions_route_through_custom_store() {
+        let store = Arc::new(RecordingStore::default());
+        let mut rbac = RbacManager::new().with_session_store(store.clone());
+        let user_id = rbac.create_user(
+            "store_user".to_string(),
+            "store@example.com".to_string(),
+            SecurityLevel::Internal,
+        ).unwrap();
+        rbac.assign_role_to_user(&user_id, "system:employee").unwrap();
+
+        let token = rbac.authenticate("store_user", "password", None).unwrap();
+        assert_eq!(store.inner.get(&token).unwrap().unwrap().user_id, user_id);
+
+        assert!(matches!(rbac.check_access(&token, "events", "read", None), AccessDecision::Allow));
+        rbac.revoke_session(&token).unwrap();
+        assert!(store.inner.get(&token).unwrap().is_none());
+        assert!(matches!(rbac.check_access(&token, "events", "read", None), AccessDecision::DenyWithReason(_)));
+        assert_eq!(rbac.cleanup_expired_sessions().unwrap(), 0);
+
+        assert_eq!(
+            *store.calls.lock().unwrap(),
+            ["insert", "get", "remove", "get", "cleanup_expired"]
+        );
+    }
+
     #[test]
     fn test_security_levels() {
         assert!(SecurityLevel::Secret.can_access(&SecurityLevel::Internal));
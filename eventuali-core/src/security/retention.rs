@@ -2,6 +2,7 @@ use crate::{Event, EventualiError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Duration, Utc};
+use crate::security::serde_time::duration_seconds;
 
 /// Data retention policy manager for GDPR and compliance
 pub struct RetentionPolicyManager {
@@ -16,6 +17,7 @@ pub struct RetentionPolicy {
     pub description: String,
     pub retention_period: RetentionPeriod,
     pub deletion_method: DeletionMethod,
+    #[serde(with = "duration_seconds")]
     pub grace_period: Duration,
     pub legal_hold_exempt: bool,
     pub data_categories: Vec<DataCategory>,
@@ -61,6 +63,34 @@ pub enum DataCategory {
     BackupData,
 }
 
+impl DataCategory {
+    /// Categories recognized from field names in event payloads
+    pub const DETECTED: [DataCategory; 7] = [
+        DataCategory::PersonalData,
+        DataCategory::SensitivePersonalData,
+        DataCategory::FinancialData,
+        DataCategory::HealthData,
+        DataCategory::CommunicationData,
+        DataCategory::BehavioralData,
+        DataCategory::MarketingData,
+    ];
+
+    /// Lowercase fragments of payload field names that indicate this category.
+    /// Categories that cannot be recognized from a payload have none.
+    pub fn indicator_fields(&self) -> &'static [&'static str] {
+        match self {
+            DataCategory::PersonalData => &["email", "phone", "address", "name"],
+            DataCategory::SensitivePersonalData => &["ssn", "passport", "driver_license", "medical"],
+            DataCategory::FinancialData => &["credit_card", "bank_account", "payment", "transaction"],
+            DataCategory::HealthData => &["medical", "health", "diagnosis", "treatment"],
+            DataCategory::CommunicationData => &["message", "communication", "chat", "email"],
+            DataCategory::BehavioralData => &["click", "view", "behavior", "interaction"],
+            DataCategory::MarketingData => &["campaign", "marketing", "advertisement", "promotion"],
+            _ => &[],
+        }
+    }
+}
+
 /// Retention enforcement action result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RetentionEnforcementResult {
@@ -335,47 +365,10 @@ impl RetentionPolicyManager {
         // Analyze event data JSON for PII indicators
         if let crate::EventData::Json(data) = &event.data {
             let data_str = data.to_string().to_lowercase();
-
-            // Check for personal data indicators
-            if data_str.contains("email") || data_str.contains("phone") || 
-               data_str.contains("address") || data_str.contains("name") {
-                categories.push(DataCategory::PersonalData);
-            }
-
-            // Check for sensitive personal data
-            if data_str.contains("ssn") || data_str.contains("passport") ||
-               data_str.contains("driver_license") || data_str.contains("medical") {
-                categories.push(DataCategory::SensitivePersonalData);
-            }
-
-            // Check for financial data
-            if data_str.contains("credit_card") || data_str.contains("bank_account") ||
-               data_str.contains("payment") || data_str.contains("transaction") {
-                categories.push(DataCategory::FinancialData);
-            }
-
-            // Check for health data
-            if data_str.contains("medical") || data_str.contains("health") ||
-               data_str.contains("diagnosis") || data_str.contains("treatment") {
-                categories.push(DataCategory::HealthData);
-            }
-
-            // Check for communication data
-            if data_str.contains("message") || data_str.contains("communication") ||
-               data_str.contains("chat") || data_str.contains("email") {
-                categories.push(DataCategory::CommunicationData);
-            }
-
-            // Check for behavioral data
-            if data_str.contains("click") || data_str.contains("view") ||
-               data_str.contains("behavior") || data_str.contains("interaction") {
-                categories.push(DataCategory::BehavioralData);
-            }
-
-            // Check for marketing data
-            if data_str.contains("campaign") || data_str.contains("marketing") ||
-               data_str.contains("advertisement") || data_str.contains("promotion") {
-                categories.push(DataCategory::MarketingData);
+            for category in DataCategory::DETECTED {
+                if category.indicator_fields().iter().any(|field| data_str.contains(field)) {
+                    categories.push(category);
+                }
             }
         }
 
@@ -406,14 +399,19 @@ impl RetentionPolicyManager {
         // Find the most restrictive category present
         for category in &priority {
             if categories.contains(category) {
-                // In a real implementation, this would map to specific policies
-                return Ok(match category {
+                // Categories without a registered policy of their own fall back to the default
+                let policy_name = match category {
                     DataCategory::HealthData => "health_data_7_years".to_string(),
                     DataCategory::FinancialData => "financial_data_10_years".to_string(),
                     DataCategory::SensitivePersonalData => "sensitive_pii_3_years".to_string(),
                     DataCategory::PersonalData => "personal_data_2_years".to_string(),
                     DataCategory::LegalData => "legal_data_indefinite".to_string(),
                     _ => self.default_policy.clone(),
+                };
+                return Ok(if self.policies.contains_key(&policy_name) {
+                    policy_name
+                } else {
+                    self.default_policy.clone()
                 });
             }
         }
@@ -690,6 +688,38 @@ mod tests {
         assert!(diff < 60); // Within 1 minute
     }
 
+    #[test]
+    fn test_retention_policy_round_trips_durations_exactly() {
+        let created_at = DateTime::parse_from_rfc3339("2024-03-31T22:30:00-02:00").unwrap().with_timezone(&Utc);
+        let policy = RetentionPolicy {
+            name: "test".to_string(),
+            description: "Test policy".to_string(),
+            retention_period: RetentionPeriod::Years(7),
+            deletion_method: DeletionMethod::Anonymize,
+            grace_period: Duration::days(14) + Duration::milliseconds(250),
+            legal_hold_exempt: false,
+            data_categories: vec![DataCategory::FinancialData],
+            created_at,
+            updated_at: created_at + Duration::days(1),
+        };
+
+        let value = serde_json::to_value(&policy).unwrap();
+        assert_eq!(value["grace_period"], serde_json::json!(1_209_600.25));
+        assert_eq!(value["created_at"], serde_json::json!("2024-04-01T00:30:00Z"));
+
+        let restored: RetentionPolicy = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.grace_period, policy.grace_period);
+        assert_eq!(restored.created_at, policy.created_at);
+        assert_eq!(restored.updated_at, policy.updated_at);
+
+        // Offsets are read as the same instant in UTC; a timestamp without one is rejected
+        let mut value = serde_json::to_value(&policy).unwrap();
+        value["created_at"] = serde_json::json!("2024-04-01T02:30:00+02:00");
+        assert_eq!(serde_json::from_value::<RetentionPolicy>(value.clone()).unwrap().created_at, created_at);
+        value["created_at"] = serde_json::json!("2024-04-01T00:30:00");
+        assert!(serde_json::from_value::<RetentionPolicy>(value).is_err());
+    }
+
     #[test]
     fn test_legal_hold() {
         let mut hold = LegalHold::new(
@@ -0,0 +1,162 @@
+//! Serde helpers for durations in security records
+//!
+//! chrono serializes a `Duration` as a `[seconds, nanoseconds]` pair, which
+//! reads as two unrelated numbers to anyone editing a policy by hand. Security
+//! records serialize durations as a plain number of seconds instead: an integer
+//! when the duration is whole seconds, a fraction otherwise. The legacy pair is
+//! still accepted when deserializing, so stored records keep loading.
+//!
+//! Timestamps need no helper: `DateTime<Utc>` serializes as RFC 3339 in UTC,
+//! and deserializing accepts only RFC 3339 values carrying an offset, which
+//! are converted to the same instant in UTC.
+
+use chrono::Duration;
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+const NANOS_PER_SECOND: f64 = 1_000_000_000.0;
+
+/// Serialize a `Duration` as seconds, for `#[serde(with = "duration_seconds")]`
+pub mod duration_seconds {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        Seconds(*duration).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Seconds::deserialize(deserializer).map(|seconds| seconds.0)
+    }
+}
+
+/// `duration_seconds` for an optional `Duration`, with `None` as null
+pub mod option_duration_seconds {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(duration: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error> {
+        duration.map(Seconds).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+        Option::<Seconds>::deserialize(deserializer).map(|seconds| seconds.map(|s| s.0))
+    }
+}
+
+/// `duration_seconds` for the values of a map
+pub mod duration_seconds_map {
+    use super::*;
+
+    pub fn serialize<K, S>(durations: &HashMap<K, Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_map(durations.iter().map(|(key, duration)| (key, Seconds(*duration))))
+    }
+
+    pub fn deserialize<'de, K, D>(deserializer: D) -> Result<HashMap<K, Duration>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        D: Deserializer<'de>,
+    {
+        let durations = HashMap::<K, Seconds>::deserialize(deserializer)?;
+        Ok(durations.into_iter().map(|(key, seconds)| (key, seconds.0)).collect())
+    }
+}
+
+/// A `Duration` in its seconds form
+struct Seconds(Duration);
+
+impl Serialize for Seconds {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let seconds = self.0.num_seconds();
+        let nanos = self.0.subsec_nanos();
+        if nanos == 0 {
+            serializer.serialize_i64(seconds)
+        } else {
+            serializer.serialize_f64(seconds as f64 + nanos as f64 / NANOS_PER_SECOND)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Seconds {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SecondsVisitor).map(Seconds)
+    }
+}
+
+struct SecondsVisitor;
+
+impl<'de> Visitor<'de> for SecondsVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a duration in seconds")
+    }
+
+    fn visit_i64<E: de::Error>(self, seconds: i64) -> Result<Duration, E> {
+        Duration::try_seconds(seconds).ok_or_else(|| E::custom("duration out of range"))
+    }
+
+    fn visit_u64<E: de::Error>(self, seconds: u64) -> Result<Duration, E> {
+        let seconds = i64::try_from(seconds).map_err(|_| E::custom("duration out of range"))?;
+        self.visit_i64(seconds)
+    }
+
+    fn visit_f64<E: de::Error>(self, seconds: f64) -> Result<Duration, E> {
+        if !seconds.is_finite() {
+            return Err(E::custom("duration must be a finite number of seconds"));
+        }
+        let whole = seconds.floor();
+        let nanos = ((seconds - whole) * NANOS_PER_SECOND).round() as i64;
+        let whole = Duration::try_seconds(whole as i64).ok_or_else(|| E::custom("duration out of range"))?;
+        whole
+            .checked_add(&Duration::nanoseconds(nanos))
+            .ok_or_else(|| E::custom("duration out of range"))
+    }
+
+    /// The `[seconds, nanoseconds]` pair chrono writes
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Duration, A::Error> {
+        let seconds: i64 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let nanos: u32 = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Duration::new(seconds, nanos).ok_or_else(|| de::Error::custom("duration out of range"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "duration_seconds")]
+        period: Duration,
+        #[serde(with = "option_duration_seconds")]
+        archival: Option<Duration>,
+    }
+
+    #[test]
+    fn test_durations_serialize_as_seconds() {
+        let record = Record { period: Duration::days(30), archival: Some(Duration::milliseconds(1_500)) };
+        let value = serde_json::to_value(&record).unwrap();
+        assert_eq!(value, json!({ "period": 2_592_000, "archival": 1.5 }));
+        assert_eq!(serde_json::from_value::<Record>(value).unwrap(), record);
+
+        let negative = Record { period: -Duration::milliseconds(250), archival: None };
+        let value = serde_json::to_value(&negative).unwrap();
+        assert_eq!(value, json!({ "period": -0.25, "archival": null }));
+        assert_eq!(serde_json::from_value::<Record>(value).unwrap(), negative);
+    }
+
+    #[test]
+    fn test_legacy_pairs_still_deserialize() {
+        let record: Record = serde_json::from_value(json!({ "period": [86_400, 0], "archival": [1, 500_000_000] })).unwrap();
+        assert_eq!(record, Record { period: Duration::days(1), archival: Some(Duration::milliseconds(1_500)) });
+        assert!(serde_json::from_value::<Record>(json!({ "period": "1d", "archival": null })).is_err());
+    }
+}
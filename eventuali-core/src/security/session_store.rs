@@ -0,0 +1,400 @@
+//! Session stores for `RbacManager`
+//!
+//! Sessions live in a `SessionStore` keyed by token. The in-memory store suits
+//! a single process; the SQLite store keeps sessions across restarts and lets
+//! several auth servers pointed at one database file share them. Sessions
+//! expire on their own once `expires_at` passes, Redis-TTL style: a store never
+//! returns an expired session, and `cleanup_expired` reclaims the space.
+
+use crate::security::rbac::Session;
+use crate::store::config::validate_table_name;
+use crate::{EventualiError, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Storage for active sessions, keyed by session token
+pub trait SessionStore {
+    /// Store `session`, replacing any session with the same token
+    fn insert(&self, session: Session) -> Result<()>;
+
+    /// The session for `token`, unless it is unknown or has expired
+    fn get(&self, token: &str) -> Result<Option<Session>>;
+
+    /// Delete the session for `token`, returning it if it existed
+    fn remove(&self, token: &str) -> Result<Option<Session>>;
+
+    /// Delete every session that expired before `now`, returning how many
+    fn cleanup_expired(&self, now: DateTime<Utc>) -> Result<usize>;
+
+    /// Number of active sessions that have not expired by `now`
+    fn active_count(&self, now: DateTime<Utc>) -> Result<usize>;
+}
+
+/// Which session store an `RbacManager` uses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SessionStoreConfig {
+    /// Sessions are private to the process and lost on restart
+    #[default]
+    InMemory,
+    /// Sessions are kept in a SQLite table shared by every manager using the file
+    Sqlite { path: String, table_name: Option<String> },
+}
+
+impl SessionStoreConfig {
+    /// Open the configured store
+    pub fn open(&self) -> Result<Arc<dyn SessionStore + Send + Sync>> {
+        Ok(match self {
+            SessionStoreConfig::InMemory => Arc::new(InMemorySessionStore::new()),
+            SessionStoreConfig::Sqlite { path, table_name } => {
+                Arc::new(SqliteSessionStore::open(path, table_name.clone())?)
+            }
+        })
+    }
+}
+
+/// Session store held in process memory
+#[derive(Debug, Default)]
+pub struct InMemorySessionStore {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl InMemorySessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, Session>>> {
+        self.sessions
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Session store lock poisoned".to_string()))
+    }
+}
+
+impl SessionStore for InMemorySessionStore {
+    fn insert(&self, session: Session) -> Result<()> {
+        self.lock()?.insert(session.token.clone(), session);
+        Ok(())
+    }
+
+    fn get(&self, token: &str) -> Result<Option<Session>> {
+        let now = Utc::now();
+        Ok(self.lock()?.get(token).filter(|s| s.expires_at > now).cloned())
+    }
+
+    fn remove(&self, token: &str) -> Result<Option<Session>> {
+        Ok(self.lock()?.remove(token))
+    }
+
+    fn cleanup_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        let mut sessions = self.lock()?;
+        let before = sessions.len();
+        sessions.retain(|_, session| session.expires_at >= now);
+        Ok(before - sessions.len())
+    }
+
+    fn active_count(&self, now: DateTime<Utc>) -> Result<usize> {
+        Ok(self.lock()?.values().filter(|s| s.is_active && s.expires_at > now).count())
+    }
+}
+
+/// Session store backed by a SQLite table
+///
+/// Rows are keyed by a SHA-256 hash of the token and the stored session has
+/// its token blanked, so the database file never holds a usable token.
+#[derive(Debug)]
+pub struct SqliteSessionStore {
+    conn: Mutex<Connection>,
+    table_name: String,
+}
+
+impl SqliteSessionStore {
+    /// Open (or create) the database at `path`; `:memory:` opens a private in-memory database
+    pub fn open(path: &str, table_name: Option<String>) -> Result<Self> {
+        let table_name = table_name.unwrap_or_else(|| "rbac_sessions".to_string());
+        validate_table_name(&table_name)?;
+
+        let conn = if path == ":memory:" {
+            Connection::open_in_memory()
+        } else {
+            Connection::open(path)
+        }
+        .map_err(|e| EventualiError::DatabaseError(format!("Failed to open session database: {e}")))?;
+
+        // Other instances may be writing to the same file
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to configure session database: {e}")))?;
+
+        let store = Self {
+            conn: Mutex::new(conn),
+            table_name,
+        };
+        store.initialize()?;
+        Ok(store)
+    }
+
+    fn initialize(&self) -> Result<()> {
+        let create_table = format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {table} (
+                token_hash TEXT PRIMARY KEY,
+                session_id TEXT NOT NULL,
+                user_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                session_json TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_{table}_expires_at ON {table} (expires_at);
+            "#,
+            table = self.table_name
+        );
+
+        let mut conn = self.lock()?;
+        self.hash_legacy_tokens(&mut conn)?;
+        conn.execute_batch(&create_table)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to create session table: {e}")))
+    }
+
+    /// Rewrite a table created when sessions were keyed by the plaintext
+    /// token into the hashed layout, keeping the sessions it holds
+    fn hash_legacy_tokens(&self, conn: &mut Connection) -> Result<()> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", self.table_name))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to inspect session table: {e}")))?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to inspect session table: {e}")))?;
+        drop(stmt);
+        if !columns.iter().any(|c| c == "token") {
+            return Ok(());
+        }
+
+        let tx = conn
+            .transaction()
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to migrate session table: {e}")))?;
+        let mut stmt = tx
+            .prepare(&format!("SELECT token, session_json FROM {}", self.table_name))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read sessions: {e}")))?;
+        let rows: Vec<(String, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read sessions: {e}")))?;
+        drop(stmt);
+
+        let rename = format!("ALTER TABLE {} RENAME COLUMN token TO token_hash", self.table_name);
+        tx.execute_batch(&rename)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to migrate session table: {e}")))?;
+        let update = format!(
+            "UPDATE {} SET token_hash = ?1, session_json = ?2 WHERE token_hash = ?3",
+            self.table_name
+        );
+        for (token, json) in rows {
+            let session: Session = serde_json::from_str(&json)?;
+            tx.execute(&update, params![token_hash(&token), stored_session_json(&session)?, token])
+                .map_err(|e| EventualiError::DatabaseError(format!("Failed to migrate session: {e}")))?;
+        }
+        tx.commit()
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to migrate session table: {e}")))
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Session store lock poisoned".to_string()))
+    }
+
+    fn read_session(&self, conn: &Connection, token: &str, now: Option<DateTime<Utc>>) -> Result<Option<Session>> {
+        let query = format!(
+            "SELECT session_json FROM {} WHERE token_hash = ?1 AND expires_at > ?2",
+            self.table_name
+        );
+        let not_expired_after = now.map_or(i64::MIN, |now| now.timestamp_micros());
+        let json = conn
+            .query_row(&query, params![token_hash(token), not_expired_after], |row| row.get::<_, String>(0))
+            .optional()
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to read session: {e}")))?;
+        json.map(|json| {
+            let mut session: Session = serde_json::from_str(&json)?;
+            session.token = token.to_string();
+            Ok(session)
+        })
+        .transpose()
+    }
+}
+
+/// Hash of `token` that stored sessions are keyed by, so a leaked database
+/// does not leak live tokens
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// The session as stored, with its token left out
+fn stored_session_json(session: &Session) -> Result<String> {
+    let mut session = session.clone();
+    session.token = String::new();
+    Ok(serde_json::to_string(&session)?)
+}
+
+impl SessionStore for SqliteSessionStore {
+    fn insert(&self, session: Session) -> Result<()> {
+        let session_json = stored_session_json(&session)?;
+        let query = format!(
+            "INSERT OR REPLACE INTO {} (token_hash, session_id, user_id, expires_at, session_json) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            self.table_name
+        );
+
+        self.lock()?
+            .execute(
+                &query,
+                params![
+                    token_hash(&session.token),
+                    session.session_id,
+                    session.user_id,
+                    session.expires_at.timestamp_micros(),
+                    session_json
+                ],
+            )
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to write session: {e}")))?;
+        Ok(())
+    }
+
+    fn get(&self, token: &str) -> Result<Option<Session>> {
+        let conn = self.lock()?;
+        self.read_session(&conn, token, Some(Utc::now()))
+    }
+
+    fn remove(&self, token: &str) -> Result<Option<Session>> {
+        let conn = self.lock()?;
+        let session = self.read_session(&conn, token, None)?;
+        conn.execute(&format!("DELETE FROM {} WHERE token_hash = ?1", self.table_name), params![token_hash(token)])
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to delete session: {e}")))?;
+        Ok(session)
+    }
+
+    fn cleanup_expired(&self, now: DateTime<Utc>) -> Result<usize> {
+        self.lock()?
+            .execute(
+                &format!("DELETE FROM {} WHERE expires_at < ?1", self.table_name),
+                params![now.timestamp_micros()],
+            )
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to delete expired sessions: {e}")))
+    }
+
+    fn active_count(&self, now: DateTime<Utc>) -> Result<usize> {
+        let conn = self.lock()?;
+        let query = format!("SELECT session_json FROM {} WHERE expires_at > ?1", self.table_name);
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to count sessions: {e}")))?;
+        let rows = stmt
+            .query_map(params![now.timestamp_micros()], |row| row.get::<_, String>(0))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to count sessions: {e}")))?;
+
+        let mut active = 0;
+        for row in rows {
+            let json = row.map_err(|e| EventualiError::DatabaseError(format!("Failed to read session: {e}")))?;
+            let session: Session = serde_json::from_str(&json)?;
+            if session.is_active {
+                active += 1;
+            }
+        }
+        Ok(active)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::collections::HashSet;
+
+    fn session(token: &str, expires_in: Duration) -> Session {
+        Session {
+            session_id: format!("session-{token}"),
+            user_id: "user-1".to_string(),
+            token: token.to_string(),
+            created_at: Utc::now(),
+            expires_at: Utc::now() + expires_in,
+            ip_address: None,
+            user_agent: None,
+            permissions_cache: HashSet::from(["events:read".to_string()]),
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_sqlite_sessions_are_shared_and_expire() {
+        let path = std::env::temp_dir().join(format!("eventuali-sessions-{}.db", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap().to_string();
+        let config = SessionStoreConfig::Sqlite { path: path.clone(), table_name: None };
+        let first = config.open().unwrap();
+        let second = config.open().unwrap();
+
+        first.insert(session("live", Duration::hours(1))).unwrap();
+        first.insert(session("stale", -Duration::seconds(1))).unwrap();
+
+        let shared = second.get("live").unwrap().expect("session visible to the other instance");
+        assert!(shared.permissions_cache.contains("events:read"));
+        assert!(second.get("stale").unwrap().is_none());
+        assert_eq!(second.active_count(Utc::now()).unwrap(), 1);
+
+        assert_eq!(second.cleanup_expired(Utc::now()).unwrap(), 1);
+        assert_eq!(first.remove("live").unwrap().map(|s| s.session_id), Some("session-live".to_string()));
+        assert!(second.get("live").unwrap().is_none());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_sqlite_store_never_holds_plaintext_tokens() {
+        let path = std::env::temp_dir().join(format!("eventuali-sessions-{}.db", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap().to_string();
+
+        // A table written before tokens were hashed
+        let legacy = Connection::open(&path).unwrap();
+        legacy
+            .execute_batch(
+                "CREATE TABLE rbac_sessions (token TEXT PRIMARY KEY, session_id TEXT NOT NULL, \
+                 user_id TEXT NOT NULL, expires_at INTEGER NOT NULL, session_json TEXT NOT NULL); \
+                 CREATE INDEX idx_rbac_sessions_expires_at ON rbac_sessions (expires_at);",
+            )
+            .unwrap();
+        let old = session("secret-old", Duration::hours(1));
+        legacy
+            .execute(
+                "INSERT INTO rbac_sessions VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    old.token,
+                    old.session_id,
+                    old.user_id,
+                    old.expires_at.timestamp_micros(),
+                    serde_json::to_string(&old).unwrap()
+                ],
+            )
+            .unwrap();
+        drop(legacy);
+
+        let store = SqliteSessionStore::open(&path, None).unwrap();
+        store.insert(session("secret-new", Duration::hours(1))).unwrap();
+        assert_eq!(store.get("secret-old").unwrap().map(|s| s.token), Some("secret-old".to_string()));
+        assert_eq!(store.get("secret-new").unwrap().map(|s| s.token), Some("secret-new".to_string()));
+
+        let raw = Connection::open(&path).unwrap();
+        let mut stmt = raw.prepare("SELECT token_hash, session_json FROM rbac_sessions").unwrap();
+        let rows: Vec<(String, String)> =
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).unwrap().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        for (hash, json) in rows {
+            assert!(!hash.starts_with("secret-"));
+            let stored: Session = serde_json::from_str(&json).unwrap();
+            assert!(stored.token.is_empty());
+        }
+
+        assert!(SqliteSessionStore::open(&path, Some("sessions; DROP TABLE x".to_string())).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
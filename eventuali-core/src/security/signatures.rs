@@ -1,3 +1,4 @@
+use crate::security::key_material::{constant_time_eq, KeyMaterial};
 use crate::{Event, EventualiError, Result};
 use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
@@ -20,7 +21,8 @@ pub struct SigningKeyManager {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SigningKey {
     pub id: String,
-    pub key_data: Vec<u8>, // HMAC signing key
+    /// HMAC key, zeroed on drop and redacted from `Debug`
+    pub key_data: KeyMaterial,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub algorithm: SignatureAlgorithm,
 }
@@ -40,6 +42,10 @@ pub struct EventSignature {
     pub signature: Vec<u8>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub event_hash: Vec<u8>, // SHA-256 of the event data for verification
+    /// Signature of the preceding event when signed as part of a chain; it is
+    /// covered by `signature`, so the link itself cannot be altered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_signature: Option<Vec<u8>>,
 }
 
 /// Signed event data
@@ -56,11 +62,11 @@ impl EventSigner {
     }
 
     /// Create a new signer instance with a single key
-    pub fn with_key(key_id: String, key_data: Vec<u8>) -> Result<Self> {
+    pub fn with_key(key_id: String, key_data: impl Into<KeyMaterial>) -> Result<Self> {
         let mut keys = HashMap::new();
         let signing_key = SigningKey {
             id: key_id.clone(),
-            key_data,
+            key_data: key_data.into(),
             created_at: chrono::Utc::now(),
             algorithm: SignatureAlgorithm::HmacSha256,
         };
@@ -81,13 +87,50 @@ impl EventSigner {
 
     /// Sign an event using a specific key
     pub fn sign_event_with_key(&self, event: &Event, key_id: &str) -> Result<SignedEvent> {
+        self.sign_linked(event, key_id, None)
+    }
+
+    /// Sign `events` as a hash chain with the default key: each signature also
+    /// covers the signature of the event before it, so removing, inserting or
+    /// reordering events breaks the chain. Check it with `verify_chain`.
+    pub fn sign_chain(&self, events: &[Event]) -> Result<Vec<SignedEvent>> {
+        let mut signed: Vec<SignedEvent> = Vec::with_capacity(events.len());
+        for event in events {
+            let previous = signed.last().map(|prev| prev.signature.signature.as_slice());
+            let signed_event = self.sign_linked(event, &self.key_manager.default_key_id, previous)?;
+            signed.push(signed_event);
+        }
+        Ok(signed)
+    }
+
+    /// Verify a chain produced by `sign_chain`: every signature must be valid,
+    /// the first event must start the chain and every later event must link to
+    /// the signature of the event right before it.
+    ///
+    /// Dropping events from the end of a chain leaves a valid, shorter chain;
+    /// compare the length or last signature with a trusted copy to detect it.
+    pub fn verify_chain(&self, signed_events: &[SignedEvent]) -> Result<bool> {
+        let mut previous: Option<&[u8]> = None;
+        for signed_event in signed_events {
+            if signed_event.signature.previous_signature.as_deref() != previous
+                || !self.verify_signature(signed_event)?
+            {
+                return Ok(false);
+            }
+            previous = Some(&signed_event.signature.signature);
+        }
+        Ok(true)
+    }
+
+    fn sign_linked(&self, event: &Event, key_id: &str, previous: Option<&[u8]>) -> Result<SignedEvent> {
         let key = self.key_manager.get_key(key_id)?;
         let event_bytes = self.serialize_event(event)?;
         let event_hash = self.hash_event_data(&event_bytes);
+        let message = Self::signed_message(&event_bytes, previous);
         
         let signature_bytes = match key.algorithm {
-            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&event_bytes, &key.key_data)?,
-            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&event_bytes, &key.key_data)?,
+            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&message, &key.key_data)?,
+            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&message, &key.key_data)?,
         };
         
         let signature = EventSignature {
@@ -96,6 +139,7 @@ impl EventSigner {
             signature: signature_bytes,
             timestamp: chrono::Utc::now(),
             event_hash,
+            previous_signature: previous.map(<[u8]>::to_vec),
         };
         
         Ok(SignedEvent {
@@ -115,10 +159,11 @@ impl EventSigner {
             return Ok(false);
         }
         
-        // Compute expected signature
+        // Compute expected signature, including the chain link if there is one
+        let message = Self::signed_message(&event_bytes, signed_event.signature.previous_signature.as_deref());
         let expected_signature = match signed_event.signature.algorithm {
-            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&event_bytes, &key.key_data)?,
-            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&event_bytes, &key.key_data)?,
+            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&message, &key.key_data)?,
+            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&message, &key.key_data)?,
         };
         
         // Constant-time comparison to prevent timing attacks
@@ -135,10 +180,11 @@ impl EventSigner {
             return Ok(false);
         }
         
-        // Compute expected signature
+        // Compute expected signature, including the chain link if there is one
+        let message = Self::signed_message(&event_bytes, signed_event.signature.previous_signature.as_deref());
         let expected_signature = match signed_event.signature.algorithm {
-            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&event_bytes, key_data)?,
-            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&event_bytes, key_data)?,
+            SignatureAlgorithm::HmacSha256 => self.hmac_sha256(&message, key_data)?,
+            SignatureAlgorithm::HmacSha512 => self.hmac_sha512(&message, key_data)?,
         };
         
         // Constant-time comparison
@@ -161,6 +207,7 @@ impl EventSigner {
             signature: signature_bytes,
             timestamp: chrono::Utc::now(),
             event_hash: data_hash,
+            previous_signature: None,
         })
     }
 
@@ -183,6 +230,21 @@ impl EventSigner {
         Ok(self.constant_time_compare(&expected_signature, &signature.signature))
     }
 
+    /// Bytes an event's signature covers: the event itself, followed by the
+    /// previous signature in the chain when there is one
+    fn signed_message(event_bytes: &[u8], previous: Option<&[u8]>) -> Vec<u8> {
+        match previous {
+            None => event_bytes.to_vec(),
+            Some(previous) => {
+                let mut message = Vec::with_capacity(event_bytes.len() + previous.len() + 16);
+                message.extend_from_slice(event_bytes);
+                message.extend_from_slice(b"\0eventuali-chain\0");
+                message.extend_from_slice(previous);
+                message
+            }
+        }
+    }
+
     /// Serialize event to bytes for signing
     fn serialize_event(&self, event: &Event) -> Result<Vec<u8>> {
         serde_json::to_vec(event)
@@ -221,16 +283,7 @@ impl EventSigner {
 
     /// Constant-time comparison to prevent timing attacks
     fn constant_time_compare(&self, a: &[u8], b: &[u8]) -> bool {
-        if a.len() != b.len() {
-            return false;
-        }
-        
-        let mut result = 0u8;
-        for (byte_a, byte_b) in a.iter().zip(b.iter()) {
-            result |= byte_a ^ byte_b;
-        }
-        
-        result == 0
+        constant_time_eq(a, b)
     }
 }
 
@@ -264,7 +317,7 @@ impl SigningKeyManager {
         let key_data = Self::generate_random_key(algorithm.key_size())?;
         Ok(SigningKey {
             id,
-            key_data,
+            key_data: key_data.into(),
             created_at: chrono::Utc::now(),
             algorithm,
         })
@@ -281,7 +334,7 @@ impl SigningKeyManager {
         use sha2::Sha256;
         
         let key_size = algorithm.key_size();
-        let mut key_data = vec![0u8; key_size];
+        let mut key_data = KeyMaterial::zeroed(key_size);
         pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, 100_000, &mut key_data);
         
         Ok(SigningKey {
@@ -434,6 +487,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_debug_never_prints_key_bytes() {
+        let key = SigningKey {
+            id: "debug-key".to_string(),
+            key_data: vec![0xC3; 32].into(),
+            created_at: chrono::Utc::now(),
+            algorithm: SignatureAlgorithm::HmacSha256,
+        };
+        let printed = format!("{key:?}");
+        assert!(printed.contains("debug-key") && printed.contains("REDACTED"));
+        assert!(!printed.contains("195"), "key bytes leaked: {printed}");
+    }
+
     #[test]
     fn test_key_generation() {
         let key = SigningKeyManager::generate_key(
@@ -503,7 +569,8 @@ mod tests {
         ).unwrap();
         
         let signer1 = EventSigner::with_key("key1".to_string(), key1.key_data).unwrap();
-        let signer2 = EventSigner::with_key("key2".to_string(), key2.key_data).unwrap();
+        // Same key ID, different key material
+        let signer2 = EventSigner::with_key("key1".to_string(), key2.key_data).unwrap();
         
         let event = create_test_event();
         let signed_event = signer1.sign_event(&event).unwrap();
@@ -546,7 +613,10 @@ mod tests {
         
         assert_eq!(key.key_data.len(), 64);
         
-        let signer = EventSigner::with_key("test-key".to_string(), key.key_data).unwrap();
+        // `with_key` always signs with HMAC-SHA256, so register the key itself
+        let mut key_manager = SigningKeyManager::new();
+        key_manager.add_key(key).unwrap();
+        let signer = EventSigner::new(key_manager);
         let event = create_test_event();
         
         let signed_event = signer.sign_event(&event).unwrap();
@@ -574,6 +644,39 @@ mod tests {
         assert!(!signer.verify_data_signature(other_data, &signature).unwrap());
     }
 
+    #[test]
+    fn test_chain_verifies_intact_and_detects_removal_and_reordering() {
+        let key = SigningKeyManager::generate_key(
+            "chain-key".to_string(),
+            SignatureAlgorithm::HmacSha256
+        ).unwrap();
+        let signer = EventSigner::with_key("chain-key".to_string(), key.key_data).unwrap();
+        let events: Vec<Event> = (0..4).map(|_| create_test_event()).collect();
+        
+        let chain = signer.sign_chain(&events).unwrap();
+        assert!(chain[0].signature.previous_signature.is_none());
+        assert_eq!(chain[2].signature.previous_signature.as_ref(), Some(&chain[1].signature.signature));
+        assert!(signer.verify_chain(&chain).unwrap());
+        // Each link is also a valid signature on its own
+        assert!(chain.iter().all(|signed| signer.verify_signature(signed).unwrap()));
+        
+        for removed in 0..chain.len() - 1 {
+            let mut gapped = chain.clone();
+            gapped.remove(removed);
+            assert!(!signer.verify_chain(&gapped).unwrap(), "removing event {removed} went unnoticed");
+        }
+        
+        let mut reordered = chain.clone();
+        reordered.swap(1, 2);
+        assert!(!signer.verify_chain(&reordered).unwrap());
+        
+        // Rewriting a link to hide a gap invalidates that event's signature
+        let mut relinked = chain.clone();
+        relinked.remove(1);
+        relinked[1].signature.previous_signature = Some(relinked[0].signature.signature.clone());
+        assert!(!signer.verify_chain(&relinked).unwrap());
+    }
+
     #[test]
     fn test_base64_serialization() {
         let key = SigningKeyManager::generate_key(
@@ -1,6 +1,8 @@
-use crate::{Event, EventualiError, Result};
+use crate::{Event, EventStore, EventualiError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
 use chrono::{DateTime, Utc};
 
 /// Vulnerability scanning and security assessment system
@@ -8,6 +10,54 @@ pub struct VulnerabilityScanner {
     scan_rules: Vec<ScanRule>,
     severity_thresholds: HashMap<VulnerabilitySeverity, u32>,
     whitelist: HashSet<String>,
+    cursor: Option<ScanCursor>,
+}
+
+/// How far `scan_store` has scanned, kept in a file so a scheduled scan
+/// resumes after the last run whose findings were committed
+#[derive(Debug)]
+pub struct ScanCursor {
+    path: PathBuf,
+    position: Mutex<u64>,
+}
+
+impl ScanCursor {
+    /// Open the cursor stored at `path`, starting at position 0 if the file
+    /// does not exist yet
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let position = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, position: Mutex::new(position) })
+    }
+
+    /// Global position of the last event scanned
+    pub fn position(&self) -> Result<u64> {
+        Ok(*self.lock()?)
+    }
+
+    /// Record that every event up to `position` has been scanned
+    pub fn advance(&self, position: u64) -> Result<()> {
+        let mut current = self.lock()?;
+        if position <= *current {
+            return Ok(());
+        }
+        // Write then rename, so a crash never leaves a truncated cursor
+        let tmp = self.path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&position)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        *current = position;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, u64>> {
+        self.position
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Scan cursor lock poisoned".to_string()))
+    }
 }
 
 /// A vulnerability scanning rule
@@ -95,6 +145,9 @@ pub struct VulnerabilityScanResult {
     pub severity_counts: HashMap<VulnerabilitySeverity, usize>,
     pub category_counts: HashMap<VulnerabilityCategory, usize>,
     pub compliance_score: f64, // 0.0 to 100.0
+    /// Global position of the last event a store scan covered
+    #[serde(default)]
+    pub scanned_through: Option<u64>,
 }
 
 /// A specific vulnerability finding
@@ -244,7 +297,7 @@ pub enum ExploitabilityLevel {
 }
 
 /// Test execution status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TestStatus {
     Pending,
     Running,
@@ -260,6 +313,7 @@ impl VulnerabilityScanner {
             scan_rules: Vec::new(),
             severity_thresholds: HashMap::new(),
             whitelist: HashSet::new(),
+            cursor: None,
         };
         
         // Initialize default severity thresholds
@@ -405,9 +459,91 @@ impl VulnerabilityScanner {
             severity_counts,
             category_counts,
             compliance_score,
+            scanned_through: None,
         })
     }
 
+    /// Persist `scan_store` progress in `cursor`, so each run resumes after
+    /// the last one passed to `commit_scan`
+    pub fn with_cursor(mut self, cursor: ScanCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /// The scan cursor, when one is configured
+    pub fn cursor(&self) -> Option<&ScanCursor> {
+        self.cursor.as_ref()
+    }
+
+    /// Scan every event in `store` after global position `from_global`, or
+    /// after the cursor's position if that is further along, reading
+    /// `batch_size` events at a time and combining the findings into one result.
+    ///
+    /// The cursor is left where it was. Once the findings are stored or
+    /// reported, pass the result to `commit_scan` so the next run starts after
+    /// it. A run that fails part-way leaves nothing to commit, so the next run
+    /// scans those events again and no finding is lost.
+    pub async fn scan_store(
+        &self,
+        store: &dyn EventStore,
+        from_global: u64,
+        batch_size: usize,
+    ) -> Result<VulnerabilityScanResult> {
+        let scan_start = std::time::Instant::now();
+        let batch_size = batch_size.max(1);
+        let mut position = match &self.cursor {
+            Some(cursor) => from_global.max(cursor.position()?),
+            None => from_global,
+        };
+
+        let mut events_scanned = 0;
+        let mut vulnerabilities = Vec::new();
+        let mut severity_counts: HashMap<VulnerabilitySeverity, usize> = HashMap::new();
+        let mut category_counts: HashMap<VulnerabilityCategory, usize> = HashMap::new();
+
+        loop {
+            let page = store.load_events_after_position(position, batch_size).await?;
+            let Some(&(last_position, _)) = page.last() else {
+                break;
+            };
+            let batch = self.scan_events(page.into_iter().map(|(_, event)| event).collect()).await?;
+
+            events_scanned += batch.events_scanned;
+            for (severity, count) in batch.severity_counts {
+                *severity_counts.entry(severity).or_insert(0) += count;
+            }
+            for (category, count) in batch.category_counts {
+                *category_counts.entry(category).or_insert(0) += count;
+            }
+            vulnerabilities.extend(batch.vulnerabilities_found);
+
+            position = last_position;
+        }
+
+        let compliance_score = self.calculate_compliance_score(&severity_counts, events_scanned);
+        Ok(VulnerabilityScanResult {
+            scan_id: uuid::Uuid::new_v4().to_string(),
+            scan_timestamp: Utc::now(),
+            events_scanned,
+            vulnerabilities_found: vulnerabilities,
+            scan_duration_ms: scan_start.elapsed().as_millis() as u64,
+            severity_counts,
+            category_counts,
+            compliance_score,
+            scanned_through: Some(position),
+        })
+    }
+
+    /// Move the cursor past the events `result` covered, once its findings
+    /// have been handed over. Does nothing without a cursor, or for a result
+    /// that did not come from `scan_store`.
+    pub fn commit_scan(&self, result: &VulnerabilityScanResult) -> Result<()> {
+        match (&self.cursor, result.scanned_through) {
+            (Some(cursor), Some(position)) => cursor.advance(position),
+            _ => Ok(()),
+        }
+    }
+
     /// Apply a single scan rule to an event
     async fn apply_scan_rule(&self, event: &Event, rule: &ScanRule) -> Result<Option<VulnerabilityFinding>> {
         let event_data_str = match &event.data {
@@ -608,6 +744,127 @@ impl Default for VulnerabilityScanner {
     }
 }
 
+/// Schema the SARIF output of `VulnerabilityScanResult::to_sarif` conforms to
+pub const SARIF_SCHEMA_URI: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+impl VulnerabilitySeverity {
+    /// SARIF result level for findings of this severity
+    pub fn sarif_level(&self) -> &'static str {
+        match self {
+            VulnerabilitySeverity::Critical | VulnerabilitySeverity::High => "error",
+            VulnerabilitySeverity::Medium => "warning",
+            VulnerabilitySeverity::Low | VulnerabilitySeverity::Info => "note",
+        }
+    }
+
+    /// CVSS-style score GitHub code scanning reads from `security-severity`
+    fn sarif_security_severity(&self) -> &'static str {
+        match self {
+            VulnerabilitySeverity::Critical => "9.5",
+            VulnerabilitySeverity::High => "8.0",
+            VulnerabilitySeverity::Medium => "5.5",
+            VulnerabilitySeverity::Low => "3.0",
+            VulnerabilitySeverity::Info => "0.0",
+        }
+    }
+}
+
+impl VulnerabilityScanResult {
+    /// Render the scan as a SARIF 2.1.0 log
+    ///
+    /// Each scan rule that produced a finding becomes a reporting descriptor
+    /// and each finding a result referencing it. Findings point at the event
+    /// through a logical location named `<aggregate_id>/<event_id>`, since
+    /// events have no file to point at.
+    ///
+    /// Evidence is event data, often the very secret or personal data a rule
+    /// flagged, so the log carries only its SHA-256 (`evidenceSha256`). The
+    /// evidence itself stays on the `VulnerabilityFinding`.
+    pub fn to_sarif(&self) -> Result<String> {
+        use serde_json::json;
+        use sha2::{Digest, Sha256};
+
+        let mut rule_indexes: HashMap<&str, usize> = HashMap::new();
+        let mut rules = Vec::new();
+        let mut results = Vec::new();
+
+        for finding in &self.vulnerabilities_found {
+            let rule_index = *rule_indexes.entry(finding.rule_id.as_str()).or_insert_with(|| {
+                let mut tags = vec!["security".to_string(), format!("{:?}", finding.category)];
+                tags.extend(finding.owasp_references.iter().cloned());
+                rules.push(json!({
+                    "id": finding.rule_id,
+                    "name": finding.title,
+                    "shortDescription": { "text": finding.title },
+                    "fullDescription": { "text": finding.description },
+                    "help": { "text": finding.remediation },
+                    "defaultConfiguration": { "level": finding.severity.sarif_level() },
+                    "properties": {
+                        "tags": tags,
+                        "security-severity": finding.severity.sarif_security_severity(),
+                    },
+                }));
+                rules.len() - 1
+            });
+
+            results.push(json!({
+                "ruleId": finding.rule_id,
+                "ruleIndex": rule_index,
+                "level": finding.severity.sarif_level(),
+                "kind": "fail",
+                "message": { "text": format!("{} in event {}", finding.title, finding.event_id) },
+                "locations": [{
+                    "logicalLocations": [{
+                        "name": finding.event_id,
+                        "fullyQualifiedName": format!("{}/{}", finding.aggregate_id, finding.event_id),
+                        "kind": "object",
+                    }],
+                }],
+                "partialFingerprints": { "findingId": finding.id },
+                "properties": {
+                    "category": format!("{:?}", finding.category),
+                    "severity": format!("{:?}", finding.severity),
+                    "evidenceSha256": format!("{:x}", Sha256::digest(finding.evidence.as_bytes())),
+                    "remediation": finding.remediation,
+                    "status": format!("{:?}", finding.status),
+                    "aggregateId": finding.aggregate_id,
+                    "eventId": finding.event_id,
+                    "foundAt": finding.found_at.to_rfc3339(),
+                    "cveReferences": finding.cve_references,
+                    "owaspReferences": finding.owasp_references,
+                },
+            }));
+        }
+
+        let log = json!({
+            "$schema": SARIF_SCHEMA_URI,
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "eventuali-vulnerability-scanner",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    },
+                },
+                "automationDetails": { "id": format!("eventuali/vulnerability-scan/{}", self.scan_id) },
+                "invocations": [{
+                    "executionSuccessful": true,
+                    "startTimeUtc": self.scan_timestamp.to_rfc3339(),
+                }],
+                "results": results,
+                "properties": {
+                    "eventsScanned": self.events_scanned,
+                    "scanDurationMs": self.scan_duration_ms,
+                    "complianceScore": self.compliance_score,
+                },
+            }],
+        });
+
+        Ok(serde_json::to_string_pretty(&log)?)
+    }
+}
+
 /// Penetration testing framework
 pub struct PenetrationTestFramework {
     active_tests: HashMap<String, PenetrationTest>,
@@ -870,6 +1127,110 @@ mod tests {
         assert_eq!(result.compliance_score, 100.0);
     }
 
+    #[tokio::test]
+    async fn test_store_scan_resumes_from_its_cursor_and_finds_every_planted_event() {
+        let store = crate::create_event_store(crate::EventStoreConfig::sqlite(":memory:".to_string()))
+            .await
+            .unwrap();
+        let seed = |start: i64, planted: &[i64]| {
+            (start..start + 10)
+                .map(|version| {
+                    let data = if planted.contains(&version) {
+                        serde_json::json!({ "query": "SELECT * FROM users WHERE id = 1 OR '1'='1" })
+                    } else {
+                        serde_json::json!({ "user_action": "login", "step": version })
+                    };
+                    let mut event = create_test_event_with_data(data);
+                    event.aggregate_version = version;
+                    event
+                })
+                .collect::<Vec<_>>()
+        };
+        let first = seed(1, &[2, 7, 10]);
+        let second = seed(11, &[11, 16]);
+        let planted: HashSet<String> = first
+            .iter()
+            .chain(&second)
+            .filter(|e| [2, 7, 10, 11, 16].contains(&e.aggregate_version))
+            .map(|e| e.id.to_string())
+            .collect();
+        store.save_events(first).await.unwrap();
+
+        let cursor_path = std::env::temp_dir().join(format!("eventuali-scan-cursor-{}.json", Uuid::new_v4()));
+        let scanner = VulnerabilityScanner::new().with_cursor(ScanCursor::open(&cursor_path).unwrap());
+
+        let initial = scanner.scan_store(store.as_ref(), 0, 3).await.unwrap();
+        assert_eq!(initial.events_scanned, 10);
+        assert_eq!(initial.vulnerabilities_found.len(), 3);
+        assert_eq!(initial.scanned_through, Some(10));
+        assert_eq!(initial.severity_counts[&VulnerabilitySeverity::High], 3);
+        assert_eq!(scanner.cursor().unwrap().position().unwrap(), 0);
+        scanner.commit_scan(&initial).unwrap();
+
+        // A scheduled run in a new process picks up only what was added since
+        store.save_events(second).await.unwrap();
+        let resumed = VulnerabilityScanner::new().with_cursor(ScanCursor::open(&cursor_path).unwrap());
+        assert_eq!(resumed.cursor().unwrap().position().unwrap(), 10);
+        let incremental = resumed.scan_store(store.as_ref(), 0, 4).await.unwrap();
+        assert_eq!(incremental.events_scanned, 10);
+        assert_eq!(incremental.vulnerabilities_found.len(), 2);
+        assert_eq!(incremental.scanned_through, Some(20));
+        resumed.commit_scan(&incremental).unwrap();
+
+        let found: HashSet<String> = initial
+            .vulnerabilities_found
+            .iter()
+            .chain(&incremental.vulnerabilities_found)
+            .map(|f| f.event_id.clone())
+            .collect();
+        assert_eq!(found, planted);
+
+        let caught_up = resumed.scan_store(store.as_ref(), 0, 4).await.unwrap();
+        assert_eq!(caught_up.events_scanned, 0);
+        assert_eq!(caught_up.scanned_through, Some(20));
+
+        std::fs::remove_file(cursor_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_store_scan_keeps_the_cursor_so_no_finding_is_lost() {
+        use crate::store::memory::MemoryBackend;
+        use crate::{BackendOperation, EventStoreImpl, FaultInjectingBackend, FaultInjector, InjectedFault};
+
+        let faults = FaultInjector::new();
+        let store = EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults.clone()));
+        let events: Vec<_> = (1..=6)
+            .map(|version| {
+                let mut event = create_test_event_with_data(
+                    serde_json::json!({ "query": "SELECT * FROM users WHERE id = 1 OR '1'='1" }),
+                );
+                event.aggregate_version = version;
+                event
+            })
+            .collect();
+        store.save_events(events).await.unwrap();
+
+        let cursor_path = std::env::temp_dir().join(format!("eventuali-scan-cursor-{}.json", Uuid::new_v4()));
+        let scanner = VulnerabilityScanner::new().with_cursor(ScanCursor::open(&cursor_path).unwrap());
+
+        // The second batch fails after the first batch's findings were made
+        let faults = faults.fail_nth_of(
+            BackendOperation::LoadEventsAfterPosition,
+            2,
+            InjectedFault::Database("connection lost".to_string()),
+        );
+        assert!(scanner.scan_store(&store, 0, 3).await.is_err());
+        assert_eq!(scanner.cursor().unwrap().position().unwrap(), 0);
+
+        faults.clear();
+        let retried = scanner.scan_store(&store, 0, 3).await.unwrap();
+        assert_eq!(retried.vulnerabilities_found.len(), 6);
+        scanner.commit_scan(&retried).unwrap();
+        assert_eq!(scanner.cursor().unwrap().position().unwrap(), 6);
+
+        std::fs::remove_file(cursor_path).unwrap();
+    }
+
     #[tokio::test]
     async fn test_whitelist_functionality() {
         let mut scanner = VulnerabilityScanner::new();
@@ -911,4 +1272,155 @@ mod tests {
         assert!(score < 100.0); // Should be penalized for vulnerabilities
         assert!(score >= 0.0);   // Should not be negative
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_sarif_output_follows_schema() {
+        let scanner = VulnerabilityScanner::new();
+        let events = vec![
+            create_test_event_with_data(serde_json::json!({"query": "SELECT * FROM users WHERE id = 1 OR '1'='1"})),
+            create_test_event_with_data(serde_json::json!({"user_ssn": "123-45-6789"})),
+            create_test_event_with_data(serde_json::json!({"query": "x' UNION SELECT password FROM users --"})),
+        ];
+        let result = scanner.scan_events(events).await.unwrap();
+        let severities: HashSet<_> = result.vulnerabilities_found.iter().map(|f| f.severity.clone()).collect();
+        assert!(severities.len() > 1, "expected mixed severities, got {severities:?}");
+
+        let sarif: serde_json::Value = serde_json::from_str(&result.to_sarif().unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        assert_eq!(sarif["$schema"], SARIF_SCHEMA_URI);
+
+        let runs = sarif["runs"].as_array().unwrap();
+        assert_eq!(runs.len(), 1);
+        let driver = &runs[0]["tool"]["driver"];
+        assert!(driver["name"].is_string());
+        let rules = driver["rules"].as_array().unwrap();
+        let rule_ids: Vec<_> = rules.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(rule_ids.len(), rule_ids.iter().collect::<HashSet<_>>().len());
+
+        let results = runs[0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), result.vulnerabilities_found.len());
+        for (sarif_result, finding) in results.iter().zip(&result.vulnerabilities_found) {
+            assert!(!sarif_result["message"]["text"].as_str().unwrap().contains(&finding.evidence));
+            assert!(["none", "note", "warning", "error"].contains(&sarif_result["level"].as_str().unwrap()));
+            assert_eq!(sarif_result["level"], finding.severity.sarif_level());
+
+            let rule = &rules[sarif_result["ruleIndex"].as_u64().unwrap() as usize];
+            assert_eq!(rule["id"], sarif_result["ruleId"]);
+            assert_eq!(rule["help"]["text"], finding.remediation);
+            assert!(rule["properties"]["security-severity"].as_str().unwrap().parse::<f64>().is_ok());
+
+            let location = &sarif_result["locations"][0]["logicalLocations"][0];
+            assert_eq!(location["name"], finding.event_id);
+            assert_eq!(sarif_result["properties"]["category"], format!("{:?}", finding.category));
+        }
+
+        assert_sarif_2_1_0_shape(&sarif);
+        let raw = result.to_sarif().unwrap();
+        for secret in ["123-45-6789", "'1'='1", "UNION SELECT password"] {
+            assert!(!raw.contains(secret), "SARIF log leaks evidence {secret:?}");
+        }
+    }
+
+    /// Check `value` against the SARIF 2.1.0 schema for the objects `to_sarif`
+    /// emits: required properties are present, no property outside the
+    /// schema's closed property lists appears, and enumerations and types hold
+    fn assert_sarif_2_1_0_shape(value: &serde_json::Value) {
+        use serde_json::Value;
+
+        fn object<'a>(value: &'a Value, path: &str, required: &[&str], allowed: &[&str]) -> &'a serde_json::Map<String, Value> {
+            let object = value.as_object().unwrap_or_else(|| panic!("{path} is not an object"));
+            for key in required {
+                assert!(object.contains_key(*key), "{path} is missing required {key}");
+            }
+            for key in object.keys() {
+                assert!(allowed.contains(&key.as_str()) || key == "properties", "{path} has unknown property {key}");
+            }
+            if let Some(properties) = object.get("properties") {
+                let bag = properties.as_object().unwrap_or_else(|| panic!("{path}.properties is not an object"));
+                if let Some(tags) = bag.get("tags") {
+                    let tags = tags.as_array().unwrap();
+                    assert!(tags.iter().all(Value::is_string), "{path}.properties.tags must be strings");
+                    assert_eq!(tags.len(), tags.iter().map(|t| t.as_str()).collect::<HashSet<_>>().len());
+                }
+            }
+            object
+        }
+        fn message(value: &Value, path: &str) {
+            let message = object(value, path, &["text"], &["text", "markdown", "id", "arguments"]);
+            assert!(message["text"].is_string(), "{path}.text is not a string");
+        }
+
+        let log = object(value, "sarifLog", &["version", "runs"], &["$schema", "version", "runs", "inlineExternalProperties"]);
+        assert_eq!(log["version"], "2.1.0");
+        assert!(log["$schema"].is_string());
+
+        for (r, run) in log["runs"].as_array().unwrap().iter().enumerate() {
+            let path = format!("runs[{r}]");
+            let run = object(run, &path, &["tool"], &["tool", "invocations", "results", "automationDetails", "redactionTokens"]);
+            let tool = object(&run["tool"], &format!("{path}.tool"), &["driver"], &["driver", "extensions"]);
+            let driver = object(&tool["driver"], &format!("{path}.tool.driver"), &["name"], &["name", "version", "rules", "informationUri"]);
+            let rules = driver.get("rules").and_then(Value::as_array).cloned().unwrap_or_default();
+            for (i, rule) in rules.iter().enumerate() {
+                let rule_path = format!("{path}.tool.driver.rules[{i}]");
+                let rule = object(
+                    rule,
+                    &rule_path,
+                    &["id"],
+                    &["id", "name", "shortDescription", "fullDescription", "help", "helpUri", "defaultConfiguration"],
+                );
+                for text in ["shortDescription", "fullDescription", "help"] {
+                    if let Some(text_value) = rule.get(text) {
+                        object(text_value, &format!("{rule_path}.{text}"), &["text"], &["text", "markdown"]);
+                    }
+                }
+                if let Some(configuration) = rule.get("defaultConfiguration") {
+                    let configuration =
+                        object(configuration, &format!("{rule_path}.defaultConfiguration"), &[], &["enabled", "level", "rank", "parameters"]);
+                    assert!(["none", "note", "warning", "error"].contains(&configuration["level"].as_str().unwrap()));
+                }
+            }
+
+            if let Some(details) = run.get("automationDetails") {
+                object(details, &format!("{path}.automationDetails"), &[], &["id", "guid", "correlationGuid", "description"]);
+            }
+            for (i, invocation) in run.get("invocations").and_then(Value::as_array).into_iter().flatten().enumerate() {
+                let invocation = object(
+                    invocation,
+                    &format!("{path}.invocations[{i}]"),
+                    &["executionSuccessful"],
+                    &["executionSuccessful", "startTimeUtc", "endTimeUtc"],
+                );
+                assert!(invocation["executionSuccessful"].is_boolean());
+            }
+
+            for (i, result) in run.get("results").and_then(Value::as_array).into_iter().flatten().enumerate() {
+                let result_path = format!("{path}.results[{i}]");
+                let result = object(
+                    result,
+                    &result_path,
+                    &["message"],
+                    &["ruleId", "ruleIndex", "kind", "level", "message", "locations", "partialFingerprints", "fingerprints"],
+                );
+                message(&result["message"], &format!("{result_path}.message"));
+                assert!(["none", "note", "warning", "error"].contains(&result["level"].as_str().unwrap()));
+                assert!(["notApplicable", "pass", "fail", "review", "open", "informational"]
+                    .contains(&result["kind"].as_str().unwrap()));
+                assert!((result["ruleIndex"].as_u64().unwrap() as usize) < rules.len());
+                assert!(result["partialFingerprints"].as_object().unwrap().values().all(Value::is_string));
+                for (l, location) in result["locations"].as_array().unwrap().iter().enumerate() {
+                    let location_path = format!("{result_path}.locations[{l}]");
+                    let location = object(location, &location_path, &[], &["id", "physicalLocation", "logicalLocations", "message"]);
+                    for (k, logical) in location["logicalLocations"].as_array().unwrap().iter().enumerate() {
+                        let logical = object(
+                            logical,
+                            &format!("{location_path}.logicalLocations[{k}]"),
+                            &[],
+                            &["name", "index", "fullyQualifiedName", "decoratedName", "parentIndex", "kind"],
+                        );
+                        assert!(logical.values().all(|v| v.is_string() || v.is_u64()));
+                    }
+                }
+            }
+        }
+    }
+}
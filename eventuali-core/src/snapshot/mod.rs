@@ -1,12 +1,20 @@
 mod sqlite_store;
+mod projection;
+mod state_codec;
 
 pub use sqlite_store::SqliteSnapshotStore;
+pub use projection::{ProjectionSnapshot, ProjectionSnapshotStore, SqliteProjectionSnapshotStore};
+pub use state_codec::{
+    JsonStateCodec, MessagePackStateCodec, StateCodec, STATE_CODEC_METADATA_KEY, state_codec_by_name,
+};
 
 use crate::{AggregateId, AggregateVersion, Result, EventualiError};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Represents a snapshot of an aggregate at a specific version
@@ -35,7 +43,38 @@ pub struct AggregateSnapshot {
 pub enum SnapshotCompression {
     None,
     Gzip,
+    /// LZ4 frame format: faster than gzip at a lower ratio
     Lz4,
+    /// Zstandard at `level`, one of `ZSTD_COMPRESSION_LEVELS`; higher levels
+    /// compress better and more slowly. Decompression does not need the level.
+    Zstd { level: i32 },
+}
+
+/// Compression levels accepted by `SnapshotCompression::Zstd`
+pub const ZSTD_COMPRESSION_LEVELS: std::ops::RangeInclusive<i32> = 1..=22;
+
+/// Zstandard level used when none is chosen, zstd's own default
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+impl SnapshotCompression {
+    /// Zstandard at `level`, rejecting levels outside `ZSTD_COMPRESSION_LEVELS`
+    pub fn zstd(level: i32) -> Result<Self> {
+        let compression = Self::Zstd { level };
+        compression.validate()?;
+        Ok(compression)
+    }
+
+    /// Check the algorithm's parameters
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Zstd { level } if !ZSTD_COMPRESSION_LEVELS.contains(level) => Err(EventualiError::Configuration(format!(
+                "Zstd compression level {level} is outside {}..={}",
+                ZSTD_COMPRESSION_LEVELS.start(),
+                ZSTD_COMPRESSION_LEVELS.end()
+            ))),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Metadata for snapshots
@@ -58,22 +97,149 @@ pub struct SnapshotMetadata {
 pub struct SnapshotConfig {
     /// How often to take snapshots (every N events)
     pub snapshot_frequency: AggregateVersion,
+    /// Per-aggregate-type frequencies replacing `snapshot_frequency`, so
+    /// high-churn types can snapshot more often than rarely-changing ones
+    pub aggregate_type_frequencies: HashMap<String, AggregateVersion>,
     /// Maximum age of snapshots before they should be replaced
     pub max_snapshot_age_hours: u64,
     /// Compression algorithm to use
     pub compression: SnapshotCompression,
     /// Whether to automatically clean up old snapshots
     pub auto_cleanup: bool,
+    /// Whether `load_latest_snapshot` checks each snapshot's stored data
+    /// against its checksum, as `load_latest_snapshot_verified` always does
+    pub verify_checksum_on_load: bool,
+    /// Largest state decompression will produce before failing, so a corrupt
+    /// or crafted snapshot cannot inflate into an unbounded allocation
+    pub max_decompressed_size: usize,
 }
 
+/// Default cap on a snapshot's decompressed state, 256 MiB
+pub const DEFAULT_MAX_SNAPSHOT_STATE_SIZE: usize = 256 * 1024 * 1024;
+
 impl Default for SnapshotConfig {
     fn default() -> Self {
         Self {
             snapshot_frequency: 100, // Snapshot every 100 events
+            aggregate_type_frequencies: HashMap::new(),
             max_snapshot_age_hours: 24 * 7, // Keep snapshots for a week
             compression: SnapshotCompression::Gzip,
             auto_cleanup: true,
+            verify_checksum_on_load: false,
+            max_decompressed_size: DEFAULT_MAX_SNAPSHOT_STATE_SIZE,
+        }
+    }
+}
+
+impl SnapshotConfig {
+    /// Compress snapshots with `compression`, rejecting invalid parameters
+    pub fn with_compression(mut self, compression: SnapshotCompression) -> Result<Self> {
+        compression.validate()?;
+        self.compression = compression;
+        Ok(self)
+    }
+
+    /// Check snapshots against their checksums whenever they are loaded
+    pub fn with_verify_checksum_on_load(mut self, verify: bool) -> Self {
+        self.verify_checksum_on_load = verify;
+        self
+    }
+
+    /// Snapshot aggregates of `aggregate_type` every `frequency` events
+    /// instead of every `snapshot_frequency`, rejecting frequencies below 1
+    pub fn with_frequency_for(mut self, aggregate_type: impl Into<String>, frequency: AggregateVersion) -> Result<Self> {
+        let aggregate_type = aggregate_type.into();
+        validate_frequency(&format!("Snapshot frequency for {aggregate_type}"), frequency)?;
+        self.aggregate_type_frequencies.insert(aggregate_type, frequency);
+        Ok(self)
+    }
+
+    /// Check the frequencies and compression parameters. Fields set directly
+    /// are checked here rather than when they are assigned.
+    pub fn validate(&self) -> Result<()> {
+        validate_frequency("Snapshot frequency", self.snapshot_frequency)?;
+        for (aggregate_type, frequency) in &self.aggregate_type_frequencies {
+            validate_frequency(&format!("Snapshot frequency for {aggregate_type}"), *frequency)?;
         }
+        self.compression.validate()
+    }
+
+    /// How often aggregates of `aggregate_type` are snapshotted
+    pub fn frequency_for(&self, aggregate_type: &str) -> AggregateVersion {
+        self.aggregate_type_frequencies
+            .get(aggregate_type)
+            .copied()
+            .unwrap_or(self.snapshot_frequency)
+    }
+}
+
+fn validate_frequency(what: &str, frequency: AggregateVersion) -> Result<()> {
+    if frequency < 1 {
+        return Err(EventualiError::Configuration(format!("{what} must be at least 1, got {frequency}")));
+    }
+    Ok(())
+}
+
+/// Sizes and checksum of state compressed by `compress_stream`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamedSnapshotData {
+    pub original_size: usize,
+    pub compressed_size: usize,
+    /// SHA-256 of the compressed bytes, as `SnapshotMetadata::checksum`
+    pub checksum: String,
+}
+
+/// Writer that counts and hashes compressed bytes on their way to `inner`
+struct ChecksumWriter<W> {
+    inner: W,
+    hasher: sha2::Sha256,
+    written: usize,
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        use sha2::Digest;
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.written += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader that fails once `inner` yields more than `remaining` bytes
+struct CappedReader<R> {
+    inner: R,
+    remaining: u64,
+    limit: usize,
+}
+
+impl<R: Read> CappedReader<R> {
+    fn new(inner: R, limit: usize) -> Self {
+        Self { inner, remaining: limit as u64, limit }
+    }
+}
+
+impl<R: Read> Read for CappedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 {
+            // At the cap: fine if the stream ends here, an error if it goes on
+            let mut probe = [0u8; 1];
+            return match self.inner.read(&mut probe)? {
+                0 => Ok(0),
+                _ => Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Decompressed snapshot state exceeds the limit of {} bytes", self.limit),
+                )),
+            };
+        }
+        let len = buf.len().min(usize::try_from(self.remaining).unwrap_or(usize::MAX));
+        let n = self.inner.read(&mut buf[..len])?;
+        self.remaining -= n as u64;
+        Ok(n)
     }
 }
 
@@ -95,13 +261,20 @@ pub trait SnapshotStore {
     /// Delete a snapshot
     async fn delete_snapshot(&self, snapshot_id: Uuid) -> Result<()>;
     
+    /// Delete every snapshot of an aggregate, returning how many were removed.
+    /// Erasing an aggregate's events leaves its snapshots, which hold the same
+    /// data, so erasure has to call this too.
+    async fn delete_aggregate_snapshots(&self, aggregate_id: &AggregateId) -> Result<u64>;
+    
     /// Clean up old snapshots based on configuration
     async fn cleanup_old_snapshots(&self, config: &SnapshotConfig) -> Result<u64>;
     
-    /// Check if a snapshot should be taken for an aggregate at the given version
+    /// Check if a snapshot should be taken for an aggregate at the given
+    /// version, at the frequency configured for its type
     async fn should_take_snapshot(
         &self, 
         aggregate_id: &AggregateId, 
+        aggregate_type: &str,
         current_version: AggregateVersion,
         config: &SnapshotConfig
     ) -> Result<bool>;
@@ -111,11 +284,22 @@ pub trait SnapshotStore {
 pub struct SnapshotService<S: SnapshotStore> {
     store: S,
     config: SnapshotConfig,
+    state_codec: Arc<dyn StateCodec>,
 }
 
 impl<S: SnapshotStore> SnapshotService<S> {
     pub fn new(store: S, config: SnapshotConfig) -> Self {
-        Self { store, config }
+        Self { store, config, state_codec: Arc::new(JsonStateCodec) }
+    }
+
+    /// Encode state passed to `create_snapshot_from_state` with `codec` instead of JSON
+    pub fn with_state_codec(mut self, codec: Arc<dyn StateCodec>) -> Self {
+        self.state_codec = codec;
+        self
+    }
+
+    pub fn state_codec(&self) -> &dyn StateCodec {
+        self.state_codec.as_ref()
     }
 
     /// Create a snapshot from aggregate state data
@@ -126,6 +310,40 @@ impl<S: SnapshotStore> SnapshotService<S> {
         aggregate_version: AggregateVersion,
         state_data: Vec<u8>,
         event_count: usize,
+    ) -> Result<AggregateSnapshot> {
+        self.save_new_snapshot(aggregate_id, aggregate_type, aggregate_version, state_data, event_count, HashMap::new())
+            .await
+    }
+
+    /// Create a snapshot from a state object, encoded with the service's state codec.
+    ///
+    /// The codec name is recorded under `STATE_CODEC_METADATA_KEY`, so
+    /// `decode_snapshot_state` can refuse snapshots written in another format.
+    pub async fn create_snapshot_from_state<T: Serialize>(
+        &self,
+        aggregate_id: AggregateId,
+        aggregate_type: String,
+        aggregate_version: AggregateVersion,
+        state: &T,
+        event_count: usize,
+    ) -> Result<AggregateSnapshot> {
+        let state_data = self.state_codec.encode(&serde_json::to_value(state)?)?;
+        let custom = HashMap::from([(
+            STATE_CODEC_METADATA_KEY.to_string(),
+            self.state_codec.name().to_string(),
+        )]);
+        self.save_new_snapshot(aggregate_id, aggregate_type, aggregate_version, state_data, event_count, custom)
+            .await
+    }
+
+    async fn save_new_snapshot(
+        &self,
+        aggregate_id: AggregateId,
+        aggregate_type: String,
+        aggregate_version: AggregateVersion,
+        state_data: Vec<u8>,
+        event_count: usize,
+        custom: HashMap<String, String>,
     ) -> Result<AggregateSnapshot> {
         let compressed_data = self.compress_data(&state_data)?;
         let checksum = self.calculate_checksum(&compressed_data);
@@ -135,7 +353,7 @@ impl<S: SnapshotStore> SnapshotService<S> {
             compressed_size: compressed_data.len(),
             event_count,
             checksum,
-            custom: HashMap::new(),
+            custom,
         };
 
         let snapshot = AggregateSnapshot {
@@ -153,27 +371,178 @@ impl<S: SnapshotStore> SnapshotService<S> {
         Ok(snapshot)
     }
 
-    /// Load the most recent snapshot for an aggregate
+    /// Load the most recent snapshot for an aggregate, verified against its
+    /// checksum when the config's `verify_checksum_on_load` is set
     pub async fn load_latest_snapshot(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateSnapshot>> {
+        if self.config.verify_checksum_on_load {
+            return self.load_latest_snapshot_verified(aggregate_id).await;
+        }
         self.store.load_latest_snapshot(aggregate_id).await
     }
 
+    /// Load the most recent snapshot for an aggregate, failing with a
+    /// `Validation` error if its stored data no longer matches its checksum
+    pub async fn load_latest_snapshot_verified(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateSnapshot>> {
+        let snapshot = self.store.load_latest_snapshot(aggregate_id).await?;
+        if let Some(snapshot) = &snapshot {
+            self.verify_checksum(snapshot)?;
+        }
+        Ok(snapshot)
+    }
+
+    /// Check that the snapshot's stored data matches the checksum taken when
+    /// it was created
+    pub fn verify_checksum(&self, snapshot: &AggregateSnapshot) -> Result<()> {
+        if self.calculate_checksum(&snapshot.state_data) != snapshot.metadata.checksum {
+            return Err(EventualiError::Validation("snapshot checksum mismatch".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Create a snapshot from state read from `state`, for aggregates too large
+    /// to hold uncompressed in memory.
+    ///
+    /// The state is compressed chunk by chunk as it is read, so the
+    /// uncompressed state is never buffered. The compressed snapshot is, since
+    /// `SnapshotStore::save_snapshot` takes it whole; to keep that out of
+    /// memory as well, `compress_stream` into a writer of your own instead.
+    pub async fn create_snapshot_from_reader<R: Read>(
+        &self,
+        aggregate_id: AggregateId,
+        aggregate_type: String,
+        aggregate_version: AggregateVersion,
+        state: R,
+        event_count: usize,
+    ) -> Result<AggregateSnapshot> {
+        let mut compressed_data = Vec::new();
+        let streamed = self.compress_stream(state, &mut compressed_data)?;
+
+        let snapshot = AggregateSnapshot {
+            snapshot_id: Uuid::new_v4(),
+            aggregate_id,
+            aggregate_type,
+            aggregate_version,
+            state_data: compressed_data,
+            compression: self.config.compression.clone(),
+            metadata: SnapshotMetadata {
+                original_size: streamed.original_size,
+                compressed_size: streamed.compressed_size,
+                event_count,
+                checksum: streamed.checksum,
+                custom: HashMap::new(),
+            },
+            created_at: Utc::now(),
+        };
+
+        self.store.save_snapshot(snapshot.clone()).await?;
+        Ok(snapshot)
+    }
+
+    /// Compress everything read from `reader` into `writer` with the configured
+    /// algorithm, a chunk at a time, hashing the output as it is written
+    pub fn compress_stream<R: Read, W: Write>(&self, mut reader: R, writer: W) -> Result<StreamedSnapshotData> {
+        use sha2::Digest;
+
+        self.config.compression.validate()?;
+        let mut out = ChecksumWriter { inner: writer, hasher: sha2::Sha256::new(), written: 0 };
+        let original_size = match self.config.compression {
+            SnapshotCompression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(&mut out, flate2::Compression::default());
+                let copied = std::io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?;
+                copied
+            }
+            SnapshotCompression::Lz4 => {
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(&mut out);
+                let copied = std::io::copy(&mut reader, &mut encoder)?;
+                encoder.finish().map_err(lz4_error)?;
+                copied
+            }
+            SnapshotCompression::Zstd { level } => {
+                let mut encoder = zstd::stream::write::Encoder::new(&mut out, level)?;
+                let copied = std::io::copy(&mut reader, &mut encoder)?;
+                encoder.finish()?;
+                copied
+            }
+            SnapshotCompression::None => std::io::copy(&mut reader, &mut out)?,
+        };
+        out.flush()?;
+
+        Ok(StreamedSnapshotData {
+            original_size: original_size as usize,
+            compressed_size: out.written,
+            checksum: format!("{:x}", out.hasher.finalize()),
+        })
+    }
+
     /// Decompress snapshot data
     pub fn decompress_snapshot_data(&self, snapshot: &AggregateSnapshot) -> Result<Vec<u8>> {
         self.decompress_data(&snapshot.state_data, &snapshot.compression)
     }
 
+    /// Reader yielding a snapshot's decompressed state as it is inflated,
+    /// so large states can be decoded without a full decompressed copy.
+    ///
+    /// Fails up front if the zstd decoder cannot be set up, and while reading
+    /// once the state passes `SnapshotConfig::max_decompressed_size`.
+    pub fn snapshot_state_reader<'a>(&self, snapshot: &'a AggregateSnapshot) -> Result<Box<dyn Read + 'a>> {
+        let data = snapshot.state_data.as_slice();
+        let max = self.config.max_decompressed_size;
+        Ok(match snapshot.compression {
+            SnapshotCompression::Gzip => Box::new(CappedReader::new(flate2::read::GzDecoder::new(data), max)),
+            SnapshotCompression::Lz4 if is_lz4_frame(data) => {
+                Box::new(CappedReader::new(lz4_flex::frame::FrameDecoder::new(data), max))
+            }
+            SnapshotCompression::Lz4 => Box::new(data),
+            SnapshotCompression::Zstd { .. } => {
+                Box::new(CappedReader::new(zstd::stream::read::Decoder::with_buffer(data)?, max))
+            }
+            SnapshotCompression::None => Box::new(data),
+        })
+    }
+
+    /// Decompress a snapshot's state into `writer` a chunk at a time,
+    /// returning the number of decompressed bytes written
+    pub fn decompress_snapshot_to<W: Write>(&self, snapshot: &AggregateSnapshot, mut writer: W) -> Result<u64> {
+        let written = std::io::copy(&mut self.snapshot_state_reader(snapshot)?, &mut writer)?;
+        writer.flush()?;
+        Ok(written)
+    }
+
+    /// Decompress and decode the state of a snapshot created with
+    /// `create_snapshot_from_state`.
+    ///
+    /// Snapshots recorded with a different codec are rejected rather than
+    /// misread; snapshots without a recorded codec are decoded as-is.
+    pub fn decode_snapshot_state<T: DeserializeOwned>(&self, snapshot: &AggregateSnapshot) -> Result<T> {
+        if let Some(name) = snapshot.metadata.custom.get(STATE_CODEC_METADATA_KEY) {
+            if name != self.state_codec.name() {
+                return Err(EventualiError::Configuration(format!(
+                    "Snapshot {} state was encoded with '{}', but this service uses '{}'",
+                    snapshot.snapshot_id,
+                    name,
+                    self.state_codec.name()
+                )));
+            }
+        }
+
+        let state_data = self.decompress_snapshot_data(snapshot)?;
+        Ok(serde_json::from_value(self.state_codec.decode(&state_data)?)?)
+    }
+
     /// Check if a snapshot should be taken
     pub async fn should_take_snapshot(
         &self,
         aggregate_id: &AggregateId,
+        aggregate_type: &str,
         current_version: AggregateVersion,
     ) -> Result<bool> {
-        self.store.should_take_snapshot(aggregate_id, current_version, &self.config).await
+        self.store.should_take_snapshot(aggregate_id, aggregate_type, current_version, &self.config).await
     }
 
     /// Compress data using the configured compression algorithm
     fn compress_data(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.config.compression.validate()?;
         match self.config.compression {
             SnapshotCompression::None => Ok(data.to_vec()),
             SnapshotCompression::Gzip => {
@@ -186,31 +555,32 @@ impl<S: SnapshotStore> SnapshotService<S> {
                 encoder.finish().map_err(EventualiError::Io)
             }
             SnapshotCompression::Lz4 => {
-                // For now, fallback to no compression if lz4 is not available
-                // In a real implementation, you'd use lz4_flex or similar crate
-                Ok(data.to_vec())
+                let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+                encoder.write_all(data).map_err(EventualiError::Io)?;
+                encoder.finish().map_err(lz4_error)
             }
+            SnapshotCompression::Zstd { level } => zstd::bulk::compress(data, level).map_err(EventualiError::Io),
         }
     }
 
     /// Decompress data using the specified compression algorithm
     fn decompress_data(&self, data: &[u8], compression: &SnapshotCompression) -> Result<Vec<u8>> {
+        let max = self.config.max_decompressed_size;
+        let mut decompressed = Vec::new();
         match compression {
-            SnapshotCompression::None => Ok(data.to_vec()),
+            SnapshotCompression::None => return Ok(data.to_vec()),
             SnapshotCompression::Gzip => {
-                use flate2::read::GzDecoder;
-                use std::io::Read;
-
-                let mut decoder = GzDecoder::new(data);
-                let mut decompressed = Vec::new();
-                decoder.read_to_end(&mut decompressed).map_err(EventualiError::Io)?;
-                Ok(decompressed)
+                CappedReader::new(flate2::read::GzDecoder::new(data), max).read_to_end(&mut decompressed)?;
             }
-            SnapshotCompression::Lz4 => {
-                // For now, fallback to no compression
-                Ok(data.to_vec())
+            SnapshotCompression::Lz4 if is_lz4_frame(data) => {
+                CappedReader::new(lz4_flex::frame::FrameDecoder::new(data), max).read_to_end(&mut decompressed)?;
+            }
+            SnapshotCompression::Lz4 => return Ok(data.to_vec()),
+            SnapshotCompression::Zstd { .. } => {
+                CappedReader::new(zstd::stream::read::Decoder::new(data)?, max).read_to_end(&mut decompressed)?;
             }
         }
+        Ok(decompressed)
     }
 
     /// Calculate checksum for data integrity
@@ -221,12 +591,30 @@ impl<S: SnapshotStore> SnapshotService<S> {
         format!("{:x}", hasher.finalize())
     }
 
+    /// Delete every snapshot of an aggregate, as part of erasing its data
+    pub async fn delete_aggregate_snapshots(&self, aggregate_id: &AggregateId) -> Result<u64> {
+        self.store.delete_aggregate_snapshots(aggregate_id).await
+    }
+
     /// Perform cleanup of old snapshots
     pub async fn cleanup_old_snapshots(&self) -> Result<u64> {
         self.store.cleanup_old_snapshots(&self.config).await
     }
 }
 
+/// Magic number opening every LZ4 frame, little-endian
+const LZ4_FRAME_MAGIC: [u8; 4] = 0x184D_2204u32.to_le_bytes();
+
+/// Whether `data` is an LZ4 frame. Lz4 snapshots written before frames were
+/// used hold the state uncompressed, and are read back as they are.
+fn is_lz4_frame(data: &[u8]) -> bool {
+    data.starts_with(&LZ4_FRAME_MAGIC)
+}
+
+fn lz4_error(error: lz4_flex::frame::Error) -> EventualiError {
+    EventualiError::Io(std::io::Error::other(error))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,8 +635,9 @@ mod tests {
             async fn load_snapshot(&self, _: Uuid) -> Result<Option<AggregateSnapshot>> { Ok(None) }
             async fn list_snapshots(&self, _: &AggregateId) -> Result<Vec<AggregateSnapshot>> { Ok(vec![]) }
             async fn delete_snapshot(&self, _: Uuid) -> Result<()> { Ok(()) }
+            async fn delete_aggregate_snapshots(&self, _: &AggregateId) -> Result<u64> { Ok(0) }
             async fn cleanup_old_snapshots(&self, _: &SnapshotConfig) -> Result<u64> { Ok(0) }
-            async fn should_take_snapshot(&self, _: &AggregateId, _: AggregateVersion, _: &SnapshotConfig) -> Result<bool> { Ok(false) }
+            async fn should_take_snapshot(&self, _: &AggregateId, _: &str, _: AggregateVersion, _: &SnapshotConfig) -> Result<bool> { Ok(false) }
         }
         
         let service = SnapshotService::new(MockStore, config);
@@ -258,6 +647,335 @@ mod tests {
         assert_eq!(compressed, data);
     }
 
+    #[tokio::test]
+    async fn test_structured_state_round_trips_through_codec() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct CartState {
+            customer: String,
+            items: Vec<(String, u32)>,
+            total_cents: u64,
+            discount: Option<f64>,
+        }
+
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let service = SnapshotService::new(store, SnapshotConfig::default())
+            .with_state_codec(Arc::new(MessagePackStateCodec));
+
+        let state = CartState {
+            customer: "alice".to_string(),
+            items: vec![("sku-1".to_string(), 2), ("sku-2".to_string(), 1)],
+            total_cents: 4_500,
+            discount: Some(0.1),
+        };
+        service
+            .create_snapshot_from_state("cart-1".to_string(), "Cart".to_string(), 3, &state, 3)
+            .await
+            .unwrap();
+
+        let snapshot = service.load_latest_snapshot(&"cart-1".to_string()).await.unwrap().unwrap();
+        assert_eq!(snapshot.metadata.custom.get(STATE_CODEC_METADATA_KEY).map(String::as_str), Some("msgpack"));
+        assert_eq!(service.decode_snapshot_state::<CartState>(&snapshot).unwrap(), state);
+
+        // A service using a different codec refuses to misread the state
+        let pool = sqlx::sqlite::SqlitePoolOptions::new().connect("sqlite://:memory:").await.unwrap();
+        let json_service = SnapshotService::new(SqliteSnapshotStore::new(pool, None), SnapshotConfig::default());
+        assert!(matches!(
+            json_service.decode_snapshot_state::<CartState>(&snapshot),
+            Err(EventualiError::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_snapshots_are_deleted_together() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let service = SnapshotService::new(store, SnapshotConfig::default());
+
+        for (aggregate_id, version) in [("cart-1", 5), ("cart-1", 10), ("cart-2", 5)] {
+            service
+                .create_snapshot_from_state(aggregate_id.to_string(), "Cart".to_string(), version, &version, version as usize)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(service.delete_aggregate_snapshots(&"cart-1".to_string()).await.unwrap(), 2);
+        assert!(service.load_latest_snapshot(&"cart-1".to_string()).await.unwrap().is_none());
+        assert!(service.load_latest_snapshot(&"cart-2".to_string()).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_types_snapshot_at_their_own_frequency() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let config = SnapshotConfig { snapshot_frequency: 50, ..Default::default() }
+            .with_frequency_for("Cart", 5)
+            .unwrap()
+            .with_frequency_for("Customer", 20)
+            .unwrap();
+        let service = SnapshotService::new(store, config);
+
+        let mut due = HashMap::new();
+        for (aggregate_id, aggregate_type) in [("cart-1", "Cart"), ("customer-1", "Customer"), ("invoice-1", "Invoice")] {
+            let mut versions = Vec::new();
+            for version in 1..=60 {
+                if service.should_take_snapshot(&aggregate_id.to_string(), aggregate_type, version).await.unwrap() {
+                    versions.push(version);
+                }
+            }
+            due.insert(aggregate_type, versions);
+        }
+
+        assert_eq!(due["Cart"], (1..=12).map(|n| n * 5).collect::<Vec<_>>());
+        assert_eq!(due["Customer"], vec![20, 40, 60]);
+        // Types without an override keep the global frequency
+        assert_eq!(due["Invoice"], vec![50]);
+
+        // A version already snapshotted is not due again
+        service.create_snapshot("cart-1".to_string(), "Cart".to_string(), 10, b"{}".to_vec(), 10).await.unwrap();
+        assert!(!service.should_take_snapshot(&"cart-1".to_string(), "Cart", 10).await.unwrap());
+
+        // A frequency of 0 is rejected rather than dividing by zero
+        assert!(matches!(SnapshotConfig::default().with_frequency_for("Cart", 0), Err(EventualiError::Configuration(_))));
+        let pool = sqlx::sqlite::SqlitePoolOptions::new().connect("sqlite://:memory:").await.unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let zero = SnapshotService::new(store, SnapshotConfig { snapshot_frequency: 0, ..Default::default() });
+        assert!(matches!(
+            zero.should_take_snapshot(&"cart-1".to_string(), "Cart", 10).await,
+            Err(EventualiError::Configuration(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_large_state_round_trips_through_chunked_compression() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let service = SnapshotService::new(store, SnapshotConfig::default());
+
+        let state: Vec<u8> = (0..200_000u32)
+            .flat_map(|i| format!("{{\"line\":{i},\"sku\":\"sku-{}\"}}\n", i % 977).into_bytes())
+            .collect();
+        let snapshot = service
+            .create_snapshot_from_reader("ledger-1".to_string(), "Ledger".to_string(), 500, state.as_slice(), 500)
+            .await
+            .unwrap();
+
+        assert_eq!(snapshot.metadata.original_size, state.len());
+        assert_eq!(snapshot.metadata.compressed_size, snapshot.state_data.len());
+        assert!(snapshot.state_data.len() < state.len() / 4);
+        assert_eq!(snapshot.metadata.checksum, service.calculate_checksum(&snapshot.state_data));
+
+        let loaded = service.load_latest_snapshot(&"ledger-1".to_string()).await.unwrap().unwrap();
+        let mut decompressed = Vec::new();
+        assert_eq!(service.decompress_snapshot_to(&loaded, &mut decompressed).unwrap(), state.len() as u64);
+        assert!(decompressed == state);
+        // The in-memory path reads the same snapshot identically
+        assert!(service.decompress_snapshot_data(&loaded).unwrap() == state);
+    }
+
+    #[tokio::test]
+    async fn test_lz4_snapshots_are_compressed_and_round_trip() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool, None);
+        store.initialize().await.unwrap();
+        let config = SnapshotConfig { compression: SnapshotCompression::Lz4, ..Default::default() };
+        let service = SnapshotService::new(store, config);
+
+        let state: Vec<u8> = (0..100 * 1024u32).map(|i| b"{\"sku\":\"sku-42\"}"[(i % 16) as usize]).collect();
+        let snapshot = service
+            .create_snapshot("order-1".to_string(), "Order".to_string(), 7, state.clone(), 7)
+            .await
+            .unwrap();
+
+        assert_eq!(snapshot.compression, SnapshotCompression::Lz4);
+        assert_eq!(snapshot.metadata.original_size, state.len());
+        assert_eq!(snapshot.metadata.compressed_size, snapshot.state_data.len());
+        assert!(snapshot.metadata.compressed_size < snapshot.metadata.original_size);
+
+        let loaded = service.load_latest_snapshot(&"order-1".to_string()).await.unwrap().unwrap();
+        assert!(service.decompress_snapshot_data(&loaded).unwrap() == state);
+
+        // The streaming paths write and read the same format
+        let mut streamed = Vec::new();
+        let sizes = service.compress_stream(state.as_slice(), &mut streamed).unwrap();
+        assert_eq!(sizes.compressed_size, streamed.len());
+        assert!(service.decompress_data(&streamed, &SnapshotCompression::Lz4).unwrap() == state);
+        let mut decompressed = Vec::new();
+        service.decompress_snapshot_to(&loaded, &mut decompressed).unwrap();
+        assert!(decompressed == state);
+
+        // Lz4 snapshots from before frames were written hold the raw state
+        let legacy = AggregateSnapshot {
+            state_data: state.clone(),
+            metadata: SnapshotMetadata {
+                compressed_size: state.len(),
+                checksum: service.calculate_checksum(&state),
+                ..loaded.metadata.clone()
+            },
+            ..loaded.clone()
+        };
+        assert!(!is_lz4_frame(&legacy.state_data));
+        assert!(service.decompress_snapshot_data(&legacy).unwrap() == state);
+        let mut decompressed = Vec::new();
+        service.decompress_snapshot_to(&legacy, &mut decompressed).unwrap();
+        assert!(decompressed == state);
+    }
+
+    #[tokio::test]
+    async fn test_zstd_snapshots_round_trip_alongside_gzip() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool.clone(), None);
+        store.initialize().await.unwrap();
+        let state: Vec<u8> = (0..20_000u32)
+            .flat_map(|i| format!("{{\"line\":{i},\"sku\":\"sku-{}\"}}\n", i % 97).into_bytes())
+            .collect();
+
+        // A gzip snapshot written before switching to zstd keeps loading
+        let gzip = SnapshotService::new(store, SnapshotConfig::default());
+        gzip.create_snapshot("order-1".to_string(), "Order".to_string(), 5, state.clone(), 5).await.unwrap();
+
+        let config = SnapshotConfig::default().with_compression(SnapshotCompression::zstd(9).unwrap()).unwrap();
+        let zstd = SnapshotService::new(SqliteSnapshotStore::new(pool.clone(), None), config);
+        let snapshot = zstd
+            .create_snapshot("order-1".to_string(), "Order".to_string(), 10, state.clone(), 10)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.metadata.compressed_size, snapshot.state_data.len());
+        assert!(snapshot.metadata.compressed_size < state.len() / 4);
+
+        let snapshots = zstd.store.list_snapshots(&"order-1".to_string()).await.unwrap();
+        assert_eq!(
+            snapshots.iter().map(|s| s.compression.clone()).collect::<Vec<_>>(),
+            vec![SnapshotCompression::Zstd { level: 9 }, SnapshotCompression::Gzip]
+        );
+        for snapshot in &snapshots {
+            assert!(zstd.decompress_snapshot_data(snapshot).unwrap() == state);
+            let mut streamed = Vec::new();
+            zstd.decompress_snapshot_to(snapshot, &mut streamed).unwrap();
+            assert!(streamed == state);
+        }
+
+        let mut streamed = Vec::new();
+        zstd.compress_stream(state.as_slice(), &mut streamed).unwrap();
+        assert!(zstd.decompress_data(&streamed, &SnapshotCompression::Zstd { level: 9 }).unwrap() == state);
+
+        for level in [0, 23] {
+            assert!(matches!(SnapshotCompression::zstd(level), Err(EventualiError::Configuration(_))));
+            let out_of_range = SnapshotCompression::Zstd { level };
+            assert!(SnapshotConfig::default().with_compression(out_of_range.clone()).is_err());
+            // A level set directly on the config is rejected when compressing
+            let config = SnapshotConfig { compression: out_of_range, ..Default::default() };
+            let service = SnapshotService::new(SqliteSnapshotStore::new(pool.clone(), None), config);
+            assert!(matches!(service.compress_data(b"{}"), Err(EventualiError::Configuration(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decompression_stops_at_the_state_size_cap() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool.clone(), None);
+        store.initialize().await.unwrap();
+        let state = vec![b'0'; 2 * 1024 * 1024];
+
+        for compression in [SnapshotCompression::Gzip, SnapshotCompression::Lz4, SnapshotCompression::zstd(19).unwrap()] {
+            let writer = SnapshotService::new(
+                SqliteSnapshotStore::new(pool.clone(), None),
+                SnapshotConfig::default().with_compression(compression.clone()).unwrap(),
+            );
+            let compressed = writer.compress_data(&state).unwrap();
+
+            let exact = SnapshotConfig { max_decompressed_size: state.len(), ..SnapshotConfig::default() };
+            let exact = SnapshotService::new(SqliteSnapshotStore::new(pool.clone(), None), exact);
+            assert!(exact.decompress_data(&compressed, &compression).unwrap() == state);
+
+            let capped = SnapshotConfig { max_decompressed_size: 1024 * 1024, ..SnapshotConfig::default() };
+            let capped = SnapshotService::new(SqliteSnapshotStore::new(pool.clone(), None), capped);
+            let err = capped.decompress_data(&compressed, &compression).unwrap_err();
+            assert!(err.to_string().contains("exceeds the limit"), "{err}");
+
+            let snapshot = writer
+                .create_snapshot(format!("bomb-{compression:?}"), "Order".to_string(), 1, state.clone(), 1)
+                .await
+                .unwrap();
+            let mut streamed = Vec::new();
+            assert!(capped.decompress_snapshot_to(&snapshot, &mut streamed).is_err());
+            assert!(streamed.len() <= 1024 * 1024);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verified_load_rejects_a_corrupted_snapshot() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite://:memory:")
+            .await
+            .unwrap();
+        let store = SqliteSnapshotStore::new(pool.clone(), None);
+        store.initialize().await.unwrap();
+        let service = SnapshotService::new(store, SnapshotConfig::default());
+        let state = br#"{"balance":100,"owner":"alice"}"#.to_vec();
+        service.create_snapshot("account-1".to_string(), "Account".to_string(), 3, state.clone(), 3).await.unwrap();
+
+        let id = "account-1".to_string();
+        let intact = service.load_latest_snapshot_verified(&id).await.unwrap().unwrap();
+        assert!(service.decompress_snapshot_data(&intact).unwrap() == state);
+
+        // Flip one byte of the stored blob
+        let mut corrupted = intact.state_data.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0x01;
+        sqlx::query("UPDATE aggregate_snapshots SET state_data = ?")
+            .bind(&corrupted)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        match service.load_latest_snapshot_verified(&id).await {
+            Err(EventualiError::Validation(message)) => assert_eq!(message, "snapshot checksum mismatch"),
+            other => panic!("expected a checksum mismatch, got {other:?}"),
+        }
+        let unverified = service.load_latest_snapshot(&id).await.unwrap().unwrap();
+        assert_eq!(unverified.state_data, corrupted);
+
+        let config = SnapshotConfig::default().with_verify_checksum_on_load(true);
+        let verifying = SnapshotService::new(SqliteSnapshotStore::new(pool, None), config);
+        assert!(matches!(verifying.load_latest_snapshot(&id).await, Err(EventualiError::Validation(_))));
+        assert!(verifying.load_latest_snapshot(&"account-2".to_string()).await.unwrap().is_none());
+    }
+
     #[test]
     fn test_snapshot_config_default() {
         let config = SnapshotConfig::default();
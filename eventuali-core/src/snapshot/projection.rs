@@ -0,0 +1,178 @@
+use crate::{Result, EventualiError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{sqlite::SqlitePool, Row};
+use uuid::Uuid;
+
+/// Represents a snapshot of a projection's read-model state at a global position
+#[derive(Debug, Clone)]
+pub struct ProjectionSnapshot {
+    /// Unique identifier for the snapshot
+    pub snapshot_id: Uuid,
+    /// Name of the projection this snapshot belongs to
+    pub projection_name: String,
+    /// Global position of the last event folded into this state
+    pub position: u64,
+    /// Serialized projection state
+    pub state_data: Vec<u8>,
+    /// When this snapshot was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl ProjectionSnapshot {
+    pub fn new(projection_name: String, position: u64, state_data: Vec<u8>) -> Self {
+        Self {
+            snapshot_id: Uuid::new_v4(),
+            projection_name,
+            position,
+            state_data,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Trait for projection snapshot storage backends
+#[async_trait]
+pub trait ProjectionSnapshotStore {
+    /// Store a new projection snapshot
+    async fn save_snapshot(&self, snapshot: ProjectionSnapshot) -> Result<()>;
+
+    /// Load the snapshot with the highest position for a projection
+    async fn load_latest_snapshot(&self, projection_name: &str) -> Result<Option<ProjectionSnapshot>>;
+
+    /// Delete every snapshot of a projection, returning how many were removed
+    async fn delete_snapshots(&self, projection_name: &str) -> Result<u64>;
+}
+
+/// SQLite-backed projection snapshot store
+pub struct SqliteProjectionSnapshotStore {
+    pool: SqlitePool,
+    table_name: String,
+}
+
+impl SqliteProjectionSnapshotStore {
+    pub fn new(pool: SqlitePool, table_name: Option<String>) -> Self {
+        Self {
+            pool,
+            table_name: table_name.unwrap_or_else(|| "projection_snapshots".to_string()),
+        }
+    }
+
+    pub async fn initialize(&self) -> Result<()> {
+        let create_table = format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {} (
+                snapshot_id TEXT PRIMARY KEY,
+                projection_name TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                state_data BLOB NOT NULL,
+                created_at TEXT NOT NULL,
+                UNIQUE(projection_name, position)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_{}_projection_position ON {} (projection_name, position DESC);
+            "#,
+            self.table_name,
+            self.table_name, self.table_name
+        );
+
+        sqlx::query(&create_table)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProjectionSnapshotStore for SqliteProjectionSnapshotStore {
+    async fn save_snapshot(&self, snapshot: ProjectionSnapshot) -> Result<()> {
+        let query = format!(
+            r#"
+            INSERT INTO {} (snapshot_id, projection_name, position, state_data, created_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+            self.table_name
+        );
+
+        sqlx::query(&query)
+            .bind(snapshot.snapshot_id.to_string())
+            .bind(&snapshot.projection_name)
+            .bind(snapshot.position as i64)
+            .bind(&snapshot.state_data)
+            .bind(snapshot.created_at.to_rfc3339())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                    EventualiError::SnapshotConflict(format!(
+                        "Snapshot already exists for projection {} at position {}",
+                        snapshot.projection_name, snapshot.position
+                    ))
+                }
+                _ => EventualiError::Database(e),
+            })?;
+
+        Ok(())
+    }
+
+    async fn load_latest_snapshot(&self, projection_name: &str) -> Result<Option<ProjectionSnapshot>> {
+        let query = format!(
+            r#"
+            SELECT snapshot_id, projection_name, position, state_data, created_at
+            FROM {}
+            WHERE projection_name = ?
+            ORDER BY position DESC
+            LIMIT 1
+            "#,
+            self.table_name
+        );
+
+        let row = sqlx::query(&query)
+            .bind(projection_name)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if let Some(row) = row {
+            Ok(Some(self.row_to_snapshot(row)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn delete_snapshots(&self, projection_name: &str) -> Result<u64> {
+        let query = format!("DELETE FROM {} WHERE projection_name = ?", self.table_name);
+
+        let result = sqlx::query(&query)
+            .bind(projection_name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+impl SqliteProjectionSnapshotStore {
+    fn row_to_snapshot(&self, row: sqlx::sqlite::SqliteRow) -> Result<ProjectionSnapshot> {
+        let snapshot_id_str: String = row.try_get("snapshot_id")?;
+        let snapshot_id = Uuid::parse_str(&snapshot_id_str)
+            .map_err(|_| EventualiError::InvalidEventData("Invalid snapshot UUID format".to_string()))?;
+
+        let projection_name: String = row.try_get("projection_name")?;
+        let position: i64 = row.try_get("position")?;
+        let state_data: Vec<u8> = row.try_get("state_data")?;
+        let created_at_str: String = row.try_get("created_at")?;
+
+        let created_at: DateTime<Utc> = DateTime::parse_from_rfc3339(&created_at_str)
+            .map_err(|_| EventualiError::InvalidEventData("Invalid timestamp format".to_string()))?
+            .with_timezone(&Utc);
+
+        Ok(ProjectionSnapshot {
+            snapshot_id,
+            projection_name,
+            position: position as u64,
+            state_data,
+            created_at,
+        })
+    }
+}
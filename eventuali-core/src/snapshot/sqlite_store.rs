@@ -58,9 +58,10 @@ impl SqliteSnapshotStore {
 impl SnapshotStore for SqliteSnapshotStore {
     async fn save_snapshot(&self, snapshot: AggregateSnapshot) -> Result<()> {
         let compression_str = match snapshot.compression {
-            SnapshotCompression::None => "none",
-            SnapshotCompression::Gzip => "gzip",
-            SnapshotCompression::Lz4 => "lz4",
+            SnapshotCompression::None => "none".to_string(),
+            SnapshotCompression::Gzip => "gzip".to_string(),
+            SnapshotCompression::Lz4 => "lz4".to_string(),
+            SnapshotCompression::Zstd { level } => format!("zstd:{level}"),
         };
 
         let metadata_json = serde_json::to_string(&snapshot.metadata)?;
@@ -88,7 +89,7 @@ impl SnapshotStore for SqliteSnapshotStore {
             .await
             .map_err(|e| match e {
                 sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
-                    EventualiError::Configuration(format!(
+                    EventualiError::SnapshotConflict(format!(
                         "Snapshot already exists for aggregate {} at version {}",
                         snapshot.aggregate_id, snapshot.aggregate_version
                     ))
@@ -183,6 +184,17 @@ impl SnapshotStore for SqliteSnapshotStore {
         Ok(())
     }
 
+    async fn delete_aggregate_snapshots(&self, aggregate_id: &AggregateId) -> Result<u64> {
+        let query = format!("DELETE FROM {} WHERE aggregate_id = ?", self.table_name);
+
+        let result = sqlx::query(&query)
+            .bind(aggregate_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     async fn cleanup_old_snapshots(&self, config: &SnapshotConfig) -> Result<u64> {
         if !config.auto_cleanup {
             return Ok(0);
@@ -206,11 +218,13 @@ impl SnapshotStore for SqliteSnapshotStore {
     async fn should_take_snapshot(
         &self,
         aggregate_id: &AggregateId,
+        aggregate_type: &str,
         current_version: AggregateVersion,
         config: &SnapshotConfig,
     ) -> Result<bool> {
-        // Check if we should take a snapshot based on frequency
-        if current_version % config.snapshot_frequency != 0 {
+        // Check if we should take a snapshot based on the type's frequency
+        config.validate()?;
+        if current_version % config.frequency_for(aggregate_type) != 0 {
             return Ok(false);
         }
 
@@ -249,9 +263,12 @@ impl SqliteSnapshotStore {
             "none" => SnapshotCompression::None,
             "gzip" => SnapshotCompression::Gzip,
             "lz4" => SnapshotCompression::Lz4,
-            _ => return Err(EventualiError::InvalidEventData(format!(
-                "Unknown compression type: {compression_str}"
-            ))),
+            other => match other.strip_prefix("zstd:").and_then(|level| level.parse().ok()) {
+                Some(level) => SnapshotCompression::Zstd { level },
+                None => return Err(EventualiError::InvalidEventData(format!(
+                    "Unknown compression type: {compression_str}"
+                ))),
+            },
         };
 
         let metadata = serde_json::from_str(&metadata_json)?;
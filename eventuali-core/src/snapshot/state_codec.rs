@@ -0,0 +1,75 @@
+//! Encodings for snapshot state
+//!
+//! `AggregateSnapshot::state_data` is opaque bytes. A `StateCodec` turns a
+//! serializable state object into those bytes and back, so every snapshot of a
+//! service is written in one format and the format is recorded alongside it.
+
+use crate::{EventualiError, Result};
+use serde_json::Value;
+
+/// Metadata key recording which codec encoded a snapshot's state
+pub const STATE_CODEC_METADATA_KEY: &str = "state_codec";
+
+/// A wire format for snapshot state.
+///
+/// State passes through a `serde_json::Value`, which keeps the trait object
+/// safe so custom codecs can be plugged into `SnapshotService`.
+pub trait StateCodec: Send + Sync {
+    /// Stable identifier recorded in the metadata of every snapshot it encodes
+    fn name(&self) -> &str;
+
+    /// Encode state into the bytes stored as `state_data`, before compression
+    fn encode(&self, state: &Value) -> Result<Vec<u8>>;
+
+    /// Decode bytes previously produced by `encode`
+    fn decode(&self, bytes: &[u8]) -> Result<Value>;
+}
+
+/// State stored as UTF-8 JSON
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonStateCodec;
+
+impl StateCodec for JsonStateCodec {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn encode(&self, state: &Value) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(state)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// State stored as MessagePack, usually smaller and faster to parse than JSON
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePackStateCodec;
+
+impl StateCodec for MessagePackStateCodec {
+    fn name(&self) -> &str {
+        "msgpack"
+    }
+
+    fn encode(&self, state: &Value) -> Result<Vec<u8>> {
+        rmp_serde::to_vec_named(state)
+            .map_err(|e| EventualiError::InvalidEventData(format!("Failed to encode snapshot state as MessagePack: {e}")))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        rmp_serde::from_slice(bytes)
+            .map_err(|e| EventualiError::InvalidEventData(format!("Failed to decode MessagePack snapshot state: {e}")))
+    }
+}
+
+/// Built-in codec for a name, as accepted by configuration and the Python API
+pub fn state_codec_by_name(name: &str) -> Result<Box<dyn StateCodec>> {
+    match name.to_lowercase().as_str() {
+        "json" => Ok(Box::new(JsonStateCodec)),
+        "msgpack" | "messagepack" => Ok(Box::new(MessagePackStateCodec)),
+        other => Err(EventualiError::Configuration(format!(
+            "Unknown snapshot state codec '{other}', expected 'json' or 'msgpack'"
+        ))),
+    }
+}
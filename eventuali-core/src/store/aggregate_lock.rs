@@ -0,0 +1,121 @@
+//! In-process per-aggregate write locks
+//!
+//! Database concurrency control still decides which write wins; these locks
+//! only stop writers in the same process from racing each other, so they queue
+//! instead of failing with `OptimisticConcurrency` and retrying.
+
+use crate::AggregateId;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Async lock per aggregate, created on first use and dropped once unused
+#[derive(Debug, Default)]
+pub struct AggregateLocks {
+    locks: Arc<Mutex<HashMap<AggregateId, Weak<AsyncMutex<()>>>>>,
+}
+
+impl AggregateLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock every aggregate in `aggregate_ids`, waiting for other holders.
+    ///
+    /// Locks are taken in sorted order, so two batches touching the same
+    /// aggregates cannot deadlock on each other.
+    pub async fn lock<'a>(&self, aggregate_ids: impl IntoIterator<Item = &'a AggregateId>) -> AggregateLockGuard {
+        let mut ids: Vec<&AggregateId> = aggregate_ids.into_iter().collect();
+        ids.sort();
+        ids.dedup();
+
+        let mut held = Vec::with_capacity(ids.len());
+        for id in ids {
+            let lock = self.lock_for(id);
+            held.push((id.clone(), lock.lock_owned().await));
+        }
+
+        AggregateLockGuard {
+            locks: self.locks.clone(),
+            held,
+        }
+    }
+
+    /// Number of aggregates that currently have a lock, held or awaited
+    pub fn len(&self) -> usize {
+        self.map().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock_for(&self, aggregate_id: &AggregateId) -> Arc<AsyncMutex<()>> {
+        let mut locks = self.map();
+        if let Some(lock) = locks.get(aggregate_id).and_then(Weak::upgrade) {
+            return lock;
+        }
+        let lock = Arc::new(AsyncMutex::new(()));
+        locks.insert(aggregate_id.clone(), Arc::downgrade(&lock));
+        lock
+    }
+
+    fn map(&self) -> std::sync::MutexGuard<'_, HashMap<AggregateId, Weak<AsyncMutex<()>>>> {
+        self.locks.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Holds aggregate locks until dropped, then reclaims any nobody else wants
+#[derive(Debug)]
+pub struct AggregateLockGuard {
+    locks: Arc<Mutex<HashMap<AggregateId, Weak<AsyncMutex<()>>>>>,
+    held: Vec<(AggregateId, OwnedMutexGuard<()>)>,
+}
+
+impl Drop for AggregateLockGuard {
+    fn drop(&mut self) {
+        let ids: Vec<AggregateId> = self.held.drain(..).map(|(id, _guard)| id).collect();
+
+        // Waiters hold a strong reference, so an entry that no longer upgrades
+        // has no holder and no waiter left
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        for id in ids {
+            if locks.get(&id).is_some_and(|lock| lock.strong_count() == 0) {
+                locks.remove(&id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_aggregate_waits_and_locks_are_reclaimed() {
+        let locks = Arc::new(AggregateLocks::new());
+        let id = "order-1".to_string();
+
+        let guard = locks.lock([&id]).await;
+        assert_eq!(locks.len(), 1);
+
+        let waiter = {
+            let locks = locks.clone();
+            let id = id.clone();
+            tokio::spawn(async move {
+                let _guard = locks.lock([&id]).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        // Other aggregates are not blocked
+        let other = "order-2".to_string();
+        drop(locks.lock([&other, &other]).await);
+
+        drop(guard);
+        waiter.await.unwrap();
+        assert!(locks.is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+//! Compaction of log-structured aggregates
+//!
+//! Aggregates made of "set field X" events only need the latest value of each
+//! field. Compaction replaces a prefix of such a stream with one snapshot event
+//! built by a user-provided `Compactor`. The snapshot takes the version and
+//! global position of the last event it replaces, and every later event is kept
+//! unchanged, so versions, concurrency checks and global order are unaffected.
+//! Each compaction is recorded with the IDs of the events it removed.
+//!
+//! A compacted stream no longer holds its early versions one by one.
+//! `CompactedStream` splits a loaded stream at its latest compaction, so
+//! reconstruction starts from the snapshot and applies only the retained tail.
+
+use crate::aggregate::replay;
+use crate::{AggregateId, AggregateVersion, Event, EventId, EventualiError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Folds a prefix of an aggregate's stream into a single snapshot event
+pub trait Compactor: Send + Sync {
+    /// Build the snapshot event for `events`, the aggregate's full stream in
+    /// version order.
+    ///
+    /// The returned event's `aggregate_version` is the last version it folds
+    /// in: every event up to and including it is replaced, later events are
+    /// retained. Replaying the snapshot followed by the retained events must
+    /// produce the same state as replaying the original stream. Return `None`
+    /// to leave the stream as it is.
+    fn compact(&self, events: &[Event]) -> Result<Option<Event>>;
+}
+
+/// Outcome of compacting one aggregate, also kept as its audit record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub compaction_id: Uuid,
+    pub aggregate_id: AggregateId,
+    /// Last version folded into the snapshot; zero when nothing was compacted
+    pub compacted_through: AggregateVersion,
+    /// The event that replaced the compacted prefix, if any
+    pub snapshot_event_id: Option<EventId>,
+    /// Events removed from the stream, in version order
+    pub removed_event_ids: Vec<EventId>,
+    pub events_before: usize,
+    pub events_after: usize,
+    pub compacted_at: DateTime<Utc>,
+}
+
+impl CompactionReport {
+    /// Report for a stream of `events` events that was left as it was
+    pub(crate) fn unchanged(aggregate_id: &AggregateId, events: usize) -> Self {
+        Self {
+            compaction_id: Uuid::new_v4(),
+            aggregate_id: aggregate_id.clone(),
+            compacted_through: 0,
+            snapshot_event_id: None,
+            removed_event_ids: Vec::new(),
+            events_before: events,
+            events_after: events,
+            compacted_at: Utc::now(),
+        }
+    }
+
+    /// Whether the stream was rewritten
+    pub fn compacted(&self) -> bool {
+        self.snapshot_event_id.is_some()
+    }
+}
+
+/// An aggregate's stream split at its latest compaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompactedStream {
+    pub aggregate_id: AggregateId,
+    /// Snapshot event standing in for every version through
+    /// `compacted_through`; `None` when the stream was never compacted
+    pub base: Option<Event>,
+    /// Last version folded into `base`; zero when the stream was never compacted
+    pub compacted_through: AggregateVersion,
+    /// Events after the compaction point, in version order
+    pub tail: Vec<Event>,
+}
+
+impl CompactedStream {
+    /// Split `events`, the aggregate's full stream in version order, at the
+    /// latest compaction in `history`. Fails if that compaction's snapshot is
+    /// not among `events`, as when the stream was compacted after it was loaded.
+    pub fn split(aggregate_id: &AggregateId, events: Vec<Event>, history: &[CompactionReport]) -> Result<Self> {
+        let latest = history.iter().rev().find(|report| report.compacted());
+        let (compacted_through, snapshot_id) = latest
+            .map(|report| (report.compacted_through, report.snapshot_event_id))
+            .unwrap_or((0, None));
+
+        let mut base = None;
+        let mut tail = Vec::with_capacity(events.len());
+        for event in events {
+            if Some(event.id) == snapshot_id {
+                base = Some(event);
+            } else {
+                tail.push(event);
+            }
+        }
+        if snapshot_id.is_some() && base.is_none() {
+            return Err(EventualiError::InvalidState(format!(
+                "Compaction snapshot of aggregate {aggregate_id} is missing from its stream"
+            )));
+        }
+
+        Ok(Self { aggregate_id: aggregate_id.clone(), base, compacted_through, tail })
+    }
+
+    /// Whether history before the compaction point has been folded away
+    pub fn is_compacted(&self) -> bool {
+        self.base.is_some()
+    }
+
+    /// Version of the last event in the stream
+    pub fn version(&self) -> Option<AggregateVersion> {
+        self.tail.last().or(self.base.as_ref()).map(|event| event.aggregate_version)
+    }
+
+    /// Rebuild state from `initial`: `restore` seeds it from the compaction
+    /// snapshot, if any, then `apply` folds in each retained event. Failures
+    /// name the event as `replay` does.
+    pub fn reconstruct<S>(
+        &self,
+        initial: S,
+        mut restore: impl FnMut(&mut S, &Event) -> Result<()>,
+        apply: impl FnMut(&mut S, &Event) -> Result<()>,
+    ) -> Result<S> {
+        let state = match &self.base {
+            Some(base) => replay(initial, std::slice::from_ref(base), &mut restore)?,
+            None => initial,
+        };
+        replay(state, &self.tail, apply)
+    }
+}
+
+/// A validated compaction, ready for a backend to apply
+pub(crate) struct CompactionPlan {
+    pub snapshot: Event,
+    pub removed: Vec<Event>,
+    pub report: CompactionReport,
+}
+
+/// Ask `compactor` for a snapshot of `events` and check it can replace the
+/// prefix it claims to cover. `None` when the compactor declines.
+pub(crate) fn plan_compaction(
+    aggregate_id: &AggregateId,
+    events: Vec<Event>,
+    compactor: &dyn Compactor,
+) -> Result<Option<CompactionPlan>> {
+    let snapshot = match compactor.compact(&events)? {
+        Some(snapshot) => snapshot,
+        None => return Ok(None),
+    };
+
+    if &snapshot.aggregate_id != aggregate_id {
+        return Err(EventualiError::Validation(format!(
+            "Compaction snapshot belongs to aggregate {}, not {}",
+            snapshot.aggregate_id, aggregate_id
+        )));
+    }
+    let through = snapshot.aggregate_version;
+    if !events.iter().any(|e| e.aggregate_version == through) {
+        return Err(EventualiError::Validation(format!(
+            "Compaction snapshot version {through} is not a version of aggregate {aggregate_id}"
+        )));
+    }
+    if events.iter().any(|e| e.id == snapshot.id) {
+        return Err(EventualiError::Validation(
+            "Compaction snapshot must be a new event, not one already in the stream".to_string(),
+        ));
+    }
+
+    let events_before = events.len();
+    let removed: Vec<Event> = events.into_iter().filter(|e| e.aggregate_version <= through).collect();
+    let report = CompactionReport {
+        compacted_through: through,
+        snapshot_event_id: Some(snapshot.id),
+        removed_event_ids: removed.iter().map(|e| e.id).collect(),
+        events_after: events_before - removed.len() + 1,
+        ..CompactionReport::unchanged(aggregate_id, events_before)
+    };
+
+    Ok(Some(CompactionPlan { snapshot, removed, report }))
+}
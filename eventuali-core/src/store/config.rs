@@ -1,16 +1,135 @@
+use crate::event::EventIdKind;
 use serde::{Deserialize, Serialize};
 
+/// Where saved events get their `timestamp` from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimestampSource {
+    /// Keep the timestamp set by the caller when the event was created
+    #[default]
+    ClientProvided,
+    /// Overwrite every timestamp with the store's clock at save time
+    ServerAssigned,
+}
+
+/// Where the global positions handed to an event streamer come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum GlobalPositionAllocation {
+    /// Use the position the database assigned to each row on insert, which is
+    /// unique and monotonic across every process writing to the database
+    #[default]
+    Database,
+    /// Number published events with a counter in this process, starting at
+    /// one. Only correct while a single process writes to the database.
+    InProcess,
+}
+
+/// What a save does with more events than the store's `max_batch_events`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OversizedBatch {
+    /// Fail the save with a validation error, writing nothing
+    #[default]
+    Reject,
+    /// Commit the events in order as consecutive transactions of at most
+    /// `max_batch_events` each. Not atomic: if one transaction fails, those
+    /// before it stay committed.
+    Split,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EventStoreConfig {
     PostgreSQL {
         connection_string: String,
         max_connections: Option<u32>,
         table_name: Option<String>,
+        /// Highest version any aggregate may reach; saves past it are rejected.
+        #[serde(default)]
+        max_aggregate_version: Option<i64>,
+        /// Name of a registered custom codec used to encode event payloads.
+        #[serde(default)]
+        codec: Option<String>,
+        /// Whether event timestamps come from the caller or the store's clock.
+        #[serde(default)]
+        timestamp_source: TimestampSource,
+        /// ID kind this store's `new_event_id` hands out.
+        #[serde(default)]
+        event_id_kind: Option<EventIdKind>,
+        /// Serialize saves to the same aggregate within this process.
+        #[serde(default)]
+        aggregate_locking: bool,
+        /// Record every saved event in an outbox table in the same transaction,
+        /// for `OutboxRelay` to publish.
+        #[serde(default)]
+        transactional_outbox: bool,
+        /// Source of the global positions published to an event streamer.
+        #[serde(default)]
+        global_position_allocation: GlobalPositionAllocation,
+        /// SQLite file recording every save the backend rejects.
+        #[serde(default)]
+        failed_write_log_path: Option<String>,
+        /// Fail any store call that takes longer than this many milliseconds.
+        #[serde(default)]
+        operation_timeout_ms: Option<u64>,
+        /// Keep this many of the latest committed events in memory for `recent_events`.
+        #[serde(default)]
+        recent_events_capacity: Option<usize>,
+        /// Remember up to this many existing aggregates for `aggregate_exists`.
+        #[serde(default)]
+        existence_cache_capacity: Option<usize>,
+        /// Most events one save may write in a single transaction.
+        #[serde(default)]
+        max_batch_events: Option<usize>,
+        /// Whether a save over `max_batch_events` is rejected or split.
+        #[serde(default)]
+        oversized_batch: OversizedBatch,
     },
     SQLite {
         database_path: String,
         max_connections: Option<u32>,
         table_name: Option<String>,
+        /// Highest version any aggregate may reach; saves past it are rejected.
+        #[serde(default)]
+        max_aggregate_version: Option<i64>,
+        /// Name of a registered custom codec used to encode event payloads.
+        #[serde(default)]
+        codec: Option<String>,
+        /// Whether event timestamps come from the caller or the store's clock.
+        #[serde(default)]
+        timestamp_source: TimestampSource,
+        /// ID kind this store's `new_event_id` hands out.
+        #[serde(default)]
+        event_id_kind: Option<EventIdKind>,
+        /// Serialize saves to the same aggregate within this process.
+        #[serde(default)]
+        aggregate_locking: bool,
+        /// Record every saved event in an outbox table in the same transaction,
+        /// for `OutboxRelay` to publish.
+        #[serde(default)]
+        transactional_outbox: bool,
+        /// Source of the global positions published to an event streamer.
+        #[serde(default)]
+        global_position_allocation: GlobalPositionAllocation,
+        /// Database file attached as `archive` to hold events moved out of the
+        /// primary file; reads span both.
+        #[serde(default)]
+        archive_path: Option<String>,
+        /// SQLite file recording every save the backend rejects.
+        #[serde(default)]
+        failed_write_log_path: Option<String>,
+        /// Fail any store call that takes longer than this many milliseconds.
+        #[serde(default)]
+        operation_timeout_ms: Option<u64>,
+        /// Keep this many of the latest committed events in memory for `recent_events`.
+        #[serde(default)]
+        recent_events_capacity: Option<usize>,
+        /// Remember up to this many existing aggregates for `aggregate_exists`.
+        #[serde(default)]
+        existence_cache_capacity: Option<usize>,
+        /// Most events one save may write in a single transaction.
+        #[serde(default)]
+        max_batch_events: Option<usize>,
+        /// Whether a save over `max_batch_events` is rejected or split.
+        #[serde(default)]
+        oversized_batch: OversizedBatch,
     },
 }
 
@@ -20,6 +139,19 @@ impl EventStoreConfig {
             connection_string,
             max_connections: None,
             table_name: None,
+            max_aggregate_version: None,
+            codec: None,
+            timestamp_source: TimestampSource::ClientProvided,
+            event_id_kind: None,
+            aggregate_locking: false,
+            transactional_outbox: false,
+            global_position_allocation: GlobalPositionAllocation::Database,
+            failed_write_log_path: None,
+            operation_timeout_ms: None,
+            recent_events_capacity: None,
+            existence_cache_capacity: None,
+            max_batch_events: None,
+            oversized_batch: OversizedBatch::Reject,
         }
     }
 
@@ -28,6 +160,19 @@ impl EventStoreConfig {
             connection_string,
             max_connections: Some(max_connections),
             table_name: None,
+            max_aggregate_version: None,
+            codec: None,
+            timestamp_source: TimestampSource::ClientProvided,
+            event_id_kind: None,
+            aggregate_locking: false,
+            transactional_outbox: false,
+            global_position_allocation: GlobalPositionAllocation::Database,
+            failed_write_log_path: None,
+            operation_timeout_ms: None,
+            recent_events_capacity: None,
+            existence_cache_capacity: None,
+            max_batch_events: None,
+            oversized_batch: OversizedBatch::Reject,
         }
     }
 
@@ -36,6 +181,20 @@ impl EventStoreConfig {
             database_path,
             max_connections: None,
             table_name: None,
+            max_aggregate_version: None,
+            codec: None,
+            timestamp_source: TimestampSource::ClientProvided,
+            event_id_kind: None,
+            aggregate_locking: false,
+            transactional_outbox: false,
+            global_position_allocation: GlobalPositionAllocation::Database,
+            archive_path: None,
+            failed_write_log_path: None,
+            operation_timeout_ms: None,
+            recent_events_capacity: None,
+            existence_cache_capacity: None,
+            max_batch_events: None,
+            oversized_batch: OversizedBatch::Reject,
         }
     }
 
@@ -44,6 +203,20 @@ impl EventStoreConfig {
             database_path,
             max_connections: Some(max_connections),
             table_name: None,
+            max_aggregate_version: None,
+            codec: None,
+            timestamp_source: TimestampSource::ClientProvided,
+            event_id_kind: None,
+            aggregate_locking: false,
+            transactional_outbox: false,
+            global_position_allocation: GlobalPositionAllocation::Database,
+            archive_path: None,
+            failed_write_log_path: None,
+            operation_timeout_ms: None,
+            recent_events_capacity: None,
+            existence_cache_capacity: None,
+            max_batch_events: None,
+            oversized_batch: OversizedBatch::Reject,
         }
     }
 
@@ -55,6 +228,152 @@ impl EventStoreConfig {
         self
     }
 
+    /// Reject saves that would push an aggregate past `max_aggregate_version`.
+    ///
+    /// A stream growing without bound usually signals a modeling bug, so this
+    /// surfaces it at write time instead of during a slow replay. Off by default.
+    pub fn with_max_aggregate_version(mut self, max_version: i64) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { max_aggregate_version: m, .. } => *m = Some(max_version),
+            EventStoreConfig::SQLite { max_aggregate_version: m, .. } => *m = Some(max_version),
+        }
+        self
+    }
+
+    /// Encode event payloads with the named codec from the store's `CodecRegistry`.
+    ///
+    /// Rows record the codec name, so events written with other formats still load.
+    pub fn with_codec(mut self, codec_name: String) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { codec, .. } => *codec = Some(codec_name),
+            EventStoreConfig::SQLite { codec, .. } => *codec = Some(codec_name),
+        }
+        self
+    }
+
+    /// Choose whether saved events keep client timestamps or get the server clock's.
+    ///
+    /// Trusted ordering needs `ServerAssigned`, since client clocks can be wrong or
+    /// spoofed. Defaults to `ClientProvided` for backward compatibility.
+    pub fn with_timestamp_source(mut self, source: TimestampSource) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { timestamp_source, .. } => *timestamp_source = source,
+            EventStoreConfig::SQLite { timestamp_source, .. } => *timestamp_source = source,
+        }
+        self
+    }
+
+    /// Hand out event IDs of `kind` (for example time-sortable ULIDs) from
+    /// the store's `new_event_id`. Other stores and `Event::new` keep the
+    /// process default (UUID v4 unless `set_default_event_id_kind` changed it).
+    pub fn with_event_id_kind(mut self, kind: EventIdKind) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { event_id_kind, .. } => *event_id_kind = Some(kind),
+            EventStoreConfig::SQLite { event_id_kind, .. } => *event_id_kind = Some(kind),
+        }
+        self
+    }
+
+    /// Make concurrent saves to the same aggregate in this process wait for each
+    /// other instead of racing into optimistic concurrency conflicts.
+    pub fn with_aggregate_locking(mut self, enabled: bool) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { aggregate_locking, .. } => *aggregate_locking = enabled,
+            EventStoreConfig::SQLite { aggregate_locking, .. } => *aggregate_locking = enabled,
+        }
+        self
+    }
+
+    /// Write an outbox row for every saved event in the event's own transaction,
+    /// so an `OutboxRelay` can publish it even if the process dies right after
+    /// the commit.
+    pub fn with_transactional_outbox(mut self, enabled: bool) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { transactional_outbox, .. } => *transactional_outbox = enabled,
+            EventStoreConfig::SQLite { transactional_outbox, .. } => *transactional_outbox = enabled,
+        }
+        self
+    }
+
+    /// Choose where published global positions come from.
+    ///
+    /// The default reads back the position the database assigned on insert, so
+    /// several processes sharing one database never publish the same position.
+    pub fn with_global_position_allocation(mut self, allocation: GlobalPositionAllocation) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { global_position_allocation, .. } => *global_position_allocation = allocation,
+            EventStoreConfig::SQLite { global_position_allocation, .. } => *global_position_allocation = allocation,
+        }
+        self
+    }
+
+    /// Attach `path` as an archive database for `archive_events_before` to move
+    /// old events into, keeping the primary file small. SQLite only; PostgreSQL
+    /// configs are returned unchanged.
+    pub fn with_archive_path(mut self, path: String) -> Self {
+        if let EventStoreConfig::SQLite { archive_path, .. } = &mut self {
+            *archive_path = Some(path);
+        }
+        self
+    }
+
+    /// Record every save the backend rejects, with the attempted versions and
+    /// the error's kind, in a SQLite file at `path` for later querying.
+    pub fn with_failed_write_log(mut self, path: String) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { failed_write_log_path, .. } => *failed_write_log_path = Some(path),
+            EventStoreConfig::SQLite { failed_write_log_path, .. } => *failed_write_log_path = Some(path),
+        }
+        self
+    }
+
+    /// Fail any store call that takes longer than `timeout_ms` with an `Io`
+    /// error of kind `TimedOut`, so a hung connection cannot block a request
+    /// indefinitely. Off by default.
+    pub fn with_operation_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { operation_timeout_ms, .. } => *operation_timeout_ms = Some(timeout_ms),
+            EventStoreConfig::SQLite { operation_timeout_ms, .. } => *operation_timeout_ms = Some(timeout_ms),
+        }
+        self
+    }
+
+    /// Keep the last `capacity` events committed through this store in memory,
+    /// so `recent_events` answers "latest activity" queries without reading the
+    /// database. Off by default.
+    pub fn with_recent_events_capacity(mut self, capacity: usize) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { recent_events_capacity, .. } => *recent_events_capacity = Some(capacity),
+            EventStoreConfig::SQLite { recent_events_capacity, .. } => *recent_events_capacity = Some(capacity),
+        }
+        self
+    }
+
+    /// Cache up to `capacity` aggregates known to exist, so repeat
+    /// `aggregate_exists` checks skip the database. Off by default.
+    pub fn with_existence_cache_capacity(mut self, capacity: usize) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { existence_cache_capacity, .. } => *existence_cache_capacity = Some(capacity),
+            EventStoreConfig::SQLite { existence_cache_capacity, .. } => *existence_cache_capacity = Some(capacity),
+        }
+        self
+    }
+
+    /// Cap a single save at `max_events` events, rejecting larger saves or
+    /// splitting them into several transactions as `oversized` says, so one
+    /// huge batch cannot hold table locks or memory indefinitely. Off by
+    /// default.
+    pub fn with_max_batch_events(mut self, max_events: usize, oversized: OversizedBatch) -> Self {
+        match &mut self {
+            EventStoreConfig::PostgreSQL { max_batch_events, oversized_batch, .. } |
+            EventStoreConfig::SQLite { max_batch_events, oversized_batch, .. } => {
+                *max_batch_events = Some(max_events);
+                *oversized_batch = oversized;
+            }
+        }
+        self
+    }
+
     pub fn table_name(&self) -> &str {
         match self {
             EventStoreConfig::PostgreSQL { table_name, .. } |
@@ -72,4 +391,111 @@ impl EventStoreConfig {
             }
         }
     }
-}
\ No newline at end of file
+
+    pub fn max_aggregate_version(&self) -> Option<i64> {
+        match self {
+            EventStoreConfig::PostgreSQL { max_aggregate_version, .. } |
+            EventStoreConfig::SQLite { max_aggregate_version, .. } => *max_aggregate_version,
+        }
+    }
+
+    pub fn codec(&self) -> Option<&str> {
+        match self {
+            EventStoreConfig::PostgreSQL { codec, .. } |
+            EventStoreConfig::SQLite { codec, .. } => codec.as_deref(),
+        }
+    }
+
+    pub fn timestamp_source(&self) -> TimestampSource {
+        match self {
+            EventStoreConfig::PostgreSQL { timestamp_source, .. } |
+            EventStoreConfig::SQLite { timestamp_source, .. } => *timestamp_source,
+        }
+    }
+
+    pub fn event_id_kind(&self) -> Option<EventIdKind> {
+        match self {
+            EventStoreConfig::PostgreSQL { event_id_kind, .. } |
+            EventStoreConfig::SQLite { event_id_kind, .. } => *event_id_kind,
+        }
+    }
+
+    pub fn aggregate_locking(&self) -> bool {
+        match self {
+            EventStoreConfig::PostgreSQL { aggregate_locking, .. } |
+            EventStoreConfig::SQLite { aggregate_locking, .. } => *aggregate_locking,
+        }
+    }
+
+    pub fn transactional_outbox(&self) -> bool {
+        match self {
+            EventStoreConfig::PostgreSQL { transactional_outbox, .. } |
+            EventStoreConfig::SQLite { transactional_outbox, .. } => *transactional_outbox,
+        }
+    }
+
+    pub fn global_position_allocation(&self) -> GlobalPositionAllocation {
+        match self {
+            EventStoreConfig::PostgreSQL { global_position_allocation, .. } |
+            EventStoreConfig::SQLite { global_position_allocation, .. } => *global_position_allocation,
+        }
+    }
+
+    pub fn failed_write_log_path(&self) -> Option<&str> {
+        match self {
+            EventStoreConfig::PostgreSQL { failed_write_log_path, .. } |
+            EventStoreConfig::SQLite { failed_write_log_path, .. } => failed_write_log_path.as_deref(),
+        }
+    }
+
+    pub fn operation_timeout(&self) -> Option<std::time::Duration> {
+        match self {
+            EventStoreConfig::PostgreSQL { operation_timeout_ms, .. } |
+            EventStoreConfig::SQLite { operation_timeout_ms, .. } => {
+                operation_timeout_ms.map(std::time::Duration::from_millis)
+            }
+        }
+    }
+
+    pub fn recent_events_capacity(&self) -> Option<usize> {
+        match self {
+            EventStoreConfig::PostgreSQL { recent_events_capacity, .. } |
+            EventStoreConfig::SQLite { recent_events_capacity, .. } => *recent_events_capacity,
+        }
+    }
+
+    pub fn existence_cache_capacity(&self) -> Option<usize> {
+        match self {
+            EventStoreConfig::PostgreSQL { existence_cache_capacity, .. } |
+            EventStoreConfig::SQLite { existence_cache_capacity, .. } => *existence_cache_capacity,
+        }
+    }
+
+    pub fn max_batch_events(&self) -> Option<usize> {
+        match self {
+            EventStoreConfig::PostgreSQL { max_batch_events, .. } |
+            EventStoreConfig::SQLite { max_batch_events, .. } => *max_batch_events,
+        }
+    }
+
+    pub fn oversized_batch(&self) -> OversizedBatch {
+        match self {
+            EventStoreConfig::PostgreSQL { oversized_batch, .. } |
+            EventStoreConfig::SQLite { oversized_batch, .. } => *oversized_batch,
+        }
+    }
+}
+
+/// Check that a configured table name is a plain SQL identifier, since table
+/// names are spliced into statements rather than bound as parameters
+pub(crate) fn validate_table_name(table_name: &str) -> crate::Result<()> {
+    let mut chars = table_name.chars();
+    let starts_well = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if starts_well && table_name.len() <= 63 && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(crate::EventualiError::Configuration(format!(
+            "Invalid table name {table_name:?}: use at most 63 ASCII letters, digits or underscores, not starting with a digit"
+        )))
+    }
+}
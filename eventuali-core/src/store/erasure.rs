@@ -0,0 +1,72 @@
+//! Erasing an aggregate's events
+//!
+//! Streams are append-only, but a request to erase a data subject has to
+//! remove what their aggregate recorded. `DeleteMode::Hard` removes the rows
+//! outright. `DeleteMode::SoftTombstone` keeps every row with its ID,
+//! versions, timestamp and global position, and replaces its type, payload
+//! and metadata with a tombstone, so the stream stays contiguous and readers
+//! replaying the store see that an event was there without seeing what it was.
+
+use crate::{Event, EventData, EventMetadata};
+use serde_json::json;
+
+/// Event type every tombstoned event is stored under
+pub const TOMBSTONE_EVENT_TYPE: &str = "EventErased";
+
+/// How `EventStore::delete_aggregate_events_with_mode` erases events
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeleteMode {
+    /// Physically delete the events, along with their outbox and compaction
+    /// records. The aggregate no longer exists afterwards. The freed global
+    /// positions are never handed out again, so the global order keeps a gap
+    /// where the events were.
+    #[default]
+    Hard,
+    /// Keep each event's row and position, replacing its type, payload and
+    /// metadata with a tombstone. The aggregate keeps its version, so new
+    /// events still append after it.
+    SoftTombstone,
+}
+
+/// `event` as stored after `DeleteMode::SoftTombstone` erased it
+pub fn tombstone(event: &Event) -> Event {
+    Event {
+        event_type: TOMBSTONE_EVENT_TYPE.to_string(),
+        event_version: 1,
+        data: EventData::Json(tombstone_data()),
+        metadata: EventMetadata::default(),
+        ..event.clone()
+    }
+}
+
+/// Payload of a tombstoned event
+pub(crate) fn tombstone_data() -> serde_json::Value {
+    json!({ "erased": true })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tombstone_keeps_position_fields_and_drops_content() {
+        let mut event = Event::new(
+            "user-1".to_string(),
+            "User".to_string(),
+            "EmailChanged".to_string(),
+            3,
+            7,
+            EventData::Json(json!({ "email": "someone@example.com" })),
+        );
+        event.metadata.user_id = Some("someone".to_string());
+
+        let erased = tombstone(&event);
+        assert_eq!(erased.id, event.id);
+        assert_eq!(erased.aggregate_id, event.aggregate_id);
+        assert_eq!(erased.aggregate_version, 7);
+        assert_eq!(erased.timestamp, event.timestamp);
+        assert_eq!(erased.event_type, TOMBSTONE_EVENT_TYPE);
+        assert_eq!(erased.data, EventData::Json(tombstone_data()));
+        assert_eq!(erased.metadata, EventMetadata::default());
+    }
+}
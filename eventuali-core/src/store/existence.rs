@@ -0,0 +1,134 @@
+//! Cache of aggregates known to exist
+//!
+//! Command handlers check whether an aggregate exists before choosing to
+//! create or update it. A store configured with an existence cache remembers
+//! every aggregate it has seen committed or loaded, so repeat checks skip the
+//! database. Only positive answers are cached: an aggregate never stops
+//! existing through this store, while "not found" can go stale the moment
+//! another process creates it. Entries are dropped least recently used first
+//! once the cache is full.
+
+use crate::{AggregateId, EventualiError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// Counters for an existence cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExistenceCacheStats {
+    /// Checks answered from the cache
+    pub hits: u64,
+    /// Checks that had to ask the database
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// Aggregate ID to its last-use tick
+    last_used: HashMap<AggregateId, u64>,
+    /// Last-use tick to aggregate ID, oldest first
+    by_age: BTreeMap<u64, AggregateId>,
+    tick: u64,
+    hits: u64,
+    misses: u64,
+}
+
+impl Entries {
+    fn touch(&mut self, aggregate_id: &AggregateId) -> bool {
+        self.tick += 1;
+        match self.last_used.get_mut(aggregate_id) {
+            Some(last_used) => {
+                self.by_age.remove(last_used);
+                *last_used = self.tick;
+                self.by_age.insert(self.tick, aggregate_id.clone());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Bounded, least-recently-used set of aggregates known to exist
+#[derive(Debug)]
+pub struct ExistenceCache {
+    entries: Mutex<Entries>,
+    capacity: usize,
+}
+
+impl ExistenceCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(Entries::default()),
+            capacity,
+        }
+    }
+
+    /// Whether `aggregate_id` is known to exist, counting a hit or a miss
+    pub fn check(&self, aggregate_id: &AggregateId) -> Result<bool> {
+        let mut entries = self.lock()?;
+        let known = entries.touch(aggregate_id);
+        if known {
+            entries.hits += 1;
+        } else {
+            entries.misses += 1;
+        }
+        Ok(known)
+    }
+
+    /// Record that each of `aggregate_ids` exists
+    pub fn insert<'a>(&self, aggregate_ids: impl IntoIterator<Item = &'a AggregateId>) -> Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut entries = self.lock()?;
+        for aggregate_id in aggregate_ids {
+            if entries.touch(aggregate_id) {
+                continue;
+            }
+            if entries.last_used.len() == self.capacity {
+                if let Some((_, oldest)) = entries.by_age.pop_first() {
+                    entries.last_used.remove(&oldest);
+                }
+            }
+            let tick = entries.tick;
+            entries.last_used.insert(aggregate_id.clone(), tick);
+            entries.by_age.insert(tick, aggregate_id.clone());
+        }
+        Ok(())
+    }
+
+    /// Forget `aggregate_id`, for when its events are removed
+    pub fn invalidate(&self, aggregate_id: &AggregateId) -> Result<()> {
+        let mut entries = self.lock()?;
+        if let Some(last_used) = entries.last_used.remove(aggregate_id) {
+            entries.by_age.remove(&last_used);
+        }
+        Ok(())
+    }
+
+    /// Forget every aggregate, keeping the hit and miss counts
+    pub fn clear(&self) -> Result<()> {
+        let mut entries = self.lock()?;
+        entries.last_used.clear();
+        entries.by_age.clear();
+        Ok(())
+    }
+
+    pub fn stats(&self) -> Result<ExistenceCacheStats> {
+        let entries = self.lock()?;
+        Ok(ExistenceCacheStats {
+            hits: entries.hits,
+            misses: entries.misses,
+            entries: entries.last_used.len(),
+            capacity: self.capacity,
+        })
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Entries>> {
+        self.entries
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Existence cache lock poisoned".to_string()))
+    }
+}
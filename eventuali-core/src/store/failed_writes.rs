@@ -0,0 +1,248 @@
+//! Durable record of rejected saves
+//!
+//! A store configured with a failed-write log writes one row per aggregate in
+//! every save it rejects: concurrency conflicts, validation failures, database
+//! errors, and for tenant storage, quota hits. The row keeps the versions and event types the caller
+//! tried to write and the error's kind, so conflicts and rejected writes can be
+//! counted and traced after the caller has moved on. The log lives in its own
+//! SQLite file, so it still records failures when the event database is the
+//! thing failing.
+
+use crate::store::config::validate_table_name;
+use crate::{AggregateVersion, Event, EventualiError, Result};
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use std::sync::{Arc, Mutex};
+
+/// One aggregate's part of a rejected save
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailedWrite {
+    pub id: i64,
+    pub aggregate_id: String,
+    pub aggregate_type: String,
+    pub attempted_versions: Vec<AggregateVersion>,
+    pub event_types: Vec<String>,
+    /// `EventualiError::kind` of the rejection
+    pub error_kind: String,
+    pub error_message: String,
+    pub retryable: bool,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// Which failed writes a query returns; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FailedWriteFilter {
+    pub aggregate_id: Option<String>,
+    pub error_kind: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// SQLite table of rejected saves
+#[derive(Debug)]
+pub struct FailedWriteLog {
+    conn: Mutex<Connection>,
+    table_name: String,
+}
+
+impl FailedWriteLog {
+    /// Open (or create) the log at `path`; `:memory:` opens a private in-memory database
+    pub fn open(path: &str, table_name: Option<String>) -> Result<Self> {
+        let table_name = table_name.unwrap_or_else(|| "failed_writes".to_string());
+        validate_table_name(&table_name)?;
+
+        let conn = if path == ":memory:" {
+            Connection::open_in_memory()
+        } else {
+            Connection::open(path)
+        }
+        .map_err(|e| EventualiError::DatabaseError(format!("Failed to open failed-write log: {e}")))?;
+
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to configure failed-write log: {e}")))?;
+
+        let log = Self {
+            conn: Mutex::new(conn),
+            table_name,
+        };
+        log.initialize()?;
+        Ok(log)
+    }
+
+    fn initialize(&self) -> Result<()> {
+        let create_table = format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {table} (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                aggregate_id TEXT NOT NULL,
+                aggregate_type TEXT NOT NULL,
+                attempted_versions TEXT NOT NULL,
+                event_types TEXT NOT NULL,
+                error_kind TEXT NOT NULL,
+                error_message TEXT NOT NULL,
+                retryable INTEGER NOT NULL,
+                failed_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_{table}_aggregate ON {table} (aggregate_id, failed_at);
+            CREATE INDEX IF NOT EXISTS idx_{table}_kind ON {table} (error_kind, failed_at);
+            CREATE INDEX IF NOT EXISTS idx_{table}_failed_at ON {table} (failed_at);
+            "#,
+            table = self.table_name
+        );
+
+        self.lock()?
+            .execute_batch(&create_table)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to create failed-write table: {e}")))
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Failed-write log lock poisoned".to_string()))
+    }
+
+    /// Record that saving `events` failed with `error`, one row per aggregate.
+    /// The insert is blocking SQLite I/O, so it runs on the blocking pool
+    /// rather than stalling the runtime thread the failed save ran on.
+    pub async fn record(self: Arc<Self>, events: Vec<Event>, error: &EventualiError, failed_at: DateTime<Utc>) -> Result<()> {
+        let (kind, message, retryable) = (error.kind(), error.to_string(), error.is_retryable());
+        tokio::task::spawn_blocking(move || self.insert(&events, kind, &message, retryable, failed_at))
+            .await
+            .map_err(|e| EventualiError::InvalidState(format!("Failed-write log task failed: {e}")))?
+    }
+
+    fn insert(
+        &self,
+        events: &[Event],
+        kind: &str,
+        message: &str,
+        retryable: bool,
+        failed_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut by_aggregate: Vec<(&str, &str, Vec<AggregateVersion>, Vec<&str>)> = Vec::new();
+        for event in events {
+            match by_aggregate.iter_mut().find(|(id, ..)| *id == event.aggregate_id) {
+                Some((_, _, versions, event_types)) => {
+                    versions.push(event.aggregate_version);
+                    event_types.push(&event.event_type);
+                }
+                None => by_aggregate.push((
+                    &event.aggregate_id,
+                    &event.aggregate_type,
+                    vec![event.aggregate_version],
+                    vec![&event.event_type],
+                )),
+            }
+        }
+
+        let query = format!(
+            "INSERT INTO {} (aggregate_id, aggregate_type, attempted_versions, event_types, \
+             error_kind, error_message, retryable, failed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            self.table_name
+        );
+        let mut conn = self.lock()?;
+        let tx = conn
+            .transaction()
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to record failed write: {e}")))?;
+        for (aggregate_id, aggregate_type, versions, event_types) in by_aggregate {
+            tx.execute(
+                &query,
+                params![
+                    aggregate_id,
+                    aggregate_type,
+                    serde_json::to_string(&versions)?,
+                    serde_json::to_string(&event_types)?,
+                    kind,
+                    message,
+                    retryable,
+                    failed_at.timestamp_micros()
+                ],
+            )
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to record failed write: {e}")))?;
+        }
+        tx.commit()
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to record failed write: {e}")))
+    }
+
+    /// Delete every failed write recorded for `aggregate_id`, returning how
+    /// many were removed. Part of erasing an aggregate's personal data.
+    pub fn erase_aggregate(&self, aggregate_id: &str) -> Result<u64> {
+        let removed = self
+            .lock()?
+            .execute(&format!("DELETE FROM {} WHERE aggregate_id = ?1", self.table_name), params![aggregate_id])
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to erase failed writes: {e}")))?;
+        Ok(removed as u64)
+    }
+
+    /// Failed writes matching `filter`, newest first, at most `limit` of them
+    pub fn query(&self, filter: &FailedWriteFilter, limit: usize) -> Result<Vec<FailedWrite>> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(aggregate_id) = &filter.aggregate_id {
+            conditions.push("aggregate_id = ?");
+            values.push(Value::Text(aggregate_id.clone()));
+        }
+        if let Some(error_kind) = &filter.error_kind {
+            conditions.push("error_kind = ?");
+            values.push(Value::Text(error_kind.clone()));
+        }
+        if let Some(since) = filter.since {
+            conditions.push("failed_at >= ?");
+            values.push(Value::Integer(since.timestamp_micros()));
+        }
+        values.push(Value::Integer(limit as i64));
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        let query = format!(
+            "SELECT id, aggregate_id, aggregate_type, attempted_versions, event_types, error_kind, \
+             error_message, retryable, failed_at FROM {} {where_clause} ORDER BY failed_at DESC, id DESC LIMIT ?",
+            self.table_name
+        );
+
+        let conn = self.lock()?;
+        let mut stmt = conn
+            .prepare(&query)
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to query failed writes: {e}")))?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, String>(6)?,
+                    row.get::<_, bool>(7)?,
+                    row.get::<_, i64>(8)?,
+                ))
+            })
+            .and_then(|rows| rows.collect::<std::result::Result<Vec<_>, _>>())
+            .map_err(|e| EventualiError::DatabaseError(format!("Failed to query failed writes: {e}")))?;
+
+        rows.into_iter()
+            .map(
+                |(id, aggregate_id, aggregate_type, versions, event_types, error_kind, error_message, retryable, failed_at)| {
+                    Ok(FailedWrite {
+                        id,
+                        aggregate_id,
+                        aggregate_type,
+                        attempted_versions: serde_json::from_str(&versions)?,
+                        event_types: serde_json::from_str(&event_types)?,
+                        error_kind,
+                        error_message,
+                        retryable,
+                        failed_at: Utc.timestamp_micros(failed_at).single().ok_or_else(|| {
+                            EventualiError::InvalidState(format!("Invalid failed_at timestamp {failed_at}"))
+                        })?,
+                    })
+                },
+            )
+            .collect()
+    }
+}
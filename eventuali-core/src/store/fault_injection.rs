@@ -0,0 +1,435 @@
+//! Deterministic backend failures for resilience tests
+//!
+//! `FaultInjectingBackend` wraps another backend and fails or delays the calls
+//! a `FaultInjector` picks out: the Nth call, the next few calls of one
+//! operation, or every call while `SQLITE_BUSY` is switched on. Retry, outbox
+//! and timeout handling can then be tested call by call instead of by racing a
+//! real database into contention.
+//!
+//! The injector is shared: keep a clone after handing the backend to a store
+//! to change faults mid-test and to count the calls the store made.
+//!
+//! Only built with the `test-util` feature.
+
+use crate::store::compaction::{CompactionReport, Compactor};
+use crate::store::erasure::DeleteMode;
+use crate::store::quarantine::LenientLoad;
+use crate::store::traits::{EventStoreBackend, StoreStats};
+use crate::{AggregateId, AggregateVersion, Event, EventId, EventualiError, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Backend calls faults can target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackendOperation {
+    SaveEvents,
+    LoadEvents,
+    LoadEventsLenient,
+    LoadEventsByType,
+    GetEventsByCorrelationId,
+    GetEventsByUserId,
+    GetAggregateVersion,
+    LoadEventsAfterPosition,
+    ListAggregateTypes,
+    Stats,
+    ArchiveEventsBefore,
+    SyncToDisk,
+    LoadUnpublishedEvents,
+    MarkEventsPublished,
+    CompactAggregate,
+    LoadCompactionHistory,
+    DeleteAggregateEvents,
+}
+
+/// Error an injected fault fails the call with
+#[derive(Debug, Clone, PartialEq)]
+pub enum InjectedFault {
+    /// SQLite's "database is locked" (`SQLITE_BUSY`), which is retryable
+    SqliteBusy,
+    /// An I/O error of this kind, such as `ConnectionReset`
+    Io(std::io::ErrorKind),
+    /// A `DatabaseError` with this message
+    Database(String),
+}
+
+impl InjectedFault {
+    fn to_error(&self) -> EventualiError {
+        match self {
+            InjectedFault::SqliteBusy => EventualiError::Database(sqlx::Error::Database(Box::new(SqliteBusy))),
+            InjectedFault::Io(kind) => EventualiError::Io(std::io::Error::new(*kind, "injected fault")),
+            InjectedFault::Database(message) => EventualiError::DatabaseError(message.clone()),
+        }
+    }
+}
+
+/// `SQLITE_BUSY` as the SQLite driver reports it
+#[derive(Debug)]
+struct SqliteBusy;
+
+impl std::fmt::Display for SqliteBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("database is locked")
+    }
+}
+
+impl std::error::Error for SqliteBusy {}
+
+impl sqlx::error::DatabaseError for SqliteBusy {
+    fn message(&self) -> &str {
+        "database is locked"
+    }
+
+    fn code(&self) -> Option<std::borrow::Cow<'_, str>> {
+        Some("5".into())
+    }
+
+    fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+        self
+    }
+
+    fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+        self
+    }
+
+    fn kind(&self) -> sqlx::error::ErrorKind {
+        sqlx::error::ErrorKind::Other
+    }
+}
+
+#[derive(Debug)]
+enum Trigger {
+    /// The call with this 1-based count
+    Nth(u64),
+    /// This many more calls
+    Next(u64),
+}
+
+#[derive(Debug)]
+struct FaultRule {
+    /// `None` matches every operation and counts calls of all of them
+    operation: Option<BackendOperation>,
+    trigger: Trigger,
+    fault: InjectedFault,
+}
+
+#[derive(Debug, Default)]
+struct FaultState {
+    rules: Vec<FaultRule>,
+    latency: Option<Duration>,
+    operation_latency: HashMap<BackendOperation, Duration>,
+    sqlite_busy: bool,
+    total_calls: u64,
+    calls: HashMap<BackendOperation, u64>,
+    faults_injected: u64,
+}
+
+/// Which calls a `FaultInjectingBackend` fails or delays; clones share state
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector {
+    state: Arc<Mutex<FaultState>>,
+}
+
+impl FaultInjector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail the `n`th backend call of any operation, counting from 1
+    pub fn fail_nth(self, n: u64, fault: InjectedFault) -> Self {
+        self.add_rule(None, Trigger::Nth(n), fault)
+    }
+
+    /// Fail the `n`th call of `operation`, counting from 1
+    pub fn fail_nth_of(self, operation: BackendOperation, n: u64, fault: InjectedFault) -> Self {
+        self.add_rule(Some(operation), Trigger::Nth(n), fault)
+    }
+
+    /// Fail the next `count` calls of `operation`
+    pub fn fail_next(self, operation: BackendOperation, count: u64, fault: InjectedFault) -> Self {
+        self.add_rule(Some(operation), Trigger::Next(count), fault)
+    }
+
+    /// Delay every call by `latency` before it runs
+    pub fn with_latency(self, latency: Duration) -> Self {
+        self.lock().latency = Some(latency);
+        self
+    }
+
+    /// Delay calls of `operation` by `latency`, replacing `with_latency` for it
+    pub fn with_latency_of(self, operation: BackendOperation, latency: Duration) -> Self {
+        self.lock().operation_latency.insert(operation, latency);
+        self
+    }
+
+    /// While on, every call fails with `SQLITE_BUSY` whatever the rules say
+    pub fn set_sqlite_busy(&self, busy: bool) {
+        self.lock().sqlite_busy = busy;
+    }
+
+    /// Drop every rule and latency and switch `SQLITE_BUSY` off; call counts
+    /// are kept
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.rules.clear();
+        state.latency = None;
+        state.operation_latency.clear();
+        state.sqlite_busy = false;
+    }
+
+    /// Calls of `operation` so far, failed ones included
+    pub fn calls(&self, operation: BackendOperation) -> u64 {
+        self.lock().calls.get(&operation).copied().unwrap_or(0)
+    }
+
+    /// Backend calls of every operation so far
+    pub fn total_calls(&self) -> u64 {
+        self.lock().total_calls
+    }
+
+    /// Calls failed by an injected fault so far
+    pub fn faults_injected(&self) -> u64 {
+        self.lock().faults_injected
+    }
+
+    fn add_rule(self, operation: Option<BackendOperation>, trigger: Trigger, fault: InjectedFault) -> Self {
+        self.lock().rules.push(FaultRule { operation, trigger, fault });
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FaultState> {
+        // A test that panicked mid-call must not hide its own failure behind
+        // a poisoned lock in the next one
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Count a call of `operation` and decide its delay and fault
+    fn on_call(&self, operation: BackendOperation) -> (Option<Duration>, Option<EventualiError>) {
+        let mut state = self.lock();
+        state.total_calls += 1;
+        let total = state.total_calls;
+        let count = {
+            let count = state.calls.entry(operation).or_insert(0);
+            *count += 1;
+            *count
+        };
+        let latency = state.operation_latency.get(&operation).copied().or(state.latency);
+
+        // The first matching rule wins; later ones are left for later calls
+        let mut fault = state.sqlite_busy.then_some(InjectedFault::SqliteBusy);
+        if fault.is_none() {
+            for rule in state.rules.iter_mut() {
+                let matched = match rule.operation {
+                    Some(target) if target != operation => false,
+                    _ => match &mut rule.trigger {
+                        Trigger::Nth(n) => *n == if rule.operation.is_some() { count } else { total },
+                        Trigger::Next(remaining) if *remaining > 0 => {
+                            *remaining -= 1;
+                            true
+                        }
+                        Trigger::Next(_) => false,
+                    },
+                };
+                if matched {
+                    fault = Some(rule.fault.clone());
+                    break;
+                }
+            }
+            state.rules.retain(|rule| !matches!(rule.trigger, Trigger::Next(0)));
+        }
+
+        if fault.is_some() {
+            state.faults_injected += 1;
+        }
+        (latency, fault.map(|f| f.to_error()))
+    }
+
+    async fn before(&self, operation: BackendOperation) -> Result<()> {
+        let (latency, fault) = self.on_call(operation);
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        fault.map_or(Ok(()), Err)
+    }
+}
+
+/// Backend that passes calls through to `inner` unless its `FaultInjector`
+/// fails or delays them
+pub struct FaultInjectingBackend<B> {
+    inner: B,
+    faults: FaultInjector,
+}
+
+impl<B: EventStoreBackend + Send + Sync> FaultInjectingBackend<B> {
+    pub fn new(inner: B, faults: FaultInjector) -> Self {
+        Self { inner, faults }
+    }
+
+    pub fn faults(&self) -> &FaultInjector {
+        &self.faults
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<B: EventStoreBackend + Send + Sync> EventStoreBackend for FaultInjectingBackend<B> {
+    async fn initialize(&mut self) -> Result<()> {
+        self.inner.initialize().await
+    }
+
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<Vec<u64>> {
+        self.faults.before(BackendOperation::SaveEvents).await?;
+        self.inner.save_events_expecting(events, expected_version).await
+    }
+
+    async fn dry_run_save_events(&self, events: Vec<Event>) -> Result<()> {
+        self.faults.before(BackendOperation::SaveEvents).await?;
+        self.inner.dry_run_save_events(events).await
+    }
+
+    async fn load_events(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<Event>> {
+        self.faults.before(BackendOperation::LoadEvents).await?;
+        self.inner.load_events(aggregate_id, from_version).await
+    }
+
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        self.faults.before(BackendOperation::LoadEvents).await?;
+        self.inner.load_events_at_version(aggregate_id, version).await
+    }
+
+    async fn load_events_by_type(
+        &self,
+        aggregate_type: &str,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<Event>> {
+        self.faults.before(BackendOperation::LoadEventsByType).await?;
+        self.inner.load_events_by_type(aggregate_type, from_version).await
+    }
+
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.faults.before(BackendOperation::GetEventsByCorrelationId).await?;
+        self.inner
+            .get_events_by_correlation_id_with_prefix(correlation_id, aggregate_id_prefix, limit)
+            .await
+    }
+
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.faults.before(BackendOperation::GetEventsByUserId).await?;
+        self.inner
+            .get_events_by_user_id_with_prefix(user_id, aggregate_id_prefix, limit)
+            .await
+    }
+
+    async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
+        self.faults.before(BackendOperation::GetAggregateVersion).await?;
+        self.inner.get_aggregate_version(aggregate_id).await
+    }
+
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        self.faults.before(BackendOperation::LoadEventsAfterPosition).await?;
+        self.inner.load_events_after_position(after_global, limit).await
+    }
+
+    async fn load_events_of_type_after_position(
+        &self,
+        aggregate_type: &str,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        self.faults.before(BackendOperation::LoadEventsAfterPosition).await?;
+        self.inner.load_events_of_type_after_position(aggregate_type, after_global, limit).await
+    }
+
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        self.faults.before(BackendOperation::ListAggregateTypes).await?;
+        self.inner.list_aggregate_types().await
+    }
+
+    async fn stats(&self) -> Result<StoreStats> {
+        self.faults.before(BackendOperation::Stats).await?;
+        self.inner.stats().await
+    }
+
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        self.faults.before(BackendOperation::LoadEventsLenient).await?;
+        self.inner.load_events_lenient(aggregate_id, from_version).await
+    }
+
+    async fn archive_events_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        self.faults.before(BackendOperation::ArchiveEventsBefore).await?;
+        self.inner.archive_events_before(cutoff).await
+    }
+
+    async fn sync_to_disk(&self) -> Result<()> {
+        self.faults.before(BackendOperation::SyncToDisk).await?;
+        self.inner.sync_to_disk().await
+    }
+
+    async fn load_unpublished_events(&self, limit: usize) -> Result<Vec<(u64, Event)>> {
+        self.faults.before(BackendOperation::LoadUnpublishedEvents).await?;
+        self.inner.load_unpublished_events(limit).await
+    }
+
+    async fn mark_events_published(&self, event_ids: &[EventId]) -> Result<()> {
+        self.faults.before(BackendOperation::MarkEventsPublished).await?;
+        self.inner.mark_events_published(event_ids).await
+    }
+
+    async fn compact_aggregate(
+        &self,
+        aggregate_id: &AggregateId,
+        compactor: &dyn Compactor,
+    ) -> Result<CompactionReport> {
+        self.faults.before(BackendOperation::CompactAggregate).await?;
+        self.inner.compact_aggregate(aggregate_id, compactor).await
+    }
+
+    async fn load_compaction_history(&self, aggregate_id: &AggregateId) -> Result<Vec<CompactionReport>> {
+        self.faults.before(BackendOperation::LoadCompactionHistory).await?;
+        self.inner.load_compaction_history(aggregate_id).await
+    }
+
+    async fn delete_aggregate_events(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<u64> {
+        self.faults.before(BackendOperation::DeleteAggregateEvents).await?;
+        self.inner.delete_aggregate_events(aggregate_id, mode).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.inner.close().await
+    }
+}
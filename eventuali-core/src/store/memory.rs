@@ -0,0 +1,212 @@
+use crate::{
+    store::traits::{
+        check_expected_version, duplicate_event_id, expected_aggregate, EventStoreBackend, StoreStats,
+        StoreStatsAccumulator,
+    },
+    Event, EventId, AggregateId, AggregateVersion, Result, EventualiError,
+};
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashSet};
+use tokio::sync::RwLock;
+
+/// Non-persistent backend that keeps events in process memory.
+///
+/// Enforces the same event ID and `(aggregate_id, aggregate_version)` uniqueness
+/// and ordering guarantees as the SQL backends, which makes it suitable for tests and for
+/// measuring the CPU cost of the save path without disk I/O.
+#[derive(Default)]
+pub struct MemoryBackend {
+    state: RwLock<MemoryState>,
+}
+
+/// An aggregate and one of its versions
+type VersionKey = (AggregateId, AggregateVersion);
+
+#[derive(Default)]
+struct MemoryState {
+    /// Events in commit order; an event's global position is its index plus one
+    events: Vec<Event>,
+    versions: HashSet<VersionKey>,
+    ids: HashSet<EventId>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of events currently held
+    pub async fn len(&self) -> usize {
+        self.state.read().await.events.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.state.read().await.events.is_empty()
+    }
+}
+
+/// Check a whole batch before any of it is stored, so a conflict leaves
+/// nothing behind; returns the batch's version keys and IDs
+fn check_batch(
+    state: &MemoryState,
+    events: &[Event],
+    expected_version: Option<AggregateVersion>,
+) -> Result<(HashSet<VersionKey>, HashSet<EventId>)> {
+    if let Some(aggregate_id) = expected_aggregate(events, expected_version)? {
+        let current = state
+            .events
+            .iter()
+            .filter(|e| &e.aggregate_id == aggregate_id)
+            .map(|e| e.aggregate_version)
+            .max();
+        check_expected_version(expected_version, current)?;
+    }
+
+    let mut batch_versions = HashSet::new();
+    let mut batch_ids = HashSet::new();
+    for event in events {
+        if state.ids.contains(&event.id) || !batch_ids.insert(event.id) {
+            return Err(duplicate_event_id(&event.id));
+        }
+        let key = (event.aggregate_id.clone(), event.aggregate_version);
+        if state.versions.contains(&key) || !batch_versions.insert(key) {
+            return Err(EventualiError::OptimisticConcurrency {
+                expected: event.aggregate_version,
+                actual: event.aggregate_version - 1,
+            });
+        }
+    }
+
+    Ok((batch_versions, batch_ids))
+}
+
+#[async_trait]
+impl EventStoreBackend for MemoryBackend {
+    async fn initialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<Vec<u64>> {
+        let mut state = self.state.write().await;
+        let (batch_versions, batch_ids) = check_batch(&state, &events, expected_version)?;
+
+        let first_position = state.events.len() as u64 + 1;
+        let positions = (first_position..first_position + events.len() as u64).collect();
+        state.versions.extend(batch_versions);
+        state.ids.extend(batch_ids);
+        state.events.extend(events);
+        Ok(positions)
+    }
+
+    async fn dry_run_save_events(&self, events: Vec<Event>) -> Result<()> {
+        let state = self.state.read().await;
+        check_batch(&state, &events, None)?;
+        Ok(())
+    }
+
+    async fn load_events(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<Event>> {
+        let state = self.state.read().await;
+        let mut events: Vec<Event> = state
+            .events
+            .iter()
+            .filter(|e| &e.aggregate_id == aggregate_id)
+            .filter(|e| from_version.is_none_or(|v| e.aggregate_version > v))
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.aggregate_version);
+        Ok(events)
+    }
+
+    async fn load_events_by_type(
+        &self,
+        aggregate_type: &str,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<Event>> {
+        let state = self.state.read().await;
+        Ok(state
+            .events
+            .iter()
+            .filter(|e| e.aggregate_type == aggregate_type)
+            .filter(|e| from_version.is_none_or(|v| e.aggregate_version > v))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let state = self.state.read().await;
+        Ok(state
+            .events
+            .iter()
+            .filter(|e| e.metadata.correlation_id.is_some_and(|id| id.to_string() == correlation_id))
+            .filter(|e| e.aggregate_id.starts_with(aggregate_id_prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let state = self.state.read().await;
+        Ok(state
+            .events
+            .iter()
+            .filter(|e| e.metadata.user_id.as_deref() == Some(user_id))
+            .filter(|e| e.aggregate_id.starts_with(aggregate_id_prefix))
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
+        let state = self.state.read().await;
+        Ok(state
+            .events
+            .iter()
+            .filter(|e| &e.aggregate_id == aggregate_id)
+            .map(|e| e.aggregate_version)
+            .max())
+    }
+
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        let state = self.state.read().await;
+        Ok(state
+            .events
+            .iter()
+            .enumerate()
+            .map(|(index, event)| (index as u64 + 1, event))
+            .skip(after_global as usize)
+            .take(limit)
+            .map(|(position, event)| (position, event.clone()))
+            .collect())
+    }
+
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        let state = self.state.read().await;
+        let types: BTreeSet<&str> = state.events.iter().map(|e| e.aggregate_type.as_str()).collect();
+        Ok(types.into_iter().map(str::to_string).collect())
+    }
+
+    async fn stats(&self) -> Result<StoreStats> {
+        let state = self.state.read().await;
+        let mut stats = StoreStatsAccumulator::default();
+        state.events.iter().for_each(|event| stats.add(event));
+        Ok(stats.finish())
+    }
+}
@@ -0,0 +1,125 @@
+//! Merging of concurrent writes that touch disjoint fields
+//!
+//! Two writers that load the same version of an aggregate and then update
+//! unrelated fields do not really conflict, yet the second save fails with
+//! `OptimisticConcurrency`. With `ConflictResolution::MergeDisjointFields` the
+//! store compares the JSON paths the losing writer's events set with those set
+//! by the events committed since it loaded, and when they are disjoint appends
+//! the losing events on top of the current version instead of failing.
+//!
+//! The merge never loses or overwrites data: any overlap, including a parent
+//! and one of its children, a non-JSON payload or a batch spanning several
+//! aggregates, is still reported as a conflict.
+
+use crate::{Event, EventData};
+use std::collections::BTreeSet;
+
+/// What the store does when a save hits a version conflict
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictResolution {
+    /// Fail with `OptimisticConcurrency`
+    #[default]
+    Reject,
+    /// Re-append the save on top of the current version when its events touch
+    /// no JSON path that was changed since the writer loaded the aggregate
+    MergeDisjointFields,
+}
+
+/// JSON Pointer (RFC 6901) paths of the values an event's payload sets, as
+/// the `replace` operations of a JSON Patch applying it would address.
+///
+/// Only leaves are listed, so sibling fields of one object stay disjoint. An
+/// empty object or array counts as a leaf. Non-JSON payloads are opaque and
+/// touch the whole document, the root path `""`.
+pub fn touched_paths(data: &EventData) -> BTreeSet<String> {
+    let mut paths = BTreeSet::new();
+    match data {
+        EventData::Json(value) => collect_leaf_paths(value, String::new(), &mut paths),
+        EventData::Protobuf(_) => {
+            paths.insert(String::new());
+        }
+    }
+    paths
+}
+
+fn collect_leaf_paths(value: &serde_json::Value, path: String, paths: &mut BTreeSet<String>) {
+    match value {
+        serde_json::Value::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let token = key.replace('~', "~0").replace('/', "~1");
+                collect_leaf_paths(child, format!("{path}/{token}"), paths);
+            }
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => {
+            // Arrays are replaced whole; merging by index would interleave edits
+            paths.insert(path);
+        }
+        _ => {
+            paths.insert(path);
+        }
+    }
+}
+
+/// Whether two JSON Pointers address the same value or one contains the other
+pub fn paths_overlap(a: &str, b: &str) -> bool {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    longer.starts_with(shorter) && (longer.len() == shorter.len() || longer[shorter.len()..].starts_with('/'))
+}
+
+/// Re-number `events`, one aggregate's save that lost a version race, to
+/// follow `committed`, the events saved since the writer loaded the aggregate.
+///
+/// Returns `None` when the save cannot be merged without risking a lost
+/// update: the batch spans aggregates, some path it sets was also set by a
+/// committed event, or no newer events are visible to merge onto.
+pub(crate) fn rebase_disjoint(events: &[Event], committed: &[Event]) -> Option<Vec<Event>> {
+    let first = events.first()?;
+    if events.iter().any(|e| e.aggregate_id != first.aggregate_id) {
+        return None;
+    }
+
+    let ours: BTreeSet<String> = events.iter().flat_map(|e| touched_paths(&e.data)).collect();
+    let theirs: BTreeSet<String> = committed.iter().flat_map(|e| touched_paths(&e.data)).collect();
+    if ours.iter().any(|a| theirs.iter().any(|b| paths_overlap(a, b))) {
+        return None;
+    }
+
+    let current = committed.iter().map(|e| e.aggregate_version).max()?;
+    let base = first.aggregate_version - 1;
+    Some(
+        events
+            .iter()
+            .cloned()
+            .map(|mut event| {
+                event.aggregate_version = current + (event.aggregate_version - base);
+                event
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_paths_are_leaves_and_overlap_only_along_one_branch() {
+        let paths = touched_paths(&EventData::Json(json!({
+            "address": { "city": "Oslo", "zip": "0150" },
+            "a/b": 1,
+            "tags": ["x"],
+        })));
+        let expected: BTreeSet<String> = ["/a~1b", "/address/city", "/address/zip", "/tags"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(paths, expected);
+
+        assert!(paths_overlap("/address", "/address/city"));
+        assert!(paths_overlap("/address/city", "/address/city"));
+        assert!(paths_overlap("", "/anything"));
+        assert!(!paths_overlap("/address/city", "/address/zip"));
+        assert!(!paths_overlap("/name", "/name_suffix"));
+    }
+}
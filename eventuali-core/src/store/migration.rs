@@ -0,0 +1,210 @@
+//! Copying every event from one store into another
+//!
+//! `StoreMigration` reads the source in global order and saves each batch to
+//! the target. Reading runs in a background task ahead of the writes, so a
+//! slow target does not leave the source connection idle; at most
+//! `max_in_flight_batches` batches are being read or waiting to be written
+//! at once.
+//!
+//! Without a governor the migration writes as fast as the target accepts,
+//! which can starve live traffic on a production database. A
+//! `ThroughputGovernor` paces the writes to a target rate instead.
+
+use crate::store::EventStore;
+use crate::{Event, EventualiError, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Pace limit for a migration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThroughputGovernor {
+    /// Events per second written to the target, averaged over the run
+    pub target_events_per_sec: f64,
+}
+
+impl ThroughputGovernor {
+    pub fn new(target_events_per_sec: f64) -> Self {
+        Self { target_events_per_sec }
+    }
+
+    /// How long after the start `written` events may have been written
+    fn due_after(&self, written: u64) -> Duration {
+        Duration::from_secs_f64(written as f64 / self.target_events_per_sec)
+    }
+}
+
+/// What a migration copied and how fast
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationReport {
+    pub events_migrated: u64,
+    pub batches_written: u64,
+    /// Global position in the source of the last event written; pass it to
+    /// `starting_after` to resume
+    pub last_source_position: u64,
+    pub elapsed: Duration,
+    /// The governor's target, if the migration was governed
+    pub target_events_per_sec: Option<f64>,
+    pub achieved_events_per_sec: f64,
+}
+
+/// Copies events from a source store to a target store in global order
+pub struct StoreMigration {
+    source: Arc<dyn EventStore + Send + Sync>,
+    target: Arc<dyn EventStore + Send + Sync>,
+    batch_size: usize,
+    max_in_flight_batches: usize,
+    after_position: u64,
+    governor: Option<ThroughputGovernor>,
+}
+
+/// A batch read from the source, holding its in-flight slot until written
+type ReadBatch = (Result<Vec<(u64, Event)>>, OwnedSemaphorePermit);
+
+impl StoreMigration {
+    pub fn new(
+        source: Arc<dyn EventStore + Send + Sync>,
+        target: Arc<dyn EventStore + Send + Sync>,
+    ) -> Self {
+        Self {
+            source,
+            target,
+            batch_size: 500,
+            max_in_flight_batches: 2,
+            after_position: 0,
+            governor: None,
+        }
+    }
+
+    /// Events read from the source and saved to the target per round trip
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Batches being read from the source or waiting to be written at once;
+    /// the reader pauses once this many are outstanding
+    pub fn with_max_in_flight_batches(mut self, max_in_flight_batches: usize) -> Self {
+        self.max_in_flight_batches = max_in_flight_batches.max(1);
+        self
+    }
+
+    /// Only migrate events after this global position in the source
+    pub fn starting_after(mut self, position: u64) -> Self {
+        self.after_position = position;
+        self
+    }
+
+    /// Pace writes to the target
+    pub fn with_governor(mut self, governor: ThroughputGovernor) -> Self {
+        self.governor = Some(governor);
+        self
+    }
+
+    /// Copy every remaining event, returning what was copied.
+    ///
+    /// Batches are saved in source order, so each aggregate's events reach the
+    /// target in version order. If a read or a save fails, the error is
+    /// returned and batches before it stay written; `starting_after` with the
+    /// last position the target holds resumes from there.
+    pub async fn run(&self) -> Result<MigrationReport> {
+        if let Some(governor) = &self.governor {
+            if governor.target_events_per_sec.is_nan() || governor.target_events_per_sec <= 0.0 {
+                return Err(EventualiError::Configuration(
+                    "Migration target_events_per_sec must be positive".to_string(),
+                ));
+            }
+        }
+
+        let (sender, mut batches) = mpsc::channel::<ReadBatch>(self.max_in_flight_batches);
+        let reader = tokio::spawn(read_batches(
+            self.source.clone(),
+            self.after_position,
+            self.batch_size,
+            Arc::new(Semaphore::new(self.max_in_flight_batches)),
+            sender,
+        ));
+
+        let started = Instant::now();
+        let mut report = MigrationReport {
+            events_migrated: 0,
+            batches_written: 0,
+            last_source_position: self.after_position,
+            elapsed: Duration::ZERO,
+            target_events_per_sec: self.governor.as_ref().map(|g| g.target_events_per_sec),
+            achieved_events_per_sec: 0.0,
+        };
+
+        let written = async {
+            while let Some((batch, in_flight)) = batches.recv().await {
+                let batch = batch?;
+                let Some(&(last_position, _)) = batch.last() else { continue };
+                let count = batch.len() as u64;
+
+                self.target
+                    .save_events(batch.into_iter().map(|(_, event)| event).collect())
+                    .await?;
+                drop(in_flight);
+                report.events_migrated += count;
+                report.batches_written += 1;
+                report.last_source_position = last_position;
+
+                if let Some(governor) = &self.governor {
+                    tokio::time::sleep_until(started + governor.due_after(report.events_migrated)).await;
+                }
+            }
+            Ok::<(), EventualiError>(())
+        }
+        .await;
+
+        // Dropping the receiver stops the reader if the writes failed
+        drop(batches);
+        reader
+            .await
+            .map_err(|e| EventualiError::InvalidState(format!("Migration reader task failed: {e}")))?;
+        written?;
+
+        report.elapsed = started.elapsed();
+        report.achieved_events_per_sec = if report.elapsed.is_zero() {
+            0.0
+        } else {
+            report.events_migrated as f64 / report.elapsed.as_secs_f64()
+        };
+        Ok(report)
+    }
+}
+
+/// Copy every event from `source` to `target` as fast as the target accepts
+pub async fn migrate_store(
+    source: Arc<dyn EventStore + Send + Sync>,
+    target: Arc<dyn EventStore + Send + Sync>,
+) -> Result<MigrationReport> {
+    StoreMigration::new(source, target).run().await
+}
+
+async fn read_batches(
+    source: Arc<dyn EventStore + Send + Sync>,
+    mut after_position: u64,
+    batch_size: usize,
+    in_flight: Arc<Semaphore>,
+    sender: mpsc::Sender<ReadBatch>,
+) {
+    loop {
+        // The semaphore is never closed, so this only waits for a written batch
+        let Ok(permit) = in_flight.clone().acquire_owned().await else { return };
+        let batch = source.load_events_after_position(after_position, batch_size).await;
+        let done = match &batch {
+            Ok(events) => {
+                if let Some(&(position, _)) = events.last() {
+                    after_position = position;
+                }
+                events.len() < batch_size
+            }
+            Err(_) => true,
+        };
+        if sender.send((batch, permit)).await.is_err() || done {
+            return;
+        }
+    }
+}
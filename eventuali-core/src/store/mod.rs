@@ -1,21 +1,92 @@
 pub mod traits;
 pub mod postgres;
 pub mod sqlite;
+pub mod memory;
 pub mod config;
+pub mod aggregate_lock;
+pub mod compaction;
+pub mod erasure;
+pub mod existence;
+pub mod failed_writes;
+#[cfg(feature = "test-util")]
+pub mod fault_injection;
+pub mod merge;
+pub mod migration;
+pub mod outbox;
+pub mod outbox_relay;
+#[cfg(feature = "parquet")]
+pub mod parquet_export;
+pub mod quarantine;
+pub mod recent;
+pub mod timeout;
+pub mod validation;
 
-pub use traits::{EventStore, EventStoreBackend};
-pub use config::EventStoreConfig;
+pub use traits::{EventStore, EventStoreBackend, StoreStats, STORAGE_FORMAT_VERSION};
+pub use config::{EventStoreConfig, GlobalPositionAllocation, OversizedBatch, TimestampSource};
+pub use aggregate_lock::{AggregateLocks, AggregateLockGuard};
+pub use compaction::{Compactor, CompactionReport, CompactedStream};
+pub use erasure::{tombstone, DeleteMode, TOMBSTONE_EVENT_TYPE};
+pub use existence::{ExistenceCache, ExistenceCacheStats};
+pub use failed_writes::{FailedWrite, FailedWriteFilter, FailedWriteLog};
+#[cfg(feature = "test-util")]
+pub use fault_injection::{BackendOperation, FaultInjectingBackend, FaultInjector, InjectedFault};
+pub use merge::ConflictResolution;
+pub use migration::{migrate_store, MigrationReport, StoreMigration, ThroughputGovernor};
+pub use outbox::{PublishFailure, PublishOutbox};
+pub use outbox_relay::OutboxRelay;
+#[cfg(feature = "parquet")]
+pub use parquet_export::{ParquetExport, ParquetExportReport};
+pub use quarantine::{LenientLoad, QuarantinedRow};
+pub use recent::RecentEvents;
+pub use timeout::{operation_timed_out, with_operation_timeout};
+pub use validation::{StreamAnomaly, StreamValidation};
 
-use crate::{Event, AggregateId, AggregateVersion, Result};
+use crate::{Event, EventId, AggregateId, AggregateVersion, CodecRegistry, EventualiError, Result};
+use crate::event::{new_event_id, IdAllocator};
+use crate::clock::{Clock, SystemClock};
 use crate::streaming::EventStreamer;
 use async_trait::async_trait;
+use futures::stream::{self, Stream, TryStreamExt};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 pub struct EventStoreImpl<B: EventStoreBackend> {
     backend: B,
     streamer: Option<Arc<dyn EventStreamer + Send + Sync>>,
+    global_position_allocation: GlobalPositionAllocation,
+    /// Last position published under `GlobalPositionAllocation::InProcess`
     global_position: Arc<Mutex<u64>>,
+    max_aggregate_version: Option<AggregateVersion>,
+    timestamp_source: TimestampSource,
+    clock: Arc<dyn Clock>,
+    aggregate_locks: Option<AggregateLocks>,
+    publish_outbox: Option<PublishOutbox>,
+    conflict_resolution: ConflictResolution,
+    failed_writes: Option<Arc<FailedWriteLog>>,
+    operation_timeout: Option<Duration>,
+    recent_events: Option<RecentEvents>,
+    existence_cache: Option<ExistenceCache>,
+    max_batch_events: Option<usize>,
+    oversized_batch: OversizedBatch,
+    id_allocator: Option<Arc<dyn IdAllocator>>,
+}
+
+/// Times a save is re-merged when writers keep committing ahead of it
+const MAX_MERGE_ATTEMPTS: usize = 3;
+
+/// Events a save committed, and their global positions unless the backend
+/// could not assign them yet
+type SavedEvents = (Vec<Event>, Result<Vec<u64>>);
+
+/// Sort a backend save's outcome into committed events or a rejected save.
+/// `PositionsPending` means the save committed, so only its positions are missing.
+fn committed_save(events: Vec<Event>, saved: Result<Vec<u64>>) -> Result<SavedEvents> {
+    match saved {
+        Ok(positions) => Ok((events, Ok(positions))),
+        Err(pending @ EventualiError::PositionsPending { .. }) => Ok((events, Err(pending))),
+        Err(e) => Err(e),
+    }
 }
 
 impl<B: EventStoreBackend> EventStoreImpl<B> {
@@ -23,38 +94,405 @@ impl<B: EventStoreBackend> EventStoreImpl<B> {
         Self { 
             backend,
             streamer: None,
+            global_position_allocation: GlobalPositionAllocation::Database,
             global_position: Arc::new(Mutex::new(0)),
+            max_aggregate_version: None,
+            timestamp_source: TimestampSource::ClientProvided,
+            clock: Arc::new(SystemClock),
+            aggregate_locks: None,
+            publish_outbox: None,
+            conflict_resolution: ConflictResolution::Reject,
+            failed_writes: None,
+            operation_timeout: None,
+            recent_events: None,
+            existence_cache: None,
+            max_batch_events: None,
+            oversized_batch: OversizedBatch::Reject,
+            id_allocator: None,
         }
     }
-}
 
-#[async_trait]
-impl<B: EventStoreBackend + Send + Sync> EventStore for EventStoreImpl<B> {
-    async fn save_events(&self, events: Vec<Event>) -> Result<()> {
+    /// Choose whether saved events keep their own timestamps or get the store clock's.
+    pub fn with_timestamp_source(mut self, source: TimestampSource) -> Self {
+        self.timestamp_source = source;
+        self
+    }
+
+    /// Clock used for server-assigned timestamps.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Choose where the global positions handed to the streamer come from.
+    pub fn with_global_position_allocation(mut self, allocation: GlobalPositionAllocation) -> Self {
+        self.global_position_allocation = allocation;
+        self
+    }
+
+    /// Reject any save that would push an aggregate past `max_version`.
+    pub fn with_max_aggregate_version(mut self, max_version: Option<AggregateVersion>) -> Self {
+        self.max_aggregate_version = max_version;
+        self
+    }
+
+    /// Serialize writes to the same aggregate within this process.
+    ///
+    /// `save_events` then waits for other in-flight saves touching the same
+    /// aggregates instead of racing them into the database. Off by default.
+    pub fn with_aggregate_locking(mut self, enabled: bool) -> Self {
+        self.aggregate_locks = enabled.then(AggregateLocks::new);
+        self
+    }
+
+    /// Choose how a save that hits a version conflict is handled.
+    ///
+    /// `MergeDisjointFields` appends a save whose events set none of the JSON
+    /// paths changed since its writer loaded the aggregate on top of the
+    /// current version, renumbering its events. It is lossy-averse: any
+    /// overlapping path still fails with `OptimisticConcurrency`, so no
+    /// committed change is ever overwritten. Off (`Reject`) by default.
+    pub fn with_conflict_resolution(mut self, resolution: ConflictResolution) -> Self {
+        self.conflict_resolution = resolution;
+        self
+    }
+
+    /// Publish saved events through a bounded outbox of `capacity` events.
+    ///
+    /// Without one, `save_events` publishes each event itself and returns only
+    /// once the streamer has taken it. With one, a background task publishes and
+    /// a save waits only while the outbox is full, so a slow streamer holds back
+    /// publishing instead of writes, and at most `capacity` events are queued.
+    /// Publish errors then no longer fail the save; collect them with
+    /// `PublishOutbox::take_failures`. The background task stops when the store
+    /// is closed, which publishes what is queued first, or dropped, which does not.
+    pub fn with_publish_outbox(mut self, capacity: usize) -> Self {
+        self.publish_outbox = Some(PublishOutbox::new(capacity));
+        self
+    }
+
+    /// The publish outbox, when one is configured
+    pub fn publish_outbox(&self) -> Option<&PublishOutbox> {
+        self.publish_outbox.as_ref()
+    }
+
+    /// Record every save the backend rejects in `log`.
+    ///
+    /// Failures to publish already-committed events are not recorded, since
+    /// the write itself succeeded. If recording fails, the save still returns
+    /// its original error and the recording error is logged.
+    pub fn with_failed_write_log(mut self, log: Arc<FailedWriteLog>) -> Self {
+        self.failed_writes = Some(log);
+        self
+    }
+
+    /// Fail any backend call that takes longer than `timeout` with an `Io`
+    /// error of kind `TimedOut`; `None` waits indefinitely. Calls inside
+    /// `with_operation_timeout` use its limit instead.
+    ///
+    /// Only the database round trip is limited. Waiting for an aggregate lock,
+    /// recording a failed write and publishing saved events are not, so a
+    /// save that committed is never reported as timed out.
+    pub fn with_operation_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.operation_timeout = timeout;
+        self
+    }
+
+    /// Keep the last `capacity` events saved through this store in memory,
+    /// served by `recent_events` without reading the database.
+    ///
+    /// Events are added once the backend has committed them, so the buffer
+    /// never holds a rejected write, but it only sees saves made through this
+    /// store. Off by default.
+    pub fn with_recent_events(mut self, capacity: usize) -> Self {
+        self.recent_events = Some(RecentEvents::new(capacity));
+        self
+    }
+
+    /// Remember up to `capacity` aggregates seen committed or loaded, so
+    /// `aggregate_exists` answers repeat checks without the database. Only
+    /// existing aggregates are cached; an unknown one is always looked up.
+    /// Off by default.
+    pub fn with_existence_cache(mut self, capacity: usize) -> Self {
+        self.existence_cache = Some(ExistenceCache::new(capacity));
+        self
+    }
+
+    /// Take the IDs `new_event_id` hands out from `allocator`, such as an
+    /// `EventIdKind` or an external sequencer, instead of the process
+    /// default kind. Only this store is affected.
+    pub fn with_id_allocator(mut self, allocator: Arc<dyn IdAllocator>) -> Self {
+        self.id_allocator = Some(allocator);
+        self
+    }
+
+    /// Cap a single save at `max_events` events; larger saves are rejected
+    /// with a `Validation` error or split into consecutive transactions as
+    /// `oversized` says. `None` leaves saves unbounded, the default.
+    pub fn with_max_batch_events(mut self, max_events: Option<usize>, oversized: OversizedBatch) -> Self {
+        self.max_batch_events = max_events.map(|max| max.max(1));
+        self.oversized_batch = oversized;
+        self
+    }
+
+    /// Record the aggregates of `events` in the existence cache, if any
+    fn note_existing(&self, events: &[Event]) -> Result<()> {
+        match &self.existence_cache {
+            Some(cache) => cache.insert(events.iter().map(|e| &e.aggregate_id)),
+            None => Ok(()),
+        }
+    }
+
+    async fn timed<T>(&self, operation: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+        timeout::run_with_timeout(self.operation_timeout, operation).await
+    }
+
+    /// The failed-write log, when one is configured
+    pub fn failed_write_log(&self) -> Option<&Arc<FailedWriteLog>> {
+        self.failed_writes.as_ref()
+    }
+
+    /// Per-aggregate locks, when aggregate locking is enabled
+    pub fn aggregate_locks(&self) -> Option<&AggregateLocks> {
+        self.aggregate_locks.as_ref()
+    }
+
+    /// Append events built from the aggregate's current version.
+    ///
+    /// With aggregate locking enabled the version read and the write happen under
+    /// the aggregate's lock, so concurrent appenders in this process never build
+    /// on a stale version. Without it this is a plain read followed by a save.
+    pub async fn append_to_aggregate<F>(&self, aggregate_id: &AggregateId, build: F) -> Result<()>
+    where
+        F: FnOnce(Option<AggregateVersion>) -> Vec<Event> + Send,
+    {
+        let _guard = match &self.aggregate_locks {
+            Some(locks) => Some(locks.lock([aggregate_id]).await),
+            None => None,
+        };
+
+        let current = self.timed(self.backend.get_aggregate_version(aggregate_id)).await?;
+        let events = build(current);
+        if let Some(event) = events.iter().find(|e| &e.aggregate_id != aggregate_id) {
+            return Err(EventualiError::Validation(format!(
+                "Event for aggregate {} cannot be appended to aggregate {}",
+                event.aggregate_id, aggregate_id
+            )));
+        }
+        self.write_bounded(events, None).await
+    }
+
+    /// Write `events` in one transaction, or several when they exceed
+    /// `max_batch_events` and oversized batches are split. Each split chunk
+    /// expects the version the chunks before it left the aggregate at.
+    async fn write_bounded(&self, events: Vec<Event>, expected_version: Option<AggregateVersion>) -> Result<()> {
+        let Some(max) = self.max_batch_events.filter(|max| events.len() > *max) else {
+            return self.write_events(events, expected_version).await;
+        };
+        traits::expected_aggregate(&events, expected_version)?;
+        match self.oversized_batch {
+            OversizedBatch::Reject => {
+                let error = EventualiError::Validation(format!(
+                    "Batch of {} events exceeds the maximum of {max} per save",
+                    events.len()
+                ));
+                self.record_rejected(events, &error).await;
+                Err(error)
+            }
+            OversizedBatch::Split => {
+                let mut expected_version = expected_version;
+                for chunk in events.chunks(max) {
+                    self.write_events(chunk.to_vec(), expected_version).await?;
+                    expected_version = expected_version.map(|version| version + chunk.len() as AggregateVersion);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Note a rejected save in the failed-write log, if there is one
+    async fn record_rejected(&self, attempted: Vec<Event>, error: &EventualiError) {
+        if let Some(log) = &self.failed_writes {
+            if let Err(log_error) = log.clone().record(attempted, error, self.clock.now()).await {
+                tracing::warn!(error = %log_error, "Failed to record rejected save");
+            }
+        }
+    }
+
+    async fn write_events(&self, events: Vec<Event>, expected_version: Option<AggregateVersion>) -> Result<()> {
         // Save events to backend first
-        self.backend.save_events(events.clone()).await?;
+        let attempted = self.failed_writes.as_ref().map(|_| events.clone());
+        let (events, positions) = match self.save_to_backend(events, expected_version).await {
+            Ok(saved) => saved,
+            Err(e) => {
+                if let Some(attempted) = attempted {
+                    self.record_rejected(attempted, &e).await;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(recent) = &self.recent_events {
+            recent.extend(&events)?;
+        }
+        self.note_existing(&events)?;
         
         // If we have a streamer configured, publish the events
         if let Some(streamer) = &self.streamer {
+            // Only database allocation needs the positions; without them the
+            // saved events cannot be published in order
+            let assigned = match self.global_position_allocation {
+                GlobalPositionAllocation::Database => positions?,
+                GlobalPositionAllocation::InProcess => Vec::new(),
+            };
             let mut global_pos = self.global_position.lock().await;
             
-            for event in events {
-                *global_pos += 1;
+            for (index, event) in events.into_iter().enumerate() {
+                let global_position = match self.global_position_allocation {
+                    GlobalPositionAllocation::Database => assigned[index],
+                    GlobalPositionAllocation::InProcess => {
+                        *global_pos += 1;
+                        *global_pos
+                    }
+                };
                 let stream_position = event.aggregate_version as u64;
                 
-                streamer.publish_event(event, stream_position, *global_pos).await?;
+                match &self.publish_outbox {
+                    Some(outbox) => outbox.push(streamer.clone(), event, stream_position, global_position).await?,
+                    None => streamer.publish_event(event, stream_position, global_position).await?,
+                }
             }
         }
         
         Ok(())
     }
 
+    async fn save_to_backend(
+        &self,
+        mut events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<SavedEvents> {
+        self.check_max_aggregate_version(&events)?;
+
+        if self.timestamp_source == TimestampSource::ServerAssigned {
+            let now = self.clock.now();
+            for event in &mut events {
+                event.timestamp = now;
+            }
+        }
+
+        // A save expecting a version asked to fail rather than be rebased
+        match self.timed(self.backend.save_events_expecting(events.clone(), expected_version)).await {
+            Err(conflict @ EventualiError::OptimisticConcurrency { .. })
+                if expected_version.is_none()
+                    && self.conflict_resolution == ConflictResolution::MergeDisjointFields =>
+            {
+                self.merge_after_conflict(events, conflict).await
+            }
+            saved => committed_save(events, saved),
+        }
+    }
+
+    /// Re-append a save that lost a version race, if it touches only fields
+    /// nobody changed since its writer loaded the aggregate; otherwise return
+    /// the original conflict.
+    async fn merge_after_conflict(
+        &self,
+        events: Vec<Event>,
+        conflict: EventualiError,
+    ) -> Result<SavedEvents> {
+        let Some(first) = events.first() else {
+            return Err(conflict);
+        };
+        let aggregate_id = first.aggregate_id.clone();
+        let loaded_version = first.aggregate_version - 1;
+
+        for _ in 0..MAX_MERGE_ATTEMPTS {
+            let committed = self.timed(self.backend.load_events(&aggregate_id, Some(loaded_version))).await?;
+            let Some(merged) = merge::rebase_disjoint(&events, &committed) else {
+                return Err(conflict);
+            };
+            self.check_max_aggregate_version(&merged)?;
+            match self.timed(self.backend.save_events_expecting(merged.clone(), None)).await {
+                Err(EventualiError::OptimisticConcurrency { .. }) => continue,
+                saved => return committed_save(merged, saved),
+            }
+        }
+        Err(conflict)
+    }
+
+    fn check_max_aggregate_version(&self, events: &[Event]) -> Result<()> {
+        if let Some(max_version) = self.max_aggregate_version {
+            if let Some(event) = events.iter().find(|e| e.aggregate_version > max_version) {
+                return Err(EventualiError::Validation(format!(
+                    "Aggregate {} would reach version {}, exceeding the configured maximum of {}",
+                    event.aggregate_id, event.aggregate_version, max_version
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: EventStoreBackend + Send + Sync> EventStore for EventStoreImpl<B> {
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<()> {
+        let _guard = match &self.aggregate_locks {
+            Some(locks) => Some(locks.lock(events.iter().map(|e| &e.aggregate_id)).await),
+            None => None,
+        };
+        self.write_bounded(events, expected_version).await
+    }
+
+    async fn dry_run_save_events(&self, mut events: Vec<Event>) -> Result<()> {
+        self.check_max_aggregate_version(&events)?;
+        if let Some(max) = self.max_batch_events.filter(|max| events.len() > *max) {
+            if self.oversized_batch == OversizedBatch::Reject {
+                return Err(EventualiError::Validation(format!(
+                    "Batch of {} events exceeds the maximum of {max} per save",
+                    events.len()
+                )));
+            }
+        }
+        if self.timestamp_source == TimestampSource::ServerAssigned {
+            let now = self.clock.now();
+            for event in &mut events {
+                event.timestamp = now;
+            }
+        }
+        self.timed(self.backend.dry_run_save_events(events)).await
+    }
+
     async fn load_events(
         &self,
         aggregate_id: &AggregateId,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>> {
-        self.backend.load_events(aggregate_id, from_version).await
+        let events = self.timed(self.backend.load_events(aggregate_id, from_version)).await?;
+        self.note_existing(&events)?;
+        Ok(events)
+    }
+
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        self.timed(self.backend.load_events_lenient(aggregate_id, from_version)).await
+    }
+
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        let events = self.timed(self.backend.load_events_at_version(aggregate_id, version)).await?;
+        self.note_existing(&events)?;
+        Ok(events)
     }
 
     async fn load_events_by_type(
@@ -62,36 +500,260 @@ impl<B: EventStoreBackend + Send + Sync> EventStore for EventStoreImpl<B> {
         aggregate_type: &str,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>> {
-        self.backend.load_events_by_type(aggregate_type, from_version).await
+        self.timed(self.backend.load_events_by_type(aggregate_type, from_version)).await
+    }
+
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.timed(
+            self.backend
+                .get_events_by_correlation_id_with_prefix(correlation_id, aggregate_id_prefix, limit),
+        )
+        .await
+    }
+
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.timed(self.backend.get_events_by_user_id_with_prefix(user_id, aggregate_id_prefix, limit))
+            .await
     }
 
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
-        self.backend.get_aggregate_version(aggregate_id).await
+        let version = self.timed(self.backend.get_aggregate_version(aggregate_id)).await?;
+        if let (Some(cache), Some(_)) = (&self.existence_cache, version) {
+            cache.insert([aggregate_id])?;
+        }
+        Ok(version)
+    }
+
+    async fn aggregate_exists(&self, aggregate_id: &AggregateId) -> Result<bool> {
+        if let Some(cache) = &self.existence_cache {
+            if cache.check(aggregate_id)? {
+                return Ok(true);
+            }
+        }
+        Ok(self.get_aggregate_version(aggregate_id).await?.is_some())
+    }
+
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        self.timed(self.backend.load_events_after_position(after_global, limit)).await
+    }
+
+    async fn load_events_of_type_after_position(
+        &self,
+        aggregate_type: &str,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        self.timed(self.backend.load_events_of_type_after_position(aggregate_type, after_global, limit)).await
+    }
+
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        self.timed(self.backend.list_aggregate_types()).await
+    }
+
+    async fn stats(&self) -> Result<StoreStats> {
+        self.timed(self.backend.stats()).await
+    }
+
+    async fn archive_events_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> Result<u64> {
+        self.timed(self.backend.archive_events_before(cutoff)).await
+    }
+
+    async fn sync_to_disk(&self) -> Result<()> {
+        self.timed(self.backend.sync_to_disk()).await
+    }
+
+    async fn load_unpublished_events(&self, limit: usize) -> Result<Vec<(u64, Event)>> {
+        self.timed(self.backend.load_unpublished_events(limit)).await
+    }
+
+    async fn mark_events_published(&self, event_ids: &[crate::EventId]) -> Result<()> {
+        self.timed(self.backend.mark_events_published(event_ids)).await
+    }
+
+    async fn compact_aggregate(&self, aggregate_id: &AggregateId, compactor: &dyn Compactor) -> Result<CompactionReport> {
+        // Appends in this process wait, so the compactor sees a settled stream
+        let _guard = match &self.aggregate_locks {
+            Some(locks) => Some(locks.lock([aggregate_id]).await),
+            None => None,
+        };
+        self.timed(self.backend.compact_aggregate(aggregate_id, compactor)).await
+    }
+
+    async fn load_compaction_history(&self, aggregate_id: &AggregateId) -> Result<Vec<CompactionReport>> {
+        self.timed(self.backend.load_compaction_history(aggregate_id)).await
+    }
+
+    async fn delete_aggregate_events_with_mode(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<u64> {
+        let _guard = match &self.aggregate_locks {
+            Some(locks) => Some(locks.lock([aggregate_id]).await),
+            None => None,
+        };
+        let erased = self.timed(self.backend.delete_aggregate_events(aggregate_id, mode)).await?;
+        if let Some(log) = &self.failed_writes {
+            log.erase_aggregate(aggregate_id)?;
+        }
+        if let (Some(cache), DeleteMode::Hard) = (&self.existence_cache, mode) {
+            cache.invalidate(aggregate_id)?;
+        }
+        if let Some(recent) = &self.recent_events {
+            recent.erase(aggregate_id, mode)?;
+        }
+        Ok(erased)
+    }
+
+    async fn query_failed_writes(&self, filter: &FailedWriteFilter, limit: usize) -> Result<Vec<FailedWrite>> {
+        match &self.failed_writes {
+            Some(log) => log.query(filter, limit),
+            None => Err(EventualiError::Configuration("Failed-write log is not enabled".to_string())),
+        }
+    }
+
+    async fn recent_events(&self, n: usize) -> Result<Vec<Event>> {
+        match &self.recent_events {
+            Some(recent) => recent.latest(n),
+            None => Err(EventualiError::Configuration("Recent-events buffer is not enabled".to_string())),
+        }
+    }
+
+    async fn existence_cache_stats(&self) -> Result<ExistenceCacheStats> {
+        match &self.existence_cache {
+            Some(cache) => cache.stats(),
+            None => Err(EventualiError::Configuration("Existence cache is not enabled".to_string())),
+        }
+    }
+
+    async fn close(&self) -> Result<()> {
+        if let Some(cache) = &self.existence_cache {
+            cache.clear()?;
+        }
+        let drained = match &self.publish_outbox {
+            Some(outbox) => outbox.close().await,
+            None => Ok(()),
+        };
+        self.backend.close().await?;
+        drained
+    }
+    
+    fn new_event_id(&self) -> Result<EventId> {
+        match &self.id_allocator {
+            Some(allocator) => allocator.allocate(),
+            None => Ok(new_event_id()),
+        }
+    }
+    
+    fn set_id_allocator(&mut self, allocator: Arc<dyn IdAllocator>) {
+        self.id_allocator = Some(allocator);
     }
     
     fn set_event_streamer(&mut self, streamer: Arc<dyn EventStreamer + Send + Sync>) {
         self.streamer = Some(streamer);
     }
+
+    fn event_streamer(&self) -> Option<Arc<dyn EventStreamer + Send + Sync>> {
+        self.streamer.clone()
+    }
+}
+
+impl dyn EventStore + Send + Sync {
+    /// Stream every event committed after global position `from_global`, in global
+    /// order, fetching `batch_size` events per query so the store is never loaded
+    /// into memory at once. Pass `0` to stream the whole store.
+    pub fn stream_all(
+        &self,
+        from_global: u64,
+        batch_size: usize,
+    ) -> impl Stream<Item = Result<Event>> + Send + '_ {
+        let batch_size = batch_size.max(1);
+
+        stream::try_unfold(from_global, move |after_global| async move {
+            let page = self.load_events_after_position(after_global, batch_size).await?;
+            // An empty page is the end of the store; otherwise resume after its last event
+            let next = page.last().map(|&(position, _)| position);
+            let events = stream::iter(page.into_iter().map(|(_, event)| Ok(event)));
+            Ok::<_, EventualiError>(next.map(|last_position| (events, last_position)))
+        })
+        .try_flatten()
+    }
 }
 
 // Factory function for creating event stores
 pub async fn create_event_store(config: EventStoreConfig) -> Result<Box<dyn EventStore + Send + Sync>> {
+    create_event_store_with_codecs(config, Arc::new(CodecRegistry::new())).await
+}
+
+/// Create an event store whose backend resolves custom payload codecs from `codecs`.
+///
+/// Fails if the config selects a codec that is neither built in nor registered.
+pub async fn create_event_store_with_codecs(
+    config: EventStoreConfig,
+    codecs: Arc<CodecRegistry>,
+) -> Result<Box<dyn EventStore + Send + Sync>> {
+    if let Some(codec) = config.codec() {
+        if !CodecRegistry::is_built_in(codec) && !codecs.contains(codec) {
+            return Err(EventualiError::Configuration(format!(
+                "Codec '{codec}' is not registered"
+            )));
+        }
+    }
+
     match &config {
         #[cfg(feature = "postgres")]
         EventStoreConfig::PostgreSQL { .. } => {
-            let mut backend = postgres::PostgreSQLBackend::new(&config).await?;
+            let mut backend = postgres::PostgreSQLBackend::new(&config)
+                .await?
+                .with_codec_registry(codecs);
             backend.initialize().await?;
-            Ok(Box::new(EventStoreImpl::new(backend)))
+            configured_store(backend, &config)
         }
         #[cfg(feature = "sqlite")]
         EventStoreConfig::SQLite { .. } => {
-            let mut backend = sqlite::SQLiteBackend::new(&config).await?;
+            let mut backend = sqlite::SQLiteBackend::new(&config)
+                .await?
+                .with_codec_registry(codecs);
             backend.initialize().await?;
-            Ok(Box::new(EventStoreImpl::new(backend)))
+            configured_store(backend, &config)
         }
-        #[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+        #[allow(unreachable_patterns)]
         _ => Err(EventualiError::Configuration(
-            "No database backend features enabled".to_string(),
+            "No database backend feature enabled for this configuration".to_string(),
         )),
     }
-}
\ No newline at end of file
+}
+
+/// Wrap an initialized backend in a store with every option `config` sets
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+fn configured_store<B>(backend: B, config: &EventStoreConfig) -> Result<Box<dyn EventStore + Send + Sync>>
+where
+    B: EventStoreBackend + Send + Sync + 'static,
+{
+    let mut store = EventStoreImpl::new(backend)
+        .with_max_aggregate_version(config.max_aggregate_version())
+        .with_timestamp_source(config.timestamp_source())
+        .with_aggregate_locking(config.aggregate_locking())
+        .with_global_position_allocation(config.global_position_allocation())
+        .with_operation_timeout(config.operation_timeout())
+        .with_max_batch_events(config.max_batch_events(), config.oversized_batch());
+    if let Some(path) = config.failed_write_log_path() {
+        store = store.with_failed_write_log(Arc::new(FailedWriteLog::open(path, None)?));
+    }
+    if let Some(capacity) = config.recent_events_capacity() {
+        store = store.with_recent_events(capacity);
+    }
+    if let Some(capacity) = config.existence_cache_capacity() {
+        store = store.with_existence_cache(capacity);
+    }
+    if let Some(kind) = config.event_id_kind() {
+        store = store.with_id_allocator(Arc::new(kind));
+    }
+    Ok(Box::new(store))
+}
@@ -0,0 +1,162 @@
+//! Bounded hand-off between committed writes and the event streamer
+//!
+//! Publishing straight from `save_events` ties every write to the speed of the
+//! slowest streamer. With an outbox, a save only enqueues its committed events
+//! and a background task publishes them in order; a save waits only when the
+//! outbox is full, and never while the database write is in progress.
+//!
+//! The drain task belongs to the outbox: it stops when the outbox is closed or
+//! dropped, and events the streamer rejects are kept as `PublishFailure`s.
+
+use crate::streaming::EventStreamer;
+use crate::{Event, EventId, EventualiError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot, OnceCell};
+use tokio::task::JoinHandle;
+
+/// Publish failures kept for `take_failures`; older ones are dropped first
+const MAX_PUBLISH_FAILURES: usize = 1000;
+
+/// A committed event the streamer did not accept
+#[derive(Debug, Clone)]
+pub struct PublishFailure {
+    pub event_id: EventId,
+    pub global_position: u64,
+    pub error: String,
+}
+
+struct Publish {
+    streamer: Arc<dyn EventStreamer + Send + Sync>,
+    event: Event,
+    stream_position: u64,
+    global_position: u64,
+}
+
+enum OutboxMessage {
+    Publish(Box<Publish>),
+    /// Acknowledged once everything queued before it has been published
+    Close(oneshot::Sender<()>),
+}
+
+/// Queue of committed events waiting to be published, drained by a background task
+pub struct PublishOutbox {
+    capacity: usize,
+    sender: OnceCell<mpsc::Sender<OutboxMessage>>,
+    drain_task: Mutex<Option<JoinHandle<()>>>,
+    failures: Arc<Mutex<Vec<PublishFailure>>>,
+    closed: AtomicBool,
+}
+
+impl PublishOutbox {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            sender: OnceCell::new(),
+            drain_task: Mutex::new(None),
+            failures: Arc::new(Mutex::new(Vec::new())),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Events queued and not yet handed to the streamer
+    pub fn pending(&self) -> usize {
+        self.sender
+            .get()
+            .map_or(0, |sender| sender.max_capacity() - sender.capacity())
+    }
+
+    /// Events the streamer rejected since the last call, oldest first
+    pub fn take_failures(&self) -> Vec<PublishFailure> {
+        self.failures.lock().map(|mut failures| std::mem::take(&mut *failures)).unwrap_or_default()
+    }
+
+    /// Publish everything already queued, then stop the drain task.
+    ///
+    /// Later pushes fail. Closing an outbox that was never used or is already
+    /// closed returns immediately. Fails if the drain task died before
+    /// publishing everything; events it did publish but the streamer rejected
+    /// are left for `take_failures`.
+    pub async fn close(&self) -> Result<()> {
+        if self.closed.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        if let Some(sender) = self.sender.get() {
+            let (done, drained) = oneshot::channel();
+            if sender.send(OutboxMessage::Close(done)).await.is_ok() {
+                let _ = drained.await;
+            }
+        }
+        let drain_task = self.drain_task.lock().ok().and_then(|mut task| task.take());
+        match drain_task {
+            Some(task) => task
+                .await
+                .map_err(|e| EventualiError::InvalidState(format!("Publish outbox drain task failed: {e}"))),
+            None => Ok(()),
+        }
+    }
+
+    /// Queue an event for publishing, waiting while the outbox is full.
+    ///
+    /// The drain task starts on first use, so it runs on the caller's runtime.
+    pub async fn push(
+        &self,
+        streamer: Arc<dyn EventStreamer + Send + Sync>,
+        event: Event,
+        stream_position: u64,
+        global_position: u64,
+    ) -> Result<()> {
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(EventualiError::InvalidState("Publish outbox has shut down".to_string()));
+        }
+        let sender = self.sender.get_or_init(|| async { self.start() }).await;
+        sender
+            .send(OutboxMessage::Publish(Box::new(Publish { streamer, event, stream_position, global_position })))
+            .await
+            .map_err(|_| EventualiError::InvalidState("Publish outbox has shut down".to_string()))
+    }
+
+    fn start(&self) -> mpsc::Sender<OutboxMessage> {
+        let (sender, mut receiver) = mpsc::channel::<OutboxMessage>(self.capacity);
+        let failures = self.failures.clone();
+        let task = tokio::spawn(async move {
+            while let Some(message) = receiver.recv().await {
+                let Publish { streamer, event, stream_position, global_position } = match message {
+                    OutboxMessage::Publish(publish) => *publish,
+                    OutboxMessage::Close(done) => {
+                        let _ = done.send(());
+                        break;
+                    }
+                };
+                let event_id = event.id;
+                // The write is already committed, so a failed publish cannot fail it
+                if let Err(e) = streamer.publish_event(event, stream_position, global_position).await {
+                    tracing::warn!(%event_id, global_position, error = %e, "Failed to publish committed event");
+                    if let Ok(mut failures) = failures.lock() {
+                        if failures.len() == MAX_PUBLISH_FAILURES {
+                            failures.remove(0);
+                        }
+                        failures.push(PublishFailure { event_id, global_position, error: e.to_string() });
+                    }
+                }
+            }
+        });
+        if let Ok(mut drain_task) = self.drain_task.lock() {
+            *drain_task = Some(task);
+        }
+        sender
+    }
+}
+
+impl Drop for PublishOutbox {
+    /// Stops the drain task; events still queued are not published
+    fn drop(&mut self) {
+        if let Some(task) = self.drain_task.get_mut().ok().and_then(Option::take) {
+            task.abort();
+        }
+    }
+}
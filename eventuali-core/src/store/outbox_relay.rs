@@ -0,0 +1,96 @@
+//! Relay from a transactional outbox to an event streamer
+//!
+//! Stores configured with `with_transactional_outbox(true)` record every saved
+//! event in an outbox table inside the same transaction as the event itself.
+//! The relay publishes those rows and marks them published afterwards, so an
+//! event committed just before a crash is still published once the process
+//! restarts. Delivery is at-least-once: a crash between publishing and marking
+//! publishes the same events again, and subscribers should deduplicate by
+//! event ID or global position.
+//!
+//! Use the relay instead of `set_event_streamer` on the same store; attaching
+//! both publishes every event twice.
+
+use crate::store::EventStore;
+use crate::streaming::EventStreamer;
+use crate::{EventId, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// Publishes events recorded in a store's transactional outbox
+pub struct OutboxRelay {
+    store: Arc<dyn EventStore + Send + Sync>,
+    streamer: Arc<dyn EventStreamer + Send + Sync>,
+    batch_size: usize,
+    poll_interval: Duration,
+}
+
+impl OutboxRelay {
+    pub fn new(
+        store: Arc<dyn EventStore + Send + Sync>,
+        streamer: Arc<dyn EventStreamer + Send + Sync>,
+    ) -> Self {
+        Self {
+            store,
+            streamer,
+            batch_size: 100,
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    /// Maximum number of outbox rows loaded and published per round trip
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// How long `spawn`'s loop waits before checking an empty outbox again
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Publish every unpublished event in global order, returning how many
+    /// were published.
+    ///
+    /// Each batch is marked published only after all of its events reached the
+    /// streamer; if publishing fails, the batch stays in the outbox and the
+    /// error is returned.
+    pub async fn relay_pending(&self) -> Result<u64> {
+        let mut published = 0u64;
+        loop {
+            let batch = self.store.load_unpublished_events(self.batch_size).await?;
+            if batch.is_empty() {
+                return Ok(published);
+            }
+
+            let mut event_ids: Vec<EventId> = Vec::with_capacity(batch.len());
+            for (global_position, event) in batch {
+                event_ids.push(event.id);
+                let stream_position = event.aggregate_version as u64;
+                self.streamer
+                    .publish_event(event, stream_position, global_position)
+                    .await?;
+            }
+
+            self.store.mark_events_published(&event_ids).await?;
+            published += event_ids.len() as u64;
+        }
+    }
+
+    /// Relay in a background task until the handle is aborted.
+    ///
+    /// Errors are logged and retried after the poll interval, so a transient
+    /// database or streamer failure does not stop the relay.
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.relay_pending().await {
+                    tracing::warn!(error = %e, "Failed to relay outbox events");
+                }
+                tokio::time::sleep(self.poll_interval).await;
+            }
+        })
+    }
+}
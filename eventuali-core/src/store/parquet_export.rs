@@ -0,0 +1,200 @@
+//! Apache Parquet export of event history
+//!
+//! Analysts query event history with columnar engines such as Spark or
+//! DuckDB. `ParquetExport` pages through a store in global order and writes
+//! each page as a record batch, so exporting never holds the whole history
+//! in memory. JSON payloads land in a `payload` text column; payloads in
+//! other encodings go to `payload_binary` as stored.
+
+use crate::store::EventStore;
+use crate::{Event, EventData, EventualiError, Result};
+use arrow_array::{
+    ArrayRef, BinaryArray, Int32Array, Int64Array, RecordBatch, StringArray,
+    TimestampMicrosecondArray, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::file::properties::WriterProperties;
+use std::io::Write;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Events read from the store per page, and written per record batch
+pub const DEFAULT_PARQUET_BATCH_SIZE: usize = 1_000;
+
+/// Record batches read ahead of the writer before reading waits for it
+const PENDING_RECORD_BATCHES: usize = 2;
+
+/// Rows per Parquet row group unless configured otherwise
+pub const DEFAULT_PARQUET_ROW_GROUP_SIZE: usize = 64 * 1024;
+
+/// Outcome of a Parquet export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParquetExportReport {
+    pub rows_written: u64,
+    /// Global position of the last event read, for resuming into a new file
+    pub last_global_position: u64,
+}
+
+/// Streams a store's events into a Parquet file
+#[derive(Debug, Clone)]
+pub struct ParquetExport {
+    batch_size: usize,
+    max_row_group_size: usize,
+    from_global: u64,
+}
+
+impl Default for ParquetExport {
+    fn default() -> Self {
+        Self {
+            batch_size: DEFAULT_PARQUET_BATCH_SIZE,
+            max_row_group_size: DEFAULT_PARQUET_ROW_GROUP_SIZE,
+            from_global: 0,
+        }
+    }
+}
+
+impl ParquetExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn with_max_row_group_size(mut self, max_row_group_size: usize) -> Self {
+        self.max_row_group_size = max_row_group_size;
+        self
+    }
+
+    /// Export only events after this global position, to extend an earlier
+    /// export into a new file
+    pub fn with_from_global(mut self, from_global: u64) -> Self {
+        self.from_global = from_global;
+        self
+    }
+
+    /// Columns of the exported file
+    pub fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("global_position", DataType::UInt64, false),
+            Field::new("id", DataType::Utf8, false),
+            Field::new("aggregate_id", DataType::Utf8, false),
+            Field::new("aggregate_type", DataType::Utf8, false),
+            Field::new("aggregate_version", DataType::Int64, false),
+            Field::new("event_type", DataType::Utf8, false),
+            Field::new("event_version", DataType::Int32, false),
+            Field::new("timestamp", DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())), false),
+            Field::new("payload", DataType::Utf8, true),
+            Field::new("payload_binary", DataType::Binary, true),
+            Field::new("metadata", DataType::Utf8, false),
+        ]))
+    }
+
+    /// Write every event in `store`, or only those of `aggregate_type`, to
+    /// `writer` as one Parquet file in global order
+    ///
+    /// Encoding and writing block, so they run on Tokio's blocking pool while
+    /// this task keeps reading pages from the store.
+    pub async fn export<S, W>(&self, store: &S, aggregate_type: Option<&str>, writer: W) -> Result<ParquetExportReport>
+    where
+        S: EventStore + Sync + ?Sized,
+        W: Write + Send + 'static,
+    {
+        if self.batch_size == 0 || self.max_row_group_size == 0 {
+            return Err(EventualiError::Configuration(
+                "Parquet export batch and row group sizes must be positive".to_string(),
+            ));
+        }
+
+        let schema = Self::schema();
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(self.max_row_group_size)
+            .build();
+        let (batches, mut pending) = mpsc::channel::<RecordBatch>(PENDING_RECORD_BATCHES);
+        let writer_schema = schema.clone();
+        let writing = tokio::task::spawn_blocking(move || -> Result<()> {
+            let mut out = ArrowWriter::try_new(writer, writer_schema, Some(properties)).map_err(parquet_error)?;
+            while let Some(batch) = pending.blocking_recv() {
+                out.write(&batch).map_err(parquet_error)?;
+            }
+            out.close().map_err(parquet_error)?;
+            Ok(())
+        });
+
+        let mut report = ParquetExportReport { rows_written: 0, last_global_position: self.from_global };
+        let reading = async {
+            loop {
+                let page = match aggregate_type {
+                    Some(aggregate_type) => {
+                        store
+                            .load_events_of_type_after_position(aggregate_type, report.last_global_position, self.batch_size)
+                            .await?
+                    }
+                    None => store.load_events_after_position(report.last_global_position, self.batch_size).await?,
+                };
+                let Some(&(last, _)) = page.last() else {
+                    return Ok(());
+                };
+                // A closed channel means the writer failed; its error is reported below
+                if batches.send(record_batch(&schema, &page)?).await.is_err() {
+                    return Ok(());
+                }
+                report.last_global_position = last;
+                report.rows_written += page.len() as u64;
+            }
+        }
+        .await;
+        drop(batches);
+
+        let written = writing
+            .await
+            .map_err(|e| EventualiError::InvalidState(format!("Parquet writer task failed: {e}")))?;
+        reading.and(written)?;
+        Ok(report)
+    }
+}
+
+fn record_batch(schema: &SchemaRef, rows: &[(u64, Event)]) -> Result<RecordBatch> {
+    let events = || rows.iter().map(|(_, event)| event);
+    let payload: Vec<Option<String>> = events()
+        .map(|event| match &event.data {
+            EventData::Json(value) => Some(value.to_string()),
+            EventData::Protobuf(_) => None,
+        })
+        .collect();
+    let payload_binary: Vec<Option<&[u8]>> = events()
+        .map(|event| match &event.data {
+            EventData::Json(_) => None,
+            EventData::Protobuf(bytes) => Some(bytes.as_slice()),
+        })
+        .collect();
+    let metadata = events()
+        .map(|event| serde_json::to_string(&event.metadata))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|(position, _)| *position))),
+        Arc::new(StringArray::from_iter_values(events().map(|e| e.id.to_string()))),
+        Arc::new(StringArray::from_iter_values(events().map(|e| e.aggregate_id.as_str()))),
+        Arc::new(StringArray::from_iter_values(events().map(|e| e.aggregate_type.as_str()))),
+        Arc::new(Int64Array::from_iter_values(events().map(|e| e.aggregate_version))),
+        Arc::new(StringArray::from_iter_values(events().map(|e| e.event_type.as_str()))),
+        Arc::new(Int32Array::from_iter_values(events().map(|e| e.event_version))),
+        Arc::new(
+            TimestampMicrosecondArray::from_iter_values(events().map(|e| e.timestamp.timestamp_micros()))
+                .with_timezone("UTC"),
+        ),
+        Arc::new(StringArray::from(payload)),
+        Arc::new(BinaryArray::from(payload_binary)),
+        Arc::new(StringArray::from(metadata)),
+    ];
+    RecordBatch::try_new(schema.clone(), columns)
+        .map_err(|e| EventualiError::InvalidState(format!("Failed to build Parquet record batch: {e}")))
+}
+
+fn parquet_error(error: parquet::errors::ParquetError) -> EventualiError {
+    EventualiError::Io(std::io::Error::other(error))
+}
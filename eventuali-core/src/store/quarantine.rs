@@ -0,0 +1,82 @@
+//! Lenient loading of partially corrupt streams
+//!
+//! A strict load fails on the first row that cannot be decoded, which leaves
+//! every other event of the aggregate unreachable. A lenient load skips such
+//! rows instead and returns them as quarantine entries, holding the raw column
+//! bytes and the decoding error so the damage can be inspected and repaired.
+
+use crate::{AggregateId, EventualiError, Event};
+use chrono::{DateTime, Utc};
+
+/// A stored row that could not be decoded into an event
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedRow {
+    pub aggregate_id: AggregateId,
+    /// Event ID column as stored, when it could be read
+    pub event_id: Option<String>,
+    pub aggregate_version: Option<i64>,
+    pub event_type: Option<String>,
+    /// Raw bytes of the event data column
+    pub raw_event_data: Vec<u8>,
+    /// Raw bytes of the metadata column
+    pub raw_metadata: Vec<u8>,
+    /// Why the row could not be decoded
+    pub error: String,
+    pub quarantined_at: DateTime<Utc>,
+}
+
+impl QuarantinedRow {
+    pub(crate) fn new(
+        aggregate_id: &AggregateId,
+        event_id: Option<String>,
+        aggregate_version: Option<i64>,
+        event_type: Option<String>,
+        raw_event_data: Vec<u8>,
+        raw_metadata: Vec<u8>,
+        error: &EventualiError,
+    ) -> Self {
+        Self {
+            aggregate_id: aggregate_id.clone(),
+            event_id,
+            aggregate_version,
+            event_type,
+            raw_event_data,
+            raw_metadata,
+            error: error.to_string(),
+            quarantined_at: Utc::now(),
+        }
+    }
+}
+
+/// Result of a lenient load: the events that decoded, in stream order, and
+/// the rows that were skipped
+#[derive(Debug, Clone, Default)]
+pub struct LenientLoad {
+    pub events: Vec<Event>,
+    pub quarantined: Vec<QuarantinedRow>,
+}
+
+impl LenientLoad {
+    /// Number of rows skipped because they could not be decoded
+    pub fn skipped(&self) -> usize {
+        self.quarantined.len()
+    }
+
+    /// Skip a row, logging it to the quarantine
+    pub(crate) fn quarantine(&mut self, row: QuarantinedRow) {
+        tracing::warn!(
+            aggregate_id = %row.aggregate_id,
+            event_id = row.event_id.as_deref().unwrap_or("<unreadable>"),
+            aggregate_version = row.aggregate_version,
+            error = %row.error,
+            "Quarantined event row that could not be decoded"
+        );
+        self.quarantined.push(row);
+    }
+}
+
+impl From<Vec<Event>> for LenientLoad {
+    fn from(events: Vec<Event>) -> Self {
+        Self { events, quarantined: Vec::new() }
+    }
+}
@@ -0,0 +1,75 @@
+//! In-memory tail of recently committed events
+//!
+//! Dashboards asking for the latest activity would otherwise query the
+//! database on every refresh. A store configured with a recent-events buffer
+//! appends each save's events once the backend has committed them and serves
+//! `recent_events` from memory. The buffer only sees saves made through this
+//! process's store: events written by other processes, or before the store
+//! was created, are not in it.
+
+use crate::store::erasure::{tombstone, DeleteMode};
+use crate::{AggregateId, Event, EventualiError, Result};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Ring buffer of the last `capacity` committed events
+#[derive(Debug)]
+pub struct RecentEvents {
+    events: Mutex<VecDeque<Event>>,
+    capacity: usize,
+}
+
+impl RecentEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Append committed events, dropping the oldest beyond capacity
+    pub fn extend(&self, committed: &[Event]) -> Result<()> {
+        let mut events = self.lock()?;
+        // Only the last `capacity` of a large batch can survive
+        let skip = committed.len().saturating_sub(self.capacity);
+        for event in &committed[skip..] {
+            if events.len() == self.capacity {
+                events.pop_front();
+            }
+            events.push_back(event.clone());
+        }
+        Ok(())
+    }
+
+    /// The latest `n` events (fewer if fewer are buffered), oldest first
+    pub fn latest(&self, n: usize) -> Result<Vec<Event>> {
+        let events = self.lock()?;
+        let skip = events.len().saturating_sub(n);
+        Ok(events.iter().skip(skip).cloned().collect())
+    }
+
+    /// Erase the buffered events of `aggregate_id` the way the store did, so
+    /// the buffer stops serving their content
+    pub fn erase(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<()> {
+        let mut events = self.lock()?;
+        match mode {
+            DeleteMode::Hard => events.retain(|e| &e.aggregate_id != aggregate_id),
+            DeleteMode::SoftTombstone => {
+                for event in events.iter_mut().filter(|e| &e.aggregate_id == aggregate_id) {
+                    *event = tombstone(event);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, VecDeque<Event>>> {
+        self.events
+            .lock()
+            .map_err(|_| EventualiError::InvalidState("Recent-events buffer lock poisoned".to_string()))
+    }
+}
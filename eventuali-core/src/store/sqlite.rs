@@ -1,6 +1,17 @@
 use crate::{
-    store::{traits::EventStoreBackend, EventStoreConfig},
-    Event, EventData, EventMetadata, AggregateId, AggregateVersion, Result, EventualiError,
+    store::{
+        compaction::{plan_compaction, CompactionReport, Compactor},
+        erasure::{tombstone_data, DeleteMode, TOMBSTONE_EVENT_TYPE},
+        quarantine::{LenientLoad, QuarantinedRow},
+        traits::{
+            check_expected_version, check_storage_format, duplicate_event_id, ensure_version_not_compacted,
+            expected_aggregate, outbox_unsupported, EventStoreBackend, StoreStats, PROMOTED_METADATA_FIELDS,
+            STORAGE_FORMAT_VERSION,
+        },
+        EventStoreConfig,
+    },
+    Event, EventData, EventId, EventMetadata, AggregateId, AggregateVersion, Result, EventualiError,
+    CodecRegistry,
 };
 use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose};
@@ -8,11 +19,22 @@ use chrono::{DateTime, Utc};
 use serde_json;
 use sqlx::{sqlite::{SqlitePool, SqliteConnectOptions, SqliteJournalMode}, Row};
 use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+/// Every column of the events table, in table order
+const EVENT_COLUMNS: &str = "id, aggregate_id, aggregate_type, event_type, event_version, \
+    aggregate_version, event_data, event_data_type, metadata, timestamp, global_position, storage_format_version";
+
 pub struct SQLiteBackend {
     pool: SqlitePool,
     table_name: String,
+    codecs: Arc<CodecRegistry>,
+    codec: Option<String>,
+    /// Whether an archive database is attached to every connection as `archive`
+    archived: bool,
+    /// Whether saves also record each event in the `{table}_outbox` table
+    outbox: bool,
 }
 
 impl SQLiteBackend {
@@ -22,11 +44,30 @@ impl SQLiteBackend {
                 database_path,
                 max_connections,
                 table_name,
+                codec,
+                archive_path,
+                transactional_outbox,
+                ..
             } => {
+                let mut pool_options = sqlx::sqlite::SqlitePoolOptions::new()
+                    .max_connections(max_connections.unwrap_or(10));
+                if let Some(archive_path) = archive_path.clone() {
+                    // ATTACH is per connection, so every pooled connection needs it
+                    pool_options = pool_options.after_connect(move |conn, _meta| {
+                        let archive_path = archive_path.clone();
+                        Box::pin(async move {
+                            sqlx::query("ATTACH DATABASE ? AS archive")
+                                .bind(archive_path)
+                                .execute(conn)
+                                .await?;
+                            Ok(())
+                        })
+                    });
+                }
+
                 let pool = if database_path == ":memory:" {
                     // For in-memory databases, use the simple connection string
-                    sqlx::sqlite::SqlitePoolOptions::new()
-                        .max_connections(max_connections.unwrap_or(10))
+                    pool_options
                         .connect("sqlite://:memory:")
                         .await?
                 } else {
@@ -59,8 +100,7 @@ impl SQLiteBackend {
                         .create_if_missing(true)
                         .journal_mode(SqliteJournalMode::Wal);
                     
-                    sqlx::sqlite::SqlitePoolOptions::new()
-                        .max_connections(max_connections.unwrap_or(10))
+                    pool_options
                         .connect_with(connect_options)
                         .await?
                 };
@@ -70,7 +110,14 @@ impl SQLiteBackend {
                     .unwrap_or("events")
                     .to_string();
 
-                let backend = Self { pool, table_name };
+                let backend = Self {
+                    pool,
+                    table_name,
+                    codecs: Arc::new(CodecRegistry::new()),
+                    codec: codec.clone().filter(|name| !CodecRegistry::is_built_in(name)),
+                    archived: archive_path.is_some(),
+                    outbox: *transactional_outbox,
+                };
                 Ok(backend)
             }
             _ => Err(EventualiError::Configuration(
@@ -79,15 +126,77 @@ impl SQLiteBackend {
         }
     }
 
+    /// Share the registry custom codecs are looked up in, for both writes and reads.
+    pub fn with_codec_registry(mut self, codecs: Arc<CodecRegistry>) -> Self {
+        self.codecs = codecs;
+        self
+    }
+
     async fn create_tables(&self) -> Result<()> {
         // Enable foreign keys (WAL mode is set in connection options)
         sqlx::query("PRAGMA foreign_keys = ON")
             .execute(&self.pool)
             .await?;
 
-        let create_events_table = format!(
+        sqlx::query(&self.events_table_ddl("main"))
+            .execute(&self.pool)
+            .await?;
+
+        self.ensure_global_position_column().await?;
+        self.ensure_storage_format_column("main").await?;
+        self.ensure_metadata_columns("main").await?;
+
+        if self.archived {
+            sqlx::query(&self.events_table_ddl("archive"))
+                .execute(&self.pool)
+                .await?;
+            self.ensure_storage_format_column("archive").await?;
+            self.ensure_metadata_columns("archive").await?;
+        }
+
+        self.ensure_position_high_water_mark().await?;
+
+        sqlx::query(&format!(
             r#"
-            CREATE TABLE IF NOT EXISTS {} (
+            CREATE TABLE IF NOT EXISTS {table}_compactions (
+                compaction_id TEXT PRIMARY KEY,
+                aggregate_id TEXT NOT NULL,
+                compacted_at TEXT NOT NULL,
+                report TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_{table}_compactions_aggregate_id
+                ON {table}_compactions (aggregate_id);
+            "#,
+            table = self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        if self.outbox {
+            sqlx::query(&format!(
+                r#"
+                CREATE TABLE IF NOT EXISTS {table}_outbox (
+                    event_id TEXT PRIMARY KEY,
+                    published_at TEXT
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_{table}_outbox_unpublished
+                    ON {table}_outbox (event_id) WHERE published_at IS NULL;
+                "#,
+                table = self.table_name
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    fn events_table_ddl(&self, schema: &str) -> String {
+        format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {schema}.{table} (
                 id TEXT PRIMARY KEY,
                 aggregate_id TEXT NOT NULL,
                 aggregate_type TEXT NOT NULL,
@@ -98,64 +207,354 @@ impl SQLiteBackend {
                 event_data_type TEXT NOT NULL DEFAULT 'json',
                 metadata TEXT NOT NULL,
                 timestamp TEXT NOT NULL,
+                global_position INTEGER,
+                storage_format_version INTEGER NOT NULL DEFAULT 1,
                 UNIQUE(aggregate_id, aggregate_version)
             );
             
-            CREATE INDEX IF NOT EXISTS idx_{}_aggregate_id ON {} (aggregate_id);
-            CREATE INDEX IF NOT EXISTS idx_{}_aggregate_type ON {} (aggregate_type);
-            CREATE INDEX IF NOT EXISTS idx_{}_timestamp ON {} (timestamp);
+            CREATE INDEX IF NOT EXISTS {schema}.idx_{table}_aggregate_id ON {table} (aggregate_id);
+            CREATE INDEX IF NOT EXISTS {schema}.idx_{table}_aggregate_type ON {table} (aggregate_type);
+            CREATE INDEX IF NOT EXISTS {schema}.idx_{table}_timestamp ON {table} (timestamp);
             "#,
-            self.table_name,
-            self.table_name, self.table_name,
-            self.table_name, self.table_name,
-            self.table_name, self.table_name
-        );
+            table = self.table_name
+        )
+    }
+
+    /// Stored text and type tag for an event's payload
+    fn encode_event_data(&self, event: &Event) -> Result<(String, &str)> {
+        Ok(match (&self.codec, &event.data) {
+            (Some(codec), data) => {
+                // Custom codec output is stored as base64, tagged with the codec name
+                let bytes = self.codecs.encode(codec, data)?;
+                (general_purpose::STANDARD.encode(bytes), codec.as_str())
+            }
+            (None, EventData::Json(value)) => (serde_json::to_string(value)?, "json"),
+            (None, EventData::Protobuf(bytes)) => {
+                // Store protobuf as base64 for SQLite
+                let base64_data = general_purpose::STANDARD.encode(bytes);
+                (base64_data, "protobuf")
+            }
+        })
+    }
+
+    /// Table expression reads select from: the events table, or the union of the
+    /// hot and archived tables when an archive is attached
+    fn source(&self) -> String {
+        if self.archived {
+            format!(
+                "(SELECT {EVENT_COLUMNS} FROM main.{table} UNION ALL SELECT {EVENT_COLUMNS} FROM archive.{table})",
+                table = self.table_name
+            )
+        } else {
+            self.table_name.clone()
+        }
+    }
+
+    /// Move events older than `cutoff` from the primary database into the
+    /// attached archive, returning how many were moved.
+    ///
+    /// The copy and the delete run in one transaction under the write lock.
+    /// SQLite only makes a transaction atomic per file in WAL mode, so the
+    /// delete only removes events the archive already holds: a crash can
+    /// leave an event in both files, never in neither, and running the move
+    /// again finishes it.
+    async fn move_to_archive(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        if !self.archived {
+            return Err(EventualiError::Configuration(
+                "No archive database is configured for this SQLite store".to_string(),
+            ));
+        }
+
+        let cutoff = cutoff.to_rfc3339();
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+
+        sqlx::query(&format!(
+            "INSERT OR IGNORE INTO archive.{table} ({EVENT_COLUMNS}) \
+             SELECT {EVENT_COLUMNS} FROM main.{table} WHERE julianday(timestamp) < julianday(?)",
+            table = self.table_name
+        ))
+        .bind(&cutoff)
+        .execute(&mut *tx)
+        .await?;
 
-        sqlx::query(&create_events_table)
+        let moved = sqlx::query(&format!(
+            "DELETE FROM main.{table} WHERE julianday(timestamp) < julianday(?) \
+             AND id IN (SELECT id FROM archive.{table})",
+            table = self.table_name
+        ))
+        .bind(&cutoff)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(moved)
+    }
+
+    /// Creates the `{table}_positions` row saves draw global positions from and
+    /// raises it to the highest stored position.
+    ///
+    /// Positions come from this high-water mark rather than `MAX(global_position)`,
+    /// so hard-deleting the newest events never hands their positions out again
+    /// to readers that already paged past them.
+    async fn ensure_position_high_water_mark(&self) -> Result<()> {
+        sqlx::query(&format!(
+            r#"
+            CREATE TABLE IF NOT EXISTS {}_positions (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                last_position INTEGER NOT NULL
+            )
+            "#,
+            self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        // `WHERE true` keeps SQLite from parsing `ON CONFLICT` as a join constraint
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO {table}_positions (id, last_position)
+            SELECT 1, COALESCE(MAX(global_position), 0) FROM {source} WHERE true
+            ON CONFLICT (id) DO UPDATE SET last_position = MAX(last_position, excluded.last_position)
+            "#,
+            table = self.table_name,
+            source = self.source()
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Adds and backfills the `global_position` column on tables created before
+    /// it existed, then indexes it.
+    ///
+    /// Global position is the commit order of an event across the whole store and
+    /// is the tie-breaker for every cross-aggregate query, so events sharing a
+    /// timestamp always load in the same order.
+    async fn ensure_global_position_column(&self) -> Result<()> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", self.table_name))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let has_column = columns.iter().any(|row| {
+            row.try_get::<String, _>("name")
+                .map(|name| name == "global_position")
+                .unwrap_or(false)
+        });
+
+        if !has_column {
+            sqlx::query(&format!(
+                "ALTER TABLE {} ADD COLUMN global_position INTEGER",
+                self.table_name
+            ))
             .execute(&self.pool)
             .await?;
+        }
+
+        sqlx::query(&format!(
+            "UPDATE {} SET global_position = rowid WHERE global_position IS NULL",
+            self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(&format!(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_{}_global_position ON {} (global_position)",
+            self.table_name, self.table_name
+        ))
+        .execute(&self.pool)
+        .await?;
 
         Ok(())
     }
-}
 
-#[async_trait]
-impl EventStoreBackend for SQLiteBackend {
-    async fn initialize(&mut self) -> Result<()> {
-        self.create_tables().await
+    /// Adds the `storage_format_version` column to tables created before it
+    /// existed. Their rows were all written in format version 1, which the
+    /// column's default gives them.
+    async fn ensure_storage_format_column(&self, schema: &str) -> Result<()> {
+        let columns = sqlx::query(&format!("PRAGMA {schema}.table_info({})", self.table_name))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let has_column = columns.iter().any(|row| {
+            row.try_get::<String, _>("name")
+                .map(|name| name == "storage_format_version")
+                .unwrap_or(false)
+        });
+
+        if !has_column {
+            sqlx::query(&format!(
+                "ALTER TABLE {schema}.{} ADD COLUMN storage_format_version INTEGER NOT NULL DEFAULT 1",
+                self.table_name
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok(())
     }
 
-    async fn save_events(&self, events: Vec<Event>) -> Result<()> {
-        if events.is_empty() {
-            return Ok(());
+    /// Expose the promoted metadata fields (correlation, causation and user
+    /// ID) as indexed columns. They are virtual generated columns, so rows
+    /// written before they existed are covered without a backfill, and saves
+    /// fill them in and index them with no change to the insert.
+    ///
+    /// Unlike PostgreSQL, this runs on existing tables too. Adding a virtual
+    /// column only changes the schema, so the one real cost is building the
+    /// indexes, paid once on the first open after upgrading; later opens find
+    /// them in place. An embedded database has no separate maintenance step
+    /// to defer that build to.
+    async fn ensure_metadata_columns(&self, schema: &str) -> Result<()> {
+        // Generated columns are hidden from table_info but listed by table_xinfo
+        let columns = sqlx::query(&format!("PRAGMA {schema}.table_xinfo({})", self.table_name))
+            .fetch_all(&self.pool)
+            .await?;
+        let existing: Vec<String> = columns
+            .iter()
+            .filter_map(|row| row.try_get::<String, _>("name").ok())
+            .collect();
+
+        for field in PROMOTED_METADATA_FIELDS {
+            if !existing.iter().any(|name| name == field) {
+                sqlx::query(&format!(
+                    "ALTER TABLE {schema}.{} ADD COLUMN {field} TEXT \
+                     GENERATED ALWAYS AS (json_extract(metadata, '$.{field}')) VIRTUAL",
+                    self.table_name
+                ))
+                .execute(&self.pool)
+                .await?;
+            }
+
+            sqlx::query(&format!(
+                "CREATE INDEX IF NOT EXISTS {schema}.idx_{table}_{field} ON {table} ({field})",
+                table = self.table_name
+            ))
+            .execute(&self.pool)
+            .await?;
         }
 
-        let mut tx = self.pool.begin().await?;
+        Ok(())
+    }
+
+    /// Up to `limit` events whose promoted metadata `column` equals `value`
+    /// and whose aggregate ID starts with `aggregate_id_prefix`, in global order
+    async fn events_by_metadata_column(
+        &self,
+        column: &str,
+        value: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let query = if self.archived {
+            format!(
+                r#"
+                SELECT {EVENT_COLUMNS} FROM main.{table}
+                WHERE {column} = ?1 AND substr(aggregate_id, 1, length(?3)) = ?3
+                UNION ALL
+                SELECT {EVENT_COLUMNS} FROM archive.{table}
+                WHERE {column} = ?1 AND substr(aggregate_id, 1, length(?3)) = ?3
+                ORDER BY global_position ASC
+                LIMIT ?2
+                "#,
+                table = self.table_name
+            )
+        } else {
+            format!(
+                r#"
+                SELECT {EVENT_COLUMNS} FROM {table}
+                WHERE {column} = ?1 AND substr(aggregate_id, 1, length(?3)) = ?3
+                ORDER BY global_position ASC
+                LIMIT ?2
+                "#,
+                table = self.table_name
+            )
+        };
+
+        let rows = sqlx::query(&query)
+            .bind(value)
+            .bind(limit as i64)
+            .bind(aggregate_id_prefix)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| self.row_to_event(row)).collect()
+    }
+
+    /// Check versions and insert `events` inside `tx` as a save does, drawing
+    /// their global positions; the caller commits or rolls back
+    async fn insert_events(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<Vec<u64>> {
+        let checked_aggregate = expected_aggregate(&events, expected_version)?.cloned();
+
+        let mut positions = Vec::with_capacity(events.len());
+
+        if let Some(aggregate_id) = &checked_aggregate {
+            let current: Option<i64> = sqlx::query_scalar(&format!(
+                "SELECT MAX(aggregate_version) FROM {} WHERE aggregate_id = ?",
+                self.source()
+            ))
+            .bind(aggregate_id)
+            .fetch_one(&mut **tx)
+            .await?;
+            check_expected_version(expected_version, current)?;
+        }
 
         for event in events {
-            let (event_data_text, event_data_type) = match &event.data {
-                EventData::Json(value) => (serde_json::to_string(value)?, "json"),
-                EventData::Protobuf(bytes) => {
-                    // Store protobuf as base64 for SQLite
-                    let base64_data = general_purpose::STANDARD.encode(bytes);
-                    (base64_data, "protobuf")
-                }
-            };
+            let (event_data_text, event_data_type) = self.encode_event_data(&event)?;
 
             let metadata_text = serde_json::to_string(&event.metadata)?;
             let timestamp_text = event.timestamp.to_rfc3339();
 
             let query = format!(
                 r#"
-                INSERT INTO {} (
+                INSERT INTO {table} (
                     id, aggregate_id, aggregate_type, event_type, event_version,
-                    aggregate_version, event_data, event_data_type, metadata, timestamp
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    aggregate_version, event_data, event_data_type, metadata, timestamp,
+                    storage_format_version, global_position
+                ) VALUES (
+                    ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                    (SELECT last_position + 1 FROM {table}_positions WHERE id = 1)
+                )
+                RETURNING global_position
                 "#,
-                self.table_name
+                table = self.table_name
             );
 
-            sqlx::query(&query)
+            if self.archived {
+                // The unique (aggregate_id, aggregate_version) constraint only
+                // covers the primary file, so check archived versions explicitly
+                let archived_conflict = sqlx::query(&format!(
+                    "SELECT 1 FROM archive.{} WHERE aggregate_id = ? AND aggregate_version = ?",
+                    self.table_name
+                ))
+                .bind(&event.aggregate_id)
+                .bind(event.aggregate_version)
+                .fetch_optional(&mut **tx)
+                .await?;
+                if archived_conflict.is_some() {
+                    let actual: Option<i64> = sqlx::query_scalar(&format!(
+                        "SELECT MAX(aggregate_version) FROM {} WHERE aggregate_id = ?",
+                        self.source()
+                    ))
+                    .bind(&event.aggregate_id)
+                    .fetch_one(&mut **tx)
+                    .await?;
+                    return Err(EventualiError::OptimisticConcurrency {
+                        expected: event.aggregate_version,
+                        actual: actual.unwrap_or(0),
+                    });
+                }
+            }
+
+            // The subquery and the bump below run under the write lock SQLite
+            // holds for the whole transaction, so concurrent writers in other
+            // processes cannot compute the same position
+            let global_position: i64 = sqlx::query_scalar(&query)
                 .bind(event.id.to_string())
                 .bind(&event.aggregate_id)
                 .bind(&event.aggregate_type)
@@ -166,9 +565,16 @@ impl EventStoreBackend for SQLiteBackend {
                 .bind(event_data_type)
                 .bind(&metadata_text)
                 .bind(&timestamp_text)
-                .execute(&mut *tx)
+                .bind(STORAGE_FORMAT_VERSION)
+                .fetch_one(&mut **tx)
                 .await
                 .map_err(|e| match e {
+                    sqlx::Error::Database(db_err)
+                        if db_err.is_unique_violation()
+                            && db_err.message().ends_with(&format!("{}.id", self.table_name)) =>
+                    {
+                        duplicate_event_id(&event.id)
+                    }
                     sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
                         EventualiError::OptimisticConcurrency {
                             expected: event.aggregate_version,
@@ -177,9 +583,54 @@ impl EventStoreBackend for SQLiteBackend {
                     }
                     _ => EventualiError::Database(e),
                 })?;
+            sqlx::query(&format!(
+                "UPDATE {}_positions SET last_position = ? WHERE id = 1",
+                self.table_name
+            ))
+            .bind(global_position)
+            .execute(&mut **tx)
+            .await?;
+            positions.push(global_position as u64);
+
+            if self.outbox {
+                sqlx::query(&format!("INSERT INTO {}_outbox (event_id) VALUES (?)", self.table_name))
+                    .bind(event.id.to_string())
+                    .execute(&mut **tx)
+                    .await?;
+            }
         }
 
+        Ok(positions)
+    }
+}
+
+#[async_trait]
+impl EventStoreBackend for SQLiteBackend {
+    async fn initialize(&mut self) -> Result<()> {
+        self.create_tables().await
+    }
+
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<Vec<u64>> {
+        if events.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Take the database write lock before touching any row, so concurrent
+        // batches queue on that one lock instead of upgrading read locks in
+        // whatever order their rows arrive
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        let positions = self.insert_events(&mut tx, events, expected_version).await?;
         tx.commit().await?;
+        Ok(positions)
+    }
+
+    async fn dry_run_save_events(&self, events: Vec<Event>) -> Result<()> {
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+        self.insert_events(&mut tx, events, None).await?;
+        tx.rollback().await?;
         Ok(())
     }
 
@@ -188,51 +639,90 @@ impl EventStoreBackend for SQLiteBackend {
         aggregate_id: &AggregateId,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>> {
-        let query = match from_version {
-            Some(_version) => format!(
-                r#"
-                SELECT id, aggregate_id, aggregate_type, event_type, event_version,
-                       aggregate_version, event_data, event_data_type, metadata, timestamp
-                FROM {} 
-                WHERE aggregate_id = ? AND aggregate_version > ?
-                ORDER BY aggregate_version ASC
-                "#,
-                self.table_name
-            ),
-            None => format!(
-                r#"
-                SELECT id, aggregate_id, aggregate_type, event_type, event_version,
-                       aggregate_version, event_data, event_data_type, metadata, timestamp
-                FROM {} 
-                WHERE aggregate_id = ?
-                ORDER BY aggregate_version ASC
-                "#,
-                self.table_name
-            ),
-        };
-
-        let rows = if let Some(version) = from_version {
-            sqlx::query(&query)
-                .bind(aggregate_id)
-                .bind(version)
-                .fetch_all(&self.pool)
-                .await?
-        } else {
-            sqlx::query(&query)
-                .bind(aggregate_id)
-                .fetch_all(&self.pool)
-                .await?
-        };
+        let rows = self.fetch_aggregate_rows(aggregate_id, from_version).await?;
 
         let mut events = Vec::new();
         for row in rows {
-            let event = self.row_to_event(row)?;
+            let event = self.row_to_event(&row)?;
             events.push(event);
         }
 
         Ok(events)
     }
 
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        let history = self.load_compaction_history(aggregate_id).await?;
+        ensure_version_not_compacted(aggregate_id, version, &history)?;
+
+        let query = format!(
+            r#"
+            SELECT id, aggregate_id, aggregate_type, event_type, event_version,
+                   aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
+            FROM {}
+            WHERE aggregate_id = ? AND aggregate_version <= ?
+            ORDER BY aggregate_version ASC
+            "#,
+            self.source()
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(aggregate_id)
+            .bind(version)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(|row| self.row_to_event(row)).collect()
+    }
+
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        let rows = self.fetch_aggregate_rows(aggregate_id, from_version).await?;
+
+        let mut load = LenientLoad::default();
+        for row in rows {
+            match self.row_to_event(&row) {
+                Ok(event) => load.events.push(event),
+                Err(e) => load.quarantine(QuarantinedRow::new(
+                    aggregate_id,
+                    row.try_get_unchecked::<String, _>("id").ok(),
+                    row.try_get("aggregate_version").ok(),
+                    row.try_get("event_type").ok(),
+                    row.try_get_unchecked::<Vec<u8>, _>("event_data").unwrap_or_default(),
+                    row.try_get_unchecked::<Vec<u8>, _>("metadata").unwrap_or_default(),
+                    &e,
+                )),
+            }
+        }
+
+        Ok(load)
+    }
+
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.events_by_metadata_column("correlation_id", correlation_id, aggregate_id_prefix, limit)
+            .await
+    }
+
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        self.events_by_metadata_column("user_id", user_id, aggregate_id_prefix, limit).await
+    }
+
     async fn load_events_by_type(
         &self,
         aggregate_type: &str,
@@ -242,22 +732,22 @@ impl EventStoreBackend for SQLiteBackend {
             Some(_version) => format!(
                 r#"
                 SELECT id, aggregate_id, aggregate_type, event_type, event_version,
-                       aggregate_version, event_data, event_data_type, metadata, timestamp
+                       aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
                 FROM {} 
                 WHERE aggregate_type = ? AND aggregate_version > ?
-                ORDER BY timestamp ASC
+                ORDER BY global_position ASC
                 "#,
-                self.table_name
+                self.source()
             ),
             None => format!(
                 r#"
                 SELECT id, aggregate_id, aggregate_type, event_type, event_version,
-                       aggregate_version, event_data, event_data_type, metadata, timestamp
+                       aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
                 FROM {} 
                 WHERE aggregate_type = ?
-                ORDER BY timestamp ASC
+                ORDER BY global_position ASC
                 "#,
-                self.table_name
+                self.source()
             ),
         };
 
@@ -276,7 +766,7 @@ impl EventStoreBackend for SQLiteBackend {
 
         let mut events = Vec::new();
         for row in rows {
-            let event = self.row_to_event(row)?;
+            let event = self.row_to_event(&row)?;
             events.push(event);
         }
 
@@ -286,7 +776,7 @@ impl EventStoreBackend for SQLiteBackend {
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
         let query = format!(
             "SELECT MAX(aggregate_version) FROM {} WHERE aggregate_id = ?",
-            self.table_name
+            self.source()
         );
 
         let row = sqlx::query(&query)
@@ -301,13 +791,404 @@ impl EventStoreBackend for SQLiteBackend {
             Ok(None)
         }
     }
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        self.load_after_position(None, after_global, limit).await
+    }
+
+    async fn load_events_of_type_after_position(
+        &self,
+        aggregate_type: &str,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        self.load_after_position(Some(aggregate_type), after_global, limit).await
+    }
+
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        let query = format!(
+            "SELECT DISTINCT aggregate_type FROM {} ORDER BY aggregate_type ASC",
+            self.source()
+        );
+
+        let rows = sqlx::query(&query)
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter()
+            .map(|row| row.try_get("aggregate_type").map_err(Into::into))
+            .collect()
+    }
+
+    async fn archive_events_before(&self, cutoff: DateTime<Utc>) -> Result<u64> {
+        self.move_to_archive(cutoff).await
+    }
+
+    async fn load_unpublished_events(&self, limit: usize) -> Result<Vec<(u64, Event)>> {
+        if !self.outbox {
+            return Err(outbox_unsupported());
+        }
+
+        let query = format!(
+            r#"
+            SELECT e.global_position, e.id, e.aggregate_id, e.aggregate_type, e.event_type, e.event_version,
+                   e.aggregate_version, e.event_data, e.event_data_type, e.metadata, e.timestamp, e.storage_format_version
+            FROM {table}_outbox o
+            JOIN {source} e ON e.id = o.event_id
+            WHERE o.published_at IS NULL
+            ORDER BY e.global_position ASC
+            LIMIT ?
+            "#,
+            table = self.table_name,
+            source = self.source()
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(limit as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let global_position: i64 = row.try_get("global_position")?;
+            events.push((global_position as u64, self.row_to_event(&row)?));
+        }
+
+        Ok(events)
+    }
+
+    async fn mark_events_published(&self, event_ids: &[EventId]) -> Result<()> {
+        if !self.outbox {
+            return Err(outbox_unsupported());
+        }
+
+        let query = format!(
+            "UPDATE {}_outbox SET published_at = ? WHERE event_id = ? AND published_at IS NULL",
+            self.table_name
+        );
+        let published_at = Utc::now().to_rfc3339();
+
+        let mut tx = self.pool.begin().await?;
+        for event_id in event_ids {
+            sqlx::query(&query)
+                .bind(&published_at)
+                .bind(event_id.to_string())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn compact_aggregate(&self, aggregate_id: &AggregateId, compactor: &dyn Compactor) -> Result<CompactionReport> {
+        let events = self.load_events(aggregate_id, None).await?;
+        let event_count = events.len();
+        let plan = match plan_compaction(aggregate_id, events, compactor)? {
+            Some(plan) => plan,
+            None => return Ok(CompactionReport::unchanged(aggregate_id, event_count)),
+        };
+        let through = plan.report.compacted_through;
+
+        // Take the write lock up front: a deferred transaction that reads
+        // first can fail with SQLITE_BUSY when it upgrades to write
+        let mut tx = self.pool.begin_with("BEGIN IMMEDIATE").await?;
+
+        // The snapshot takes the place of the last event it replaces in global order
+        let global_position: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT global_position FROM {} WHERE aggregate_id = ? AND aggregate_version = ?",
+            self.source()
+        ))
+        .bind(aggregate_id)
+        .bind(through)
+        .fetch_optional(&mut *tx)
+        .await?
+        .flatten();
+
+        let schemas: &[&str] = if self.archived { &["main", "archive"] } else { &["main"] };
+        let mut deleted = 0;
+        for schema in schemas {
+            deleted += sqlx::query(&format!(
+                "DELETE FROM {schema}.{} WHERE aggregate_id = ? AND aggregate_version <= ?",
+                self.table_name
+            ))
+            .bind(aggregate_id)
+            .bind(through)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+        if deleted != plan.removed.len() as u64 {
+            // Dropping the transaction rolls the deletes back
+            return Err(EventualiError::InvalidState(format!(
+                "Aggregate {aggregate_id} changed while it was being compacted"
+            )));
+        }
+
+        let snapshot = &plan.snapshot;
+        let (event_data_text, event_data_type) = self.encode_event_data(snapshot)?;
+        sqlx::query(&format!(
+            r#"
+            INSERT INTO {} (
+                id, aggregate_id, aggregate_type, event_type, event_version,
+                aggregate_version, event_data, event_data_type, metadata, timestamp,
+                storage_format_version, global_position
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+            self.table_name
+        ))
+        .bind(snapshot.id.to_string())
+        .bind(&snapshot.aggregate_id)
+        .bind(&snapshot.aggregate_type)
+        .bind(&snapshot.event_type)
+        .bind(snapshot.event_version)
+        .bind(snapshot.aggregate_version)
+        .bind(&event_data_text)
+        .bind(event_data_type)
+        .bind(serde_json::to_string(&snapshot.metadata)?)
+        .bind(snapshot.timestamp.to_rfc3339())
+        .bind(STORAGE_FORMAT_VERSION)
+        .bind(global_position)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
+            "INSERT INTO {}_compactions (compaction_id, aggregate_id, compacted_at, report) VALUES (?, ?, ?, ?)",
+            self.table_name
+        ))
+        .bind(plan.report.compaction_id.to_string())
+        .bind(aggregate_id)
+        .bind(plan.report.compacted_at.to_rfc3339())
+        .bind(serde_json::to_string(&plan.report)?)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(plan.report)
+    }
+
+    async fn load_compaction_history(&self, aggregate_id: &AggregateId) -> Result<Vec<CompactionReport>> {
+        let reports: Vec<String> = sqlx::query_scalar(&format!(
+            "SELECT report FROM {}_compactions WHERE aggregate_id = ? ORDER BY compacted_at ASC",
+            self.table_name
+        ))
+        .bind(aggregate_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        reports
+            .iter()
+            .map(|report| Ok(serde_json::from_str(report)?))
+            .collect()
+    }
+
+    async fn delete_aggregate_events(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let schemas: &[&str] = if self.archived { &["main", "archive"] } else { &["main"] };
+        let mut erased = 0;
+
+        match mode {
+            DeleteMode::Hard => {
+                if self.outbox {
+                    for schema in schemas {
+                        sqlx::query(&format!(
+                            "DELETE FROM {table}_outbox WHERE event_id IN \
+                             (SELECT id FROM {schema}.{table} WHERE aggregate_id = ?)",
+                            table = self.table_name
+                        ))
+                        .bind(aggregate_id)
+                        .execute(&mut *tx)
+                        .await?;
+                    }
+                }
+                for schema in schemas {
+                    erased += sqlx::query(&format!(
+                        "DELETE FROM {schema}.{} WHERE aggregate_id = ?",
+                        self.table_name
+                    ))
+                    .bind(aggregate_id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                }
+                sqlx::query(&format!("DELETE FROM {}_compactions WHERE aggregate_id = ?", self.table_name))
+                    .bind(aggregate_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            DeleteMode::SoftTombstone => {
+                // The promoted metadata columns are generated from `metadata`,
+                // so clearing it clears them too
+                let event_data_text = serde_json::to_string(&tombstone_data())?;
+                let metadata_text = serde_json::to_string(&EventMetadata::default())?;
+                for schema in schemas {
+                    erased += sqlx::query(&format!(
+                        r#"
+                        UPDATE {schema}.{} SET event_type = ?, event_version = 1, event_data = ?,
+                            event_data_type = 'json', metadata = ?, storage_format_version = ?
+                        WHERE aggregate_id = ?
+                        "#,
+                        self.table_name
+                    ))
+                    .bind(TOMBSTONE_EVENT_TYPE)
+                    .bind(&event_data_text)
+                    .bind(&metadata_text)
+                    .bind(STORAGE_FORMAT_VERSION)
+                    .bind(aggregate_id)
+                    .execute(&mut *tx)
+                    .await?
+                    .rows_affected();
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(erased)
+    }
+
+    async fn close(&self) -> Result<()> {
+        // Fold the WAL into the database file first so the next process to
+        // open it does not start by recovering this one's log. Best effort:
+        // it fails harmlessly on a store that is already closed
+        let _ = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&self.pool).await;
+
+        // Resolves once every checked-out connection is back and closed
+        self.pool.close().await;
+        Ok(())
+    }
+
+    async fn sync_to_disk(&self) -> Result<()> {
+        // A TRUNCATE checkpoint copies the WAL into the database files (the
+        // archive too, when attached), fsyncs them and only then returns, so the
+        // commit no longer depends on the synchronous mode
+        let row = sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(&self.pool)
+            .await?;
+        let busy: i64 = row.try_get(0)?;
+        if busy != 0 {
+            return Err(EventualiError::DatabaseError(
+                "WAL checkpoint could not complete because of concurrent readers".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn stats(&self) -> Result<StoreStats> {
+        let query = format!(
+            r#"
+            SELECT COUNT(*) AS total_events,
+                   COUNT(DISTINCT aggregate_id) AS total_aggregates,
+                   COUNT(DISTINCT aggregate_type) AS aggregate_types,
+                   COUNT(DISTINCT event_type) AS event_types,
+                   COALESCE(SUM(
+                       LENGTH(id) + LENGTH(aggregate_id) + LENGTH(aggregate_type) + LENGTH(event_type)
+                       + LENGTH(event_data) + LENGTH(event_data_type) + LENGTH(metadata) + LENGTH(timestamp)
+                       + 24
+                   ), 0) AS estimated_bytes
+            FROM {}
+            "#,
+            self.source()
+        );
+
+        let row = sqlx::query(&query)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(StoreStats {
+            total_events: row.try_get::<i64, _>("total_events")? as u64,
+            total_aggregates: row.try_get::<i64, _>("total_aggregates")? as u64,
+            aggregate_types: row.try_get::<i64, _>("aggregate_types")? as u64,
+            event_types: row.try_get::<i64, _>("event_types")? as u64,
+            estimated_bytes: row.try_get::<i64, _>("estimated_bytes")? as u64,
+        })
+    }
 }
 
 impl SQLiteBackend {
-    fn row_to_event(&self, row: sqlx::sqlite::SqliteRow) -> Result<Event> {
+    /// Events after `after_global` in global order, only those of
+    /// `aggregate_type` when given
+    async fn load_after_position(
+        &self,
+        aggregate_type: Option<&str>,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        let type_filter = if aggregate_type.is_some() { " AND aggregate_type = ?" } else { "" };
+        let query = format!(
+            r#"
+            SELECT global_position, id, aggregate_id, aggregate_type, event_type, event_version,
+                   aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
+            FROM {}
+            WHERE global_position > ?{type_filter}
+            ORDER BY global_position ASC
+            LIMIT ?
+            "#,
+            self.source()
+        );
+
+        let mut query = sqlx::query(&query).bind(after_global as i64);
+        if let Some(aggregate_type) = aggregate_type {
+            query = query.bind(aggregate_type);
+        }
+        let rows = query.bind(limit as i64).fetch_all(&self.pool).await?;
+
+        let mut events = Vec::with_capacity(rows.len());
+        for row in rows {
+            let global_position: i64 = row.try_get("global_position")?;
+            events.push((global_position as u64, self.row_to_event(&row)?));
+        }
+
+        Ok(events)
+    }
+
+    /// Rows of one aggregate's events in version order, undecoded
+    async fn fetch_aggregate_rows(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<sqlx::sqlite::SqliteRow>> {
+        let query = match from_version {
+            Some(_version) => format!(
+                r#"
+                SELECT id, aggregate_id, aggregate_type, event_type, event_version,
+                       aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
+                FROM {} 
+                WHERE aggregate_id = ? AND aggregate_version > ?
+                ORDER BY aggregate_version ASC
+                "#,
+                self.source()
+            ),
+            None => format!(
+                r#"
+                SELECT id, aggregate_id, aggregate_type, event_type, event_version,
+                       aggregate_version, event_data, event_data_type, metadata, timestamp, storage_format_version
+                FROM {} 
+                WHERE aggregate_id = ?
+                ORDER BY aggregate_version ASC
+                "#,
+                self.source()
+            ),
+        };
+
+        let rows = if let Some(version) = from_version {
+            sqlx::query(&query)
+                .bind(aggregate_id)
+                .bind(version)
+                .fetch_all(&self.pool)
+                .await?
+        } else {
+            sqlx::query(&query)
+                .bind(aggregate_id)
+                .fetch_all(&self.pool)
+                .await?
+        };
+
+        Ok(rows)
+    }
+
+    fn row_to_event(&self, row: &sqlx::sqlite::SqliteRow) -> Result<Event> {
         let id_str: String = row.try_get("id")?;
         let id = Uuid::parse_str(&id_str)
             .map_err(|_| EventualiError::InvalidEventData("Invalid UUID format".to_string()))?;
+        check_storage_format(id, row.try_get("storage_format_version")?)?;
         
         let aggregate_id: String = row.try_get("aggregate_id")?;
         let aggregate_type: String = row.try_get("aggregate_type")?;
@@ -330,10 +1211,11 @@ impl SQLiteBackend {
                 })?;
                 EventData::Protobuf(bytes)
             }
-            _ => {
-                return Err(EventualiError::InvalidEventData(format!(
-                    "Unknown event data type: {event_data_type}"
-                )))
+            codec => {
+                let bytes = general_purpose::STANDARD.decode(&event_data_text).map_err(|_| {
+                    EventualiError::InvalidEventData(format!("Invalid base64 data for codec {codec}"))
+                })?;
+                self.codecs.decode(codec, &bytes)?
             }
         };
 
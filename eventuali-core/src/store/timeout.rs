@@ -0,0 +1,51 @@
+//! Time limits for event store operations
+//!
+//! A store created with an operation timeout fails any backend call that takes
+//! longer with an `Io` error of kind `TimedOut`, so a hung connection cannot
+//! hold a request forever. The limit covers the database round trip only:
+//! time spent waiting for an aggregate lock or publishing saved events does
+//! not count against it. `with_operation_timeout` replaces that limit for the calls
+//! made inside one future, for requests that need a tighter or looser bound
+//! than the store default.
+//!
+//! A save that times out may still have committed: the limit stops waiting for
+//! the database, and the write can finish after the caller has given up.
+//! Retry saves with the same aggregate versions so a committed retry fails with
+//! a concurrency conflict instead of writing twice.
+
+use crate::{EventualiError, Result};
+use std::future::Future;
+use std::time::Duration;
+
+tokio::task_local! {
+    static OPERATION_TIMEOUT: Option<Duration>;
+}
+
+/// Run `future` with every event store call inside it limited to `timeout`
+/// instead of the store's configured operation timeout
+pub async fn with_operation_timeout<F: Future>(timeout: Duration, future: F) -> F::Output {
+    OPERATION_TIMEOUT.scope(Some(timeout), future).await
+}
+
+/// The error a timed-out operation fails with
+pub fn operation_timed_out(timeout: Duration) -> EventualiError {
+    EventualiError::Io(std::io::Error::new(
+        std::io::ErrorKind::TimedOut,
+        format!("operation timed out after {}ms", timeout.as_millis()),
+    ))
+}
+
+/// Await `operation`, failing once the per-call timeout, or else `default`,
+/// has passed
+pub(crate) async fn run_with_timeout<T>(
+    default: Option<Duration>,
+    operation: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let limit = OPERATION_TIMEOUT.try_with(|timeout| *timeout).ok().flatten().or(default);
+    match limit {
+        Some(limit) => tokio::time::timeout(limit, operation)
+            .await
+            .unwrap_or_else(|_| Err(operation_timed_out(limit))),
+        None => operation.await,
+    }
+}
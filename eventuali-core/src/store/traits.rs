@@ -1,49 +1,704 @@
-use crate::{Event, AggregateId, AggregateVersion, Result};
+use crate::event::IdAllocator;
+use crate::{Event, EventId, AggregateId, AggregateVersion, EventualiError, Result};
+use chrono::{DateTime, Utc};
+use crate::store::compaction::{CompactedStream, CompactionReport, Compactor};
+use crate::store::erasure::DeleteMode;
+use crate::store::existence::ExistenceCacheStats;
+use crate::store::failed_writes::{FailedWrite, FailedWriteFilter};
+use crate::store::quarantine::LenientLoad;
+use crate::store::validation::StreamValidation;
 use crate::streaming::EventStreamer;
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+/// Store-wide counts and size estimate for dashboards and capacity reports
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreStats {
+    pub total_events: u64,
+    pub total_aggregates: u64,
+    pub aggregate_types: u64,
+    pub event_types: u64,
+    /// Approximate bytes used by stored events; how it is measured depends on the backend
+    pub estimated_bytes: u64,
+}
+
+/// Builds `StoreStats` by visiting events one at a time, for stores without a
+/// cheaper aggregate query. Sizes are estimated from each event's JSON encoding.
+#[derive(Debug, Default)]
+pub(crate) struct StoreStatsAccumulator {
+    total_events: u64,
+    /// Events seen per aggregate, aggregate type and event type
+    aggregates: HashMap<String, u64>,
+    aggregate_types: HashMap<String, u64>,
+    event_types: HashMap<String, u64>,
+    estimated_bytes: u64,
+}
+
+impl StoreStatsAccumulator {
+    pub(crate) fn add(&mut self, event: &Event) {
+        self.total_events += 1;
+        *self.aggregates.entry(event.aggregate_id.clone()).or_default() += 1;
+        *self.aggregate_types.entry(event.aggregate_type.clone()).or_default() += 1;
+        *self.event_types.entry(event.event_type.clone()).or_default() += 1;
+        self.estimated_bytes += serde_json::to_vec(event).map(|v| v.len() as u64).unwrap_or(0);
+    }
+
+    pub(crate) fn finish(self) -> StoreStats {
+        StoreStats {
+            total_events: self.total_events,
+            total_aggregates: self.aggregates.len() as u64,
+            aggregate_types: self.aggregate_types.len() as u64,
+            event_types: self.event_types.len() as u64,
+            estimated_bytes: self.estimated_bytes,
+        }
+    }
+}
+
 #[async_trait]
 pub trait EventStore {
-    async fn save_events(&self, events: Vec<Event>) -> Result<()>;
+    async fn save_events(&self, events: Vec<Event>) -> Result<()> {
+        self.save_events_expecting(events, None).await
+    }
     
+    /// Save `events` only if their aggregate is still at `expected_version`,
+    /// checked in the same transaction as the insert; `Some(0)` expects a new
+    /// aggregate. A mismatch fails with `OptimisticConcurrency` and writes
+    /// nothing. With an expected version the events must all belong to one
+    /// aggregate; `None` skips the check.
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<()>;
+    
+    /// Run `events` through the save path, including the backend's inserts,
+    /// then roll the transaction back, so nothing is stored or published.
+    /// Fails as the save would. Only some stores support it.
+    async fn dry_run_save_events(&self, _events: Vec<Event>) -> Result<()> {
+        Err(dry_run_unsupported())
+    }
+    
+    /// Load the events of a single aggregate, always ordered by `aggregate_version`.
     async fn load_events(
         &self,
         aggregate_id: &AggregateId,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>>;
     
+    /// Load the events of a single aggregate like `load_events`, but skip
+    /// rows that cannot be decoded instead of failing. Skipped rows are logged
+    /// and returned as quarantine entries with their raw bytes, so one corrupt
+    /// row does not make the rest of the stream unreachable.
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        Ok(self.load_events(aggregate_id, from_version).await?.into())
+    }
+    
+    /// Load the aggregate's events up to and including `version`, ordered by
+    /// `aggregate_version`, to reconstruct its state as of that version.
+    ///
+    /// Fails with `HistoryCompacted` when `version` comes before the latest
+    /// compaction point, since the events needed to rebuild that version were
+    /// folded into the compaction snapshot. From the compaction point on, the
+    /// snapshot is the first event returned.
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        let history = match self.load_compaction_history(aggregate_id).await {
+            Ok(history) => history,
+            Err(EventualiError::Configuration(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        ensure_version_not_compacted(aggregate_id, version, &history)?;
+        let mut events = self.load_events(aggregate_id, None).await?;
+        events.retain(|e| e.aggregate_version <= version);
+        Ok(events)
+    }
+    
+    /// Load the aggregate's full stream and report any version gaps,
+    /// duplicate versions or timestamp regressions in it.
+    async fn validate_stream(&self, aggregate_id: &AggregateId) -> Result<StreamValidation> {
+        let events = self.load_events(aggregate_id, None).await?;
+        Ok(StreamValidation::of(aggregate_id, &events))
+    }
+    
+    /// Load the events of every aggregate of a type, always ordered by global
+    /// position (commit order), so events sharing a timestamp load deterministically.
     async fn load_events_by_type(
         &self,
         aggregate_type: &str,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>>;
     
+    /// Load up to `limit` events whose metadata carries `correlation_id`,
+    /// ordered by global position, to follow one request across aggregates.
+    async fn get_events_by_correlation_id(&self, correlation_id: &str, limit: usize) -> Result<Vec<Event>> {
+        self.get_events_by_correlation_id_with_prefix(correlation_id, "", limit).await
+    }
+    
+    /// Like `get_events_by_correlation_id`, counting toward `limit` only the
+    /// events whose aggregate ID starts with `aggregate_id_prefix`, so a
+    /// tenant-scoped store still gets a full page of its own events.
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>>;
+    
+    /// Load up to `limit` events whose metadata carries `user_id`, ordered by
+    /// global position, for per-user audits.
+    async fn get_events_by_user_id(&self, user_id: &str, limit: usize) -> Result<Vec<Event>> {
+        self.get_events_by_user_id_with_prefix(user_id, "", limit).await
+    }
+    
+    /// Like `get_events_by_user_id`, counting toward `limit` only the events
+    /// whose aggregate ID starts with `aggregate_id_prefix`.
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>>;
+    
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>>;
     
+    /// Whether any event has been saved for the aggregate.
+    async fn aggregate_exists(&self, aggregate_id: &AggregateId) -> Result<bool> {
+        Ok(self.get_aggregate_version(aggregate_id).await?.is_some())
+    }
+    
+    /// Save `events` as the start of a new aggregate, or return `false`
+    /// without writing if the aggregate already exists.
+    ///
+    /// The events must belong to one aggregate and start at version 1, so a
+    /// writer racing to create the same aggregate loses with a version
+    /// conflict, which is also reported as `false`.
+    async fn append_if_not_exists(&self, events: Vec<Event>) -> Result<bool> {
+        check_new_aggregate(&events)?;
+        if self.aggregate_exists(&events[0].aggregate_id).await? {
+            return Ok(false);
+        }
+        match self.save_events(events).await {
+            Ok(()) => Ok(true),
+            Err(EventualiError::OptimisticConcurrency { .. }) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Load up to `limit` events committed after global position `after_global`,
+    /// ordered by global position and paired with it. Pass `0` to start at the beginning.
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>>;
+    
+    /// Like `load_events_after_position`, counting toward `limit` only events
+    /// of `aggregate_type`. The default pages through every event; SQL
+    /// backends filter in the query.
+    async fn load_events_of_type_after_position(
+        &self,
+        aggregate_type: &str,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        collect_events_of_type(aggregate_type, after_global, limit, |after| {
+            self.load_events_after_position(after, limit)
+        })
+        .await
+    }
+    
+    /// List the distinct aggregate types that have at least one event, sorted.
+    async fn list_aggregate_types(&self) -> Result<Vec<String>>;
+    
+    /// Count events, aggregates and types, and estimate storage used.
+    async fn stats(&self) -> Result<StoreStats>;
+    
+    /// Move events older than `cutoff` into cold storage, returning how many
+    /// moved. Loads keep returning archived events. Only some backends support it.
+    async fn archive_events_before(&self, _cutoff: DateTime<Utc>) -> Result<u64> {
+        Err(archiving_unsupported())
+    }
+    
+    /// Force every committed write onto disk before returning, whatever the
+    /// backend's configured sync mode. Stores whose commits are already durable
+    /// keep the default.
+    async fn sync_to_disk(&self) -> Result<()> {
+        Ok(())
+    }
+    
+    /// Save events and return only once they are durable on disk, for writes
+    /// that cannot be lost to a crash right after commit.
+    ///
+    /// A sync failure after the save committed is reported as
+    /// `EventualiError::NotDurable`, so callers do not retry a save that
+    /// already went through.
+    async fn save_events_durable(&self, events: Vec<Event>) -> Result<()> {
+        self.save_events(events).await?;
+        self.sync_to_disk()
+            .await
+            .map_err(|source| EventualiError::NotDurable { source: Box::new(source) })
+    }
+    
+    /// Load up to `limit` events whose outbox rows are not yet marked published,
+    /// in global order and paired with their global position. Only stores
+    /// configured with a transactional outbox support it.
+    async fn load_unpublished_events(&self, _limit: usize) -> Result<Vec<(u64, Event)>> {
+        Err(outbox_unsupported())
+    }
+    
+    /// Mark the outbox rows of `event_ids` as published.
+    async fn mark_events_published(&self, _event_ids: &[EventId]) -> Result<()> {
+        Err(outbox_unsupported())
+    }
+    
+    /// Replace a prefix of the aggregate's stream with a snapshot event built by
+    /// `compactor`, keeping later events, and record the compaction. Only some
+    /// backends support it.
+    async fn compact_aggregate(
+        &self,
+        _aggregate_id: &AggregateId,
+        _compactor: &dyn Compactor,
+    ) -> Result<CompactionReport> {
+        Err(compaction_unsupported())
+    }
+    
+    /// Every compaction recorded for the aggregate, oldest first.
+    async fn load_compaction_history(&self, _aggregate_id: &AggregateId) -> Result<Vec<CompactionReport>> {
+        Err(compaction_unsupported())
+    }
+    
+    /// Permanently delete every event of the aggregate, for hard erasure of
+    /// personal data, returning how many were removed. Only some backends
+    /// support it.
+    async fn delete_aggregate_events(&self, aggregate_id: &AggregateId) -> Result<u64> {
+        self.delete_aggregate_events_with_mode(aggregate_id, DeleteMode::Hard).await
+    }
+    
+    /// Erase every event of the aggregate as `mode` says, in one transaction,
+    /// returning how many events were deleted or tombstoned.
+    ///
+    /// The store's failed-write log rows for the aggregate go too. Aggregate
+    /// snapshots live in a separate `SnapshotStore` the event store does not
+    /// know about; they hold the aggregate's state, so callers erasing personal
+    /// data must also call `SnapshotStore::delete_aggregate_snapshots`.
+    async fn delete_aggregate_events_with_mode(
+        &self,
+        _aggregate_id: &AggregateId,
+        _mode: DeleteMode,
+    ) -> Result<u64> {
+        Err(deletion_unsupported())
+    }
+    
+    /// Load the aggregate's stream split at its latest compaction, to
+    /// reconstruct it from the compaction snapshot plus the retained tail.
+    /// Streams of backends without compaction load as never compacted.
+    async fn load_compacted(&self, aggregate_id: &AggregateId) -> Result<CompactedStream> {
+        let events = self.load_events(aggregate_id, None).await?;
+        let history = match self.load_compaction_history(aggregate_id).await {
+            Ok(history) => history,
+            Err(EventualiError::Configuration(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        CompactedStream::split(aggregate_id, events, &history)
+    }
+    
+    /// Load events like `load_events`, but fail with `HistoryCompacted`
+    /// instead of returning a compaction snapshot in place of the individual
+    /// events it replaced, when any version after `from_version` was compacted.
+    async fn load_history(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<Vec<Event>> {
+        let stream = self.load_compacted(aggregate_id).await?;
+        if stream.is_compacted() && from_version.is_none_or(|version| version < stream.compacted_through) {
+            return Err(EventualiError::HistoryCompacted {
+                aggregate_id: aggregate_id.clone(),
+                compacted_through: stream.compacted_through,
+            });
+        }
+        let from_version = from_version.unwrap_or(0);
+        Ok(stream.tail.into_iter().filter(|e| e.aggregate_version > from_version).collect())
+    }
+    
+    /// Rejected saves matching `filter`, newest first, from a store created
+    /// with a failed-write log.
+    async fn query_failed_writes(&self, _filter: &FailedWriteFilter, _limit: usize) -> Result<Vec<FailedWrite>> {
+        Err(EventualiError::Configuration("Failed-write log is not enabled".to_string()))
+    }
+    
+    /// The latest `n` events committed through this store, oldest first, from
+    /// a store created with a recent-events buffer. Served from memory, and
+    /// never more than the buffer's capacity.
+    async fn recent_events(&self, _n: usize) -> Result<Vec<Event>> {
+        Err(EventualiError::Configuration("Recent-events buffer is not enabled".to_string()))
+    }
+    
+    /// Write every event, or only those of `aggregate_type`, to `writer` as
+    /// one Apache Parquet file in global order, with default `ParquetExport`
+    /// settings.
+    #[cfg(feature = "parquet")]
+    async fn export_parquet(
+        &self,
+        aggregate_type: Option<&str>,
+        writer: Box<dyn std::io::Write + Send>,
+    ) -> Result<crate::store::ParquetExportReport> {
+        crate::store::ParquetExport::new().export(self, aggregate_type, writer).await
+    }
+    
+    /// Hit and miss counts of the existence cache behind `aggregate_exists`,
+    /// from a store created with one.
+    async fn existence_cache_stats(&self) -> Result<ExistenceCacheStats> {
+        Err(EventualiError::Configuration("Existence cache is not enabled".to_string()))
+    }
+    
+    /// Shut the store down cleanly: finish publishing events already handed
+    /// to background tasks, wait for in-flight operations to return their
+    /// connections and close the pool. Operations after `close` fail.
+    ///
+    /// Views sharing a backend with other stores, such as tenant-scoped
+    /// stores, leave the backend open.
+    ///
+    /// The publish outbox's drain task is the only task a store starts
+    /// itself; `close` publishes what is queued, stops it and fails if it
+    /// died. Tasks started next to it, such as `OutboxRelay::spawn`, a
+    /// metrics collector or health checks, hold their own handle to the
+    /// store and belong to the caller, who stops them before closing it.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// An ID for a new event to be saved through this store, from the
+    /// allocator it was configured with or else the process default kind.
+    /// Use it with `Event::with_id`.
+    fn new_event_id(&self) -> Result<EventId> {
+        Ok(crate::event::new_event_id())
+    }
+
+    /// Hand out the IDs of `new_event_id` from `allocator` from now on, such
+    /// as an `EventIdKind` or an external sequencer. Only this store is affected.
+    fn set_id_allocator(&mut self, allocator: Arc<dyn IdAllocator>);
+
     /// Set the event streamer for publishing events
     fn set_event_streamer(&mut self, streamer: Arc<dyn EventStreamer + Send + Sync>);
+
+    /// The streamer saved events are published to, if any
+    fn event_streamer(&self) -> Option<Arc<dyn EventStreamer + Send + Sync>> {
+        None
+    }
 }
 
 #[async_trait]
 pub trait EventStoreBackend {
     async fn initialize(&mut self) -> Result<()>;
     
-    async fn save_events(&self, events: Vec<Event>) -> Result<()>;
+    /// Save `events` atomically and return the global position the database
+    /// assigned to each, in the order the events were given. A backend that
+    /// assigns positions after committing fails with `PositionsPending` when
+    /// only that step failed; the events are saved.
+    async fn save_events(&self, events: Vec<Event>) -> Result<Vec<u64>> {
+        self.save_events_expecting(events, None).await
+    }
     
+    /// `save_events`, but fail with `OptimisticConcurrency` unless the events'
+    /// aggregate is at `expected_version` when the transaction takes its lock.
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<Vec<u64>>;
+    
+    /// Perform every check and insert of `save_events` in a transaction that
+    /// is rolled back instead of committed.
+    async fn dry_run_save_events(&self, _events: Vec<Event>) -> Result<()> {
+        Err(dry_run_unsupported())
+    }
+    
+    /// Load the events of a single aggregate, always ordered by `aggregate_version`.
     async fn load_events(
         &self,
         aggregate_id: &AggregateId,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>>;
     
+    /// Load the events of every aggregate of a type, always ordered by global
+    /// position (commit order), so events sharing a timestamp load deterministically.
     async fn load_events_by_type(
         &self,
         aggregate_type: &str,
         from_version: Option<AggregateVersion>,
     ) -> Result<Vec<Event>>;
     
+    /// Load up to `limit` events whose metadata carries `correlation_id`,
+    /// ordered by global position, to follow one request across aggregates.
+    async fn get_events_by_correlation_id(&self, correlation_id: &str, limit: usize) -> Result<Vec<Event>> {
+        self.get_events_by_correlation_id_with_prefix(correlation_id, "", limit).await
+    }
+    
+    /// Like `get_events_by_correlation_id`, counting toward `limit` only the
+    /// events whose aggregate ID starts with `aggregate_id_prefix`, so a
+    /// tenant-scoped store still gets a full page of its own events.
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>>;
+    
+    /// Load up to `limit` events whose metadata carries `user_id`, ordered by
+    /// global position, for per-user audits.
+    async fn get_events_by_user_id(&self, user_id: &str, limit: usize) -> Result<Vec<Event>> {
+        self.get_events_by_user_id_with_prefix(user_id, "", limit).await
+    }
+    
+    /// Like `get_events_by_user_id`, counting toward `limit` only the events
+    /// whose aggregate ID starts with `aggregate_id_prefix`.
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>>;
+    
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>>;
+    
+    /// Load up to `limit` events committed after global position `after_global`,
+    /// ordered by global position and paired with it. Pass `0` to start at the beginning.
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>>;
+    
+    /// Like `load_events_after_position`, counting toward `limit` only events
+    /// of `aggregate_type`. The default pages through every event; SQL
+    /// backends filter in the query.
+    async fn load_events_of_type_after_position(
+        &self,
+        aggregate_type: &str,
+        after_global: u64,
+        limit: usize,
+    ) -> Result<Vec<(u64, Event)>> {
+        collect_events_of_type(aggregate_type, after_global, limit, |after| {
+            self.load_events_after_position(after, limit)
+        })
+        .await
+    }
+    
+    /// List the distinct aggregate types that have at least one event, sorted.
+    async fn list_aggregate_types(&self) -> Result<Vec<String>>;
+    
+    /// Count events, aggregates and types, and estimate storage used.
+    async fn stats(&self) -> Result<StoreStats>;
+
+    /// Load an aggregate's events up to `version`; see
+    /// `EventStore::load_events_at_version`. Backends that can filter on
+    /// version in the query override it.
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        let history = match self.load_compaction_history(aggregate_id).await {
+            Ok(history) => history,
+            Err(EventualiError::Configuration(_)) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+        ensure_version_not_compacted(aggregate_id, version, &history)?;
+        let mut events = self.load_events(aggregate_id, None).await?;
+        events.retain(|e| e.aggregate_version <= version);
+        Ok(events)
+    }
+
+    /// Load an aggregate's events, skipping rows that cannot be decoded; see
+    /// `EventStore::load_events_lenient`. Backends whose rows are never
+    /// stored encoded decode strictly.
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        Ok(self.load_events(aggregate_id, from_version).await?.into())
+    }
+
+    async fn archive_events_before(&self, _cutoff: DateTime<Utc>) -> Result<u64> {
+        Err(archiving_unsupported())
+    }
+
+    /// Force committed writes onto disk; see `EventStore::sync_to_disk`.
+    async fn sync_to_disk(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn load_unpublished_events(&self, _limit: usize) -> Result<Vec<(u64, Event)>> {
+        Err(outbox_unsupported())
+    }
+
+    async fn mark_events_published(&self, _event_ids: &[EventId]) -> Result<()> {
+        Err(outbox_unsupported())
+    }
+
+    async fn compact_aggregate(
+        &self,
+        _aggregate_id: &AggregateId,
+        _compactor: &dyn Compactor,
+    ) -> Result<CompactionReport> {
+        Err(compaction_unsupported())
+    }
+
+    async fn load_compaction_history(&self, _aggregate_id: &AggregateId) -> Result<Vec<CompactionReport>> {
+        Err(compaction_unsupported())
+    }
+
+    /// Erase the aggregate's events; see `EventStore::delete_aggregate_events_with_mode`.
+    async fn delete_aggregate_events(&self, _aggregate_id: &AggregateId, _mode: DeleteMode) -> Result<u64> {
+        Err(deletion_unsupported())
+    }
+
+    /// Wait for checked-out connections to be returned, then close the pool.
+    async fn close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Check that `events` could open a new aggregate's stream
+fn check_new_aggregate(events: &[Event]) -> Result<()> {
+    let Some(first) = events.first() else {
+        return Err(EventualiError::Validation("No events to append".to_string()));
+    };
+    if first.aggregate_version != 1 {
+        return Err(EventualiError::Validation(format!(
+            "A new aggregate starts at version 1, not {}",
+            first.aggregate_version
+        )));
+    }
+    if let Some(event) = events.iter().find(|e| e.aggregate_id != first.aggregate_id) {
+        return Err(EventualiError::Validation(format!(
+            "Event for aggregate {} cannot be appended to aggregate {}",
+            event.aggregate_id, first.aggregate_id
+        )));
+    }
+    Ok(())
+}
+
+/// The aggregate a save expecting `expected_version` must check, or `None`
+/// when there is nothing to check
+pub(crate) fn expected_aggregate(
+    events: &[Event],
+    expected_version: Option<AggregateVersion>,
+) -> Result<Option<&AggregateId>> {
+    let (Some(_), Some(first)) = (expected_version, events.first()) else {
+        return Ok(None);
+    };
+    if let Some(event) = events.iter().find(|e| e.aggregate_id != first.aggregate_id) {
+        return Err(EventualiError::Validation(format!(
+            "A save expecting a version covers one aggregate, but has events for {} and {}",
+            first.aggregate_id, event.aggregate_id
+        )));
+    }
+    Ok(Some(&first.aggregate_id))
+}
+
+/// Fail with `OptimisticConcurrency` unless an aggregate at `current` (`None`
+/// for one with no events) is at `expected_version`
+pub(crate) fn check_expected_version(
+    expected_version: Option<AggregateVersion>,
+    current: Option<AggregateVersion>,
+) -> Result<()> {
+    let actual = current.unwrap_or(0);
+    match expected_version {
+        Some(expected) if expected != actual => Err(EventualiError::OptimisticConcurrency { expected, actual }),
+        _ => Ok(()),
+    }
+}
+
+/// Fail with `HistoryCompacted` if the latest compaction in `history` folded
+/// away events needed to rebuild the aggregate at `version`
+pub(crate) fn ensure_version_not_compacted(
+    aggregate_id: &AggregateId,
+    version: AggregateVersion,
+    history: &[CompactionReport],
+) -> Result<()> {
+    match history.iter().rev().find(|report| report.compacted()) {
+        Some(report) if version < report.compacted_through => Err(EventualiError::HistoryCompacted {
+            aggregate_id: aggregate_id.clone(),
+            compacted_through: report.compacted_through,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Error for saving an event whose ID another stored event already has
+pub(crate) fn duplicate_event_id(id: &EventId) -> EventualiError {
+    EventualiError::Validation(format!("Event ID {id} is already in use"))
+}
+
+fn dry_run_unsupported() -> EventualiError {
+    EventualiError::Configuration("Dry-run writes are not supported by this backend".to_string())
+}
+
+fn archiving_unsupported() -> EventualiError {
+    EventualiError::Configuration("Event archiving is not supported by this backend".to_string())
+}
+
+fn compaction_unsupported() -> EventualiError {
+    EventualiError::Configuration("Event compaction is not supported by this backend".to_string())
+}
+
+fn deletion_unsupported() -> EventualiError {
+    EventualiError::Configuration("Deleting events is not supported by this backend".to_string())
+}
+
+/// Metadata fields backends expose as indexed columns of the events table, so
+/// filtering on them does not extract JSON from every row. The metadata column
+/// keeps the full metadata, headers included.
+pub(crate) const PROMOTED_METADATA_FIELDS: [&str; 3] = ["correlation_id", "causation_id", "user_id"];
+
+/// Version of the row format backends write events in, stored with every
+/// event so a reader can tell which codec, compression and encryption rules
+/// a row was written under. Rows from before the stamp existed are version 1.
+pub const STORAGE_FORMAT_VERSION: i32 = 1;
+
+/// Refuse rows written in a format newer than this build reads, rather than
+/// mis-parsing them
+pub(crate) fn check_storage_format(event_id: impl ToString, version: i32) -> Result<()> {
+    if version > STORAGE_FORMAT_VERSION {
+        return Err(EventualiError::UnsupportedStorageFormat {
+            event_id: event_id.to_string(),
+            version,
+            supported: STORAGE_FORMAT_VERSION,
+        });
+    }
+    Ok(())
+}
+
+/// Up to `limit` events of `aggregate_type` after `after_global`, filtered
+/// from the unfiltered pages `load_page` returns
+async fn collect_events_of_type<F, Fut>(
+    aggregate_type: &str,
+    mut after_global: u64,
+    limit: usize,
+    mut load_page: F,
+) -> Result<Vec<(u64, Event)>>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<(u64, Event)>>>,
+{
+    let mut events = Vec::new();
+    while events.len() < limit {
+        let page = load_page(after_global).await?;
+        let Some(&(last_position, _)) = page.last() else {
+            break;
+        };
+        events.extend(page.into_iter().filter(|(_, event)| event.aggregate_type == aggregate_type));
+        after_global = last_position;
+    }
+    events.truncate(limit);
+    Ok(events)
+}
+
+pub(crate) fn outbox_unsupported() -> EventualiError {
+    EventualiError::Configuration("No transactional outbox is configured for this store".to_string())
 }
 
 pub trait EventSerializer {
@@ -1,8 +1,14 @@
-use crate::{Event, Result, EventualiError};
+use crate::{Event, EventId, Result, EventualiError};
+use crate::snapshot::{ProjectionSnapshot, ProjectionSnapshotStore};
+use crate::store::EventStore;
+#[cfg(feature = "observability")]
+use crate::observability::{HealthCheckResult, HealthChecker, HealthStatus, MetricsCollector};
 use async_trait::async_trait;
 use tokio::sync::broadcast;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
 use uuid::Uuid;
 
 /// Event stream subscription
@@ -12,6 +18,41 @@ pub struct Subscription {
     pub aggregate_type_filter: Option<String>,
     pub event_type_filter: Option<String>,
     pub from_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    /// Maximum events per second while catching up from the store; live events are never throttled
+    pub replay_rate_limit: Option<u32>,
+}
+
+impl Subscription {
+    /// Whether `event` passes the subscription's aggregate and event type filters
+    pub fn matches(&self, event: &Event) -> bool {
+        self.aggregate_type_filter.as_ref().is_none_or(|t| *t == event.aggregate_type)
+            && self.event_type_filter.as_ref().is_none_or(|t| *t == event.event_type)
+    }
+}
+
+/// Paces catch-up replay to a fixed number of events per second
+#[derive(Debug)]
+pub struct ReplayThrottle {
+    interval: Duration,
+    next_slot: Option<Instant>,
+}
+
+impl ReplayThrottle {
+    pub fn new(events_per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / events_per_second.max(1) as f64),
+            next_slot: None,
+        }
+    }
+
+    /// Wait until the next event may be replayed. Slots missed while the caller
+    /// was busy are not saved up, so replay never bursts above the rate.
+    pub async fn acquire(&mut self) {
+        let now = Instant::now();
+        let slot = self.next_slot.map_or(now, |next| next.max(now));
+        tokio::time::sleep_until(slot).await;
+        self.next_slot = Some(slot + self.interval);
+    }
 }
 
 /// Event stream message
@@ -35,6 +76,129 @@ pub trait EventStreamer {
 /// Event stream receiver
 pub type EventStreamReceiver = tokio::sync::broadcast::Receiver<StreamEvent>;
 
+/// Message from a catch-up subscription
+#[derive(Debug, Clone)]
+pub enum CatchUpEvent {
+    /// A stored or live event that passed the subscription's filters
+    Event(Box<StreamEvent>),
+    /// Replay reached the live tail at `global_position`; sent once, before the
+    /// first live event
+    CaughtUp { global_position: u64 },
+}
+
+/// Subscription that replays stored events from a position, then follows the
+/// live stream.
+///
+/// The live receiver is opened before replay starts so nothing committed
+/// meanwhile is missed; live events at or before the last replayed position
+/// are dropped as already delivered. This relies on the streamer publishing
+/// the store's own global positions. If the live receiver lags, the gap is
+/// filled from the store again without sending a second `CaughtUp`, and so
+/// is a live event that skips past the next position, since concurrent saves
+/// can publish out of commit order.
+pub struct CatchUpSubscription {
+    store: Arc<dyn EventStore + Send + Sync>,
+    live: EventStreamReceiver,
+    subscription: Subscription,
+    batch_size: usize,
+    position: u64,
+    pending: VecDeque<(u64, Event)>,
+    replaying: bool,
+    caught_up: bool,
+    throttle: Option<ReplayThrottle>,
+}
+
+impl CatchUpSubscription {
+    /// Subscribe to `streamer` and replay `store` from after `from_position`,
+    /// reading `batch_size` events per query
+    pub async fn start(
+        streamer: &(dyn EventStreamer + Send + Sync),
+        store: Arc<dyn EventStore + Send + Sync>,
+        subscription: Subscription,
+        from_position: u64,
+        batch_size: usize,
+    ) -> Result<Self> {
+        let live = streamer.subscribe(subscription.clone()).await?;
+        Ok(Self {
+            store,
+            live,
+            throttle: subscription.replay_rate_limit.map(ReplayThrottle::new),
+            subscription,
+            batch_size: batch_size.max(1),
+            position: from_position,
+            pending: VecDeque::new(),
+            replaying: true,
+            caught_up: false,
+        })
+    }
+
+    /// Global position of the last event delivered or skipped by the filters
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Whether `CaughtUp` has been sent
+    pub fn is_caught_up(&self) -> bool {
+        self.caught_up
+    }
+
+    /// Next message, or `None` once the live stream has closed
+    pub async fn next(&mut self) -> Result<Option<CatchUpEvent>> {
+        loop {
+            if let Some((global_position, event)) = self.pending.pop_front() {
+                self.position = global_position;
+                if !self.subscription.matches(&event) {
+                    continue;
+                }
+                if !self.caught_up {
+                    if let Some(throttle) = self.throttle.as_mut() {
+                        throttle.acquire().await;
+                    }
+                }
+                return Ok(Some(CatchUpEvent::Event(Box::new(StreamEvent {
+                    stream_position: event.aggregate_version as u64,
+                    global_position,
+                    event,
+                }))));
+            }
+
+            if self.replaying {
+                let page = self.store.load_events_after_position(self.position, self.batch_size).await?;
+                if !page.is_empty() {
+                    self.pending.extend(page);
+                    continue;
+                }
+                self.replaying = false;
+                if !self.caught_up {
+                    self.caught_up = true;
+                    return Ok(Some(CatchUpEvent::CaughtUp { global_position: self.position }));
+                }
+            }
+
+            match self.live.recv().await {
+                Ok(stream_event) => {
+                    if stream_event.global_position <= self.position {
+                        continue;
+                    }
+                    if stream_event.global_position > self.position + 1 {
+                        // Saves publish after they commit, so a later commit
+                        // can be published first; read every position up to
+                        // this one from the store, which holds them in order
+                        self.replaying = true;
+                        continue;
+                    }
+                    self.position = stream_event.global_position;
+                    if self.subscription.matches(&stream_event.event) {
+                        return Ok(Some(CatchUpEvent::Event(Box::new(stream_event))));
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => self.replaying = true,
+                Err(broadcast::error::RecvError::Closed) => return Ok(None),
+            }
+        }
+    }
+}
+
 /// In-memory event streamer implementation
 pub struct InMemoryEventStreamer {
     sender: broadcast::Sender<StreamEvent>,
@@ -116,22 +280,654 @@ impl EventStreamer for InMemoryEventStreamer {
     }
 }
 
+/// Hub that several in-process stores publish into, merging their events
+/// into one stream.
+///
+/// Each attached store keeps its own global positions, which collide across
+/// stores, so the hub renumbers every event it receives with a hub-wide
+/// position in arrival order. Publishing is serialized, and each store
+/// publishes an aggregate's events in version order, so subscribers see every
+/// aggregate's events in order. Hub positions are unrelated to any store's
+/// positions: catch-up subscriptions need the store's own streamer, which
+/// attaching leaves in place.
+pub struct SharedStreamer {
+    hub: InMemoryEventStreamer,
+    /// Last hub position assigned, held while broadcasting so subscribers
+    /// receive events in position order
+    position: tokio::sync::Mutex<u64>,
+    /// Last version published per (store ID, aggregate ID); aggregate IDs
+    /// from different stores may collide
+    stream_positions: Mutex<HashMap<(String, String), u64>>,
+}
+
+impl SharedStreamer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            hub: InMemoryEventStreamer::new(capacity),
+            position: tokio::sync::Mutex::new(0),
+            stream_positions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Publish every event `store` saves from now on into this hub as well,
+    /// under `store_id`. A streamer the store already had keeps receiving its
+    /// events with the store's own positions.
+    pub fn attach(self: &Arc<Self>, store_id: impl Into<String>, store: &mut (dyn EventStore + Send + Sync)) {
+        store.set_event_streamer(Arc::new(AttachedStore {
+            hub: self.clone(),
+            store_id: store_id.into(),
+            own: store.event_streamer(),
+        }));
+    }
+
+    /// Last version of `aggregate_id` the store attached as `store_id` published
+    pub fn store_stream_position(&self, store_id: &str, aggregate_id: &str) -> Result<Option<u64>> {
+        let positions = self.stream_positions.lock()
+            .map_err(|_| EventualiError::Configuration("Failed to acquire stream positions lock".to_string()))?;
+        Ok(positions.get(&(store_id.to_string(), aggregate_id.to_string())).copied())
+    }
+
+    async fn publish_from(&self, store_id: &str, event: Event, stream_position: u64) -> Result<()> {
+        let mut position = self.position.lock().await;
+        {
+            let mut positions = self.stream_positions.lock()
+                .map_err(|_| EventualiError::Configuration("Failed to acquire stream positions lock".to_string()))?;
+            positions.insert((store_id.to_string(), event.aggregate_id.clone()), stream_position);
+        }
+        self.hub.publish_event(event, stream_position, *position + 1).await?;
+        *position += 1;
+        Ok(())
+    }
+}
+
+/// A store's streamer once attached to a `SharedStreamer`: events go to the
+/// streamer the store had, if any, and then to the hub
+struct AttachedStore {
+    hub: Arc<SharedStreamer>,
+    store_id: String,
+    own: Option<Arc<dyn EventStreamer + Send + Sync>>,
+}
+
+#[async_trait]
+impl EventStreamer for AttachedStore {
+    async fn subscribe(&self, subscription: Subscription) -> Result<EventStreamReceiver> {
+        match &self.own {
+            Some(own) => own.subscribe(subscription).await,
+            None => self.hub.subscribe(subscription).await,
+        }
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> Result<()> {
+        match &self.own {
+            Some(own) => own.unsubscribe(subscription_id).await,
+            None => self.hub.unsubscribe(subscription_id).await,
+        }
+    }
+
+    async fn publish_event(&self, event: Event, stream_position: u64, global_position: u64) -> Result<()> {
+        if let Some(own) = &self.own {
+            own.publish_event(event.clone(), stream_position, global_position).await?;
+        }
+        self.hub.publish_from(&self.store_id, event, stream_position).await
+    }
+
+    async fn get_stream_position(&self, stream_id: &str) -> Result<Option<u64>> {
+        match &self.own {
+            Some(own) => own.get_stream_position(stream_id).await,
+            None => self.hub.store_stream_position(&self.store_id, stream_id),
+        }
+    }
+
+    async fn get_global_position(&self) -> Result<u64> {
+        match &self.own {
+            Some(own) => own.get_global_position().await,
+            None => self.hub.get_global_position().await,
+        }
+    }
+}
+
+#[async_trait]
+impl EventStreamer for SharedStreamer {
+    async fn subscribe(&self, subscription: Subscription) -> Result<EventStreamReceiver> {
+        self.hub.subscribe(subscription).await
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> Result<()> {
+        self.hub.unsubscribe(subscription_id).await
+    }
+
+    /// Broadcast `event` at the next hub position, as published by no
+    /// attached store; the publisher's `global_position` is not comparable
+    /// across stores and is ignored
+    async fn publish_event(&self, event: Event, stream_position: u64, _global_position: u64) -> Result<()> {
+        self.publish_from("", event, stream_position).await
+    }
+
+    /// Last version of `stream_id` published straight into the hub; see
+    /// `store_stream_position` for attached stores
+    async fn get_stream_position(&self, stream_id: &str) -> Result<Option<u64>> {
+        self.store_stream_position("", stream_id)
+    }
+
+    async fn get_global_position(&self) -> Result<u64> {
+        self.hub.get_global_position().await
+    }
+}
+
 /// Event stream processor for handling events as they arrive
 #[async_trait]
 pub trait EventStreamProcessor {
     async fn process_event(&self, event: &StreamEvent) -> Result<()>;
 }
 
+/// Bounded LRU set of recently seen event IDs
+#[derive(Debug)]
+struct DedupWindow {
+    capacity: usize,
+    /// Event ID to the tick at which it was last seen
+    seen: HashMap<EventId, u64>,
+    /// Access order; entries whose tick no longer matches `seen` are stale
+    order: VecDeque<(EventId, u64)>,
+    tick: u64,
+    duplicates_skipped: u64,
+}
+
+impl DedupWindow {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            seen: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+            tick: 0,
+            duplicates_skipped: 0,
+        }
+    }
+
+    /// Record `id`, returning false if it is already in the window
+    fn insert(&mut self, id: EventId) -> bool {
+        self.tick += 1;
+        let is_new = self.seen.insert(id, self.tick).is_none();
+        self.order.push_back((id, self.tick));
+        if !is_new {
+            self.duplicates_skipped += 1;
+        }
+
+        while self.seen.len() > self.capacity {
+            if let Some((old_id, tick)) = self.order.pop_front() {
+                if self.seen.get(&old_id) == Some(&tick) {
+                    self.seen.remove(&old_id);
+                }
+            }
+        }
+        // Repeated hits leave stale entries behind; drop them once they dominate
+        if self.order.len() > self.capacity.saturating_mul(2).max(16) {
+            let seen = &self.seen;
+            self.order.retain(|(id, tick)| seen.get(id) == Some(tick));
+        }
+
+        is_new
+    }
+
+    /// Whether `id` is in the window; a hit refreshes it and counts as a duplicate
+    fn check(&mut self, id: EventId) -> bool {
+        self.seen.contains_key(&id) && !self.insert(id)
+    }
+
+    fn clear(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+    }
+}
+
+/// Throughput and health of a projection since its processor was created
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProjectionStats {
+    /// Events the projection handled successfully
+    pub events_processed: u64,
+    /// Events the projection's handler failed on
+    pub errors: u64,
+    /// Events handled per second since the first event arrived
+    pub events_per_second: f64,
+    /// Share of handled events that failed, from 0 to 1
+    pub error_rate: f64,
+    /// Events between the last processed position and the latest position seen
+    pub lag: u64,
+    /// Global position of the last event processed
+    pub last_position: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct ProjectionCounters {
+    started: Option<Instant>,
+    processed: u64,
+    errors: u64,
+    head: u64,
+    last_position: Option<u64>,
+}
+
+impl ProjectionCounters {
+    fn stats(&self) -> ProjectionStats {
+        let elapsed = self.started.map_or(0.0, |started| started.elapsed().as_secs_f64());
+        let attempted = self.processed + self.errors;
+        ProjectionStats {
+            events_processed: self.processed,
+            errors: self.errors,
+            events_per_second: if elapsed > 0.0 { self.processed as f64 / elapsed } else { 0.0 },
+            error_rate: if attempted > 0 { self.errors as f64 / attempted as f64 } else { 0.0 },
+            lag: self.head.saturating_sub(self.last_position.unwrap_or(0)),
+            last_position: self.last_position,
+        }
+    }
+}
+
+/// Raised by a `LagWatchdog` when a projection's lag has kept growing for the
+/// whole stall window
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LagAlert {
+    pub projection_name: String,
+    /// Lag at the latest sample
+    pub lag: u64,
+    /// Lag when the current run of growth began
+    pub lag_at_start: u64,
+    /// How long the lag has grown without falling
+    pub stalled_for: Duration,
+}
+
+/// Called with each alert a `LagWatchdog` raises
+pub type LagAlertCallback = Arc<dyn Fn(&LagAlert) + Send + Sync>;
+
+#[derive(Debug)]
+struct LagTrend {
+    last_lag: u64,
+    /// When the current run of growth began, and the lag before it
+    growing_since: Option<(Instant, u64)>,
+    alert: Option<LagAlert>,
+}
+
+/// Detects projections whose lag keeps growing, a sign they are stuck or too
+/// slow for the write rate.
+///
+/// Feed it lag samples with `observe`, or attach it to a processor with
+/// `ProjectionProcessor::with_lag_watchdog`. Once a projection's lag has risen
+/// and not fallen for `stall_window`, every registered callback is called
+/// once and the projection is reported as stalled, which turns the
+/// watchdog's health check Degraded. Lag that holds steady does not end the
+/// run, since samples can arrive faster than new events; any fall in lag
+/// clears the stall and re-arms the alert.
+pub struct LagWatchdog {
+    stall_window: Duration,
+    trends: Mutex<HashMap<String, LagTrend>>,
+    callbacks: Mutex<Vec<LagAlertCallback>>,
+}
+
+impl LagWatchdog {
+    pub fn new(stall_window: Duration) -> Self {
+        Self {
+            stall_window,
+            trends: Mutex::new(HashMap::new()),
+            callbacks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn stall_window(&self) -> Duration {
+        self.stall_window
+    }
+
+    /// Call `callback` with every alert raised from now on
+    pub fn on_alert(&self, callback: impl Fn(&LagAlert) + Send + Sync + 'static) {
+        if let Ok(mut callbacks) = self.callbacks.lock() {
+            callbacks.push(Arc::new(callback));
+        }
+    }
+
+    /// Record `projection_name`'s current lag, returning the alert if this
+    /// sample completes a stall window
+    pub fn observe(&self, projection_name: &str, lag: u64) -> Option<LagAlert> {
+        let now = Instant::now();
+        let raised = {
+            let Ok(mut trends) = self.trends.lock() else {
+                return None;
+            };
+            let trend = trends.entry(projection_name.to_string()).or_insert(LagTrend {
+                last_lag: lag,
+                growing_since: None,
+                alert: None,
+            });
+            if lag < trend.last_lag {
+                trend.growing_since = None;
+                trend.alert = None;
+            } else if lag > trend.last_lag && trend.growing_since.is_none() {
+                trend.growing_since = Some((now, trend.last_lag));
+            }
+            trend.last_lag = lag;
+
+            let (since, lag_at_start) = trend.growing_since?;
+            let stalled_for = now.duration_since(since);
+            match trend.alert.as_mut() {
+                Some(alert) => {
+                    alert.lag = lag;
+                    alert.stalled_for = stalled_for;
+                    None
+                }
+                None if stalled_for >= self.stall_window => {
+                    let alert = LagAlert {
+                        projection_name: projection_name.to_string(),
+                        lag,
+                        lag_at_start,
+                        stalled_for,
+                    };
+                    trend.alert = Some(alert.clone());
+                    Some(alert)
+                }
+                None => None,
+            }
+        };
+
+        // Callbacks run without the watchdog's locks held, so they may query it
+        if let Some(alert) = &raised {
+            let callbacks = self.callbacks.lock().map(|c| c.clone()).unwrap_or_default();
+            for callback in callbacks {
+                callback(alert);
+            }
+        }
+        raised
+    }
+
+    /// Projections currently stalled, by name
+    pub fn stalled(&self) -> Vec<LagAlert> {
+        let Ok(trends) = self.trends.lock() else {
+            return Vec::new();
+        };
+        let mut stalled: Vec<LagAlert> = trends.values().filter_map(|trend| trend.alert.clone()).collect();
+        stalled.sort_by(|a, b| a.projection_name.cmp(&b.projection_name));
+        stalled
+    }
+
+    /// Degraded while any projection is stalled
+    #[cfg(feature = "observability")]
+    pub fn health_status(&self) -> HealthStatus {
+        if self.stalled().is_empty() {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Degraded
+        }
+    }
+}
+
+#[cfg(feature = "observability")]
+#[async_trait]
+impl HealthChecker for LagWatchdog {
+    fn name(&self) -> &str {
+        "projection_lag"
+    }
+
+    async fn check(&self) -> Result<HealthCheckResult> {
+        let stalled = self.stalled();
+        if stalled.is_empty() {
+            return Ok(HealthCheckResult::new(
+                self.name().to_string(),
+                HealthStatus::Healthy,
+                "No projection lag is growing".to_string(),
+            ));
+        }
+
+        let summary: Vec<String> = stalled
+            .iter()
+            .map(|alert| format!("{} (lag {})", alert.projection_name, alert.lag))
+            .collect();
+        let details = stalled
+            .iter()
+            .map(|alert| (alert.projection_name.clone(), serde_json::json!(alert.lag)))
+            .collect();
+        Ok(HealthCheckResult::new(
+            self.name().to_string(),
+            HealthStatus::Degraded,
+            format!("Lag has grown for over {:?}: {}", self.stall_window, summary.join(", ")),
+        )
+        .with_details(details))
+    }
+}
+
 /// Built-in processors
 /// Projection processor that updates read models
 pub struct ProjectionProcessor<P: Projection> {
     projection: Arc<P>,
+    dedup: Option<Mutex<DedupWindow>>,
+    counters: Mutex<ProjectionCounters>,
+    #[cfg(feature = "observability")]
+    metrics: Option<(String, Arc<MetricsCollector>)>,
+    lag_watchdog: Option<(String, Arc<LagWatchdog>)>,
 }
 
 impl<P: Projection> ProjectionProcessor<P> {
     pub fn new(projection: P) -> Self {
         Self {
             projection: Arc::new(projection),
+            dedup: None,
+            counters: Mutex::new(ProjectionCounters::default()),
+            #[cfg(feature = "observability")]
+            metrics: None,
+            lag_watchdog: None,
+        }
+    }
+
+    /// Export the projection's `ProjectionStats` to `collector` as gauges
+    /// labelled with `projection_name`, updated as each event is processed
+    #[cfg(feature = "observability")]
+    pub fn with_metrics(mut self, projection_name: impl Into<String>, collector: Arc<MetricsCollector>) -> Self {
+        self.metrics = Some((projection_name.into(), collector));
+        self
+    }
+
+    /// Report the projection's lag to `watchdog` under `projection_name` each
+    /// time it changes, so a stall raises an alert
+    pub fn with_lag_watchdog(mut self, projection_name: impl Into<String>, watchdog: Arc<LagWatchdog>) -> Self {
+        self.lag_watchdog = Some((projection_name.into(), watchdog));
+        self
+    }
+
+    /// Skip events whose ID was among the last `window_size` distinct IDs seen.
+    ///
+    /// Guards projections against at-least-once delivery, where the same event
+    /// can arrive more than once from a streamer. A size of zero disables it.
+    pub fn with_dedup_window(mut self, window_size: usize) -> Self {
+        self.dedup = (window_size > 0).then(|| Mutex::new(DedupWindow::new(window_size)));
+        self
+    }
+
+    pub fn projection(&self) -> &Arc<P> {
+        &self.projection
+    }
+
+    /// Number of duplicate events skipped by the de-duplication window
+    pub fn duplicates_skipped(&self) -> u64 {
+        self.dedup
+            .as_ref()
+            .and_then(|d| d.lock().ok().map(|w| w.duplicates_skipped))
+            .unwrap_or(0)
+    }
+
+    /// Throughput, error rate and lag of the projection so far
+    pub fn stats(&self) -> ProjectionStats {
+        self.counters.lock().map(|c| c.stats()).unwrap_or_default()
+    }
+
+    /// Report the latest global position in the store, so lag counts events
+    /// that have not reached the processor yet
+    pub fn observe_head(&self, head_position: u64) {
+        self.update_counters(|counters| counters.head = counters.head.max(head_position));
+    }
+
+    /// Count the outcome of handling the event at `global_position`
+    fn record_outcome(&self, global_position: u64, outcome: &Result<()>) {
+        self.update_counters(|counters| {
+            counters.started.get_or_insert_with(Instant::now);
+            counters.head = counters.head.max(global_position);
+            match outcome {
+                Ok(()) => {
+                    counters.processed += 1;
+                    counters.last_position = Some(global_position);
+                }
+                Err(_) => counters.errors += 1,
+            }
+        });
+    }
+
+    /// Move the position past an event the projection did not need to handle
+    fn record_skipped(&self, global_position: u64) {
+        self.update_counters(|counters| {
+            counters.head = counters.head.max(global_position);
+            counters.last_position = Some(global_position);
+        });
+    }
+
+    fn update_counters(&self, update: impl FnOnce(&mut ProjectionCounters)) {
+        let lag = {
+            let Ok(mut counters) = self.counters.lock() else {
+                return;
+            };
+            update(&mut counters);
+            #[cfg(feature = "observability")]
+            if let Some((name, collector)) = &self.metrics {
+                collector.record_projection_stats(name, &counters.stats());
+            }
+            counters.stats().lag
+        };
+        // Outside the counters lock, so alert callbacks can read `stats`
+        if let Some((name, watchdog)) = &self.lag_watchdog {
+            watchdog.observe(name, lag);
+        }
+    }
+
+    /// Handle `event`, counting the outcome
+    async fn handle(&self, event: &Event, global_position: u64) -> Result<()> {
+        let outcome = self.projection.handle_event(event).await;
+        self.record_outcome(global_position, &outcome);
+        outcome
+    }
+
+    /// Whether `event` was already handled within the dedup window
+    fn is_redelivery(&self, event: &Event) -> Result<bool> {
+        let Some(dedup) = &self.dedup else {
+            return Ok(false);
+        };
+        let mut window = dedup.lock()
+            .map_err(|_| EventualiError::Configuration("Failed to acquire dedup window lock".to_string()))?;
+        Ok(window.check(event.id))
+    }
+
+    /// Remember `event` as handled. Only called once handling succeeded, so a
+    /// redelivery of an event that failed is handled again.
+    fn record_delivery(&self, event: &Event) -> Result<()> {
+        if let Some(dedup) = &self.dedup {
+            dedup.lock()
+                .map_err(|_| EventualiError::Configuration("Failed to acquire dedup window lock".to_string()))?
+                .insert(event.id);
+        }
+        Ok(())
+    }
+}
+
+impl<P: Projection + Send + Sync> ProjectionProcessor<P> {
+    /// Persist the projection's current state at its last processed position.
+    ///
+    /// Returns `None` when the projection does not support snapshots or has not
+    /// processed any events yet.
+    pub async fn take_snapshot(
+        &self,
+        projection_name: &str,
+        snapshots: &(dyn ProjectionSnapshotStore + Send + Sync),
+    ) -> Result<Option<ProjectionSnapshot>> {
+        let Some(position) = self.projection.get_last_processed_position().await? else {
+            return Ok(None);
+        };
+        let Some(state_data) = self.projection.snapshot_state().await? else {
+            return Ok(None);
+        };
+
+        let snapshot = ProjectionSnapshot::new(projection_name.to_string(), position, state_data);
+        snapshots.save_snapshot(snapshot.clone()).await?;
+        Ok(Some(snapshot))
+    }
+
+    /// Rebuild the projection from `events`, which must be in global order.
+    ///
+    /// When a snapshot store is given and holds a snapshot for the projection, the
+    /// state is restored from it and only events past the snapshot position are
+    /// replayed; otherwise the projection is reset and every event is replayed.
+    /// Returns the number of events applied.
+    pub async fn rebuild(
+        &self,
+        projection_name: &str,
+        events: &[StreamEvent],
+        snapshots: Option<&(dyn ProjectionSnapshotStore + Send + Sync)>,
+    ) -> Result<u64> {
+        self.projection.reset().await?;
+        if let Some(dedup) = &self.dedup {
+            dedup.lock()
+                .map_err(|_| EventualiError::Configuration("Failed to acquire dedup window lock".to_string()))?
+                .clear();
+        }
+
+        let mut start_position = 0;
+        if let Some(store) = snapshots {
+            if let Some(snapshot) = store.load_latest_snapshot(projection_name).await? {
+                self.projection.restore_state(&snapshot.state_data).await?;
+                self.projection.set_last_processed_position(snapshot.position).await?;
+                start_position = snapshot.position;
+            }
+        }
+
+        let mut processed = 0;
+        for stream_event in events.iter().filter(|e| e.global_position > start_position) {
+            self.handle(&stream_event.event, stream_event.global_position).await?;
+            self.projection.set_last_processed_position(stream_event.global_position).await?;
+            processed += 1;
+        }
+
+        Ok(processed)
+    }
+}
+
+impl<P: Projection + Send + Sync> ProjectionProcessor<P> {
+    /// Replay events from `store` that the projection has not processed yet, up to
+    /// the live tail, reading `batch_size` events per query.
+    ///
+    /// Replay is paced to the subscription's `replay_rate_limit` so a large rebuild
+    /// does not starve the primary workload; once this returns the projection is
+    /// caught up and live events go through `process_event` without throttling.
+    /// Events outside the subscription's filters advance the position but are not
+    /// handled. Returns the number of events handled.
+    pub async fn catch_up(
+        &self,
+        store: &(dyn EventStore + Send + Sync),
+        subscription: &Subscription,
+        batch_size: usize,
+    ) -> Result<u64> {
+        let batch_size = batch_size.max(1);
+        let mut throttle = subscription.replay_rate_limit.map(ReplayThrottle::new);
+        let mut position = self.projection.get_last_processed_position().await?.unwrap_or(0);
+        let mut handled = 0;
+
+        loop {
+            let page = store.load_events_after_position(position, batch_size).await?;
+            if page.is_empty() {
+                return Ok(handled);
+            }
+
+            for (global_position, event) in page {
+                if let Some(throttle) = throttle.as_mut() {
+                    throttle.acquire().await;
+                }
+                if subscription.matches(&event) {
+                    self.handle(&event, global_position).await?;
+                    handled += 1;
+                } else {
+                    self.record_skipped(global_position);
+                }
+                self.projection.set_last_processed_position(global_position).await?;
+                position = global_position;
+            }
         }
     }
 }
@@ -139,7 +935,11 @@ impl<P: Projection> ProjectionProcessor<P> {
 #[async_trait]
 impl<P: Projection + Send + Sync> EventStreamProcessor for ProjectionProcessor<P> {
     async fn process_event(&self, event: &StreamEvent) -> Result<()> {
-        self.projection.handle_event(&event.event).await
+        if self.is_redelivery(&event.event)? {
+            return Ok(());
+        }
+        self.handle(&event.event, event.global_position).await?;
+        self.record_delivery(&event.event)
     }
 }
 
@@ -150,31 +950,443 @@ pub trait Projection {
     async fn reset(&self) -> Result<()>;
     async fn get_last_processed_position(&self) -> Result<Option<u64>>;
     async fn set_last_processed_position(&self, position: u64) -> Result<()>;
+
+    /// Serialize the read-model state for a projection snapshot.
+    ///
+    /// Returning `None` (the default) opts the projection out of snapshotting.
+    async fn snapshot_state(&self) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    /// Restore read-model state previously produced by `snapshot_state`.
+    async fn restore_state(&self, _state: &[u8]) -> Result<()> {
+        Err(EventualiError::InvalidState(
+            "Projection does not support restoring from snapshots".to_string(),
+        ))
+    }
+}
+
+/// Metadata header naming the projection that derived an event
+pub const DERIVED_BY_HEADER: &str = "derived_by";
+/// Metadata header naming the rule that derived an event
+pub const DERIVATION_RULE_HEADER: &str = "derivation_rule";
+
+/// An event a derivation rule asks to emit
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedEvent {
+    pub event_type: String,
+    pub data: serde_json::Value,
+}
+
+impl DerivedEvent {
+    pub fn new(event_type: impl Into<String>, data: serde_json::Value) -> Self {
+        Self {
+            event_type: event_type.into(),
+            data,
+        }
+    }
+}
+
+/// Folds an event into an aggregate's computed state
+pub type StateFold<S> = Box<dyn Fn(&mut S, &Event) -> Result<()> + Send + Sync>;
+
+/// Inspects an aggregate's state before and after an event and returns the
+/// event to derive, if the rule fires
+pub type DerivationRule<S> = Box<dyn Fn(&S, &S, &Event) -> Result<Option<DerivedEvent>> + Send + Sync>;
+
+/// Computed state of one source aggregate
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct DerivedAggregate<S> {
+    state: S,
+    /// Version of the last event derived from this aggregate
+    derived_version: i64,
+}
+
+struct DerivingState<S> {
+    aggregates: HashMap<String, DerivedAggregate<S>>,
+    position: Option<u64>,
+}
+
+/// Projection that folds events into per-aggregate state and saves new events
+/// to a store when a rule fires on a state change.
+///
+/// Rules see the state before and after each event, so a rule that compares
+/// the two (a balance going from above a threshold to below it) fires once per
+/// crossing rather than on every event past it.
+///
+/// Derived events from aggregate `id` go to the stream `"{name}-{id}"` of
+/// aggregate type `name`, caused by the source event and tagged with the
+/// `DERIVED_BY_HEADER` and `DERIVATION_RULE_HEADER` headers. Events carrying
+/// `DERIVED_BY_HEADER` are skipped entirely, so derived events are never
+/// derived from again, whichever projection wrote them.
+///
+/// Derived versions count up from the state, not the store, so replaying the
+/// same source events proposes the same versions; derivations the store
+/// already holds are rejected as concurrency conflicts and skipped. Rebuilding
+/// the projection therefore does not emit duplicates.
+pub struct DerivingProjection<S> {
+    name: String,
+    store: Arc<dyn EventStore + Send + Sync>,
+    fold: StateFold<S>,
+    rules: Vec<(String, DerivationRule<S>)>,
+    state: tokio::sync::Mutex<DerivingState<S>>,
+    emitted: std::sync::atomic::AtomicU64,
+}
+
+impl<S> DerivingProjection<S>
+where
+    S: Clone + Default + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    /// A projection named `name` that folds events with `fold` and saves
+    /// derived events to `store`
+    pub fn new(
+        name: impl Into<String>,
+        store: Arc<dyn EventStore + Send + Sync>,
+        fold: impl Fn(&mut S, &Event) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            store,
+            fold: Box::new(fold),
+            rules: Vec::new(),
+            state: tokio::sync::Mutex::new(DerivingState {
+                aggregates: HashMap::new(),
+                position: None,
+            }),
+            emitted: std::sync::atomic::AtomicU64::new(0),
+        }
+    }
+
+    /// Add a rule, checked in the order rules were added
+    pub fn with_rule(
+        mut self,
+        name: impl Into<String>,
+        rule: impl Fn(&S, &S, &Event) -> Result<Option<DerivedEvent>> + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push((name.into(), Box::new(rule)));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Stream that events derived from `aggregate_id` are saved to
+    pub fn derived_stream_id(&self, aggregate_id: &str) -> String {
+        format!("{}-{}", self.name, aggregate_id)
+    }
+
+    /// Computed state of `aggregate_id`, if any of its events were handled
+    pub async fn state_of(&self, aggregate_id: &str) -> Option<S> {
+        self.state.lock().await.aggregates.get(aggregate_id).map(|a| a.state.clone())
+    }
+
+    /// Number of derived events saved by this projection
+    pub fn emitted_count(&self) -> u64 {
+        self.emitted.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Whether the store already holds `derived`, saved from `source` by the
+    /// same rules, rather than other events at those versions
+    async fn already_derived(&self, source: &Event, derived: &[Event]) -> Result<bool> {
+        let stream_id = self.derived_stream_id(&source.aggregate_id);
+        let first_version = derived.iter().map(|e| e.aggregate_version).min().unwrap_or(1);
+        let stored = self.store.load_events(&stream_id, Some(first_version - 1)).await?;
+        Ok(derived.iter().all(|proposed| {
+            stored.iter().any(|existing| {
+                existing.aggregate_version == proposed.aggregate_version
+                    && existing.metadata.causation_id == Some(source.id)
+                    && existing.metadata.headers.get(DERIVATION_RULE_HEADER)
+                        == proposed.metadata.headers.get(DERIVATION_RULE_HEADER)
+            })
+        }))
+    }
+
+    fn derived_event(
+        &self,
+        source: &Event,
+        rule_name: &str,
+        derived: DerivedEvent,
+        version: i64,
+    ) -> Event {
+        let mut event = Event::new(
+            self.derived_stream_id(&source.aggregate_id),
+            self.name.clone(),
+            derived.event_type,
+            1,
+            version,
+            crate::EventData::Json(derived.data),
+        );
+        event.metadata.causation_id = Some(source.id);
+        event.metadata.correlation_id = Some(source.metadata.correlation_id.unwrap_or(source.id));
+        event.metadata.user_id = source.metadata.user_id.clone();
+        event.metadata.headers.insert(DERIVED_BY_HEADER.to_string(), self.name.clone());
+        event.metadata.headers.insert(DERIVATION_RULE_HEADER.to_string(), rule_name.to_string());
+        event
+    }
+}
+
+#[async_trait]
+impl<S> Projection for DerivingProjection<S>
+where
+    S: Clone + Default + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    async fn handle_event(&self, event: &Event) -> Result<()> {
+        if event.metadata.headers.contains_key(DERIVED_BY_HEADER) {
+            return Ok(());
+        }
+
+        let mut state = self.state.lock().await;
+        let before = state.aggregates.get(&event.aggregate_id).cloned().unwrap_or_default();
+        let mut after = before.clone();
+        (self.fold)(&mut after.state, event)?;
+
+        let mut derived = Vec::new();
+        for (rule_name, rule) in &self.rules {
+            if let Some(output) = rule(&before.state, &after.state, event)? {
+                after.derived_version += 1;
+                derived.push(self.derived_event(event, rule_name, output, after.derived_version));
+            }
+        }
+
+        if !derived.is_empty() {
+            let count = derived.len() as u64;
+            match self.store.save_events(derived.clone()).await {
+                Ok(()) => {
+                    self.emitted.fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+                }
+                // Already derived on an earlier pass over this event
+                Err(EventualiError::OptimisticConcurrency { .. }) if self.already_derived(event, &derived).await? => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        state.aggregates.insert(event.aggregate_id.clone(), after);
+        Ok(())
+    }
+
+    async fn reset(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.aggregates.clear();
+        state.position = None;
+        Ok(())
+    }
+
+    async fn get_last_processed_position(&self) -> Result<Option<u64>> {
+        Ok(self.state.lock().await.position)
+    }
+
+    async fn set_last_processed_position(&self, position: u64) -> Result<()> {
+        self.state.lock().await.position = Some(position);
+        Ok(())
+    }
+
+    async fn snapshot_state(&self) -> Result<Option<Vec<u8>>> {
+        Ok(Some(serde_json::to_vec(&self.state.lock().await.aggregates)?))
+    }
+
+    async fn restore_state(&self, state: &[u8]) -> Result<()> {
+        self.state.lock().await.aggregates = serde_json::from_slice(state)?;
+        Ok(())
+    }
+}
+
+/// Applies one event type to a projection's state
+pub type EventTypeHandler<S> = Box<dyn Fn(&mut S, &Event) -> Result<()> + Send + Sync>;
+
+struct TypedState<S> {
+    state: S,
+    position: Option<u64>,
+}
+
+/// Projection that dispatches each event to the handler registered for its
+/// `event_type`, so handlers never match on the type themselves.
+///
+/// Events of types without a handler are skipped; `event_types` lists the
+/// types the projection covers.
+pub struct TypedProjection<S> {
+    handlers: HashMap<String, EventTypeHandler<S>>,
+    state: tokio::sync::Mutex<TypedState<S>>,
+}
+
+impl<S> TypedProjection<S>
+where
+    S: Clone + Default + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+            state: tokio::sync::Mutex::new(TypedState {
+                state: S::default(),
+                position: None,
+            }),
+        }
+    }
+
+    /// Handle events of `event_type` with `handler`, replacing any handler
+    /// already registered for it
+    pub fn on(
+        mut self,
+        event_type: impl Into<String>,
+        handler: impl Fn(&mut S, &Event) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
+        self.handlers.insert(event_type.into(), Box::new(handler));
+        self
+    }
+
+    /// Whether events of `event_type` have a handler
+    pub fn handles(&self, event_type: &str) -> bool {
+        self.handlers.contains_key(event_type)
+    }
+
+    /// Event types with a handler, sorted
+    pub fn event_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.handlers.keys().map(String::as_str).collect();
+        types.sort_unstable();
+        types
+    }
+
+    /// Current read-model state
+    pub async fn state(&self) -> S {
+        self.state.lock().await.state.clone()
+    }
 }
 
-/// Saga processor for long-running workflows
+impl<S> Default for TypedProjection<S>
+where
+    S: Clone + Default + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<S> Projection for TypedProjection<S>
+where
+    S: Clone + Default + serde::Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    async fn handle_event(&self, event: &Event) -> Result<()> {
+        let Some(handler) = self.handlers.get(&event.event_type) else {
+            return Ok(());
+        };
+        handler(&mut self.state.lock().await.state, event)
+    }
+
+    async fn reset(&self) -> Result<()> {
+        let mut state = self.state.lock().await;
+        state.state = S::default();
+        state.position = None;
+        Ok(())
+    }
+
+    async fn get_last_processed_position(&self) -> Result<Option<u64>> {
+        Ok(self.state.lock().await.position)
+    }
+
+    async fn set_last_processed_position(&self, position: u64) -> Result<()> {
+        self.state.lock().await.position = Some(position);
+        Ok(())
+    }
+
+    async fn snapshot_state(&self) -> Result<Option<Vec<u8>>> {
+        Ok(Some(serde_json::to_vec(&self.state.lock().await.state)?))
+    }
+
+    async fn restore_state(&self, state: &[u8]) -> Result<()> {
+        self.state.lock().await.state = serde_json::from_slice(state)?;
+        Ok(())
+    }
+}
+
+/// Events read per query when a saga fills in positions it was not delivered
+const SAGA_FILL_BATCH: usize = 500;
+
+/// Saga processor for long-running workflows.
+///
+/// Events are dispatched one at a time in strict global-position order, so a
+/// saga coordinating several aggregates sees their events in the order they
+/// were committed, whatever order the stream delivers them in.
 pub struct SagaProcessor {
     saga_handlers: HashMap<String, Box<dyn SagaHandler + Send + Sync>>,
+    /// Store the events come from, which confirms which positions exist
+    position_source: Arc<dyn EventStore + Send + Sync>,
+    /// Every event up to this position has been dispatched
+    dispatched: tokio::sync::Mutex<u64>,
 }
 
 impl SagaProcessor {
-    pub fn new() -> Self {
+    /// Saga over events from `store`, which is the record of which
+    /// positions exist.
+    ///
+    /// An event delivered ahead of lower positions first dispatches those
+    /// positions' events, read from `store`, and positions delivered again
+    /// later are skipped. Positions no event holds, left by rolled-back
+    /// inserts or deleted events, are passed over, so nothing is buffered
+    /// waiting for them. A new processor starts at the beginning of the
+    /// store; use `starting_after` to start later.
+    pub fn new(store: Arc<dyn EventStore + Send + Sync>) -> Self {
         Self {
             saga_handlers: HashMap::new(),
+            position_source: store,
+            dispatched: tokio::sync::Mutex::new(0),
         }
     }
 
+    /// Treat every position up to `position` as dispatched, for a saga that
+    /// resumes from a checkpoint or subscribes from a later position
+    pub fn starting_after(mut self, position: u64) -> Self {
+        *self.dispatched.get_mut() = position;
+        self
+    }
+
     pub fn register_handler<H: SagaHandler + Send + Sync + 'static>(&mut self, event_type: String, handler: H) {
         self.saga_handlers.insert(event_type, Box::new(handler));
     }
+
+    /// Position up to which every event has been dispatched
+    pub async fn last_dispatched_position(&self) -> u64 {
+        *self.dispatched.lock().await
+    }
+
+    async fn dispatch(&self, event: &Event) -> Result<()> {
+        if let Some(handler) = self.saga_handlers.get(&event.event_type) {
+            handler.handle_event(event).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl EventStreamProcessor for SagaProcessor {
+    /// Dispatching `event` may first dispatch earlier events read from the
+    /// store, and an error from any of them is returned here. Dispatch stops
+    /// at the failed event, which is read from the store again the next time
+    /// an event arrives.
     async fn process_event(&self, event: &StreamEvent) -> Result<()> {
-        if let Some(handler) = self.saga_handlers.get(&event.event.event_type) {
-            handler.handle_event(&event.event).await?;
+        let store = &self.position_source;
+        let mut dispatched = self.dispatched.lock().await;
+        if event.global_position <= *dispatched {
+            return Ok(());
+        }
+        // Stores assign positions in commit order, so every event below a
+        // delivered one is already readable and any position missing from
+        // the store will never be filled
+        'fill: while *dispatched + 1 < event.global_position {
+            let page = store.load_events_after_position(*dispatched, SAGA_FILL_BATCH).await?;
+            if page.is_empty() {
+                break;
+            }
+            for (position, stored) in page {
+                if position >= event.global_position {
+                    break 'fill;
+                }
+                self.dispatch(&stored).await?;
+                *dispatched = position;
+            }
         }
+        self.dispatch(&event.event).await?;
+        *dispatched = event.global_position;
         Ok(())
     }
 }
@@ -198,6 +1410,7 @@ impl SubscriptionBuilder {
                 aggregate_type_filter: None,
                 event_type_filter: None,
                 from_timestamp: None,
+                replay_rate_limit: None,
             },
         }
     }
@@ -222,6 +1435,12 @@ impl SubscriptionBuilder {
         self
     }
 
+    /// Cap catch-up replay at `events_per_second`; zero leaves replay unthrottled
+    pub fn with_replay_rate_limit(mut self, events_per_second: u32) -> Self {
+        self.subscription.replay_rate_limit = (events_per_second > 0).then_some(events_per_second);
+        self
+    }
+
     pub fn build(self) -> Subscription {
         self.subscription
     }
@@ -232,9 +1451,3 @@ impl Default for SubscriptionBuilder {
         Self::new()
     }
 }
-
-impl Default for SagaProcessor {
-    fn default() -> Self {
-        Self::new()
-    }
-}
\ No newline at end of file
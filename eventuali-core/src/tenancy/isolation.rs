@@ -1,14 +1,38 @@
 use std::sync::{Arc, RwLock};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use crate::event::Event;
+use crate::event::{Event, IdAllocator};
 use crate::aggregate::{AggregateId, AggregateVersion};
-use crate::store::EventStore;
+use crate::store::{DeleteMode, EventStore, LenientLoad, StoreStats};
+use crate::store::traits::StoreStatsAccumulator;
 use crate::error::{EventualiError, Result};
 use super::tenant::{TenantId, TenantError};
 
+/// Page size used when scanning a tenant's events for type discovery and stats
+pub(crate) const TENANT_SCAN_BATCH: usize = 1000;
+
+/// Visit every event whose aggregate ID starts with the tenant `prefix`,
+/// loading the global log one page at a time through `load_page`
+pub(crate) async fn for_each_tenant_event<F, Fut>(prefix: &str, mut load_page: F, mut visit: impl FnMut(Event)) -> Result<()>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<Vec<(u64, Event)>>>,
+{
+    let mut after_global = 0;
+    loop {
+        let page = load_page(after_global).await?;
+        let Some(&(last_position, _)) = page.last() else {
+            return Ok(());
+        };
+        page.into_iter()
+            .filter(|(_, event)| event.aggregate_id.starts_with(prefix))
+            .for_each(|(_, event)| visit(event));
+        after_global = last_position;
+    }
+}
+
 /// Tenant isolation enforcement mechanism
 pub struct TenantIsolation {
     isolation_policies: Arc<RwLock<HashMap<TenantId, IsolationPolicy>>>,
@@ -36,6 +60,11 @@ impl TenantIsolation {
         Ok(())
     }
     
+    /// Drop a tenant's isolation policy, so its operations are rejected
+    pub fn unregister_tenant(&self, tenant_id: &TenantId) {
+        self.isolation_policies.write().unwrap().remove(tenant_id);
+    }
+    
     /// Validate that an operation is allowed for the tenant
     pub fn validate_operation(&self, tenant_id: &TenantId, operation: &TenantOperation) -> Result<()> {
         let start_time = std::time::Instant::now();
@@ -70,6 +99,8 @@ impl TenantIsolation {
 pub enum TenantOperation {
     CreateEvent { aggregate_id: AggregateId },
     ReadEvents { aggregate_id: AggregateId },
+    /// Delete or tombstone an aggregate's events
+    EraseEvents { aggregate_id: AggregateId },
     CreateProjection { name: String },
     StreamEvents { from_timestamp: Option<DateTime<Utc>> },
 }
@@ -119,6 +150,13 @@ impl IsolationPolicy {
                     )));
                 }
             },
+            TenantOperation::EraseEvents { aggregate_id } => {
+                if self.enforce_namespace && !self.validate_aggregate_namespace(aggregate_id) {
+                    return Err(EventualiError::from(TenantError::IsolationViolation(
+                        "Aggregate ID does not match tenant namespace".to_string()
+                    )));
+                }
+            },
             TenantOperation::CreateProjection { .. } => {
                 // Additional validation for projections
             },
@@ -203,6 +241,7 @@ pub struct IsolatedEventStore {
     tenant_id: TenantId,
     inner_store: Arc<dyn EventStore + Send + Sync>,
     isolation: Arc<TenantIsolation>,
+    id_allocator: Option<Arc<dyn IdAllocator>>,
 }
 
 impl IsolatedEventStore {
@@ -215,6 +254,7 @@ impl IsolatedEventStore {
             tenant_id,
             inner_store,
             isolation,
+            id_allocator: None,
         }
     }
     
@@ -231,7 +271,11 @@ impl IsolatedEventStore {
 
 #[async_trait]
 impl EventStore for IsolatedEventStore {
-    async fn save_events(&self, events: Vec<Event>) -> Result<()> {
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<()> {
         // For each event, validate operation and transform aggregate IDs
         let mut scoped_events = Vec::new();
         
@@ -247,7 +291,11 @@ impl EventStore for IsolatedEventStore {
         }
         
         // Delegate to inner store
-        self.inner_store.save_events(scoped_events).await
+        self.inner_store.save_events_expecting(scoped_events, expected_version).await
+    }
+    
+    async fn sync_to_disk(&self) -> Result<()> {
+        self.inner_store.sync_to_disk().await
     }
     
     async fn load_events(&self, aggregate_id: &AggregateId, from_version: Option<AggregateVersion>) -> Result<Vec<Event>> {
@@ -270,6 +318,39 @@ impl EventStore for IsolatedEventStore {
         Ok(events)
     }
     
+    async fn load_events_lenient(&self, aggregate_id: &AggregateId, from_version: Option<AggregateVersion>) -> Result<LenientLoad> {
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents { 
+            aggregate_id: aggregate_id.clone() 
+        })?;
+        
+        let scoped_aggregate_id = self.tenant_scoped_aggregate_id(aggregate_id);
+        let mut load = self.inner_store.load_events_lenient(&scoped_aggregate_id, from_version).await?;
+        
+        for event in &mut load.events {
+            event.aggregate_id = aggregate_id.clone();
+        }
+        for row in &mut load.quarantined {
+            row.aggregate_id = aggregate_id.clone();
+        }
+        
+        Ok(load)
+    }
+    
+    async fn load_events_at_version(&self, aggregate_id: &AggregateId, version: AggregateVersion) -> Result<Vec<Event>> {
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents { 
+            aggregate_id: aggregate_id.clone() 
+        })?;
+        
+        let scoped_aggregate_id = self.tenant_scoped_aggregate_id(aggregate_id);
+        let mut events = self.inner_store.load_events_at_version(&scoped_aggregate_id, version).await?;
+        
+        for event in &mut events {
+            event.aggregate_id = aggregate_id.clone();
+        }
+        
+        Ok(events)
+    }
+    
     async fn load_events_by_type(&self, aggregate_type: &str, from_version: Option<AggregateVersion>) -> Result<Vec<Event>> {
         // Create a tenant-scoped aggregate type
         let scoped_aggregate_type = format!("{}:{}", self.tenant_id.db_prefix(), aggregate_type);
@@ -288,6 +369,51 @@ impl EventStore for IsolatedEventStore {
         Ok(events)
     }
     
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        // Correlation IDs are not tenant-scoped; the query keeps only this
+        // tenant's events, so the limit counts none of anyone else's
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let events = self
+            .inner_store
+            .get_events_by_correlation_id_with_prefix(correlation_id, &format!("{prefix}{aggregate_id_prefix}"), limit)
+            .await?;
+        
+        Ok(events
+            .into_iter()
+            .filter_map(|mut event| {
+                event.aggregate_id = event.aggregate_id.strip_prefix(&prefix)?.to_string();
+                Some(event)
+            })
+            .collect())
+    }
+    
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        // User IDs are not tenant-scoped either
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let events = self
+            .inner_store
+            .get_events_by_user_id_with_prefix(user_id, &format!("{prefix}{aggregate_id_prefix}"), limit)
+            .await?;
+        
+        Ok(events
+            .into_iter()
+            .filter_map(|mut event| {
+                event.aggregate_id = event.aggregate_id.strip_prefix(&prefix)?.to_string();
+                Some(event)
+            })
+            .collect())
+    }
+    
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
         // Validate operation (as read)
         self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents { 
@@ -301,6 +427,82 @@ impl EventStore for IsolatedEventStore {
         self.inner_store.get_aggregate_version(&scoped_aggregate_id).await
     }
     
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut after_global = after_global;
+        
+        // Keep paging past other tenants' events so an empty page always means the end
+        loop {
+            let page = self.inner_store.load_events_after_position(after_global, limit).await?;
+            let Some(&(last_position, _)) = page.last() else {
+                return Ok(Vec::new());
+            };
+            
+            let events: Vec<(u64, Event)> = page
+                .into_iter()
+                .filter_map(|(position, mut event)| {
+                    let unscoped = event.aggregate_id.strip_prefix(&prefix)?.to_string();
+                    event.aggregate_id = unscoped;
+                    Some((position, event))
+                })
+                .collect();
+            
+            if !events.is_empty() {
+                return Ok(events);
+            }
+            after_global = last_position;
+        }
+    }
+    
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        // Aggregate types are not tenant-scoped, so collect them from this tenant's events
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut types = BTreeSet::new();
+        for_each_tenant_event(
+            &prefix,
+            |after_global| self.inner_store.load_events_after_position(after_global, TENANT_SCAN_BATCH),
+            |event| {
+                types.insert(event.aggregate_type);
+            },
+        )
+        .await?;
+        Ok(types.into_iter().collect())
+    }
+
+    async fn delete_aggregate_events_with_mode(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<u64> {
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::EraseEvents {
+            aggregate_id: aggregate_id.clone()
+        })?;
+        
+        let scoped_aggregate_id = self.tenant_scoped_aggregate_id(aggregate_id);
+        self.inner_store.delete_aggregate_events_with_mode(&scoped_aggregate_id, mode).await
+    }
+    
+    async fn stats(&self) -> Result<StoreStats> {
+        // Only this tenant's events count, so scan them rather than asking the backend
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut stats = StoreStatsAccumulator::default();
+        for_each_tenant_event(
+            &prefix,
+            |after_global| self.inner_store.load_events_after_position(after_global, TENANT_SCAN_BATCH),
+            |event| stats.add(&event),
+        )
+        .await?;
+        Ok(stats.finish())
+    }
+    
+    fn new_event_id(&self) -> Result<crate::EventId> {
+        match &self.id_allocator {
+            Some(allocator) => allocator.allocate(),
+            None => self.inner_store.new_event_id(),
+        }
+    }
+    
+    fn set_id_allocator(&mut self, allocator: Arc<dyn IdAllocator>) {
+        // The inner store is shared, so the allocator applies to this view only
+        self.id_allocator = Some(allocator);
+    }
+    
     fn set_event_streamer(&mut self, _streamer: Arc<dyn crate::streaming::EventStreamer + Send + Sync>) {
         // This would need to be handled differently as we have a reference to the inner store
         // For now, we'll need to assume the inner store is mutable or use interior mutability
@@ -379,4 +581,42 @@ mod tests {
         assert!(metrics.is_performance_target_met());
         assert!(metrics.isolation_success_rate() > 99.0);
     }
+    
+    #[tokio::test]
+    async fn test_user_lookup_limit_counts_only_the_tenants_own_events() {
+        let inner: Arc<dyn EventStore + Send + Sync> = Arc::from(
+            crate::store::create_event_store(crate::store::EventStoreConfig::sqlite(":memory:".to_string()))
+                .await
+                .unwrap(),
+        );
+        let isolation = Arc::new(TenantIsolation::new());
+        let tenant_store = |name: &str| {
+            let tenant_id = TenantId::new(name.to_string()).unwrap();
+            isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+            IsolatedEventStore::new(tenant_id, inner.clone(), isolation.clone())
+        };
+        let busy = tenant_store("busy-tenant");
+        let quiet = tenant_store("quiet-tenant");
+        
+        let by_alice = |aggregate_id: String| {
+            Event::new(
+                aggregate_id,
+                "Document".to_string(),
+                "DocumentEdited".to_string(),
+                1,
+                1,
+                crate::EventData::Json(serde_json::json!({})),
+            )
+            .with_metadata(crate::EventMetadata { user_id: Some("alice".to_string()), ..Default::default() })
+        };
+        
+        // The other tenant's events come first in global order
+        busy.save_events((0..5).map(|i| by_alice(format!("doc-{i}"))).collect()).await.unwrap();
+        quiet.save_events((0..3).map(|i| by_alice(format!("doc-{i}"))).collect()).await.unwrap();
+        
+        let page = quiet.get_events_by_user_id("alice", 2).await.unwrap();
+        let ids: Vec<&str> = page.iter().map(|event| event.aggregate_id.as_str()).collect();
+        assert_eq!(ids, ["doc-0", "doc-1"]);
+        assert_eq!(busy.get_events_by_user_id("alice", 10).await.unwrap().len(), 5);
+    }
 }
\ No newline at end of file
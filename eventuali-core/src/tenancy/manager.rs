@@ -1,19 +1,25 @@
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::collections::HashMap;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
 use super::tenant::{TenantId, TenantInfo, TenantConfig, TenantStatus, TenantError};
 use super::isolation::{TenantIsolation, IsolationPolicy};
-use super::quota::{TenantQuota, ResourceUsage, ResourceType};
+use super::quota::{TenantQuota, ResourceUsage, ResourceType, QuotaCheckResult, QuotaTier};
+use super::configuration::{TenantConfigurationManager, ConfigurationValue, ConfigurationSchema};
 use crate::error::{EventualiError, Result};
+use crate::security::{RbacManager, Role};
 
 /// Central tenant management system
 pub struct TenantManager {
     tenants: Arc<RwLock<HashMap<TenantId, TenantInfo>>>,
     quotas: Arc<RwLock<HashMap<TenantId, Arc<TenantQuota>>>>,
+    configurations: Arc<RwLock<HashMap<TenantId, Arc<TenantConfigurationManager>>>>,
     isolation: Arc<TenantIsolation>,
     registry: Arc<RwLock<TenantRegistry>>,
+    /// Where onboarding registers tenants' default roles
+    rbac: Arc<Mutex<RbacManager>>,
+    tenant_roles: Arc<RwLock<HashMap<TenantId, Vec<String>>>>,
 }
 
 impl Default for TenantManager {
@@ -27,13 +33,99 @@ impl TenantManager {
         Self {
             tenants: Arc::new(RwLock::new(HashMap::new())),
             quotas: Arc::new(RwLock::new(HashMap::new())),
+            configurations: Arc::new(RwLock::new(HashMap::new())),
             isolation: Arc::new(TenantIsolation::new()),
             registry: Arc::new(RwLock::new(TenantRegistry::new())),
+            rbac: Arc::new(Mutex::new(RbacManager::new())),
+            tenant_roles: Arc::new(RwLock::new(HashMap::new())),
         }
     }
     
+    /// Register onboarded tenants' default roles with a shared RBAC manager
+    /// instead of this manager's own
+    pub fn with_rbac(mut self, rbac: Arc<Mutex<RbacManager>>) -> Self {
+        self.rbac = rbac;
+        self
+    }
+    
+    /// The RBAC manager holding tenants' default roles
+    pub fn rbac(&self) -> Arc<Mutex<RbacManager>> {
+        self.rbac.clone()
+    }
+    
     /// Create a new tenant
     pub async fn create_tenant(&self, tenant_id: TenantId, name: String, config: Option<TenantConfig>) -> Result<TenantInfo> {
+        self.register_tenant(tenant_id, name, config, QuotaTier::Standard)
+    }
+    
+    /// Create a tenant with its quota tier, default configuration and default
+    /// roles as one operation.
+    ///
+    /// If seeding any configuration value or role fails, everything created
+    /// so far is removed again and the error is returned, so a failed signup
+    /// can simply be retried.
+    pub async fn onboard_tenant(&self, spec: TenantOnboardingSpec) -> Result<TenantInfo> {
+        let TenantOnboardingSpec { tenant_id, name, config, quota_tier, configuration, roles } = spec;
+        let tenant_info = self.register_tenant(tenant_id.clone(), name, Some(config), quota_tier)?;
+        
+        let mut role_ids = Vec::new();
+        match self.seed_tenant(&tenant_id, configuration, roles, &mut role_ids) {
+            Ok(()) => {
+                self.tenant_roles.write().unwrap().insert(tenant_id, role_ids);
+                Ok(tenant_info)
+            }
+            Err(e) => {
+                let mut rbac = self.rbac.lock().unwrap_or_else(|e| e.into_inner());
+                for role_id in &role_ids {
+                    let _ = rbac.delete_role(role_id);
+                }
+                drop(rbac);
+                self.discard_tenant(&tenant_id);
+                Err(e)
+            }
+        }
+    }
+    
+    fn seed_tenant(
+        &self,
+        tenant_id: &TenantId,
+        configuration: Vec<OnboardingConfigurationValue>,
+        roles: Vec<OnboardingRole>,
+        role_ids: &mut Vec<String>,
+    ) -> Result<()> {
+        let configuration_manager = self.get_tenant_configuration(tenant_id)?;
+        for entry in configuration {
+            configuration_manager.set_configuration(
+                entry.key,
+                entry.value,
+                entry.schema,
+                None,
+                "onboarding".to_string(),
+                "Tenant onboarding defaults".to_string(),
+            )?;
+        }
+        
+        let mut rbac = self.rbac.lock().unwrap_or_else(|e| e.into_inner());
+        for role in roles {
+            let role_id = rbac.create_role(format!("{tenant_id}:{}", role.name), role.description)?;
+            role_ids.push(role_id.clone());
+            for permission in &role.permissions {
+                rbac.assign_permission_to_role(&role_id, permission)?;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Remove every record of a tenant created by `register_tenant`
+    fn discard_tenant(&self, tenant_id: &TenantId) {
+        self.isolation.unregister_tenant(tenant_id);
+        self.tenants.write().unwrap().remove(tenant_id);
+        self.quotas.write().unwrap().remove(tenant_id);
+        self.configurations.write().unwrap().remove(tenant_id);
+        self.registry.write().unwrap().unregister_tenant(tenant_id);
+    }
+    
+    fn register_tenant(&self, tenant_id: TenantId, name: String, config: Option<TenantConfig>, tier: QuotaTier) -> Result<TenantInfo> {
         // Check if tenant already exists
         {
             let tenants = self.tenants.read().unwrap();
@@ -49,7 +141,7 @@ impl TenantManager {
         }
         
         // Set up quota management
-        let quota = Arc::new(TenantQuota::new(tenant_id.clone(), tenant_info.config.resource_limits.clone()));
+        let quota = Arc::new(TenantQuota::with_tier(tenant_id.clone(), tenant_info.config.resource_limits.clone(), tier));
         
         // Set up isolation policy
         let isolation_policy = match tenant_info.config.isolation_level {
@@ -68,6 +160,10 @@ impl TenantManager {
             
             tenants.insert(tenant_id.clone(), tenant_info.clone());
             quotas.insert(tenant_id.clone(), quota);
+            self.configurations.write().unwrap().insert(
+                tenant_id.clone(),
+                Arc::new(TenantConfigurationManager::new(tenant_id.clone())),
+            );
             registry.register_tenant(tenant_id.clone())?;
         }
         
@@ -150,6 +246,32 @@ impl TenantManager {
         Ok(quota.get_legacy_usage())
     }
     
+    /// The quota enforced for a tenant, shared with its tenant-aware storage
+    pub fn get_tenant_quota(&self, tenant_id: &TenantId) -> Result<Arc<TenantQuota>> {
+        let quotas = self.quotas.read().unwrap();
+        quotas.get(tenant_id)
+            .cloned()
+            .ok_or_else(|| EventualiError::from(TenantError::TenantNotFound(tenant_id.clone())))
+    }
+    
+    /// A tenant's configuration values
+    pub fn get_tenant_configuration(&self, tenant_id: &TenantId) -> Result<Arc<TenantConfigurationManager>> {
+        let configurations = self.configurations.read().unwrap();
+        configurations.get(tenant_id)
+            .cloned()
+            .ok_or_else(|| EventualiError::from(TenantError::TenantNotFound(tenant_id.clone())))
+    }
+    
+    /// Default roles registered when the tenant was onboarded
+    pub fn get_tenant_roles(&self, tenant_id: &TenantId) -> Vec<Role> {
+        let tenant_roles = self.tenant_roles.read().unwrap();
+        let Some(role_ids) = tenant_roles.get(tenant_id) else {
+            return Vec::new();
+        };
+        let rbac = self.rbac.lock().unwrap_or_else(|e| e.into_inner());
+        role_ids.iter().filter_map(|role_id| rbac.get_role(role_id).cloned()).collect()
+    }
+    
     /// Check if tenant can perform operation
     pub fn check_tenant_quota(&self, tenant_id: &TenantId, resource_type: ResourceType, amount: u64) -> Result<()> {
         let quotas = self.quotas.read().unwrap();
@@ -169,6 +291,18 @@ impl TenantManager {
         }
     }
     
+    /// Check whether a whole batch of resource increments fits the tenant's quota.
+    ///
+    /// The batch is evaluated as one unit, so callers can reject it up front
+    /// instead of committing part of it before a later check fails.
+    pub fn check_tenant_quota_batch(&self, tenant_id: &TenantId, entries: &[(ResourceType, u64)]) -> Result<QuotaCheckResult> {
+        let quotas = self.quotas.read().unwrap();
+        let quota = quotas.get(tenant_id)
+            .ok_or_else(|| EventualiError::from(TenantError::TenantNotFound(tenant_id.clone())))?;
+        
+        Ok(quota.check_quota_batch(entries))
+    }
+    
     /// Record resource usage for a tenant
     pub fn record_tenant_usage(&self, tenant_id: &TenantId, resource_type: ResourceType, amount: u64) -> Result<()> {
         let quotas = self.quotas.read().unwrap();
@@ -181,18 +315,43 @@ impl TenantManager {
         let mut tenants = self.tenants.write().unwrap();
         if let Some(tenant) = tenants.get_mut(tenant_id) {
             tenant.metadata.last_activity = Some(Utc::now());
-            
-            match resource_type {
-                ResourceType::Events => tenant.metadata.total_events += amount,
-                ResourceType::Aggregates => tenant.metadata.total_aggregates += amount,
-                ResourceType::Storage => tenant.metadata.storage_used_mb += amount as f64,
-                _ => {}
+            Self::apply_usage_to_metadata(tenant, resource_type, amount);
+        }
+        
+        Ok(())
+    }
+    
+    /// Record several resource increments for a tenant at once.
+    ///
+    /// Every increment is applied under a single acquisition of the quota and
+    /// tenant locks, which keeps contention low for high-write tenants.
+    pub fn record_tenant_usage_batch(&self, tenant_id: &TenantId, entries: &[(ResourceType, u64)]) -> Result<()> {
+        let quotas = self.quotas.read().unwrap();
+        let quota = quotas.get(tenant_id)
+            .ok_or_else(|| EventualiError::from(TenantError::TenantNotFound(tenant_id.clone())))?;
+        
+        quota.record_usage_batch(entries);
+        
+        let mut tenants = self.tenants.write().unwrap();
+        if let Some(tenant) = tenants.get_mut(tenant_id) {
+            tenant.metadata.last_activity = Some(Utc::now());
+            for (resource_type, amount) in entries {
+                Self::apply_usage_to_metadata(tenant, *resource_type, *amount);
             }
         }
         
         Ok(())
     }
     
+    fn apply_usage_to_metadata(tenant: &mut TenantInfo, resource_type: ResourceType, amount: u64) {
+        match resource_type {
+            ResourceType::Events => tenant.metadata.total_events += amount,
+            ResourceType::Aggregates => tenant.metadata.total_aggregates += amount,
+            ResourceType::Storage => tenant.metadata.storage_used_mb += amount as f64,
+            _ => {}
+        }
+    }
+    
     /// Get tenants that are near their resource limits
     pub fn get_tenants_near_limits(&self) -> Vec<(TenantId, ResourceUsage)> {
         let quotas = self.quotas.read().unwrap();
@@ -225,6 +384,69 @@ pub struct TenantUpdate {
     pub config: Option<TenantConfig>,
 }
 
+/// Everything a new tenant starts with, applied by `TenantManager::onboard_tenant`
+#[derive(Debug, Clone)]
+pub struct TenantOnboardingSpec {
+    pub tenant_id: TenantId,
+    pub name: String,
+    pub config: TenantConfig,
+    pub quota_tier: QuotaTier,
+    pub configuration: Vec<OnboardingConfigurationValue>,
+    pub roles: Vec<OnboardingRole>,
+}
+
+impl TenantOnboardingSpec {
+    pub fn new(tenant_id: TenantId, name: impl Into<String>) -> Self {
+        Self {
+            tenant_id,
+            name: name.into(),
+            config: TenantConfig::default(),
+            quota_tier: QuotaTier::default(),
+            configuration: Vec::new(),
+            roles: Vec::new(),
+        }
+    }
+    
+    pub fn with_config(mut self, config: TenantConfig) -> Self {
+        self.config = config;
+        self
+    }
+    
+    pub fn with_quota_tier(mut self, quota_tier: QuotaTier) -> Self {
+        self.quota_tier = quota_tier;
+        self
+    }
+    
+    /// Seed a configuration value, validated against `schema`
+    pub fn with_configuration(mut self, key: impl Into<String>, value: ConfigurationValue, schema: ConfigurationSchema) -> Self {
+        self.configuration.push(OnboardingConfigurationValue { key: key.into(), value, schema });
+        self
+    }
+    
+    /// Register a role named `{tenant_id}:{name}` holding `permissions`
+    pub fn with_role(mut self, name: impl Into<String>, description: impl Into<String>, permissions: Vec<String>) -> Self {
+        self.roles.push(OnboardingRole { name: name.into(), description: description.into(), permissions });
+        self
+    }
+}
+
+/// A configuration value seeded during onboarding
+#[derive(Debug, Clone)]
+pub struct OnboardingConfigurationValue {
+    pub key: String,
+    pub value: ConfigurationValue,
+    pub schema: ConfigurationSchema,
+}
+
+/// A default role registered during onboarding
+#[derive(Debug, Clone)]
+pub struct OnboardingRole {
+    pub name: String,
+    pub description: String,
+    /// IDs of existing permissions, such as `events:read`
+    pub permissions: Vec<String>,
+}
+
 /// Trait for tenant operations
 #[async_trait]
 pub trait TenantOperations {
@@ -292,6 +514,12 @@ impl TenantRegistry {
         Ok(())
     }
     
+    pub fn unregister_tenant(&mut self, tenant_id: &TenantId) {
+        if self.registered_tenants.remove(tenant_id).is_some() {
+            self.performance_stats.total_tenants -= 1;
+        }
+    }
+    
     pub fn record_activity(&mut self, tenant_id: &TenantId) {
         if let Some(info) = self.registered_tenants.get_mut(tenant_id) {
             info.last_activity = Utc::now();
@@ -341,6 +569,7 @@ impl PerformanceStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tenancy::tenant::ResourceLimits;
     
     #[tokio::test]
     async fn test_tenant_creation() {
@@ -396,4 +625,153 @@ mod tests {
         // For this test, we'll assume the tenant exists
         assert!(manager.check_tenant_quota(&tenant_id, ResourceType::Events, 100).is_err());
     }
-}
\ No newline at end of file
+    
+    fn onboarding_spec(tenant_id: &TenantId) -> TenantOnboardingSpec {
+        TenantOnboardingSpec::new(tenant_id.clone(), "Acme")
+            .with_quota_tier(QuotaTier::Professional)
+            .with_configuration(
+                "max_projections",
+                ConfigurationValue::Integer(25),
+                ConfigurationSchema::Integer { min: Some(1), max: Some(100) },
+            )
+            .with_role("admin", "Tenant administrators", vec!["events:read".to_string(), "events:write".to_string()])
+            .with_role("viewer", "Read-only access", vec!["projections:read".to_string()])
+    }
+    
+    #[tokio::test]
+    async fn test_onboarding_applies_tier_configuration_and_roles() {
+        let manager = TenantManager::new();
+        let tenant_id = TenantId::new("acme".to_string()).unwrap();
+        
+        let info = manager.onboard_tenant(onboarding_spec(&tenant_id)).await.unwrap();
+        assert_eq!(info.name, "Acme");
+        assert!(manager.get_tenant(&tenant_id).is_ok());
+        
+        let quota = manager.get_tenant_quota(&tenant_id).unwrap();
+        assert!(matches!(quota.tier(), QuotaTier::Professional));
+        assert_eq!(quota.write_rate_limit(), Some(QuotaTier::Professional.write_rate_limit()));
+        
+        let configuration = manager.get_tenant_configuration(&tenant_id).unwrap();
+        assert!(matches!(
+            configuration.get_configuration("max_projections", None),
+            Some(ConfigurationValue::Integer(25))
+        ));
+        
+        let mut roles = manager.get_tenant_roles(&tenant_id);
+        roles.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<&str> = roles.iter().map(|role| role.name.as_str()).collect();
+        assert_eq!(names, ["acme:admin", "acme:viewer"]);
+        assert!(roles[0].permissions.contains("events:write"));
+        assert!(roles[1].permissions.contains("projections:read"));
+    }
+    
+    #[tokio::test]
+    async fn test_failed_onboarding_leaves_nothing_behind() {
+        let manager = TenantManager::new();
+        let tenant_id = TenantId::new("acme".to_string()).unwrap();
+        let roles_before = manager.rbac().lock().unwrap().get_system_stats()["total_roles"].clone();
+        
+        // The configuration and the first two roles are in place before the
+        // unknown permission fails the last role
+        let spec = onboarding_spec(&tenant_id)
+            .with_role("auditor", "Audit access", vec!["audit:everything".to_string()]);
+        let error = manager.onboard_tenant(spec).await.unwrap_err();
+        assert!(error.to_string().contains("audit:everything"), "{error}");
+        
+        assert!(manager.get_tenant(&tenant_id).is_err());
+        assert!(manager.get_tenant_quota(&tenant_id).is_err());
+        assert!(manager.get_tenant_configuration(&tenant_id).is_err());
+        assert!(manager.get_tenant_roles(&tenant_id).is_empty());
+        assert!(manager.list_tenants(None).is_empty());
+        assert_eq!(manager.rbac().lock().unwrap().get_system_stats()["total_roles"], roles_before);
+        assert_eq!(manager.registry.read().unwrap().get_stats().total_tenants, 0);
+        
+        // The same tenant can then be onboarded cleanly
+        manager.onboard_tenant(onboarding_spec(&tenant_id)).await.unwrap();
+        assert_eq!(manager.get_tenant_roles(&tenant_id).len(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_batch_quota_check_rejects_collective_overage() {
+        let manager = TenantManager::new();
+        let tenant_id = TenantId::new("near-limit".to_string()).unwrap();
+        let config = TenantConfig {
+            resource_limits: ResourceLimits {
+                max_events_per_day: Some(1_000),
+                ..ResourceLimits::default()
+            },
+            ..TenantConfig::default()
+        };
+        manager.create_tenant(tenant_id.clone(), "Near Limit".to_string(), Some(config)).await.unwrap();
+        manager.record_tenant_usage(&tenant_id, ResourceType::Events, 900).unwrap();
+        
+        // Each half of the batch fits on its own...
+        let batch = [(ResourceType::Events, 100), (ResourceType::Aggregates, 5), (ResourceType::Events, 100)];
+        assert!(manager.check_tenant_quota(&tenant_id, ResourceType::Events, 100).is_ok());
+        
+        // ...but together they overshoot the limit and its 5% grace allowance
+        let result = manager.check_tenant_quota_batch(&tenant_id, &batch).unwrap();
+        assert!(!result.allowed);
+        assert_eq!(result.current_usage, 900);
+        assert_eq!(result.limit, Some(1_000));
+        
+        let fitting = manager.check_tenant_quota_batch(&tenant_id, &batch[..2]).unwrap();
+        assert!(fitting.allowed);
+        assert!(!fitting.grace_period_active);
+        
+        let in_grace = manager
+            .check_tenant_quota_batch(&tenant_id, &[(ResourceType::Events, 60), (ResourceType::Events, 60)])
+            .unwrap();
+        assert!(in_grace.allowed);
+        assert!(in_grace.grace_period_active);
+        
+        let unknown = TenantId::new("unknown".to_string()).unwrap();
+        assert!(manager.check_tenant_quota_batch(&unknown, &batch).is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_batched_usage_recording_matches_single_calls_and_is_applied_at_once() {
+        let manager = TenantManager::new();
+        let single_tenant = TenantId::new("single-usage".to_string()).unwrap();
+        let batch_tenant = TenantId::new("batch-usage".to_string()).unwrap();
+        manager.create_tenant(single_tenant.clone(), "Single".to_string(), None).await.unwrap();
+        manager.create_tenant(batch_tenant.clone(), "Batch".to_string(), None).await.unwrap();
+        
+        let entries: Vec<(ResourceType, u64)> = (0..5_000)
+            .map(|i| if i % 5 == 0 { (ResourceType::Aggregates, 1) } else { (ResourceType::Events, 1) })
+            .collect();
+        
+        for (resource_type, amount) in &entries {
+            manager.record_tenant_usage(&single_tenant, *resource_type, *amount).unwrap();
+        }
+        
+        // The whole batch goes in under one acquisition of the usage lock, so a
+        // reader sees either none of it or all of it
+        let recorded = std::sync::atomic::AtomicBool::new(false);
+        let observed = std::thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                let mut observed = Vec::new();
+                while !recorded.load(std::sync::atomic::Ordering::SeqCst) {
+                    observed.push(manager.get_tenant_usage(&batch_tenant).unwrap().daily_events);
+                }
+                observed
+            });
+            manager.record_tenant_usage_batch(&batch_tenant, &entries).unwrap();
+            recorded.store(true, std::sync::atomic::Ordering::SeqCst);
+            reader.join().unwrap()
+        });
+        assert!(observed.iter().all(|&events| events == 0 || events == 4_000), "saw a partial batch");
+        
+        let single_usage = manager.get_tenant_usage(&single_tenant).unwrap();
+        let batch_usage = manager.get_tenant_usage(&batch_tenant).unwrap();
+        assert_eq!(batch_usage.daily_events, 4_000);
+        assert_eq!(batch_usage.total_aggregates, 1_000);
+        assert_eq!(batch_usage.daily_events, single_usage.daily_events);
+        assert_eq!(batch_usage.total_aggregates, single_usage.total_aggregates);
+        
+        let single_meta = manager.get_tenant(&single_tenant).unwrap().metadata;
+        let batch_meta = manager.get_tenant(&batch_tenant).unwrap().metadata;
+        assert_eq!(batch_meta.total_events, single_meta.total_events);
+        assert_eq!(batch_meta.total_aggregates, single_meta.total_aggregates);
+    }
+}
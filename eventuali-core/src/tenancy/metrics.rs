@@ -993,12 +993,11 @@ mod tests {
 
     #[test]
     fn test_aggregated_metric() {
-        let points = vec![
-            &MetricDataPoint::new(10.0),
-            &MetricDataPoint::new(20.0),
-            &MetricDataPoint::new(30.0),
-            &MetricDataPoint::new(40.0),
-        ];
+        let data_points: Vec<MetricDataPoint> = [10.0, 20.0, 30.0, 40.0]
+            .into_iter()
+            .map(MetricDataPoint::new)
+            .collect();
+        let points: Vec<&MetricDataPoint> = data_points.iter().collect();
         
         let agg = AggregatedMetric::from_points("test".to_string(), &points);
         assert_eq!(agg.min, 10.0);
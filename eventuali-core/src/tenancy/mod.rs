@@ -12,6 +12,7 @@ pub mod quota;
 pub mod manager;
 pub mod storage;
 pub mod projections;
+pub mod streaming;
 pub mod configuration;
 pub mod metrics;
 
@@ -19,11 +20,15 @@ pub use tenant::{TenantId, TenantInfo, TenantConfig, TenantMetadata, ResourceLim
 pub use isolation::{TenantIsolation, IsolatedEventStore, TenantScope};
 pub use quota::{
     TenantQuota, ResourceType, QuotaTier, QuotaCheckResult, 
-    QuotaExceeded, EnhancedResourceUsage, ResourceUsage,
+    QuotaExceeded, WriteRateLimit, EnhancedResourceUsage, ResourceUsage,
     QuotaAlert, AlertType, BillingAnalytics, UsageTrends
 };
-pub use manager::{TenantManager, TenantOperations, TenantRegistry};
+pub use manager::{
+    TenantManager, TenantOperations, TenantRegistry, TenantOnboardingSpec,
+    OnboardingConfigurationValue, OnboardingRole
+};
 pub use storage::{TenantAwareEventStorage, TenantStorageMetrics, TenantEventBatch};
+pub use streaming::{TenantEventStreamer, TenantSubscription};
 pub use projections::{
     TenantScopedProjection, TenantProjectionManager, TenantProjectionRegistry, 
     TenantProjectionMetrics
@@ -629,11 +629,8 @@ mod tests {
     use super::sample_projections::*;
     use crate::tenancy::isolation::{TenantIsolation, IsolationPolicy};
     use crate::tenancy::quota::TenantQuota;
-    use crate::tenancy::tenant::{TenantConfig, ResourceLimits};
-    use crate::event::{Event, EventData, EventMetadata};
-    use std::collections::HashMap;
-    use chrono::Utc;
-    use uuid::Uuid;
+    use crate::tenancy::tenant::ResourceLimits;
+    use crate::event::{Event, EventData};
     
     #[tokio::test]
     async fn test_tenant_scoped_projection() {
@@ -1,5 +1,6 @@
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 use std::collections::HashMap;
+use std::time::Instant;
 use chrono::{DateTime, Utc, Duration, Datelike};
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +29,91 @@ pub enum QuotaTier {
     Enterprise,
 }
 
+impl QuotaTier {
+    /// Default write rate limit for tenants on this tier
+    pub fn write_rate_limit(&self) -> WriteRateLimit {
+        match self {
+            QuotaTier::Starter => WriteRateLimit { events_per_second: 50.0, burst: 100 },
+            QuotaTier::Standard => WriteRateLimit { events_per_second: 200.0, burst: 500 },
+            QuotaTier::Professional => WriteRateLimit { events_per_second: 1_000.0, burst: 2_500 },
+            QuotaTier::Enterprise => WriteRateLimit { events_per_second: 5_000.0, burst: 10_000 },
+        }
+    }
+}
+
+/// Instantaneous cap on how fast a tenant may write events.
+///
+/// Daily quotas bound total volume; this bounds the rate, so a tenant cannot
+/// spend a day's allowance in a one-minute spike. Up to `burst` events may be
+/// written at once, refilling at `events_per_second`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WriteRateLimit {
+    pub events_per_second: f64,
+    pub burst: u64,
+}
+
+/// Token bucket enforcing a `WriteRateLimit`
+#[derive(Debug)]
+struct TokenBucket {
+    limit: WriteRateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: WriteRateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.burst as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let refilled = now.duration_since(self.last_refill).as_secs_f64() * self.limit.events_per_second;
+        self.tokens = (self.tokens + refilled).min(self.limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// Report whether `amount` tokens are available without taking them, or
+    /// how long until they are. `Err(None)` means `amount` exceeds the burst
+    /// and can never be taken.
+    fn check(&mut self, amount: u64) -> std::result::Result<(), Option<std::time::Duration>> {
+        self.refill();
+        let amount = amount as f64;
+        if amount <= self.tokens {
+            return Ok(());
+        }
+        if amount > self.limit.burst as f64 || self.limit.events_per_second <= 0.0 {
+            return Err(None);
+        }
+        Err(Some(std::time::Duration::from_secs_f64(
+            (amount - self.tokens) / self.limit.events_per_second,
+        )))
+    }
+
+    /// Take `amount` tokens, or report how long until that many are available
+    fn try_take(&mut self, amount: u64) -> std::result::Result<(), Option<std::time::Duration>> {
+        self.check(amount)?;
+        self.tokens -= amount as f64;
+        Ok(())
+    }
+
+    /// Take `amount` tokens unconditionally. The bucket may go negative, which
+    /// holds back later writes until it refills.
+    fn take(&mut self, amount: u64) {
+        self.refill();
+        self.tokens -= amount as f64;
+    }
+
+    /// Give back tokens taken for a write that did not happen
+    fn refund(&mut self, amount: u64) {
+        self.refill();
+        self.tokens = (self.tokens + amount as f64).min(self.limit.burst as f64);
+    }
+}
+
 
 /// Result of quota check with detailed information
 #[derive(Debug, Clone)]
@@ -471,6 +557,16 @@ impl EnhancedResourceTracker {
         }
     }
     
+    pub fn get_concurrent_streams(&self) -> u32 {
+        self.concurrent_streams
+    }
+    
+    /// Record that `amount` streams have closed
+    pub fn release_streams(&mut self, amount: u32) {
+        self.concurrent_streams = self.concurrent_streams.saturating_sub(amount);
+        self.last_updated = Utc::now();
+    }
+    
     pub fn get_api_calls_today(&self) -> u64 {
         if self.is_daily_counter_stale() {
             0
@@ -751,6 +847,7 @@ pub struct TenantQuota {
     tracker: Arc<RwLock<EnhancedResourceTracker>>,
     alert_manager: Arc<RwLock<QuotaAlertManager>>,
     billing_tracker: Arc<RwLock<BillingTracker>>,
+    write_rate: Mutex<Option<TokenBucket>>,
 }
 
 impl TenantQuota {
@@ -762,12 +859,116 @@ impl TenantQuota {
         Self {
             tenant_id: tenant_id.clone(),
             limits,
-            tier,
             tracker: Arc::new(RwLock::new(EnhancedResourceTracker::new())),
             alert_manager: Arc::new(RwLock::new(QuotaAlertManager::new(tenant_id.clone()))),
             billing_tracker: Arc::new(RwLock::new(BillingTracker::new(tenant_id))),
+            write_rate: Mutex::new(Some(TokenBucket::new(tier.write_rate_limit()))),
+            tier,
+        }
+    }
+
+    /// Replace the tier's write rate limit for this tenant; `None` removes it
+    pub fn with_write_rate_limit(self, limit: Option<WriteRateLimit>) -> Self {
+        *self.write_rate.lock().unwrap() = limit.map(TokenBucket::new);
+        self
+    }
+
+    pub fn tier(&self) -> &QuotaTier {
+        &self.tier
+    }
+
+    /// The write rate limit currently enforced, if any
+    pub fn write_rate_limit(&self) -> Option<WriteRateLimit> {
+        self.write_rate.lock().unwrap().as_ref().map(|bucket| bucket.limit)
+    }
+
+    /// Claim one of the tenant's concurrent stream slots, failing when
+    /// `max_concurrent_streams` are already open. The check and the increment
+    /// happen under one lock, so concurrent subscribers cannot both take the
+    /// last slot. Every successful call must be paired with `release_stream`.
+    pub fn try_acquire_stream(&self) -> std::result::Result<(), QuotaExceeded> {
+        {
+            let mut tracker = self.tracker.write().unwrap();
+            let open = tracker.get_concurrent_streams();
+            if let Some(limit) = self.limits.max_concurrent_streams {
+                if open >= limit {
+                    return Err(QuotaExceeded {
+                        tenant_id: self.tenant_id.clone(),
+                        resource_type: ResourceType::Streams,
+                        quota: "concurrent_streams".to_string(),
+                        current_usage: open as u64,
+                        limit: limit as u64,
+                        attempted: 1,
+                        retry_after: None,
+                    });
+                }
+            }
+            tracker.record_usage(ResourceType::Streams, 1);
+        }
+        self.billing_tracker.write().unwrap().record_usage(ResourceType::Streams, 1);
+        Ok(())
+    }
+
+    /// Return a stream slot claimed with `try_acquire_stream`
+    pub fn release_stream(&self) {
+        self.tracker.write().unwrap().release_streams(1);
+    }
+
+    /// Streams the tenant currently has open
+    pub fn active_streams(&self) -> u32 {
+        self.tracker.read().unwrap().get_concurrent_streams()
+    }
+
+    /// Admit a write of `events` events under the tenant's write rate limit.
+    ///
+    /// Admitted events are deducted immediately; give them back with
+    /// `refund_write_rate` if the write then fails. A rejected write deducts
+    /// nothing; `retry_after` says how long until it would be admitted, and is
+    /// `None` when the write is larger than the burst and must be split.
+    pub fn check_write_rate(&self, events: u64) -> std::result::Result<(), QuotaExceeded> {
+        self.with_write_rate(events, |bucket| bucket.try_take(events))
+    }
+
+    /// Report whether a write of `events` events would be admitted under the
+    /// write rate limit, without deducting anything. Follow a successful write
+    /// with `record_write_rate`.
+    pub fn peek_write_rate(&self, events: u64) -> std::result::Result<(), QuotaExceeded> {
+        self.with_write_rate(events, |bucket| bucket.check(events))
+    }
+
+    /// Deduct a completed write of `events` events from the write rate limit
+    pub fn record_write_rate(&self, events: u64) {
+        if let Some(bucket) = self.write_rate.lock().unwrap().as_mut() {
+            bucket.take(events);
+        }
+    }
+
+    /// Give back events admitted by `check_write_rate` for a write that failed
+    pub fn refund_write_rate(&self, events: u64) {
+        if let Some(bucket) = self.write_rate.lock().unwrap().as_mut() {
+            bucket.refund(events);
         }
     }
+
+    fn with_write_rate(
+        &self,
+        events: u64,
+        admit: impl FnOnce(&mut TokenBucket) -> std::result::Result<(), Option<std::time::Duration>>,
+    ) -> std::result::Result<(), QuotaExceeded> {
+        let mut write_rate = self.write_rate.lock().unwrap();
+        let Some(bucket) = write_rate.as_mut() else {
+            return Ok(());
+        };
+        admit(bucket).map_err(|retry_after| QuotaExceeded {
+            tenant_id: self.tenant_id.clone(),
+            resource_type: ResourceType::Events,
+            quota: "write_rate".to_string(),
+            current_usage: bucket.tokens.max(0.0) as u64,
+            limit: bucket.limit.burst,
+            attempted: events,
+            retry_after,
+        })
+    }
     
     /// Check if an operation would exceed quotas with enhanced validation
     pub fn check_quota(&self, resource_type: ResourceType, amount: u64) -> Result<QuotaCheckResult> {
@@ -797,13 +998,14 @@ impl TenantQuota {
                             result.grace_period_active = true;
                             result.estimated_overage_cost = self.calculate_overage_cost(&resource_type, amount);
                         } else {
-                            result.allowed = false;
                             return Err(EventualiError::from(QuotaExceeded {
                                 tenant_id: self.tenant_id.clone(),
-                                resource_type: "daily_events".to_string(),
+                                resource_type: ResourceType::Events,
+                                quota: "daily_events".to_string(),
                                 current_usage: current_daily,
                                 limit,
                                 attempted: amount,
+                                retry_after: None,
                             }));
                         }
                     }
@@ -822,13 +1024,14 @@ impl TenantQuota {
                             result.grace_period_active = true;
                             result.estimated_overage_cost = self.calculate_overage_cost(&resource_type, amount);
                         } else {
-                            result.allowed = false;
                             return Err(EventualiError::from(QuotaExceeded {
                                 tenant_id: self.tenant_id.clone(),
-                                resource_type: "api_calls".to_string(),
+                                resource_type: ResourceType::ApiCalls,
+                                quota: "api_calls".to_string(),
                                 current_usage: current,
                                 limit: *limit,
                                 attempted: amount,
+                                retry_after: None,
                             }));
                         }
                     }
@@ -852,6 +1055,81 @@ impl TenantQuota {
         Ok(result)
     }
     
+    /// Check a whole batch of resource increments against the remaining quota at once.
+    ///
+    /// Amounts for the same resource are summed before they are compared with the
+    /// limit, so a batch whose entries each fit but together overshoot is rejected
+    /// as a whole instead of being admitted piecemeal. Overage is reported through
+    /// `allowed` rather than as an error; the usage fields describe the binding
+    /// resource (a rejected one if any, otherwise the most heavily utilized).
+    pub fn check_quota_batch(&self, entries: &[(ResourceType, u64)]) -> QuotaCheckResult {
+        let mut totals: Vec<(ResourceType, u64)> = Vec::new();
+        for &(resource_type, amount) in entries {
+            match totals.iter_mut().find(|(existing, _)| *existing == resource_type) {
+                Some((_, total)) => *total = total.saturating_add(amount),
+                None => totals.push((resource_type, amount)),
+            }
+        }
+        
+        let mut result = QuotaCheckResult {
+            allowed: true,
+            current_usage: 0,
+            limit: None,
+            utilization_percentage: 0.0,
+            grace_period_active: false,
+            warning_triggered: false,
+            estimated_overage_cost: 0.0,
+        };
+        let mut binding: Option<(bool, f64)> = None;
+        let mut warnings = Vec::new();
+        
+        {
+            let tracker = self.tracker.read().unwrap();
+            for (resource_type, amount) in totals {
+                let Some((current, limit)) = self.current_usage_and_limit(&tracker, resource_type) else {
+                    continue;
+                };
+                
+                let projected = current.saturating_add(amount);
+                let fits = if projected <= limit {
+                    true
+                } else if projected <= self.calculate_grace_limit(&resource_type, limit) {
+                    result.grace_period_active = true;
+                    result.estimated_overage_cost += self.calculate_overage_cost(&resource_type, amount);
+                    true
+                } else {
+                    false
+                };
+                result.allowed &= fits;
+                
+                let percentage = |usage: u64| if limit == 0 { 100.0 } else { (usage as f64 / limit as f64) * 100.0 };
+                let utilization = percentage(current);
+                let rejected = !fits;
+                let projected_percentage = percentage(projected);
+                let outranks = |(was_rejected, best_percentage): (bool, f64)| {
+                    (rejected && !was_rejected) || (rejected == was_rejected && projected_percentage > best_percentage)
+                };
+                if binding.is_none_or(outranks) {
+                    binding = Some((rejected, projected_percentage));
+                    result.current_usage = current;
+                    result.limit = Some(limit);
+                    result.utilization_percentage = utilization;
+                }
+                
+                if (80.0..90.0).contains(&utilization) {
+                    warnings.push((resource_type, utilization));
+                }
+            }
+        }
+        
+        for (resource_type, utilization) in warnings {
+            result.warning_triggered = true;
+            self.trigger_warning_alert(resource_type, utilization);
+        }
+        
+        result
+    }
+    
     /// Record resource usage with billing integration
     pub fn record_usage(&self, resource_type: ResourceType, amount: u64) {
         {
@@ -869,6 +1147,34 @@ impl TenantQuota {
         self.check_and_trigger_alerts(resource_type, amount);
     }
     
+    /// Record several resource increments under a single acquisition of each lock
+    pub fn record_usage_batch(&self, entries: &[(ResourceType, u64)]) {
+        if entries.is_empty() {
+            return;
+        }
+        
+        {
+            let mut tracker = self.tracker.write().unwrap();
+            for (resource_type, amount) in entries {
+                tracker.record_usage(*resource_type, *amount);
+            }
+        }
+        
+        {
+            let mut billing_tracker = self.billing_tracker.write().unwrap();
+            for (resource_type, amount) in entries {
+                billing_tracker.record_usage(*resource_type, *amount);
+            }
+        }
+        
+        {
+            let mut alert_manager = self.alert_manager.write().unwrap();
+            for (resource_type, amount) in entries {
+                alert_manager.check_and_trigger_alerts(*resource_type, *amount);
+            }
+        }
+    }
+    
     /// Get comprehensive usage statistics with analytics
     pub fn get_usage(&self) -> EnhancedResourceUsage {
         let tracker = self.tracker.read().unwrap();
@@ -936,6 +1242,18 @@ impl TenantQuota {
         (base_limit as f64 * (1.0 + grace_percentage)) as u64
     }
     
+    /// Current usage and limit for the resources `check_quota` enforces
+    fn current_usage_and_limit(&self, tracker: &EnhancedResourceTracker, resource_type: ResourceType) -> Option<(u64, u64)> {
+        match resource_type {
+            ResourceType::Events => self.limits.max_events_per_day.map(|limit| (tracker.get_daily_events(), limit)),
+            ResourceType::ApiCalls => tracker
+                .api_call_limits
+                .get(&resource_type)
+                .map(|limit| (tracker.get_api_calls_today(), *limit)),
+            _ => None,
+        }
+    }
+    
     /// Calculate overage cost based on tier and usage
     fn calculate_overage_cost(&self, resource_type: &ResourceType, overage_amount: u64) -> f64 {
         let cost_per_unit = match (&self.tier, resource_type) {
@@ -1005,9 +1323,10 @@ impl TenantQuota {
         alert_manager.get_alerts_history(limit.unwrap_or(100))
     }
     
-    /// Update quota tier
+    /// Update quota tier, switching to the new tier's write rate limit
     pub fn update_tier(&mut self, new_tier: QuotaTier) {
         self.tier = new_tier.clone();
+        *self.write_rate.lock().unwrap() = Some(TokenBucket::new(new_tier.write_rate_limit()));
         
         // Update billing tracker with new tier
         let mut billing_tracker = self.billing_tracker.write().unwrap();
@@ -1015,20 +1334,35 @@ impl TenantQuota {
     }
 }
 
-/// Error type for quota violations
-#[derive(Debug, thiserror::Error)]
-#[error("Quota exceeded for tenant {tenant_id}: {resource_type} - current: {current_usage}, limit: {limit}, attempted: {attempted}")]
+/// Error type for quota violations, carrying enough detail for a client to
+/// tell which quota to back off on
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Quota exceeded for tenant {tenant_id}: {quota} ({resource_type:?}) - current: {current_usage}, limit: {limit}, attempted: {attempted}{}", retry_hint(.retry_after))]
 pub struct QuotaExceeded {
     pub tenant_id: TenantId,
-    pub resource_type: String,
+    /// Resource the exceeded quota limits
+    pub resource_type: ResourceType,
+    /// Name of the exceeded limit, such as `daily_events` or `write_rate`,
+    /// telling apart quotas on the same resource
+    pub quota: String,
     pub current_usage: u64,
+    /// The configured limit that was hit
     pub limit: u64,
     pub attempted: u64,
+    /// How long to wait before the same request would be admitted, for limits
+    /// that replenish continuously
+    pub retry_after: Option<std::time::Duration>,
+}
+
+fn retry_hint(retry_after: &Option<std::time::Duration>) -> String {
+    retry_after
+        .map(|d| format!(", retry after {}ms", d.as_millis().max(1)))
+        .unwrap_or_default()
 }
 
 impl From<QuotaExceeded> for crate::error::EventualiError {
     fn from(err: QuotaExceeded) -> Self {
-        crate::error::EventualiError::Tenant(err.to_string())
+        crate::error::EventualiError::QuotaExceeded(Box::new(err))
     }
 }
 
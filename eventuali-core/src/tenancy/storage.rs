@@ -1,14 +1,17 @@
 use std::sync::{Arc, RwLock};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use crate::event::Event;
+use crate::event::{Event, EventId, IdAllocator};
 use crate::aggregate::{AggregateId, AggregateVersion};
-use crate::store::{EventStore, EventStoreBackend};
+use crate::store::{DeleteMode, EventStore, EventStoreBackend, FailedWriteLog, LenientLoad, StoreStats};
+use crate::store::traits::StoreStatsAccumulator;
 use crate::error::{EventualiError, Result};
 use super::tenant::TenantId;
-use super::isolation::{TenantIsolation, TenantOperation};
+use super::isolation::{for_each_tenant_event, TenantIsolation, TenantOperation, TENANT_SCAN_BATCH};
 use super::quota::{TenantQuota, ResourceType};
+use super::streaming::{TenantEventStreamer, TenantSubscription};
+use crate::streaming::{EventStreamer, Subscription};
 
 /// Tenant-aware event storage that ensures complete isolation between tenants
 /// while providing high-performance event operations
@@ -18,6 +21,9 @@ pub struct TenantAwareEventStorage {
     isolation: Arc<TenantIsolation>,
     quota: Arc<TenantQuota>,
     metrics: Arc<RwLock<TenantStorageMetrics>>,
+    id_allocator: Option<Arc<dyn IdAllocator>>,
+    failed_writes: Option<Arc<FailedWriteLog>>,
+    streamer: Option<Arc<TenantEventStreamer>>,
 }
 
 impl TenantAwareEventStorage {
@@ -33,6 +39,35 @@ impl TenantAwareEventStorage {
             isolation,
             quota,
             metrics: Arc::new(RwLock::new(TenantStorageMetrics::new())),
+            id_allocator: None,
+            failed_writes: None,
+            streamer: None,
+        }
+    }
+    
+    /// Take the IDs `new_event_id` hands out from `allocator`, like
+    /// `EventStoreImpl::with_id_allocator` does for an unscoped store
+    pub fn with_id_allocator(mut self, allocator: Arc<dyn IdAllocator>) -> Self {
+        self.id_allocator = Some(allocator);
+        self
+    }
+    
+    /// Record every save this storage rejects in `log`, quota and isolation
+    /// rejections included. Rows carry the tenant-scoped aggregate ID, so one
+    /// log can be shared by every tenant and queried with `FailedWriteLog::query`.
+    pub fn with_failed_write_log(mut self, log: Arc<FailedWriteLog>) -> Self {
+        self.failed_writes = Some(log);
+        self
+    }
+    
+    /// Open a subscription on the tenant's streamer. It counts against the
+    /// tenant's concurrent stream limit until the returned handle is dropped.
+    pub async fn open_subscription(&self, subscription: Subscription) -> Result<TenantSubscription> {
+        match &self.streamer {
+            Some(streamer) => streamer.open(subscription).await,
+            None => Err(EventualiError::Configuration(
+                "No event streamer is set for this tenant's storage".to_string(),
+            )),
         }
     }
     
@@ -72,15 +107,23 @@ impl TenantAwareEventStorage {
     }
     
     /// Validate and record event storage operation
-    fn validate_and_record(&self, operation: TenantOperation, event_count: u64) -> Result<()> {
+    fn validate_and_record(&self, operation: TenantOperation, events: &[Event]) -> Result<()> {
+        let event_count = events.len() as u64;
+        
         // Validate tenant isolation
         self.isolation.validate_operation(&self.tenant_id, &operation)?;
         
         // Check quotas
         self.quota.check_quota(ResourceType::Events, event_count)?;
+        self.quota.check_write_rate(event_count)?;
         
-        // Record usage
-        self.quota.record_usage(ResourceType::Events, event_count);
+        // Record the whole save's usage in one pass over the quota locks
+        let new_aggregates = events.iter().filter(|e| e.aggregate_version == 1).count() as u64;
+        let mut usage = vec![(ResourceType::Events, event_count)];
+        if new_aggregates > 0 {
+            usage.push((ResourceType::Aggregates, new_aggregates));
+        }
+        self.quota.record_usage_batch(&usage);
         
         // Update metrics
         let mut metrics = self.metrics.write().unwrap();
@@ -96,34 +139,67 @@ impl TenantAwareEventStorage {
 
 #[async_trait]
 impl EventStore for TenantAwareEventStorage {
-    async fn save_events(&self, events: Vec<Event>) -> Result<()> {
+    async fn save_events_expecting(
+        &self,
+        events: Vec<Event>,
+        expected_version: Option<AggregateVersion>,
+    ) -> Result<()> {
         let start_time = std::time::Instant::now();
-        
-        // Validate operation for the first event's aggregate (assuming batch operations on same aggregate)
-        if let Some(first_event) = events.first() {
-            self.validate_and_record(
-                TenantOperation::CreateEvent {
-                    aggregate_id: first_event.aggregate_id.clone()
-                },
-                events.len() as u64
-            )?;
-        }
+        let first_aggregate = events.first().map(|event| event.aggregate_id.clone());
+        let published = self.streamer.as_ref().map(|_| events.clone());
         
         // Transform events to include tenant scoping
         let scoped_events: Vec<Event> = events
             .into_iter()
             .map(|event| self.tenant_scoped_event(event))
             .collect();
+        let attempted = self.failed_writes.as_ref().map(|_| scoped_events.clone());
+        
+        // Validate operation for the first event's aggregate (assuming batch operations on same aggregate)
+        let validated = match first_aggregate {
+            Some(aggregate_id) => {
+                self.validate_and_record(TenantOperation::CreateEvent { aggregate_id }, &scoped_events)
+            }
+            None => Ok(()),
+        };
         
         // Delegate to backend
-        let result = self.backend.save_events(scoped_events).await;
+        let result = match validated {
+            Ok(()) => {
+                let event_count = scoped_events.len() as u64;
+                let result = self.backend.save_events_expecting(scoped_events, expected_version).await;
+                if result.is_err() {
+                    // Only writes that land count against the write rate
+                    self.quota.refund_write_rate(event_count);
+                }
+                
+                // Record performance metrics
+                self.metrics.write().unwrap().record_save_operation(start_time.elapsed(), result.is_ok());
+                result
+            }
+            Err(e) => Err(e),
+        };
         
-        // Record performance metrics
-        let duration = start_time.elapsed();
-        let mut metrics = self.metrics.write().unwrap();
-        metrics.record_save_operation(duration, result.is_ok());
+        if let (Err(e), Some(log), Some(attempted)) = (&result, &self.failed_writes, attempted) {
+            if let Err(log_error) = log.clone().record(attempted, e, Utc::now()).await {
+                tracing::warn!(error = %log_error, "Failed to record rejected save");
+            }
+        }
         
-        result
+        let positions = result?;
+        
+        // Subscribers see the tenant's own aggregate IDs, as loads return them
+        if let (Some(streamer), Some(published)) = (&self.streamer, published) {
+            for (event, global_position) in published.into_iter().zip(positions) {
+                let stream_position = event.aggregate_version as u64;
+                streamer.publish_event(event, stream_position, global_position).await?;
+            }
+        }
+        Ok(())
+    }
+    
+    async fn sync_to_disk(&self) -> Result<()> {
+        self.backend.sync_to_disk().await
     }
     
     async fn load_events(
@@ -167,6 +243,65 @@ impl EventStore for TenantAwareEventStorage {
         final_result
     }
     
+    async fn load_events_at_version(
+        &self,
+        aggregate_id: &AggregateId,
+        version: AggregateVersion,
+    ) -> Result<Vec<Event>> {
+        let start_time = std::time::Instant::now();
+        
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents {
+            aggregate_id: aggregate_id.clone()
+        })?;
+        
+        let scoped_aggregate_id = format!("{}:{}", self.tenant_id.db_prefix(), aggregate_id);
+        let result = self.backend.load_events_at_version(&scoped_aggregate_id, version).await;
+        
+        let mut metrics = self.metrics.write().unwrap();
+        match result {
+            Ok(events) => {
+                let events: Vec<Event> = events.into_iter().map(|event| self.unscoped_event(event)).collect();
+                metrics.record_load_operation(start_time.elapsed(), true, events.len());
+                Ok(events)
+            }
+            Err(e) => {
+                metrics.record_load_operation(start_time.elapsed(), false, 0);
+                Err(e)
+            }
+        }
+    }
+    
+    async fn load_events_lenient(
+        &self,
+        aggregate_id: &AggregateId,
+        from_version: Option<AggregateVersion>,
+    ) -> Result<LenientLoad> {
+        let start_time = std::time::Instant::now();
+        
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents {
+            aggregate_id: aggregate_id.clone()
+        })?;
+        
+        let scoped_aggregate_id = format!("{}:{}", self.tenant_id.db_prefix(), aggregate_id);
+        let result = self.backend.load_events_lenient(&scoped_aggregate_id, from_version).await;
+        
+        let mut metrics = self.metrics.write().unwrap();
+        match result {
+            Ok(mut load) => {
+                load.events = load.events.into_iter().map(|event| self.unscoped_event(event)).collect();
+                for row in &mut load.quarantined {
+                    row.aggregate_id = aggregate_id.clone();
+                }
+                metrics.record_load_operation(start_time.elapsed(), true, load.events.len());
+                Ok(load)
+            }
+            Err(e) => {
+                metrics.record_load_operation(start_time.elapsed(), false, 0);
+                Err(e)
+            }
+        }
+    }
+    
     async fn load_events_by_type(
         &self,
         aggregate_type: &str,
@@ -201,6 +336,67 @@ impl EventStore for TenantAwareEventStorage {
         }
     }
     
+    async fn get_events_by_correlation_id_with_prefix(
+        &self,
+        correlation_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let start_time = std::time::Instant::now();
+        
+        // Correlation IDs are not tenant-scoped; the query keeps only this
+        // tenant's events, so the limit counts none of anyone else's
+        let prefix = format!("{}:{aggregate_id_prefix}", self.tenant_id.db_prefix());
+        let result = self
+            .backend
+            .get_events_by_correlation_id_with_prefix(correlation_id, &prefix, limit)
+            .await
+            .map(|events| {
+                events
+                    .into_iter()
+                    .map(|event| self.unscoped_event(event))
+                    .collect::<Vec<Event>>()
+            });
+        
+        let mut metrics = self.metrics.write().unwrap();
+        match &result {
+            Ok(events) => metrics.record_load_operation(start_time.elapsed(), true, events.len()),
+            Err(_) => metrics.record_load_operation(start_time.elapsed(), false, 0),
+        }
+        
+        result
+    }
+    
+    async fn get_events_by_user_id_with_prefix(
+        &self,
+        user_id: &str,
+        aggregate_id_prefix: &str,
+        limit: usize,
+    ) -> Result<Vec<Event>> {
+        let start_time = std::time::Instant::now();
+        
+        // User IDs are not tenant-scoped either
+        let prefix = format!("{}:{aggregate_id_prefix}", self.tenant_id.db_prefix());
+        let result = self
+            .backend
+            .get_events_by_user_id_with_prefix(user_id, &prefix, limit)
+            .await
+            .map(|events| {
+                events
+                    .into_iter()
+                    .map(|event| self.unscoped_event(event))
+                    .collect::<Vec<Event>>()
+            });
+        
+        let mut metrics = self.metrics.write().unwrap();
+        match &result {
+            Ok(events) => metrics.record_load_operation(start_time.elapsed(), true, events.len()),
+            Err(_) => metrics.record_load_operation(start_time.elapsed(), false, 0),
+        }
+        
+        result
+    }
+    
     async fn get_aggregate_version(&self, aggregate_id: &AggregateId) -> Result<Option<AggregateVersion>> {
         // Validate operation
         self.isolation.validate_operation(&self.tenant_id, &TenantOperation::ReadEvents {
@@ -213,9 +409,103 @@ impl EventStore for TenantAwareEventStorage {
         self.backend.get_aggregate_version(&scoped_aggregate_id).await
     }
     
-    fn set_event_streamer(&mut self, _streamer: Arc<dyn crate::streaming::EventStreamer + Send + Sync>) {
-        // For tenant-aware storage, streaming would need to be tenant-scoped as well
-        // This would be implemented in a production system
+    async fn load_events_after_position(&self, after_global: u64, limit: usize) -> Result<Vec<(u64, Event)>> {
+        let start_time = std::time::Instant::now();
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut after_global = after_global;
+        
+        // Keep paging past other tenants' events so an empty page always means the end
+        let result = loop {
+            let page = match self.backend.load_events_after_position(after_global, limit).await {
+                Ok(page) => page,
+                Err(e) => break Err(e),
+            };
+            let Some(&(last_position, _)) = page.last() else {
+                break Ok(Vec::new());
+            };
+            
+            let events: Vec<(u64, Event)> = page
+                .into_iter()
+                .filter(|(_, event)| event.aggregate_id.starts_with(&prefix))
+                .map(|(position, event)| (position, self.unscoped_event(event)))
+                .collect();
+            
+            if !events.is_empty() {
+                break Ok(events);
+            }
+            after_global = last_position;
+        };
+        
+        let mut metrics = self.metrics.write().unwrap();
+        match &result {
+            Ok(events) => metrics.record_load_operation(start_time.elapsed(), true, events.len()),
+            Err(_) => metrics.record_load_operation(start_time.elapsed(), false, 0),
+        }
+        
+        result
+    }
+    
+    async fn list_aggregate_types(&self) -> Result<Vec<String>> {
+        // Aggregate types are not tenant-scoped, so collect them from this tenant's events
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut types = BTreeSet::new();
+        for_each_tenant_event(
+            &prefix,
+            |after_global| self.backend.load_events_after_position(after_global, TENANT_SCAN_BATCH),
+            |event| {
+                types.insert(event.aggregate_type);
+            },
+        )
+        .await?;
+        Ok(types.into_iter().collect())
+    }
+
+    async fn delete_aggregate_events_with_mode(&self, aggregate_id: &AggregateId, mode: DeleteMode) -> Result<u64> {
+        self.isolation.validate_operation(&self.tenant_id, &TenantOperation::EraseEvents {
+            aggregate_id: aggregate_id.clone()
+        })?;
+        
+        let scoped_aggregate_id = format!("{}:{}", self.tenant_id.db_prefix(), aggregate_id);
+        self.backend.delete_aggregate_events(&scoped_aggregate_id, mode).await
+    }
+    
+    async fn stats(&self) -> Result<StoreStats> {
+        // Only this tenant's events count, so scan them rather than asking the backend
+        let prefix = format!("{}:", self.tenant_id.db_prefix());
+        let mut stats = StoreStatsAccumulator::default();
+        for_each_tenant_event(
+            &prefix,
+            |after_global| self.backend.load_events_after_position(after_global, TENANT_SCAN_BATCH),
+            |event| stats.add(&event),
+        )
+        .await?;
+        Ok(stats.finish())
+    }
+    
+    fn new_event_id(&self) -> Result<EventId> {
+        match &self.id_allocator {
+            Some(allocator) => allocator.allocate(),
+            None => Ok(crate::event::new_event_id()),
+        }
+    }
+    
+    fn set_id_allocator(&mut self, allocator: Arc<dyn IdAllocator>) {
+        self.id_allocator = Some(allocator);
+    }
+    
+    /// Publish this tenant's saves to `streamer`, holding its subscriptions to
+    /// the tenant's concurrent stream limit. Give each tenant its own streamer:
+    /// published events carry unscoped aggregate IDs.
+    fn set_event_streamer(&mut self, streamer: Arc<dyn EventStreamer + Send + Sync>) {
+        self.streamer = Some(Arc::new(TenantEventStreamer::new(
+            self.tenant_id.clone(),
+            streamer,
+            self.quota.clone(),
+        )));
+    }
+    
+    fn event_streamer(&self) -> Option<Arc<dyn EventStreamer + Send + Sync>> {
+        self.streamer.clone().map(|streamer| streamer as Arc<dyn EventStreamer + Send + Sync>)
     }
 }
 
@@ -268,6 +558,7 @@ impl TenantStorageMetrics {
         let operation_type = match operation {
             TenantOperation::CreateEvent { .. } => "create_event",
             TenantOperation::ReadEvents { .. } => "read_events",
+            TenantOperation::EraseEvents { .. } => "erase_events",
             TenantOperation::CreateProjection { .. } => "create_projection",
             TenantOperation::StreamEvents { .. } => "stream_events",
         };
@@ -389,10 +680,31 @@ impl TenantEventBatch {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::event::EventData;
+    use crate::store::EventStoreConfig;
     use crate::store::sqlite::SQLiteBackend;
     use crate::tenancy::isolation::{TenantIsolation, IsolationPolicy};
-    use crate::tenancy::quota::{TenantQuota};
-    use crate::tenancy::tenant::{TenantConfig, ResourceLimits};
+    use crate::tenancy::quota::{TenantQuota, WriteRateLimit};
+    use crate::tenancy::tenant::ResourceLimits;
+    
+    #[tokio::test]
+    async fn test_tenant_storage_hands_out_ids_from_its_allocator() {
+        let tenant_id = TenantId::new("id-tenant".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let isolation = Arc::new(TenantIsolation::new());
+        isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+        let quota = Arc::new(TenantQuota::new(tenant_id.clone(), ResourceLimits::default()));
+        
+        let storage = TenantAwareEventStorage::new(tenant_id, Arc::new(backend), isolation, quota)
+            .with_id_allocator(Arc::new(crate::EventIdKind::Ulid));
+        
+        let first = storage.new_event_id().unwrap();
+        let second = storage.new_event_id().unwrap();
+        let created = crate::ulid_timestamp(&first).unwrap();
+        assert!((Utc::now() - created).num_seconds().abs() < 5);
+        assert!(first < second);
+    }
     
     #[tokio::test]
     async fn test_tenant_aware_storage_isolation() {
@@ -400,7 +712,7 @@ mod tests {
         let tenant_id = TenantId::new("test-tenant".to_string()).unwrap();
         
         // Create in-memory SQLite backend
-        let mut backend = SQLiteBackend::new("sqlite://:memory:".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
         backend.initialize().await.unwrap();
         
         // Set up isolation and quota
@@ -445,6 +757,216 @@ mod tests {
         assert!(metrics.is_performance_target_met());
     }
     
+    #[tokio::test]
+    async fn test_bursting_past_the_write_rate_is_throttled_with_a_retry_hint() {
+        let tenant_id = TenantId::new("rate-limited".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let isolation = Arc::new(TenantIsolation::new());
+        isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+        let quota = Arc::new(
+            TenantQuota::new(tenant_id.clone(), ResourceLimits::default())
+                .with_write_rate_limit(Some(WriteRateLimit { events_per_second: 10.0, burst: 5 })),
+        );
+        let storage = TenantAwareEventStorage::new(tenant_id, Arc::new(backend), isolation, quota.clone());
+
+        let event = |version: i64| Event::new(
+            "metered".to_string(),
+            "Meter".to_string(),
+            "Ticked".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({"tick": version})),
+        );
+
+        storage.save_events((1..=5).map(event).collect()).await.unwrap();
+        let err = storage.save_events(vec![event(6)]).await.unwrap_err();
+        assert!(matches!(&err, EventualiError::QuotaExceeded(exceeded) if exceeded.quota == "write_rate" && exceeded.retry_after.is_some()));
+        assert!(err.to_string().contains("retry after"));
+        assert_eq!(storage.load_events(&"metered".to_string(), None).await.unwrap().len(), 5);
+
+        let throttled = quota.check_write_rate(1).unwrap_err();
+        let retry_after = throttled.retry_after.expect("throttled writes carry a retry hint");
+        assert!(retry_after > std::time::Duration::ZERO);
+        assert!(retry_after <= std::time::Duration::from_millis(100));
+
+        tokio::time::sleep(retry_after + std::time::Duration::from_millis(10)).await;
+        storage.save_events(vec![event(6)]).await.unwrap();
+
+        // A batch larger than the burst can never be admitted, so it gets no hint
+        assert_eq!(quota.check_write_rate(6).unwrap_err().retry_after, None);
+    }
+
+    #[tokio::test]
+    async fn test_only_writes_that_land_use_up_the_write_rate() {
+        let tenant_id = TenantId::new("rate-refunded".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let isolation = Arc::new(TenantIsolation::new());
+        isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+        let quota = Arc::new(
+            TenantQuota::new(tenant_id.clone(), ResourceLimits::default())
+                .with_write_rate_limit(Some(WriteRateLimit { events_per_second: 0.001, burst: 3 })),
+        );
+        let storage = TenantAwareEventStorage::new(tenant_id, Arc::new(backend), isolation, quota.clone());
+
+        let event = |version: i64| Event::new(
+            "refunded".to_string(),
+            "Meter".to_string(),
+            "Ticked".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({"tick": version})),
+        );
+        storage.save_events(vec![event(1)]).await.unwrap();
+
+        // A version conflict rejected by the backend gives its admission back
+        for _ in 0..3 {
+            storage.save_events(vec![event(1)]).await.unwrap_err();
+        }
+
+        // Peeking never deducts
+        quota.peek_write_rate(2).unwrap();
+        quota.peek_write_rate(2).unwrap();
+        storage.save_events(vec![event(2), event(3)]).await.unwrap();
+        assert_eq!(quota.peek_write_rate(1).unwrap_err().quota, "write_rate");
+
+        quota.refund_write_rate(1);
+        quota.peek_write_rate(1).unwrap();
+        quota.record_write_rate(1);
+        assert!(quota.peek_write_rate(1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exceeding_the_daily_event_quota_reports_resource_limit_and_usage() {
+        let tenant_id = TenantId::new("daily-capped".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let isolation = Arc::new(TenantIsolation::new());
+        isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+        let limits = ResourceLimits { max_events_per_day: Some(4), ..ResourceLimits::default() };
+        let quota = Arc::new(TenantQuota::new(tenant_id.clone(), limits));
+        let failed_writes = Arc::new(FailedWriteLog::open(":memory:", None).unwrap());
+        let storage = TenantAwareEventStorage::new(tenant_id.clone(), Arc::new(backend), isolation, quota)
+            .with_failed_write_log(failed_writes.clone());
+
+        let event = |version: i64| Event::new(
+            "capped".to_string(),
+            "Counter".to_string(),
+            "Incremented".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({"n": version})),
+        );
+        storage.save_events((1..=3).map(event).collect()).await.unwrap();
+
+        let err = storage.save_events(vec![event(4), event(5)]).await.unwrap_err();
+        let EventualiError::QuotaExceeded(exceeded) = &err else {
+            panic!("expected a quota error, got {err}");
+        };
+        assert_eq!(exceeded.tenant_id, tenant_id);
+        assert_eq!(exceeded.resource_type, ResourceType::Events);
+        assert_eq!(exceeded.quota, "daily_events");
+        assert_eq!((exceeded.limit, exceeded.current_usage, exceeded.attempted), (4, 3, 2));
+        // The daily quota does not replenish within a retry loop
+        assert_eq!(exceeded.retry_after, None);
+        assert!(!err.is_retryable());
+        assert_eq!(err.kind(), "QuotaExceeded");
+        
+        // The quota hit is in the failed-write log under the tenant's namespace
+        let failed = failed_writes.query(&crate::store::FailedWriteFilter::default(), 10).unwrap();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].aggregate_id, format!("{}:capped", tenant_id.db_prefix()));
+        assert_eq!(failed[0].attempted_versions, vec![4, 5]);
+        assert_eq!(failed[0].error_kind, "QuotaExceeded");
+    }
+
+    #[tokio::test]
+    async fn test_correlation_lookup_limit_counts_only_the_tenants_own_events() {
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let backend: Arc<dyn EventStoreBackend + Send + Sync> = Arc::new(backend);
+        let isolation = Arc::new(TenantIsolation::new());
+        let tenant_storage = |name: &str| {
+            let tenant_id = TenantId::new(name.to_string()).unwrap();
+            isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+            let quota = Arc::new(TenantQuota::new(tenant_id.clone(), ResourceLimits::default()));
+            TenantAwareEventStorage::new(tenant_id, backend.clone(), isolation.clone(), quota)
+        };
+        let busy = tenant_storage("busy-tenant");
+        let quiet = tenant_storage("quiet-tenant");
+
+        let correlation_id = uuid::Uuid::new_v4();
+        let correlated = |aggregate_id: String| {
+            Event::new(
+                aggregate_id,
+                "Order".to_string(),
+                "OrderPlaced".to_string(),
+                1,
+                1,
+                EventData::Json(serde_json::json!({})),
+            )
+            .with_metadata(crate::EventMetadata { correlation_id: Some(correlation_id), ..Default::default() })
+        };
+
+        // The other tenant's events come first in global order
+        busy.save_events((0..5).map(|i| correlated(format!("order-{i}"))).collect()).await.unwrap();
+        quiet.save_events((0..3).map(|i| correlated(format!("order-{i}"))).collect()).await.unwrap();
+
+        let page = quiet.get_events_by_correlation_id(&correlation_id.to_string(), 2).await.unwrap();
+        let ids: Vec<&str> = page.iter().map(|event| event.aggregate_id.as_str()).collect();
+        assert_eq!(ids, ["order-0", "order-1"]);
+
+        let scoped = quiet
+            .get_events_by_correlation_id_with_prefix(&correlation_id.to_string(), "order-2", 10)
+            .await
+            .unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].aggregate_id, "order-2");
+    }
+
+    #[tokio::test]
+    async fn test_tenant_subscriptions_see_the_tenants_saves_within_its_stream_limit() {
+        let tenant_id = TenantId::new("streaming-storage-tenant".to_string()).unwrap();
+        let mut backend = SQLiteBackend::new(&EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+        backend.initialize().await.unwrap();
+        let isolation = Arc::new(TenantIsolation::new());
+        isolation.register_tenant(tenant_id.clone(), IsolationPolicy::strict()).unwrap();
+        let limits = ResourceLimits { max_concurrent_streams: Some(1), ..ResourceLimits::default() };
+        let quota = Arc::new(TenantQuota::new(tenant_id.clone(), limits));
+        let mut storage = TenantAwareEventStorage::new(tenant_id, Arc::new(backend), isolation, quota.clone());
+        storage.set_event_streamer(Arc::new(crate::streaming::InMemoryEventStreamer::new(16)));
+
+        let subscription = |id: &str| Subscription {
+            id: id.to_string(),
+            aggregate_type_filter: None,
+            event_type_filter: None,
+            from_timestamp: None,
+            replay_rate_limit: None,
+        };
+        let mut open = storage.open_subscription(subscription("first")).await.unwrap();
+        let Err(err) = storage.open_subscription(subscription("second")).await else {
+            panic!("a second subscription should exceed the stream limit");
+        };
+        assert!(matches!(&err, EventualiError::QuotaExceeded(exceeded) if exceeded.quota == "concurrent_streams"));
+
+        storage.save_events(vec![Event::new(
+            "streamed".to_string(),
+            "Counter".to_string(),
+            "Incremented".to_string(),
+            1,
+            1,
+            EventData::Json(serde_json::json!({"n": 1})),
+        )]).await.unwrap();
+        let received = open.receiver().recv().await.unwrap();
+        assert_eq!(received.event.aggregate_id, "streamed");
+        assert_eq!(received.stream_position, 1);
+
+        drop(open);
+        assert_eq!(quota.active_streams(), 0);
+        let _second = storage.open_subscription(subscription("second")).await.unwrap();
+    }
+
     #[test]
     fn test_tenant_event_batch() {
         let tenant_id = TenantId::new("batch-test".to_string()).unwrap();
@@ -0,0 +1,217 @@
+//! Tenant-scoped event streaming
+//!
+//! Wraps an `EventStreamer` so every subscription a tenant opens counts
+//! against its `max_concurrent_streams` limit until it is closed.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::streaming::{EventStreamReceiver, EventStreamer, Subscription};
+use crate::Event;
+use super::quota::TenantQuota;
+use super::tenant::TenantId;
+
+/// Event streamer that enforces a tenant's concurrent stream limit.
+///
+/// A subscription holds one of the tenant's stream slots from `subscribe`
+/// until `unsubscribe`. Subscriptions opened with `open` also give their slot
+/// back when the returned `TenantSubscription` is dropped.
+pub struct TenantEventStreamer {
+    tenant_id: TenantId,
+    inner: Arc<dyn EventStreamer + Send + Sync>,
+    quota: Arc<TenantQuota>,
+    active: Arc<Mutex<HashSet<String>>>,
+}
+
+impl TenantEventStreamer {
+    pub fn new(
+        tenant_id: TenantId,
+        inner: Arc<dyn EventStreamer + Send + Sync>,
+        quota: Arc<TenantQuota>,
+    ) -> Self {
+        Self {
+            tenant_id,
+            inner,
+            quota,
+            active: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    pub fn tenant_id(&self) -> &TenantId {
+        &self.tenant_id
+    }
+
+    /// Subscriptions currently open through this streamer
+    pub fn active_subscriptions(&self) -> usize {
+        self.active.lock().unwrap().len()
+    }
+
+    /// Subscribe and return a handle that closes the subscription, freeing its
+    /// stream slot, when dropped
+    pub async fn open(&self, subscription: Subscription) -> Result<TenantSubscription> {
+        let subscription_id = subscription.id.clone();
+        let receiver = self.subscribe(subscription).await?;
+        Ok(TenantSubscription {
+            subscription_id,
+            receiver,
+            inner: self.inner.clone(),
+            quota: self.quota.clone(),
+            active: self.active.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EventStreamer for TenantEventStreamer {
+    async fn subscribe(&self, subscription: Subscription) -> Result<EventStreamReceiver> {
+        // Re-subscribing under an open ID replaces the subscription and keeps its slot
+        let subscription_id = subscription.id.clone();
+        let slot = {
+            let mut active = self.active.lock().unwrap();
+            if active.contains(&subscription_id) {
+                None
+            } else {
+                self.quota.try_acquire_stream()?;
+                active.insert(subscription_id.clone());
+                Some(StreamSlot {
+                    subscription_id,
+                    quota: self.quota.clone(),
+                    active: self.active.clone(),
+                    kept: false,
+                })
+            }
+        };
+
+        // The slot goes back if the inner subscribe fails or this future is dropped
+        let receiver = self.inner.subscribe(subscription).await?;
+        if let Some(mut slot) = slot {
+            slot.kept = true;
+        }
+        Ok(receiver)
+    }
+
+    async fn unsubscribe(&self, subscription_id: &str) -> Result<()> {
+        self.inner.unsubscribe(subscription_id).await?;
+        if self.active.lock().unwrap().remove(subscription_id) {
+            self.quota.release_stream();
+        }
+        Ok(())
+    }
+
+    async fn publish_event(&self, event: Event, stream_position: u64, global_position: u64) -> Result<()> {
+        self.inner.publish_event(event, stream_position, global_position).await
+    }
+
+    async fn get_stream_position(&self, stream_id: &str) -> Result<Option<u64>> {
+        self.inner.get_stream_position(stream_id).await
+    }
+
+    async fn get_global_position(&self) -> Result<u64> {
+        self.inner.get_global_position().await
+    }
+}
+
+/// A stream slot taken for a subscription that is still being opened
+struct StreamSlot {
+    subscription_id: String,
+    quota: Arc<TenantQuota>,
+    active: Arc<Mutex<HashSet<String>>>,
+    /// Set once the subscription is open; the slot is then held until it closes
+    kept: bool,
+}
+
+impl Drop for StreamSlot {
+    fn drop(&mut self) {
+        if !self.kept && self.active.lock().unwrap().remove(&self.subscription_id) {
+            self.quota.release_stream();
+        }
+    }
+}
+
+/// An open tenant subscription; dropping it unsubscribes and frees its slot
+pub struct TenantSubscription {
+    subscription_id: String,
+    receiver: EventStreamReceiver,
+    inner: Arc<dyn EventStreamer + Send + Sync>,
+    quota: Arc<TenantQuota>,
+    active: Arc<Mutex<HashSet<String>>>,
+}
+
+impl TenantSubscription {
+    pub fn id(&self) -> &str {
+        &self.subscription_id
+    }
+
+    pub fn receiver(&mut self) -> &mut EventStreamReceiver {
+        &mut self.receiver
+    }
+}
+
+impl Drop for TenantSubscription {
+    fn drop(&mut self) {
+        // Already closed through `unsubscribe`
+        if !self.active.lock().unwrap().remove(&self.subscription_id) {
+            return;
+        }
+        self.quota.release_stream();
+
+        let inner = self.inner.clone();
+        let subscription_id = self.subscription_id.clone();
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            runtime.spawn(async move {
+                if let Err(e) = inner.unsubscribe(&subscription_id).await {
+                    tracing::warn!(%subscription_id, error = %e, "Failed to unsubscribe dropped tenant subscription");
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::EventualiError;
+    use crate::streaming::InMemoryEventStreamer;
+    use crate::tenancy::tenant::ResourceLimits;
+
+    fn subscription(id: &str) -> Subscription {
+        Subscription {
+            id: id.to_string(),
+            aggregate_type_filter: None,
+            event_type_filter: None,
+            from_timestamp: None,
+            replay_rate_limit: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscriptions_beyond_the_stream_limit_are_rejected_until_one_closes() {
+        let tenant_id = TenantId::new("streaming-tenant".to_string()).unwrap();
+        let limits = ResourceLimits { max_concurrent_streams: Some(2), ..ResourceLimits::default() };
+        let quota = Arc::new(TenantQuota::new(tenant_id.clone(), limits));
+        let streamer = TenantEventStreamer::new(
+            tenant_id,
+            Arc::new(InMemoryEventStreamer::new(16)),
+            quota.clone(),
+        );
+
+        let _first = streamer.subscribe(subscription("first")).await.unwrap();
+        let second = streamer.open(subscription("second")).await.unwrap();
+        assert_eq!(quota.active_streams(), 2);
+
+        let err = streamer.subscribe(subscription("third")).await.unwrap_err();
+        assert!(matches!(&err, EventualiError::QuotaExceeded(exceeded) if exceeded.quota == "concurrent_streams"));
+        assert_eq!(quota.active_streams(), 2);
+
+        streamer.unsubscribe("first").await.unwrap();
+        let _third = streamer.subscribe(subscription("third")).await.unwrap();
+        assert_eq!(quota.active_streams(), 2);
+
+        drop(second);
+        assert_eq!(quota.active_streams(), 1);
+        let _fourth = streamer.subscribe(subscription("fourth")).await.unwrap();
+        assert_eq!(streamer.active_subscriptions(), 2);
+    }
+}
@@ -70,7 +70,7 @@ pub struct TenantConfig {
 impl Default for TenantConfig {
     fn default() -> Self {
         Self {
-            isolation_level: IsolationLevel::Database,
+            isolation_level: IsolationLevel::default(),
             resource_limits: ResourceLimits::default(),
             encryption_enabled: true,
             audit_enabled: true,
@@ -80,9 +80,10 @@ impl Default for TenantConfig {
 }
 
 /// Tenant isolation levels
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum IsolationLevel {
     /// Database-level isolation with separate schemas/tables
+    #[default]
     Database,
     /// Application-level isolation with tenant filtering
     Application,
@@ -90,6 +91,31 @@ pub enum IsolationLevel {
     Row,
 }
 
+impl IsolationLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IsolationLevel::Database => "database",
+            IsolationLevel::Application => "application",
+            IsolationLevel::Row => "row",
+        }
+    }
+}
+
+impl FromStr for IsolationLevel {
+    type Err = TenantError;
+    
+    /// Parse a lowercase isolation level name; unknown names are rejected rather
+    /// than falling back to a default, since a typo must not change isolation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "database" => Ok(IsolationLevel::Database),
+            "application" => Ok(IsolationLevel::Application),
+            "row" => Ok(IsolationLevel::Row),
+            other => Err(TenantError::InvalidIsolationLevel(other.to_string())),
+        }
+    }
+}
+
 /// Resource limits for a tenant
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceLimits {
@@ -220,6 +246,9 @@ pub enum TenantError {
         limit_type: String,
     },
     
+    #[error("Invalid isolation level '{0}': expected database, application or row")]
+    InvalidIsolationLevel(String),
+    
     #[error("Tenant isolation violation: {0}")]
     IsolationViolation(String),
     
@@ -250,6 +279,25 @@ mod tests {
         assert!(TenantId::new("a".repeat(129)).is_err());
     }
     
+    #[test]
+    fn test_isolation_level_parsing_rejects_unknown_values() {
+        assert_eq!("database".parse::<IsolationLevel>().unwrap(), IsolationLevel::Database);
+        assert_eq!("application".parse::<IsolationLevel>().unwrap(), IsolationLevel::Application);
+        assert_eq!("row".parse::<IsolationLevel>().unwrap(), IsolationLevel::Row);
+        
+        for invalid in ["databse", "Database", "", "tenant"] {
+            assert!(matches!(
+                invalid.parse::<IsolationLevel>(),
+                Err(TenantError::InvalidIsolationLevel(value)) if value == invalid
+            ));
+        }
+        
+        for level in [IsolationLevel::Database, IsolationLevel::Application, IsolationLevel::Row] {
+            assert_eq!(level.as_str().parse::<IsolationLevel>().unwrap(), level);
+        }
+        assert_eq!(TenantConfig::default().isolation_level, IsolationLevel::Database);
+    }
+    
     #[test]
     fn test_tenant_info_creation() {
         let tenant_id = TenantId::new("test-tenant".to_string()).unwrap();
@@ -0,0 +1,159 @@
+//! Test utilities for code built on eventuali
+//!
+//! Projections are rebuilt by replay and receive duplicates whenever a
+//! subscription restarts from its last checkpoint, so a projection that is not
+//! idempotent drifts from the truth in ways ordinary tests rarely exercise.
+//! These helpers let downstream crates check that property for their own
+//! projections.
+
+use crate::streaming::Projection;
+use crate::{Event, EventualiError, Result};
+
+/// Check that delivering `events` a second time leaves `projection` in the
+/// state a single delivery produced.
+///
+/// The projection is reset, fed every event, and its state captured through
+/// `Projection::snapshot_state`. Every event is then delivered again, as a
+/// subscription restarted from the beginning would, and the state captured
+/// again. Events are handled strictly in order, so the check is deterministic.
+///
+/// Returns `InvalidState` describing both states when they differ, and
+/// `Configuration` when the projection does not expose its state.
+pub async fn check_projection_idempotent<P>(projection: &P, events: &[Event]) -> Result<()>
+where
+    P: Projection + Sync + ?Sized,
+{
+    projection.reset().await?;
+    for event in events {
+        projection.handle_event(event).await?;
+    }
+    let once = capture_state(projection).await?;
+
+    for event in events {
+        projection.handle_event(event).await?;
+    }
+    let twice = capture_state(projection).await?;
+
+    if once != twice {
+        return Err(EventualiError::InvalidState(format!(
+            "Projection is not idempotent: reprocessing {} events changed its state from {} to {}",
+            events.len(),
+            String::from_utf8_lossy(&once),
+            String::from_utf8_lossy(&twice),
+        )));
+    }
+    Ok(())
+}
+
+/// Panic unless `projection` is idempotent over `events`, as checked by
+/// [`check_projection_idempotent`]
+pub async fn assert_projection_idempotent<P>(projection: &P, events: &[Event])
+where
+    P: Projection + Sync + ?Sized,
+{
+    if let Err(e) = check_projection_idempotent(projection, events).await {
+        panic!("{e}");
+    }
+}
+
+async fn capture_state<P>(projection: &P) -> Result<Vec<u8>>
+where
+    P: Projection + Sync + ?Sized,
+{
+    projection.snapshot_state().await?.ok_or_else(|| {
+        EventualiError::Configuration(
+            "Idempotency checks need a projection that exposes its state through snapshot_state".to_string(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventData;
+    use async_trait::async_trait;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    /// Balances per account. Idempotent when it remembers applied event IDs.
+    struct Balances {
+        dedupe: bool,
+        state: Mutex<(BTreeMap<String, i64>, Vec<uuid::Uuid>)>,
+    }
+
+    impl Balances {
+        fn new(dedupe: bool) -> Self {
+            Self { dedupe, state: Mutex::new(Default::default()) }
+        }
+    }
+
+    #[async_trait]
+    impl Projection for Balances {
+        async fn handle_event(&self, event: &Event) -> Result<()> {
+            let mut state = self.state.lock().unwrap();
+            if self.dedupe {
+                if state.1.contains(&event.id) {
+                    return Ok(());
+                }
+                state.1.push(event.id);
+            }
+            let amount = match &event.data {
+                EventData::Json(data) => data["amount"].as_i64().unwrap_or_default(),
+                EventData::Protobuf(_) => 0,
+            };
+            *state.0.entry(event.aggregate_id.clone()).or_default() += amount;
+            Ok(())
+        }
+
+        async fn reset(&self) -> Result<()> {
+            *self.state.lock().unwrap() = Default::default();
+            Ok(())
+        }
+
+        async fn get_last_processed_position(&self) -> Result<Option<u64>> {
+            Ok(None)
+        }
+
+        async fn set_last_processed_position(&self, _position: u64) -> Result<()> {
+            Ok(())
+        }
+
+        async fn snapshot_state(&self) -> Result<Option<Vec<u8>>> {
+            Ok(Some(serde_json::to_vec(&self.state.lock().unwrap().0)?))
+        }
+    }
+
+    fn deposits() -> Vec<Event> {
+        (1..=3)
+            .map(|version| {
+                Event::new(
+                    "account-1".to_string(),
+                    "Account".to_string(),
+                    "Deposited".to_string(),
+                    1,
+                    version,
+                    EventData::Json(serde_json::json!({ "amount": 10 })),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_projection_passes() {
+        assert_projection_idempotent(&Balances::new(true), &deposits()).await;
+    }
+
+    #[tokio::test]
+    async fn test_non_idempotent_projection_fails_with_both_states() {
+        let err = check_projection_idempotent(&Balances::new(false), &deposits()).await.unwrap_err();
+
+        assert!(matches!(&err, EventualiError::InvalidState(msg)
+            if msg.contains(r#"{"account-1":30}"#) && msg.contains(r#"{"account-1":60}"#)));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Projection is not idempotent")]
+    async fn test_assert_panics_on_non_idempotent_projection() {
+        assert_projection_idempotent(&Balances::new(false), &deposits()).await;
+    }
+}
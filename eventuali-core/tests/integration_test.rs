@@ -1,8 +1,19 @@
 use eventuali_core::{
     Event, EventData, EventMetadata, Aggregate, 
-    EventStoreConfig, create_event_store,
+    EventStoreConfig, EventualiError, create_event_store, create_event_store_with_codecs,
+    Codec, CodecRegistry, EventStore, EventStoreImpl, FixedClock, MemoryBackend, StoreStats, STORAGE_FORMAT_VERSION,
+    TimestampSource, EventIdKind, default_event_id_kind, IdAllocator,
+    ReadModelProcessor, ReadModelProjection, ReadModelSink, ReadModelWrite, SqliteReadModelSink,
+    OutboxRelay, Compactor, ConflictResolution, StoreMigration, ThroughputGovernor, FailedWriteFilter,
+    with_operation_timeout, SQLiteBackend, StreamAnomaly, StreamValidation, OversizedBatch,
+    BackendOperation, FaultInjectingBackend, FaultInjector, InjectedFault,
+    DeleteMode, TOMBSTONE_EVENT_TYPE,
+    store::EventStoreBackend,
+    streaming::{EventStreamer, InMemoryEventStreamer, SubscriptionBuilder},
 };
-use tokio;
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -70,10 +81,2243 @@ async fn test_aggregate_creation() {
 
 #[test]
 fn test_event_metadata() {
-    let mut metadata = EventMetadata::default();
-    metadata.user_id = Some("user-123".to_string());
+    let mut metadata = EventMetadata {
+        user_id: Some("user-123".to_string()),
+        ..Default::default()
+    };
     metadata.headers.insert("source".to_string(), "web-app".to_string());
     
     assert_eq!(metadata.user_id, Some("user-123".to_string()));
     assert_eq!(metadata.headers.get("source"), Some(&"web-app".to_string()));
-}
\ No newline at end of file
+}
+
+#[tokio::test]
+async fn test_same_timestamp_events_load_in_stable_order() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string());
+    let store = create_event_store(config).await.unwrap();
+
+    let timestamp = chrono::Utc::now();
+    let aggregate_id = Uuid::new_v4().to_string();
+
+    // Insert versions out of order; all events share one timestamp.
+    let mut events = Vec::new();
+    for version in [3, 1, 5, 2, 4] {
+        let mut event = Event::new(
+            aggregate_id.clone(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::from_json(&serde_json::json!({ "version": version })).unwrap(),
+        );
+        event.timestamp = timestamp;
+        events.push(event);
+    }
+    store.save_events(events).await.unwrap();
+
+    for _ in 0..5 {
+        let loaded = store.load_events(&aggregate_id, None).await.unwrap();
+        let versions: Vec<i64> = loaded.iter().map(|e| e.aggregate_version).collect();
+        assert_eq!(versions, vec![1, 2, 3, 4, 5]);
+    }
+
+    // Across aggregates, same-timestamp events come back in commit order.
+    let other_ids: Vec<String> = (0..5).map(|_| Uuid::new_v4().to_string()).collect();
+    for other_id in &other_ids {
+        let mut event = Event::new(
+            other_id.clone(),
+            "Invoice".to_string(),
+            "InvoiceIssued".to_string(),
+            1,
+            1,
+            EventData::from_json(&serde_json::json!({})).unwrap(),
+        );
+        event.timestamp = timestamp;
+        store.save_events(vec![event]).await.unwrap();
+    }
+
+    for _ in 0..5 {
+        let loaded = store.load_events_by_type("Invoice", None).await.unwrap();
+        let ids: Vec<String> = loaded.iter().map(|e| e.aggregate_id.clone()).collect();
+        assert_eq!(ids, other_ids);
+    }
+}
+
+#[tokio::test]
+async fn test_save_past_max_aggregate_version_is_rejected() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_max_aggregate_version(2);
+    let store = create_event_store(config).await.unwrap();
+
+    let aggregate_id = Uuid::new_v4().to_string();
+    let make_event = |version: i64| {
+        Event::new(
+            aggregate_id.clone(),
+            "Counter".to_string(),
+            "Incremented".to_string(),
+            1,
+            version,
+            EventData::from_json(&serde_json::json!({ "by": 1 })).unwrap(),
+        )
+    };
+
+    store.save_events(vec![make_event(1), make_event(2)]).await.unwrap();
+
+    let result = store.save_events(vec![make_event(3)]).await;
+    assert!(matches!(result, Err(EventualiError::Validation(_))));
+
+    // Nothing past the limit was written.
+    let version = store.get_aggregate_version(&aggregate_id).await.unwrap();
+    assert_eq!(version, Some(2));
+}
+
+/// Stores JSON payloads with their bytes XOR-ed, so raw rows are unreadable without it.
+struct XorJsonCodec;
+
+impl Codec for XorJsonCodec {
+    fn name(&self) -> &str {
+        "xor-json"
+    }
+
+    fn encode(&self, data: &EventData) -> eventuali_core::Result<Vec<u8>> {
+        let bytes = serde_json::to_vec(&data.to_json::<serde_json::Value>()?)?;
+        Ok(bytes.into_iter().map(|b| b ^ 0x5a).collect())
+    }
+
+    fn decode(&self, bytes: &[u8]) -> eventuali_core::Result<EventData> {
+        let bytes: Vec<u8> = bytes.iter().map(|b| b ^ 0x5a).collect();
+        Ok(EventData::Json(serde_json::from_slice(&bytes)?))
+    }
+}
+
+#[tokio::test]
+async fn test_custom_codec_round_trips_and_loads_by_stored_name() {
+    let mut registry = CodecRegistry::new();
+    registry.register(XorJsonCodec).unwrap();
+    let registry = Arc::new(registry);
+
+    let db_path = std::env::temp_dir().join(format!("eventuali-codec-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+
+    let aggregate_id = Uuid::new_v4().to_string();
+    let payload = serde_json::json!({ "sku": "A-1", "quantity": 3 });
+    {
+        let config = EventStoreConfig::sqlite(db_path.clone()).with_codec("xor-json".to_string());
+        let store = create_event_store_with_codecs(config, registry.clone()).await.unwrap();
+        let event = Event::new(
+            aggregate_id.clone(),
+            "Cart".to_string(),
+            "ItemAdded".to_string(),
+            1,
+            1,
+            EventData::Json(payload.clone()),
+        );
+        store.save_events(vec![event]).await.unwrap();
+
+        let loaded = store.load_events(&aggregate_id, None).await.unwrap();
+        assert_eq!(loaded[0].data, EventData::Json(payload.clone()));
+    }
+
+    // A store writing the default format still decodes rows tagged with the codec
+    let reader = create_event_store_with_codecs(EventStoreConfig::sqlite(db_path.clone()), registry)
+        .await
+        .unwrap();
+    let loaded = reader.load_events(&aggregate_id, None).await.unwrap();
+    assert_eq!(loaded[0].data, EventData::Json(payload));
+
+    // Without the codec registered the row cannot be decoded
+    let unaware = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    let result = unaware.load_events(&aggregate_id, None).await;
+    assert!(matches!(result, Err(EventualiError::InvalidEventData(_))));
+
+    // Selecting an unregistered codec is a configuration error
+    let config = EventStoreConfig::sqlite(":memory:".to_string()).with_codec("missing".to_string());
+    let result = create_event_store(config).await;
+    assert!(matches!(result, Err(EventualiError::Configuration(_))));
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+#[tokio::test]
+async fn test_stream_all_yields_every_event_in_global_order() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string());
+    let store = create_event_store(config).await.unwrap();
+
+    // Interleave three aggregates so global order differs from per-aggregate order
+    let aggregate_ids: Vec<String> = (0..3).map(|_| Uuid::new_v4().to_string()).collect();
+    let mut expected = Vec::new();
+    for version in 1..=9 {
+        for aggregate_id in &aggregate_ids {
+            let event = Event::new(
+                aggregate_id.clone(),
+                "Account".to_string(),
+                "Deposited".to_string(),
+                1,
+                version,
+                EventData::from_json(&serde_json::json!({ "amount": version })).unwrap(),
+            );
+            expected.push(event.id);
+            store.save_events(vec![event]).await.unwrap();
+        }
+    }
+
+    // A batch size that does not divide the total exercises the final short page
+    let streamed: Vec<Event> = store
+        .stream_all(0, 4)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(streamed.len(), 27);
+    assert_eq!(streamed.iter().map(|e| e.id).collect::<Vec<_>>(), expected);
+
+    // Resuming from a global position skips everything up to and including it
+    let resumed: Vec<Event> = store
+        .stream_all(20, 4)
+        .map(|event| event.unwrap())
+        .collect()
+        .await;
+    assert_eq!(resumed.iter().map(|e| e.id).collect::<Vec<_>>(), expected[20..].to_vec());
+}
+
+#[tokio::test]
+async fn test_list_aggregate_types_returns_distinct_sorted_types() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string());
+    let store = create_event_store(config).await.unwrap();
+
+    assert!(store.list_aggregate_types().await.unwrap().is_empty());
+
+    for aggregate_type in ["User", "Order", "Invoice", "Order", "User", "Account"] {
+        let event = Event::new(
+            Uuid::new_v4().to_string(),
+            aggregate_type.to_string(),
+            "Created".to_string(),
+            1,
+            1,
+            EventData::from_json(&serde_json::json!({})).unwrap(),
+        );
+        store.save_events(vec![event]).await.unwrap();
+    }
+
+    assert_eq!(
+        store.list_aggregate_types().await.unwrap(),
+        vec!["Account", "Invoice", "Order", "User"]
+    );
+}
+
+#[tokio::test]
+async fn test_server_assigned_timestamps_overwrite_client_values() {
+    let bogus = chrono::DateTime::parse_from_rfc3339("1999-12-31T23:59:59Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    let new_event = |aggregate_id: &str| {
+        let mut event = Event::new(
+            aggregate_id.to_string(),
+            "Account".to_string(),
+            "Opened".to_string(),
+            1,
+            1,
+            EventData::from_json(&serde_json::json!({})).unwrap(),
+        );
+        event.timestamp = bogus;
+        event
+    };
+
+    // Through config, saves are stamped with the system clock
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_timestamp_source(TimestampSource::ServerAssigned);
+    let store = create_event_store(config).await.unwrap();
+    let before = chrono::Utc::now();
+    store.save_events(vec![new_event("server")]).await.unwrap();
+    let loaded = store.load_events(&"server".to_string(), None).await.unwrap();
+    assert!(loaded[0].timestamp >= before);
+
+    // Client mode remains the default and keeps the caller's value
+    let client_store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    client_store.save_events(vec![new_event("client")]).await.unwrap();
+    let loaded = client_store.load_events(&"client".to_string(), None).await.unwrap();
+    assert_eq!(loaded[0].timestamp, bogus);
+
+    // A custom clock decides the assigned value
+    let now = chrono::Utc::now();
+    let clock_store = EventStoreImpl::new(MemoryBackend::new())
+        .with_timestamp_source(TimestampSource::ServerAssigned)
+        .with_clock(Arc::new(FixedClock::new(now)));
+    clock_store.save_events(vec![new_event("fixed")]).await.unwrap();
+    let loaded = clock_store.load_events(&"fixed".to_string(), None).await.unwrap();
+    assert_eq!(loaded[0].timestamp, now);
+}
+
+#[tokio::test]
+async fn test_stats_match_seeded_dataset() {
+    let sqlite_store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let memory_store = EventStoreImpl::new(MemoryBackend::new());
+    let stores: [&(dyn EventStore + Send + Sync); 2] = [sqlite_store.as_ref(), &memory_store];
+
+    for store in stores {
+        assert_eq!(store.stats().await.unwrap(), StoreStats::default());
+
+        // 3 users with 4 events each, 2 orders with 2 events each
+        let seeded = [
+            ("user", "User", 3, 4, ["Registered", "Renamed"]),
+            ("order", "Order", 2, 2, ["Placed", "Shipped"]),
+        ];
+        for (prefix, aggregate_type, aggregates, per_aggregate, event_types) in seeded {
+            for n in 0..aggregates {
+                let events = (1..=per_aggregate)
+                    .map(|version| {
+                        Event::new(
+                            format!("{prefix}-{n}"),
+                            aggregate_type.to_string(),
+                            event_types[(version as usize - 1) % 2].to_string(),
+                            1,
+                            version,
+                            EventData::from_json(&serde_json::json!({ "version": version })).unwrap(),
+                        )
+                    })
+                    .collect();
+                store.save_events(events).await.unwrap();
+            }
+        }
+
+        let stats = store.stats().await.unwrap();
+        assert_eq!(stats.total_events, 16);
+        assert_eq!(stats.total_aggregates, 5);
+        assert_eq!(stats.aggregate_types, 2);
+        assert_eq!(stats.event_types, 4);
+        assert!(stats.estimated_bytes > 16 * 36, "estimate should cover at least the event ids");
+    }
+}
+
+#[tokio::test]
+async fn test_both_event_id_kinds_round_trip() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_event_id_kind(EventIdKind::Ulid);
+    let store = create_event_store(config).await.unwrap();
+    // The kind is the store's own; the process default is untouched
+    assert_eq!(default_event_id_kind(), EventIdKind::UuidV4);
+
+    let aggregate_id = "id-kinds".to_string();
+    let events: Vec<Event> = (0..3)
+        .map(|i| {
+            let event = Event::new(
+                aggregate_id.clone(),
+                "Ledger".to_string(),
+                "EntryPosted".to_string(),
+                1,
+                i as i64 + 1,
+                EventData::Json(serde_json::json!({ "entry": i })),
+            );
+            // The first keeps the process default ID; the rest are the store's ULIDs
+            if i == 0 { event } else { event.with_id(store.new_event_id().unwrap()) }
+        })
+        .collect();
+    let ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+    assert_eq!(ids[0].get_version_num(), 4);
+    assert!(ids[1] < ids[2]);
+    assert!(eventuali_core::ulid_timestamp(&ids[1]).is_some_and(|created| (chrono::Utc::now() - created).num_seconds().abs() < 5));
+
+    store.save_events(events).await.unwrap();
+
+    let loaded = store.load_events(&aggregate_id, None).await.unwrap();
+    let loaded_ids: Vec<Uuid> = loaded.iter().map(|e| e.id).collect();
+    assert_eq!(loaded_ids, ids);
+}
+
+#[tokio::test]
+async fn test_aggregate_locking_serializes_concurrent_appends() {
+    let store = Arc::new(EventStoreImpl::new(MemoryBackend::new()).with_aggregate_locking(true));
+    let aggregate_id = "contended".to_string();
+
+    let writers: Vec<_> = (0..16)
+        .map(|writer| {
+            let store = store.clone();
+            let aggregate_id = aggregate_id.clone();
+            tokio::spawn(async move {
+                store
+                    .append_to_aggregate(&aggregate_id, |current| {
+                        vec![Event::new(
+                            aggregate_id.clone(),
+                            "Counter".to_string(),
+                            "Incremented".to_string(),
+                            1,
+                            current.unwrap_or(0) + 1,
+                            EventData::Json(serde_json::json!({ "writer": writer })),
+                        )]
+                    })
+                    .await
+            })
+        })
+        .collect();
+
+    for writer in writers {
+        writer.await.unwrap().expect("locked appends should never conflict");
+    }
+
+    let versions: Vec<i64> = store
+        .load_events(&aggregate_id, None)
+        .await
+        .unwrap()
+        .iter()
+        .map(|e| e.aggregate_version)
+        .collect();
+    assert_eq!(versions, (1..=16).collect::<Vec<_>>());
+    assert!(store.aggregate_locks().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_archived_events_still_load_across_both_files() {
+    let dir = std::env::temp_dir().join(format!("eventuali-archive-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = EventStoreConfig::sqlite(dir.join("hot.db").to_string_lossy().to_string())
+        .with_archive_path(dir.join("archive.db").to_string_lossy().to_string());
+    let store = create_event_store(config).await.unwrap();
+
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(30);
+    let aggregate_id = "account-1".to_string();
+    let events: Vec<Event> = (1..=6)
+        .map(|version| {
+            let mut event = Event::new(
+                aggregate_id.clone(),
+                "Account".to_string(),
+                "Deposited".to_string(),
+                1,
+                version,
+                EventData::Json(serde_json::json!({ "amount": version })),
+            );
+            // The first four events are old enough to archive
+            if version <= 4 {
+                event.timestamp = cutoff - chrono::Duration::days(10 - version);
+            }
+            event
+        })
+        .collect();
+    store.save_events(events.clone()).await.unwrap();
+
+    assert_eq!(store.archive_events_before(cutoff).await.unwrap(), 4);
+    assert_eq!(store.archive_events_before(cutoff).await.unwrap(), 0);
+
+    let loaded = store.load_events(&aggregate_id, None).await.unwrap();
+    let versions: Vec<i64> = loaded.iter().map(|e| e.aggregate_version).collect();
+    assert_eq!(versions, (1..=6).collect::<Vec<_>>());
+    assert_eq!(loaded[0].id, events[0].id);
+    assert_eq!(store.get_aggregate_version(&aggregate_id).await.unwrap(), Some(6));
+    assert_eq!(store.load_events_by_type("Account", None).await.unwrap().len(), 6);
+    assert_eq!(store.stats().await.unwrap().total_events, 6);
+
+    // Archived versions still count for conflict detection
+    let stale = Event::new(
+        aggregate_id.clone(),
+        "Account".to_string(),
+        "Deposited".to_string(),
+        1,
+        2,
+        EventData::Json(serde_json::json!({ "amount": 0 })),
+    );
+    assert!(matches!(
+        store.save_events(vec![stale]).await,
+        Err(EventualiError::OptimisticConcurrency { expected: 2, actual: 6 })
+    ));
+
+    // New events continue the global order after the archived ones
+    let next = Event::new(
+        aggregate_id.clone(),
+        "Account".to_string(),
+        "Deposited".to_string(),
+        1,
+        7,
+        EventData::Json(serde_json::json!({ "amount": 7 })),
+    );
+    store.save_events(vec![next]).await.unwrap();
+    let positions: Vec<u64> = store
+        .load_events_after_position(0, 100)
+        .await
+        .unwrap()
+        .iter()
+        .map(|(position, _)| *position)
+        .collect();
+    assert_eq!(positions, (1..=7).collect::<Vec<_>>());
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_archiving_requires_an_archive_database() {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let result = store.archive_events_before(chrono::Utc::now()).await;
+    assert!(matches!(result, Err(EventualiError::Configuration(_))));
+}
+
+#[tokio::test]
+async fn test_durable_save_checkpoints_before_returning() {
+    let dir = std::env::temp_dir().join(format!("eventuali-durable-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let db_path = dir.join("events.db");
+    let wal_path = dir.join("events.db-wal");
+    let store = create_event_store(EventStoreConfig::sqlite(db_path.to_string_lossy().to_string()))
+        .await
+        .unwrap();
+
+    let new_event = |version: i64| {
+        Event::new(
+            "ledger-1".to_string(),
+            "Ledger".to_string(),
+            "Posted".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "amount": version })),
+        )
+    };
+
+    // A plain save leaves the commit in the WAL
+    store.save_events(vec![new_event(1)]).await.unwrap();
+    assert!(std::fs::metadata(&wal_path).unwrap().len() > 0);
+
+    // The durable save has checkpointed the WAL into the database by the time it returns
+    store.save_events_durable(vec![new_event(2)]).await.unwrap();
+    assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+
+    let versions: Vec<i64> = store
+        .load_events(&"ledger-1".to_string(), None)
+        .await
+        .unwrap()
+        .iter()
+        .map(|e| e.aggregate_version)
+        .collect();
+    assert_eq!(versions, vec![1, 2]);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_durable_save_reports_a_failed_sync_apart_from_the_save() {
+    let faults = FaultInjector::new().fail_next(
+        BackendOperation::SyncToDisk,
+        1,
+        InjectedFault::Io(std::io::ErrorKind::Other),
+    );
+    let store = EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults));
+    let event = Event::new(
+        "ledger-1".to_string(),
+        "Ledger".to_string(),
+        "Posted".to_string(),
+        1,
+        1,
+        EventData::Json(serde_json::json!({ "amount": 1 })),
+    );
+
+    let error = store.save_events_durable(vec![event]).await.unwrap_err();
+    assert!(matches!(error, EventualiError::NotDurable { .. }), "{error}");
+    assert!(!error.is_retryable());
+
+    // The save itself committed, so the events are there and only the sync needs repeating
+    assert_eq!(store.load_events(&"ledger-1".to_string(), None).await.unwrap().len(), 1);
+    store.sync_to_disk().await.unwrap();
+}
+
+struct AccountBalances;
+
+impl ReadModelProjection for AccountBalances {
+    fn project(&self, event: &Event) -> eventuali_core::Result<Vec<ReadModelWrite>> {
+        Ok(match event.event_type.as_str() {
+            "AccountClosed" => vec![ReadModelWrite::delete(event.aggregate_id.clone())],
+            _ => {
+                let data: serde_json::Value = event.data.to_json()?;
+                vec![ReadModelWrite::upsert(
+                    event.aggregate_id.clone(),
+                    serde_json::json!({ "balance": data["balance"], "version": event.aggregate_version }),
+                )]
+            }
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_read_model_rows_are_queryable_after_processing() {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let new_event = |aggregate_id: &str, event_type: &str, version: i64, balance: i64| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Account".to_string(),
+            event_type.to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "balance": balance })),
+        )
+    };
+    store
+        .save_events(vec![
+            new_event("acc-1", "Deposited", 1, 100),
+            new_event("acc-1", "Withdrawn", 2, 40),
+            new_event("acc-2", "Deposited", 1, 75),
+            new_event("acc-3", "Deposited", 1, 10),
+            new_event("acc-3", "AccountClosed", 2, 0),
+        ])
+        .await
+        .unwrap();
+
+    let db_path = std::env::temp_dir().join(format!("eventuali-read-model-{}.db", Uuid::new_v4()));
+    let options = sqlx::sqlite::SqliteConnectOptions::new()
+        .filename(&db_path)
+        .create_if_missing(true);
+    let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+    let sink = Arc::new(SqliteReadModelSink::new(pool.clone(), Some("account_balances".to_string())));
+    sink.initialize().await.unwrap();
+
+    let processor = ReadModelProcessor::new("balances", AccountBalances, sink.clone());
+    assert_eq!(processor.catch_up(store.as_ref(), 2).await.unwrap(), 5);
+    assert_eq!(sink.checkpoint("balances").await.unwrap(), Some(5));
+
+    assert_eq!(
+        sink.get("balances", "acc-1").await.unwrap(),
+        Some(serde_json::json!({ "balance": 40, "version": 2 }))
+    );
+    assert!(sink.get("balances", "acc-3").await.unwrap().is_none());
+
+    // Rows are plain table rows, queryable without going through the sink
+    let ids: Vec<String> = sqlx::query_scalar(
+        "SELECT entity_id FROM account_balances WHERE projection_name = 'balances' ORDER BY entity_id",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(ids, vec!["acc-1".to_string(), "acc-2".to_string()]);
+
+    // Redelivered events at or before the checkpoint are not applied again
+    let stale = new_event("acc-2", "Deposited", 1, 9_999);
+    assert!(!processor.apply_event(3, &stale).await.unwrap());
+    assert!(!sink
+        .apply("balances", vec![ReadModelWrite::delete("acc-2".to_string())], 5)
+        .await
+        .unwrap());
+    assert_eq!(sink.get("balances", "acc-2").await.unwrap().unwrap()["balance"], 75);
+
+    // A second projection sharing the table keeps its rows through a rebuild of the first
+    let audit = ReadModelProcessor::new("balances_audit", AccountBalances, sink.clone());
+    assert_eq!(audit.catch_up(store.as_ref(), 10).await.unwrap(), 5);
+    sink.clear("balances").await.unwrap();
+    assert!(sink.get("balances", "acc-1").await.unwrap().is_none());
+    assert_eq!(sink.checkpoint("balances").await.unwrap(), None);
+    assert_eq!(sink.get("balances_audit", "acc-1").await.unwrap().unwrap()["balance"], 40);
+    assert_eq!(sink.checkpoint("balances_audit").await.unwrap(), Some(5));
+
+    // A rebuild arrives at the same rows, and then there is nothing new to catch up on
+    assert_eq!(processor.rebuild(store.as_ref(), 10).await.unwrap(), 5);
+    assert_eq!(processor.catch_up(store.as_ref(), 2).await.unwrap(), 0);
+    assert_eq!(sink.get("balances", "acc-1").await.unwrap().unwrap()["balance"], 40);
+    assert_eq!(sink.get("balances_audit", "acc-2").await.unwrap().unwrap()["balance"], 75);
+
+    pool.close().await;
+    std::fs::remove_file(db_path).unwrap();
+}
+
+async fn round_trip_json(data: serde_json::Value) -> serde_json::Value {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let aggregate_id = Uuid::new_v4().to_string();
+    let event = Event::new(
+        aggregate_id.clone(),
+        "Ledger".to_string(),
+        "AmountPosted".to_string(),
+        1,
+        1,
+        EventData::Json(data),
+    );
+    store.save_events(vec![event]).await.unwrap();
+
+    match store.load_events(&aggregate_id, None).await.unwrap().remove(0).data {
+        EventData::Json(value) => value,
+        EventData::Protobuf(_) => panic!("expected JSON data"),
+    }
+}
+
+#[tokio::test]
+async fn test_64_bit_integers_round_trip_exactly() {
+    let data = serde_json::json!({
+        "account_id": u64::MAX,
+        "balance_cents": i64::MIN,
+        "transfer_id": 9_007_199_254_740_993_i64,
+    });
+    let loaded = round_trip_json(data.clone()).await;
+
+    assert_eq!(loaded, data);
+    assert_eq!(loaded["account_id"].as_u64(), Some(u64::MAX));
+    // 2^53 + 1 is the first integer an f64 cannot hold
+    assert_eq!(loaded["transfer_id"].as_i64(), Some(9_007_199_254_740_993));
+}
+
+#[cfg(feature = "arbitrary-precision")]
+#[tokio::test]
+async fn test_high_precision_numbers_round_trip_exactly() {
+    let text = r#"{"rate":0.1234567890123456789012345,"reference":123456789012345678901234567890}"#;
+    let data: serde_json::Value = serde_json::from_str(text).unwrap();
+    let loaded = round_trip_json(data).await;
+
+    assert_eq!(loaded["rate"].to_string(), "0.1234567890123456789012345");
+    assert_eq!(loaded["reference"].to_string(), "123456789012345678901234567890");
+}
+
+#[tokio::test]
+async fn test_outbox_relay_publishes_events_committed_before_a_crash() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-outbox-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+    let config = EventStoreConfig::sqlite(db_path.clone()).with_transactional_outbox(true);
+
+    let aggregate_id = Uuid::new_v4().to_string();
+    {
+        // The process dies after committing, before anything is published
+        let store = create_event_store(config.clone()).await.unwrap();
+        let events: Vec<Event> = (1..=5)
+            .map(|version| Event::new(
+                aggregate_id.clone(),
+                "Order".to_string(),
+                "OrderUpdated".to_string(),
+                1,
+                version,
+                EventData::Json(serde_json::json!({ "version": version })),
+            ))
+            .collect();
+        store.save_events(events).await.unwrap();
+    }
+
+    let store: Arc<dyn EventStore + Send + Sync> = Arc::from(create_event_store(config).await.unwrap());
+    let streamer = Arc::new(InMemoryEventStreamer::new(100));
+    let mut receiver = streamer.subscribe(SubscriptionBuilder::new().build()).await.unwrap();
+    let relay = OutboxRelay::new(store.clone(), streamer.clone()).with_batch_size(2);
+
+    assert_eq!(relay.relay_pending().await.unwrap(), 5);
+    for version in 1..=5 {
+        let received = receiver.recv().await.unwrap();
+        assert_eq!(received.event.aggregate_id, aggregate_id);
+        assert_eq!(received.event.aggregate_version, version);
+        assert_eq!(received.global_position, version as u64);
+    }
+
+    // Published rows are not relayed again
+    assert_eq!(relay.relay_pending().await.unwrap(), 0);
+    assert!(store.load_unpublished_events(10).await.unwrap().is_empty());
+
+    // Stores without an outbox reject outbox reads
+    let plain = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    assert!(matches!(plain.load_unpublished_events(10).await, Err(EventualiError::Configuration(_))));
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+#[tokio::test]
+async fn test_two_writers_on_one_database_never_publish_the_same_position() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-positions-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+
+    // Two store instances with separate pools stand in for two processes
+    let mut writers = Vec::new();
+    let mut receivers = Vec::new();
+    for _ in 0..2 {
+        let mut store = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+        let streamer = Arc::new(InMemoryEventStreamer::new(1000));
+        receivers.push(streamer.subscribe(SubscriptionBuilder::new().build()).await.unwrap());
+        store.set_event_streamer(streamer);
+        writers.push(Arc::<dyn EventStore + Send + Sync>::from(store));
+    }
+
+    let mut handles = Vec::new();
+    for (writer_index, store) in writers.iter().enumerate() {
+        for task in 0..4 {
+            let store = store.clone();
+            handles.push(tokio::spawn(async move {
+                let aggregate_id = format!("writer-{writer_index}-{task}");
+                for version in 1..=10 {
+                    let event = Event::new(
+                        aggregate_id.clone(),
+                        "Counter".to_string(),
+                        "Incremented".to_string(),
+                        1,
+                        version,
+                        EventData::Json(serde_json::json!({ "by": 1 })),
+                    );
+                    store.save_events(vec![event]).await.unwrap();
+                }
+            }));
+        }
+    }
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let mut published = std::collections::HashMap::new();
+    for receiver in &mut receivers {
+        while let Ok(stream_event) = receiver.try_recv() {
+            let previous = published.insert(stream_event.global_position, stream_event.event.id);
+            assert!(previous.is_none(), "position {} published twice", stream_event.global_position);
+        }
+    }
+    assert_eq!(published.len(), 80);
+
+    // Published positions are the ones the database stored
+    let stored = writers[0].load_events_after_position(0, 1000).await.unwrap();
+    assert_eq!(stored.len(), 80);
+    for (position, event) in stored {
+        assert_eq!(published.get(&position), Some(&event.id));
+    }
+
+    drop(writers);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+#[tokio::test]
+async fn test_closed_store_releases_the_database_for_reopening() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-close-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+    let order_event = |version: i64| Event::new(
+        "order-1".to_string(),
+        "Order".to_string(),
+        "OrderUpdated".to_string(),
+        1,
+        version,
+        EventData::Json(serde_json::json!({ "version": version })),
+    );
+
+    let store = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    store.save_events(vec![order_event(1), order_event(2)]).await.unwrap();
+    store.close().await.unwrap();
+
+    // The pool is gone and the WAL was checkpointed into the database file
+    assert!(store.load_events(&"order-1".to_string(), None).await.is_err());
+    let wal_len = std::fs::metadata(format!("{db_path}-wal")).map(|m| m.len()).unwrap_or(0);
+    assert_eq!(wal_len, 0);
+    store.close().await.unwrap();
+
+    let reopened = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    reopened.save_events(vec![order_event(3)]).await.unwrap();
+    let versions: Vec<i64> = reopened
+        .load_events(&"order-1".to_string(), None)
+        .await
+        .unwrap()
+        .iter()
+        .map(|e| e.aggregate_version)
+        .collect();
+    assert_eq!(versions, vec![1, 2, 3]);
+    reopened.close().await.unwrap();
+
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+fn profile_update(version: i64, data: serde_json::Value) -> Event {
+    Event::new("profile-1".to_string(), "Profile".to_string(), "ProfileUpdated".to_string(), 1, version, EventData::Json(data))
+}
+
+#[tokio::test]
+async fn test_concurrent_updates_to_disjoint_fields_merge() {
+    let store = EventStoreImpl::new(MemoryBackend::new())
+        .with_conflict_resolution(ConflictResolution::MergeDisjointFields);
+    store.save_events(vec![profile_update(1, serde_json::json!({
+        "name": "Ada", "address": { "city": "London", "zip": "N1" }
+    }))]).await.unwrap();
+
+    // Both writers loaded version 1; the second one loses the race
+    store.save_events(vec![profile_update(2, serde_json::json!({ "address": { "city": "Paris" } }))]).await.unwrap();
+    store.save_events(vec![
+        profile_update(2, serde_json::json!({ "name": "Ada Lovelace" })),
+        profile_update(3, serde_json::json!({ "address": { "zip": "75001" } })),
+    ]).await.unwrap();
+
+    let events = store.load_events(&"profile-1".to_string(), None).await.unwrap();
+    let versions: Vec<i64> = events.iter().map(|e| e.aggregate_version).collect();
+    assert_eq!(versions, vec![1, 2, 3, 4]);
+    assert_eq!(events[2].data, EventData::Json(serde_json::json!({ "name": "Ada Lovelace" })));
+    assert_eq!(events[3].data, EventData::Json(serde_json::json!({ "address": { "zip": "75001" } })));
+}
+
+#[tokio::test]
+async fn test_concurrent_updates_to_the_same_field_still_conflict() {
+    let merging = EventStoreImpl::new(MemoryBackend::new())
+        .with_conflict_resolution(ConflictResolution::MergeDisjointFields);
+    let rejecting = EventStoreImpl::new(MemoryBackend::new());
+
+    for store in [&merging, &rejecting] {
+        store.save_events(vec![profile_update(1, serde_json::json!({ "address": { "city": "London" } }))]).await.unwrap();
+        store.save_events(vec![profile_update(2, serde_json::json!({ "address": { "city": "Paris" } }))]).await.unwrap();
+    }
+
+    // Replacing the whole address overlaps the committed change to its city
+    let overlapping = profile_update(2, serde_json::json!({ "address": { "city": "Rome" } }));
+    let parent = profile_update(2, serde_json::json!({ "address": null }));
+    for event in [overlapping, parent] {
+        assert!(matches!(
+            merging.save_events(vec![event]).await,
+            Err(EventualiError::OptimisticConcurrency { .. })
+        ));
+    }
+
+    // Without the merge strategy even disjoint fields conflict
+    assert!(matches!(
+        rejecting.save_events(vec![profile_update(2, serde_json::json!({ "name": "Ada" }))]).await,
+        Err(EventualiError::OptimisticConcurrency { .. })
+    ));
+    assert_eq!(merging.load_events(&"profile-1".to_string(), None).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_lenient_load_skips_and_quarantines_a_corrupt_row() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-quarantine-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+    let store = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    let aggregate_id = "order-1".to_string();
+    store
+        .save_events((1..=3).map(|version| Event::new(
+            aggregate_id.clone(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "version": version })),
+        )).collect())
+        .await
+        .unwrap();
+
+    let options = sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path);
+    let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+    sqlx::query("UPDATE events SET event_data = '{\"version\": 2' WHERE aggregate_version = 2")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    assert!(store.load_events(&aggregate_id, None).await.is_err());
+
+    let load = store.load_events_lenient(&aggregate_id, None).await.unwrap();
+    let versions: Vec<i64> = load.events.iter().map(|e| e.aggregate_version).collect();
+    assert_eq!(versions, vec![1, 3]);
+    assert_eq!(load.skipped(), 1);
+    let quarantined = &load.quarantined[0];
+    assert_eq!(quarantined.aggregate_id, aggregate_id);
+    assert_eq!(quarantined.aggregate_version, Some(2));
+    assert_eq!(quarantined.event_type.as_deref(), Some("OrderUpdated"));
+    assert_eq!(quarantined.raw_event_data, br#"{"version": 2"#.to_vec());
+    assert!(quarantined.event_id.is_some());
+    assert!(!quarantined.error.is_empty());
+
+    store.close().await.unwrap();
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+fn profile_state(events: &[Event]) -> serde_json::Map<String, serde_json::Value> {
+    let mut state = serde_json::Map::new();
+    for event in events {
+        let EventData::Json(data) = &event.data else { panic!("expected JSON payload") };
+        match event.event_type.as_str() {
+            "ProfileCompacted" => state = data["fields"].as_object().unwrap().clone(),
+            "FieldSet" => {
+                state.insert(data["field"].as_str().unwrap().to_string(), data["value"].clone());
+            }
+            other => panic!("unexpected event type {other}"),
+        }
+    }
+    state
+}
+
+/// Folds every event but the last `keep` into one snapshot of the fields
+struct KeepLatest {
+    keep: usize,
+}
+
+impl Compactor for KeepLatest {
+    fn compact(&self, events: &[Event]) -> eventuali_core::Result<Option<Event>> {
+        if events.len() <= self.keep + 1 {
+            return Ok(None);
+        }
+        let prefix = &events[..events.len() - self.keep];
+        let last = prefix.last().unwrap();
+        Ok(Some(Event::new(
+            last.aggregate_id.clone(),
+            last.aggregate_type.clone(),
+            "ProfileCompacted".to_string(),
+            1,
+            last.aggregate_version,
+            EventData::Json(serde_json::json!({ "fields": profile_state(prefix) })),
+        )))
+    }
+}
+
+#[tokio::test]
+async fn test_compaction_keeps_reconstructed_state() {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let aggregate_id = Uuid::new_v4().to_string();
+    let fields = ["name", "email", "city"];
+    let events: Vec<Event> = (1..=30)
+        .map(|version| Event::new(
+            aggregate_id.clone(),
+            "Profile".to_string(),
+            "FieldSet".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({
+                "field": fields[version as usize % fields.len()],
+                "value": format!("value-{version}"),
+            })),
+        ))
+        .collect();
+    store.save_events(events).await.unwrap();
+
+    let original = store.load_events(&aggregate_id, None).await.unwrap();
+    let report = store.compact_aggregate(&aggregate_id, &KeepLatest { keep: 2 }).await.unwrap();
+    assert!(report.compacted());
+    assert_eq!(report.compacted_through, 28);
+    assert_eq!(report.removed_event_ids.len(), 28);
+    assert_eq!((report.events_before, report.events_after), (30, 3));
+
+    let compacted = store.load_events(&aggregate_id, None).await.unwrap();
+    assert_eq!(compacted.len(), 3);
+    assert_eq!(compacted[0].event_type, "ProfileCompacted");
+    assert_eq!(compacted[1..], original[28..]);
+    assert_eq!(profile_state(&compacted), profile_state(&original));
+    assert_eq!(store.get_aggregate_version(&aggregate_id).await.unwrap(), Some(30));
+
+    // Global order still runs snapshot first, then the retained events
+    let positions: Vec<Event> = store
+        .load_events_after_position(0, 100)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(_, event)| event)
+        .collect();
+    assert_eq!(positions, compacted);
+
+    // A short stream is left alone, and the audit trail lists what was removed
+    let unchanged = store.compact_aggregate(&aggregate_id, &KeepLatest { keep: 2 }).await.unwrap();
+    assert!(!unchanged.compacted());
+    let history = store.load_compaction_history(&aggregate_id).await.unwrap();
+    assert_eq!(history, vec![report.clone()]);
+    let original_ids: Vec<_> = original[..28].iter().map(|e| e.id).collect();
+    assert_eq!(history[0].removed_event_ids, original_ids);
+
+    // Appends continue from the original version
+    let next = Event::new(
+        aggregate_id.clone(),
+        "Profile".to_string(),
+        "FieldSet".to_string(),
+        1,
+        31,
+        EventData::Json(serde_json::json!({ "field": "name", "value": "after" })),
+    );
+    store.save_events(vec![next]).await.unwrap();
+    assert_eq!(profile_state(&store.load_events(&aggregate_id, None).await.unwrap())["name"], "after");
+}
+
+#[tokio::test]
+async fn test_compacted_aggregate_reconstructs_to_its_original_state() {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let aggregate_id = Uuid::new_v4().to_string();
+    let fields = ["name", "email", "city", "phone"];
+    let events: Vec<Event> = (1..=12)
+        .map(|version| Event::new(
+            aggregate_id.clone(),
+            "Profile".to_string(),
+            "FieldSet".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({
+                "field": fields[version as usize % fields.len()],
+                "value": format!("value-{version}"),
+            })),
+        ))
+        .collect();
+    store.save_events(events).await.unwrap();
+
+    let restore = |state: &mut serde_json::Map<String, serde_json::Value>, event: &Event| {
+        let EventData::Json(data) = &event.data else { panic!("expected JSON payload") };
+        *state = data["fields"].as_object().unwrap().clone();
+        Ok(())
+    };
+    let apply = |state: &mut serde_json::Map<String, serde_json::Value>, event: &Event| {
+        let EventData::Json(data) = &event.data else { panic!("expected JSON payload") };
+        state.insert(data["field"].as_str().unwrap().to_string(), data["value"].clone());
+        Ok(())
+    };
+
+    let before = store.load_compacted(&aggregate_id).await.unwrap();
+    assert!(!before.is_compacted());
+    assert_eq!(before.tail.len(), 12);
+    let original_state = before.reconstruct(serde_json::Map::new(), restore, apply).unwrap();
+    assert_eq!(store.load_history(&aggregate_id, None).await.unwrap(), before.tail);
+
+    store.compact_aggregate(&aggregate_id, &KeepLatest { keep: 3 }).await.unwrap();
+    let after = store.load_compacted(&aggregate_id).await.unwrap();
+    assert!(after.is_compacted());
+    assert_eq!(after.compacted_through, 9);
+    assert_eq!(after.base.as_ref().unwrap().event_type, "ProfileCompacted");
+    assert_eq!(after.tail, before.tail[9..]);
+    assert_eq!(after.version(), Some(12));
+    assert_eq!(after.reconstruct(serde_json::Map::new(), restore, apply).unwrap(), original_state);
+
+    // Versions folded into the snapshot can no longer be loaded one by one
+    for from_version in [None, Some(0), Some(8)] {
+        match store.load_history(&aggregate_id, from_version).await {
+            Err(EventualiError::HistoryCompacted { compacted_through, .. }) => assert_eq!(compacted_through, 9),
+            other => panic!("expected HistoryCompacted, got {other:?}"),
+        }
+    }
+    assert_eq!(store.load_history(&aggregate_id, Some(9)).await.unwrap(), before.tail[9..]);
+    assert_eq!(store.load_history(&aggregate_id, Some(10)).await.unwrap(), before.tail[10..]);
+
+    // Point-in-time loads before the compaction point fail the same way
+    // instead of returning the snapshot as if it were that version's state
+    for version in [1, 5, 8] {
+        match store.load_events_at_version(&aggregate_id, version).await {
+            Err(EventualiError::HistoryCompacted { compacted_through, .. }) => assert_eq!(compacted_through, 9),
+            other => panic!("expected HistoryCompacted at version {version}, got {other:?}"),
+        }
+    }
+    let at_ten = store.load_events_at_version(&aggregate_id, 10).await.unwrap();
+    assert_eq!(at_ten.len(), 2);
+    assert_eq!(at_ten[0].event_type, "ProfileCompacted");
+    assert_eq!(at_ten[1], before.tail[9]);
+}
+
+#[tokio::test]
+async fn test_events_by_correlation_id_follow_one_request_across_aggregates() {
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let correlation_id = Uuid::new_v4();
+
+    let correlated = |aggregate_id: &str, aggregate_type: &str, event_type: &str| {
+        let metadata = EventMetadata { correlation_id: Some(correlation_id), ..EventMetadata::default() };
+        Event::new(
+            aggregate_id.to_string(),
+            aggregate_type.to_string(),
+            event_type.to_string(),
+            1,
+            1,
+            EventData::Json(serde_json::json!({})),
+        )
+        .with_metadata(metadata)
+    };
+
+    store.save_events(vec![correlated("order-1", "Order", "OrderPlaced")]).await.unwrap();
+    store
+        .save_events(vec![Event::new(
+            "order-2".to_string(),
+            "Order".to_string(),
+            "OrderPlaced".to_string(),
+            1,
+            1,
+            EventData::Json(serde_json::json!({})),
+        )])
+        .await
+        .unwrap();
+    store.save_events(vec![correlated("payment-1", "Payment", "PaymentCaptured")]).await.unwrap();
+    store.save_events(vec![correlated("shipment-1", "Shipment", "ShipmentBooked")]).await.unwrap();
+
+    let chain = store.get_events_by_correlation_id(&correlation_id.to_string(), 10).await.unwrap();
+    let event_types: Vec<&str> = chain.iter().map(|e| e.event_type.as_str()).collect();
+    assert_eq!(event_types, ["OrderPlaced", "PaymentCaptured", "ShipmentBooked"]);
+    assert_eq!(chain[0].aggregate_id, "order-1");
+
+    let first_two = store.get_events_by_correlation_id(&correlation_id.to_string(), 2).await.unwrap();
+    assert_eq!(first_two.len(), 2);
+    assert_eq!(first_two[1].aggregate_id, "payment-1");
+
+    let unknown = store.get_events_by_correlation_id(&Uuid::new_v4().to_string(), 10).await.unwrap();
+    assert!(unknown.is_empty());
+}
+
+#[tokio::test]
+async fn test_storage_format_version_is_stamped_and_future_versions_are_refused() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-storage-format-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+
+    // A table from before rows carried a format version, holding one event
+    let options = sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path).create_if_missing(true);
+    let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+    sqlx::query(
+        "CREATE TABLE events (
+            id TEXT PRIMARY KEY,
+            aggregate_id TEXT NOT NULL,
+            aggregate_type TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            event_version INTEGER NOT NULL,
+            aggregate_version INTEGER NOT NULL,
+            event_data TEXT NOT NULL,
+            event_data_type TEXT NOT NULL DEFAULT 'json',
+            metadata TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            global_position INTEGER,
+            UNIQUE(aggregate_id, aggregate_version)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO events (id, aggregate_id, aggregate_type, event_type, event_version, aggregate_version, \
+         event_data, metadata, timestamp, global_position) VALUES (?, 'account-1', 'Account', 'AccountOpened', 1, 1, '{}', ?, ?, 1)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(serde_json::to_string(&EventMetadata::default()).unwrap())
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let store = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    let deposit = Event::new(
+        "account-1".to_string(),
+        "Account".to_string(),
+        "Deposited".to_string(),
+        1,
+        2,
+        EventData::Json(serde_json::json!({ "amount": 10 })),
+    );
+    store.save_events(vec![deposit.clone()]).await.unwrap();
+    assert_eq!(store.load_events(&"account-1".to_string(), None).await.unwrap().len(), 2);
+
+    let pool = sqlx::SqlitePool::connect_with(sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path))
+        .await
+        .unwrap();
+    let versions: Vec<i32> = sqlx::query_scalar("SELECT storage_format_version FROM events ORDER BY global_position")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(versions, vec![STORAGE_FORMAT_VERSION, STORAGE_FORMAT_VERSION]);
+    assert_eq!(STORAGE_FORMAT_VERSION, 1);
+
+    // A row written by a newer release is refused rather than mis-parsed
+    sqlx::query("UPDATE events SET storage_format_version = 2 WHERE id = ?")
+        .bind(deposit.id.to_string())
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    match store.load_events(&"account-1".to_string(), None).await {
+        Err(EventualiError::UnsupportedStorageFormat { event_id, version, supported }) => {
+            assert_eq!(event_id, deposit.id.to_string());
+            assert_eq!((version, supported), (2, 1));
+        }
+        other => panic!("expected UnsupportedStorageFormat, got {other:?}"),
+    }
+    let lenient = store.load_events_lenient(&"account-1".to_string(), None).await.unwrap();
+    assert_eq!(lenient.events.len(), 1);
+    assert_eq!(lenient.quarantined.len(), 1);
+
+    store.close().await.unwrap();
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+#[tokio::test]
+async fn test_promoted_metadata_columns_are_migrated_populated_and_indexed() {
+    let db_path = std::env::temp_dir().join(format!("eventuali-metadata-columns-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+    let legacy_causation = Uuid::new_v4();
+    let legacy_correlation = Uuid::new_v4();
+
+    // A table from before causation and user IDs were promoted, holding one event
+    let options = sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path).create_if_missing(true);
+    let pool = sqlx::SqlitePool::connect_with(options).await.unwrap();
+    sqlx::query(
+        "CREATE TABLE events (
+            id TEXT PRIMARY KEY,
+            aggregate_id TEXT NOT NULL,
+            aggregate_type TEXT NOT NULL,
+            event_type TEXT NOT NULL,
+            event_version INTEGER NOT NULL,
+            aggregate_version INTEGER NOT NULL,
+            event_data TEXT NOT NULL,
+            event_data_type TEXT NOT NULL DEFAULT 'json',
+            metadata TEXT NOT NULL,
+            timestamp TEXT NOT NULL,
+            global_position INTEGER,
+            correlation_id TEXT GENERATED ALWAYS AS (json_extract(metadata, '$.correlation_id')) VIRTUAL,
+            UNIQUE(aggregate_id, aggregate_version)
+        )",
+    )
+    .execute(&pool)
+    .await
+    .unwrap();
+    sqlx::query(
+        "INSERT INTO events (id, aggregate_id, aggregate_type, event_type, event_version, aggregate_version, \
+         event_data, metadata, timestamp, global_position) VALUES (?, 'account-1', 'Account', 'AccountOpened', 1, 1, '{}', ?, ?, 1)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(serde_json::json!({
+        "causation_id": legacy_causation,
+        "correlation_id": legacy_correlation,
+        "user_id": "alice",
+        "headers": {},
+    }).to_string())
+    .bind(chrono::Utc::now().to_rfc3339())
+    .execute(&pool)
+    .await
+    .unwrap();
+    pool.close().await;
+
+    let store = create_event_store(EventStoreConfig::sqlite(db_path.clone())).await.unwrap();
+    let causation_id = Uuid::new_v4();
+    let by_user = |aggregate_id: &str, user_id: &str| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Account".to_string(),
+            "Deposited".to_string(),
+            1,
+            2,
+            EventData::Json(serde_json::json!({ "amount": 10 })),
+        )
+        .with_metadata(EventMetadata {
+            causation_id: Some(causation_id),
+            user_id: Some(user_id.to_string()),
+            ..EventMetadata::default()
+        })
+    };
+    store.save_events(vec![by_user("account-1", "alice")]).await.unwrap();
+    store.save_events(vec![by_user("account-2", "bob")]).await.unwrap();
+
+    let pool = sqlx::SqlitePool::connect_with(sqlx::sqlite::SqliteConnectOptions::new().filename(&db_path))
+        .await
+        .unwrap();
+    let rows: Vec<(Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT correlation_id, causation_id, user_id FROM events ORDER BY global_position",
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(rows, vec![
+        (Some(legacy_correlation.to_string()), Some(legacy_causation.to_string()), Some("alice".to_string())),
+        (None, Some(causation_id.to_string()), Some("alice".to_string())),
+        (None, Some(causation_id.to_string()), Some("bob".to_string())),
+    ]);
+
+    for column in ["correlation_id", "causation_id", "user_id"] {
+        let details: Vec<String> = sqlx::query(&format!("EXPLAIN QUERY PLAN SELECT id FROM events WHERE {column} = 'x'"))
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| sqlx::Row::get::<String, _>(row, "detail"))
+            .collect();
+        assert!(
+            details.iter().any(|detail| detail.contains(&format!("idx_events_{column}"))),
+            "{column} lookup does not use its index: {details:?}"
+        );
+    }
+    pool.close().await;
+
+    let alice = store.get_events_by_user_id("alice", 10).await.unwrap();
+    let versions: Vec<(&str, i64)> = alice.iter().map(|e| (e.aggregate_id.as_str(), e.aggregate_version)).collect();
+    assert_eq!(versions, [("account-1", 1), ("account-1", 2)]);
+    assert_eq!(alice[0].metadata.causation_id, Some(legacy_causation));
+    assert_eq!(store.get_events_by_user_id("alice", 1).await.unwrap().len(), 1);
+    assert_eq!(store.get_events_by_user_id("bob", 10).await.unwrap()[0].aggregate_id, "account-2");
+    assert!(store.get_events_by_user_id("carol", 10).await.unwrap().is_empty());
+
+    store.close().await.unwrap();
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+/// A store holding 20 accounts of 30 events each, and the faults its backend
+/// counts calls with
+async fn migration_source() -> (Arc<dyn EventStore + Send + Sync>, FaultInjector) {
+    let faults = FaultInjector::new();
+    let source: Arc<dyn EventStore + Send + Sync> =
+        Arc::new(EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults.clone())));
+    for aggregate in 0..20 {
+        let events = (1..=30)
+            .map(|version| {
+                Event::new(
+                    format!("account-{aggregate}"),
+                    "Account".to_string(),
+                    "Deposited".to_string(),
+                    1,
+                    version,
+                    EventData::Json(serde_json::json!({ "amount": version })),
+                )
+            })
+            .collect();
+        source.save_events(events).await.unwrap();
+    }
+    (source, faults)
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_governed_migration_holds_the_target_rate() {
+    let (source, _) = migration_source().await;
+    let target: Arc<dyn EventStore + Send + Sync> = Arc::new(EventStoreImpl::new(MemoryBackend::new()));
+
+    let report = StoreMigration::new(source.clone(), target.clone())
+        .with_batch_size(25)
+        .with_governor(ThroughputGovernor::new(1500.0))
+        .run()
+        .await
+        .unwrap();
+
+    assert_eq!(report.events_migrated, 600);
+    assert_eq!(report.batches_written, 24);
+    assert_eq!(report.target_events_per_sec, Some(1500.0));
+    // The clock is paused, so only the governor's pauses take any time:
+    // 600 events at 1500/s is exactly 400ms
+    assert!((report.elapsed.as_secs_f64() - 0.4).abs() < 1e-6, "took {:?}", report.elapsed);
+    assert!((report.achieved_events_per_sec - 1500.0).abs() < 0.01, "achieved {}", report.achieved_events_per_sec);
+
+    assert_eq!(target.stats().await.unwrap().total_events, 600);
+    let migrated = target.load_events(&"account-7".to_string(), None).await.unwrap();
+    assert_eq!(migrated.iter().map(|e| e.aggregate_version).collect::<Vec<_>>(), (1..=30).collect::<Vec<_>>());
+
+    let resumed = StoreMigration::new(source, target)
+        .starting_after(report.last_source_position)
+        .run()
+        .await
+        .unwrap();
+    assert_eq!(resumed.events_migrated, 0);
+    assert_eq!(resumed.target_events_per_sec, None);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_migration_reads_at_most_max_in_flight_batches_ahead() {
+    let (source, source_faults) = migration_source().await;
+    let target_faults = FaultInjector::new().with_latency_of(BackendOperation::SaveEvents, Duration::from_millis(10));
+    let target: Arc<dyn EventStore + Send + Sync> =
+        Arc::new(EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), target_faults.clone())));
+
+    let migration = StoreMigration::new(source, target)
+        .with_batch_size(25)
+        .with_max_in_flight_batches(3);
+    let run = tokio::spawn(async move { migration.run().await });
+
+    // Reads and saves are both counted as they start, so a save still in
+    // progress keeps its batch counted as in flight
+    let mut most_ahead = 0;
+    while !run.is_finished() {
+        let reads = source_faults.calls(BackendOperation::LoadEventsAfterPosition);
+        let saves = target_faults.calls(BackendOperation::SaveEvents);
+        most_ahead = most_ahead.max(reads.saturating_sub(saves.saturating_sub(1)));
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+    let report = run.await.unwrap().unwrap();
+
+    assert_eq!(report.events_migrated, 600);
+    assert_eq!(report.target_events_per_sec, None);
+    assert_eq!(most_ahead, 3);
+}
+
+#[tokio::test]
+async fn test_rejected_saves_are_recorded_in_the_failed_write_log() {
+    let log_path = std::env::temp_dir().join(format!("eventuali-failed-writes-{}.db", Uuid::new_v4()));
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_max_aggregate_version(3)
+        .with_failed_write_log(log_path.to_string_lossy().to_string());
+    let store = create_event_store(config).await.unwrap();
+
+    let event = |aggregate_id: &str, version: i64| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({})),
+        )
+    };
+
+    store.save_events(vec![event("order-1", 1), event("order-1", 2)]).await.unwrap();
+
+    let conflict = store.save_events(vec![event("order-1", 2), event("order-1", 3)]).await.unwrap_err();
+    let too_long = store.save_events(vec![event("order-2", 4)]).await.unwrap_err();
+    let mixed = store
+        .save_events(vec![event("order-3", 1), event("order-1", 1)])
+        .await
+        .unwrap_err();
+
+    let all = store.query_failed_writes(&FailedWriteFilter::default(), 100).await.unwrap();
+    // The mixed save touched two aggregates, so it has a row for each
+    assert_eq!(all.len(), 4);
+
+    let for_order_1 = store
+        .query_failed_writes(&FailedWriteFilter { aggregate_id: Some("order-1".to_string()), ..Default::default() }, 100)
+        .await
+        .unwrap();
+    assert_eq!(for_order_1.len(), 2);
+    assert_eq!(for_order_1[1].attempted_versions, vec![2, 3]);
+    assert_eq!(for_order_1[1].error_kind, conflict.kind());
+    assert_eq!(for_order_1[1].error_message, conflict.to_string());
+    assert!(!for_order_1[1].retryable);
+    assert_eq!(for_order_1[0].error_kind, mixed.kind());
+    assert_eq!(for_order_1[0].event_types, vec!["OrderUpdated".to_string()]);
+
+    let validation = store
+        .query_failed_writes(&FailedWriteFilter { error_kind: Some("Validation".to_string()), ..Default::default() }, 100)
+        .await
+        .unwrap();
+    assert_eq!(too_long.kind(), "Validation");
+    assert_eq!(validation.len(), 1);
+    assert_eq!(validation[0].aggregate_id, "order-2");
+    assert_eq!(validation[0].attempted_versions, vec![4]);
+
+    let newest = store.query_failed_writes(&FailedWriteFilter::default(), 2).await.unwrap();
+    assert_eq!(newest.len(), 2);
+    assert!(newest.iter().all(|f| f.error_kind == mixed.kind()));
+    assert!(newest[0].failed_at >= all[3].failed_at);
+
+    // Erasing an aggregate erases its failed writes with it
+    store.delete_aggregate_events(&"order-1".to_string()).await.unwrap();
+    let remaining = store.query_failed_writes(&FailedWriteFilter::default(), 100).await.unwrap();
+    assert_eq!(remaining.len(), 2);
+    assert!(remaining.iter().all(|f| f.aggregate_id != "order-1"));
+
+    std::fs::remove_file(log_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_recent_events_serves_the_committed_tail_within_capacity() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_max_aggregate_version(4)
+        .with_recent_events_capacity(5);
+    let store = create_event_store(config).await.unwrap();
+
+    let event = |aggregate_id: &str, version: i64| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "version": version })),
+        )
+    };
+    let tail = |events: Vec<Event>| {
+        events.iter().map(|e| (e.aggregate_id.clone(), e.aggregate_version)).collect::<Vec<_>>()
+    };
+
+    assert!(store.recent_events(10).await.unwrap().is_empty());
+
+    store.save_events(vec![event("order-1", 1), event("order-1", 2)]).await.unwrap();
+    assert_eq!(tail(store.recent_events(10).await.unwrap()), vec![("order-1".to_string(), 1), ("order-1".to_string(), 2)]);
+
+    store
+        .save_events(vec![event("order-2", 1), event("order-2", 2), event("order-2", 3), event("order-2", 4)])
+        .await
+        .unwrap();
+    // Rejected saves never reach the buffer
+    store.save_events(vec![event("order-1", 2)]).await.unwrap_err();
+    store.save_events(vec![event("order-3", 5)]).await.unwrap_err();
+    store.save_events(vec![event("order-1", 3)]).await.unwrap();
+
+    let expected: Vec<(String, i64)> = vec![
+        ("order-2".to_string(), 1),
+        ("order-2".to_string(), 2),
+        ("order-2".to_string(), 3),
+        ("order-2".to_string(), 4),
+        ("order-1".to_string(), 3),
+    ];
+    assert_eq!(tail(store.recent_events(100).await.unwrap()), expected);
+    assert_eq!(tail(store.recent_events(2).await.unwrap()), expected[3..].to_vec());
+    assert!(store.recent_events(0).await.unwrap().is_empty());
+
+    // A save larger than the buffer keeps only its own tail
+    let batch: Vec<Event> = (1..=4).map(|v| event("order-4", v)).chain((1..=3).map(|v| event("order-5", v))).collect();
+    store.save_events(batch).await.unwrap();
+    let latest = store.recent_events(100).await.unwrap();
+    assert_eq!(latest.len(), 5);
+    assert_eq!(latest[0].aggregate_id, "order-4");
+    assert_eq!(latest[0].aggregate_version, 3);
+    assert_eq!(latest[4].aggregate_id, "order-5");
+    assert_eq!(latest[4].aggregate_version, 3);
+
+    let disabled = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    assert!(matches!(disabled.recent_events(1).await, Err(EventualiError::Configuration(_))));
+}
+
+#[tokio::test]
+async fn test_existence_cache_answers_repeat_checks_and_never_caches_absence() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("existence.db").to_string_lossy().to_string();
+    let config = EventStoreConfig::sqlite(path.clone())
+        .with_max_aggregate_version(3)
+        .with_existence_cache_capacity(2);
+    let store = create_event_store(config).await.unwrap();
+    let other_process = create_event_store(EventStoreConfig::sqlite(path.clone())).await.unwrap();
+
+    let event = |aggregate_id: &str, version: i64| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Order".to_string(),
+            "OrderPlaced".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({})),
+        )
+    };
+    let id = |aggregate_id: &str| aggregate_id.to_string();
+
+    assert!(!store.aggregate_exists(&id("order-1")).await.unwrap());
+    assert!(store.append_if_not_exists(vec![event("order-1", 1), event("order-1", 2)]).await.unwrap());
+    assert!(store.aggregate_exists(&id("order-1")).await.unwrap());
+    // Answered from the cache, without attempting the write
+    assert!(!store.append_if_not_exists(vec![event("order-1", 1)]).await.unwrap());
+    assert_eq!(store.load_events(&id("order-1"), None).await.unwrap().len(), 2);
+    let stats = store.existence_cache_stats().await.unwrap();
+    assert_eq!((stats.hits, stats.misses, stats.entries, stats.capacity), (2, 2, 1, 2));
+
+    // A rejected save leaves the aggregate unknown
+    store.save_events(vec![event("order-2", 1), event("order-2", 4)]).await.unwrap_err();
+    assert!(!store.aggregate_exists(&id("order-2")).await.unwrap());
+
+    // Absence is never cached, so an aggregate created elsewhere shows up at once
+    assert!(other_process.append_if_not_exists(vec![event("order-2", 1)]).await.unwrap());
+    assert!(store.aggregate_exists(&id("order-2")).await.unwrap());
+    // Creating an aggregate this store has not seen loses the race cleanly
+    other_process.save_events(vec![event("order-3", 1)]).await.unwrap();
+    assert!(!store.append_if_not_exists(vec![event("order-3", 1)]).await.unwrap());
+
+    // Loads populate the cache; the least recently used entry is evicted
+    store.load_events(&id("order-3"), None).await.unwrap();
+    let stats = store.existence_cache_stats().await.unwrap();
+    assert_eq!(stats.entries, 2);
+    let misses = stats.misses;
+    assert!(store.aggregate_exists(&id("order-1")).await.unwrap());
+    assert_eq!(store.existence_cache_stats().await.unwrap().misses, misses + 1);
+
+    for bad in [vec![], vec![event("order-4", 2)], vec![event("order-4", 1), event("order-5", 2)]] {
+        assert!(matches!(store.append_if_not_exists(bad).await, Err(EventualiError::Validation(_))));
+    }
+    assert!(matches!(other_process.existence_cache_stats().await, Err(EventualiError::Configuration(_))));
+}
+
+#[tokio::test]
+async fn test_stream_validation_reports_gaps_duplicates_and_timestamp_regressions() {
+    let aggregate_id = "order-1".to_string();
+    let start = chrono::Utc::now();
+    let event = |version: i64, seconds: i64| {
+        let mut event = Event::new(
+            aggregate_id.clone(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "version": version })),
+        );
+        event.timestamp = start + chrono::Duration::seconds(seconds);
+        event
+    };
+
+    // Versions 1, 2, 2, 5, 6 with version 6 stamped before version 5
+    let stream = vec![event(1, 0), event(2, 1), event(2, 2), event(5, 4), event(6, 3)];
+    let validation = StreamValidation::of(&aggregate_id, &stream);
+    assert!(!validation.is_valid());
+    assert_eq!((validation.event_count, validation.first_version, validation.last_version), (5, Some(1), Some(6)));
+    assert_eq!(
+        validation.anomalies,
+        vec![
+            StreamAnomaly::DuplicateVersion { version: 2, event_ids: vec![stream[1].id, stream[2].id] },
+            StreamAnomaly::VersionGap { after: 2, next: 5 },
+            StreamAnomaly::TimestampRegression {
+                version: 6,
+                event_id: stream[4].id,
+                timestamp: stream[4].timestamp,
+                previous_timestamp: stream[3].timestamp,
+            },
+        ]
+    );
+
+    // Saves accept a gap, which validation then finds in the stored stream
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    store.save_events(vec![event(1, 0), event(2, 1)]).await.unwrap();
+    assert!(store.validate_stream(&aggregate_id).await.unwrap().is_valid());
+    store.save_events(vec![event(4, 2)]).await.unwrap();
+    let validation = store.validate_stream(&aggregate_id).await.unwrap();
+    assert_eq!(validation.anomalies, vec![StreamAnomaly::VersionGap { after: 2, next: 4 }]);
+
+    let empty = store.validate_stream(&"order-2".to_string()).await.unwrap();
+    assert!(empty.is_valid());
+    assert_eq!((empty.event_count, empty.last_version), (0, None));
+}
+
+#[tokio::test]
+async fn test_oversized_batches_are_split_or_rejected() {
+    let events = |aggregate_id: &str, count: i64| -> Vec<Event> {
+        (1..=count)
+            .map(|version| Event::new(
+                aggregate_id.to_string(),
+                "Order".to_string(),
+                "OrderUpdated".to_string(),
+                1,
+                version,
+                EventData::Json(serde_json::json!({ "version": version })),
+            ))
+            .collect()
+    };
+
+    // 10 events at 4 per transaction commit as 4 + 4 + 2
+    let faults = FaultInjector::new();
+    let split = EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults.clone()))
+        .with_max_batch_events(Some(4), OversizedBatch::Split);
+    split.save_events(events("order-1", 10)).await.unwrap();
+    assert_eq!(faults.calls(BackendOperation::SaveEvents), 3);
+    let versions: Vec<i64> = split.load_events(&"order-1".to_string(), None).await.unwrap()
+        .iter().map(|e| e.aggregate_version).collect();
+    assert_eq!(versions, (1..=10).collect::<Vec<i64>>());
+
+    let faults = FaultInjector::new();
+    let strict = EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults.clone()))
+        .with_max_batch_events(Some(4), OversizedBatch::Reject);
+    let error = strict.save_events(events("order-1", 5)).await.unwrap_err();
+    assert!(matches!(error, EventualiError::Validation(_)), "{error}");
+    assert_eq!(faults.calls(BackendOperation::SaveEvents), 0);
+    assert!(strict.load_events(&"order-1".to_string(), None).await.unwrap().is_empty());
+    strict.save_events(events("order-1", 4)).await.unwrap();
+    assert_eq!(faults.calls(BackendOperation::SaveEvents), 1);
+
+    // The limit reaches stores built from a config
+    let config = EventStoreConfig::sqlite(":memory:".to_string()).with_max_batch_events(2, OversizedBatch::Reject);
+    let store = create_event_store(config).await.unwrap();
+    assert!(matches!(store.save_events(events("order-2", 3)).await, Err(EventualiError::Validation(_))));
+    store.save_events(events("order-2", 2)).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_operation_timeout_fails_calls_to_a_slow_backend() {
+    use std::time::{Duration, Instant};
+
+    // Saves and loads stand in for a hung database
+    let faults = FaultInjector::new()
+        .with_latency_of(BackendOperation::SaveEvents, Duration::from_millis(300))
+        .with_latency_of(BackendOperation::LoadEvents, Duration::from_millis(300));
+    let backend = FaultInjectingBackend::new(MemoryBackend::new(), faults);
+    let store = EventStoreImpl::new(backend).with_operation_timeout(Some(Duration::from_millis(50)));
+    let event = Event::new(
+        "order-1".to_string(),
+        "Order".to_string(),
+        "OrderPlaced".to_string(),
+        1,
+        1,
+        EventData::Json(serde_json::json!({})),
+    );
+
+    let started = Instant::now();
+    let error = store.save_events(vec![event.clone()]).await.unwrap_err();
+    assert!(started.elapsed() < Duration::from_millis(250), "timeout fired late: {:?}", started.elapsed());
+    match &error {
+        EventualiError::Io(e) => assert_eq!(e.kind(), std::io::ErrorKind::TimedOut),
+        other => panic!("expected a timeout, got {other:?}"),
+    }
+    assert!(error.to_string().contains("operation timed out"));
+    assert!(error.is_retryable());
+
+    // Calls without a slow path are unaffected
+    assert_eq!(store.get_aggregate_version(&"order-1".to_string()).await.unwrap(), None);
+
+    // A per-call limit overrides the store's, in either direction
+    with_operation_timeout(Duration::from_secs(5), store.save_events(vec![event])).await.unwrap();
+    let error = with_operation_timeout(Duration::from_millis(10), store.load_events(&"order-1".to_string(), None))
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("10ms"));
+    let loaded = with_operation_timeout(Duration::from_secs(5), store.load_events(&"order-1".to_string(), None))
+        .await
+        .unwrap();
+    assert_eq!(loaded.len(), 1);
+}
+
+#[tokio::test]
+async fn test_operation_timeout_does_not_count_waiting_for_an_aggregate_lock() {
+    use std::time::Duration;
+
+    let store = EventStoreImpl::new(MemoryBackend::new())
+        .with_aggregate_locking(true)
+        .with_operation_timeout(Some(Duration::from_millis(50)));
+    let aggregate_id = "order-1".to_string();
+    let event = Event::new(
+        aggregate_id.clone(),
+        "Order".to_string(),
+        "OrderPlaced".to_string(),
+        1,
+        1,
+        EventData::Json(serde_json::json!({})),
+    );
+
+    // Another save holds the aggregate for longer than the timeout
+    let held = store.aggregate_locks().unwrap().lock([&aggregate_id]).await;
+    let (saved, ()) = tokio::join!(store.save_events(vec![event]), async {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        drop(held);
+    });
+    saved.unwrap();
+    assert_eq!(store.get_aggregate_version(&aggregate_id).await.unwrap(), Some(1));
+}
+
+#[tokio::test]
+async fn test_injected_faults_follow_the_configured_scenario() {
+    let faults = FaultInjector::new()
+        .fail_nth(2, InjectedFault::Io(std::io::ErrorKind::ConnectionReset))
+        .fail_next(BackendOperation::LoadEvents, 2, InjectedFault::Database("replica unavailable".to_string()));
+    let store = EventStoreImpl::new(FaultInjectingBackend::new(MemoryBackend::new(), faults.clone()));
+    let order = |version| Event::new(
+        "order-1".to_string(),
+        "Order".to_string(),
+        "OrderUpdated".to_string(),
+        1,
+        version,
+        EventData::Json(serde_json::json!({ "version": version })),
+    );
+    let aggregate_id = "order-1".to_string();
+
+    // Call 1 passes, call 2 of any kind fails, call 3 passes
+    store.save_events(vec![order(1)]).await.unwrap();
+    let error = store.save_events(vec![order(2)]).await.unwrap_err();
+    assert!(matches!(&error, EventualiError::Io(e) if e.kind() == std::io::ErrorKind::ConnectionReset));
+    assert!(error.is_retryable());
+    store.save_events(vec![order(2)]).await.unwrap();
+
+    // The next two loads fail, then loads recover
+    for _ in 0..2 {
+        assert!(matches!(store.load_events(&aggregate_id, None).await, Err(EventualiError::DatabaseError(_))));
+    }
+    assert_eq!(store.load_events(&aggregate_id, None).await.unwrap().len(), 2);
+
+    // SQLITE_BUSY on demand fails every call, retryably, until switched off
+    faults.set_sqlite_busy(true);
+    let busy = store.get_aggregate_version(&aggregate_id).await.unwrap_err();
+    assert!(busy.is_retryable());
+    assert!(busy.to_string().contains("database is locked"));
+    assert!(store.save_events(vec![order(3)]).await.unwrap_err().is_retryable());
+    faults.set_sqlite_busy(false);
+    store.save_events(vec![order(3)]).await.unwrap();
+    assert_eq!(store.get_aggregate_version(&aggregate_id).await.unwrap(), Some(3));
+
+    assert_eq!(faults.calls(BackendOperation::SaveEvents), 5);
+    assert_eq!(faults.calls(BackendOperation::LoadEvents), 3);
+    assert_eq!(faults.calls(BackendOperation::GetAggregateVersion), 2);
+    assert_eq!(faults.total_calls(), 10);
+    assert_eq!(faults.faults_injected(), 5);
+}
+
+#[tokio::test]
+async fn test_outbox_relay_redelivers_a_batch_whose_mark_failed() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string()).with_transactional_outbox(true);
+    let mut sqlite = SQLiteBackend::new(&config).await.unwrap();
+    sqlite.initialize().await.unwrap();
+    // Events reach the streamer, then recording that they did fails
+    let faults = FaultInjector::new().fail_nth_of(BackendOperation::MarkEventsPublished, 1, InjectedFault::SqliteBusy);
+    let store: Arc<dyn EventStore + Send + Sync> =
+        Arc::new(EventStoreImpl::new(FaultInjectingBackend::new(sqlite, faults.clone())));
+
+    let events: Vec<Event> = (1..=3)
+        .map(|version| Event::new(
+            "order-1".to_string(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "version": version })),
+        ))
+        .collect();
+    let event_ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+    store.save_events(events).await.unwrap();
+
+    let streamer = Arc::new(InMemoryEventStreamer::new(100));
+    let mut receiver = streamer.subscribe(SubscriptionBuilder::new().build()).await.unwrap();
+    let relay = OutboxRelay::new(store.clone(), streamer.clone()).with_batch_size(10);
+
+    let error = relay.relay_pending().await.unwrap_err();
+    assert!(error.is_retryable());
+    assert_eq!(store.load_unpublished_events(10).await.unwrap().len(), 3);
+
+    // The retry publishes the same events again, then marks them
+    assert_eq!(relay.relay_pending().await.unwrap(), 3);
+    let mut delivered = Vec::new();
+    for _ in 0..6 {
+        delivered.push(receiver.recv().await.unwrap().event.id);
+    }
+    assert_eq!(&delivered[..3], event_ids.as_slice());
+    assert_eq!(&delivered[3..], event_ids.as_slice());
+
+    assert_eq!(relay.relay_pending().await.unwrap(), 0);
+    assert_eq!(faults.calls(BackendOperation::MarkEventsPublished), 2);
+    assert_eq!(faults.faults_injected(), 1);
+}
+
+#[tokio::test]
+async fn test_custom_id_allocator_assigns_sequential_ids_the_store_accepts() {
+    /// Hands out IDs from a counter, as a central sequencer would
+    struct Sequencer {
+        next: std::sync::atomic::AtomicU64,
+    }
+
+    impl IdAllocator for Sequencer {
+        fn allocate(&self) -> eventuali_core::Result<Uuid> {
+            let n = self.next.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Uuid::from_u64_pair(0x5e9_0000, n))
+        }
+    }
+
+    /// A sequencer that cannot be reached
+    struct Unreachable;
+
+    impl IdAllocator for Unreachable {
+        fn allocate(&self) -> eventuali_core::Result<Uuid> {
+            Err(EventualiError::Configuration("sequencer unreachable".to_string()))
+        }
+    }
+
+    let sequencer = Arc::new(Sequencer { next: std::sync::atomic::AtomicU64::new(1) });
+    let mut sqlite_store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    sqlite_store.set_id_allocator(sequencer.clone());
+    let memory_store = EventStoreImpl::new(MemoryBackend::new()).with_id_allocator(sequencer);
+
+    let event = |aggregate_id: &str, version: i64| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Shipment".to_string(),
+            "ShipmentScanned".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "scan": version })),
+        )
+        .with_id(sqlite_store.new_event_id().unwrap())
+    };
+    let events: Vec<Event> = (1..=3).map(|version| event("shipment-1", version)).collect();
+    let duplicate = event("shipment-2", 1).with_id(events[0].id);
+
+    // The sequencer belongs to these stores alone, so its IDs are contiguous
+    let ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+    assert_eq!(ids, (1..=3).map(|n| Uuid::from_u64_pair(0x5e9_0000, n)).collect::<Vec<_>>());
+
+    // Stores without an allocator, and Event::new, keep the process default
+    let plain_store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    assert_eq!(plain_store.new_event_id().unwrap().get_version_num(), 4);
+
+    let stores: [&(dyn EventStore + Send + Sync); 2] = [sqlite_store.as_ref(), &memory_store];
+    for store in stores {
+        store.save_events(events.clone()).await.unwrap();
+        let loaded = store.load_events(&"shipment-1".to_string(), None).await.unwrap();
+        assert_eq!(loaded.iter().map(|e| e.id).collect::<Vec<_>>(), ids);
+
+        // Reusing an ID is rejected as such, not as a version conflict
+        let err = store.save_events(vec![duplicate.clone()]).await.unwrap_err();
+        assert!(matches!(err, EventualiError::Validation(ref message) if message.contains(&ids[0].to_string())));
+        assert!(store.load_events(&"shipment-2".to_string(), None).await.unwrap().is_empty());
+    }
+
+    // A failing allocator is reported to the caller rather than panicking
+    let unreachable = EventStoreImpl::new(MemoryBackend::new()).with_id_allocator(Arc::new(Unreachable));
+    let err = unreachable.new_event_id().unwrap_err();
+    assert!(matches!(err, EventualiError::Configuration(ref message) if message.contains("unreachable")));
+}
+
+#[cfg(feature = "parquet")]
+#[tokio::test]
+async fn test_parquet_export_round_trips_rows_and_fields() {
+    use arrow_array::{Array, Int64Array, StringArray};
+    use eventuali_core::ParquetExport;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+    let store = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let event = |aggregate_id: &str, aggregate_type: &str, version: i64, data: EventData| {
+        Event::new(aggregate_id.to_string(), aggregate_type.to_string(), "Recorded".to_string(), 1, version, data)
+    };
+    let mut events: Vec<Event> = (1..=5)
+        .map(|version| event("order-1", "Order", version, EventData::Json(serde_json::json!({ "line": version }))))
+        .collect();
+    events.push(event("invoice-1", "Invoice", 1, EventData::Protobuf(vec![1, 2, 3])));
+    for event in &events {
+        store.save_events(vec![event.clone()]).await.unwrap();
+    }
+
+    let dir = tempfile::TempDir::new().unwrap();
+    let path = dir.path().join("events.parquet");
+    let report = store.export_parquet(None, Box::new(std::fs::File::create(&path).unwrap())).await.unwrap();
+    assert_eq!((report.rows_written, report.last_global_position), (6, 6));
+
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    let batches: Vec<_> = reader.map(|batch| batch.unwrap()).collect();
+    assert_eq!(batches.iter().map(|batch| batch.num_rows()).sum::<usize>(), 6);
+    let batch = &batches[0];
+    let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+    let ids = column("id");
+    let ids = ids.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(ids.value(2), events[2].id.to_string());
+    let versions = column("aggregate_version");
+    assert_eq!(versions.as_any().downcast_ref::<Int64Array>().unwrap().value(2), 3);
+    let payloads = column("payload");
+    let payloads = payloads.as_any().downcast_ref::<StringArray>().unwrap();
+    assert_eq!(serde_json::from_str::<serde_json::Value>(payloads.value(2)).unwrap(), serde_json::json!({ "line": 3 }));
+    // Non-JSON payloads go to the binary column instead
+    assert!(payloads.is_null(5));
+
+    // Filtering by aggregate type, in small pages, keeps only that type's rows
+    let report = ParquetExport::new()
+        .with_batch_size(2)
+        .export(store.as_ref(), Some("Order"), std::fs::File::create(&path).unwrap())
+        .await
+        .unwrap();
+    assert_eq!((report.rows_written, report.last_global_position), (5, 5));
+    let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+    assert_eq!(reader.map(|batch| batch.unwrap().num_rows()).sum::<usize>(), 5);
+}
+
+#[tokio::test]
+async fn test_batches_over_overlapping_aggregates_in_opposite_orders_both_commit() {
+    let counter_event = |aggregate_id: &str, version: i64| Event::new(
+        aggregate_id.to_string(),
+        "Counter".to_string(),
+        "Incremented".to_string(),
+        1,
+        version,
+        EventData::Json(serde_json::json!({ "by": 1 })),
+    );
+    let rounds = 20;
+
+    for aggregate_locking in [false, true] {
+        let db_path = std::env::temp_dir().join(format!("eventuali-lock-order-{}.db", Uuid::new_v4()));
+        let db_path = db_path.to_string_lossy().to_string();
+        let config = EventStoreConfig::sqlite(db_path.clone()).with_aggregate_locking(aggregate_locking);
+
+        // Separate pools stand in for two processes writing the same database
+        let mut writers = Vec::new();
+        for _ in 0..2 {
+            let store = create_event_store(config.clone()).await.unwrap();
+            writers.push(Arc::<dyn EventStore + Send + Sync>::from(store));
+        }
+
+        let run = async {
+            for round in 0..rounds {
+                let first = vec![counter_event("a", 2 * round + 1), counter_event("b", 2 * round + 1)];
+                let second = vec![counter_event("b", 2 * round + 2), counter_event("a", 2 * round + 2)];
+                let (store_a, store_b) = (writers[0].clone(), writers[1].clone());
+                let first = tokio::spawn(async move { store_a.save_events(first).await });
+                let second = tokio::spawn(async move { store_b.save_events(second).await });
+                first.await.unwrap().expect("first batch should commit");
+                second.await.unwrap().expect("second batch should commit");
+            }
+        };
+        tokio::time::timeout(std::time::Duration::from_secs(60), run)
+            .await
+            .expect("opposite-order batches deadlocked");
+
+        for aggregate_id in ["a", "b"] {
+            let versions: Vec<i64> = writers[0]
+                .load_events(&aggregate_id.to_string(), None)
+                .await
+                .unwrap()
+                .iter()
+                .map(|e| e.aggregate_version)
+                .collect();
+            assert_eq!(versions, (1..=2 * rounds).collect::<Vec<_>>());
+        }
+
+        drop(writers);
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_saves_expecting_a_stale_version_conflict_atomically() {
+    let counter_event = |aggregate_id: &str, version: i64| Event::new(
+        aggregate_id.to_string(),
+        "Counter".to_string(),
+        "Incremented".to_string(),
+        1,
+        version,
+        EventData::Json(serde_json::json!({ "by": 1 })),
+    );
+
+    let memory = EventStoreImpl::new(MemoryBackend::new());
+    memory.save_events_expecting(vec![counter_event("a", 1), counter_event("a", 2)], Some(0)).await.unwrap();
+    assert!(matches!(
+        memory.save_events_expecting(vec![counter_event("a", 3)], Some(1)).await,
+        Err(EventualiError::OptimisticConcurrency { expected: 1, actual: 2 })
+    ));
+    memory.save_events_expecting(vec![counter_event("a", 3)], Some(2)).await.unwrap();
+    assert!(matches!(
+        memory.save_events_expecting(vec![counter_event("a", 4), counter_event("b", 1)], Some(3)).await,
+        Err(EventualiError::Validation(_))
+    ));
+    memory.save_events(vec![counter_event("a", 4), counter_event("b", 1)]).await.unwrap();
+
+    let db_path = std::env::temp_dir().join(format!("eventuali-expected-version-{}.db", Uuid::new_v4()));
+    let db_path = db_path.to_string_lossy().to_string();
+    let config = EventStoreConfig::sqlite(db_path.clone());
+
+    // Separate pools stand in for two processes writing the same database
+    let mut writers = Vec::new();
+    for _ in 0..2 {
+        let store = create_event_store(config.clone()).await.unwrap();
+        writers.push(Arc::<dyn EventStore + Send + Sync>::from(store));
+    }
+
+    let rounds = 20;
+    for round in 0..rounds {
+        // Both writers read the same version, so exactly one save may commit
+        let expected = writers[0].get_aggregate_version(&"a".to_string()).await.unwrap().unwrap_or(0);
+        assert_eq!(expected, round);
+        let saves: Vec<_> = writers
+            .iter()
+            .map(|store| {
+                let store = store.clone();
+                let events = vec![counter_event("a", expected + 1)];
+                tokio::spawn(async move { store.save_events_expecting(events, Some(expected)).await })
+            })
+            .collect();
+
+        let mut committed = 0;
+        for save in saves {
+            match save.await.unwrap() {
+                Ok(()) => committed += 1,
+                Err(EventualiError::OptimisticConcurrency { expected: stale, actual }) => {
+                    assert_eq!((stale, actual), (expected, expected + 1));
+                }
+                Err(e) => panic!("unexpected error: {e}"),
+            }
+        }
+        assert_eq!(committed, 1);
+    }
+
+    let versions: Vec<i64> = writers[1]
+        .load_events(&"a".to_string(), None)
+        .await
+        .unwrap()
+        .iter()
+        .map(|e| e.aggregate_version)
+        .collect();
+    assert_eq!(versions, (1..=rounds).collect::<Vec<_>>());
+
+    drop(writers);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{db_path}{suffix}"));
+    }
+}
+
+#[tokio::test]
+async fn test_aggregate_state_can_be_reconstructed_at_an_earlier_version() {
+    let sqlite = create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap();
+    let memory: Box<dyn EventStore + Send + Sync> = Box::new(EventStoreImpl::new(MemoryBackend::new()));
+    let aggregate_id = "account-1".to_string();
+    let deposits: Vec<Event> = (1..=10)
+        .map(|version| Event::new(
+            aggregate_id.clone(),
+            "Account".to_string(),
+            "Deposited".to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({ "amount": version * 10 })),
+        ))
+        .collect();
+    let balance = |events: &[Event]| {
+        eventuali_core::aggregate::replay(0i64, events, |balance, event| {
+            let EventData::Json(data) = &event.data else { panic!("expected JSON payload") };
+            *balance += data["amount"].as_i64().unwrap();
+            Ok(())
+        })
+        .unwrap()
+    };
+
+    for store in [&sqlite, &memory] {
+        store.save_events(deposits.clone()).await.unwrap();
+        // Another aggregate's events never leak into the reconstruction
+        store.save_events(vec![Event::new(
+            "account-2".to_string(),
+            "Account".to_string(),
+            "Deposited".to_string(),
+            1,
+            1,
+            EventData::Json(serde_json::json!({ "amount": 1_000 })),
+        )]).await.unwrap();
+
+        let at_four = store.load_events_at_version(&aggregate_id, 4).await.unwrap();
+        assert_eq!(at_four, deposits[..4]);
+        assert_eq!(balance(&at_four), 10 + 20 + 30 + 40);
+
+        assert_eq!(store.load_events_at_version(&aggregate_id, 10).await.unwrap(), deposits);
+        assert_eq!(store.load_events_at_version(&aggregate_id, 50).await.unwrap(), deposits);
+        assert_eq!(balance(&store.load_events_at_version(&aggregate_id, 50).await.unwrap()), 550);
+        assert!(store.load_events_at_version(&aggregate_id, 0).await.unwrap().is_empty());
+        assert!(store.load_events_at_version(&"missing".to_string(), 5).await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn test_deleted_aggregates_are_removed_or_tombstoned() {
+    let config = EventStoreConfig::sqlite(":memory:".to_string())
+        .with_recent_events_capacity(10)
+        .with_existence_cache_capacity(10);
+    let store = create_event_store(config).await.unwrap();
+
+    let event = |aggregate_id: &str, version: i64| {
+        let mut event = Event::new(
+            aggregate_id.to_string(),
+            "User".to_string(),
+            "EmailChanged".to_string(),
+            2,
+            version,
+            EventData::Json(serde_json::json!({ "email": format!("{aggregate_id}@example.com") })),
+        );
+        event.metadata.user_id = Some(aggregate_id.to_string());
+        event
+    };
+    let user_1: Vec<Event> = (1..=3).map(|v| event("user-1", v)).collect();
+    store.save_events(user_1.clone()).await.unwrap();
+    store.save_events(vec![event("user-2", 1), event("user-2", 2)]).await.unwrap();
+    assert!(store.aggregate_exists(&"user-1".to_string()).await.unwrap());
+
+    // Tombstoning keeps every row in place with its content gone
+    let tombstoned = store
+        .delete_aggregate_events_with_mode(&"user-1".to_string(), DeleteMode::SoftTombstone)
+        .await
+        .unwrap();
+    assert_eq!(tombstoned, 3);
+    let erased = store.load_events(&"user-1".to_string(), None).await.unwrap();
+    assert_eq!(erased.len(), 3);
+    for (erased, original) in erased.iter().zip(&user_1) {
+        assert_eq!(erased.id, original.id);
+        assert_eq!(erased.aggregate_version, original.aggregate_version);
+        assert_eq!(erased.event_type, TOMBSTONE_EVENT_TYPE);
+        assert!(!serde_json::to_string(&erased.data).unwrap().contains("example.com"));
+        assert_eq!(erased.metadata, EventMetadata::default());
+    }
+    assert!(store.get_events_by_user_id("user-1", 10).await.unwrap().is_empty());
+    assert!(store.recent_events(10).await.unwrap().iter().all(|e| e.event_type == TOMBSTONE_EVENT_TYPE || e.aggregate_id == "user-2"));
+    // The stream stays contiguous, so appends continue after it
+    assert_eq!(store.get_aggregate_version(&"user-1".to_string()).await.unwrap(), Some(3));
+    store.save_events(vec![event("user-1", 4)]).await.unwrap();
+
+    // A hard delete removes the aggregate entirely
+    assert_eq!(store.delete_aggregate_events(&"user-1".to_string()).await.unwrap(), 4);
+    assert!(store.load_events(&"user-1".to_string(), None).await.unwrap().is_empty());
+    assert!(!store.aggregate_exists(&"user-1".to_string()).await.unwrap());
+    assert!(store.recent_events(10).await.unwrap().iter().all(|e| e.aggregate_id == "user-2"));
+    assert_eq!(store.load_events(&"user-2".to_string(), None).await.unwrap().len(), 2);
+    assert_eq!(store.delete_aggregate_events(&"user-1".to_string()).await.unwrap(), 0);
+
+    // user-1's last event held the newest position; deleting it must not let a
+    // later save take that position again behind readers that already passed it
+    let before: Vec<u64> = store.load_events_after_position(0, 10).await.unwrap().iter().map(|(p, _)| *p).collect();
+    assert_eq!(before, vec![4, 5]);
+    store.save_events(vec![event("user-3", 1)]).await.unwrap();
+    let after = store.load_events_after_position(5, 10).await.unwrap();
+    assert_eq!(after.len(), 1);
+    assert_eq!(after[0].0, 7);
+
+    let memory: Box<dyn EventStore + Send + Sync> = Box::new(EventStoreImpl::new(MemoryBackend::new()));
+    assert!(matches!(
+        memory.delete_aggregate_events(&"user-1".to_string()).await,
+        Err(EventualiError::Configuration(_))
+    ));
+}
@@ -3,7 +3,6 @@ use eventuali_core::{
     streaming::{InMemoryEventStreamer, EventStreamer, SubscriptionBuilder}
 };
 use std::time::Instant;
-use tokio;
 use uuid::Uuid;
 
 #[tokio::test]
@@ -65,7 +64,7 @@ async fn test_streaming_performance() {
         .with_id("perf-test".to_string())
         .build();
     
-    let receiver = streamer.subscribe(subscription).await.unwrap();
+    let _receiver = streamer.subscribe(subscription).await.unwrap();
     
     let event_count = 5000;
     let start = Instant::now();
@@ -1,10 +1,19 @@
 use eventuali_core::{
-    Event, EventData,
+    Event, EventData, EventStore, EventStoreConfig, EventStoreImpl, EventualiError, MemoryBackend,
+    create_event_store,
+    ProjectionSnapshotStore, SqliteProjectionSnapshotStore,
     streaming::{
-        InMemoryEventStreamer, EventStreamer,
+        InMemoryEventStreamer, EventStreamer, SharedStreamer,
         SubscriptionBuilder,
-        StreamEvent
-    }
+        StreamEvent, Projection, ProjectionProcessor, EventStreamProcessor,
+        CatchUpEvent, CatchUpSubscription, DerivingProjection, DerivedEvent, DERIVED_BY_HEADER,
+        TypedProjection, LagWatchdog, LagAlert, SagaHandler, SagaProcessor
+    },
+};
+#[cfg(feature = "observability")]
+use eventuali_core::{
+    observability::{HealthChecker, HealthStatus, MetricLabels},
+    MetricsCollector, ObservabilityConfig,
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -321,4 +330,949 @@ async fn test_position_tracking() {
         assert_eq!(stream_event.stream_position, expected_pos);
         assert_eq!(stream_event.global_position, expected_pos);
     }
-}
\ No newline at end of file
+}
+struct CountingProjection {
+    totals: Arc<Mutex<std::collections::BTreeMap<String, i64>>>,
+    last_position: Arc<Mutex<Option<u64>>>,
+}
+
+impl CountingProjection {
+    fn new() -> Self {
+        Self {
+            totals: Arc::new(Mutex::new(std::collections::BTreeMap::new())),
+            last_position: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Projection for CountingProjection {
+    async fn handle_event(&self, event: &Event) -> eventuali_core::Result<()> {
+        *self.totals.lock().await.entry(event.aggregate_id.clone()).or_insert(0) += 1;
+        Ok(())
+    }
+
+    async fn reset(&self) -> eventuali_core::Result<()> {
+        self.totals.lock().await.clear();
+        *self.last_position.lock().await = None;
+        Ok(())
+    }
+
+    async fn get_last_processed_position(&self) -> eventuali_core::Result<Option<u64>> {
+        Ok(*self.last_position.lock().await)
+    }
+
+    async fn set_last_processed_position(&self, position: u64) -> eventuali_core::Result<()> {
+        *self.last_position.lock().await = Some(position);
+        Ok(())
+    }
+
+    async fn snapshot_state(&self) -> eventuali_core::Result<Option<Vec<u8>>> {
+        Ok(Some(serde_json::to_vec(&*self.totals.lock().await)?))
+    }
+
+    async fn restore_state(&self, state: &[u8]) -> eventuali_core::Result<()> {
+        *self.totals.lock().await = serde_json::from_slice(state)?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_projection_rebuild_from_snapshot_matches_full_rebuild() {
+    let events: Vec<StreamEvent> = (0..100u64)
+        .map(|i| StreamEvent {
+            event: Event::new(
+                format!("user-{}", i % 7),
+                "User".to_string(),
+                "UserUpdated".to_string(),
+                1,
+                (i / 7) as i64 + 1,
+                EventData::from_json(&serde_json::json!({"seq": i})).unwrap(),
+            ),
+            stream_position: i / 7 + 1,
+            global_position: i + 1,
+        })
+        .collect();
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let snapshots = SqliteProjectionSnapshotStore::new(pool, None);
+    snapshots.initialize().await.unwrap();
+
+    // Fold the first 80 events, then snapshot at position 80
+    let snapshotted = ProjectionProcessor::new(CountingProjection::new());
+    snapshotted.rebuild("user-counts", &events[..80], Some(&snapshots)).await.unwrap();
+    let snapshot = snapshotted.take_snapshot("user-counts", &snapshots).await.unwrap().unwrap();
+    assert_eq!(snapshot.position, 80);
+
+    // A second snapshot at the same position conflicts with the first
+    assert!(matches!(
+        snapshotted.take_snapshot("user-counts", &snapshots).await,
+        Err(EventualiError::SnapshotConflict(_))
+    ));
+
+    let from_zero = ProjectionProcessor::new(CountingProjection::new());
+    let full_count = from_zero.rebuild("user-counts", &events, None).await.unwrap();
+
+    let from_snapshot = ProjectionProcessor::new(CountingProjection::new());
+    let tail_count = from_snapshot.rebuild("user-counts", &events, Some(&snapshots)).await.unwrap();
+
+    assert_eq!(full_count, 100);
+    assert_eq!(tail_count, 20);
+    assert_eq!(
+        *from_zero.projection().totals.lock().await,
+        *from_snapshot.projection().totals.lock().await
+    );
+    assert_eq!(from_snapshot.projection().get_last_processed_position().await.unwrap(), Some(100));
+
+    assert_eq!(snapshots.delete_snapshots("user-counts").await.unwrap(), 1);
+    assert!(snapshots.load_latest_snapshot("user-counts").await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_dedup_window_skips_redelivered_events() {
+    let stream_event = |aggregate_id: &str, position: u64| StreamEvent {
+        event: Event::new(
+            aggregate_id.to_string(),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            1,
+            EventData::from_json(&serde_json::json!({"seq": position})).unwrap(),
+        ),
+        stream_position: 1,
+        global_position: position,
+    };
+    let first = stream_event("user-1", 1);
+    let second = stream_event("user-2", 2);
+    let third = stream_event("user-3", 3);
+
+    let processor = ProjectionProcessor::new(CountingProjection::new()).with_dedup_window(2);
+
+    processor.process_event(&first).await.unwrap();
+    processor.process_event(&first).await.unwrap();
+    assert_eq!(processor.projection().totals.lock().await.get("user-1"), Some(&1));
+    assert_eq!(processor.duplicates_skipped(), 1);
+
+    // Re-seeing `first` refreshed it, so `second` is evicted by `third`
+    processor.process_event(&second).await.unwrap();
+    processor.process_event(&first).await.unwrap();
+    processor.process_event(&third).await.unwrap();
+    processor.process_event(&first).await.unwrap();
+    processor.process_event(&second).await.unwrap();
+
+    let totals = processor.projection().totals.lock().await.clone();
+    assert_eq!(totals.get("user-1"), Some(&1));
+    assert_eq!(totals.get("user-2"), Some(&2));
+    assert_eq!(totals.get("user-3"), Some(&1));
+    assert_eq!(processor.duplicates_skipped(), 3);
+
+    // Without a window every delivery is handled
+    let unguarded = ProjectionProcessor::new(CountingProjection::new());
+    unguarded.process_event(&first).await.unwrap();
+    unguarded.process_event(&first).await.unwrap();
+    assert_eq!(unguarded.projection().totals.lock().await.get("user-1"), Some(&2));
+}
+
+/// Fails the first delivery of every event, then counts like `CountingProjection`
+struct RetriedProjection {
+    inner: CountingProjection,
+    failed: Mutex<std::collections::HashSet<eventuali_core::EventId>>,
+}
+
+#[async_trait::async_trait]
+impl Projection for RetriedProjection {
+    async fn handle_event(&self, event: &Event) -> eventuali_core::Result<()> {
+        if self.failed.lock().await.insert(event.id) {
+            return Err(eventuali_core::EventualiError::InvalidState("read model unavailable".to_string()));
+        }
+        self.inner.handle_event(event).await
+    }
+
+    async fn reset(&self) -> eventuali_core::Result<()> {
+        self.inner.reset().await
+    }
+
+    async fn get_last_processed_position(&self) -> eventuali_core::Result<Option<u64>> {
+        self.inner.get_last_processed_position().await
+    }
+
+    async fn set_last_processed_position(&self, position: u64) -> eventuali_core::Result<()> {
+        self.inner.set_last_processed_position(position).await
+    }
+}
+
+#[tokio::test]
+async fn test_dedup_window_retries_events_that_failed() {
+    let stream_event = StreamEvent {
+        event: Event::new(
+            "user-1".to_string(),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            1,
+            EventData::from_json(&serde_json::json!({"seq": 1})).unwrap(),
+        ),
+        stream_position: 1,
+        global_position: 1,
+    };
+    let processor = ProjectionProcessor::new(RetriedProjection {
+        inner: CountingProjection::new(),
+        failed: Mutex::new(std::collections::HashSet::new()),
+    })
+    .with_dedup_window(8);
+
+    assert!(processor.process_event(&stream_event).await.is_err());
+    processor.process_event(&stream_event).await.unwrap();
+    processor.process_event(&stream_event).await.unwrap();
+
+    assert_eq!(processor.projection().inner.totals.lock().await.get("user-1"), Some(&1));
+    assert_eq!(processor.duplicates_skipped(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_catch_up_replay_is_throttled_until_live() {
+    let store = EventStoreImpl::new(MemoryBackend::new());
+    let history: Vec<Event> = (1..=100)
+        .map(|version| Event::new(
+            format!("user-{}", version % 4),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            (version - 1) / 4 + 1,
+            EventData::from_json(&serde_json::json!({"seq": version})).unwrap(),
+        ))
+        .collect();
+    store.save_events(history).await.unwrap();
+    let store: &(dyn EventStore + Send + Sync) = &store;
+
+    let subscription = SubscriptionBuilder::new()
+        .with_id("throttled".to_string())
+        .with_replay_rate_limit(400)
+        .build();
+    assert_eq!(subscription.replay_rate_limit, Some(400));
+
+    // With the clock paused only the throttle takes time: the first event goes
+    // straight away and each of the other 99 waits one 2.5ms slot, with the
+    // last wake-up rounded up to Tokio's millisecond timer
+    let processor = ProjectionProcessor::new(CountingProjection::new());
+    let started = tokio::time::Instant::now();
+    let replayed = processor.catch_up(store, &subscription, 16).await.unwrap();
+    let throttled_elapsed = started.elapsed();
+    assert_eq!(replayed, 100);
+    let slots = Duration::from_micros(2500) * 99;
+    assert!(
+        throttled_elapsed >= slots && throttled_elapsed < slots + Duration::from_millis(1),
+        "replay took {throttled_elapsed:?}"
+    );
+    assert_eq!(processor.projection().get_last_processed_position().await.unwrap(), Some(100));
+
+    // At the live tail the limit no longer applies
+    let live_started = tokio::time::Instant::now();
+    for i in 0..400u64 {
+        let event = Event::new(
+            "user-live".to_string(),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            i as i64 + 1,
+            EventData::from_json(&serde_json::json!({"seq": i})).unwrap(),
+        );
+        processor
+            .process_event(&StreamEvent { event, stream_position: i + 1, global_position: 101 + i })
+            .await
+            .unwrap();
+    }
+    assert_eq!(live_started.elapsed(), Duration::ZERO);
+    assert_eq!(processor.projection().totals.lock().await.get("user-live"), Some(&400));
+
+    // Nothing new in the store, so a second catch-up returns straight away
+    assert_eq!(processor.catch_up(store, &subscription, 16).await.unwrap(), 0);
+
+    // Without a limit the same replay is not paced, and filters still apply
+    let unthrottled = ProjectionProcessor::new(CountingProjection::new());
+    let filtered = SubscriptionBuilder::new()
+        .filter_by_event_type("UserDeleted".to_string())
+        .build();
+    let started = tokio::time::Instant::now();
+    assert_eq!(unthrottled.catch_up(store, &filtered, 16).await.unwrap(), 0);
+    assert_eq!(started.elapsed(), Duration::ZERO);
+    assert_eq!(unthrottled.projection().get_last_processed_position().await.unwrap(), Some(100));
+}
+
+#[tokio::test]
+async fn test_catch_up_subscription_signals_caught_up_once() {
+    let streamer = Arc::new(InMemoryEventStreamer::new(100));
+    let mut store = EventStoreImpl::new(MemoryBackend::new());
+    store.set_event_streamer(streamer.clone());
+    let store: Arc<dyn EventStore + Send + Sync> = Arc::new(store);
+
+    let user_event = |aggregate_id: &str, version: i64| Event::new(
+        aggregate_id.to_string(),
+        "User".to_string(),
+        "UserUpdated".to_string(),
+        1,
+        version,
+        EventData::from_json(&serde_json::json!({"version": version})).unwrap(),
+    );
+    let seeded: Vec<Event> = (1..=10).map(|version| user_event("user-1", version)).collect();
+    store.save_events(seeded).await.unwrap();
+
+    let mut subscription = CatchUpSubscription::start(
+        streamer.as_ref(),
+        store.clone(),
+        SubscriptionBuilder::new().build(),
+        0,
+        3,
+    )
+    .await
+    .unwrap();
+
+    // Committed after the subscription opened, so these arrive both from the
+    // store and on the live stream; each must be delivered once
+    store.save_events(vec![user_event("user-2", 1), user_event("user-2", 2)]).await.unwrap();
+
+    let mut delivered = Vec::new();
+    let caught_up_at = loop {
+        match subscription.next().await.unwrap().unwrap() {
+            CatchUpEvent::Event(stream_event) => delivered.push(stream_event.global_position),
+            CatchUpEvent::CaughtUp { global_position } => break global_position,
+        }
+    };
+    assert_eq!(delivered, (1..=12).collect::<Vec<u64>>());
+    assert_eq!(caught_up_at, 12);
+    assert!(subscription.is_caught_up());
+
+    // Live events follow without a second signal
+    store.save_events(vec![user_event("user-2", 3)]).await.unwrap();
+    match subscription.next().await.unwrap().unwrap() {
+        CatchUpEvent::Event(stream_event) => {
+            assert_eq!(stream_event.global_position, 13);
+            assert_eq!(stream_event.event.aggregate_id, "user-2");
+        }
+        CatchUpEvent::CaughtUp { .. } => panic!("caught-up signal sent twice"),
+    }
+    assert!(timeout(Duration::from_millis(50), subscription.next()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_catch_up_subscription_delivers_events_published_out_of_commit_order() {
+    // Events are published by hand, so the store has no streamer of its own
+    let streamer = InMemoryEventStreamer::new(100);
+    let store: Arc<dyn EventStore + Send + Sync> = Arc::new(EventStoreImpl::new(MemoryBackend::new()));
+    let user_event = |version: i64| Event::new(
+        "user-1".to_string(),
+        "User".to_string(),
+        "UserUpdated".to_string(),
+        1,
+        version,
+        EventData::from_json(&serde_json::json!({"version": version})).unwrap(),
+    );
+    store.save_events(vec![user_event(1)]).await.unwrap();
+
+    let mut subscription = CatchUpSubscription::start(&streamer, store.clone(), SubscriptionBuilder::new().build(), 0, 10)
+        .await
+        .unwrap();
+    assert!(matches!(subscription.next().await.unwrap().unwrap(), CatchUpEvent::Event(_)));
+    assert!(matches!(subscription.next().await.unwrap().unwrap(), CatchUpEvent::CaughtUp { global_position: 1 }));
+
+    // Two concurrent saves commit at positions 2 and 3, but 3 is published first
+    store.save_events(vec![user_event(2)]).await.unwrap();
+    store.save_events(vec![user_event(3)]).await.unwrap();
+    streamer.publish_event(user_event(3), 3, 3).await.unwrap();
+    streamer.publish_event(user_event(2), 2, 2).await.unwrap();
+
+    let mut delivered = Vec::new();
+    while let Ok(next) = timeout(Duration::from_millis(50), subscription.next()).await {
+        match next.unwrap().unwrap() {
+            CatchUpEvent::Event(stream_event) => delivered.push(stream_event.global_position),
+            CatchUpEvent::CaughtUp { .. } => panic!("caught-up signal sent twice"),
+        }
+    }
+    assert_eq!(delivered, vec![2, 3]);
+    assert_eq!(subscription.position(), 3);
+}
+
+/// Streamer that takes `delay` to accept each event
+struct SlowStreamer {
+    delay: Duration,
+    published: Arc<Mutex<Vec<u64>>>,
+}
+
+#[async_trait::async_trait]
+impl EventStreamer for SlowStreamer {
+    async fn subscribe(&self, _subscription: eventuali_core::Subscription) -> eventuali_core::Result<eventuali_core::EventStreamReceiver> {
+        Ok(tokio::sync::broadcast::channel(1).1)
+    }
+
+    async fn unsubscribe(&self, _subscription_id: &str) -> eventuali_core::Result<()> {
+        Ok(())
+    }
+
+    async fn publish_event(&self, _event: Event, _stream_position: u64, global_position: u64) -> eventuali_core::Result<()> {
+        tokio::time::sleep(self.delay).await;
+        self.published.lock().await.push(global_position);
+        Ok(())
+    }
+
+    async fn get_stream_position(&self, _stream_id: &str) -> eventuali_core::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    async fn get_global_position(&self) -> eventuali_core::Result<u64> {
+        Ok(self.published.lock().await.last().copied().unwrap_or(0))
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_publish_outbox_keeps_writes_ahead_of_slow_streamer() {
+    let published = Arc::new(Mutex::new(Vec::new()));
+    let streamer = Arc::new(SlowStreamer { delay: Duration::from_millis(25), published: published.clone() });
+    let mut store = EventStoreImpl::new(MemoryBackend::new()).with_publish_outbox(4);
+    store.set_event_streamer(streamer);
+
+    let mut max_pending = 0;
+    let started = tokio::time::Instant::now();
+    for version in 1..=20 {
+        let event = Event::new(
+            "user-1".to_string(),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            version,
+            EventData::from_json(&serde_json::json!({"version": version})).unwrap(),
+        );
+        timeout(Duration::from_secs(5), store.save_events(vec![event])).await.unwrap().unwrap();
+        max_pending = max_pending.max(store.publish_outbox().unwrap().pending());
+        if version == 4 {
+            // Saves up to the outbox capacity do not wait for the streamer at all
+            assert_eq!(started.elapsed(), Duration::ZERO);
+            assert!(published.lock().await.is_empty());
+        }
+    }
+
+    // Every event is committed even though most are still being published
+    assert_eq!(store.load_events(&"user-1".to_string(), None).await.unwrap().len(), 20);
+    assert!(max_pending <= 4, "outbox grew to {max_pending}");
+    assert!(published.lock().await.len() < 20);
+
+    timeout(Duration::from_secs(5), async {
+        while published.lock().await.len() < 20 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(*published.lock().await, (1..=20).collect::<Vec<u64>>());
+    assert_eq!(store.publish_outbox().unwrap().pending(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_close_publishes_queued_events_before_returning() {
+    let published = Arc::new(Mutex::new(Vec::new()));
+    let streamer = Arc::new(SlowStreamer { delay: Duration::from_millis(10), published: published.clone() });
+    let mut store = EventStoreImpl::new(MemoryBackend::new()).with_publish_outbox(8);
+    store.set_event_streamer(streamer);
+
+    let user_event = |version: i64| Event::new(
+        "user-1".to_string(),
+        "User".to_string(),
+        "UserUpdated".to_string(),
+        1,
+        version,
+        EventData::from_json(&serde_json::json!({"version": version})).unwrap(),
+    );
+    for version in 1..=8 {
+        store.save_events(vec![user_event(version)]).await.unwrap();
+    }
+    assert!(published.lock().await.len() < 8);
+
+    timeout(Duration::from_secs(5), store.close()).await.unwrap().unwrap();
+    assert_eq!(*published.lock().await, (1..=8).collect::<Vec<u64>>());
+    assert!(matches!(
+        store.save_events(vec![user_event(9)]).await,
+        Err(EventualiError::InvalidState(_))
+    ));
+}
+
+/// Streamer that rejects every event at an even global position
+struct RejectingStreamer {
+    published: Arc<Mutex<Vec<u64>>>,
+}
+
+#[async_trait::async_trait]
+impl EventStreamer for RejectingStreamer {
+    async fn subscribe(&self, _subscription: eventuali_core::Subscription) -> eventuali_core::Result<eventuali_core::EventStreamReceiver> {
+        Ok(tokio::sync::broadcast::channel(1).1)
+    }
+
+    async fn unsubscribe(&self, _subscription_id: &str) -> eventuali_core::Result<()> {
+        Ok(())
+    }
+
+    async fn publish_event(&self, _event: Event, _stream_position: u64, global_position: u64) -> eventuali_core::Result<()> {
+        if global_position.is_multiple_of(2) {
+            return Err(EventualiError::InvalidState("subscriber gone".to_string()));
+        }
+        self.published.lock().await.push(global_position);
+        Ok(())
+    }
+
+    async fn get_stream_position(&self, _stream_id: &str) -> eventuali_core::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    async fn get_global_position(&self) -> eventuali_core::Result<u64> {
+        Ok(0)
+    }
+}
+
+#[tokio::test]
+async fn test_publish_outbox_reports_rejected_events() {
+    let published = Arc::new(Mutex::new(Vec::new()));
+    let mut store = EventStoreImpl::new(MemoryBackend::new()).with_publish_outbox(8);
+    store.set_event_streamer(Arc::new(RejectingStreamer { published: published.clone() }));
+
+    for version in 1..=4 {
+        let event = Event::new(
+            "user-1".to_string(),
+            "User".to_string(),
+            "UserUpdated".to_string(),
+            1,
+            version,
+            EventData::from_json(&serde_json::json!({"version": version})).unwrap(),
+        );
+        // The write is committed, so a rejected publish does not fail it
+        store.save_events(vec![event]).await.unwrap();
+    }
+    store.close().await.unwrap();
+
+    assert_eq!(*published.lock().await, vec![1, 3]);
+    let failures = store.publish_outbox().unwrap().take_failures();
+    assert_eq!(failures.iter().map(|f| f.global_position).collect::<Vec<_>>(), vec![2, 4]);
+    assert!(failures.iter().all(|f| f.error.contains("subscriber gone")));
+    assert!(store.publish_outbox().unwrap().take_failures().is_empty());
+}
+
+#[tokio::test]
+async fn test_deriving_projection_emits_one_event_per_threshold_crossing() {
+    let store: Arc<dyn EventStore + Send + Sync> = Arc::new(EventStoreImpl::new(MemoryBackend::new()));
+    let amounts = [100, -80, -50, -10, 70, -5];
+    let history: Vec<Event> = amounts
+        .iter()
+        .enumerate()
+        .map(|(i, amount)| Event::new(
+            "account-1".to_string(),
+            "Account".to_string(),
+            if *amount > 0 { "Deposited" } else { "Withdrawn" }.to_string(),
+            1,
+            i as i64 + 1,
+            EventData::from_json(&serde_json::json!({"amount": amount})).unwrap(),
+        ))
+        .collect();
+    store.save_events(history).await.unwrap();
+
+    let balances = || {
+        DerivingProjection::<i64>::new("overdraft", store.clone(), |balance, event| {
+            if let EventData::Json(data) = &event.data {
+                *balance += data["amount"].as_i64().unwrap_or_default();
+            }
+            Ok(())
+        })
+        .with_rule("overdrawn", |before, after, event| {
+            Ok((*before >= 0 && *after < 0).then(|| DerivedEvent::new(
+                "AccountOverdrawn",
+                serde_json::json!({"account_id": event.aggregate_id, "balance": after}),
+            )))
+        })
+    };
+
+    // 100, 20, -30 (crosses), -40, 30, 25: one crossing below zero. Catch-up
+    // also reaches the derived event at position 7, which is skipped rather
+    // than folded into the balance or derived from again.
+    let processor = ProjectionProcessor::new(balances());
+    let subscription = SubscriptionBuilder::new().build();
+    assert_eq!(processor.catch_up(&*store, &subscription, 4).await.unwrap(), 7);
+    assert_eq!(processor.projection().emitted_count(), 1);
+    assert_eq!(processor.projection().state_of("account-1").await, Some(25));
+    assert_eq!(processor.projection().get_last_processed_position().await.unwrap(), Some(7));
+
+    let derived = store.load_events(&"overdraft-account-1".to_string(), None).await.unwrap();
+    assert_eq!(derived.len(), 1);
+    assert_eq!(derived[0].event_type, "AccountOverdrawn");
+    assert_eq!(derived[0].aggregate_type, "overdraft");
+    assert_eq!(derived[0].data, EventData::Json(serde_json::json!({"account_id": "account-1", "balance": -30})));
+    assert_eq!(derived[0].metadata.headers.get(DERIVED_BY_HEADER).map(String::as_str), Some("overdraft"));
+    let crossing = store.load_events(&"account-1".to_string(), Some(2)).await.unwrap();
+    assert_eq!(derived[0].metadata.causation_id, Some(crossing[0].id));
+
+    // Replaying from scratch proposes the same derived version, which the store already holds
+    let rebuilt = ProjectionProcessor::new(balances());
+    assert_eq!(rebuilt.catch_up(&*store, &subscription, 4).await.unwrap(), 7);
+    assert_eq!(rebuilt.projection().emitted_count(), 0);
+    assert_eq!(store.load_events(&"overdraft-account-1".to_string(), None).await.unwrap().len(), 1);
+
+    // Another writer's event at the derived version is a real conflict, not a replay
+    store.save_events(vec![Event::new(
+        "overdraft-account-2".to_string(),
+        "overdraft".to_string(),
+        "ManualAdjustment".to_string(),
+        1,
+        1,
+        EventData::from_json(&serde_json::json!({})).unwrap(),
+    )]).await.unwrap();
+    let overdraw = Event::new(
+        "account-2".to_string(),
+        "Account".to_string(),
+        "Withdrawn".to_string(),
+        1,
+        1,
+        EventData::from_json(&serde_json::json!({"amount": -10})).unwrap(),
+    );
+    assert!(matches!(
+        balances().handle_event(&overdraw).await,
+        Err(EventualiError::OptimisticConcurrency { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_shared_streamer_merges_two_stores_into_one_subscription() {
+    let hub = Arc::new(SharedStreamer::new(1000));
+    let mut orders = EventStoreImpl::new(MemoryBackend::new());
+    let mut billing = EventStoreImpl::new(MemoryBackend::new());
+    // A streamer the store already has keeps its events and positions
+    let orders_own = Arc::new(InMemoryEventStreamer::new(1000));
+    orders.set_event_streamer(orders_own.clone());
+    let mut orders_receiver = orders_own.subscribe(SubscriptionBuilder::new().build()).await.unwrap();
+    hub.attach("orders", &mut orders);
+    hub.attach("billing", &mut billing);
+    let mut receiver = hub.subscribe(SubscriptionBuilder::new().build()).await.unwrap();
+
+    // Both stores assign their own positions from 1; the hub renumbers them
+    let save = |store: Arc<EventStoreImpl<MemoryBackend>>, aggregate_type: &'static str| {
+        tokio::spawn(async move {
+            for version in 1..=10 {
+                for aggregate in ["a", "b"] {
+                    // Both stores have an aggregate "shared", at different versions
+                    let aggregate_id = match aggregate {
+                        "a" => format!("{aggregate_type}-a"),
+                        _ => "shared".to_string(),
+                    };
+                    let version = if aggregate_type == "invoice" && aggregate == "b" { version + 100 } else { version };
+                    let event = Event::new(
+                        aggregate_id,
+                        aggregate_type.to_string(),
+                        "Updated".to_string(),
+                        1,
+                        version,
+                        EventData::Json(serde_json::json!({ "version": version })),
+                    );
+                    store.save_events(vec![event]).await.unwrap();
+                }
+            }
+        })
+    };
+    let (first, second) = tokio::join!(save(Arc::new(orders), "order"), save(Arc::new(billing), "invoice"));
+    first.unwrap();
+    second.unwrap();
+
+    let mut received = Vec::new();
+    for _ in 0..40 {
+        received.push(timeout(Duration::from_secs(1), receiver.recv()).await.unwrap().unwrap());
+    }
+    let positions: Vec<u64> = received.iter().map(|e| e.global_position).collect();
+    assert_eq!(positions, (1..=40).collect::<Vec<u64>>());
+    assert_eq!(hub.get_global_position().await.unwrap(), 40);
+
+    for (store_id, aggregate_id, first) in [("orders", "order-a", 1), ("billing", "invoice-a", 1)] {
+        let versions: Vec<i64> = received
+            .iter()
+            .filter(|e| e.event.aggregate_id == aggregate_id)
+            .map(|e| e.event.aggregate_version)
+            .collect();
+        assert_eq!(versions, (first..first + 10).collect::<Vec<i64>>(), "{aggregate_id}");
+        assert_eq!(hub.store_stream_position(store_id, aggregate_id).unwrap(), Some(10));
+    }
+    assert_eq!(hub.store_stream_position("orders", "shared").unwrap(), Some(10));
+    assert_eq!(hub.store_stream_position("billing", "shared").unwrap(), Some(110));
+    assert_eq!(hub.get_stream_position("shared").await.unwrap(), None);
+
+    // The orders store's own streamer got its 20 events at the store's positions
+    for position in 1..=20u64 {
+        let event = timeout(Duration::from_secs(1), orders_receiver.recv()).await.unwrap().unwrap();
+        assert_eq!(event.global_position, position);
+    }
+    assert_eq!(orders_own.get_stream_position("shared").await.unwrap(), Some(10));
+}
+
+#[tokio::test]
+async fn test_typed_projection_dispatches_only_registered_event_types() {
+    let projection = TypedProjection::<Vec<String>>::new()
+        .on("OrderPlaced", |seen, event| {
+            seen.push(format!("placed:{}", event.aggregate_id));
+            Ok(())
+        })
+        .on("OrderShipped", |seen, event| {
+            seen.push(format!("shipped:{}", event.aggregate_id));
+            Ok(())
+        });
+    assert_eq!(projection.event_types(), vec!["OrderPlaced", "OrderShipped"]);
+    assert!(!projection.handles("OrderCancelled"));
+
+    let event = |aggregate_id: &str, event_type: &str, version| {
+        Event::new(
+            aggregate_id.to_string(),
+            "Order".to_string(),
+            event_type.to_string(),
+            1,
+            version,
+            EventData::Json(serde_json::json!({})),
+        )
+    };
+    for e in [
+        event("order-1", "OrderPlaced", 1),
+        event("order-2", "OrderPlaced", 1),
+        event("order-1", "OrderCancelled", 2),
+        event("order-2", "OrderShipped", 2),
+    ] {
+        projection.handle_event(&e).await.unwrap();
+    }
+
+    assert_eq!(projection.state().await, vec!["placed:order-1", "placed:order-2", "shipped:order-2"]);
+
+    projection.reset().await.unwrap();
+    assert!(projection.state().await.is_empty());
+}
+
+/// Counts events, failing on any whose payload sets `fail`
+#[derive(Default)]
+struct FlakyProjection {
+    handled: std::sync::atomic::AtomicU64,
+}
+
+#[async_trait::async_trait]
+impl Projection for FlakyProjection {
+    async fn handle_event(&self, event: &Event) -> eventuali_core::Result<()> {
+        if let EventData::Json(data) = &event.data {
+            if data["fail"].as_bool() == Some(true) {
+                return Err(EventualiError::InvalidEventData(format!("cannot project {}", event.id)));
+            }
+        }
+        self.handled.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Ok(())
+    }
+
+    async fn reset(&self) -> eventuali_core::Result<()> {
+        Ok(())
+    }
+
+    async fn get_last_processed_position(&self) -> eventuali_core::Result<Option<u64>> {
+        Ok(None)
+    }
+
+    async fn set_last_processed_position(&self, _position: u64) -> eventuali_core::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "observability")]
+#[tokio::test]
+async fn test_projection_metrics_count_processed_events_and_handler_errors() {
+    let collector = Arc::new(MetricsCollector::new(&ObservabilityConfig::default()).unwrap());
+    let processor = ProjectionProcessor::new(FlakyProjection::default()).with_metrics("order-summary", collector.clone());
+
+    for position in 1..=5u64 {
+        let event = Event::new(
+            "order-1".to_string(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            position as i64,
+            EventData::Json(serde_json::json!({ "fail": position == 2 || position == 5 })),
+        );
+        let outcome = processor
+            .process_event(&StreamEvent { event, stream_position: position, global_position: position })
+            .await;
+        assert_eq!(outcome.is_err(), position == 2 || position == 5);
+    }
+    processor.observe_head(8);
+
+    let stats = processor.stats();
+    assert_eq!((stats.events_processed, stats.errors), (3, 2));
+    assert!((stats.error_rate - 0.4).abs() < f64::EPSILON);
+    assert_eq!(stats.last_position, Some(4));
+    assert_eq!(stats.lag, 4);
+    assert!(stats.events_per_second > 0.0);
+
+    let labels = MetricLabels::new().with_label("projection", "order-summary");
+    assert_eq!(collector.gauge_value("eventuali_projection_events_processed", &labels), Some(3.0));
+    assert_eq!(collector.gauge_value("eventuali_projection_errors", &labels), Some(2.0));
+    let rendered = collector.render_prometheus().expect("metrics are enabled");
+    for line in [
+        "eventuali_projection_events_processed{projection=\"order-summary\"} 3",
+        "eventuali_projection_errors{projection=\"order-summary\"} 2",
+        "eventuali_projection_error_rate{projection=\"order-summary\"} 0.4",
+        "eventuali_projection_lag_events{projection=\"order-summary\"} 4",
+        "eventuali_projection_last_position{projection=\"order-summary\"} 4",
+    ] {
+        assert!(rendered.contains(line), "missing {line} in {rendered}");
+    }
+}
+
+#[tokio::test]
+async fn test_lag_watchdog_alerts_once_a_stalled_projection_falls_behind_for_the_window() {
+    let watchdog = Arc::new(LagWatchdog::new(Duration::from_millis(150)));
+    let alerts: Arc<std::sync::Mutex<Vec<LagAlert>>> = Arc::default();
+    let sink = alerts.clone();
+    watchdog.on_alert(move |alert| sink.lock().unwrap().push(alert.clone()));
+
+    let processor = ProjectionProcessor::new(FlakyProjection::default())
+        .with_lag_watchdog("order-summary", watchdog.clone());
+    let event_at = |position: u64| StreamEvent {
+        event: Event::new(
+            "order-1".to_string(),
+            "Order".to_string(),
+            "OrderUpdated".to_string(),
+            1,
+            position as i64,
+            EventData::Json(serde_json::json!({ "fail": false })),
+        ),
+        stream_position: position,
+        global_position: position,
+    };
+
+    // A projection keeping up never alerts, however long it runs
+    for position in 1..=5u64 {
+        processor.process_event(&event_at(position)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(40)).await;
+    }
+    assert!(alerts.lock().unwrap().is_empty());
+
+    // The projection stalls while writes keep arriving
+    let mut head = 5;
+    while alerts.lock().unwrap().is_empty() {
+        assert!(head < 5 + 50, "watchdog never fired");
+        head += 1;
+        processor.observe_head(head);
+        if head == 7 {
+            assert!(watchdog.stalled().is_empty(), "fired before the window elapsed");
+            #[cfg(feature = "observability")]
+            assert_eq!(watchdog.check().await.unwrap().status, HealthStatus::Healthy);
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    let alert = alerts.lock().unwrap()[0].clone();
+    assert_eq!(alert.projection_name, "order-summary");
+    assert_eq!(alert.lag, head - 5);
+    assert_eq!(alert.lag_at_start, 0);
+    assert!(alert.stalled_for >= Duration::from_millis(150));
+    #[cfg(feature = "observability")]
+    {
+        let health = watchdog.check().await.unwrap();
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health.message.contains("order-summary"), "{}", health.message);
+        assert_eq!(health.details["order-summary"], serde_json::json!(head - 5));
+    }
+
+    // Still stalled, but the alert is raised once per stall
+    processor.observe_head(head + 1);
+    assert_eq!(alerts.lock().unwrap().len(), 1);
+    assert_eq!(watchdog.stalled()[0].lag, head + 1 - 5);
+
+    // Catching up clears the stall
+    processor.process_event(&event_at(6)).await.unwrap();
+    assert!(watchdog.stalled().is_empty());
+    #[cfg(feature = "observability")]
+    assert_eq!(watchdog.health_status(), HealthStatus::Healthy);
+}
+
+/// Saga handler that records the events it sees, in order
+#[derive(Clone, Default)]
+struct RecordingSaga {
+    seen: Arc<std::sync::Mutex<Vec<(String, i64)>>>,
+}
+
+#[async_trait::async_trait]
+impl SagaHandler for RecordingSaga {
+    async fn handle_event(&self, event: &Event) -> eventuali_core::Result<()> {
+        self.seen.lock().unwrap().push((event.aggregate_id.clone(), event.aggregate_version));
+        Ok(())
+    }
+}
+
+fn saga_processor(saga: &RecordingSaga, store: &Arc<dyn EventStore + Send + Sync>) -> SagaProcessor {
+    let mut processor = SagaProcessor::new(store.clone());
+    processor.register_handler("OrderPlaced".to_string(), saga.clone());
+    processor.register_handler("PaymentReceived".to_string(), saga.clone());
+    processor
+}
+
+#[tokio::test]
+async fn test_saga_dispatches_events_from_two_aggregates_in_global_order() {
+    let store: Arc<dyn EventStore + Send + Sync> =
+        Arc::from(create_event_store(EventStoreConfig::sqlite(":memory:".to_string())).await.unwrap());
+    for (aggregate_id, event_type, version) in [
+        ("order-1", "OrderPlaced", 1),
+        ("payment-1", "PaymentReceived", 1),
+        ("order-1", "OrderPlaced", 2),
+        ("abandoned-1", "OrderPlaced", 1),
+        ("payment-1", "PaymentReceived", 2),
+        ("payment-1", "PaymentReceived", 3),
+        ("order-1", "OrderPlaced", 3),
+    ] {
+        store
+            .save_events(vec![Event::new(
+                aggregate_id.to_string(),
+                "Checkout".to_string(),
+                event_type.to_string(),
+                1,
+                version,
+                EventData::Json(serde_json::json!({})),
+            )])
+            .await
+            .unwrap();
+    }
+    // Erasing an aggregate leaves position 4 empty for good
+    store.delete_aggregate_events(&"abandoned-1".to_string()).await.unwrap();
+
+    let log: Vec<StreamEvent> = store
+        .load_events_after_position(0, 100)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|(global_position, event)| StreamEvent {
+            stream_position: event.aggregate_version as u64,
+            global_position,
+            event,
+        })
+        .collect();
+    assert_eq!(log.iter().map(|e| e.global_position).collect::<Vec<_>>(), vec![1, 2, 3, 5, 6, 7]);
+    let in_global_order: Vec<(String, i64)> = log
+        .iter()
+        .map(|e| (e.event.aggregate_id.clone(), e.event.aggregate_version))
+        .collect();
+
+    let saga = RecordingSaga::default();
+    let processor = saga_processor(&saga, &store);
+    // Delivered out of order and across the gap, with redeliveries
+    processor.process_event(&log[1]).await.unwrap();
+    // order-1's earlier event is read from the store and dispatched first
+    assert_eq!(*saga.seen.lock().unwrap(), in_global_order[..2]);
+    for index in [0, 3, 5, 0, 2, 4] {
+        processor.process_event(&log[index]).await.unwrap();
+    }
+    assert_eq!(*saga.seen.lock().unwrap(), in_global_order);
+    assert_eq!(processor.last_dispatched_position().await, 7);
+
+    // A saga resuming from a checkpoint only sees later positions
+    let resumed = RecordingSaga::default();
+    let processor = saga_processor(&resumed, &store).starting_after(5);
+    for index in [5, 2, 4] {
+        processor.process_event(&log[index]).await.unwrap();
+    }
+    assert_eq!(*resumed.seen.lock().unwrap(), in_global_order[4..]);
+}
@@ -8,10 +8,15 @@ the performance and memory safety of Rust with the ease of use of Python.
 from ._eventuali import (
     PyEventStore as _PyEventStore, 
     PyEvent as _PyEvent, 
+    PyEventBuilder as EventBuilder,
+    new_event_id,
     PyAggregate as _PyAggregate,
     SnapshotService as _PySnapshotService,
     SnapshotConfig as _PySnapshotConfig,
     AggregateSnapshot as _PyAggregateSnapshot,
+    ProjectionSnapshotStore as _PyProjectionSnapshotStore,
+    ReadModelSink,
+    LagWatchdog,
     # Security classes
     EventEncryption,
     KeyManager,
@@ -84,6 +89,7 @@ from ._eventuali import (
     HealthReport,
     HealthConfig,
     HealthMonitorService,
+    ProbeResult,
     HealthHttpServer,
     # Observability classes
     ObservabilityConfig,
@@ -114,10 +120,12 @@ from .event_store import EventStore
 from .event import Event  
 from .aggregate import Aggregate
 from .streaming import (
-    EventStreamer, EventStreamReceiver, StreamEvent, Subscription,
-    SubscriptionBuilder, Projection, SagaHandler
+    EventStreamer, SharedStreamer, EventStreamReceiver, CatchUpReceiver, CaughtUp, StreamEvent, Subscription,
+    SubscriptionBuilder, Projection, DerivingProjection, TypedProjection, SagaHandler
+)
+from .snapshot import (
+    SnapshotService, SnapshotConfig, AggregateSnapshot, ProjectionSnapshot, ProjectionSnapshotStore
 )
-from .snapshot import SnapshotService, SnapshotConfig, AggregateSnapshot
 from .exceptions import *
 
 __version__ = "0.1.1"
@@ -127,19 +135,31 @@ __email__ = "info@primeval.ai"
 __all__ = [
     "EventStore",
     "Event", 
+    "EventBuilder",
+    "new_event_id",
     "Aggregate",
     # Streaming
     "EventStreamer",
+    "SharedStreamer",
     "EventStreamReceiver",
+    "CatchUpReceiver",
+    "CaughtUp",
     "StreamEvent",
     "Subscription",
     "SubscriptionBuilder",
     "Projection",
+    "DerivingProjection",
+    "TypedProjection",
     "SagaHandler",
+    "LagWatchdog",
     # Snapshots
     "SnapshotService",
     "SnapshotConfig",
     "AggregateSnapshot",
+    "ProjectionSnapshot",
+    "ProjectionSnapshotStore",
+    # Read models
+    "ReadModelSink",
     # Security
     "EventEncryption",
     "KeyManager", 
@@ -211,6 +231,7 @@ __all__ = [
     "HealthReport",
     "HealthConfig",
     "HealthMonitorService",
+    "ProbeResult",
     "HealthHttpServer",
     # Observability
     "ObservabilityConfig",
@@ -243,6 +264,9 @@ __all__ = [
     "SerializationError",
     "AggregateNotFoundError",
     "InvalidEventError",
+    "ApplyError",
+    "QuotaExceededError",
+    "NotDurableError",
     "DatabaseError",
     "ConfigurationError",
     "ProjectionError",
@@ -7,6 +7,7 @@ from typing import Any, Dict, List, Optional, Type, TypeVar, get_type_hints
 from pydantic import BaseModel, Field
 from uuid import UUID, uuid4
 from .event import Event
+from .exceptions import ApplyError
 
 T = TypeVar('T', bound='Aggregate')
 
@@ -109,6 +110,10 @@ class Aggregate(BaseModel, ABC):
             
         Returns:
             Aggregate with state reconstructed from events
+            
+        Raises:
+            ApplyError: If an event fails to apply; it names the event and the
+                aggregate version it was applied at
         """
         if not events:
             raise ValueError("Cannot create aggregate from empty event list")
@@ -124,7 +129,19 @@ class Aggregate(BaseModel, ABC):
         
         # Apply all events
         for event in events:
-            aggregate._apply_event(event)
+            # _apply_event overwrites the version, so keep the one it was stored at
+            stored_version = event.aggregate_version
+            try:
+                aggregate._apply_event(event)
+            except Exception as e:
+                version = stored_version if stored_version is not None else aggregate.version + 1
+                raise ApplyError(
+                    f"Failed to apply event {event.event_id} ({event.get_event_type()}) "
+                    f"at aggregate version {version}: {e}",
+                    event_id=str(event.event_id) if event.event_id else None,
+                    aggregate_version=version,
+                    event_type=event.get_event_type(),
+                ) from e
         
         return aggregate
     
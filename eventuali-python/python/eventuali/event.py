@@ -4,13 +4,24 @@ Event base classes and utilities.
 
 import json
 from abc import ABC, abstractmethod
-from datetime import datetime
+from datetime import datetime, timezone
 from typing import Any, Dict, Optional, Type, TypeVar
 from pydantic import BaseModel, Field
-from uuid import UUID, uuid4
+from uuid import UUID
+
+from ._eventuali import PyEvent as _PyEvent, new_event_id
 
 T = TypeVar('T', bound='Event')
 
+_BASE_FIELDS = (
+    'event_id', 'aggregate_id', 'aggregate_type', 'event_type', 'event_version',
+    'aggregate_version', 'timestamp', 'causation_id', 'correlation_id', 'user_id',
+)
+
+
+def _new_event_id() -> UUID:
+    return UUID(new_event_id())
+
 
 class Event(BaseModel, ABC):
     """
@@ -21,7 +32,7 @@ class Event(BaseModel, ABC):
     """
     
     # These fields will be set automatically by the event store
-    event_id: Optional[UUID] = Field(default_factory=uuid4, description="Unique event identifier")
+    event_id: Optional[UUID] = Field(default_factory=_new_event_id, description="Unique event identifier")
     aggregate_id: Optional[str] = Field(default=None, description="ID of the aggregate that generated this event")
     aggregate_type: Optional[str] = Field(default=None, description="Type of the aggregate")
     event_type: Optional[str] = Field(default=None, description="Type of the event")
@@ -66,6 +77,45 @@ class Event(BaseModel, ABC):
     def from_dict(cls: Type[T], data: Dict[str, Any]) -> T:
         """Create event from dictionary."""
         return cls.model_validate(data)
+    
+    def to_cloudevent(self, source_prefix: str = "") -> Dict[str, Any]:
+        """
+        Convert event to a CloudEvents 1.0 dict.
+        
+        The event type becomes ``type``, the aggregate type (under
+        ``source_prefix``) becomes ``source``, and the aggregate ID, versions
+        and correlation IDs travel as extension attributes.
+        """
+        fields = self.model_dump(mode="json")
+        timestamp = self.timestamp or datetime.utcnow()
+        if timestamp.tzinfo is None:
+            timestamp = timestamp.replace(tzinfo=timezone.utc)
+        metadata = {
+            key: fields[key]
+            for key in ('causation_id', 'correlation_id', 'user_id')
+            if fields.get(key) is not None
+        }
+        return _PyEvent.from_dict({
+            'event_id': fields['event_id'],
+            'aggregate_id': self.aggregate_id,
+            'aggregate_type': self.aggregate_type,
+            'event_type': self.event_type or self.get_event_type(),
+            'event_version': self.event_version or 1,
+            'aggregate_version': self.aggregate_version,
+            'timestamp': timestamp.isoformat(),
+            'data': {key: value for key, value in fields.items() if key not in _BASE_FIELDS},
+            'metadata': metadata,
+        }).to_cloudevent(source_prefix)
+    
+    @classmethod
+    def from_cloudevent(cls: Type[T], cloudevent: Dict[str, Any]) -> T:
+        """Create event from a CloudEvents 1.0 dict, such as one from ``to_cloudevent``."""
+        data = _PyEvent.from_cloudevent(cloudevent).to_dict()
+        metadata = data.pop('metadata', {})
+        for key in ('causation_id', 'correlation_id', 'user_id'):
+            if metadata.get(key) is not None:
+                data[key] = metadata[key]
+        return cls.from_dict(data)
 
 
 class DomainEvent(Event):
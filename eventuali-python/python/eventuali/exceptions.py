@@ -46,6 +46,52 @@ class AggregateNotFoundError(EventualiError):
         super().__init__(message)
 
 
+class ApplyError(EventualiError):
+    """
+    Raised when an event fails to apply while rebuilding an aggregate.
+    
+    Identifies the event that broke the replay; the original exception is
+    chained as ``__cause__``.
+    """
+    
+    def __init__(self, message: str, event_id: str = None, aggregate_version: int = None, event_type: str = None):
+        super().__init__(message)
+        self.event_id = event_id
+        self.aggregate_version = aggregate_version
+        self.event_type = event_type
+
+
+class QuotaExceededError(EventualiError):
+    """
+    Raised when a tenant operation would exceed one of its quotas.
+    
+    ``resource`` is the limited resource (such as ``"events"``) and ``quota``
+    the specific limit hit (such as ``"daily_events"`` or ``"write_rate"``).
+    ``retry_after`` is the wait in seconds before the same request would be
+    admitted, or None when waiting will not help.
+    """
+    
+    def __init__(self, message: str, tenant_id: str = None, resource: str = None, quota: str = None,
+                 limit: int = None, current_usage: int = None, attempted: int = None,
+                 retry_after: float = None):
+        super().__init__(message)
+        self.tenant_id = tenant_id
+        self.resource = resource
+        self.quota = quota
+        self.limit = limit
+        self.current_usage = current_usage
+        self.attempted = attempted
+        self.retry_after = retry_after
+
+
+class NotDurableError(EventualiError):
+    """
+    Raised by a durable save whose events were committed but could not be
+    synced to disk. Do not retry the save; the events are already stored.
+    """
+    pass
+
+
 class InvalidEventError(EventualiError):
     """Raised when an event is invalid or malformed."""
     pass
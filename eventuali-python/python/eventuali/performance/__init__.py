@@ -32,6 +32,8 @@ if _perf is not None:
     WalConfig = _perf.WalConfig
     WalStats = _perf.WalStats
     benchmark_wal_configurations = _perf.benchmark_wal_configurations
+    benchmark_dry_run = _perf.benchmark_dry_run
+    benchmark_event_roundtrip = _perf.benchmark_event_roundtrip
     
     # Read replicas
     ReadPreference = _perf.ReadPreference
@@ -75,6 +77,7 @@ else:
             self.total_connections = 0
             self.active_connections = 0
             self.idle_connections = 0
+            self.waiting_requests = 0
             
     class ConnectionPool:
         def __init__(self):
@@ -153,6 +156,13 @@ else:
         # Return mock benchmark results
         return [("Default", 1000.0, {"total_checkpoints": 10, "avg_checkpoint_time_ms": 5.0, "cache_hit_rate": 0.85})]
     
+    async def benchmark_dry_run(*args, **kwargs):
+        # Dry-run timings are only meaningful from the compiled save path
+        raise RuntimeError("Rust bindings not available. Please build with 'uv run maturin develop --release'")
+    
+    async def benchmark_event_roundtrip(*args, **kwargs):
+        raise RuntimeError("Rust bindings not available. Please build with 'uv run maturin develop --release'")
+    
     # Read replica fallbacks
     class ReadPreference:
         PRIMARY = "PRIMARY"
@@ -201,6 +211,7 @@ else:
         def __init__(self, **kwargs):
             self.level = kwargs.get('level', 3)
             self.enable_parallel = kwargs.get('enable_parallel', True)
+            self.max_decompressed_size = kwargs.get('max_decompressed_size') or 64 * 1024 * 1024
         
         @staticmethod
         def default():
@@ -225,6 +236,8 @@ __all__ = [
     "WalConfig",
     "WalStats",
     "benchmark_wal_configurations",
+    "benchmark_dry_run",
+    "benchmark_event_roundtrip",
     # Read replicas
     "ReadPreference",
     "ReplicaConfig",
@@ -7,17 +7,19 @@ reconstruction performance for aggregates with many events.
 
 from typing import Optional, List, Dict, Any
 import json
-from dataclasses import dataclass
+from dataclasses import dataclass, field
 
 try:
     from . import _PySnapshotService as PySnapshotService
     from . import _PySnapshotConfig as PySnapshotConfig 
     from . import _PyAggregateSnapshot as PyAggregateSnapshot
+    from . import _PyProjectionSnapshotStore as PyProjectionSnapshotStore
 except ImportError:
     # Fallback for development/testing when Rust bindings aren't available
     PySnapshotService = None
     PySnapshotConfig = None 
     PyAggregateSnapshot = None
+    PyProjectionSnapshotStore = None
 
 
 @dataclass
@@ -26,8 +28,29 @@ class SnapshotConfig:
     
     snapshot_frequency: int = 100  # Take snapshot every N events
     max_snapshot_age_hours: int = 168  # 7 days
-    compression: str = "gzip"  # none, gzip, lz4
+    compression: str = "gzip"  # none, gzip, lz4, zstd
     auto_cleanup: bool = True
+    # Per-aggregate-type overrides of snapshot_frequency
+    aggregate_type_frequencies: Dict[str, int] = field(default_factory=dict)
+    # Zstandard level from 1 to 22; None uses zstd's default of 3
+    compression_level: Optional[int] = None
+    # Check snapshots against their checksums in load_latest_snapshot
+    verify_checksum_on_load: bool = False
+    # Largest decompressed state in bytes; None uses the 256 MiB default
+    max_decompressed_size: Optional[int] = None
+    
+    def __post_init__(self):
+        if self.snapshot_frequency < 1:
+            raise ValueError(f"snapshot_frequency must be at least 1, got {self.snapshot_frequency}")
+        for aggregate_type, frequency in self.aggregate_type_frequencies.items():
+            if frequency < 1:
+                raise ValueError(
+                    f"Snapshot frequency for {aggregate_type} must be at least 1, got {frequency}"
+                )
+    
+    def frequency_for(self, aggregate_type: str) -> int:
+        """How often aggregates of the given type are snapshotted."""
+        return self.aggregate_type_frequencies.get(aggregate_type, self.snapshot_frequency)
     
     def to_rust(self) -> "PySnapshotConfig":
         """Convert to Rust snapshot config."""
@@ -37,7 +60,11 @@ class SnapshotConfig:
             self.snapshot_frequency,
             self.max_snapshot_age_hours, 
             self.compression,
-            self.auto_cleanup
+            self.auto_cleanup,
+            dict(self.aggregate_type_frequencies),
+            self.compression_level,
+            self.verify_checksum_on_load,
+            self.max_decompressed_size,
         )
 
 
@@ -118,16 +145,19 @@ class AggregateSnapshot:
 class SnapshotService:
     """Service for managing aggregate snapshots with high performance."""
     
-    def __init__(self, config: Optional[SnapshotConfig] = None):
+    def __init__(self, config: Optional[SnapshotConfig] = None, state_codec: str = "json"):
         """Initialize snapshot service.
         
         Args:
             config: Snapshot configuration. Uses defaults if None.
+            state_codec: Encoding for state passed to create_snapshot_from_state
+                ('json' or 'msgpack')
         """
         if PySnapshotService is None:
             raise RuntimeError("Rust bindings not available. Please build with 'uv run maturin develop --release'")
         
         self.config = config or SnapshotConfig()
+        self.state_codec = state_codec
         self._rust_service = PySnapshotService()
         self._initialized = False
     
@@ -138,7 +168,7 @@ class SnapshotService:
             database_url: SQLite database URL (e.g., 'sqlite:///snapshots.db' or 'sqlite://:memory:')
         """
         rust_config = self.config.to_rust()
-        self._rust_service.initialize(database_url, rust_config)
+        self._rust_service.initialize(database_url, rust_config, self.state_codec)
         self._initialized = True
     
     def create_snapshot(
@@ -173,6 +203,50 @@ class SnapshotService:
         
         return AggregateSnapshot(rust_snapshot)
     
+    def create_snapshot_from_state(
+        self,
+        aggregate_id: str,
+        aggregate_type: str,
+        aggregate_version: int,
+        state: Dict[str, Any],
+        event_count: int
+    ) -> AggregateSnapshot:
+        """Create a snapshot from a state dict, encoded with the service's state codec.
+        
+        Args:
+            aggregate_id: ID of the aggregate
+            aggregate_type: Type of the aggregate
+            aggregate_version: Current version of the aggregate
+            state: JSON-serializable aggregate state
+            event_count: Number of events used to build this state
+            
+        Returns:
+            Created aggregate snapshot
+        """
+        self._ensure_initialized()
+        
+        rust_snapshot = self._rust_service.create_snapshot_from_state(
+            aggregate_id,
+            aggregate_type,
+            aggregate_version,
+            state,
+            event_count
+        )
+        
+        return AggregateSnapshot(rust_snapshot)
+    
+    def decode_snapshot_state(self, snapshot: AggregateSnapshot) -> Dict[str, Any]:
+        """Decode the state of a snapshot created with create_snapshot_from_state.
+        
+        Args:
+            snapshot: Aggregate snapshot
+            
+        Returns:
+            The state dict the snapshot was created from
+        """
+        self._ensure_initialized()
+        return self._rust_service.decode_snapshot_state(snapshot._rust_snapshot)
+    
     def load_latest_snapshot(self, aggregate_id: str) -> Optional[AggregateSnapshot]:
         """Load the most recent snapshot for an aggregate.
         
@@ -190,6 +264,26 @@ class SnapshotService:
         
         return AggregateSnapshot(rust_snapshot)
     
+    def load_latest_snapshot_verified(self, aggregate_id: str) -> Optional[AggregateSnapshot]:
+        """Load the most recent snapshot for an aggregate, checking its checksum.
+        
+        Args:
+            aggregate_id: ID of the aggregate
+            
+        Returns:
+            Latest snapshot or None if no snapshots exist
+            
+        Raises:
+            ValueError: If the snapshot's stored data does not match its checksum
+        """
+        self._ensure_initialized()
+        
+        rust_snapshot = self._rust_service.load_latest_snapshot_verified(aggregate_id)
+        if rust_snapshot is None:
+            return None
+        
+        return AggregateSnapshot(rust_snapshot)
+    
     def decompress_snapshot_data(self, snapshot: AggregateSnapshot) -> bytes:
         """Decompress snapshot state data.
         
@@ -202,18 +296,34 @@ class SnapshotService:
         self._ensure_initialized()
         return bytes(self._rust_service.decompress_snapshot_data(snapshot._rust_snapshot))
     
-    def should_take_snapshot(self, aggregate_id: str, current_version: int) -> bool:
+    def should_take_snapshot(
+        self, aggregate_id: str, current_version: int, aggregate_type: Optional[str] = None
+    ) -> bool:
         """Check if a snapshot should be taken for the current aggregate state.
         
         Args:
             aggregate_id: ID of the aggregate
             current_version: Current version of the aggregate
+            aggregate_type: Type of the aggregate, to apply its entry in
+                ``aggregate_type_frequencies``; the global frequency otherwise
             
         Returns:
             True if a snapshot should be taken
         """
         self._ensure_initialized()
-        return self._rust_service.should_take_snapshot(aggregate_id, current_version)
+        return self._rust_service.should_take_snapshot(aggregate_id, current_version, aggregate_type)
+    
+    def delete_aggregate_snapshots(self, aggregate_id: str) -> int:
+        """Delete every snapshot of an aggregate.
+        
+        Erasing an aggregate's events does not touch its snapshots, which hold
+        the same data, so erasure requests have to call this as well.
+        
+        Returns:
+            Number of snapshots deleted
+        """
+        self._ensure_initialized()
+        return self._rust_service.delete_aggregate_snapshots(aggregate_id)
     
     def cleanup_old_snapshots(self) -> int:
         """Clean up old snapshots based on configuration.
@@ -234,6 +344,109 @@ class SnapshotService:
         return f"SnapshotService({status}, config={self.config})"
 
 
+class ProjectionSnapshot:
+    """Represents a projection's read-model state at a global position."""
+    
+    def __init__(self, rust_snapshot):
+        self._rust_snapshot = rust_snapshot
+    
+    @property
+    def snapshot_id(self) -> str:
+        """Unique identifier for this snapshot."""
+        return self._rust_snapshot.snapshot_id
+    
+    @property
+    def projection_name(self) -> str:
+        """Name of the projection this snapshot belongs to."""
+        return self._rust_snapshot.projection_name
+    
+    @property
+    def position(self) -> int:
+        """Global position of the last event folded into the state."""
+        return self._rust_snapshot.position
+    
+    @property
+    def state_data(self) -> bytes:
+        """Serialized projection state."""
+        return bytes(self._rust_snapshot.state_data)
+    
+    @property
+    def created_at(self) -> str:
+        """When this snapshot was created (ISO format)."""
+        return self._rust_snapshot.created_at
+    
+    def __repr__(self) -> str:
+        return (f"ProjectionSnapshot(id={self.snapshot_id[:8]}..., "
+                f"projection={self.projection_name}, position={self.position})")
+
+
+class ProjectionSnapshotStore:
+    """SQLite-backed store for projection snapshots, used to speed up rebuilds."""
+    
+    def __init__(self):
+        if PyProjectionSnapshotStore is None:
+            raise RuntimeError("Rust bindings not available. Please build with 'uv run maturin develop --release'")
+        
+        self._rust_store = PyProjectionSnapshotStore()
+        self._initialized = False
+    
+    def initialize(self, database_url: str) -> None:
+        """Initialize the store with a database connection.
+        
+        Args:
+            database_url: SQLite database URL (e.g., 'sqlite:///snapshots.db' or 'sqlite://:memory:')
+        """
+        self._rust_store.initialize(database_url)
+        self._initialized = True
+    
+    def save_snapshot(self, projection_name: str, position: int, state_data: bytes) -> ProjectionSnapshot:
+        """Save projection state taken at a global position.
+        
+        Args:
+            projection_name: Name of the projection
+            position: Global position of the last event folded into the state
+            state_data: Serialized projection state
+            
+        Returns:
+            Created projection snapshot
+        """
+        self._ensure_initialized()
+        return ProjectionSnapshot(self._rust_store.save_snapshot(projection_name, position, state_data))
+    
+    def load_latest_snapshot(self, projection_name: str) -> Optional[ProjectionSnapshot]:
+        """Load the snapshot with the highest position for a projection.
+        
+        Args:
+            projection_name: Name of the projection
+            
+        Returns:
+            Latest snapshot or None if no snapshots exist
+        """
+        self._ensure_initialized()
+        rust_snapshot = self._rust_store.load_latest_snapshot(projection_name)
+        if rust_snapshot is None:
+            return None
+        return ProjectionSnapshot(rust_snapshot)
+    
+    def delete_snapshots(self, projection_name: str) -> int:
+        """Delete every snapshot of a projection.
+        
+        Returns:
+            Number of snapshots deleted
+        """
+        self._ensure_initialized()
+        return self._rust_store.delete_snapshots(projection_name)
+    
+    def _ensure_initialized(self) -> None:
+        """Ensure the store is initialized."""
+        if not self._initialized:
+            raise RuntimeError("ProjectionSnapshotStore must be initialized with a database URL first")
+    
+    def __repr__(self) -> str:
+        status = "initialized" if self._initialized else "not initialized"
+        return f"ProjectionSnapshotStore({status})"
+
+
 # Helper functions for common snapshot patterns
 
 def create_json_snapshot(
@@ -291,6 +504,8 @@ __all__ = [
     "SnapshotService",
     "SnapshotConfig", 
     "AggregateSnapshot",
+    "ProjectionSnapshot",
+    "ProjectionSnapshotStore",
     "create_json_snapshot",
     "load_json_snapshot_state",
 ]
\ No newline at end of file
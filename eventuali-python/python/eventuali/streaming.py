@@ -7,12 +7,20 @@ other event-driven components.
 """
 
 import asyncio
-from typing import Optional, Dict, Any, Callable, AsyncIterator
+import inspect
+from collections import OrderedDict
+from typing import Optional, Dict, List, Any, Callable, AsyncIterator, Iterable, Union, TYPE_CHECKING
 from datetime import datetime
 
-from ._eventuali import PyEventStreamer, PyEventStreamReceiver, PySubscriptionBuilder, PyProjection
+from ._eventuali import (
+    PyEventStreamer, PySharedStreamer, PyEventStreamReceiver, PySubscriptionBuilder, PyProjection, PyDerivingProjection
+)
 from .event import Event
 
+if TYPE_CHECKING:
+    from .event_store import EventStore
+    from .snapshot import ProjectionSnapshot, ProjectionSnapshotStore
+
 
 class EventStreamer:
     """
@@ -44,6 +52,31 @@ class EventStreamer:
         receiver = await self._streamer.subscribe(subscription.to_dict())
         return EventStreamReceiver(receiver)
     
+    async def subscribe_from(
+        self,
+        event_store: 'EventStore',
+        subscription: 'Subscription',
+        from_position: int = 0,
+        batch_size: int = 1000,
+    ) -> 'CatchUpReceiver':
+        """
+        Replay stored events after a global position, then follow live events.
+        
+        Args:
+            event_store: Store to replay history from
+            subscription: Subscription configuration defining which events to receive
+            from_position: Global position to replay after (default: from the start)
+            batch_size: Number of stored events read per query
+            
+        Returns:
+            CatchUpReceiver that yields a single CaughtUp marker when replay
+            reaches the live tail
+        """
+        receiver = await self._streamer.subscribe_from(
+            event_store._inner, subscription.to_dict(), from_position, batch_size
+        )
+        return CatchUpReceiver(receiver)
+    
     async def unsubscribe(self, subscription_id: str) -> None:
         """
         Unsubscribe from an event stream.
@@ -86,6 +119,73 @@ class EventStreamer:
         await self._streamer.publish_event(event._inner, stream_position, global_position)
 
 
+class SharedStreamer:
+    """
+    Hub merging the events of several stores into one stream.
+    
+    Pass the hub to ``EventStore.create(..., shared_streamer=hub)`` for each
+    store, then subscribe to the hub to receive every attached store's events.
+    Events are renumbered with hub-wide global positions in arrival order, and
+    each aggregate's events arrive in version order.
+    """
+    
+    def __init__(self, capacity: int = 1000):
+        """
+        Initialize the hub.
+        
+        Args:
+            capacity: Maximum number of events to buffer in memory (default: 1000)
+        """
+        self._streamer = PySharedStreamer(capacity)
+    
+    async def subscribe(self, subscription: 'Subscription') -> 'EventStreamReceiver':
+        """
+        Subscribe to the merged stream.
+        
+        Args:
+            subscription: Subscription configuration defining which events to receive
+            
+        Returns:
+            EventStreamReceiver for consuming events
+        """
+        receiver = await self._streamer.subscribe(subscription.to_dict())
+        return EventStreamReceiver(receiver)
+    
+    async def unsubscribe(self, subscription_id: str) -> None:
+        """
+        Unsubscribe from the merged stream.
+        
+        Args:
+            subscription_id: ID of the subscription to remove
+        """
+        await self._streamer.unsubscribe(subscription_id)
+    
+    async def get_stream_position(self, store: str, stream_id: str) -> Optional[int]:
+        """
+        Get the last version an attached store published for an aggregate.
+        
+        Aggregate IDs may repeat across stores, so positions are kept per store.
+        
+        Args:
+            store: ``shared_streamer_store_id`` the store was created with,
+                by default its connection string
+            stream_id: ID of the aggregate
+            
+        Returns:
+            Last published version, or None if the store published nothing for it
+        """
+        return self._streamer.get_stream_position(store, stream_id)
+    
+    async def get_global_position(self) -> int:
+        """
+        Get the hub position of the last event published.
+        
+        Returns:
+            Number of events published into the hub
+        """
+        return await self._streamer.get_global_position()
+
+
 class EventStreamReceiver:
     """
     Receiver for consuming events from a subscription.
@@ -123,6 +223,54 @@ class EventStreamReceiver:
             return
 
 
+class CatchUpReceiver:
+    """
+    Receiver for a subscription that replays stored events before going live.
+    """
+    
+    def __init__(self, receiver):
+        self._receiver = receiver
+    
+    async def recv(self) -> Union['StreamEvent', 'CaughtUp']:
+        """
+        Receive the next event, or the CaughtUp marker sent once when replay ends.
+        
+        Raises:
+            RuntimeError: If the channel is closed or no more events are available
+        """
+        message = await self._receiver.recv()
+        if message['caught_up']:
+            return CaughtUp(global_position=message['global_position'])
+        return StreamEvent(
+            event=message['event'],
+            stream_position=message['stream_position'],
+            global_position=message['global_position']
+        )
+    
+    async def __aiter__(self) -> AsyncIterator[Union['StreamEvent', 'CaughtUp']]:
+        """
+        Iterate over events and the caught-up marker.
+        """
+        try:
+            while True:
+                yield await self.recv()
+        except RuntimeError:
+            return
+
+
+class CaughtUp:
+    """
+    Marker sent once when a catch-up subscription has replayed everything
+    stored and switches to live events.
+    """
+    
+    def __init__(self, global_position: int):
+        self.global_position = global_position
+    
+    def __repr__(self) -> str:
+        return f"CaughtUp(global_pos={self.global_position})"
+
+
 class StreamEvent:
     """
     Event wrapper containing position information for streaming.
@@ -147,7 +295,8 @@ class Subscription:
         id: Optional[str] = None,
         aggregate_type_filter: Optional[str] = None,
         event_type_filter: Optional[str] = None,
-        from_timestamp: Optional[datetime] = None
+        from_timestamp: Optional[datetime] = None,
+        replay_rate_limit: Optional[int] = None
     ):
         """
         Create a new subscription.
@@ -157,11 +306,14 @@ class Subscription:
             aggregate_type_filter: Only receive events from aggregates of this type
             event_type_filter: Only receive events of this type
             from_timestamp: Only receive events from this timestamp onwards
+            replay_rate_limit: Maximum events per second while catching up from
+                the store; live events are never throttled
         """
         self.id = id
         self.aggregate_type_filter = aggregate_type_filter
         self.event_type_filter = event_type_filter
         self.from_timestamp = from_timestamp
+        self.replay_rate_limit = replay_rate_limit
     
     def to_dict(self) -> Dict[str, Any]:
         """Convert subscription to dictionary for Rust interop."""
@@ -174,6 +326,8 @@ class Subscription:
             result['event_type_filter'] = self.event_type_filter
         if self.from_timestamp:
             result['from_timestamp'] = self.from_timestamp.isoformat()
+        if self.replay_rate_limit:
+            result['replay_rate_limit'] = self.replay_rate_limit
         return result
 
 
@@ -205,6 +359,11 @@ class SubscriptionBuilder:
         # For now, just store this - full implementation would handle timestamps
         return self
     
+    def with_replay_rate_limit(self, events_per_second: int) -> 'SubscriptionBuilder':
+        """Cap catch-up replay at this many events per second; 0 disables the cap."""
+        self._builder.with_replay_rate_limit(events_per_second)
+        return self
+    
     def build(self) -> Subscription:
         """Build the subscription."""
         sub_dict = self._builder.build()
@@ -212,7 +371,8 @@ class SubscriptionBuilder:
             id=sub_dict.get('id'),
             aggregate_type_filter=sub_dict.get('aggregate_type_filter'),
             event_type_filter=sub_dict.get('event_type_filter'),
-            from_timestamp=None  # Simplified for now
+            from_timestamp=None,  # Simplified for now
+            replay_rate_limit=sub_dict.get('replay_rate_limit')
         )
 
 
@@ -223,8 +383,40 @@ class Projection:
     Projections allow you to build optimized read models by processing
     events as they arrive, maintaining eventual consistency with the
     event store.
+    
+    Set ``dedup_window_size`` to a positive number to have ``process_event``
+    skip events whose ID was among that many recently seen IDs, which guards
+    against at-least-once delivery from a streamer.
     """
     
+    dedup_window_size: int = 0
+    
+    async def process_event(self, event: Event) -> bool:
+        """
+        Handle an event unless it is a duplicate within the de-duplication window.
+        
+        Args:
+            event: The event to process
+            
+        Returns:
+            True if the event was handled, False if it was skipped as a duplicate
+        """
+        dedup = self.dedup_window_size > 0 and event.event_id is not None
+        if dedup:
+            seen = self.__dict__.setdefault("_seen_event_ids", OrderedDict())
+            if event.event_id in seen:
+                seen.move_to_end(event.event_id)
+                return False
+        
+        await self.handle_event(event)
+        
+        # Only a handled event counts as seen, so a failed one is retried on redelivery
+        if dedup:
+            seen[event.event_id] = None
+            while len(seen) > self.dedup_window_size:
+                seen.popitem(last=False)
+        return True
+    
     async def handle_event(self, event: Event) -> None:
         """
         Handle an event and update the projection.
@@ -257,6 +449,229 @@ class Projection:
             position: Position to set
         """
         raise NotImplementedError("Subclasses must implement set_last_processed_position")
+    
+    async def snapshot_state(self) -> Optional[bytes]:
+        """
+        Serialize the read-model state for a projection snapshot.
+        
+        Returns:
+            Serialized state, or None (the default) if the projection does not
+            support snapshots
+        """
+        return None
+    
+    async def restore_state(self, state: bytes) -> None:
+        """
+        Restore read-model state previously produced by snapshot_state.
+        
+        Args:
+            state: Serialized state from a snapshot
+        """
+        raise NotImplementedError("Subclasses that support snapshots must implement restore_state")
+    
+    async def take_snapshot(self, name: str, store: 'ProjectionSnapshotStore') -> Optional['ProjectionSnapshot']:
+        """
+        Persist the current state at the last processed position.
+        
+        Args:
+            name: Projection name the snapshot is stored under
+            store: Projection snapshot store
+            
+        Returns:
+            The saved snapshot, or None if there is no state to snapshot
+        """
+        position = await self.get_last_processed_position()
+        if position is None:
+            return None
+        state = await self.snapshot_state()
+        if state is None:
+            return None
+        return store.save_snapshot(name, position, state)
+    
+    async def rebuild(
+        self,
+        name: str,
+        events: Iterable['StreamEvent'],
+        store: Optional['ProjectionSnapshotStore'] = None,
+    ) -> int:
+        """
+        Rebuild the projection from events in global order.
+        
+        If a snapshot store is given and holds a snapshot for this projection,
+        the state is restored from it and only events past the snapshot
+        position are replayed.
+        
+        Args:
+            name: Projection name snapshots are stored under
+            events: Stream events ordered by global position
+            store: Optional projection snapshot store
+            
+        Returns:
+            Number of events applied
+        """
+        await self.reset()
+        self.__dict__.pop("_seen_event_ids", None)
+        
+        start_position = 0
+        if store is not None:
+            snapshot = store.load_latest_snapshot(name)
+            if snapshot is not None:
+                await self.restore_state(snapshot.state_data)
+                await self.set_last_processed_position(snapshot.position)
+                start_position = snapshot.position
+        
+        processed = 0
+        for stream_event in events:
+            if stream_event.global_position <= start_position:
+                continue
+            await self.handle_event(stream_event.event)
+            await self.set_last_processed_position(stream_event.global_position)
+            processed += 1
+        
+        return processed
+
+
+class TypedProjection(Projection):
+    """
+    Projection that dispatches each event to the handler registered for its
+    event type, so handlers never check the type themselves.
+    
+    Register handler methods with the ``handles`` decorator::
+    
+        class OrderSummary(TypedProjection):
+            @TypedProjection.handles("OrderPlaced")
+            async def on_placed(self, event):
+                ...
+    
+    Events of types without a handler are skipped. Handlers may be plain or
+    async methods. Subclasses that keep state should extend ``reset``.
+    """
+    
+    _event_type_handlers: Dict[str, str] = {}
+    
+    @staticmethod
+    def handles(*event_types: str) -> Callable[[Callable], Callable]:
+        """
+        Register the decorated method as the handler for the given event types.
+        
+        Args:
+            event_types: Event types the method handles
+        """
+        if not event_types:
+            raise ValueError("handles() needs at least one event type")
+        
+        def register(method: Callable) -> Callable:
+            method.__dict__.setdefault("_handled_event_types", []).extend(event_types)
+            return method
+        return register
+    
+    def __init_subclass__(cls, **kwargs):
+        super().__init_subclass__(**kwargs)
+        handlers = dict(cls._event_type_handlers)
+        for name, member in vars(cls).items():
+            for event_type in getattr(member, "_handled_event_types", ()):
+                handlers[event_type] = name
+        cls._event_type_handlers = handlers
+    
+    @classmethod
+    def event_types(cls) -> List[str]:
+        """Event types with a handler, sorted."""
+        return sorted(cls._event_type_handlers)
+    
+    @classmethod
+    def handles_event_type(cls, event_type: str) -> bool:
+        """Whether events of the given type have a handler."""
+        return event_type in cls._event_type_handlers
+    
+    async def handle_event(self, event: Event) -> None:
+        name = self._event_type_handlers.get(event.event_type or event.get_event_type())
+        if name is None:
+            return
+        result = getattr(self, name)(event)
+        if inspect.isawaitable(result):
+            await result
+    
+    async def reset(self) -> None:
+        self._last_processed_position = None
+    
+    async def get_last_processed_position(self) -> Optional[int]:
+        return getattr(self, "_last_processed_position", None)
+    
+    async def set_last_processed_position(self, position: int) -> None:
+        self._last_processed_position = position
+
+
+DERIVED_BY_HEADER = "derived_by"
+"""Metadata header naming the projection that derived an event."""
+
+DERIVATION_RULE_HEADER = "derivation_rule"
+"""Metadata header naming the rule that derived an event."""
+
+
+class DerivingProjection:
+    """
+    Projection that emits new events when its computed state changes.
+    
+    Events are folded into per-aggregate state; after each event every rule
+    sees the state before and after it and may return an event to save.
+    Comparing the two (a balance going from non-negative to negative) fires a
+    rule once per crossing rather than on every event past the threshold.
+    
+    Derived events from aggregate ``id`` are saved to the stream
+    ``"{name}-{id}"`` with the source event as their causation and the
+    ``derived_by`` and ``derivation_rule`` headers set. Events carrying
+    ``derived_by`` are skipped, so derived events are never derived from again.
+    Replaying the projection from scratch does not save duplicates.
+    """
+    
+    def __init__(
+        self,
+        name: str,
+        event_store: 'EventStore',
+        fold: Callable[[Any, Dict[str, Any]], Any],
+        rules: Dict[str, Callable[[Any, Any, Dict[str, Any]], Optional[tuple]]],
+        initial_state: Any = None,
+    ):
+        """
+        Args:
+            name: Projection name, also the aggregate type of derived events
+            event_store: Store to read source events from and save derived events to
+            fold: ``fold(state, event)`` returning the aggregate's new state;
+                state must be JSON-serializable
+            rules: Rule name to ``rule(before, after, event)``, returning None
+                or an ``(event_type, data)`` tuple to derive
+            initial_state: State passed to ``fold`` for an aggregate's first event
+        
+        Events are passed to ``fold`` and the rules as dicts.
+        """
+        self._inner = PyDerivingProjection(
+            name, event_store._inner, fold, list(rules.items()), initial_state
+        )
+    
+    async def catch_up(self, batch_size: int = 1000) -> int:
+        """
+        Process every stored event past the last processed position.
+        
+        Returns:
+            Number of events handled, derived events included
+        """
+        return await self._inner.catch_up(batch_size)
+    
+    async def state_of(self, aggregate_id: str) -> Any:
+        """Computed state of an aggregate, or None if none of its events were handled."""
+        return await self._inner.state_of(aggregate_id)
+    
+    async def get_last_processed_position(self) -> Optional[int]:
+        return await self._inner.get_last_processed_position()
+    
+    @property
+    def emitted_count(self) -> int:
+        """Number of derived events saved."""
+        return self._inner.emitted_count
+    
+    def derived_stream_id(self, aggregate_id: str) -> str:
+        """Stream that events derived from an aggregate are saved to."""
+        return self._inner.derived_stream_id(aggregate_id)
 
 
 class SagaHandler:
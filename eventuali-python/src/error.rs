@@ -1,9 +1,43 @@
 use pyo3::prelude::*;
 use pyo3::exceptions;
 use eventuali_core::EventualiError as CoreError;
+use eventuali_core::tenancy::{QuotaExceeded, ResourceType};
+use pyo3::types::PyDict;
+use crate::performance::PyPoolStats;
 
-/// Convert a Rust error to a Python exception
+/// Convert a Rust error to a Python exception.
+///
+/// The exception's `retryable` attribute mirrors `EventualiError::is_retryable`,
+/// and the errors the Rust error wraps (such as the underlying I/O or driver
+/// error) become its `__cause__` chain, root cause last.
 pub fn map_rust_error_to_python(error: CoreError) -> PyErr {
+    let retryable = error.is_retryable();
+    let mut causes = Vec::new();
+    let mut source = std::error::Error::source(&error);
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+
+    let exception = core_error_to_python(error);
+    Python::with_gil(|py| {
+        // Setting an attribute on a fresh built-in exception instance cannot fail
+        let _ = exception.value(py).setattr("retryable", retryable);
+
+        // Build from the root cause outwards so each exception wraps the next one down
+        let cause = causes.into_iter().rev().fold(None, |inner: Option<PyErr>, message| {
+            let cause = PyErr::new::<exceptions::PyRuntimeError, _>(message);
+            cause.set_cause(py, inner);
+            Some(cause)
+        });
+        if cause.is_some() {
+            exception.set_cause(py, cause);
+        }
+        exception
+    })
+}
+
+fn core_error_to_python(error: CoreError) -> PyErr {
     match error {
         CoreError::Database(e) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Database error: {e}"))
@@ -37,6 +71,7 @@ pub fn map_rust_error_to_python(error: CoreError) -> PyErr {
         CoreError::Tenant(msg) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Tenant error: {msg}"))
         }
+        CoreError::QuotaExceeded(exceeded) => quota_exceeded_to_python(&exceeded),
         CoreError::ObservabilityError(msg) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Observability error: {msg}"))
         }
@@ -46,6 +81,11 @@ pub fn map_rust_error_to_python(error: CoreError) -> PyErr {
         CoreError::Authentication(msg) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Authentication error: {msg}"))
         }
+        CoreError::AuthenticationFailed { key_id, keys_tried } => {
+            PyErr::new::<exceptions::PyRuntimeError, _>(format!(
+                "Decryption failed for data under key {key_id}: no held key authenticated it ({keys_tried} tried)"
+            ))
+        }
         CoreError::Authorization(msg) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Authorization error: {msg}"))
         }
@@ -61,6 +101,128 @@ pub fn map_rust_error_to_python(error: CoreError) -> PyErr {
         CoreError::DatabaseError(msg) => {
             PyErr::new::<exceptions::PyRuntimeError, _>(format!("Database error: {msg}"))
         }
+        CoreError::PoolTimeout { timeout_ms, stats_snapshot } => {
+            // args[1] carries the pool stats at the moment of the timeout
+            let message = format!("Connection pool timed out after {timeout_ms}ms ({stats_snapshot})");
+            PyErr::new::<exceptions::PyTimeoutError, _>((message, PyPoolStats { inner: *stats_snapshot }))
+        }
+        CoreError::ApplyError { event_id, aggregate_version, .. } => {
+            apply_error_to_python(&event_id.to_string(), aggregate_version)
+        }
+        CoreError::HistoryCompacted { aggregate_id, compacted_through } => {
+            let exception = PyErr::new::<exceptions::PyLookupError, _>(format!(
+                "History of aggregate {aggregate_id} through version {compacted_through} was compacted into a snapshot"
+            ));
+            Python::with_gil(|py| {
+                let value = exception.value(py);
+                let _ = value.setattr("aggregate_id", aggregate_id);
+                let _ = value.setattr("compacted_through", compacted_through);
+            });
+            exception
+        }
+        CoreError::UnsupportedStorageFormat { event_id, version, supported } => {
+            PyErr::new::<exceptions::PyValueError, _>(format!(
+                "Event {event_id} is stored in format version {version}, newer than the supported version {supported}"
+            ))
+        }
+        CoreError::SnapshotConflict(msg) => {
+            PyErr::new::<exceptions::PyRuntimeError, _>(format!("Snapshot conflict: {msg}"))
+        }
+        CoreError::NotDurable { source } => {
+            let message = format!("Events were saved but could not be synced to disk: {source}");
+            Python::with_gil(|py| {
+                py.import("eventuali.exceptions")
+                    .and_then(|module| module.getattr("NotDurableError"))
+                    .and_then(|class| class.call1((message.clone(),)))
+                    .map(PyErr::from_value)
+                    .unwrap_or_else(|_| PyErr::new::<exceptions::PyRuntimeError, _>(message))
+            })
+        }
+        CoreError::PositionsPending { source } => {
+            PyErr::new::<exceptions::PyRuntimeError, _>(format!(
+                "Events were saved but their global positions are not yet assigned: {source}"
+            ))
+        }
+    }
+}
+
+/// Raise `eventuali.exceptions.QuotaExceededError` carrying the exceeded quota's
+/// details, falling back to a `RuntimeError` with the same attributes if the
+/// Python package cannot be imported
+fn quota_exceeded_to_python(exceeded: &QuotaExceeded) -> PyErr {
+    let message = exceeded.to_string();
+    let resource = resource_name(exceeded.resource_type);
+    let retry_after = exceeded.retry_after.map(|d| d.as_secs_f64());
+    Python::with_gil(|py| {
+        let kwargs = PyDict::new(py);
+        let attributes = [
+            ("tenant_id", exceeded.tenant_id.as_str().into_py(py)),
+            ("resource", resource.into_py(py)),
+            ("quota", exceeded.quota.clone().into_py(py)),
+            ("limit", exceeded.limit.into_py(py)),
+            ("current_usage", exceeded.current_usage.into_py(py)),
+            ("attempted", exceeded.attempted.into_py(py)),
+            ("retry_after", retry_after.into_py(py)),
+        ];
+        for (name, value) in &attributes {
+            // Setting an item on a fresh dict cannot fail
+            let _ = kwargs.set_item(*name, value);
+        }
+
+        let raised = py
+            .import("eventuali.exceptions")
+            .and_then(|module| module.getattr("QuotaExceededError"))
+            .and_then(|class| class.call((message.clone(),), Some(kwargs)));
+        match raised {
+            Ok(instance) => PyErr::from_value(instance),
+            Err(_) => {
+                let exception = PyErr::new::<exceptions::PyRuntimeError, _>(message);
+                let value = exception.value(py);
+                for (name, value_of) in attributes {
+                    let _ = value.setattr(name, value_of);
+                }
+                exception
+            }
+        }
+    })
+}
+
+/// An `eventuali.exceptions.ApplyError`; the error the event failed with
+/// becomes its `__cause__` in `map_rust_error_to_python`
+fn apply_error_to_python(event_id: &str, aggregate_version: i64) -> PyErr {
+    let message = format!("Failed to apply event {event_id} at aggregate version {aggregate_version}");
+    Python::with_gil(|py| {
+        let kwargs = PyDict::new(py);
+        // Setting an item on a fresh dict cannot fail
+        let _ = kwargs.set_item("event_id", event_id);
+        let _ = kwargs.set_item("aggregate_version", aggregate_version);
+
+        let raised = py
+            .import("eventuali.exceptions")
+            .and_then(|module| module.getattr("ApplyError"))
+            .and_then(|class| class.call((message.clone(),), Some(kwargs)));
+        match raised {
+            Ok(instance) => PyErr::from_value(instance),
+            Err(_) => {
+                let exception = PyErr::new::<exceptions::PyRuntimeError, _>(message);
+                let value = exception.value(py);
+                let _ = value.setattr("event_id", event_id);
+                let _ = value.setattr("aggregate_version", aggregate_version);
+                exception
+            }
+        }
+    })
+}
+
+/// Name Python APIs use for a resource type, as accepted by `check_tenant_quota`
+fn resource_name(resource_type: ResourceType) -> &'static str {
+    match resource_type {
+        ResourceType::Events => "events",
+        ResourceType::Storage => "storage",
+        ResourceType::Streams => "streams",
+        ResourceType::Projections => "projections",
+        ResourceType::Aggregates => "aggregates",
+        ResourceType::ApiCalls => "api_calls",
     }
 }
 
@@ -1,6 +1,8 @@
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use eventuali_core::{Event as CoreEvent, EventData, EventMetadata};
+use eventuali_core::new_event_id as core_new_event_id;
+use eventuali_core::{EventId, EventualiError, IdAllocator};
 use uuid::Uuid;
 use std::collections::HashMap;
 
@@ -48,7 +50,7 @@ impl PyEvent {
             .ok()
             .and_then(|v| v)
             .and_then(|v| v.extract().ok())
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
+            .unwrap_or_else(|| core_new_event_id().to_string());
         
         let aggregate_id: String = data_dict.get_item("aggregate_id")?
             .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>("Missing aggregate_id"))?
@@ -96,44 +98,9 @@ impl PyEvent {
             };
             
             // Parse metadata
-            let metadata = if let Ok(Some(meta_dict)) = data_dict.get_item("metadata") {
-                let meta_dict = meta_dict.downcast::<PyDict>()?;
-                let causation_id = meta_dict.get_item("causation_id")
-                    .ok()
-                    .and_then(|v| v)
-                    .and_then(|v| v.extract::<String>().ok())
-                    .and_then(|s| Uuid::parse_str(&s).ok());
-                let correlation_id = meta_dict.get_item("correlation_id")
-                    .ok()
-                    .and_then(|v| v)
-                    .and_then(|v| v.extract::<String>().ok())
-                    .and_then(|s| Uuid::parse_str(&s).ok());
-                let user_id = meta_dict.get_item("user_id")
-                    .ok()
-                    .and_then(|v| v)
-                    .and_then(|v| v.extract::<String>().ok());
-                
-                let headers = if let Ok(Some(headers_dict)) = meta_dict.get_item("headers") {
-                    let headers_dict = headers_dict.downcast::<PyDict>()?;
-                    let mut headers = HashMap::new();
-                    for (k, v) in headers_dict.iter() {
-                        let key: String = k.extract()?;
-                        let value: String = v.extract()?;
-                        headers.insert(key, value);
-                    }
-                    headers
-                } else {
-                    HashMap::new()
-                };
-                
-                EventMetadata {
-                    causation_id,
-                    correlation_id,
-                    user_id,
-                    headers,
-                }
-            } else {
-                EventMetadata::default()
+            let metadata = match data_dict.get_item("metadata") {
+                Ok(Some(meta_dict)) => metadata_from_dict(meta_dict.downcast::<PyDict>()?)?,
+                _ => EventMetadata::default(),
             };
             
             let id = Uuid::parse_str(&id_str)
@@ -201,6 +168,38 @@ impl PyEvent {
         }
     }
 
+    /// JSON Patch (RFC 6902) operations that turn this event's data into `other`'s
+    pub fn diff(&self, py: Python, other: &PyEvent) -> PyResult<PyObject> {
+        let patch = self.inner.data.diff(&other.inner.data)
+            .map_err(crate::error::map_rust_error_to_python)?;
+        let json_str = serde_json::to_string(&patch)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let json_module = py.import("json")?;
+        Ok(json_module.call_method1("loads", (json_str,))?.into())
+    }
+
+    /// This event as a CloudEvents 1.0 dict, with `source` set to
+    /// `"{source_prefix}/{aggregate_type}"`
+    #[pyo3(signature = (source_prefix = ""))]
+    pub fn to_cloudevent(&self, py: Python, source_prefix: &str) -> PyResult<PyObject> {
+        let json_str = serde_json::to_string(&self.inner.to_cloudevent(source_prefix))
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let json_module = py.import("json")?;
+        Ok(json_module.call_method1("loads", (json_str,))?.into())
+    }
+
+    /// Read a CloudEvents 1.0 dict, such as one from `to_cloudevent`
+    #[staticmethod]
+    pub fn from_cloudevent(py: Python, cloudevent: &PyDict) -> PyResult<Self> {
+        let json_module = py.import("json")?;
+        let json_str: String = json_module.call_method1("dumps", (cloudevent,))?.extract()?;
+        let value: serde_json::Value = serde_json::from_str(&json_str)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let event = CoreEvent::from_cloudevent(&value)
+            .map_err(crate::error::map_rust_error_to_python)?;
+        Ok(PyEvent { inner: event })
+    }
+
     pub fn to_dict(&self, py: Python) -> PyResult<PyObject> {
         let dict = PyDict::new(py);
         
@@ -253,4 +252,165 @@ impl PyEvent {
         
         Ok(dict.into())
     }
-}
\ No newline at end of file
+}
+
+/// Generate an event ID of the process default kind (UUID v4); a store's
+/// `event_id_type` or `id_allocator` does not apply.
+#[pyfunction]
+pub fn new_event_id() -> String {
+    core_new_event_id().to_string()
+}
+
+/// ID allocator backed by a Python callable returning a UUID or its string form
+pub(crate) struct PyIdAllocator {
+    pub(crate) callback: PyObject,
+}
+
+impl IdAllocator for PyIdAllocator {
+    fn allocate(&self) -> eventuali_core::Result<EventId> {
+        let text = Python::with_gil(|py| -> PyResult<String> {
+            self.callback.call0(py)?.as_ref(py).str()?.extract()
+        })
+        .map_err(|e| EventualiError::Configuration(format!("Event ID allocator failed: {e}")))?;
+        Uuid::parse_str(&text).map_err(|_| {
+            EventualiError::Validation(format!("Event ID allocator returned '{text}', which is not a UUID"))
+        })
+    }
+}
+
+/// Parse the `metadata` dict shape produced by `PyEvent.to_dict`
+fn metadata_from_dict(meta_dict: &PyDict) -> PyResult<EventMetadata> {
+    let causation_id = meta_dict.get_item("causation_id")
+        .ok()
+        .and_then(|v| v)
+        .and_then(|v| v.extract::<String>().ok())
+        .and_then(|s| Uuid::parse_str(&s).ok());
+    let correlation_id = meta_dict.get_item("correlation_id")
+        .ok()
+        .and_then(|v| v)
+        .and_then(|v| v.extract::<String>().ok())
+        .and_then(|s| Uuid::parse_str(&s).ok());
+    let user_id = meta_dict.get_item("user_id")
+        .ok()
+        .and_then(|v| v)
+        .and_then(|v| v.extract::<String>().ok());
+    
+    let headers = if let Ok(Some(headers_dict)) = meta_dict.get_item("headers") {
+        let headers_dict = headers_dict.downcast::<PyDict>()?;
+        let mut headers = HashMap::new();
+        for (k, v) in headers_dict.iter() {
+            let key: String = k.extract()?;
+            let value: String = v.extract()?;
+            headers.insert(key, value);
+        }
+        headers
+    } else {
+        HashMap::new()
+    };
+    
+    Ok(EventMetadata {
+        causation_id,
+        correlation_id,
+        user_id,
+        headers,
+    })
+}
+
+/// Fluent builder for `PyEvent`.
+///
+/// Only the aggregate ID, aggregate type and event type are required; `build()`
+/// fills in a new event ID, the current time, version 1 and an empty payload.
+#[pyclass]
+#[derive(Clone, Default)]
+pub struct PyEventBuilder {
+    aggregate_id: Option<String>,
+    aggregate_type: Option<String>,
+    event_type: Option<String>,
+    event_version: Option<i32>,
+    aggregate_version: Option<i64>,
+    data: Option<serde_json::Value>,
+    metadata: Option<EventMetadata>,
+}
+
+#[pymethods]
+impl PyEventBuilder {
+    #[new]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn aggregate_id(mut slf: PyRefMut<Self>, aggregate_id: String) -> PyRefMut<Self> {
+        slf.aggregate_id = Some(aggregate_id);
+        slf
+    }
+
+    pub fn aggregate_type(mut slf: PyRefMut<Self>, aggregate_type: String) -> PyRefMut<Self> {
+        slf.aggregate_type = Some(aggregate_type);
+        slf
+    }
+
+    pub fn event_type(mut slf: PyRefMut<Self>, event_type: String) -> PyRefMut<Self> {
+        slf.event_type = Some(event_type);
+        slf
+    }
+
+    /// Aggregate version this event brings the aggregate to
+    pub fn version(mut slf: PyRefMut<Self>, aggregate_version: i64) -> PyRefMut<Self> {
+        slf.aggregate_version = Some(aggregate_version);
+        slf
+    }
+
+    /// Schema version of the event payload
+    pub fn event_version(mut slf: PyRefMut<Self>, event_version: i32) -> PyRefMut<Self> {
+        slf.event_version = Some(event_version);
+        slf
+    }
+
+    /// Event payload, given as any JSON-serializable dict
+    pub fn data_dict<'p>(mut slf: PyRefMut<'p, Self>, py: Python<'_>, data: &PyDict) -> PyResult<PyRefMut<'p, Self>> {
+        let json_str: String = py.import("json")?.call_method1("dumps", (data,))?.extract()?;
+        let value = serde_json::from_str(&json_str)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        slf.data = Some(value);
+        Ok(slf)
+    }
+
+    /// Metadata in the shape `PyEvent.to_dict()` produces: `causation_id`,
+    /// `correlation_id`, `user_id` and `headers`
+    pub fn metadata<'p>(mut slf: PyRefMut<'p, Self>, metadata: &PyDict) -> PyResult<PyRefMut<'p, Self>> {
+        slf.metadata = Some(metadata_from_dict(metadata)?);
+        Ok(slf)
+    }
+
+    pub fn build(&self) -> PyResult<PyEvent> {
+        let required = |value: &Option<String>, field: &str| {
+            value
+                .clone()
+                .filter(|v| !v.is_empty())
+                .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Event {field} is required")))
+        };
+        let aggregate_id = required(&self.aggregate_id, "aggregate_id")?;
+        let aggregate_type = required(&self.aggregate_type, "aggregate_type")?;
+        let event_type = required(&self.event_type, "event_type")?;
+
+        let aggregate_version = self.aggregate_version.unwrap_or(1);
+        if aggregate_version < 1 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Aggregate version must be at least 1, got {aggregate_version}"
+            )));
+        }
+
+        let data = self.data.clone().unwrap_or_else(|| serde_json::Value::Object(Default::default()));
+        let event = CoreEvent::new(
+            aggregate_id,
+            aggregate_type,
+            event_type,
+            self.event_version.unwrap_or(1),
+            aggregate_version,
+            EventData::Json(data),
+        )
+        .with_metadata(self.metadata.clone().unwrap_or_default());
+
+        Ok(PyEvent { inner: event })
+    }
+}
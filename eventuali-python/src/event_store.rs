@@ -1,19 +1,94 @@
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyList};
+use pyo3::types::{PyBytes, PyDict, PyList};
 use eventuali_core::{
-    EventStoreConfig, create_event_store, EventStore, Event, EventData, EventMetadata
+    EventStoreConfig, create_event_store_with_codecs, EventStore, Event, EventData, EventMetadata,
+    FailedWriteFilter, OversizedBatch, StreamAnomaly,
+    Codec, CodecRegistry, DeleteMode, EventualiError, EventIdKind, TimestampSource, new_event_id,
+    StoreMigration, ThroughputGovernor, with_operation_timeout,
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use crate::event::PyEvent;
+use crate::event::{PyEvent, PyIdAllocator};
 use crate::error::map_rust_error_to_python;
+use crate::streaming::PySharedStreamer;
+
+/// The aggregate every event in a retryable batch belongs to
+fn single_aggregate_id(events: &[Event]) -> PyResult<String> {
+    let Some(first) = events.first() else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "save_events_with_retry needs at least one event",
+        ));
+    };
+    if events.iter().any(|event| event.aggregate_id != first.aggregate_id) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "save_events_with_retry saves events for a single aggregate",
+        ));
+    }
+    Ok(first.aggregate_id.clone())
+}
+
+fn parse_delete_mode(value: &str) -> PyResult<DeleteMode> {
+    match value.to_lowercase().as_str() {
+        "hard" => Ok(DeleteMode::Hard),
+        "tombstone" => Ok(DeleteMode::SoftTombstone),
+        other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Unknown delete mode '{other}', expected 'hard' or 'tombstone'"
+        ))),
+    }
+}
+
+/// Await `operation` under a per-call timeout of `timeout_ms`, when given,
+/// instead of the store's default
+async fn within<F: std::future::Future>(timeout_ms: Option<u64>, operation: F) -> F::Output {
+    match timeout_ms {
+        Some(ms) => with_operation_timeout(std::time::Duration::from_millis(ms), operation).await,
+        None => operation.await,
+    }
+}
+
+/// Codec backed by Python callables exchanging JSON text for bytes.
+struct PythonCodec {
+    name: String,
+    encode: PyObject,
+    decode: PyObject,
+}
+
+impl Codec for PythonCodec {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn encode(&self, data: &EventData) -> eventuali_core::Result<Vec<u8>> {
+        let EventData::Json(value) = data else {
+            return Err(EventualiError::InvalidEventData(format!(
+                "Codec {} only encodes JSON event data", self.name
+            )));
+        };
+        let json_text = serde_json::to_string(value)?;
+
+        Python::with_gil(|py| -> PyResult<Vec<u8>> {
+            let encoded = self.encode.call1(py, (json_text,))?;
+            Ok(encoded.as_ref(py).downcast::<PyBytes>()?.as_bytes().to_vec())
+        })
+        .map_err(|e| EventualiError::InvalidEventData(format!("Codec {} failed to encode: {e}", self.name)))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> eventuali_core::Result<EventData> {
+        let json_text = Python::with_gil(|py| -> PyResult<String> {
+            self.decode.call1(py, (PyBytes::new(py, bytes),))?.extract(py)
+        })
+        .map_err(|e| EventualiError::InvalidEventData(format!("Codec {} failed to decode: {e}", self.name)))?;
+
+        Ok(EventData::Json(serde_json::from_str(&json_text)?))
+    }
+}
 
 #[pyclass]
 pub struct PyEventStore {
-    store: Arc<Mutex<Option<Box<dyn EventStore + Send + Sync>>>>,
+    pub(crate) store: Arc<Mutex<Option<Arc<dyn EventStore + Send + Sync>>>>,
 }
 
 impl Default for PyEventStore {
@@ -31,12 +106,67 @@ impl PyEventStore {
         }
     }
 
-    #[pyo3(signature = (connection_string))]
-    pub fn create<'p>(&self, py: Python<'p>, connection_string: String) -> PyResult<&'p PyAny> {
+    #[pyo3(signature = (connection_string, max_aggregate_version = None, codec = None, codecs = None, timestamp_source = None, event_id_type = None, archive_path = None, failed_write_log_path = None, operation_timeout_ms = None, recent_events_capacity = None, existence_cache_capacity = None, shared_streamer = None, max_batch_events = None, oversized_batch = None, id_allocator = None, shared_streamer_store_id = None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn create<'p>(
+        &self,
+        py: Python<'p>,
+        connection_string: String,
+        max_aggregate_version: Option<i64>,
+        codec: Option<String>,
+        codecs: Option<Vec<(String, PyObject, PyObject)>>,
+        timestamp_source: Option<String>,
+        event_id_type: Option<String>,
+        archive_path: Option<String>,
+        failed_write_log_path: Option<String>,
+        operation_timeout_ms: Option<u64>,
+        recent_events_capacity: Option<usize>,
+        existence_cache_capacity: Option<usize>,
+        shared_streamer: Option<Py<PySharedStreamer>>,
+        max_batch_events: Option<usize>,
+        oversized_batch: Option<String>,
+        id_allocator: Option<PyObject>,
+        shared_streamer_store_id: Option<String>,
+    ) -> PyResult<&'p PyAny> {
         let store = self.store.clone();
+        let shared_streamer = shared_streamer.map(|hub| hub.borrow(py).streamer.clone());
+        let shared_streamer_store_id = shared_streamer_store_id.unwrap_or_else(|| connection_string.clone());
+
+        let timestamp_source = match timestamp_source.as_deref() {
+            None | Some("client") => TimestampSource::ClientProvided,
+            Some("server") => TimestampSource::ServerAssigned,
+            Some(other) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown timestamp source '{other}', expected 'client' or 'server'"
+                )))
+            }
+        };
+
+        let oversized_batch = match oversized_batch.as_deref() {
+            None | Some("reject") => OversizedBatch::Reject,
+            Some("split") => OversizedBatch::Split,
+            Some(other) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Unknown oversized batch handling '{other}', expected 'reject' or 'split'"
+                )))
+            }
+        };
+
+        let event_id_kind = event_id_type
+            .map(|name| name.parse::<EventIdKind>())
+            .transpose()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+        let mut registry = CodecRegistry::new();
+        for (name, encode, decode) in codecs.unwrap_or_default() {
+            registry
+                .register(PythonCodec { name, encode, decode })
+                .map_err(map_rust_error_to_python)?;
+        }
+        let registry = Arc::new(registry);
         
         pyo3_asyncio::tokio::future_into_py(py, async move {
-            let config = if connection_string.starts_with("postgresql://") || connection_string.starts_with("postgres://") {
+            let mut config = if connection_string.starts_with("postgresql://") || connection_string.starts_with("postgres://") {
                 EventStoreConfig::postgres(connection_string)
             } else if connection_string.starts_with("sqlite://") {
                 // Parse SQLite URLs properly
@@ -66,26 +196,61 @@ impl PyEventStore {
                 ));
             };
 
-            let event_store = create_event_store(config)
+            if let Some(max_version) = max_aggregate_version {
+                config = config.with_max_aggregate_version(max_version);
+            }
+            if let Some(codec) = codec {
+                config = config.with_codec(codec);
+            }
+            config = config.with_timestamp_source(timestamp_source);
+            if let Some(kind) = event_id_kind {
+                config = config.with_event_id_kind(kind);
+            }
+            if let Some(archive_path) = archive_path {
+                config = config.with_archive_path(archive_path);
+            }
+            if let Some(path) = failed_write_log_path {
+                config = config.with_failed_write_log(path);
+            }
+            if let Some(timeout_ms) = operation_timeout_ms {
+                config = config.with_operation_timeout_ms(timeout_ms);
+            }
+            if let Some(capacity) = recent_events_capacity {
+                config = config.with_recent_events_capacity(capacity);
+            }
+            if let Some(capacity) = existence_cache_capacity {
+                config = config.with_existence_cache_capacity(capacity);
+            }
+            if let Some(max_events) = max_batch_events {
+                config = config.with_max_batch_events(max_events, oversized_batch);
+            }
+
+            let mut event_store = create_event_store_with_codecs(config, registry)
                 .await
                 .map_err(map_rust_error_to_python)?;
+            if let Some(hub) = shared_streamer {
+                hub.attach(shared_streamer_store_id, &mut *event_store);
+            }
+            if let Some(callback) = id_allocator {
+                event_store.set_id_allocator(Arc::new(PyIdAllocator { callback }));
+            }
 
             let mut store_guard = store.lock().await;
-            *store_guard = Some(event_store);
+            *store_guard = Some(Arc::from(event_store));
 
             Ok(())
         })
     }
 
-    #[pyo3(signature = (events))]
-    pub fn save_events<'p>(&self, py: Python<'p>, events: &PyList) -> PyResult<&'p PyAny> {
+    #[pyo3(signature = (events, timeout_ms = None))]
+    pub fn save_events<'p>(&self, py: Python<'p>, events: &PyList, timeout_ms: Option<u64>) -> PyResult<&'p PyAny> {
         let store = self.store.clone();
-        let events_data = self.convert_py_events_to_rust(py, events)?;
+        let events_data = Self::convert_py_events_to_rust(py, events)?;
         
         pyo3_asyncio::tokio::future_into_py(py, async move {
             let store_guard = store.lock().await;
             if let Some(ref event_store) = *store_guard {
-                event_store.save_events(events_data)
+                within(timeout_ms, event_store.save_events(events_data))
                     .await
                     .map_err(map_rust_error_to_python)?;
                 Ok(())
@@ -97,19 +262,154 @@ impl PyEventStore {
         })
     }
 
-    #[pyo3(signature = (aggregate_id, from_version = None))]
+    /// Save one aggregate's events, rebuilding them after each concurrency
+    /// conflict. On conflict the aggregate's events are reloaded and passed to
+    /// `load_and_retry_callback`, which returns the events to try next (and may
+    /// be async). The store is not locked while the callback runs, so it may
+    /// use this store itself. Resolves to the number of attempts made; once
+    /// `max_attempts` is reached the last conflict is raised.
+    #[pyo3(signature = (events, load_and_retry_callback, max_attempts = 3, timeout_ms = None))]
+    pub fn save_events_with_retry<'p>(
+        &self,
+        py: Python<'p>,
+        events: &PyList,
+        load_and_retry_callback: PyObject,
+        max_attempts: u32,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<&'p PyAny> {
+        if max_attempts == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "max_attempts must be at least 1",
+            ));
+        }
+        let store = self.store.clone();
+        let mut events_data = Self::convert_py_events_to_rust(py, events)?;
+        let aggregate_id = single_aggregate_id(&events_data)?;
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            // Hold the lock only long enough to take the store, never across the callback
+            let current_store = || async {
+                store.lock().await.clone().ok_or_else(|| {
+                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("EventStore not initialized")
+                })
+            };
+
+            let mut attempt = 1;
+            loop {
+                let event_store = current_store().await?;
+                match within(timeout_ms, event_store.save_events(events_data)).await {
+                    Ok(_) => return Ok(attempt),
+                    Err(EventualiError::OptimisticConcurrency { .. }) if attempt < max_attempts => {}
+                    Err(e) => return Err(map_rust_error_to_python(e)),
+                }
+
+                let current = within(timeout_ms, event_store.load_events(&aggregate_id, None))
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                drop(event_store);
+                let rebuilt = Python::with_gil(|py| -> PyResult<_> {
+                    let py_events = PyList::empty(py);
+                    for event in current {
+                        py_events.append(Py::new(py, PyEvent { inner: event })?)?;
+                    }
+                    let result = load_and_retry_callback.call1(py, (py_events,))?;
+                    if result.as_ref(py).hasattr("__await__")? {
+                        Ok(Err(pyo3_asyncio::tokio::into_future(result.as_ref(py))?))
+                    } else {
+                        Ok(Ok(result))
+                    }
+                })?;
+                let rebuilt = match rebuilt {
+                    Ok(events) => events,
+                    Err(pending) => pending.await?,
+                };
+
+                events_data = Python::with_gil(|py| {
+                    Self::convert_py_events_to_rust(py, rebuilt.as_ref(py).downcast::<PyList>()?)
+                })?;
+                if single_aggregate_id(&events_data)? != aggregate_id {
+                    return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                        "Retried events must stay on aggregate {aggregate_id}"
+                    )));
+                }
+                attempt += 1;
+            }
+        })
+    }
+
+    /// Save events opening a new aggregate, resolving to False without writing
+    /// if the aggregate already exists
+    #[pyo3(signature = (events, timeout_ms = None))]
+    pub fn append_if_not_exists<'p>(&self, py: Python<'p>, events: &PyList, timeout_ms: Option<u64>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        let events_data = Self::convert_py_events_to_rust(py, events)?;
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                within(timeout_ms, event_store.append_if_not_exists(events_data))
+                    .await
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    pub fn aggregate_exists<'p>(&self, py: Python<'p>, aggregate_id: String) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                event_store.aggregate_exists(&aggregate_id)
+                    .await
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Save events and resolve only once they are synced to disk
+    #[pyo3(signature = (events, timeout_ms = None))]
+    pub fn save_events_durable<'p>(&self, py: Python<'p>, events: &PyList, timeout_ms: Option<u64>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        let events_data = Self::convert_py_events_to_rust(py, events)?;
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                within(timeout_ms, event_store.save_events_durable(events_data))
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                Ok(())
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    #[pyo3(signature = (aggregate_id, from_version = None, timeout_ms = None))]
     pub fn load_events<'p>(
         &self, 
         py: Python<'p>, 
         aggregate_id: String,
-        from_version: Option<i64>
+        from_version: Option<i64>,
+        timeout_ms: Option<u64>,
     ) -> PyResult<&'p PyAny> {
         let store = self.store.clone();
         
         pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
             let store_guard = store.lock().await;
             if let Some(ref event_store) = *store_guard {
-                let events = event_store.load_events(&aggregate_id, from_version)
+                let events = within(timeout_ms, event_store.load_events(&aggregate_id, from_version))
                     .await
                     .map_err(map_rust_error_to_python)?;
                 
@@ -129,6 +429,146 @@ impl PyEventStore {
         })
     }
 
+    /// Load an aggregate's events up to and including `version`
+    #[pyo3(signature = (aggregate_id, version, timeout_ms = None))]
+    pub fn load_events_at_version<'p>(
+        &self,
+        py: Python<'p>,
+        aggregate_id: String,
+        version: i64,
+        timeout_ms: Option<u64>,
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let events = within(timeout_ms, event_store.load_events_at_version(&aggregate_id, version))
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_events = PyList::empty(py);
+                    for event in events {
+                        py_events.append(Py::new(py, PyEvent { inner: event })?)?;
+                    }
+                    Ok(py_events.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Load an aggregate's events, skipping rows that cannot be decoded.
+    /// Returns a dict with `events`, `skipped` and `quarantined`, one dict per
+    /// skipped row holding its raw column bytes and the decoding error.
+    #[pyo3(signature = (aggregate_id, from_version = None))]
+    pub fn load_events_lenient<'p>(
+        &self,
+        py: Python<'p>,
+        aggregate_id: String,
+        from_version: Option<i64>
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let load = event_store.load_events_lenient(&aggregate_id, from_version)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let skipped = load.skipped();
+                    let py_events = PyList::empty(py);
+                    for event in load.events {
+                        py_events.append(Py::new(py, PyEvent { inner: event })?)?;
+                    }
+                    let py_quarantined = PyList::empty(py);
+                    for row in load.quarantined {
+                        let entry = PyDict::new(py);
+                        entry.set_item("aggregate_id", row.aggregate_id)?;
+                        entry.set_item("event_id", row.event_id)?;
+                        entry.set_item("aggregate_version", row.aggregate_version)?;
+                        entry.set_item("event_type", row.event_type)?;
+                        entry.set_item("raw_event_data", PyBytes::new(py, &row.raw_event_data))?;
+                        entry.set_item("raw_metadata", PyBytes::new(py, &row.raw_metadata))?;
+                        entry.set_item("error", row.error)?;
+                        entry.set_item("quarantined_at", row.quarantined_at.to_rfc3339())?;
+                        py_quarantined.append(entry)?;
+                    }
+                    let result = PyDict::new(py);
+                    result.set_item("events", py_events)?;
+                    result.set_item("skipped", skipped)?;
+                    result.set_item("quarantined", py_quarantined)?;
+                    Ok(result.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Check the aggregate's stream for version gaps, duplicate versions and
+    /// timestamp regressions
+    pub fn validate_stream<'p>(&self, py: Python<'p>, aggregate_id: String) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let validation = event_store.validate_stream(&aggregate_id)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_anomalies = PyList::empty(py);
+                    for anomaly in validation.anomalies.iter() {
+                        let entry = PyDict::new(py);
+                        match anomaly {
+                            StreamAnomaly::VersionGap { after, next } => {
+                                entry.set_item("kind", "version_gap")?;
+                                entry.set_item("after", after)?;
+                                entry.set_item("next", next)?;
+                            }
+                            StreamAnomaly::DuplicateVersion { version, event_ids } => {
+                                entry.set_item("kind", "duplicate_version")?;
+                                entry.set_item("version", version)?;
+                                let ids: Vec<String> = event_ids.iter().map(|id| id.to_string()).collect();
+                                entry.set_item("event_ids", ids)?;
+                            }
+                            StreamAnomaly::TimestampRegression { version, event_id, timestamp, previous_timestamp } => {
+                                entry.set_item("kind", "timestamp_regression")?;
+                                entry.set_item("version", version)?;
+                                entry.set_item("event_id", event_id.to_string())?;
+                                entry.set_item("timestamp", timestamp.to_rfc3339())?;
+                                entry.set_item("previous_timestamp", previous_timestamp.to_rfc3339())?;
+                            }
+                        }
+                        py_anomalies.append(entry)?;
+                    }
+                    let result = PyDict::new(py);
+                    result.set_item("aggregate_id", &validation.aggregate_id)?;
+                    result.set_item("event_count", validation.event_count)?;
+                    result.set_item("first_version", validation.first_version)?;
+                    result.set_item("last_version", validation.last_version)?;
+                    result.set_item("valid", validation.is_valid())?;
+                    result.set_item("anomalies", py_anomalies)?;
+                    Ok(result.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
     #[pyo3(signature = (aggregate_type, from_version = None))]
     pub fn load_events_by_type<'p>(
         &self,
@@ -161,6 +601,409 @@ impl PyEventStore {
         })
     }
 
+    /// Load up to `limit` events carrying a correlation ID, in global order
+    #[pyo3(signature = (correlation_id, limit=100))]
+    pub fn get_events_by_correlation_id<'p>(
+        &self,
+        py: Python<'p>,
+        correlation_id: String,
+        limit: usize
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let events = event_store.get_events_by_correlation_id(&correlation_id, limit)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_events = PyList::empty(py);
+                    for event in events {
+                        let py_event = PyEvent { inner: event };
+                        py_events.append(Py::new(py, py_event)?)?;
+                    }
+                    Ok(py_events.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Load up to `limit` events caused by a user, in global order
+    #[pyo3(signature = (user_id, limit=100))]
+    pub fn get_events_by_user_id<'p>(
+        &self,
+        py: Python<'p>,
+        user_id: String,
+        limit: usize
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let events = event_store.get_events_by_user_id(&user_id, limit)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_events = PyList::empty(py);
+                    for event in events {
+                        let py_event = PyEvent { inner: event };
+                        py_events.append(Py::new(py, py_event)?)?;
+                    }
+                    Ok(py_events.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Write every event, or only those of `aggregate_type`, to a Parquet file
+    /// at `path`. Resolves to a dict with `rows_written` and `last_global_position`.
+    #[cfg(feature = "parquet")]
+    #[pyo3(signature = (path, aggregate_type=None))]
+    pub fn export_parquet<'p>(&self, py: Python<'p>, path: String, aggregate_type: Option<String>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let file = std::fs::File::create(&path)
+                    .map_err(|e| map_rust_error_to_python(EventualiError::Io(e)))?;
+                let report = event_store.export_parquet(aggregate_type.as_deref(), Box::new(file))
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+
+                Python::with_gil(|py| {
+                    let result = PyDict::new(py);
+                    result.set_item("rows_written", report.rows_written)?;
+                    result.set_item("last_global_position", report.last_global_position)?;
+                    Ok(result.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// The latest `n` events committed through this store, oldest first, from
+    /// its in-memory recent-events buffer
+    #[pyo3(signature = (n))]
+    pub fn recent_events<'p>(&self, py: Python<'p>, n: usize) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let events = event_store.recent_events(n)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_events = PyList::empty(py);
+                    for event in events {
+                        let py_event = PyEvent { inner: event };
+                        py_events.append(Py::new(py, py_event)?)?;
+                    }
+                    Ok(py_events.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Load one page of events committed after `after_global`, as (global_position, event) pairs
+    #[pyo3(signature = (after_global, limit))]
+    pub fn load_events_after_position<'p>(
+        &self,
+        py: Python<'p>,
+        after_global: u64,
+        limit: usize
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let events = event_store.load_events_after_position(after_global, limit)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let py_events = PyList::empty(py);
+                    for (position, event) in events {
+                        let py_event = Py::new(py, PyEvent { inner: event })?;
+                        py_events.append((position, py_event))?;
+                    }
+                    Ok(py_events.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// List the distinct aggregate types present in the store, sorted
+    pub fn list_aggregate_types<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                event_store.list_aggregate_types()
+                    .await
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Store-wide counts and storage estimate as a dict
+    pub fn stats<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let stats = event_store.stats()
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let dict = PyDict::new(py);
+                    dict.set_item("total_events", stats.total_events)?;
+                    dict.set_item("total_aggregates", stats.total_aggregates)?;
+                    dict.set_item("aggregate_types", stats.aggregate_types)?;
+                    dict.set_item("event_types", stats.event_types)?;
+                    dict.set_item("estimated_bytes", stats.estimated_bytes)?;
+                    Ok(dict.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Close the store, returning its pooled connections; later calls fail as uninitialized
+    pub fn close<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let event_store = store.lock().await.take();
+            if let Some(event_store) = event_store {
+                event_store.close().await.map_err(map_rust_error_to_python)?;
+            }
+            Ok(Python::with_gil(|py| py.None()))
+        })
+    }
+
+    pub fn existence_cache_stats<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let stats = event_store.existence_cache_stats()
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+                
+                Python::with_gil(|py| {
+                    let dict = PyDict::new(py);
+                    dict.set_item("hits", stats.hits)?;
+                    dict.set_item("misses", stats.misses)?;
+                    dict.set_item("entries", stats.entries)?;
+                    dict.set_item("capacity", stats.capacity)?;
+                    Ok(dict.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Copy every event after `starting_after` into `target` in global order,
+    /// paced to `target_events_per_sec` when given
+    #[pyo3(signature = (target, batch_size=500, target_events_per_sec=None, max_in_flight_batches=2, starting_after=0))]
+    pub fn migrate_to<'p>(
+        &self,
+        py: Python<'p>,
+        target: PyRef<PyEventStore>,
+        batch_size: usize,
+        target_events_per_sec: Option<f64>,
+        max_in_flight_batches: usize,
+        starting_after: u64,
+    ) -> PyResult<&'p PyAny> {
+        let source = self.store.clone();
+        let target = target.store.clone();
+
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let source = source.lock().await.clone();
+            let target = target.lock().await.clone();
+            let (Some(source), Some(target)) = (source, target) else {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ));
+            };
+
+            let mut migration = StoreMigration::new(source, target)
+                .with_batch_size(batch_size)
+                .with_max_in_flight_batches(max_in_flight_batches)
+                .starting_after(starting_after);
+            if let Some(rate) = target_events_per_sec {
+                migration = migration.with_governor(ThroughputGovernor::new(rate));
+            }
+            let report = migration.run().await.map_err(map_rust_error_to_python)?;
+
+            Python::with_gil(|py| {
+                let dict = PyDict::new(py);
+                dict.set_item("events_migrated", report.events_migrated)?;
+                dict.set_item("batches_written", report.batches_written)?;
+                dict.set_item("last_source_position", report.last_source_position)?;
+                dict.set_item("elapsed_seconds", report.elapsed.as_secs_f64())?;
+                dict.set_item("target_events_per_sec", report.target_events_per_sec)?;
+                dict.set_item("achieved_events_per_sec", report.achieved_events_per_sec)?;
+                Ok(dict.to_object(py))
+            })
+        })
+    }
+
+    /// Rejected saves from the failed-write log, newest first; `since` is an
+    /// RFC 3339 timestamp
+    #[pyo3(signature = (aggregate_id=None, error_kind=None, since=None, limit=100))]
+    pub fn query_failed_writes<'p>(
+        &self,
+        py: Python<'p>,
+        aggregate_id: Option<String>,
+        error_kind: Option<String>,
+        since: Option<String>,
+        limit: usize,
+    ) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        let since = since
+            .map(|since| DateTime::parse_from_rfc3339(&since).map(|t| t.with_timezone(&Utc)))
+            .transpose()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid since timestamp: {e}")))?;
+        let filter = FailedWriteFilter { aggregate_id, error_kind, since };
+
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                let failed = event_store.query_failed_writes(&filter, limit)
+                    .await
+                    .map_err(map_rust_error_to_python)?;
+
+                Python::with_gil(|py| {
+                    let py_failed = PyList::empty(py);
+                    for write in failed {
+                        let dict = PyDict::new(py);
+                        dict.set_item("id", write.id)?;
+                        dict.set_item("aggregate_id", write.aggregate_id)?;
+                        dict.set_item("aggregate_type", write.aggregate_type)?;
+                        dict.set_item("attempted_versions", write.attempted_versions)?;
+                        dict.set_item("event_types", write.event_types)?;
+                        dict.set_item("error_kind", write.error_kind)?;
+                        dict.set_item("error_message", write.error_message)?;
+                        dict.set_item("retryable", write.retryable)?;
+                        dict.set_item("failed_at", write.failed_at.to_rfc3339())?;
+                        py_failed.append(dict)?;
+                    }
+                    Ok(py_failed.to_object(py))
+                })
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Move events older than `cutoff` (an RFC 3339 timestamp) into the archive
+    /// database, returning how many were moved
+    #[pyo3(signature = (cutoff))]
+    pub fn archive_events_before<'p>(&self, py: Python<'p>, cutoff: String) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        let cutoff = DateTime::parse_from_rfc3339(&cutoff)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid cutoff timestamp: {e}")))?
+            .with_timezone(&Utc);
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                event_store.archive_events_before(cutoff)
+                    .await
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// An ID for a new event from this store's configured kind, or the
+    /// process default kind
+    pub fn new_event_id<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                event_store.new_event_id()
+                    .map(|id| id.to_string())
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
+    /// Erase every event of the aggregate, `mode` being "hard" to delete the
+    /// rows or "tombstone" to keep them with their content replaced,
+    /// returning how many events were erased
+    #[pyo3(signature = (aggregate_id, mode = "hard"))]
+    pub fn delete_aggregate_events<'p>(&self, py: Python<'p>, aggregate_id: String, mode: &str) -> PyResult<&'p PyAny> {
+        let store = self.store.clone();
+        let mode = parse_delete_mode(mode)?;
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store_guard = store.lock().await;
+            if let Some(ref event_store) = *store_guard {
+                event_store.delete_aggregate_events_with_mode(&aggregate_id, mode)
+                    .await
+                    .map_err(map_rust_error_to_python)
+            } else {
+                Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "EventStore not initialized"
+                ))
+            }
+        })
+    }
+
     #[pyo3(signature = (aggregate_id))]
     pub fn get_aggregate_version<'p>(
         &self,
@@ -194,7 +1037,7 @@ impl PyEventStore {
 }
 
 impl PyEventStore {
-    fn convert_py_events_to_rust(&self, py: Python, events: &PyList) -> PyResult<Vec<Event>> {
+    fn convert_py_events_to_rust(py: Python, events: &PyList) -> PyResult<Vec<Event>> {
         let mut rust_events = Vec::new();
         
         for item in events.iter() {
@@ -202,8 +1045,8 @@ impl PyEventStore {
             
             // Extract fields from Python dict
             let id_str: String = py_dict.get_item("event_id")?
-                .map(|v| v.extract().unwrap_or_else(|_| Uuid::new_v4().to_string()))
-                .unwrap_or_else(|| Uuid::new_v4().to_string());
+                .map(|v| v.extract().unwrap_or_else(|_| new_event_id().to_string()))
+                .unwrap_or_else(|| new_event_id().to_string());
             let id = Uuid::parse_str(&id_str)
                 .map_err(|_| PyErr::new::<pyo3::exceptions::PyValueError, _>("Invalid UUID"))?;
             
@@ -7,6 +7,7 @@ mod aggregate;
 mod error;
 mod streaming;
 mod snapshot;
+mod read_model;
 mod security;
 mod tenancy;
 mod performance;
@@ -15,10 +16,14 @@ mod performance;
 mod observability;
 
 use event_store::PyEventStore;
-use event::PyEvent;
+use event::{PyEvent, PyEventBuilder};
 use aggregate::PyAggregate;
-use streaming::{PyEventStreamer, PyEventStreamReceiver, PySubscriptionBuilder, PyProjection};
-use snapshot::{PySnapshotService, PySnapshotConfig, PyAggregateSnapshot};
+use streaming::{PyEventStreamer, PySharedStreamer, PyEventStreamReceiver, PyCatchUpReceiver, PySubscriptionBuilder, PyProjection, PyDerivingProjection, PyLagWatchdog};
+use snapshot::{
+    PySnapshotService, PySnapshotConfig, PyAggregateSnapshot, PyProjectionSnapshot,
+    PyProjectionSnapshotStore,
+};
+use read_model::PyReadModelSink;
 use security::{
     PyEventEncryption, PyKeyManager, PyEncryptionKey, PyEncryptedEventData, PyEncryptionAlgorithm, PySecurityUtils,
     PyRbacManager, PyUser, PyRole, PyPermission, PySecurityLevel, PySession, PyAccessDecision, PyAuditEntry,
@@ -47,18 +52,27 @@ use tenancy::{
 fn _eventuali(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyEventStore>()?;
     m.add_class::<PyEvent>()?;
+    m.add_class::<PyEventBuilder>()?;
+    m.add_function(wrap_pyfunction!(event::new_event_id, m)?)?;
     m.add_class::<PyAggregate>()?;
     
     // Register streaming classes
     m.add_class::<PyEventStreamer>()?;
+    m.add_class::<PySharedStreamer>()?;
     m.add_class::<PyEventStreamReceiver>()?;
+    m.add_class::<PyCatchUpReceiver>()?;
     m.add_class::<PySubscriptionBuilder>()?;
     m.add_class::<PyProjection>()?;
+    m.add_class::<PyDerivingProjection>()?;
+    m.add_class::<PyLagWatchdog>()?;
     
     // Register snapshot classes
     m.add_class::<PySnapshotService>()?;
     m.add_class::<PySnapshotConfig>()?;
     m.add_class::<PyAggregateSnapshot>()?;
+    m.add_class::<PyProjectionSnapshot>()?;
+    m.add_class::<PyProjectionSnapshotStore>()?;
+    m.add_class::<PyReadModelSink>()?;
     
     // Register security classes
     m.add_class::<PyEventEncryption>()?;
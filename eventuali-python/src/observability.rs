@@ -10,7 +10,7 @@ use eventuali_core::{
 };
 use eventuali_core::observability::{
     HealthStatus, HealthCheckResult, HealthReport, HealthConfig,
-    HealthMonitorService, SystemMetrics, MetricLabels,
+    HealthMonitorService, ProbeResult, SystemMetrics, MetricLabels, StatsdConfig,
     ProfileType, ProfileEntry, MemoryInfo, IoInfo, ProfilingConfig,
     RegressionDetection, PerformanceSnapshot, RegressionSeverity,
     FlameGraph, FlameGraphNode, BottleneckAnalysis, Bottleneck,
@@ -38,7 +38,15 @@ impl PyObservabilityConfig {
         prometheus_endpoint = None,
         sample_rate = 1.0,
         max_events_per_span = 128,
-        export_timeout_millis = 30000
+        export_timeout_millis = 30000,
+        event_type_allowlist = None,
+        aggregate_type_allowlist = None,
+        max_type_labels = 100,
+        redacted_fields = None,
+        statsd_endpoint = None,
+        statsd_prefix = String::new(),
+        statsd_flush_interval_millis = 10000,
+        statsd_tags = None
     ))]
     pub fn new(
         service_name: String,
@@ -52,7 +60,23 @@ impl PyObservabilityConfig {
         sample_rate: f64,
         max_events_per_span: u32,
         export_timeout_millis: u64,
+        event_type_allowlist: Option<Vec<String>>,
+        aggregate_type_allowlist: Option<Vec<String>>,
+        max_type_labels: usize,
+        redacted_fields: Option<Vec<String>>,
+        statsd_endpoint: Option<String>,
+        statsd_prefix: String,
+        statsd_flush_interval_millis: u64,
+        statsd_tags: Option<HashMap<String, String>>,
     ) -> Self {
+        let statsd = statsd_endpoint.map(|endpoint| StatsdConfig {
+            endpoint,
+            prefix: statsd_prefix,
+            flush_interval: std::time::Duration::from_millis(statsd_flush_interval_millis),
+            tags: MetricLabels {
+                labels: statsd_tags.unwrap_or_default(),
+            },
+        });
         Self {
             inner: ObservabilityConfig {
                 service_name,
@@ -66,6 +90,12 @@ impl PyObservabilityConfig {
                 sample_rate,
                 max_events_per_span,
                 export_timeout_millis,
+                event_type_allowlist,
+                aggregate_type_allowlist,
+                max_type_labels,
+                redacted_fields: redacted_fields.unwrap_or_default(),
+                redacted_categories: Vec::new(),
+                statsd,
             },
         }
     }
@@ -99,6 +129,11 @@ impl PyObservabilityConfig {
     pub fn structured_logging(&self) -> bool {
         self.inner.structured_logging
     }
+
+    #[getter]
+    pub fn statsd_endpoint(&self) -> Option<String> {
+        self.inner.statsd.as_ref().map(|statsd| statsd.endpoint.clone())
+    }
 }
 
 #[pyclass(name = "CorrelationId")]
@@ -665,6 +700,50 @@ impl PyHealthReport {
     }
 }
 
+#[pyclass(name = "ProbeResult")]
+#[derive(Clone)]
+pub struct PyProbeResult {
+    inner: ProbeResult,
+}
+
+#[pymethods]
+impl PyProbeResult {
+    #[getter]
+    pub fn passed(&self) -> bool {
+        self.inner.passed
+    }
+
+    #[getter]
+    pub fn message(&self) -> String {
+        self.inner.message.clone()
+    }
+
+    #[getter]
+    pub fn components(&self) -> Vec<PyHealthCheckResult> {
+        self.inner.components.iter()
+            .map(|c| PyHealthCheckResult { inner: c.clone() })
+            .collect()
+    }
+
+    #[getter]
+    pub fn timestamp(&self) -> u64 {
+        self.inner.timestamp
+    }
+
+    pub fn http_status(&self) -> u16 {
+        self.inner.http_status()
+    }
+
+    pub fn to_json(&self) -> PyResult<String> {
+        serde_json::to_string(&self.inner)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to serialize probe result: {e}")))
+    }
+
+    pub fn __str__(&self) -> String {
+        format!("ProbeResult(passed={}, message='{}')", self.inner.passed, self.inner.message)
+    }
+}
+
 #[pyclass(name = "HealthConfig")]
 #[derive(Clone)]
 pub struct PyHealthConfig {
@@ -793,6 +872,20 @@ impl PyHealthMonitorService {
         })
     }
 
+    /// Liveness probe: whether the process is still making progress, ignoring dependencies
+    pub fn liveness(&self) -> PyProbeResult {
+        PyProbeResult {
+            inner: self.runtime.block_on(self.inner.liveness()),
+        }
+    }
+
+    /// Readiness probe: whether every critical dependency can serve traffic
+    pub fn readiness(&self) -> PyProbeResult {
+        PyProbeResult {
+            inner: self.runtime.block_on(self.inner.readiness()),
+        }
+    }
+
     pub fn get_health_summary(&self) -> HashMap<String, String> {
         let summary = self.runtime.block_on(async {
             self.inner.get_health_summary().await
@@ -883,7 +976,7 @@ impl PyHealthHttpServer {
 
     pub fn get_readiness_json(&self) -> String {
         let is_ready = self.runtime.block_on(async {
-            self.health_service.is_ready().await
+            self.health_service.readiness().await.passed
         });
         let status = if is_ready { "ready" } else { "not_ready" };
         let http_code = if is_ready { 200 } else { 503 };
@@ -893,7 +986,7 @@ impl PyHealthHttpServer {
 
     pub fn get_liveness_json(&self) -> String {
         let is_live = self.runtime.block_on(async {
-            self.health_service.is_live().await
+            self.health_service.liveness().await.passed
         });
         let status = if is_live { "live" } else { "not_live" };
         let http_code = if is_live { 200 } else { 503 };
@@ -1580,6 +1673,7 @@ pub fn register_observability_classes(_py: Python, m: &PyModule) -> PyResult<()>
     m.add_class::<PyHealthReport>()?;
     m.add_class::<PyHealthConfig>()?;
     m.add_class::<PyHealthMonitorService>()?;
+    m.add_class::<PyProbeResult>()?;
     m.add_class::<PyHealthHttpServer>()?;
     
     // Profiling classes
@@ -6,15 +6,37 @@
 use pyo3::prelude::*;
 use std::collections::HashMap;
 use eventuali_core::performance::{
-    ConnectionPool, PoolConfig, PoolStats, BatchConfig, BatchStats, BatchProcessor, EventBatchProcessor,
+    ConnectionPool, PoolConfig, PoolFairness, ForkPolicy, PoolStats, BatchConfig, BatchStats, BatchProcessor, EventBatchProcessor,
     WalConfig, WalStats, WalSynchronousMode, WalJournalMode, TempStoreMode, AutoVacuumMode,
     ReplicaConfig, ReadPreference, ReadReplicaManager,
     CacheConfig, EvictionPolicy, CacheManager,
-    CompressionConfig, CompressionAlgorithm, CompressionManager
+    CompressionConfig, CompressionAlgorithm, CompressionManager,
+    DryRunConfig, benchmark_dry_run_writes, LatencyPercentiles,
 };
 use eventuali_core::event::Event;
+use eventuali_core::{create_event_store, EventStoreConfig};
 use std::sync::Arc;
 
+fn parse_pool_fairness(value: &str) -> PyResult<PoolFairness> {
+    match value.to_lowercase().as_str() {
+        "fifo" => Ok(PoolFairness::Fifo),
+        "lifo" => Ok(PoolFairness::Lifo),
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown pool fairness '{other}', expected 'fifo' or 'lifo'"
+        ))),
+    }
+}
+
+fn parse_fork_policy(value: &str) -> PyResult<ForkPolicy> {
+    match value.to_lowercase().as_str() {
+        "reinitialize" => Ok(ForkPolicy::Reinitialize),
+        "reject" => Ok(ForkPolicy::Reject),
+        other => Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "Unknown fork policy '{other}', expected 'reinitialize' or 'reject'"
+        ))),
+    }
+}
+
 /// Python wrapper for PoolConfig
 #[pyclass(name = "PoolConfig")]
 #[derive(Clone)]
@@ -33,8 +55,11 @@ impl PyPoolConfig {
         health_check_interval_ms = 30000,
         auto_scaling_enabled = true,
         scale_up_threshold = 0.8,
-        scale_down_threshold = 0.3
+        scale_down_threshold = 0.3,
+        fairness = "fifo",
+        fork_policy = "reinitialize"
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         min_connections: usize,
         max_connections: usize,
@@ -44,8 +69,10 @@ impl PyPoolConfig {
         auto_scaling_enabled: bool,
         scale_up_threshold: f64,
         scale_down_threshold: f64,
-    ) -> Self {
-        Self {
+        fairness: &str,
+        fork_policy: &str,
+    ) -> PyResult<Self> {
+        Ok(Self {
             inner: PoolConfig {
                 min_connections,
                 max_connections,
@@ -55,8 +82,10 @@ impl PyPoolConfig {
                 auto_scaling_enabled,
                 scale_up_threshold,
                 scale_down_threshold,
+                fairness: parse_pool_fairness(fairness)?,
+                fork_policy: parse_fork_policy(fork_policy)?,
             }
-        }
+        })
     }
 
     #[staticmethod]
@@ -78,6 +107,8 @@ impl PyPoolConfig {
                 auto_scaling_enabled: true,
                 scale_up_threshold: 0.7,
                 scale_down_threshold: 0.2,
+                fairness: PoolFairness::Fifo,
+                fork_policy: ForkPolicy::Reinitialize,
             }
         }
     }
@@ -94,6 +125,8 @@ impl PyPoolConfig {
                 auto_scaling_enabled: true,
                 scale_up_threshold: 0.9,
                 scale_down_threshold: 0.1,
+                fairness: PoolFairness::Fifo,
+                fork_policy: ForkPolicy::Reinitialize,
             }
         }
     }
@@ -158,13 +191,45 @@ impl PyPoolConfig {
         self.inner.scale_down_threshold = value;
     }
 
+    /// Order in which waiting requests get connections: "fifo" or "lifo"
+    #[getter]
+    pub fn fairness(&self) -> &'static str {
+        match self.inner.fairness {
+            PoolFairness::Fifo => "fifo",
+            PoolFairness::Lifo => "lifo",
+        }
+    }
+
+    #[setter]
+    pub fn set_fairness(&mut self, value: &str) -> PyResult<()> {
+        self.inner.fairness = parse_pool_fairness(value)?;
+        Ok(())
+    }
+
+    /// What the pool does when used in a process forked after it was created:
+    /// "reinitialize" starts afresh in the child, "reject" raises
+    #[getter]
+    pub fn fork_policy(&self) -> &'static str {
+        match self.inner.fork_policy {
+            ForkPolicy::Reinitialize => "reinitialize",
+            ForkPolicy::Reject => "reject",
+        }
+    }
+
+    #[setter]
+    pub fn set_fork_policy(&mut self, value: &str) -> PyResult<()> {
+        self.inner.fork_policy = parse_fork_policy(value)?;
+        Ok(())
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
-            "PoolConfig(min_connections={}, max_connections={}, connection_timeout_ms={}, auto_scaling_enabled={})",
+            "PoolConfig(min_connections={}, max_connections={}, connection_timeout_ms={}, auto_scaling_enabled={}, fairness={})",
             self.inner.min_connections,
             self.inner.max_connections,
             self.inner.connection_timeout_ms,
-            self.inner.auto_scaling_enabled
+            self.inner.auto_scaling_enabled,
+            self.fairness()
         )
     }
 }
@@ -218,6 +283,11 @@ impl PyPoolStats {
         self.inner.max_wait_time_ms
     }
 
+    #[getter]
+    pub fn waiting_requests(&self) -> usize {
+        self.inner.waiting_requests
+    }
+
     #[getter]
     pub fn success_rate(&self) -> f64 {
         if self.inner.total_requests == 0 {
@@ -246,6 +316,7 @@ impl PyPoolStats {
         result.insert("failed_requests".to_string(), self.inner.failed_requests as f64);
         result.insert("avg_wait_time_ms".to_string(), self.inner.avg_wait_time_ms);
         result.insert("max_wait_time_ms".to_string(), self.inner.max_wait_time_ms as f64);
+        result.insert("waiting_requests".to_string(), self.inner.waiting_requests as f64);
         result.insert("success_rate".to_string(), self.success_rate());
         result.insert("utilization".to_string(), self.utilization());
         result
@@ -346,10 +417,9 @@ pub fn benchmark_connection_pool<'py>(
     let config = config.map(|c| c.inner).unwrap_or_default();
     
     pyo3_asyncio::tokio::future_into_py(py, async move {
-        match benchmark_pool_performance(database_path, config, num_operations, concurrency).await {
-            Ok(result) => Ok(result),
-            Err(e) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("{e}")))
-        }
+        benchmark_pool_performance(database_path, config, num_operations, concurrency)
+            .await
+            .map_err(crate::error::map_rust_error_to_python)
     })
 }
 
@@ -406,16 +476,13 @@ async fn benchmark_pool_performance(
             let mut successful_ops = 0;
             
             for _ in 0..operations_per_task {
-                match pool_clone.get_connection().await {
-                    Ok(guard) => {
-                        // Perform a simple operation
-                        if let Ok(conn) = guard.create_connection() {
-                            if let Ok(_) = conn.execute("SELECT 1", []) {
-                                successful_ops += 1;
-                            }
+                if let Ok(guard) = pool_clone.get_connection().await {
+                    // Perform a simple operation
+                    if let Ok(conn) = guard.create_connection() {
+                        if conn.execute("SELECT 1", []).is_ok() {
+                            successful_ops += 1;
                         }
                     }
-                    Err(_) => {}
                 }
             }
             
@@ -474,6 +541,7 @@ impl PyBatchConfig {
         parallel_processing = true
     ))]
     #[allow(unused_variables)] // Some parameters are part of Python API but not yet used in Rust implementation
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         max_batch_size: usize,
         min_batch_size: usize,
@@ -494,6 +562,7 @@ impl PyBatchConfig {
                 timeout_ms: max_wait_ms * 2,
                 worker_pool_size,
                 parallel_processing,
+                max_buffer_memory_mb,
             }
         }
     }
@@ -566,13 +635,24 @@ impl PyBatchConfig {
         self.inner.parallel_processing = value;
     }
 
+    #[getter]
+    pub fn max_buffer_memory_mb(&self) -> usize {
+        self.inner.max_buffer_memory_mb
+    }
+
+    #[setter]
+    pub fn set_max_buffer_memory_mb(&mut self, value: usize) {
+        self.inner.max_buffer_memory_mb = value;
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
-            "BatchConfig(max_batch_size={}, min_batch_size={}, worker_pool_size={}, parallel_processing={})",
+            "BatchConfig(max_batch_size={}, min_batch_size={}, worker_pool_size={}, parallel_processing={}, max_buffer_memory_mb={})",
             self.inner.max_batch_size,
             self.inner.min_batch_size,
             self.inner.worker_pool_size,
-            self.inner.parallel_processing
+            self.inner.parallel_processing,
+            self.inner.max_buffer_memory_mb
         )
     }
 }
@@ -631,6 +711,16 @@ impl PyBatchStats {
         self.inner.current_queue_depth
     }
 
+    #[getter]
+    pub fn current_buffer_bytes(&self) -> usize {
+        self.inner.current_buffer_bytes
+    }
+
+    #[getter]
+    pub fn memory_flushes(&self) -> u64 {
+        self.inner.memory_flushes
+    }
+
     #[getter]
     pub fn max_queue_depth(&self) -> usize {
         self.inner.max_queue_depth
@@ -1040,6 +1130,7 @@ impl PyWalConfig {
         page_size = 4096,
         auto_vacuum = None
     ))]
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         synchronous_mode: Option<PyWalSynchronousMode>,
         journal_mode: Option<PyWalJournalMode>,
@@ -1412,17 +1503,20 @@ pub struct PyCompressionConfig {
 #[pymethods]
 impl PyCompressionConfig {
     #[new]
-    #[pyo3(signature = (algorithm = None, level = 3, enable_parallel = true))]
+    #[pyo3(signature = (algorithm = None, level = 3, enable_parallel = true, max_decompressed_size = None))]
     pub fn new(
         algorithm: Option<PyCompressionAlgorithm>,
         level: u32,
         enable_parallel: bool,
+        max_decompressed_size: Option<usize>,
     ) -> Self {
         Self {
             inner: CompressionConfig {
                 algorithm: algorithm.map(|a| a.inner).unwrap_or(CompressionAlgorithm::LZ4),
                 level,
                 enable_parallel,
+                max_decompressed_size: max_decompressed_size
+                    .unwrap_or(eventuali_core::performance::DEFAULT_MAX_DECOMPRESSED_SIZE),
             }
         }
     }
@@ -1454,12 +1548,23 @@ impl PyCompressionConfig {
         self.inner.enable_parallel = value;
     }
 
+    #[getter]
+    pub fn max_decompressed_size(&self) -> usize {
+        self.inner.max_decompressed_size
+    }
+
+    #[setter]
+    pub fn set_max_decompressed_size(&mut self, value: usize) {
+        self.inner.max_decompressed_size = value;
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
-            "CompressionConfig(algorithm={:?}, level={}, enable_parallel={})",
+            "CompressionConfig(algorithm={:?}, level={}, enable_parallel={}, max_decompressed_size={})",
             self.inner.algorithm,
             self.inner.level,
-            self.inner.enable_parallel
+            self.inner.enable_parallel,
+            self.inner.max_decompressed_size
         )
     }
 }
@@ -1479,11 +1584,50 @@ impl PyCompressionManager {
         }
     }
 
+    /// Compress ZSTD data with a dictionary from `train_compression_dictionary`,
+    /// returning its ID
+    pub fn use_dictionary(&mut self, dictionary: &[u8]) -> PyResult<u32> {
+        self.inner
+            .use_dictionary(dictionary.to_vec())
+            .map_err(crate::error::map_rust_error_to_python)
+    }
+
+    /// Make an older dictionary available for decompression, returning its ID
+    pub fn add_dictionary(&mut self, dictionary: &[u8]) -> PyResult<u32> {
+        self.inner
+            .add_dictionary(dictionary.to_vec())
+            .map_err(crate::error::map_rust_error_to_python)
+    }
+
+    #[getter]
+    pub fn active_dictionary_id(&self) -> Option<u32> {
+        self.inner.active_dictionary_id()
+    }
+
+    pub fn compress(&self, py: Python, data: &[u8]) -> PyResult<PyObject> {
+        let compressed = self.inner.compress(data).map_err(crate::error::map_rust_error_to_python)?;
+        Ok(pyo3::types::PyBytes::new(py, &compressed).into())
+    }
+
+    pub fn decompress(&self, py: Python, data: &[u8]) -> PyResult<PyObject> {
+        let decompressed = self.inner.decompress(data).map_err(crate::error::map_rust_error_to_python)?;
+        Ok(pyo3::types::PyBytes::new(py, &decompressed).into())
+    }
+
     pub fn __repr__(&self) -> String {
         "CompressionManager".to_string()
     }
 }
 
+/// Train a ZSTD dictionary of at most `dict_size` bytes from sample payloads
+#[pyfunction]
+#[pyo3(signature = (samples, dict_size = 16384))]
+pub fn train_compression_dictionary(py: Python, samples: Vec<Vec<u8>>, dict_size: usize) -> PyResult<PyObject> {
+    let dictionary = eventuali_core::performance::train_dictionary(&samples, dict_size)
+        .map_err(crate::error::map_rust_error_to_python)?;
+    Ok(pyo3::types::PyBytes::new(py, &dictionary).into())
+}
+
 // ============================================================================
 // Benchmarking Functions
 // ============================================================================
@@ -1525,6 +1669,105 @@ pub fn benchmark_wal_configurations<'py>(
     })
 }
 
+/// Estimate write throughput by running the full save path against `event_store`,
+/// rolling every write back; without a store, an in-memory SQLite store is used
+#[pyfunction]
+#[pyo3(signature = (
+    event_store = None,
+    event_count = 10000,
+    batch_size = 100,
+    payload_size_bytes = 256,
+    compression = None,
+    encryption_enabled = false
+))]
+pub fn benchmark_dry_run<'py>(
+    py: Python<'py>,
+    event_store: Option<&crate::event_store::PyEventStore>,
+    event_count: usize,
+    batch_size: usize,
+    payload_size_bytes: usize,
+    compression: Option<PyCompressionAlgorithm>,
+    encryption_enabled: bool,
+) -> PyResult<&'py PyAny> {
+    let config = DryRunConfig {
+        event_count,
+        batch_size,
+        payload_size_bytes,
+        compression: compression.map(|c| c.inner).unwrap_or(CompressionAlgorithm::None),
+        encryption_enabled,
+    };
+    let shared = event_store.map(|event_store| event_store.store.clone());
+
+    pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+        let store = match shared {
+            Some(shared) => shared.lock().await.clone().ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("EventStore not initialized")
+            })?,
+            None => Arc::from(
+                create_event_store(EventStoreConfig::sqlite(":memory:".to_string()))
+                    .await
+                    .map_err(crate::error::map_rust_error_to_python)?,
+            ),
+        };
+        let report = benchmark_dry_run_writes(store.as_ref(), config)
+            .await
+            .map_err(crate::error::map_rust_error_to_python)?;
+
+        Python::with_gil(|py| {
+            let stages: Vec<HashMap<String, PyObject>> = report.stages.iter()
+                .map(|stage| HashMap::from([
+                    ("stage".to_string(), stage.stage.to_object(py)),
+                    ("total_ms".to_string(), stage.total_ms.to_object(py)),
+                    ("per_event_us".to_string(), stage.per_event_us.to_object(py)),
+                ]))
+                .collect();
+
+            let result: HashMap<String, PyObject> = HashMap::from([
+                ("events_written".to_string(), report.events_written.to_object(py)),
+                ("total_time_ms".to_string(), report.total_time_ms.to_object(py)),
+                ("events_per_second".to_string(), report.events_per_second.to_object(py)),
+                ("stages".to_string(), stages.to_object(py)),
+            ]);
+            Ok(result.to_object(py))
+        })
+    })
+}
+
+/// Measure end-to-end save and load throughput and latency against a SQLite store
+#[pyfunction]
+#[pyo3(signature = (database_path = ":memory:".to_string(), num_events = 1000, payload_size = 256))]
+pub fn benchmark_event_roundtrip<'py>(
+    py: Python<'py>,
+    database_path: String,
+    num_events: usize,
+    payload_size: usize,
+) -> PyResult<&'py PyAny> {
+    pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+        let report = eventuali_core::performance::benchmark_event_roundtrip(&database_path, num_events, payload_size)
+            .await
+            .map_err(crate::error::map_rust_error_to_python)?;
+
+        let latency = |latency: &LatencyPercentiles| HashMap::from([
+            ("p50_ms".to_string(), latency.p50_ms),
+            ("p95_ms".to_string(), latency.p95_ms),
+            ("p99_ms".to_string(), latency.p99_ms),
+            ("max_ms".to_string(), latency.max_ms),
+        ]);
+
+        Python::with_gil(|py| {
+            let result: HashMap<String, PyObject> = HashMap::from([
+                ("events".to_string(), report.events.to_object(py)),
+                ("payload_size_bytes".to_string(), report.payload_size_bytes.to_object(py)),
+                ("save_events_per_second".to_string(), report.save_events_per_second.to_object(py)),
+                ("load_events_per_second".to_string(), report.load_events_per_second.to_object(py)),
+                ("save_latency".to_string(), latency(&report.save_latency).to_object(py)),
+                ("load_latency".to_string(), latency(&report.load_latency).to_object(py)),
+            ]);
+            Ok(result.to_object(py))
+        })
+    })
+}
+
 /// Register performance optimization Python module
 pub fn register_performance_module(py: Python, m: &PyModule) -> PyResult<()> {
     let performance_module = PyModule::new(py, "performance")?;
@@ -1544,6 +1787,8 @@ pub fn register_performance_module(py: Python, m: &PyModule) -> PyResult<()> {
     performance_module.add_class::<PyWalConfig>()?;
     performance_module.add_class::<PyWalStats>()?;
     performance_module.add_function(wrap_pyfunction!(benchmark_wal_configurations, performance_module)?)?;
+    performance_module.add_function(wrap_pyfunction!(benchmark_dry_run, performance_module)?)?;
+    performance_module.add_function(wrap_pyfunction!(benchmark_event_roundtrip, performance_module)?)?;
     
     // Read replica classes
     performance_module.add_class::<PyReadPreference>()?;
@@ -1559,6 +1804,7 @@ pub fn register_performance_module(py: Python, m: &PyModule) -> PyResult<()> {
     performance_module.add_class::<PyCompressionAlgorithm>()?;
     performance_module.add_class::<PyCompressionConfig>()?;
     performance_module.add_class::<PyCompressionManager>()?;
+    performance_module.add_function(wrap_pyfunction!(train_compression_dictionary, performance_module)?)?;
     
     // Batch processing (temporarily disabled - complex async/sync conflicts)
     // performance_module.add_class::<PyBatchConfig>()?;
@@ -0,0 +1,122 @@
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use eventuali_core::{ReadModelSink, ReadModelWrite, SqliteReadModelSink};
+
+/// Python wrapper for the SQLite read-model sink
+#[pyclass(name = "ReadModelSink")]
+pub struct PyReadModelSink {
+    inner: Option<SqliteReadModelSink>,
+}
+
+#[pymethods]
+impl PyReadModelSink {
+    #[new]
+    fn new() -> Self {
+        Self { inner: None }
+    }
+
+    /// Initialize the sink with a SQLite database, creating its tables
+    #[pyo3(signature = (database_url, table_name=None))]
+    fn initialize(&mut self, database_url: &str, table_name: Option<String>) -> PyResult<()> {
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let pool = sqlx::sqlite::SqlitePoolOptions::new()
+                    .max_connections(10)
+                    .connect(database_url)
+                    .await
+                    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                let sink = SqliteReadModelSink::new(pool, table_name);
+                sink.initialize().await
+                    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                self.inner = Some(sink);
+                Ok(())
+            })
+    }
+
+    /// Apply `(entity_id, row)` pairs in order and move the projection's
+    /// checkpoint to `position`, in one transaction. A row of `None` deletes
+    /// the entity; any other row must be JSON-serializable. Returns False,
+    /// writing nothing, when `position` is at or before the checkpoint.
+    fn apply(&self, py: Python, projection_name: &str, writes: &PyList, position: u64) -> PyResult<bool> {
+        let sink = self.sink()?;
+        let json_module = py.import("json")?;
+
+        let mut parsed = Vec::with_capacity(writes.len());
+        for item in writes.iter() {
+            let (entity_id, row): (String, &PyAny) = item.extract()?;
+            if row.is_none() {
+                parsed.push(ReadModelWrite::delete(entity_id));
+            } else {
+                let json_str: String = json_module.call_method1("dumps", (row,))?.extract()?;
+                let value = serde_json::from_str(&json_str)
+                    .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid row: {e}")))?;
+                parsed.push(ReadModelWrite::upsert(entity_id, value));
+            }
+        }
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                sink.apply(projection_name, parsed, position)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))
+            })
+    }
+
+    /// Last global position applied for a projection
+    fn checkpoint(&self, projection_name: &str) -> PyResult<Option<u64>> {
+        let sink = self.sink()?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                sink.checkpoint(projection_name)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))
+            })
+    }
+
+    /// A projection's current row for an entity, or None
+    fn get(&self, py: Python, projection_name: &str, entity_id: &str) -> PyResult<Option<PyObject>> {
+        let sink = self.sink()?;
+
+        let row = pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                sink.get(projection_name, entity_id)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))
+            })?;
+
+        match row {
+            Some(row) => {
+                let json_module = py.import("json")?;
+                Ok(Some(json_module.call_method1("loads", (row.to_string(),))?.into()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Remove the projection's rows and checkpoint
+    fn clear(&self, projection_name: &str) -> PyResult<()> {
+        let sink = self.sink()?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                sink.clear(projection_name)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))
+            })
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.inner {
+            Some(sink) => format!("ReadModelSink(table={})", sink.table_name()),
+            None => "ReadModelSink(not initialized)".to_string(),
+        }
+    }
+}
+
+impl PyReadModelSink {
+    fn sink(&self) -> PyResult<&SqliteReadModelSink> {
+        self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("ReadModelSink not initialized")
+        })
+    }
+}
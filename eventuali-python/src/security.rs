@@ -1,11 +1,11 @@
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::types::{PyBytes, PyDict, PyType};
 use pyo3::exceptions::PyRuntimeError;
 use eventuali_core::security::{
     EventEncryption as CoreEventEncryption, KeyManager as CoreKeyManager, 
     EncryptionKey as CoreEncryptionKey, EncryptedEventData as CoreEncryptedEventData,
     EncryptionAlgorithm as CoreEncryptionAlgorithm,
-    RbacManager as CoreRbacManager, User as CoreUser, Role as CoreRole,
+    RbacManager as CoreRbacManager, SessionStoreConfig as CoreSessionStoreConfig, User as CoreUser, Role as CoreRole,
     Permission as CorePermission, Session as CoreSession, SecurityLevel as CoreSecurityLevel,
     AccessDecision as CoreAccessDecision, AuditEntry as CoreAuditEntry,
     AuditManager as CoreAuditManager, AuditTrailEntry as CoreAuditTrailEntry,
@@ -23,6 +23,8 @@ use eventuali_core::security::{
     ConsentMethod as CoreConsentMethod, ConsentEvidence as CoreConsentEvidence,
     DataSubjectRight as CoreDataSubjectRight, RequestStatus as CoreRequestStatus,
     BreachType as CoreBreachType, ExportFormat as CoreExportFormat,
+    ExportFormatter as CoreExportFormatter, ExportHeader as CoreExportHeader,
+    ExportProgress as CoreExportProgress, DEFAULT_EXPORT_CHUNK_SIZE,
     // Digital signatures
     EventSigner as CoreEventSigner, SigningKeyManager as CoreSigningKeyManager,
     SigningKey as CoreSigningKey, SignatureAlgorithm as CoreSignatureAlgorithm,
@@ -37,13 +39,17 @@ use eventuali_core::security::{
     VulnerabilityScanner as CoreVulnerabilityScanner, VulnerabilityScanResult as CoreVulnerabilityScanResult,
     VulnerabilityFinding as CoreVulnerabilityFinding, VulnerabilityCategory as CoreVulnerabilityCategory,
     VulnerabilitySeverity as CoreVulnerabilitySeverity,
-    PenetrationTestFramework as CorePenetrationTestFramework, PenetrationTest as CorePenetrationTest
+    PenetrationTestFramework as CorePenetrationTestFramework, PenetrationTest as CorePenetrationTest,
+    // Audit sinks
+    JsonLinesAuditSink as CoreJsonLinesAuditSink, SqliteAuditSink as CoreSqliteAuditSink
 };
-use eventuali_core::{EventData as CoreEventData};
+use eventuali_core::{EventData as CoreEventData, EventualiError as CoreError, TenantId as CoreTenantId};
 use eventuali_core::security::retention::RetentionPolicy as CoreRetentionPolicy;
 use crate::event::PyEvent;
 use crate::error::map_rust_error_to_python;
 use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Arc;
 
 /// Python wrapper for EventEncryption
 #[pyclass(name = "EventEncryption")]
@@ -146,6 +152,14 @@ impl PyEventEncryption {
             }
         }
     }
+
+    /// Re-encrypt data under another key held by this instance
+    pub fn reencrypt(&self, encrypted_data: &PyEncryptedEventData, target_key_id: &str) -> PyResult<PyEncryptedEventData> {
+        self.inner
+            .reencrypt(&encrypted_data.inner, target_key_id)
+            .map(|inner| PyEncryptedEventData { inner })
+            .map_err(map_rust_error_to_python)
+    }
 }
 
 impl Default for PyKeyManager {
@@ -171,10 +185,12 @@ impl PyKeyManager {
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
 
-    /// Generate a new AES-256 key
+    /// Generate a new key, for AES-256-GCM unless another algorithm is given
     #[classmethod]
-    pub fn generate_key(_cls: &PyType, id: String) -> PyResult<PyEncryptionKey> {
-        CoreKeyManager::generate_key(id)
+    #[pyo3(signature = (id, algorithm=None))]
+    pub fn generate_key(_cls: &PyType, id: String, algorithm: Option<PyEncryptionAlgorithm>) -> PyResult<PyEncryptionKey> {
+        let algorithm = algorithm.map_or(CoreEncryptionAlgorithm::Aes256Gcm, |algorithm| algorithm.inner);
+        CoreKeyManager::generate_key_with_algorithm(id, algorithm)
             .map(|inner| PyEncryptionKey { inner })
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
@@ -199,11 +215,35 @@ impl PyKeyManager {
             .map_err(|e| PyRuntimeError::new_err(e.to_string()))
     }
 
-    /// Get all key IDs
+    /// Export all keys as a passphrase-wrapped, portable blob
+    pub fn export_wrapped(&self, passphrase: &str) -> PyResult<String> {
+        self.inner
+            .export_wrapped(passphrase)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Import keys from a blob produced by export_wrapped
+    pub fn import_wrapped(&mut self, blob: &str, passphrase: &str) -> PyResult<()> {
+        self.inner
+            .import_wrapped(blob, passphrase)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))
+    }
+
+    /// Make a held key the default for new data, keeping older keys for decryption
+    pub fn rotate_to(&mut self, new_key_id: &str) -> PyResult<()> {
+        self.inner
+            .rotate_to(new_key_id)
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Get all key IDs, sorted
     pub fn get_key_ids(&self) -> Vec<String> {
-        // Since we can't access the inner HashMap directly, we'll return an empty vec for now
-        // In a real implementation, we'd add a method to CoreKeyManager to list key IDs
-        vec![]
+        self.inner.list_key_ids()
+    }
+
+    /// ID of the default key, or None while no key is held
+    pub fn default_key_id(&self) -> Option<String> {
+        self.inner.default_key_id()
     }
 }
 
@@ -284,6 +324,7 @@ impl PyEncryptionAlgorithm {
     pub fn __str__(&self) -> &'static str {
         match self.inner {
             CoreEncryptionAlgorithm::Aes256Gcm => "AES-256-GCM",
+            CoreEncryptionAlgorithm::ChaCha20Poly1305 => "ChaCha20-Poly1305",
         }
     }
 
@@ -294,6 +335,14 @@ impl PyEncryptionAlgorithm {
             inner: CoreEncryptionAlgorithm::Aes256Gcm,
         }
     }
+
+    /// Create ChaCha20-Poly1305 algorithm
+    #[classmethod]
+    pub fn chacha20poly1305(_cls: &PyType) -> Self {
+        Self {
+            inner: CoreEncryptionAlgorithm::ChaCha20Poly1305,
+        }
+    }
 }
 
 /// Python wrapper for RBAC Manager
@@ -435,18 +484,35 @@ impl PySecurityUtils {
 
 impl Default for PyRbacManager {
     fn default() -> Self {
-        Self::new()
+        Self {
+            inner: CoreRbacManager::new(),
+        }
     }
 }
 
 #[pymethods]
 impl PyRbacManager {
-    /// Create a new RBAC manager
+    /// Create a new RBAC manager. Sessions are kept in memory unless
+    /// `session_database_path` names a SQLite file, which lets several
+    /// managers share sessions and keeps them across restarts.
     #[new]
-    pub fn new() -> Self {
-        Self {
-            inner: CoreRbacManager::new(),
+    #[pyo3(signature = (session_database_path=None, session_table_name=None, session_ttl_seconds=None))]
+    pub fn new(
+        session_database_path: Option<String>,
+        session_table_name: Option<String>,
+        session_ttl_seconds: Option<i64>,
+    ) -> PyResult<Self> {
+        let store_config = match session_database_path {
+            Some(path) => CoreSessionStoreConfig::Sqlite { path, table_name: session_table_name },
+            None => CoreSessionStoreConfig::InMemory,
+        };
+        let mut inner = CoreRbacManager::new()
+            .with_session_store_config(&store_config)
+            .map_err(map_rust_error_to_python)?;
+        if let Some(seconds) = session_ttl_seconds {
+            inner = inner.with_session_ttl(chrono::Duration::seconds(seconds));
         }
+        Ok(Self { inner })
     }
 
     /// Create a new user
@@ -490,9 +556,11 @@ impl PyRbacManager {
             .map_err(map_rust_error_to_python)
     }
 
-    /// Clean up expired sessions
-    pub fn cleanup_expired_sessions(&mut self) {
-        self.inner.cleanup_expired_sessions();
+    /// Clean up expired sessions, returning how many were removed
+    pub fn cleanup_expired_sessions(&mut self) -> PyResult<usize> {
+        self.inner
+            .cleanup_expired_sessions()
+            .map_err(map_rust_error_to_python)
     }
 
     /// Get audit trail
@@ -746,6 +814,54 @@ impl PyAuditManager {
         }
     }
 
+    /// Create an audit manager that keeps entries in an indexed SQLite table
+    /// instead of in memory, continuing the hash chains already stored there
+    #[classmethod]
+    #[pyo3(signature = (database_path, table_name=None))]
+    pub fn with_sqlite_store(_cls: &PyType, database_path: String, table_name: Option<String>) -> PyResult<Self> {
+        CoreAuditManager::new()
+            .with_sqlite_store(&database_path, table_name)
+            .map(|inner| Self { inner })
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Ship every subsequently logged entry to a JSON Lines file
+    pub fn add_file_sink(&mut self, path: String) -> PyResult<()> {
+        let sink = CoreJsonLinesAuditSink::open(path).map_err(map_rust_error_to_python)?;
+        self.inner.add_sink(Arc::new(sink));
+        Ok(())
+    }
+
+    /// Ship every subsequently logged entry to a SQLite table
+    #[pyo3(signature = (database_path, table_name=None))]
+    pub fn add_sqlite_sink(&mut self, database_path: String, table_name: Option<String>) -> PyResult<()> {
+        let sink = CoreSqliteAuditSink::open(&database_path, table_name).map_err(map_rust_error_to_python)?;
+        self.inner.add_sink(Arc::new(sink));
+        Ok(())
+    }
+
+    /// Number of configured audit sinks, not counting the SQLite store
+    #[getter]
+    pub fn sink_count(&self) -> usize {
+        self.inner.sink_count()
+    }
+
+    /// Sink writes that failed since the last call, as dicts with
+    /// `entry_id`, `sink_index` and `error`; the entries themselves were logged
+    pub fn take_sink_failures(&mut self) -> Vec<HashMap<String, PyObject>> {
+        Python::with_gil(|py| {
+            self.inner
+                .take_sink_failures()
+                .into_iter()
+                .map(|failure| HashMap::from([
+                    ("entry_id".to_string(), failure.entry_id.into_py(py)),
+                    ("sink_index".to_string(), failure.sink_index.into_py(py)),
+                    ("error".to_string(), failure.error.into_py(py)),
+                ]))
+                .collect()
+        })
+    }
+
     /// Log an audit event
     pub fn log_audit_event(
         &mut self,
@@ -814,7 +930,7 @@ impl PyAuditManager {
             .map_err(map_rust_error_to_python)
     }
 
-    /// Search audit entries
+    /// Search the global audit log, leaving out entries logged for a tenant
     pub fn search_audit_entries(
         &self,
         user_id: Option<String>,
@@ -823,50 +939,34 @@ impl PyAuditManager {
         end_time: Option<String>,
         limit: Option<usize>,
     ) -> PyResult<Vec<PyAuditTrailEntry>> {
-        use chrono::DateTime;
-        
-        let core_event_types = event_types.map(|types| {
-            types.into_iter().map(|t| t.inner).collect()
-        });
-        
-        let start_dt = if let Some(time_str) = start_time {
-            Some(DateTime::parse_from_rfc3339(&time_str)
-                .map_err(|e| PyRuntimeError::new_err(format!("Invalid start_time format: {e}")))?
-                .with_timezone(&chrono::Utc))
-        } else {
-            None
-        };
-        
-        let end_dt = if let Some(time_str) = end_time {
-            Some(DateTime::parse_from_rfc3339(&time_str)
-                .map_err(|e| PyRuntimeError::new_err(format!("Invalid end_time format: {e}")))?
-                .with_timezone(&chrono::Utc))
-        } else {
-            None
-        };
+        let criteria = audit_search_criteria(user_id, event_types, start_time, end_time)?;
+        let results = self
+            .inner
+            .search_audit_entries(&criteria, limit)
+            .map_err(map_rust_error_to_python)?;
 
-        let criteria = CoreAuditSearchCriteria {
-            user_id,
-            event_types: core_event_types,
-            resources: None,
-            start_time: start_dt,
-            end_time: end_dt,
-            risk_levels: None,
-            compliance_tags: None,
-            ip_addresses: None,
-            outcomes: None,
-            text_search: None,
-        };
+        Ok(results.into_iter().map(|inner| PyAuditTrailEntry { inner }).collect())
+    }
 
-        let results = self.inner.search_audit_entries(&criteria, limit);
-        
-        Ok(results
-            .into_iter()
-            .map(|entry| PyAuditTrailEntry { inner: entry.clone() })
-            .collect())
+    /// Search every tenant's entries and the global log (platform operators only)
+    pub fn search_audit_entries_across_tenants(
+        &self,
+        user_id: Option<String>,
+        event_types: Option<Vec<PyAuditEventType>>,
+        start_time: Option<String>,
+        end_time: Option<String>,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<PyAuditTrailEntry>> {
+        let criteria = audit_search_criteria(user_id, event_types, start_time, end_time)?;
+        let results = self
+            .inner
+            .search_audit_entries_across_tenants(&criteria, limit)
+            .map_err(map_rust_error_to_python)?;
+
+        Ok(results.into_iter().map(|inner| PyAuditTrailEntry { inner }).collect())
     }
 
-    /// Generate compliance report
+    /// Generate a compliance report for the global audit log
     pub fn generate_compliance_report(
         &self,
         framework: PyComplianceTag,
@@ -895,15 +995,135 @@ impl PyAuditManager {
         PyIntegrityStatus { inner: status }
     }
 
+    /// Log an audit event on a tenant's own hash chain
+    #[allow(clippy::too_many_arguments)]
+    pub fn log_tenant_audit_event(
+        &mut self,
+        tenant_id: String,
+        event_type: PyAuditEventType,
+        user_id: String,
+        action: String,
+        resource: String,
+        outcome: PyAuditOutcome,
+        metadata: Option<HashMap<String, String>>,
+    ) -> PyResult<String> {
+        self.inner
+            .log_tenant_audit_event(
+                parse_tenant_id(tenant_id)?,
+                event_type.inner,
+                user_id,
+                action,
+                resource,
+                outcome.inner,
+                metadata,
+            )
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Search only the entries logged for a tenant
+    #[pyo3(signature = (tenant_id, user_id=None, event_types=None, start_time=None, end_time=None, limit=None))]
+    pub fn search_tenant_audit_entries(
+        &self,
+        tenant_id: String,
+        user_id: Option<String>,
+        event_types: Option<Vec<PyAuditEventType>>,
+        start_time: Option<String>,
+        end_time: Option<String>,
+        limit: Option<usize>,
+    ) -> PyResult<Vec<PyAuditTrailEntry>> {
+        let tenant_id = parse_tenant_id(tenant_id)?;
+        let criteria = audit_search_criteria(user_id, event_types, start_time, end_time)?;
+        let results = self
+            .inner
+            .search_tenant_audit_entries(&tenant_id, &criteria, limit)
+            .map_err(map_rust_error_to_python)?;
+
+        Ok(results.into_iter().map(|inner| PyAuditTrailEntry { inner }).collect())
+    }
+
+    /// Generate a compliance report covering only a tenant's entries
+    pub fn generate_tenant_compliance_report(
+        &self,
+        tenant_id: String,
+        framework: PyComplianceTag,
+        start_time: String,
+        end_time: String,
+    ) -> PyResult<PyComplianceReport> {
+        let tenant_id = parse_tenant_id(tenant_id)?;
+        let start_dt = chrono::DateTime::parse_from_rfc3339(&start_time)
+            .map_err(|e| PyRuntimeError::new_err(format!("Invalid start_time format: {e}")))?
+            .with_timezone(&chrono::Utc);
+        let end_dt = chrono::DateTime::parse_from_rfc3339(&end_time)
+            .map_err(|e| PyRuntimeError::new_err(format!("Invalid end_time format: {e}")))?
+            .with_timezone(&chrono::Utc);
+
+        self.inner
+            .generate_tenant_compliance_report(&tenant_id, framework.inner, start_dt, end_dt)
+            .map(|report| PyComplianceReport { inner: report })
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Verify only a tenant's hash chain
+    pub fn verify_tenant_integrity(&self, tenant_id: String) -> PyResult<PyIntegrityStatus> {
+        let tenant_id = parse_tenant_id(tenant_id)?;
+        Ok(PyIntegrityStatus { inner: self.inner.verify_tenant_integrity(&tenant_id) })
+    }
+
     /// Get audit statistics
-    pub fn get_audit_statistics(&self, last_hours: u32) -> HashMap<String, String> {
-        let stats = self.inner.get_audit_statistics(last_hours);
-        stats.into_iter()
+    pub fn get_audit_statistics(&self, last_hours: u32) -> PyResult<HashMap<String, String>> {
+        let stats = self.inner.get_audit_statistics(last_hours).map_err(map_rust_error_to_python)?;
+        Ok(stats.into_iter()
             .map(|(k, v)| (k, v.to_string()))
-            .collect()
+            .collect())
     }
 }
 
+fn parse_tenant_id(tenant_id: String) -> PyResult<CoreTenantId> {
+    CoreTenantId::new(tenant_id).map_err(|e| PyRuntimeError::new_err(format!("Tenant ID error: {e}")))
+}
+
+fn audit_search_criteria(
+    user_id: Option<String>,
+    event_types: Option<Vec<PyAuditEventType>>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+) -> PyResult<CoreAuditSearchCriteria> {
+    use chrono::DateTime;
+
+    let core_event_types = event_types.map(|types| {
+        types.into_iter().map(|t| t.inner).collect()
+    });
+    
+    let start_dt = if let Some(time_str) = start_time {
+        Some(DateTime::parse_from_rfc3339(&time_str)
+            .map_err(|e| PyRuntimeError::new_err(format!("Invalid start_time format: {e}")))?
+            .with_timezone(&chrono::Utc))
+    } else {
+        None
+    };
+    
+    let end_dt = if let Some(time_str) = end_time {
+        Some(DateTime::parse_from_rfc3339(&time_str)
+            .map_err(|e| PyRuntimeError::new_err(format!("Invalid end_time format: {e}")))?
+            .with_timezone(&chrono::Utc))
+    } else {
+        None
+    };
+
+    Ok(CoreAuditSearchCriteria {
+        user_id,
+        event_types: core_event_types,
+        resources: None,
+        start_time: start_dt,
+        end_time: end_dt,
+        risk_levels: None,
+        compliance_tags: None,
+        ip_addresses: None,
+        outcomes: None,
+        text_search: None,
+    })
+}
+
 #[pymethods]
 impl PyAuditEventType {
     #[classmethod]
@@ -1191,6 +1411,11 @@ impl PyAuditTrailEntry {
         self.inner.session_id.clone()
     }
 
+    #[getter]
+    pub fn tenant_id(&self) -> Option<String> {
+        self.inner.tenant_id.as_ref().map(|id| id.as_str().to_string())
+    }
+
     #[getter]
     pub fn metadata(&self) -> HashMap<String, String> {
         self.inner.metadata.clone()
@@ -1366,6 +1591,81 @@ pub struct PyBreachNotification {
     pub(crate) inner: CoreBreachNotification,
 }
 
+/// `std::io::Write` adapter over a Python binary file object
+struct PyFileWriter<'py> {
+    file: &'py PyAny,
+}
+
+impl Write for PyFileWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file
+            .call_method1("write", (PyBytes::new(self.file.py(), buf),))
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.file.hasattr("flush").unwrap_or(false) {
+            self.file
+                .call_method0("flush")
+                .map_err(|e| std::io::Error::other(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Export formatter implemented by a Python object with `header(export_id, header)`,
+/// `record(record)` and `footer(export_id, records_written)` methods, each returning
+/// the `str` or `bytes` to write
+struct PyExportFormatter {
+    name: String,
+    formatter: PyObject,
+}
+
+impl PyExportFormatter {
+    fn write_output(&self, out: &mut dyn Write, method: &str, args: impl IntoPy<Py<pyo3::types::PyTuple>>) -> eventuali_core::Result<()> {
+        let bytes = Python::with_gil(|py| -> PyResult<Vec<u8>> {
+            let output = self.formatter.call_method1(py, method, args)?;
+            let output = output.as_ref(py);
+            match output.downcast::<PyBytes>() {
+                Ok(bytes) => Ok(bytes.as_bytes().to_vec()),
+                Err(_) => Ok(output.extract::<String>()?.into_bytes()),
+            }
+        })
+        .map_err(|e| CoreError::Configuration(format!("Export formatter '{}' failed in {method}: {e}", self.name)))?;
+        out.write_all(&bytes)?;
+        Ok(())
+    }
+
+    fn to_python(value: &impl serde::Serialize) -> eventuali_core::Result<PyObject> {
+        let text = serde_json::to_string(value)?;
+        Python::with_gil(|py| -> PyResult<PyObject> {
+            Ok(py.import("json")?.call_method1("loads", (text,))?.into())
+        })
+        .map_err(|e| CoreError::Validation(format!("Failed to convert export data for Python: {e}")))
+    }
+}
+
+impl CoreExportFormatter for PyExportFormatter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn write_header(&self, out: &mut dyn Write, header: &CoreExportHeader<'_>) -> eventuali_core::Result<()> {
+        let header_value = Self::to_python(header)?;
+        self.write_output(out, "header", (header.export_id.to_string(), header_value))
+    }
+
+    fn write_record(&self, out: &mut dyn Write, record: &serde_json::Value) -> eventuali_core::Result<()> {
+        let record = Self::to_python(record)?;
+        self.write_output(out, "record", (record,))
+    }
+
+    fn write_footer(&self, out: &mut dyn Write, export_id: &str, records_written: u64) -> eventuali_core::Result<()> {
+        self.write_output(out, "footer", (export_id.to_string(), records_written))
+    }
+}
+
 /// Python wrapper for GdprComplianceStatus
 #[pyclass(name = "GdprComplianceStatus")]
 #[derive(Clone)]
@@ -1510,6 +1810,42 @@ impl PyGdprManager {
             .map_err(map_rust_error_to_python)
     }
 
+    /// Make a consent lapse at an RFC 3339 timestamp
+    pub fn set_consent_expiry(&mut self, consent_id: String, expires_at: String) -> PyResult<()> {
+        let expires_at = chrono::DateTime::parse_from_rfc3339(&expires_at)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid expires_at: {e}")))?
+            .with_timezone(&chrono::Utc);
+        self.inner
+            .set_consent_expiry(&consent_id, expires_at)
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Document a lawful basis other than consent for a purpose
+    pub fn record_lawful_basis(
+        &mut self,
+        data_subject_id: String,
+        purpose: String,
+        basis_type: PyLawfulBasisType,
+        basis_details: String,
+    ) -> PyResult<String> {
+        self.inner
+            .record_lawful_basis(data_subject_id, purpose, basis_type.inner, basis_details)
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Whether the subject currently has valid consent for a purpose
+    pub fn has_valid_consent(&self, subject_id: String, purpose: String) -> bool {
+        self.inner.has_valid_consent(&subject_id, &purpose)
+    }
+
+    /// The lawful basis for processing a subject's data for a purpose; raises if none applies
+    pub fn assert_lawful_basis(&self, subject_id: String, purpose: String) -> PyResult<PyLawfulBasisType> {
+        self.inner
+            .assert_lawful_basis(&subject_id, &purpose)
+            .map(|inner| PyLawfulBasisType { inner })
+            .map_err(map_rust_error_to_python)
+    }
+
     /// Process data subject access request (Article 15)
     pub fn process_access_request(
         &mut self,
@@ -1558,6 +1894,94 @@ impl PyGdprManager {
             .map_err(map_rust_error_to_python)
     }
 
+    /// Register a Python export formatter under `name`.
+    ///
+    /// `formatter` must provide `header(export_id, header)`, `record(record)` and
+    /// `footer(export_id, records_written)`, each returning the `str` or `bytes`
+    /// to write. Exports requesting `ExportFormat.structured(name)` use it.
+    pub fn register_export_formatter(&mut self, name: String, formatter: PyObject) -> PyResult<()> {
+        self.inner
+            .register_export_formatter(PyExportFormatter { name, formatter })
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Names of the export formats that can be streamed
+    pub fn export_formatter_names(&self) -> Vec<String> {
+        self.inner.export_formatters().names()
+    }
+
+    /// Set the format `stream_data_export` writes when none is given
+    pub fn set_default_export_format(&mut self, export_format: PyExportFormat) -> PyResult<()> {
+        self.inner
+            .set_default_export_format(export_format.inner)
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Format `stream_data_export` writes when none is given
+    pub fn default_export_format(&self) -> PyExportFormat {
+        PyExportFormat { inner: self.inner.default_export_format().clone() }
+    }
+
+    /// Stream an export for a data subject into a binary file object.
+    ///
+    /// `events` may be any iterable of `Event` objects or JSON-serializable values;
+    /// it is consumed lazily and written in chunks of `chunk_size` records.
+    /// `progress_callback`, if given, is called as `(records_written, bytes_written)`
+    /// after every chunk. `export_format` selects a registered formatter and
+    /// defaults to the manager's default format, JSON Lines unless changed.
+    /// Returns the export id, record count and byte size.
+    #[pyo3(signature = (data_subject_id, events, file, chunk_size=DEFAULT_EXPORT_CHUNK_SIZE, progress_callback=None, export_format=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn stream_data_export(
+        &mut self,
+        py: Python<'_>,
+        data_subject_id: String,
+        events: &PyAny,
+        file: &PyAny,
+        chunk_size: usize,
+        progress_callback: Option<PyObject>,
+        export_format: Option<PyExportFormat>,
+    ) -> PyResult<PyObject> {
+        let export_format = export_format
+            .map(|format| format.inner)
+            .unwrap_or_else(|| self.inner.default_export_format().clone());
+        let json_module = py.import("json")?;
+        let records = events.iter()?.map(|item| -> eventuali_core::Result<serde_json::Value> {
+            let item = item.map_err(|e| CoreError::Validation(format!("Failed to read export record: {e}")))?;
+            if let Ok(event) = item.extract::<PyRef<PyEvent>>() {
+                return Ok(serde_json::to_value(&event.inner)?);
+            }
+            let text: String = json_module
+                .call_method1("dumps", (item,))
+                .and_then(|text| text.extract())
+                .map_err(|e| CoreError::Validation(format!("Export record is not JSON serializable: {e}")))?;
+            Ok(serde_json::from_str(&text)?)
+        });
+
+        let mut last_progress = CoreExportProgress::default();
+        let mut callback_error = None;
+        let record = self
+            .inner
+            .stream_data_export_as(&export_format, &data_subject_id, records, PyFileWriter { file }, chunk_size, |progress| {
+                last_progress = progress;
+                if let (Some(callback), None) = (&progress_callback, &callback_error) {
+                    if let Err(e) = callback.call1(py, (progress.records_written, progress.bytes_written)) {
+                        callback_error = Some(e);
+                    }
+                }
+            })
+            .map_err(map_rust_error_to_python)?;
+        if let Some(e) = callback_error {
+            return Err(e);
+        }
+
+        let result = PyDict::new(py);
+        result.set_item("export_id", record.export_id)?;
+        result.set_item("records_written", last_progress.records_written)?;
+        result.set_item("bytes_written", last_progress.bytes_written)?;
+        Ok(result.into())
+    }
+
     /// Report a personal data breach (Articles 33-34)
     pub fn report_data_breach(
         &mut self,
@@ -1881,6 +2305,18 @@ impl PyExportFormat {
         Self { inner: CoreExportFormat::Pdf }
     }
 
+    /// A format written by the export formatter registered under `name`
+    #[classmethod]
+    pub fn structured(_cls: &PyType, name: String) -> Self {
+        Self { inner: CoreExportFormat::StructuredFormat(name) }
+    }
+
+    /// Name of the formatter that writes this format
+    #[getter]
+    pub fn name(&self) -> String {
+        self.inner.formatter_name().to_string()
+    }
+
     pub fn __str__(&self) -> &'static str {
         match &self.inner {
             CoreExportFormat::Json => "Json",
@@ -2184,6 +2620,23 @@ impl PyEventSigner {
             .map_err(map_rust_error_to_python)
     }
 
+    /// Sign events as a chain, each signature covering the previous one
+    pub fn sign_chain(&self, events: Vec<PyRef<PyEvent>>) -> PyResult<Vec<PySignedEvent>> {
+        let events: Vec<_> = events.iter().map(|event| event.inner.clone()).collect();
+        self.inner
+            .sign_chain(&events)
+            .map(|chain| chain.into_iter().map(|signed| PySignedEvent { inner: signed }).collect())
+            .map_err(map_rust_error_to_python)
+    }
+
+    /// Verify that a chain is intact: no event removed, inserted or reordered
+    pub fn verify_chain(&self, signed_events: Vec<PySignedEvent>) -> PyResult<bool> {
+        let chain: Vec<_> = signed_events.into_iter().map(|signed| signed.inner).collect();
+        self.inner
+            .verify_chain(&chain)
+            .map_err(map_rust_error_to_python)
+    }
+
     /// Sign raw data
     pub fn sign_data(&self, data: Vec<u8>, key_id: &str) -> PyResult<PyEventSignature> {
         self.inner
@@ -2341,6 +2794,12 @@ impl PyEventSignature {
         self.inner.signature.len()
     }
 
+    /// Whether this signature links to a previous one in a chain
+    #[getter]
+    pub fn is_chained(&self) -> bool {
+        self.inner.previous_signature.is_some()
+    }
+
     /// Serialize to base64
     pub fn to_base64(&self) -> String {
         self.inner.to_base64()
@@ -2881,6 +3340,12 @@ impl PyVulnerabilityScanResult {
     pub fn scan_timestamp(&self) -> String {
         self.inner.scan_timestamp.to_rfc3339()
     }
+
+    /// Render the scan as a SARIF 2.1.0 JSON document; evidence appears only
+    /// as its SHA-256
+    pub fn to_sarif(&self) -> PyResult<String> {
+        self.inner.to_sarif().map_err(map_rust_error_to_python)
+    }
 }
 
 #[pymethods]
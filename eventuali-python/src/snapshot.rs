@@ -1,10 +1,14 @@
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::{PyBytes, PyDict};
+use std::sync::Arc;
 
 use eventuali_core::{
     AggregateSnapshot, SnapshotService, SnapshotConfig, 
-    SnapshotCompression, SqliteSnapshotStore
+    SnapshotCompression, SqliteSnapshotStore, ProjectionSnapshot,
+    ProjectionSnapshotStore, SqliteProjectionSnapshotStore, state_codec_by_name,
+    DEFAULT_ZSTD_COMPRESSION_LEVEL, DEFAULT_MAX_SNAPSHOT_STATE_SIZE,
 };
+use crate::error::map_rust_error_to_python;
 
 /// Python wrapper for AggregateSnapshot
 #[pyclass(name = "AggregateSnapshot")]
@@ -42,11 +46,13 @@ impl PyAggregateSnapshot {
 
     #[getter]
     fn compression(&self) -> String {
-        match self.inner.compression {
-            SnapshotCompression::None => "none".to_string(),
-            SnapshotCompression::Gzip => "gzip".to_string(),
-            SnapshotCompression::Lz4 => "lz4".to_string(),
-        }
+        compression_name(&self.inner.compression).to_string()
+    }
+
+    /// Zstandard level, or None for algorithms without levels
+    #[getter]
+    fn compression_level(&self) -> Option<i32> {
+        compression_level(&self.inner.compression)
     }
 
     #[getter]
@@ -101,30 +107,47 @@ pub struct PySnapshotConfig {
 #[pymethods]
 impl PySnapshotConfig {
     #[new]
-    #[pyo3(signature = (snapshot_frequency=100, max_snapshot_age_hours=168, compression="gzip", auto_cleanup=true))]
+    #[allow(clippy::too_many_arguments)]
+    #[pyo3(signature = (snapshot_frequency=100, max_snapshot_age_hours=168, compression="gzip", auto_cleanup=true, aggregate_type_frequencies=None, compression_level=None, verify_checksum_on_load=false, max_decompressed_size=None))]
     fn new(
         snapshot_frequency: i64,
         max_snapshot_age_hours: u64,
         compression: &str,
         auto_cleanup: bool,
+        aggregate_type_frequencies: Option<std::collections::HashMap<String, i64>>,
+        compression_level: Option<i32>,
+        verify_checksum_on_load: bool,
+        max_decompressed_size: Option<usize>,
     ) -> PyResult<Self> {
+        if compression_level.is_some() && compression != "zstd" {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                format!("compression_level applies only to zstd, not {compression}")
+            ));
+        }
         let compression_enum = match compression {
             "none" => SnapshotCompression::None,
             "gzip" => SnapshotCompression::Gzip,
             "lz4" => SnapshotCompression::Lz4,
+            "zstd" => SnapshotCompression::Zstd {
+                level: compression_level.unwrap_or(DEFAULT_ZSTD_COMPRESSION_LEVEL),
+            },
             _ => return Err(pyo3::exceptions::PyValueError::new_err(
                 format!("Unknown compression type: {compression}")
             )),
         };
 
-        Ok(Self {
-            inner: SnapshotConfig {
-                snapshot_frequency,
-                max_snapshot_age_hours,
-                compression: compression_enum,
-                auto_cleanup,
-            }
-        })
+        let config = SnapshotConfig {
+            snapshot_frequency,
+            aggregate_type_frequencies: aggregate_type_frequencies.unwrap_or_default(),
+            max_snapshot_age_hours,
+            auto_cleanup,
+            verify_checksum_on_load,
+            max_decompressed_size: max_decompressed_size.unwrap_or(DEFAULT_MAX_SNAPSHOT_STATE_SIZE),
+            ..SnapshotConfig::default()
+        };
+        let config = config.with_compression(compression_enum).map_err(map_rust_error_to_python)?;
+        config.validate().map_err(map_rust_error_to_python)?;
+        Ok(Self { inner: config })
     }
 
     #[getter]
@@ -132,6 +155,16 @@ impl PySnapshotConfig {
         self.inner.snapshot_frequency
     }
 
+    #[getter]
+    fn aggregate_type_frequencies(&self) -> std::collections::HashMap<String, i64> {
+        self.inner.aggregate_type_frequencies.clone()
+    }
+
+    /// How often aggregates of `aggregate_type` are snapshotted
+    fn frequency_for(&self, aggregate_type: &str) -> i64 {
+        self.inner.frequency_for(aggregate_type)
+    }
+
     #[getter]
     fn max_snapshot_age_hours(&self) -> u64 {
         self.inner.max_snapshot_age_hours
@@ -139,11 +172,13 @@ impl PySnapshotConfig {
 
     #[getter]
     fn compression(&self) -> String {
-        match self.inner.compression {
-            SnapshotCompression::None => "none".to_string(),
-            SnapshotCompression::Gzip => "gzip".to_string(),
-            SnapshotCompression::Lz4 => "lz4".to_string(),
-        }
+        compression_name(&self.inner.compression).to_string()
+    }
+
+    /// Zstandard level, or None for algorithms without levels
+    #[getter]
+    fn compression_level(&self) -> Option<i32> {
+        compression_level(&self.inner.compression)
     }
 
     #[getter]
@@ -151,6 +186,16 @@ impl PySnapshotConfig {
         self.inner.auto_cleanup
     }
 
+    #[getter]
+    fn verify_checksum_on_load(&self) -> bool {
+        self.inner.verify_checksum_on_load
+    }
+
+    #[getter]
+    fn max_decompressed_size(&self) -> usize {
+        self.inner.max_decompressed_size
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "SnapshotConfig(frequency={}, max_age={}h, compression={})",
@@ -161,6 +206,22 @@ impl PySnapshotConfig {
     }
 }
 
+fn compression_name(compression: &SnapshotCompression) -> &'static str {
+    match compression {
+        SnapshotCompression::None => "none",
+        SnapshotCompression::Gzip => "gzip",
+        SnapshotCompression::Lz4 => "lz4",
+        SnapshotCompression::Zstd { .. } => "zstd",
+    }
+}
+
+fn compression_level(compression: &SnapshotCompression) -> Option<i32> {
+    match compression {
+        SnapshotCompression::Zstd { level } => Some(*level),
+        _ => None,
+    }
+}
+
 /// Python wrapper for SnapshotService with SQLite backend
 #[pyclass(name = "SnapshotService")]
 pub struct PySnapshotService {
@@ -175,7 +236,11 @@ impl PySnapshotService {
     }
 
     /// Initialize the snapshot service with SQLite database
-    fn initialize(&mut self, database_url: &str, config: &PySnapshotConfig) -> PyResult<()> {
+    #[pyo3(signature = (database_url, config, state_codec="json"))]
+    fn initialize(&mut self, database_url: &str, config: &PySnapshotConfig, state_codec: &str) -> PyResult<()> {
+        let codec = state_codec_by_name(state_codec)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+
         pyo3_asyncio::tokio::get_runtime()
             .block_on(async {
                 // Create database pool
@@ -191,7 +256,8 @@ impl PySnapshotService {
                     .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
 
                 // Create snapshot service
-                let service = SnapshotService::new(store, config.inner.clone());
+                let service = SnapshotService::new(store, config.inner.clone())
+                    .with_state_codec(Arc::from(codec));
                 self.inner = Some(service);
 
                 Ok(())
@@ -225,6 +291,50 @@ impl PySnapshotService {
             })
     }
 
+    /// Create a snapshot from a state dict, encoded with the service's state codec
+    fn create_snapshot_from_state(
+        &self,
+        py: Python,
+        aggregate_id: &str,
+        aggregate_type: &str,
+        aggregate_version: i64,
+        state: &PyDict,
+        event_count: usize,
+    ) -> PyResult<PyAggregateSnapshot> {
+        let service = self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("SnapshotService not initialized")
+        })?;
+
+        let json_str: String = py.import("json")?.call_method1("dumps", (state,))?.extract()?;
+        let state: serde_json::Value = serde_json::from_str(&json_str)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid snapshot state: {e}")))?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let snapshot = service.create_snapshot_from_state(
+                    aggregate_id.to_string(),
+                    aggregate_type.to_string(),
+                    aggregate_version,
+                    &state,
+                    event_count,
+                ).await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                Ok(PyAggregateSnapshot::from(snapshot))
+            })
+    }
+
+    /// Decode the state of a snapshot created with `create_snapshot_from_state` back into a dict
+    fn decode_snapshot_state(&self, py: Python, snapshot: &PyAggregateSnapshot) -> PyResult<PyObject> {
+        let service = self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("SnapshotService not initialized")
+        })?;
+
+        let state: serde_json::Value = service.decode_snapshot_state(&snapshot.inner)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Serialization error: {e}")))?;
+
+        Ok(py.import("json")?.call_method1("loads", (state.to_string(),))?.into())
+    }
+
     /// Load the most recent snapshot for an aggregate
     fn load_latest_snapshot(&self, aggregate_id: &str) -> PyResult<Option<PyAggregateSnapshot>> {
         let service = self.inner.as_ref().ok_or_else(|| {
@@ -234,7 +344,23 @@ impl PySnapshotService {
         pyo3_asyncio::tokio::get_runtime()
             .block_on(async {
                 let snapshot = service.load_latest_snapshot(&aggregate_id.to_string())
-                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+                    .await.map_err(map_rust_error_to_python)?;
+
+                Ok(snapshot.map(PyAggregateSnapshot::from))
+            })
+    }
+
+    /// Load the most recent snapshot for an aggregate, raising ValueError if
+    /// its stored data no longer matches its checksum
+    fn load_latest_snapshot_verified(&self, aggregate_id: &str) -> PyResult<Option<PyAggregateSnapshot>> {
+        let service = self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("SnapshotService not initialized")
+        })?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let snapshot = service.load_latest_snapshot_verified(&aggregate_id.to_string())
+                    .await.map_err(map_rust_error_to_python)?;
 
                 Ok(snapshot.map(PyAggregateSnapshot::from))
             })
@@ -252,21 +378,36 @@ impl PySnapshotService {
         Ok(decompressed)
     }
 
-    /// Check if a snapshot should be taken
-    fn should_take_snapshot(&self, aggregate_id: &str, current_version: i64) -> PyResult<bool> {
+    /// Check if a snapshot should be taken, at the frequency configured for
+    /// `aggregate_type` when given
+    #[pyo3(signature = (aggregate_id, current_version, aggregate_type=None))]
+    fn should_take_snapshot(&self, aggregate_id: &str, current_version: i64, aggregate_type: Option<&str>) -> PyResult<bool> {
         let service = self.inner.as_ref().ok_or_else(|| {
             pyo3::exceptions::PyRuntimeError::new_err("SnapshotService not initialized")
         })?;
 
         pyo3_asyncio::tokio::get_runtime()
             .block_on(async {
-                let should_take = service.should_take_snapshot(&aggregate_id.to_string(), current_version)
+                let should_take = service.should_take_snapshot(&aggregate_id.to_string(), aggregate_type.unwrap_or_default(), current_version)
                     .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
 
                 Ok(should_take)
             })
     }
 
+    /// Delete every snapshot of an aggregate
+    fn delete_aggregate_snapshots(&self, aggregate_id: &str) -> PyResult<u64> {
+        let service = self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("SnapshotService not initialized")
+        })?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                service.delete_aggregate_snapshots(&aggregate_id.to_string())
+                    .await.map_err(map_rust_error_to_python)
+            })
+    }
+
     /// Perform cleanup of old snapshots
     fn cleanup_old_snapshots(&self) -> PyResult<u64> {
         let service = self.inner.as_ref().ok_or_else(|| {
@@ -289,4 +430,150 @@ impl PySnapshotService {
             "SnapshotService(not initialized)".to_string()
         }
     }
-}
\ No newline at end of file
+}
+/// Python wrapper for ProjectionSnapshot
+#[pyclass(name = "ProjectionSnapshot")]
+#[derive(Clone)]
+pub struct PyProjectionSnapshot {
+    inner: ProjectionSnapshot,
+}
+
+#[pymethods]
+impl PyProjectionSnapshot {
+    #[getter]
+    fn snapshot_id(&self) -> String {
+        self.inner.snapshot_id.to_string()
+    }
+
+    #[getter]
+    fn projection_name(&self) -> &str {
+        &self.inner.projection_name
+    }
+
+    #[getter]
+    fn position(&self) -> u64 {
+        self.inner.position
+    }
+
+    #[getter]
+    fn state_data<'py>(&self, py: Python<'py>) -> PyResult<&'py PyBytes> {
+        Ok(PyBytes::new(py, &self.inner.state_data))
+    }
+
+    #[getter]
+    fn created_at(&self) -> String {
+        self.inner.created_at.to_rfc3339()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ProjectionSnapshot(id={}, projection={}, position={}, size={})",
+            self.inner.snapshot_id,
+            self.inner.projection_name,
+            self.inner.position,
+            self.inner.state_data.len()
+        )
+    }
+}
+
+impl From<ProjectionSnapshot> for PyProjectionSnapshot {
+    fn from(inner: ProjectionSnapshot) -> Self {
+        Self { inner }
+    }
+}
+
+/// Python wrapper for the SQLite projection snapshot store
+#[pyclass(name = "ProjectionSnapshotStore")]
+pub struct PyProjectionSnapshotStore {
+    inner: Option<SqliteProjectionSnapshotStore>,
+}
+
+#[pymethods]
+impl PyProjectionSnapshotStore {
+    #[new]
+    fn new() -> Self {
+        Self { inner: None }
+    }
+
+    /// Initialize the store with a SQLite database
+    fn initialize(&mut self, database_url: &str) -> PyResult<()> {
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let pool = sqlx::sqlite::SqlitePoolOptions::new()
+                    .max_connections(10)
+                    .connect(database_url)
+                    .await
+                    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                let store = SqliteProjectionSnapshotStore::new(pool, None);
+                store.initialize().await
+                    .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                self.inner = Some(store);
+                Ok(())
+            })
+    }
+
+    /// Save projection state taken at the given global position
+    fn save_snapshot(
+        &self,
+        projection_name: &str,
+        position: u64,
+        state_data: &[u8],
+    ) -> PyResult<PyProjectionSnapshot> {
+        let store = self.store()?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let snapshot = ProjectionSnapshot::new(
+                    projection_name.to_string(),
+                    position,
+                    state_data.to_vec(),
+                );
+                store.save_snapshot(snapshot.clone())
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                Ok(PyProjectionSnapshot::from(snapshot))
+            })
+    }
+
+    /// Load the most recent snapshot for a projection
+    fn load_latest_snapshot(&self, projection_name: &str) -> PyResult<Option<PyProjectionSnapshot>> {
+        let store = self.store()?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                let snapshot = store.load_latest_snapshot(projection_name)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))?;
+
+                Ok(snapshot.map(PyProjectionSnapshot::from))
+            })
+    }
+
+    /// Delete every snapshot of a projection
+    fn delete_snapshots(&self, projection_name: &str) -> PyResult<u64> {
+        let store = self.store()?;
+
+        pyo3_asyncio::tokio::get_runtime()
+            .block_on(async {
+                store.delete_snapshots(projection_name)
+                    .await.map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("Database error: {e}")))
+            })
+    }
+
+    fn __repr__(&self) -> String {
+        if self.inner.is_some() {
+            "ProjectionSnapshotStore(initialized)".to_string()
+        } else {
+            "ProjectionSnapshotStore(not initialized)".to_string()
+        }
+    }
+}
+
+impl PyProjectionSnapshotStore {
+    fn store(&self) -> PyResult<&SqliteProjectionSnapshotStore> {
+        self.inner.as_ref().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("ProjectionSnapshotStore not initialized")
+        })
+    }
+}
@@ -1,13 +1,18 @@
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use eventuali_core::{
-    EventStreamer, EventStreamReceiver, Subscription,
-    InMemoryEventStreamer
+    EventStreamer, EventStreamReceiver, Subscription, SubscriptionBuilder,
+    InMemoryEventStreamer, SharedStreamer, CatchUpEvent, CatchUpSubscription,
+    Event, EventualiError, Projection, ProjectionProcessor, DerivingProjection, DerivedEvent,
+    LagWatchdog, LagAlert
 };
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use crate::event::PyEvent;
+use crate::event_store::PyEventStore;
 use crate::error::map_rust_error_to_python;
+#[cfg(feature = "observability")]
+use crate::observability::PyHealthStatus;
 use uuid::Uuid;
 
 #[pyclass]
@@ -27,27 +32,7 @@ impl PyEventStreamer {
 
     pub fn subscribe<'p>(&self, py: Python<'p>, subscription_dict: &PyDict) -> PyResult<&'p PyAny> {
         let streamer = self.streamer.clone();
-        
-        // Extract subscription parameters from dictionary
-        let subscription_id = subscription_dict
-            .get_item("id")?
-            .and_then(|v| v.extract::<String>().ok())
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
-            
-        let aggregate_type_filter = subscription_dict
-            .get_item("aggregate_type_filter")?
-            .and_then(|v| v.extract::<String>().ok());
-            
-        let event_type_filter = subscription_dict
-            .get_item("event_type_filter")?
-            .and_then(|v| v.extract::<String>().ok());
-        
-        let subscription = Subscription {
-            id: subscription_id,
-            aggregate_type_filter,
-            event_type_filter,
-            from_timestamp: None,
-        };
+        let subscription = subscription_from_dict(subscription_dict)?;
         
         pyo3_asyncio::tokio::future_into_py(py, async move {
             let streamer_guard = streamer.lock().await;
@@ -61,6 +46,37 @@ impl PyEventStreamer {
         })
     }
 
+    /// Replay `event_store` from after `from_position`, then follow live
+    /// events. The receiver yields a `caught_up` message once replay reaches
+    /// the live tail.
+    #[pyo3(signature = (event_store, subscription_dict, from_position=0, batch_size=1000))]
+    pub fn subscribe_from<'p>(
+        &self,
+        py: Python<'p>,
+        event_store: &PyEventStore,
+        subscription_dict: &PyDict,
+        from_position: u64,
+        batch_size: usize,
+    ) -> PyResult<&'p PyAny> {
+        let streamer = self.streamer.clone();
+        let subscription = subscription_from_dict(subscription_dict)?;
+        let store = event_store.store.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let store = store.lock().await.clone().ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("EventStore not initialized")
+            })?;
+            let streamer_guard = streamer.lock().await;
+            let catch_up = CatchUpSubscription::start(&*streamer_guard, store, subscription, from_position, batch_size)
+                .await
+                .map_err(map_rust_error_to_python)?;
+
+            Ok(PyCatchUpReceiver {
+                subscription: Arc::new(Mutex::new(catch_up)),
+            })
+        })
+    }
+
     #[pyo3(signature = (subscription_id))]
     pub fn unsubscribe<'p>(&self, py: Python<'p>, subscription_id: String) -> PyResult<&'p PyAny> {
         let streamer = self.streamer.clone();
@@ -125,6 +141,68 @@ impl PyEventStreamer {
     // Methods moved to pymethods block
 }
 
+/// Hub that stores created with it publish into, merging their events into
+/// one stream numbered by hub position
+#[pyclass]
+pub struct PySharedStreamer {
+    pub(crate) streamer: Arc<SharedStreamer>,
+}
+
+#[pymethods]
+impl PySharedStreamer {
+    #[new]
+    pub fn new(capacity: Option<usize>) -> Self {
+        Self {
+            streamer: Arc::new(SharedStreamer::new(capacity.unwrap_or(1000))),
+        }
+    }
+
+    pub fn subscribe<'p>(&self, py: Python<'p>, subscription_dict: &PyDict) -> PyResult<&'p PyAny> {
+        let streamer = self.streamer.clone();
+        let subscription = subscription_from_dict(subscription_dict)?;
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let receiver = streamer.subscribe(subscription)
+                .await
+                .map_err(map_rust_error_to_python)?;
+            
+            Ok(PyEventStreamReceiver { 
+                receiver: Arc::new(Mutex::new(receiver)) 
+            })
+        })
+    }
+
+    #[pyo3(signature = (subscription_id))]
+    pub fn unsubscribe<'p>(&self, py: Python<'p>, subscription_id: String) -> PyResult<&'p PyAny> {
+        let streamer = self.streamer.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            streamer.unsubscribe(&subscription_id)
+                .await
+                .map_err(map_rust_error_to_python)?;
+            Ok(())
+        })
+    }
+
+    #[pyo3(signature = (store_id, stream_id))]
+    pub fn get_stream_position(&self, store_id: String, stream_id: String) -> PyResult<Option<u64>> {
+        self.streamer
+            .store_stream_position(&store_id, &stream_id)
+            .map_err(map_rust_error_to_python)
+    }
+
+    pub fn get_global_position<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let streamer = self.streamer.clone();
+        
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let position = streamer.get_global_position()
+                .await
+                .map_err(map_rust_error_to_python)?;
+            Ok(position)
+        })
+    }
+}
+
 #[pyclass]
 pub struct PyEventStreamReceiver {
     receiver: Arc<Mutex<EventStreamReceiver>>,
@@ -156,11 +234,85 @@ impl PyEventStreamReceiver {
     }
 }
 
+/// Receiver for a subscription that replays stored events before going live
+#[pyclass]
+pub struct PyCatchUpReceiver {
+    subscription: Arc<Mutex<CatchUpSubscription>>,
+}
+
+#[pymethods]
+impl PyCatchUpReceiver {
+    /// Next message as a dict. Events carry `event`, `stream_position` and
+    /// `global_position`; the one-off caught-up signal carries
+    /// `caught_up: True` and the `global_position` replay ended at.
+    pub fn recv<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let subscription = self.subscription.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let mut subscription_guard = subscription.lock().await;
+            match subscription_guard.next().await.map_err(map_rust_error_to_python)? {
+                Some(CatchUpEvent::Event(stream_event)) => {
+                    let stream_event = *stream_event;
+                    Python::with_gil(|py| {
+                        let py_dict = PyDict::new(py);
+                        let py_event = PyEvent { inner: stream_event.event };
+                        py_dict.set_item("caught_up", false)?;
+                        py_dict.set_item("event", Py::new(py, py_event)?)?;
+                        py_dict.set_item("stream_position", stream_event.stream_position)?;
+                        py_dict.set_item("global_position", stream_event.global_position)?;
+                        Ok(py_dict.to_object(py))
+                    })
+                }
+                Some(CatchUpEvent::CaughtUp { global_position }) => {
+                    Python::with_gil(|py| {
+                        let py_dict = PyDict::new(py);
+                        py_dict.set_item("caught_up", true)?;
+                        py_dict.set_item("global_position", global_position)?;
+                        Ok(py_dict.to_object(py))
+                    })
+                }
+                None => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "Channel closed or no more events"
+                )),
+            }
+        })
+    }
+}
+
+fn subscription_from_dict(subscription_dict: &PyDict) -> PyResult<Subscription> {
+    let subscription_id = subscription_dict
+        .get_item("id")?
+        .and_then(|v| v.extract::<String>().ok())
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let aggregate_type_filter = subscription_dict
+        .get_item("aggregate_type_filter")?
+        .and_then(|v| v.extract::<String>().ok());
+
+    let event_type_filter = subscription_dict
+        .get_item("event_type_filter")?
+        .and_then(|v| v.extract::<String>().ok());
+
+    let replay_rate_limit = subscription_dict
+        .get_item("replay_rate_limit")?
+        .and_then(|v| v.extract::<u32>().ok())
+        .filter(|&rate| rate > 0);
+
+    Ok(Subscription {
+        id: subscription_id,
+        aggregate_type_filter,
+        event_type_filter,
+        from_timestamp: None,
+        replay_rate_limit,
+    })
+}
+
 #[pyclass]
 pub struct PySubscriptionBuilder {
     id: Option<String>,
     aggregate_type_filter: Option<String>,
     event_type_filter: Option<String>,
+    replay_rate_limit: Option<u32>,
 }
 
 impl Default for PySubscriptionBuilder {
@@ -177,6 +329,7 @@ impl PySubscriptionBuilder {
             id: None,
             aggregate_type_filter: None,
             event_type_filter: None,
+            replay_rate_limit: None,
         }
     }
 
@@ -195,6 +348,12 @@ impl PySubscriptionBuilder {
         slf
     }
 
+    /// Cap catch-up replay at `events_per_second`; zero leaves replay unthrottled
+    pub fn with_replay_rate_limit(mut slf: PyRefMut<Self>, events_per_second: u32) -> PyRefMut<Self> {
+        slf.replay_rate_limit = (events_per_second > 0).then_some(events_per_second);
+        slf
+    }
+
     pub fn build(&self, py: Python<'_>) -> PyResult<PyObject> {
         let py_dict = PyDict::new(py);
         
@@ -209,6 +368,10 @@ impl PySubscriptionBuilder {
             py_dict.set_item("event_type_filter", event_filter)?;
         }
         
+        if let Some(rate) = self.replay_rate_limit {
+            py_dict.set_item("replay_rate_limit", rate)?;
+        }
+        
         Ok(py_dict.to_object(py))
     }
 }
@@ -277,4 +440,212 @@ impl PyProjection {
             Ok(())
         })
     }
-}
\ No newline at end of file
+}
+
+/// Projection that folds events into per-aggregate JSON state and saves an
+/// event to the store whenever one of its rules fires.
+///
+/// `fold(state, event)` returns the aggregate's new state; `state` is
+/// `initial_state` before the aggregate's first event. Each rule is called as
+/// `rule(before, after, event)` and returns `None` or an
+/// `(event_type, data)` tuple to derive. Events are passed as dicts.
+#[pyclass]
+pub struct PyDerivingProjection {
+    processor: Arc<ProjectionProcessor<DerivingProjection<serde_json::Value>>>,
+    store: Arc<dyn eventuali_core::EventStore + Send + Sync>,
+}
+
+#[pymethods]
+impl PyDerivingProjection {
+    #[new]
+    #[pyo3(signature = (name, event_store, fold, rules, initial_state = None))]
+    pub fn new(
+        py: Python<'_>,
+        name: String,
+        event_store: &PyEventStore,
+        fold: PyObject,
+        rules: Vec<(String, PyObject)>,
+        initial_state: Option<&PyAny>,
+    ) -> PyResult<Self> {
+        // Wait out whoever holds the store without holding the GIL, so a busy
+        // store is not mistaken for an uninitialized one
+        let shared = event_store.store.clone();
+        let store = py
+            .allow_threads(move || shared.blocking_lock().clone())
+            .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("EventStore not initialized"))?;
+        let initial_state = match initial_state {
+            Some(state) => json_from_py(py, state)?,
+            None => serde_json::Value::Null,
+        };
+
+        let fold_name = name.clone();
+        let mut projection = DerivingProjection::new(name, store.clone(), move |state: &mut serde_json::Value, event: &Event| {
+            Python::with_gil(|py| {
+                let current = if state.is_null() { &initial_state } else { &*state };
+                let result = fold.call1(py, (json_to_py(py, current)?, event_dict(py, event)?))?;
+                *state = json_from_py(py, result.as_ref(py))?;
+                Ok(())
+            })
+            .map_err(|e: PyErr| EventualiError::InvalidState(format!("Fold of projection {fold_name} failed: {e}")))
+        });
+        for (rule_name, rule) in rules {
+            let label = rule_name.clone();
+            projection = projection.with_rule(rule_name, move |before: &serde_json::Value, after: &serde_json::Value, event: &Event| {
+                Python::with_gil(|py| {
+                    let result = rule.call1(py, (json_to_py(py, before)?, json_to_py(py, after)?, event_dict(py, event)?))?;
+                    let derived: Option<(String, &PyAny)> = result.extract(py)?;
+                    derived
+                        .map(|(event_type, data)| Ok(DerivedEvent::new(event_type, json_from_py(py, data)?)))
+                        .transpose()
+                })
+                .map_err(|e: PyErr| EventualiError::InvalidState(format!("Derivation rule {label} failed: {e}")))
+            });
+        }
+
+        Ok(Self {
+            processor: Arc::new(ProjectionProcessor::new(projection)),
+            store,
+        })
+    }
+
+    /// Fold and check one event
+    pub fn handle_event<'p>(&self, py: Python<'p>, event: &PyEvent) -> PyResult<&'p PyAny> {
+        let processor = self.processor.clone();
+        let event = event.inner.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            processor.projection().handle_event(&event).await.map_err(map_rust_error_to_python)
+        })
+    }
+
+    /// Process every stored event past the last processed position, returning
+    /// how many were handled
+    #[pyo3(signature = (batch_size = 1000))]
+    pub fn catch_up<'p>(&self, py: Python<'p>, batch_size: usize) -> PyResult<&'p PyAny> {
+        let processor = self.processor.clone();
+        let store = self.store.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let subscription = SubscriptionBuilder::new().build();
+            processor
+                .catch_up(&*store, &subscription, batch_size)
+                .await
+                .map_err(map_rust_error_to_python)
+        })
+    }
+
+    /// Computed state of an aggregate, or None if none of its events were handled
+    pub fn state_of<'p>(&self, py: Python<'p>, aggregate_id: String) -> PyResult<&'p PyAny> {
+        let processor = self.processor.clone();
+
+        pyo3_asyncio::tokio::future_into_py::<_, PyObject>(py, async move {
+            let state = processor.projection().state_of(&aggregate_id).await;
+            Python::with_gil(|py| match state {
+                Some(state) => Ok(json_to_py(py, &state)?.to_object(py)),
+                None => Ok(py.None()),
+            })
+        })
+    }
+
+    pub fn get_last_processed_position<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let processor = self.processor.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            processor
+                .projection()
+                .get_last_processed_position()
+                .await
+                .map_err(map_rust_error_to_python)
+        })
+    }
+
+    /// Number of derived events saved
+    #[getter]
+    pub fn emitted_count(&self) -> u64 {
+        self.processor.projection().emitted_count()
+    }
+
+    /// Stream that events derived from `aggregate_id` are saved to
+    pub fn derived_stream_id(&self, aggregate_id: &str) -> String {
+        self.processor.projection().derived_stream_id(aggregate_id)
+    }
+}
+
+/// Alerts when a projection's lag keeps growing for `stall_window_ms`.
+///
+/// Report each projection's lag with `observe`. Callbacks registered with
+/// `on_alert` are called with a dict holding `projection_name`, `lag`,
+/// `lag_at_start` and `stalled_for_ms`, once per stall.
+#[pyclass(name = "LagWatchdog")]
+pub struct PyLagWatchdog {
+    inner: Arc<LagWatchdog>,
+}
+
+#[pymethods]
+impl PyLagWatchdog {
+    #[new]
+    pub fn new(stall_window_ms: u64) -> Self {
+        Self {
+            inner: Arc::new(LagWatchdog::new(std::time::Duration::from_millis(stall_window_ms))),
+        }
+    }
+
+    /// Call `callback(alert)` with every alert raised from now on. Exceptions
+    /// it raises are printed and ignored, so one failing callback cannot stop
+    /// the others.
+    pub fn on_alert(&self, callback: PyObject) {
+        self.inner.on_alert(move |alert| {
+            Python::with_gil(|py| {
+                if let Err(e) = lag_alert_dict(py, alert).and_then(|alert| callback.call1(py, (alert,))) {
+                    e.print(py);
+                }
+            })
+        });
+    }
+
+    /// Record a projection's current lag, returning the alert dict if this
+    /// sample completes a stall window
+    pub fn observe(&self, py: Python<'_>, projection_name: &str, lag: u64) -> PyResult<Option<PyObject>> {
+        let alert = py.allow_threads(|| self.inner.observe(projection_name, lag));
+        alert.map(|alert| lag_alert_dict(py, &alert)).transpose()
+    }
+
+    /// Alert dicts for the projections currently stalled, by name
+    pub fn stalled(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.inner.stalled().iter().map(|alert| lag_alert_dict(py, alert)).collect()
+    }
+
+    /// Degraded while any projection is stalled
+    #[cfg(feature = "observability")]
+    #[getter]
+    pub fn health_status(&self) -> PyHealthStatus {
+        self.inner.health_status().into()
+    }
+
+    #[getter]
+    pub fn stall_window_ms(&self) -> u64 {
+        self.inner.stall_window().as_millis() as u64
+    }
+}
+
+fn lag_alert_dict(py: Python<'_>, alert: &LagAlert) -> PyResult<PyObject> {
+    let dict = PyDict::new(py);
+    dict.set_item("projection_name", &alert.projection_name)?;
+    dict.set_item("lag", alert.lag)?;
+    dict.set_item("lag_at_start", alert.lag_at_start)?;
+    dict.set_item("stalled_for_ms", alert.stalled_for.as_millis() as u64)?;
+    Ok(dict.into())
+}
+
+fn event_dict(py: Python<'_>, event: &Event) -> PyResult<PyObject> {
+    PyEvent { inner: event.clone() }.to_dict(py)
+}
+
+fn json_to_py<'p>(py: Python<'p>, value: &serde_json::Value) -> PyResult<&'p PyAny> {
+    py.import("json")?.call_method1("loads", (value.to_string(),))
+}
+
+fn json_from_py(py: Python<'_>, value: &PyAny) -> PyResult<serde_json::Value> {
+    let text: String = py.import("json")?.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&text).map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
@@ -1,10 +1,10 @@
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyType};
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use eventuali_core::tenancy::{
     TenantId as CoreTenantId, TenantInfo as CoreTenantInfo, TenantConfig as CoreTenantConfig,
     TenantMetadata as CoreTenantMetadata, ResourceLimits as CoreResourceLimits,
-    TenantManager as CoreTenantManager,
+    TenantManager as CoreTenantManager, TenantOnboardingSpec as CoreTenantOnboardingSpec,
     TenantStorageMetrics as CoreTenantStorageMetrics,
     ResourceType as CoreResourceType, QuotaTier as CoreQuotaTier, QuotaCheckResult as CoreQuotaCheckResult,
     EnhancedResourceUsage as CoreEnhancedResourceUsage, QuotaAlert as CoreQuotaAlert,
@@ -160,15 +160,16 @@ impl PyTenantConfig {
         encryption_enabled: Option<bool>,
         audit_enabled: Option<bool>,
         custom_settings: Option<HashMap<String, String>>,
-    ) -> Self {
+    ) -> PyResult<Self> {
+        // Only an omitted level falls back to the default; unknown names are an error
         let isolation_level = match isolation_level.as_deref() {
-            Some("database") => eventuali_core::tenancy::tenant::IsolationLevel::Database,
-            Some("application") => eventuali_core::tenancy::tenant::IsolationLevel::Application,
-            Some("row") => eventuali_core::tenancy::tenant::IsolationLevel::Row,
-            _ => eventuali_core::tenancy::tenant::IsolationLevel::Database,
+            Some(level) => level
+                .parse::<eventuali_core::tenancy::tenant::IsolationLevel>()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            None => eventuali_core::tenancy::tenant::IsolationLevel::default(),
         };
         
-        Self {
+        Ok(Self {
             inner: CoreTenantConfig {
                 isolation_level,
                 resource_limits: resource_limits.map(|rl| rl.inner).unwrap_or_default(),
@@ -176,7 +177,7 @@ impl PyTenantConfig {
                 audit_enabled: audit_enabled.unwrap_or(true),
                 custom_settings: custom_settings.unwrap_or_default(),
             },
-        }
+        })
     }
     
     #[classmethod]
@@ -188,11 +189,7 @@ impl PyTenantConfig {
     
     #[getter]
     fn isolation_level(&self) -> String {
-        match self.inner.isolation_level {
-            eventuali_core::tenancy::tenant::IsolationLevel::Database => "database".to_string(),
-            eventuali_core::tenancy::tenant::IsolationLevel::Application => "application".to_string(),
-            eventuali_core::tenancy::tenant::IsolationLevel::Row => "row".to_string(),
-        }
+        self.inner.isolation_level.as_str().to_string()
     }
     
     #[getter]
@@ -421,38 +418,153 @@ impl PyTenantManager {
         resource_type: String, 
         amount: u64
     ) -> PyResult<()> {
-        let resource_type = match resource_type.as_str() {
-            "events" => eventuali_core::tenancy::quota::ResourceType::Events,
-            "storage" => eventuali_core::tenancy::quota::ResourceType::Storage,
-            "streams" => eventuali_core::tenancy::quota::ResourceType::Streams,
-            "projections" => eventuali_core::tenancy::quota::ResourceType::Projections,
-            "aggregates" => eventuali_core::tenancy::quota::ResourceType::Aggregates,
-            _ => return Err(PyRuntimeError::new_err(format!("Invalid resource type: {resource_type}"))),
-        };
+        let resource_type = parse_resource_type(&resource_type)?;
         
         self.inner.check_tenant_quota(&tenant_id.inner, resource_type, amount)
             .map_err(map_rust_error_to_python)
     }
     
+    /// Create a tenant with its quota tier, default configuration values and
+    /// default roles in one step; on any failure nothing is left behind.
+    /// `roles` are dicts with `name`, `description` and `permissions`.
+    #[pyo3(signature = (tenant_id, name, config=None, quota_tier=None, configuration=None, roles=None))]
+    fn onboard_tenant(
+        &self,
+        tenant_id: PyTenantId,
+        name: String,
+        config: Option<PyTenantConfig>,
+        quota_tier: Option<PyQuotaTier>,
+        configuration: Option<HashMap<String, PyConfigurationValue>>,
+        roles: Option<Vec<&PyDict>>,
+    ) -> PyResult<PyTenantInfo> {
+        let mut spec = CoreTenantOnboardingSpec::new(tenant_id.inner, name);
+        if let Some(config) = config {
+            spec = spec.with_config(config.inner);
+        }
+        if let Some(quota_tier) = quota_tier {
+            spec = spec.with_quota_tier(quota_tier.inner);
+        }
+        for (key, value) in configuration.unwrap_or_default() {
+            let schema = unconstrained_schema(&value.inner)?;
+            spec = spec.with_configuration(key, value.inner, schema);
+        }
+        for role in roles.unwrap_or_default() {
+            let role_name: String = role.get_item("name")?
+                .ok_or_else(|| PyValueError::new_err("Role is missing 'name'"))?
+                .extract()?;
+            let permissions: Vec<String> = role.get_item("permissions")?
+                .ok_or_else(|| PyValueError::new_err(format!("Role {role_name} is missing 'permissions'")))?
+                .extract()?;
+            let description: String = match role.get_item("description")? {
+                Some(description) => description.extract()?,
+                None => String::new(),
+            };
+            spec = spec.with_role(role_name, description, permissions);
+        }
+        
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(self.inner.onboard_tenant(spec))
+            .map(|tenant_info| PyTenantInfo { inner: tenant_info })
+            .map_err(map_rust_error_to_python)
+    }
+    
+    /// A tenant's configuration value, or None if unset
+    fn get_tenant_configuration(&self, tenant_id: PyTenantId, key: String) -> PyResult<Option<PyConfigurationValue>> {
+        let configuration = self.inner.get_tenant_configuration(&tenant_id.inner)
+            .map_err(map_rust_error_to_python)?;
+        Ok(configuration.get_configuration(&key, None).map(|inner| PyConfigurationValue { inner }))
+    }
+    
+    /// Default roles registered at onboarding, as dicts with `role_id`,
+    /// `name`, `description` and sorted `permissions`
+    fn get_tenant_roles(&self, py: Python, tenant_id: PyTenantId) -> PyResult<Vec<Py<PyDict>>> {
+        self.inner.get_tenant_roles(&tenant_id.inner)
+            .into_iter()
+            .map(|role| {
+                let mut permissions: Vec<String> = role.permissions.into_iter().collect();
+                permissions.sort();
+                let dict = PyDict::new(py);
+                dict.set_item("role_id", role.role_id)?;
+                dict.set_item("name", role.name)?;
+                dict.set_item("description", role.description)?;
+                dict.set_item("permissions", permissions)?;
+                Ok(dict.into())
+            })
+            .collect()
+    }
+    
+    /// Number of event streams the tenant currently has open, counted against
+    /// its `max_concurrent_streams` limit
+    fn get_active_streams(&self, tenant_id: PyTenantId) -> PyResult<u32> {
+        self.inner.get_tenant_quota(&tenant_id.inner)
+            .map(|quota| quota.active_streams())
+            .map_err(map_rust_error_to_python)
+    }
+    
+    /// Check whether a write of `events` events is within the tenant's write
+    /// rate limit, without using any of it up. Raises `QuotaExceededError` when
+    /// throttled; its `retry_after` is the wait in seconds, or None when the
+    /// batch exceeds the burst and must be split. Call `record_tenant_write`
+    /// once the write has succeeded.
+    fn check_tenant_write_rate(&self, tenant_id: PyTenantId, events: u64) -> PyResult<()> {
+        let quota = self.inner.get_tenant_quota(&tenant_id.inner)
+            .map_err(map_rust_error_to_python)?;
+        quota.peek_write_rate(events)
+            .map_err(|exceeded| map_rust_error_to_python(exceeded.into()))
+    }
+    
+    /// Count a successful write of `events` events against the tenant's
+    /// write rate limit
+    fn record_tenant_write(&self, tenant_id: PyTenantId, events: u64) -> PyResult<()> {
+        let quota = self.inner.get_tenant_quota(&tenant_id.inner)
+            .map_err(map_rust_error_to_python)?;
+        quota.record_write_rate(events);
+        Ok(())
+    }
+    
+    fn check_tenant_quota_batch(
+        &self,
+        tenant_id: PyTenantId,
+        entries: Vec<(String, u64)>
+    ) -> PyResult<PyQuotaCheckResult> {
+        let mut batch = Vec::with_capacity(entries.len());
+        for (resource_type, amount) in entries {
+            let resource_type = parse_resource_type(&resource_type)?;
+            batch.push((resource_type, amount));
+        }
+        
+        self.inner.check_tenant_quota_batch(&tenant_id.inner, &batch)
+            .map(|inner| PyQuotaCheckResult { inner })
+            .map_err(map_rust_error_to_python)
+    }
+    
     fn record_tenant_usage(
         &self, 
         tenant_id: PyTenantId, 
         resource_type: String, 
         amount: u64
     ) -> PyResult<()> {
-        let resource_type = match resource_type.as_str() {
-            "events" => eventuali_core::tenancy::quota::ResourceType::Events,
-            "storage" => eventuali_core::tenancy::quota::ResourceType::Storage,
-            "streams" => eventuali_core::tenancy::quota::ResourceType::Streams,
-            "projections" => eventuali_core::tenancy::quota::ResourceType::Projections,
-            "aggregates" => eventuali_core::tenancy::quota::ResourceType::Aggregates,
-            _ => return Err(PyRuntimeError::new_err(format!("Invalid resource type: {resource_type}"))),
-        };
+        let resource_type = parse_resource_type(&resource_type)?;
         
         self.inner.record_tenant_usage(&tenant_id.inner, resource_type, amount)
             .map_err(map_rust_error_to_python)
     }
     
+    fn record_tenant_usage_batch(
+        &self,
+        tenant_id: PyTenantId,
+        entries: Vec<(String, u64)>
+    ) -> PyResult<()> {
+        let mut usage = Vec::with_capacity(entries.len());
+        for (resource_type, amount) in entries {
+            let resource_type = parse_resource_type(&resource_type)?;
+            usage.push((resource_type, amount));
+        }
+        
+        self.inner.record_tenant_usage_batch(&tenant_id.inner, &usage)
+            .map_err(map_rust_error_to_python)
+    }
+    
     fn get_tenants_near_limits(&self) -> Vec<Py<PyDict>> {
         let tenants = self.inner.get_tenants_near_limits();
         
@@ -612,6 +724,16 @@ impl PyQuotaTier {
         Ok(Self { inner: tier })
     }
     
+    /// Default write rate limit of the tier as `{"events_per_second", "burst"}`
+    #[getter]
+    fn write_rate_limit(&self, py: Python) -> PyResult<PyObject> {
+        let limit = self.inner.write_rate_limit();
+        let dict = PyDict::new(py);
+        dict.set_item("events_per_second", limit.events_per_second)?;
+        dict.set_item("burst", limit.burst)?;
+        Ok(dict.into_py(py))
+    }
+    
     #[classmethod]
     fn starter(_cls: &PyType) -> Self {
         Self { inner: CoreQuotaTier::Starter }
@@ -933,15 +1055,7 @@ impl PyEnhancedResourceUsage {
     }
     
     fn utilization_percentage(&self, resource_type: String) -> Option<f64> {
-        let resource_type = match resource_type.as_str() {
-            "events" => CoreResourceType::Events,
-            "storage" => CoreResourceType::Storage,
-            "streams" => CoreResourceType::Streams,
-            "projections" => CoreResourceType::Projections,
-            "aggregates" => CoreResourceType::Aggregates,
-            "api_calls" => CoreResourceType::ApiCalls,
-            _ => return None,
-        };
+        let resource_type = parse_resource_type(&resource_type).ok()?;
         
         self.inner.utilization_percentage(resource_type)
     }
@@ -1087,6 +1201,40 @@ impl PyConfigurationValue {
     }
 }
 
+/// Schema accepting any value of `value`'s scalar type
+/// Resource type named `name`, as Python passes it to the quota methods
+fn parse_resource_type(name: &str) -> PyResult<CoreResourceType> {
+    match name {
+        "events" => Ok(CoreResourceType::Events),
+        "storage" => Ok(CoreResourceType::Storage),
+        "streams" => Ok(CoreResourceType::Streams),
+        "projections" => Ok(CoreResourceType::Projections),
+        "aggregates" => Ok(CoreResourceType::Aggregates),
+        "api_calls" => Ok(CoreResourceType::ApiCalls),
+        _ => Err(PyRuntimeError::new_err(format!("Invalid resource type: {name}"))),
+    }
+}
+
+fn unconstrained_schema(value: &CoreConfigurationValue) -> PyResult<CoreConfigurationSchema> {
+    Ok(match value {
+        CoreConfigurationValue::String(_) => CoreConfigurationSchema::String {
+            min_length: None,
+            max_length: None,
+            pattern: None,
+        },
+        CoreConfigurationValue::Integer(_) => CoreConfigurationSchema::Integer {
+            min: None,
+            max: None,
+        },
+        CoreConfigurationValue::Float(_) => CoreConfigurationSchema::Float {
+            min: None,
+            max: None,
+        },
+        CoreConfigurationValue::Boolean(_) => CoreConfigurationSchema::Boolean,
+        _ => return Err(PyRuntimeError::new_err("Unsupported configuration value type")),
+    })
+}
+
 /// Python wrapper for TenantConfigurationManager
 #[pyclass(name = "TenantConfigurationManager")]
 pub struct PyTenantConfigurationManager {
@@ -1111,36 +1259,8 @@ impl PyTenantConfigurationManager {
         change_reason: String,
         environment: Option<PyConfigurationEnvironment>,
     ) -> PyResult<()> {
-        // Create a configuration value and schema from the Python value
-        let (config_value, schema) = match &value.inner {
-            CoreConfigurationValue::String(s) => (
-                CoreConfigurationValue::String(s.clone()),
-                CoreConfigurationSchema::String {
-                    min_length: None,
-                    max_length: None,
-                    pattern: None,
-                }
-            ),
-            CoreConfigurationValue::Integer(i) => (
-                CoreConfigurationValue::Integer(*i),
-                CoreConfigurationSchema::Integer {
-                    min: None,
-                    max: None,
-                }
-            ),
-            CoreConfigurationValue::Float(f) => (
-                CoreConfigurationValue::Float(*f),
-                CoreConfigurationSchema::Float {
-                    min: None,
-                    max: None,
-                }
-            ),
-            CoreConfigurationValue::Boolean(b) => (
-                CoreConfigurationValue::Boolean(*b),
-                CoreConfigurationSchema::Boolean
-            ),
-            _ => return Err(PyRuntimeError::new_err("Unsupported configuration value type")),
-        };
+        let schema = unconstrained_schema(&value.inner)?;
+        let config_value = value.inner;
         
         let env = environment.map(|e| e.inner);
         
@@ -1429,10 +1549,9 @@ impl PyTenantMetricsCollector {
     }
     
     fn record_metrics(&self, metrics: Vec<(String, f64)>) -> PyResult<()> {
-        let metrics_with_labels: Vec<(String, f64, Option<HashMap<String, String>>)> = 
-            metrics.into_iter()
-                .map(|(name, value)| (name, value, None))
-                .collect();
+        let metrics_with_labels: Vec<_> = metrics.into_iter()
+            .map(|(name, value)| (name, value, None))
+            .collect();
         
         self.inner.record_metrics(metrics_with_labels);
         Ok(())
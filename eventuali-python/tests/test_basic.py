@@ -2,10 +2,12 @@
 Basic tests for Eventuali functionality.
 """
 
+import json
 import pytest
 import asyncio
-from eventuali import EventStore
-from eventuali.event import UserRegistered, UserEmailChanged
+from eventuali import EventStore, EventBuilder
+from eventuali.event import DomainEvent, UserRegistered, UserEmailChanged
+from eventuali.exceptions import ApplyError
 from eventuali.aggregate import User
 
 
@@ -29,6 +31,86 @@ class TestEventSourcing:
         event2 = UserRegistered.from_json(json_str)
         assert event2.name == event.name
         assert event2.email == event.email
+
+    def test_event_cloudevent_round_trip(self):
+        """Test converting an event to a CloudEvent and back."""
+        event = UserRegistered(name="John Doe", email="john@example.com")
+        event.aggregate_id = "user-1"
+        event.aggregate_type = "User"
+        event.aggregate_version = 1
+        event.user_id = "admin"
+
+        cloudevent = event.to_cloudevent("https://example.com/eventuali")
+        assert cloudevent["specversion"] == "1.0"
+        assert cloudevent["id"] == str(event.event_id)
+        assert cloudevent["source"] == "https://example.com/eventuali/User"
+        assert cloudevent["type"] == "UserRegistered"
+        assert cloudevent["data"] == {"name": "John Doe", "email": "john@example.com"}
+        assert cloudevent["aggregateid"] == "user-1"
+        assert cloudevent["aggregateversion"] == 1
+
+        restored = UserRegistered.from_cloudevent(cloudevent)
+        assert restored.event_id == event.event_id
+        assert restored.name == event.name
+        assert restored.aggregate_version == 1
+        assert restored.user_id == "admin"
+
+    @pytest.mark.asyncio
+    async def test_custom_id_allocator(self):
+        """Test that a store's allocator assigns the IDs of the events it saves."""
+        from uuid import UUID
+
+        counter = iter(range(1, 1000))
+        store = await EventStore.create(
+            "sqlite://:memory:", id_allocator=lambda: UUID(int=next(counter))
+        )
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        user.change_email("john.doe@example.com")
+        events = list(user.get_uncommitted_events())
+        # New events keep the process default until the store saves them
+        assert all(event.event_id.version == 4 for event in events)
+
+        await store.save(user)
+        assert [event.event_id for event in events] == [UUID(int=1), UUID(int=2)]
+        loaded = await store.load(User, user.id)
+        assert loaded.email == "john.doe@example.com"
+
+        # Another store is unaffected
+        other = await EventStore.create("sqlite://:memory:")
+        other_user = User()
+        other_user.apply(UserRegistered(name="Jane", email="jane@example.com"))
+        other_event = other_user.get_uncommitted_events()[0]
+        await other.save(other_user)
+        assert other_event.event_id.version == 4
+
+    def test_event_builder(self):
+        """Test building a complete event with the fluent builder."""
+        event = (
+            EventBuilder()
+            .aggregate_id("user-1")
+            .aggregate_type("User")
+            .event_type("UserRegistered")
+            .version(3)
+            .data_dict({"name": "John Doe", "email": "john@example.com"})
+            .metadata({"user_id": "admin", "headers": {"source": "signup"}})
+            .build()
+        )
+
+        assert event.aggregate_id == "user-1"
+        assert event.aggregate_type == "User"
+        assert event.event_type == "UserRegistered"
+        assert event.aggregate_version == 3
+        assert event.event_version == 1
+        assert event.id
+        assert event.timestamp
+        assert json.loads(event.data) == {"name": "John Doe", "email": "john@example.com"}
+        as_dict = event.to_dict()
+        assert as_dict["metadata"]["user_id"] == "admin"
+        assert as_dict["metadata"]["headers"] == {"source": "signup"}
+
+        with pytest.raises(ValueError):
+            EventBuilder().aggregate_id("user-1").aggregate_type("User").build()
     
     def test_aggregate_creation(self):
         """Test aggregate creation and event application."""
@@ -93,6 +175,29 @@ class TestEventSourcing:
         assert not user.has_uncommitted_events()
         assert not user.is_new()
     
+    def test_aggregate_from_events_identifies_failing_event(self):
+        """Test that a replay failure names the event that broke it."""
+        class UserPromoted(DomainEvent):
+            role: str
+        
+        events = [
+            UserRegistered(name="John Doe", email="john@example.com"),
+            UserPromoted(role="admin"),
+            UserEmailChanged(old_email="john@example.com", new_email="john.doe@example.com"),
+        ]
+        # Stored versions are reported even when they differ from the replay count
+        for version, event in enumerate(events, start=11):
+            event.aggregate_id = "user-123"
+            event.aggregate_version = version
+        
+        with pytest.raises(ApplyError) as exc_info:
+            User.from_events(events)
+        
+        assert exc_info.value.event_id == str(events[1].event_id)
+        assert exc_info.value.aggregate_version == 12
+        assert exc_info.value.event_type == "UserPromoted"
+        assert isinstance(exc_info.value.__cause__, NotImplementedError)
+    
     @pytest.mark.asyncio
     async def test_event_store_creation(self):
         """Test event store creation."""
@@ -101,6 +206,435 @@ class TestEventSourcing:
         assert store is not None
         assert store._initialized
     
+    @pytest.mark.asyncio
+    async def test_event_store_reopens_after_close(self, tmp_path):
+        """Test that a closed store releases its database for the next one."""
+        url = f"sqlite://{tmp_path / 'events.db'}"
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        
+        async with await EventStore.create(url) as store:
+            await store.save(user)
+        assert not store._initialized
+        
+        async with await EventStore.create(url) as reopened:
+            loaded = await reopened.load(User, user.id)
+            assert loaded.email == "john@example.com"
+    
+    @pytest.mark.asyncio
+    async def test_ulid_store_assigns_ids_without_changing_the_process_default(self):
+        """Test that a store's event ID kind applies only to events it saves."""
+        store = await EventStore.create("sqlite://:memory:", event_id_type="ulid")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        event = user.get_uncommitted_events()[0]
+        default_id = event.event_id
+        assert default_id.version == 4
+        
+        await store.save(user)
+        assert event.event_id != default_id
+        # A ULID carries its creation time in milliseconds in the top 48 bits
+        created_ms = event.event_id.int >> 80
+        assert abs(created_ms - datetime.now(timezone.utc).timestamp() * 1000) < 60_000
+        assert User().get_uncommitted_events() == []
+        assert UserRegistered(name="Jane", email="jane@example.com").event_id.version == 4
+    
+    @pytest.mark.asyncio
+    async def test_deleted_aggregate_no_longer_loads(self):
+        """Test that a hard delete erases the aggregate and unknown modes are refused."""
+        store = await EventStore.create("sqlite://:memory:")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        user.change_email("john.doe@example.com")
+        await store.save(user)
+        
+        with pytest.raises(ValueError, match="delete mode"):
+            await store.delete_aggregate_events(user.id, mode="shred")
+        assert await store.delete_aggregate_events(user.id) == 2
+        assert await store.load(User, user.id) is None
+    
+    @pytest.mark.asyncio
+    async def test_save_with_retry_rebuilds_after_a_conflicting_writer(self, tmp_path):
+        """Test that a conflicting save is retried against the reloaded aggregate."""
+        store = await EventStore.create(f"sqlite://{tmp_path / 'events.db'}")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        await store.save(user)
+        
+        ours = await store.load(User, user.id)
+        theirs = await store.load(User, user.id)
+        theirs.change_email("theirs@example.com")
+        await store.save(theirs)
+        
+        attempts = []
+        def change_email(aggregate):
+            attempts.append(aggregate.version)
+            aggregate.change_email("ours@example.com")
+        
+        ours.change_email("ours@example.com")
+        saved = await store.save_with_retry(ours, change_email, max_attempts=2)
+        
+        # The retry ran once, against the version the other writer saved
+        assert attempts == [2]
+        assert saved.version == 3
+        loaded = await store.load(User, user.id)
+        assert loaded.email == "ours@example.com"
+        assert loaded.version == 3
+        
+        # Without attempts to spare, the conflict is raised
+        stale = await store.load(User, user.id)
+        other = await store.load(User, user.id)
+        other.change_email("other@example.com")
+        await store.save(other)
+        stale.change_email("stale@example.com")
+        with pytest.raises(RuntimeError, match="concurrency"):
+            await store.save_with_retry(stale, change_email, max_attempts=1)
+    
+    @pytest.mark.asyncio
+    async def test_save_with_retry_callback_can_use_the_store(self, tmp_path):
+        """Test that the retry callback may read from the store it is retrying on."""
+        store = await EventStore.create(f"sqlite://{tmp_path / 'events.db'}")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        await store.save(user)
+        
+        ours = await store.load(User, user.id)
+        theirs = await store.load(User, user.id)
+        theirs.change_email("theirs@example.com")
+        await store.save(theirs)
+        
+        seen = []
+        async def change_email(aggregate):
+            current = await asyncio.wait_for(store.load(User, aggregate.id), timeout=5)
+            seen.append(current.email)
+            aggregate.change_email("ours@example.com")
+        
+        ours.change_email("ours@example.com")
+        await store.save_with_retry(ours, change_email, max_attempts=2)
+        assert seen == ["theirs@example.com"]
+        assert (await store.load(User, user.id)).email == "ours@example.com"
+    
+    @pytest.mark.asyncio
+    async def test_validate_stream_reports_a_version_gap(self, tmp_path):
+        """Test that stream validation reports versions missing from storage."""
+        import sqlite3
+        
+        path = tmp_path / "events.db"
+        store = await EventStore.create(f"sqlite://{path}")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        user.change_email("second@example.com")
+        user.change_email("third@example.com")
+        await store.save(user)
+        
+        validation = await store.validate_stream(user.id)
+        assert validation["valid"]
+        assert validation["event_count"] == 3
+        
+        with sqlite3.connect(path) as db:
+            db.execute("UPDATE events SET aggregate_version = 5 WHERE aggregate_version = 3")
+        
+        validation = await store.validate_stream(user.id)
+        assert not validation["valid"]
+        assert validation["last_version"] == 5
+        assert validation["anomalies"] == [{"kind": "version_gap", "after": 2, "next": 5}]
+    
+    @pytest.mark.asyncio
+    async def test_load_at_version_reconstructs_earlier_state(self):
+        """Test that an aggregate can be loaded as it was at an earlier version."""
+        store = await EventStore.create("sqlite://:memory:")
+        user = User()
+        user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        user.change_email("second@example.com")
+        user.change_email("third@example.com")
+        await store.save(user)
+        
+        events = await store.load_events_at_version(user.id, 2)
+        assert [e.aggregate_version for e in events] == [1, 2]
+        
+        earlier = await store.load_at_version(User, user.id, 2)
+        assert earlier.version == 2
+        assert earlier.email == "second@example.com"
+        assert (await store.load_at_version(User, user.id, 3)).email == "third@example.com"
+        assert await store.load_at_version(User, user.id, 0) is None
+    
+    @pytest.mark.asyncio
+    async def test_shared_streamer_merges_events_of_two_stores(self):
+        """Test that one subscriber to a hub receives every attached store's events."""
+        from eventuali import SharedStreamer, Subscription
+        
+        hub = SharedStreamer()
+        accounts = await EventStore.create("sqlite://:memory:", shared_streamer=hub, shared_streamer_store_id="accounts")
+        profiles = await EventStore.create("sqlite://:memory:", shared_streamer=hub, shared_streamer_store_id="profiles")
+        receiver = await hub.subscribe(Subscription())
+        
+        first, second = User(), User()
+        first.apply(UserRegistered(name="John Doe", email="john@example.com"))
+        second.apply(UserRegistered(name="Jane Doe", email="jane@example.com"))
+        second.change_email("jane@example.org")
+        await accounts.save(first)
+        await profiles.save(second)
+        
+        received = [await asyncio.wait_for(receiver.recv(), 1) for _ in range(3)]
+        assert [e.global_position for e in received] == [1, 2, 3]
+        assert [(e.event.aggregate_id, e.stream_position) for e in received] == [
+            (first.id, 1), (second.id, 1), (second.id, 2),
+        ]
+        assert await hub.get_global_position() == 3
+        assert await hub.get_stream_position("profiles", second.id) == 2
+        assert await hub.get_stream_position("accounts", second.id) is None
+    
+    @pytest.mark.asyncio
+    async def test_oversized_saves_are_rejected_or_split(self):
+        """Test that max_batch_events rejects or splits saves over the limit."""
+        def three_events():
+            user = User()
+            user.apply(UserRegistered(name="John Doe", email="john@example.com"))
+            user.change_email("second@example.com")
+            user.change_email("third@example.com")
+            return user
+        
+        strict = await EventStore.create("sqlite://:memory:", max_batch_events=2)
+        user = three_events()
+        with pytest.raises(ValueError, match="exceeds the maximum"):
+            await strict.save(user)
+        assert await strict.load(User, user.id) is None
+        
+        split = await EventStore.create("sqlite://:memory:", max_batch_events=2, oversized_batch="split")
+        user = three_events()
+        await split.save(user)
+        loaded = await split.load(User, user.id)
+        assert loaded.version == 3
+        assert loaded.email == "third@example.com"
+        
+        with pytest.raises(ValueError):
+            await EventStore.create("sqlite://:memory:", max_batch_events=2, oversized_batch="truncate")
+    
+    @pytest.mark.asyncio
+    async def test_typed_projection_skips_unregistered_event_types(self):
+        """Test that a TypedProjection dispatches only event types it registered."""
+        from eventuali import TypedProjection
+        
+        class UserDirectory(TypedProjection):
+            def __init__(self):
+                self.handled = []
+            
+            @TypedProjection.handles("UserRegistered")
+            async def on_registered(self, event):
+                self.handled.append(("registered", event.name))
+            
+            @TypedProjection.handles("UserEmailChanged")
+            def on_email_changed(self, event):
+                self.handled.append(("email", event.new_email))
+        
+        projection = UserDirectory()
+        assert UserDirectory.event_types() == ["UserEmailChanged", "UserRegistered"]
+        for event in [
+            UserRegistered(name="John Doe", email="john@example.com"),
+            DomainEvent(event_type="UserDeactivated"),
+            UserEmailChanged(old_email="john@example.com", new_email="new@example.com"),
+        ]:
+            await projection.handle_event(event)
+        
+        assert projection.handled == [("registered", "John Doe"), ("email", "new@example.com")]
+        assert not UserDirectory.handles_event_type("UserDeactivated")
+    
+    @pytest.mark.asyncio
+    async def test_projection_dedup_retries_failed_events(self):
+        """Test that an event whose handler failed is handled again on redelivery."""
+        from uuid import uuid4
+        from eventuali.streaming import Projection
+        
+        class FailsOnce(Projection):
+            dedup_window_size = 8
+            
+            def __init__(self):
+                self.attempts = 0
+                self.handled = 0
+            
+            async def handle_event(self, event):
+                self.attempts += 1
+                if self.attempts == 1:
+                    raise RuntimeError("read model unavailable")
+                self.handled += 1
+        
+        projection = FailsOnce()
+        event = DomainEvent(event_type="UserDeactivated", event_id=uuid4())
+        with pytest.raises(RuntimeError):
+            await projection.process_event(event)
+        assert await projection.process_event(event)
+        assert not await projection.process_event(event)
+        assert projection.handled == 1
+    
+    def test_gdpr_export_through_registered_formatter(self):
+        """Test streaming a GDPR export through a custom formatter."""
+        import io
+        from eventuali import GdprManager, ExportFormat
+
+        class IdListFormatter:
+            def header(self, export_id, header):
+                return f"subject {header['subject']['subject_id']}\n"
+
+            def record(self, record):
+                return f"{record['id']}\n"
+
+            def footer(self, export_id, records_written):
+                return b"end\n"
+
+        manager = GdprManager()
+        subject_id = manager.register_data_subject("user123", None, None)
+        manager.register_export_formatter("id-list", IdListFormatter())
+        assert "id-list" in manager.export_formatter_names()
+
+        out = io.BytesIO()
+        result = manager.stream_data_export(
+            subject_id, [{"id": 1}, {"id": 2}], out,
+            export_format=ExportFormat.structured("id-list"),
+        )
+        assert result["records_written"] == 2
+        assert out.getvalue().decode() == f"subject {subject_id}\n1\n2\nend\n"
+
+        with pytest.raises(Exception):
+            manager.set_default_export_format(ExportFormat.structured("fhir"))
+        manager.set_default_export_format(ExportFormat.structured("id-list"))
+        assert manager.default_export_format().name == "id-list"
+
+    def test_metrics_pushed_to_statsd(self):
+        """Test that recorded metrics reach a StatsD endpoint as tagged lines."""
+        import socket
+        from eventuali import ObservabilityConfig, ObservabilityService
+
+        agent = socket.socket(socket.AF_INET, socket.SOCK_DGRAM)
+        agent.bind(("127.0.0.1", 0))
+        agent.settimeout(5)
+        host, port = agent.getsockname()
+
+        config = ObservabilityConfig(
+            tracing_enabled=False,
+            statsd_endpoint=f"{host}:{port}",
+            statsd_prefix="eventuali",
+            statsd_tags={"env": "test"},
+        )
+        assert config.statsd_endpoint == f"{host}:{port}"
+        service = ObservabilityService(config)
+        service.record_metric("payload_size_bytes", 512.0, {"aggregate_type": "User"})
+        service.shutdown()
+
+        line = agent.recv(1024).decode()
+        assert line == "eventuali.payload_size_bytes:512|h|#env:test,aggregate_type:User"
+        agent.close()
+
+    def test_tenant_onboarding_applies_defaults_or_nothing(self):
+        """Test that onboarding seeds a tenant fully or rolls back entirely."""
+        from eventuali import TenantManager, TenantId, QuotaTier, ConfigurationValue
+
+        manager = TenantManager()
+        tenant_id = TenantId("acme")
+        roles = [{"name": "admin", "description": "Admins", "permissions": ["events:write"]}]
+
+        with pytest.raises(Exception):
+            manager.onboard_tenant(
+                tenant_id, "Acme",
+                roles=roles + [{"name": "auditor", "permissions": ["audit:everything"]}],
+            )
+        assert manager.list_tenants() == []
+
+        manager.onboard_tenant(
+            tenant_id, "Acme",
+            quota_tier=QuotaTier.professional(),
+            configuration={"max_projections": ConfigurationValue.integer(25)},
+            roles=roles,
+        )
+        assert manager.get_tenant(tenant_id).name == "Acme"
+        assert "25" in str(manager.get_tenant_configuration(tenant_id, "max_projections"))
+        assert [(r["name"], r["permissions"]) for r in manager.get_tenant_roles(tenant_id)] == [
+            ("acme:admin", ["events:write"])
+        ]
+
+    def test_key_manager_lists_key_ids_and_default(self):
+        """Test that a key manager reports its key IDs, sorted, and its default key."""
+        from eventuali import KeyManager
+
+        manager = KeyManager()
+        assert manager.get_key_ids() == []
+        assert manager.default_key_id() is None
+
+        for key_id in ["key-2025", "key-2023", "key-2024"]:
+            manager.add_key(KeyManager.generate_key(key_id))
+        assert manager.get_key_ids() == ["key-2023", "key-2024", "key-2025"]
+        assert manager.default_key_id() == "key-2025"
+
+        manager.set_default_key("key-2024")
+        assert manager.default_key_id() == "key-2024"
+
+    def test_rotated_keys_still_decrypt_and_reencrypt_old_data(self):
+        """Test that rotating keys keeps old ciphertext readable and movable to the new key."""
+        from eventuali import EventEncryption, KeyManager
+
+        manager = KeyManager()
+        manager.add_key(KeyManager.generate_key("key-1"))
+        old = EventEncryption(manager).encrypt_json_data('{"balance": 120}')
+
+        manager.add_key(KeyManager.generate_key("key-2"))
+        manager.rotate_to("key-2")
+        encryption = EventEncryption(manager)
+        assert encryption.encrypt_json_data("{}").key_id == "key-2"
+        assert json.loads(encryption.decrypt_to_json(old)) == {"balance": 120}
+
+        moved = encryption.reencrypt(old, "key-2")
+        assert moved.key_id == "key-2"
+        assert json.loads(encryption.decrypt_to_json(moved)) == {"balance": 120}
+
+    def test_chacha20_poly1305_round_trip(self):
+        """Test that ChaCha20-Poly1305 keys encrypt, decrypt and never open AES data."""
+        from eventuali import EncryptionAlgorithm, EventEncryption, KeyManager
+
+        assert str(EncryptionAlgorithm.chacha20poly1305()) == "ChaCha20-Poly1305"
+        assert str(EncryptionAlgorithm.aes256gcm()) == "AES-256-GCM"
+
+        manager = KeyManager()
+        manager.add_key(KeyManager.generate_key("chacha", EncryptionAlgorithm.chacha20poly1305()))
+        manager.add_key(KeyManager.generate_key("aes"))
+        encryption = EventEncryption(manager)
+
+        encrypted = encryption.encrypt_json_data('{"card": "4111"}')
+        assert encrypted.key_id == "chacha"
+        assert str(encrypted.algorithm) == "ChaCha20-Poly1305"
+        assert json.loads(encryption.decrypt_to_json(encrypted)) == {"card": "4111"}
+
+        aes_only = KeyManager()
+        aes_only.add_key(KeyManager.generate_key("aes"))
+        with pytest.raises(RuntimeError):
+            EventEncryption(aes_only).decrypt_to_json(encrypted)
+
+    def test_lag_watchdog_alerts_on_a_stalled_projection(self):
+        """Test that growing lag raises one alert after the stall window and clears on catch-up."""
+        import time
+        from eventuali import HealthStatus, LagWatchdog
+
+        watchdog = LagWatchdog(100)
+        alerts = []
+        watchdog.on_alert(alerts.append)
+
+        lag = 0
+        watchdog.observe("order-summary", lag)
+        deadline = time.monotonic() + 5
+        while not alerts and time.monotonic() < deadline:
+            lag += 1
+            watchdog.observe("order-summary", lag)
+            time.sleep(0.02)
+
+        assert len(alerts) == 1
+        assert alerts[0]["projection_name"] == "order-summary"
+        assert alerts[0]["lag"] == lag
+        assert alerts[0]["stalled_for_ms"] >= 100
+        assert str(watchdog.health_status) == str(HealthStatus.Degraded)
+
+        assert watchdog.observe("order-summary", lag + 1) is None
+        assert watchdog.observe("order-summary", 0) is None
+        assert watchdog.stalled() == []
+        assert str(watchdog.health_status) == str(HealthStatus.Healthy)
+
     def test_event_store_not_initialized(self):
         """Test that uninitialized event store raises error."""
         store = EventStore()
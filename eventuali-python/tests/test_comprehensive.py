@@ -384,6 +384,20 @@ class TestPerformance:
         assert user.version == 100
         assert events_per_sec > 1000  # Should replay very quickly
 
+    @pytest.mark.asyncio
+    async def test_event_roundtrip_benchmark(self):
+        """Test the end-to-end save/load benchmark reports throughput and percentiles."""
+        from eventuali.performance import benchmark_event_roundtrip
+        
+        stats = await benchmark_event_roundtrip(":memory:", 200, 128)
+        
+        assert stats["events"] == 200
+        assert stats["save_events_per_second"] > 0
+        assert stats["load_events_per_second"] > 0
+        for latency in (stats["save_latency"], stats["load_latency"]):
+            assert 0 < latency["p50_ms"] <= latency["p95_ms"] <= latency["p99_ms"]
+            assert latency["p99_ms"] < 10_000
+
 
 @pytest.mark.asyncio
 async def test_async_workflow():